@@ -7,6 +7,7 @@ use tokenator::ParseError;
 use tokenator::TokenParser;
 use tokenator::TokenSerializable;
 
+use crate::Error;
 use crate::Pubkey;
 use crate::SecretKey;
 
@@ -82,6 +83,27 @@ impl<'a> FilledKeypair<'a> {
             secret_key: self.secret_key.to_owned(),
         }
     }
+
+    /// The secret key as a bech32 `nsec1...` string
+    pub fn to_nsec(&self) -> Result<String, Error> {
+        self.secret_key
+            .to_bech32()
+            .map_err(|e| Error::Generic(e.to_string()))
+    }
+
+    /// The secret key encrypted with `pass` as a NIP-49 `ncryptsec1...`
+    /// string. Each step of `log_n` doubles the work of decrypting it.
+    pub fn to_ncryptsec(&self, pass: &str, log_n: u8) -> Result<String, Error> {
+        EncryptedSecretKey::new(
+            self.secret_key,
+            pass,
+            log_n,
+            nostr::nips::nip49::KeySecurity::Unknown,
+        )
+        .map_err(|e| Error::Generic(e.to_string()))?
+        .to_bech32()
+        .map_err(|e| Error::Generic(e.to_string()))
+    }
 }
 
 impl<'a> From<&'a FilledKeypair<'a>> for KeypairUnowned<'a> {
@@ -119,6 +141,20 @@ impl FullKeypair {
             secret_key: Some(self.secret_key),
         }
     }
+
+    /// Decrypt a NIP-49 `ncryptsec1...` string
+    pub fn from_ncryptsec(ncryptsec: &str, pass: &str) -> Result<Self, Error> {
+        let secret_key = EncryptedSecretKey::from_bech32(ncryptsec.trim())
+            .map_err(|_| Error::InvalidBech32)?
+            .to_secret_key(pass)
+            .map_err(|e| Error::DecodeFailed(e.to_string()))?;
+        let keypair = Keypair::from_secret(secret_key);
+
+        Ok(FullKeypair {
+            pubkey: keypair.pubkey,
+            secret_key: keypair.secret_key.ok_or(Error::InvalidByteSize)?,
+        })
+    }
 }
 
 impl std::fmt::Display for Keypair {
@@ -245,6 +281,7 @@ fn parse_seckey<'a>(parser: &mut TokenParser<'a>) -> Result<SecretKey, ParseErro
 #[cfg(test)]
 mod tests {
 
+    use nostr::nips::nip19::FromBech32;
     use tokenator::{TokenParser, TokenSerializable, TokenWriter};
 
     use super::{FullKeypair, Keypair};
@@ -288,4 +325,26 @@ mod tests {
 
         assert_eq!(kp, new_kp);
     }
+
+    #[test]
+    fn test_ncryptsec_roundtrip() {
+        let kp = FullKeypair::generate();
+        let ncryptsec = kp.to_filled().to_ncryptsec("hunter2", 4).unwrap();
+
+        assert!(ncryptsec.starts_with("ncryptsec1"));
+        assert_eq!(
+            FullKeypair::from_ncryptsec(&ncryptsec, "hunter2").unwrap(),
+            kp
+        );
+        assert!(FullKeypair::from_ncryptsec(&ncryptsec, "hunter3").is_err());
+    }
+
+    #[test]
+    fn test_nsec_roundtrip() {
+        let kp = FullKeypair::generate();
+        let nsec = kp.to_filled().to_nsec().unwrap();
+
+        assert!(nsec.starts_with("nsec1"));
+        assert_eq!(nostr::SecretKey::from_bech32(&nsec).unwrap(), kp.secret_key);
+    }
 }
@@ -23,9 +23,10 @@ pub use relay::pool::{PoolEvent, PoolEventBuf, PoolRelay, RelayPool};
 pub use relay::subs_debug::{OwnedRelayEvent, RelayLogEvent, SubsDebug, TransferStats};
 pub use relay::{
     Nip11ApplyOutcome, Nip11FetchRequest, Nip11LimitationsRaw, NormRelayUrl, OutboxPool,
-    OutboxSession, OutboxSessionHandler, OutboxSubId, RelayCoordinatorLimits, RelayId,
-    RelayImplType, RelayLimitations, RelayReqId, RelayReqStatus, RelayStatus, RelayType,
-    RelayUrlPkgs, SubPass, SubPassGuardian, SubPassRevocation, WebsocketConn,
+    OutboxSession, OutboxSessionHandler, OutboxSubId, PublishAck, PublishAcks,
+    RelayCoordinatorLimits, RelayHealth, RelayId, RelayImplType, RelayIncident, RelayIncidentKind,
+    RelayLimitations, RelayReqId, RelayReqStatus, RelayStatus, RelayType, RelayUrlPkgs, ReqPacing,
+    SubPass, SubPassGuardian, SubPassRevocation, WebsocketConn,
 };
 
 pub type Result<T> = std::result::Result<T, error::Error>;
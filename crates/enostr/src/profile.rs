@@ -59,7 +59,7 @@ impl ProfileState {
 
     #[inline]
     pub fn banner(&self) -> Option<&str> {
-        self.get_str("name")
+        self.get_str("banner")
     }
 
     #[inline]
@@ -97,6 +97,13 @@ impl ProfileState {
         self.get_str("website")
     }
 
+    /// Drop fields that are empty strings, e.g. ones an edit form created
+    /// with [`Self::str_mut`] but never filled in
+    pub fn remove_empty_strings(&mut self) {
+        self.values_mut()
+            .retain(|_, v| v.as_str().is_none_or(|s| !s.is_empty()));
+    }
+
     pub fn from_note_contents(contents: &str) -> Self {
         let json = serde_json::from_str(contents);
         let data = if let Ok(Value::Object(data)) = json {
@@ -108,3 +115,24 @@ impl ProfileState {
         Self::new(data)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remove_empty_strings() {
+        let mut state =
+            ProfileState::from_note_contents(r#"{"name":"bob","about":"","bot":false}"#);
+        *state.str_mut("website") = String::new();
+        *state.str_mut("banner") = "https://example.com/b.png".to_string();
+
+        state.remove_empty_strings();
+
+        assert_eq!(state.name(), Some("bob"));
+        assert_eq!(state.banner(), Some("https://example.com/b.png"));
+        assert_eq!(state.about(), None);
+        assert_eq!(state.website(), None);
+        assert_eq!(state.value().get("bot"), Some(&Value::Bool(false)));
+    }
+}
@@ -3,12 +3,9 @@ use std::collections::HashMap;
 use hashbrown::HashSet;
 use nostrdb::Filter;
 
-use crate::{
-    relay::{
-        websocket::WebsocketRelay, OutboxSubId, OutboxSubscriptions, QueuedTasks, RelayReqId,
-        RelayReqStatus, RelayTask, SubPass, SubPassGuardian, SubPassRevocation,
-    },
-    ClientMessage,
+use crate::relay::{
+    websocket::WebsocketRelay, OutboxSubId, OutboxSubscriptions, QueuedTasks, RelayReqId,
+    RelayReqStatus, RelayTask, SubPass, SubPassGuardian, SubPassRevocation,
 };
 
 /// CompactionData tracks every compaction REQ on a relay along with the
@@ -51,7 +48,7 @@ impl CompactionData {
     }
 
     /// Returns the OutboxSubIds associated with the given relay subscription ID.
-    pub fn ids(&self, sid: &RelayReqId) -> Option<&HashSet<OutboxSubId>> {
+    pub fn ids(&self, sid: &str) -> Option<&HashSet<OutboxSubId>> {
         self.relay_subs.get(sid).map(|d| &d.requests.requests)
     }
 }
@@ -156,10 +153,7 @@ impl<'a> CompactionRelay<'a> {
                 continue;
             }
 
-            handler
-                .relay
-                .conn
-                .send(&ClientMessage::req(sid.to_string(), filters));
+            handler.relay.send_req(sid.to_string(), filters);
         }
     }
 
@@ -433,15 +427,13 @@ impl<'a> Drop for CompactionHandler<'a> {
                     let filters = self.subs.filters_all(&data.requests.requests);
 
                     if filters.is_empty() {
-                        self.relay.conn.send(&ClientMessage::close(id.0.clone()));
+                        self.relay.send_close(id.0.clone());
                     } else {
-                        self.relay
-                            .conn
-                            .send(&ClientMessage::req(id.0.clone(), filters));
+                        self.relay.send_req(id.0.clone(), filters);
                     }
                 }
                 SubSessionTask::Removed => {
-                    self.relay.conn.send(&ClientMessage::close(id.0.clone()));
+                    self.relay.send_close(id.0.clone());
                 }
                 SubSessionTask::New => {
                     let Some(data) = self.data.relay_subs.get(id) else {
@@ -449,9 +441,7 @@ impl<'a> Drop for CompactionHandler<'a> {
                     };
 
                     let filters = self.subs.filters_all(&data.requests.requests);
-                    self.relay
-                        .conn
-                        .send(&ClientMessage::req(id.0.clone(), filters));
+                    self.relay.send_req(id.0.clone(), filters);
                 }
             }
         }
@@ -609,7 +599,7 @@ mod tests {
             },
         );
 
-        let ids = data.ids(&relay_id);
+        let ids = data.ids(&relay_id.0);
         assert!(ids.is_some());
         assert!(ids.unwrap().contains(&id));
     }
@@ -1,17 +1,21 @@
 use ewebsock::{WsEvent, WsMessage};
 use hashbrown::{HashMap, HashSet};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use crate::{
     relay::{
         compaction::{CompactionData, CompactionRelay, CompactionSession},
+        health::RelayIncidentKind,
         nip11::Nip11FetchLifecycle,
+        pacing::ReqPacing,
+        publish_acks::PublishAck,
         transparent::{revocate_transparent_subs, TransparentData, TransparentRelay},
+        validation::{validate_event, EventRejection},
         BroadcastCache, BroadcastRelay, NormRelayUrl, OutboxSubId, OutboxSubscriptions,
         RawEventData, RelayCoordinatorLimits, RelayImplType, RelayLimitations, RelayReqId,
         RelayReqStatus, RelayType, SubPassGuardian, SubPassRevocation, WebsocketRelay,
     },
-    EventClientMessage, RelayMessage, RelayStatus, Wakeup, WebsocketConn,
+    EventClientMessage, NoteId, RelayMessage, RelayStatus, Wakeup, WebsocketConn,
 };
 
 /// RelayCoordinator routes each Outbox subscription to either the compaction or
@@ -25,6 +29,8 @@ pub struct CoordinationData {
     broadcast_cache: BroadcastCache,
     eose_queue: Vec<RelayReqId>,
     pub(crate) nip11: Nip11FetchLifecycle,
+    /// NIPs the relay advertises in its NIP-11 document
+    pub(crate) supported_nips: HashSet<u32>,
 }
 
 impl CoordinationData {
@@ -50,6 +56,7 @@ impl CoordinationData {
             broadcast_cache: Default::default(),
             eose_queue: Vec::new(),
             nip11: Nip11FetchLifecycle::default(),
+            supported_nips: HashSet::new(),
         }
     }
 
@@ -61,6 +68,29 @@ impl CoordinationData {
         }
     }
 
+    /// Change how REQs to this relay are staggered.
+    pub fn set_req_pacing(&mut self, pacing: ReqPacing) {
+        if let Some(websocket) = &mut self.websocket {
+            websocket.reqs.set_pacing(pacing);
+        }
+    }
+
+    /// Send the queued REQs this relay's pacing allows right now.
+    pub fn flush_reqs(&mut self, now: Instant) {
+        if let Some(websocket) = &mut self.websocket {
+            websocket.flush_reqs(now);
+        }
+    }
+
+    /// How long until the next queued REQ can go out, if any are queued.
+    pub fn next_req_release(&self, now: Instant) -> Option<Duration> {
+        let websocket = self.websocket.as_ref()?;
+        if !websocket.is_connected() {
+            return None;
+        }
+        websocket.reqs.next_release(now)
+    }
+
     /// Apply new effective relay limits to the coordinator.
     pub fn set_limits(&mut self, subs: &OutboxSubscriptions, limits: RelayLimitations) {
         let json_limit_shrunk = limits.max_json_bytes < self.limits.max_json_bytes;
@@ -196,8 +226,8 @@ impl CoordinationData {
         // Drain EOSE queue and collect IDs
         for sid in self.eose_queue.drain(..) {
             // Try compaction first
-            let Some(compaction_ids) = self.compaction_data.ids(&sid) else {
-                let Some(transparent_id) = self.transparent_data.id(&sid) else {
+            let Some(compaction_ids) = self.compaction_data.ids(&sid.0) else {
+                let Some(transparent_id) = self.transparent_data.id(&sid.0) else {
                     continue;
                 };
 
@@ -304,7 +334,12 @@ impl CoordinationData {
 
     // whether we received
     #[profiling::function]
-    pub(crate) fn try_recv<F>(&mut self, subs: &OutboxSubscriptions, act: &mut F) -> RecvResponse
+    pub(crate) fn try_recv<F>(
+        &mut self,
+        subs: &OutboxSubscriptions,
+        max_event_bytes: usize,
+        act: &mut F,
+    ) -> RecvResponse
     where
         for<'a> F: FnMut(RawEventData<'a>),
     {
@@ -321,12 +356,14 @@ impl CoordinationData {
             event
         };
 
+        let mut incident = None;
         let msg = match &event {
             WsEvent::Opened => {
                 websocket.conn.set_status(RelayStatus::Connected);
                 websocket.reconnect_attempt = 0;
                 websocket.last_pong = Instant::now();
                 websocket.retry_connect_after = WebsocketRelay::initial_reconnect_duration();
+                websocket.reqs.reset();
                 handle_relay_open(
                     websocket,
                     &mut self.broadcast_cache,
@@ -345,6 +382,7 @@ impl CoordinationData {
             WsEvent::Error(err) => {
                 tracing::error!("relay {} error: {:?}", websocket.conn.url, err);
                 websocket.conn.set_status(RelayStatus::Disconnected);
+                incident = Some((RelayIncidentKind::ConnectionError, Some(format!("{err:?}"))));
                 None
             }
             WsEvent::Message(ws_message) => match ws_message {
@@ -368,6 +406,10 @@ impl CoordinationData {
                                 websocket.conn.url,
                                 err
                             );
+                            incident = Some((
+                                RelayIncidentKind::MalformedMessage,
+                                Some(format!("{err:?}")),
+                            ));
                             None
                         }
                     }
@@ -377,13 +419,26 @@ impl CoordinationData {
         };
 
         let mut resp = RecvResponse::received();
+        resp.incident = incident;
         let Some(msg) = msg else {
             return resp;
         };
 
         match msg {
-            RelayMessage::OK(cr) => tracing::info!("OK {:?}", cr),
+            RelayMessage::OK(cr) => {
+                tracing::info!("OK {:?}", cr);
+                if let Ok(note_id) = NoteId::from_hex(cr.event_id()) {
+                    resp.ack = Some((
+                        note_id,
+                        PublishAck {
+                            accepted: cr.status(),
+                            message: cr.message().to_owned(),
+                        },
+                    ));
+                }
+            }
             RelayMessage::Eose(sid) => {
+                websocket.reqs.eose(sid);
                 tracing::debug!("Relay {} received EOSE for subscription: {sid}", self.url());
                 self.compaction_data
                     .set_req_status(sid, RelayReqStatus::Eose);
@@ -391,20 +446,47 @@ impl CoordinationData {
                     .set_req_status(sid, RelayReqStatus::Eose);
                 self.eose_queue.push(RelayReqId(sid.to_string()));
             }
-            RelayMessage::Event(_, ev) => {
+            RelayMessage::Event(sid, ev) => {
                 profiling::scope!("ingest event");
                 resp.event_was_nostr_note = true;
+
+                let header = match self.compaction_data.ids(sid) {
+                    Some(ids) => validate_event(ev, max_event_bytes, subs.guards(ids)),
+                    None => {
+                        let id = self.transparent_data.id(sid);
+                        validate_event(ev, max_event_bytes, subs.guards(id.as_ref()))
+                    }
+                };
+
+                let header = match header {
+                    Ok(header) => header,
+                    Err(rejection) => {
+                        tracing::debug!("dropping event from {}: {rejection}", websocket.conn.url);
+                        let kind = match rejection {
+                            EventRejection::Oversized(_) => RelayIncidentKind::OversizedEvent,
+                            EventRejection::Unrequested { .. } => {
+                                RelayIncidentKind::UnrequestedEvent
+                            }
+                        };
+                        resp.incident = Some((kind, Some(rejection.to_string())));
+                        return resp;
+                    }
+                };
+
                 act(RawEventData {
                     url: websocket.conn.url.as_str(),
                     event_json: ev,
+                    kind: header.map(|header| header.kind),
                     relay_type: RelayImplType::Websocket,
                 });
             }
             RelayMessage::Notice(msg) => {
                 tracing::warn!("Notice from {}: {}", self.url(), msg)
             }
-            RelayMessage::Closed(sid, _) => {
+            RelayMessage::Closed(sid, reason) => {
+                websocket.reqs.closed(sid);
                 tracing::trace!("Relay {} received CLOSED: {sid}", self.url());
+                resp.incident = Some((RelayIncidentKind::SubClosed, Some(reason.to_owned())));
                 self.compaction_data
                     .set_req_status(sid, RelayReqStatus::Closed);
                 self.transparent_data
@@ -420,6 +502,10 @@ impl CoordinationData {
 pub struct RecvResponse {
     pub received_event: bool,
     pub event_was_nostr_note: bool,
+    /// Misbehaviour observed while handling this message, if any
+    pub incident: Option<(RelayIncidentKind, Option<String>)>,
+    /// The relay's answer to a note we published
+    pub ack: Option<(NoteId, PublishAck)>,
 }
 
 impl RecvResponse {
@@ -427,6 +513,8 @@ impl RecvResponse {
         RecvResponse {
             received_event: true,
             event_was_nostr_note: false,
+            incident: None,
+            ack: None,
         }
     }
 }
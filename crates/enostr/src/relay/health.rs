@@ -0,0 +1,282 @@
+use hashbrown::{HashMap, HashSet};
+use std::{
+    collections::VecDeque,
+    time::{Duration, SystemTime},
+};
+
+use crate::relay::NormRelayUrl;
+
+/// Maximum number of incidents retained per relay.
+const MAX_INCIDENTS_PER_RELAY: usize = 32;
+
+/// Window in which incidents count towards a review suggestion.
+const REVIEW_WINDOW: Duration = Duration::from_secs(10 * 60);
+
+/// Number of incidents within [`REVIEW_WINDOW`] before a relay is flagged.
+const REVIEW_THRESHOLD: usize = 5;
+
+/// The kind of misbehaviour observed on a relay connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RelayIncidentKind {
+    /// Opening the websocket failed outright.
+    ConnectFailed,
+    /// The websocket reported an error and was dropped.
+    ConnectionError,
+    /// No pong was received within the keepalive timeout.
+    PongTimeout,
+    /// The relay closed one of our subscriptions.
+    SubClosed,
+    /// The relay sent a message we could not decode.
+    MalformedMessage,
+    /// The relay sent an event that failed ingestion (bad id, signature, ...).
+    InvalidEvent,
+    /// The relay sent an event whose signature doesn't match its id and
+    /// pubkey.
+    InvalidSignature,
+    /// The relay sent an event larger than we accept.
+    OversizedEvent,
+    /// The relay sent an event that doesn't match the subscription it was
+    /// sent on.
+    UnrequestedEvent,
+}
+
+impl RelayIncidentKind {
+    /// Whether the incident means an event from the relay was dropped.
+    pub fn is_rejected_event(&self) -> bool {
+        matches!(
+            self,
+            RelayIncidentKind::OversizedEvent | RelayIncidentKind::UnrequestedEvent
+        )
+    }
+
+    /// How much the incident counts towards flagging the relay. A forged
+    /// event is reason enough on its own, the rest need to pile up.
+    fn review_weight(&self) -> usize {
+        match self {
+            RelayIncidentKind::InvalidSignature => REVIEW_THRESHOLD,
+            _ => 1,
+        }
+    }
+}
+
+/// A single structured failure record for a relay.
+#[derive(Debug, Clone)]
+pub struct RelayIncident {
+    pub kind: RelayIncidentKind,
+    pub at: SystemTime,
+    pub detail: Option<String>,
+}
+
+/// Tracks relay incidents and the session blacklist.
+///
+/// Blocked relays are never connected to, subscribed on or published to until
+/// they are unblocked. The blacklist only lives for the current session.
+#[derive(Default)]
+pub struct RelayHealth {
+    incidents: HashMap<NormRelayUrl, VecDeque<RelayIncident>>,
+    blocked: HashSet<NormRelayUrl>,
+    /// Events dropped per relay this session, not capped like the log
+    rejected_events: HashMap<NormRelayUrl, u64>,
+    /// Events with bad signatures per relay this session
+    invalid_signatures: HashMap<NormRelayUrl, u64>,
+}
+
+impl RelayHealth {
+    pub fn record(
+        &mut self,
+        relay: &NormRelayUrl,
+        kind: RelayIncidentKind,
+        detail: Option<String>,
+        at: SystemTime,
+    ) {
+        if kind.is_rejected_event() {
+            *self.rejected_events.entry(relay.clone()).or_default() += 1;
+        }
+        if kind == RelayIncidentKind::InvalidSignature {
+            *self.invalid_signatures.entry(relay.clone()).or_default() += 1;
+        }
+
+        let log = self.incidents.entry(relay.clone()).or_default();
+        if log.len() >= MAX_INCIDENTS_PER_RELAY {
+            log.pop_front();
+        }
+        log.push_back(RelayIncident { kind, at, detail });
+    }
+
+    /// All retained incidents for the relay, oldest first.
+    pub fn incidents(&self, relay: &NormRelayUrl) -> impl Iterator<Item = &RelayIncident> {
+        self.incidents.get(relay).into_iter().flatten()
+    }
+
+    /// Number of events from the relay dropped as oversized or unrequested
+    /// this session.
+    pub fn rejected_events(&self, relay: &NormRelayUrl) -> u64 {
+        self.rejected_events.get(relay).copied().unwrap_or(0)
+    }
+
+    /// Number of events from the relay with bad signatures this session.
+    pub fn invalid_signatures(&self, relay: &NormRelayUrl) -> u64 {
+        self.invalid_signatures.get(relay).copied().unwrap_or(0)
+    }
+
+    fn recent(
+        &self,
+        relay: &NormRelayUrl,
+        now: SystemTime,
+    ) -> impl Iterator<Item = &RelayIncident> {
+        self.incidents(relay).filter(move |i| {
+            now.duration_since(i.at)
+                .is_ok_and(|elapsed| elapsed <= REVIEW_WINDOW)
+        })
+    }
+
+    /// Number of incidents for the relay within the review window ending at `now`.
+    pub fn recent_count(&self, relay: &NormRelayUrl, now: SystemTime) -> usize {
+        self.recent(relay, now).count()
+    }
+
+    /// Whether the relay has failed often enough that the user should be
+    /// offered to block it.
+    pub fn needs_review(&self, relay: &NormRelayUrl, now: SystemTime) -> bool {
+        !self.is_blocked(relay)
+            && self
+                .recent(relay, now)
+                .map(|i| i.kind.review_weight())
+                .sum::<usize>()
+                >= REVIEW_THRESHOLD
+    }
+
+    pub fn is_blocked(&self, relay: &NormRelayUrl) -> bool {
+        self.blocked.contains(relay)
+    }
+
+    /// Returns true if the relay was not already blocked.
+    pub fn block(&mut self, relay: NormRelayUrl) -> bool {
+        self.blocked.insert(relay)
+    }
+
+    /// Returns true if the relay was blocked. Its incident log is cleared so
+    /// it isn't immediately flagged again.
+    pub fn unblock(&mut self, relay: &NormRelayUrl) -> bool {
+        if !self.blocked.remove(relay) {
+            return false;
+        }
+        self.incidents.remove(relay);
+        true
+    }
+
+    pub fn blocked(&self) -> impl Iterator<Item = &NormRelayUrl> {
+        self.blocked.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url() -> NormRelayUrl {
+        NormRelayUrl::new("wss://relay.example.com").unwrap()
+    }
+
+    /// Relays are flagged for review only after enough recent incidents.
+    #[test]
+    fn needs_review_after_threshold() {
+        let mut health = RelayHealth::default();
+        let relay = url();
+        let now = SystemTime::now();
+
+        for _ in 0..REVIEW_THRESHOLD - 1 {
+            health.record(&relay, RelayIncidentKind::ConnectFailed, None, now);
+        }
+        assert!(!health.needs_review(&relay, now));
+
+        health.record(&relay, RelayIncidentKind::PongTimeout, None, now);
+        assert!(health.needs_review(&relay, now));
+    }
+
+    /// Incidents older than the review window don't count.
+    #[test]
+    fn old_incidents_are_ignored() {
+        let mut health = RelayHealth::default();
+        let relay = url();
+        let now = SystemTime::now();
+        let old = now - REVIEW_WINDOW - Duration::from_secs(1);
+
+        for _ in 0..REVIEW_THRESHOLD {
+            health.record(&relay, RelayIncidentKind::SubClosed, None, old);
+        }
+
+        assert_eq!(health.recent_count(&relay, now), 0);
+        assert!(!health.needs_review(&relay, now));
+    }
+
+    /// The per-relay log is bounded.
+    #[test]
+    fn incident_log_is_capped() {
+        let mut health = RelayHealth::default();
+        let relay = url();
+        let now = SystemTime::now();
+
+        for _ in 0..MAX_INCIDENTS_PER_RELAY + 10 {
+            health.record(&relay, RelayIncidentKind::MalformedMessage, None, now);
+        }
+
+        assert_eq!(health.incidents(&relay).count(), MAX_INCIDENTS_PER_RELAY);
+    }
+
+    /// Dropped events keep counting after the log has wrapped.
+    #[test]
+    fn rejected_events_are_counted() {
+        let mut health = RelayHealth::default();
+        let relay = url();
+        let now = SystemTime::now();
+
+        for _ in 0..MAX_INCIDENTS_PER_RELAY + 10 {
+            health.record(&relay, RelayIncidentKind::UnrequestedEvent, None, now);
+        }
+        health.record(&relay, RelayIncidentKind::OversizedEvent, None, now);
+        health.record(&relay, RelayIncidentKind::PongTimeout, None, now);
+
+        assert_eq!(
+            health.rejected_events(&relay),
+            MAX_INCIDENTS_PER_RELAY as u64 + 11
+        );
+    }
+
+    /// A single forged event flags the relay and is counted separately.
+    #[test]
+    fn invalid_signatures_flag_right_away() {
+        let mut health = RelayHealth::default();
+        let relay = url();
+        let now = SystemTime::now();
+
+        health.record(&relay, RelayIncidentKind::InvalidSignature, None, now);
+
+        assert!(health.needs_review(&relay, now));
+        assert_eq!(health.recent_count(&relay, now), 1);
+        assert_eq!(health.invalid_signatures(&relay), 1);
+        assert_eq!(health.rejected_events(&relay), 0);
+    }
+
+    /// Blocked relays are not flagged and unblocking clears their history.
+    #[test]
+    fn block_and_unblock() {
+        let mut health = RelayHealth::default();
+        let relay = url();
+        let now = SystemTime::now();
+
+        for _ in 0..REVIEW_THRESHOLD {
+            health.record(&relay, RelayIncidentKind::ConnectionError, None, now);
+        }
+
+        assert!(health.block(relay.clone()));
+        assert!(!health.block(relay.clone()));
+        assert!(health.is_blocked(&relay));
+        assert!(!health.needs_review(&relay, now));
+
+        assert!(health.unblock(&relay));
+        assert!(!health.is_blocked(&relay));
+        assert_eq!(health.incidents(&relay).count(), 0);
+        assert!(!health.unblock(&relay));
+    }
+}
@@ -8,6 +8,22 @@ pub struct CommandResult<'a> {
     message: &'a str,
 }
 
+impl<'a> CommandResult<'a> {
+    /// Hex id of the event the relay is answering about
+    pub fn event_id(&self) -> &'a str {
+        self.event_id
+    }
+
+    /// Whether the relay accepted the event
+    pub fn status(&self) -> bool {
+        self.status
+    }
+
+    pub fn message(&self) -> &'a str {
+        self.message
+    }
+}
+
 pub fn calculate_command_result_size(result: &CommandResult) -> usize {
     std::mem::size_of_val(result) + result.event_id.len() + result.message.len()
 }
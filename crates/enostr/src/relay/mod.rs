@@ -2,20 +2,25 @@ mod backoff;
 mod broadcast;
 mod compaction;
 mod coordinator;
+mod health;
 mod identity;
 mod limits;
 pub mod message;
 mod multicast;
 mod nip11;
 mod outbox;
+mod pacing;
 pub mod pool;
+mod publish_acks;
 mod queue;
 pub mod subs_debug;
 mod subscription;
 mod transparent;
+mod validation;
 mod websocket;
 
 pub use broadcast::{BroadcastCache, BroadcastRelay};
+pub use health::{RelayHealth, RelayIncident, RelayIncidentKind};
 pub use identity::{
     NormRelayUrl, OutboxSubId, RelayId, RelayReqId, RelayReqStatus, RelayType, RelayUrlPkgs,
 };
@@ -26,11 +31,14 @@ pub use multicast::{MulticastRelay, MulticastRelayCache};
 pub use nip11::{Nip11ApplyOutcome, Nip11FetchRequest, Nip11LimitationsRaw};
 use nostrdb::Filter;
 pub use outbox::{OutboxPool, OutboxSession, OutboxSessionHandler};
+pub use pacing::ReqPacing;
+pub use publish_acks::{PublishAck, PublishAcks};
 pub use queue::QueuedTasks;
 pub use subscription::{
     FullModificationTask, ModifyFiltersTask, ModifyRelaysTask, ModifyTask, OutboxSubscriptions,
     OutboxTask, SubscribeTask,
 };
+pub use validation::EventGuard;
 pub use websocket::{WebsocketConn, WebsocketRelay};
 
 #[cfg(test)]
@@ -52,6 +60,9 @@ enum UnownedRelay<'a> {
 pub struct RawEventData<'a> {
     pub url: &'a str,
     pub event_json: &'a str,
+    /// The event's kind, when the relay already parsed it to check the
+    /// event against its subscription
+    pub kind: Option<u64>,
     pub relay_type: RelayImplType,
 }
 
@@ -69,6 +80,7 @@ pub enum RelayTask {
 pub struct FilterMetadata {
     filter_json_size: usize,
     last_seen: Option<u64>,
+    guard: EventGuard,
 }
 
 pub struct MetadataFilters {
@@ -83,6 +95,7 @@ impl MetadataFilters {
             .map(|f| FilterMetadata {
                 filter_json_size: f.json().ok().map(|j| j.len()).unwrap_or(0),
                 last_seen: None,
+                guard: EventGuard::from_filter(f),
             })
             .collect();
         Self { filters, meta }
@@ -106,6 +119,11 @@ impl MetadataFilters {
         &self.filters
     }
 
+    /// What incoming events each filter allows, in filter order.
+    pub fn guards(&self) -> impl Iterator<Item = &EventGuard> {
+        self.meta.iter().map(|m| &m.guard)
+    }
+
     #[allow(dead_code)]
     pub fn iter(&self) -> MetadataFiltersIter<'_> {
         MetadataFiltersIter {
@@ -218,6 +218,7 @@ impl MulticastRelayCache {
         process(RawEventData {
             url: "multicast",
             event_json: &text,
+            kind: None,
             relay_type: RelayImplType::Multicast,
         });
     }
@@ -65,6 +65,14 @@ where
         self.outbox.broadcast_note(note, relays, &self.wakeup);
     }
 
+    pub fn block_relay(&mut self, relay: &NormRelayUrl) {
+        self.outbox.block_relay(relay);
+    }
+
+    pub fn unblock_relay(&mut self, relay: &NormRelayUrl) {
+        self.outbox.unblock_relay(relay, &self.wakeup);
+    }
+
     /// Eject the session from the handler.
     /// This is only necessary between initialization of the app and the first frame
     pub fn export(mut self) -> OutboxSession {
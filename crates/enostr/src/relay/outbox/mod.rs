@@ -9,6 +9,10 @@ use crate::{
     relay::{
         backoff,
         coordinator::{CoordinationData, CoordinationSession, EoseIds},
+        health::{RelayHealth, RelayIncidentKind},
+        pacing::ReqPacing,
+        publish_acks::PublishAcks,
+        validation::DEFAULT_MAX_EVENT_BYTES,
         websocket::WebsocketRelay,
         ModifyTask, MulticastRelayCache, Nip11ApplyOutcome, Nip11FetchRequest, Nip11LimitationsRaw,
         NormRelayUrl, OutboxSubId, OutboxSubscriptions, OutboxTask, RawEventData, RelayId,
@@ -36,6 +40,10 @@ pub struct OutboxPool {
     subs: OutboxSubscriptions,
     multicast: MulticastRelayCache,
     pong_timeout: Duration,
+    max_event_bytes: usize,
+    req_pacing: ReqPacing,
+    health: RelayHealth,
+    acks: PublishAcks,
 }
 
 impl Default for OutboxPool {
@@ -46,6 +54,10 @@ impl Default for OutboxPool {
             multicast: Default::default(),
             subs: Default::default(),
             pong_timeout: PONG_TIMEOUT,
+            max_event_bytes: DEFAULT_MAX_EVENT_BYTES,
+            req_pacing: ReqPacing::default(),
+            health: RelayHealth::default(),
+            acks: PublishAcks::default(),
         }
     }
 }
@@ -57,6 +69,31 @@ impl OutboxPool {
         self.pong_timeout = timeout;
     }
 
+    /// Events from relays larger than this are dropped and counted against
+    /// the relay instead of being ingested.
+    pub fn set_max_event_bytes(&mut self, max_bytes: usize) {
+        self.max_event_bytes = max_bytes;
+    }
+
+    /// Controls how REQs are staggered per relay so a cold start with many
+    /// columns doesn't trip relay rate limits.
+    pub fn set_req_pacing(&mut self, pacing: ReqPacing) {
+        self.req_pacing = pacing;
+        for relay in self.relays.values_mut() {
+            relay.set_req_pacing(pacing);
+        }
+    }
+
+    /// How long until some relay has a queued REQ ready to send. Callers
+    /// should poll again by then so paced REQs don't stall.
+    pub fn next_req_release(&self) -> Option<Duration> {
+        let now = Instant::now();
+        self.relays
+            .values()
+            .filter_map(|relay| relay.next_req_release(now))
+            .min()
+    }
+
     fn remove_completed_oneshots(&mut self, ids: HashSet<OutboxSubId>) {
         for id in ids {
             if self.all_have_eose(&id) {
@@ -200,6 +237,11 @@ impl OutboxPool {
     {
         let mut pending_eoses = EoseIds::default();
         for (relay_id, session) in sessions {
+            if self.health.is_blocked(&relay_id) {
+                // the subscription keeps the relay so it resumes on unblock
+                continue;
+            }
+
             let relay = match self.relays.raw_entry_mut().from_key(&relay_id) {
                 RawEntryMut::Occupied(e) => 's: {
                     let res = e.into_mut();
@@ -215,11 +257,13 @@ impl OutboxPool {
                     };
 
                     res.websocket = Some(WebsocketRelay::new(websocket));
+                    res.set_req_pacing(self.req_pacing);
 
                     res
                 }
                 RawEntryMut::Vacant(e) => {
-                    let coordinator = build_relay(relay_id.clone(), wakeup.clone());
+                    let coordinator =
+                        build_relay(relay_id.clone(), self.req_pacing, wakeup.clone());
                     let (_, res) = e.insert(relay_id, coordinator);
                     res
                 }
@@ -243,6 +287,24 @@ impl OutboxPool {
         }
     }
 
+    /// Send CLOSE for every open subscription, so relays can drop them right
+    /// away on shutdown instead of waiting for the connection to time out.
+    pub fn close_all_subscriptions<W>(&mut self, wakeup: W)
+    where
+        W: Wakeup,
+    {
+        let ids: Vec<OutboxSubId> = self.subs.iter().map(|(id, _)| *id).collect();
+        if ids.is_empty() {
+            return;
+        }
+
+        tracing::debug!("closing {} subscriptions", ids.len());
+        let mut handler = self.start_session(wakeup);
+        for id in ids {
+            handler.unsubscribe(id);
+        }
+    }
+
     pub fn broadcast_note<W>(&mut self, note: &Note, relays: Vec<RelayId>, wakeup: &W)
     where
         W: Wakeup,
@@ -253,6 +315,9 @@ impl OutboxPool {
             };
             match relay_id {
                 RelayId::Websocket(norm_relay_url) => {
+                    if self.health.is_blocked(&norm_relay_url) {
+                        continue;
+                    }
                     let rel = self.ensure_relay(&norm_relay_url, wakeup);
                     rel.send_event(msg);
                 }
@@ -269,7 +334,7 @@ impl OutboxPool {
 
     #[profiling::function]
     pub fn keepalive_ping(&mut self, wakeup: impl Fn() + Send + Sync + Clone + 'static) {
-        for relay in self.relays.values_mut() {
+        for (relay_url, relay) in self.relays.iter_mut() {
             let now = Instant::now();
 
             let Some(websocket) = &mut relay.websocket else {
@@ -298,6 +363,12 @@ impl OutboxPool {
                         websocket.retry_connect_after = next_duration;
                         if let Err(err) = websocket.conn.connect(wakeup.clone()) {
                             tracing::error!("error connecting to relay: {}", err);
+                            self.health.record(
+                                relay_url,
+                                RelayIncidentKind::ConnectFailed,
+                                Some(err.to_string()),
+                                SystemTime::now(),
+                            );
                         }
                     }
                 }
@@ -314,6 +385,12 @@ impl OutboxPool {
                             websocket.conn.url
                         );
                         websocket.conn.set_status(RelayStatus::Disconnected);
+                        self.health.record(
+                            relay_url,
+                            RelayIncidentKind::PongTimeout,
+                            None,
+                            SystemTime::now(),
+                        );
                         continue;
                     }
 
@@ -394,6 +471,37 @@ impl OutboxPool {
         Nip11ApplyOutcome::Applied
     }
 
+    /// Remember which NIPs a relay advertises in its NIP-11 document.
+    pub fn set_supported_nips(
+        &mut self,
+        relay: &NormRelayUrl,
+        nips: impl IntoIterator<Item = u32>,
+    ) -> Nip11ApplyOutcome {
+        let Some(coord) = self.relays.get_mut(relay) else {
+            return Nip11ApplyOutcome::RelayUnknown;
+        };
+
+        let nips: HashSet<u32> = nips.into_iter().collect();
+        if coord.supported_nips == nips {
+            return Nip11ApplyOutcome::Unchanged;
+        }
+
+        coord.supported_nips = nips;
+        Nip11ApplyOutcome::Applied
+    }
+
+    /// Relays in the pool that advertise support for `nip`, sorted by url.
+    pub fn relays_supporting(&self, nip: u32) -> Vec<&NormRelayUrl> {
+        let mut relays: Vec<&NormRelayUrl> = self
+            .relays
+            .iter()
+            .filter(|(_, coord)| coord.supported_nips.contains(&nip))
+            .map(|(url, _)| url)
+            .collect();
+        relays.sort();
+        relays
+    }
+
     /// Record a failed NIP-11 fetch so the relay can be retried later.
     pub fn record_nip11_failure(
         &mut self,
@@ -421,13 +529,76 @@ impl OutboxPool {
             RawEntryMut::Vacant(entry) => {
                 let (_, res) = entry.insert(
                     relay_id.clone(),
-                    build_relay(relay_id.clone(), wakeup.clone()),
+                    build_relay(relay_id.clone(), self.req_pacing, wakeup.clone()),
                 );
                 res
             }
         }
     }
 
+    /// Incident log and session blacklist for all relays.
+    pub fn health(&self) -> &RelayHealth {
+        &self.health
+    }
+
+    /// What relays answered to the notes we published recently.
+    pub fn publish_acks(&self) -> &PublishAcks {
+        &self.acks
+    }
+
+    /// Record an incident observed outside of the outbox, e.g. an event from
+    /// this relay that failed ingestion.
+    pub fn record_relay_incident(
+        &mut self,
+        relay: &NormRelayUrl,
+        kind: RelayIncidentKind,
+        detail: Option<String>,
+    ) {
+        self.health.record(relay, kind, detail, SystemTime::now());
+    }
+
+    /// Blacklist a relay for the rest of the session and drop its connection.
+    ///
+    /// Subscriptions keep the relay in their relay set, so they pick it back up
+    /// when it is unblocked.
+    pub fn block_relay(&mut self, relay: &NormRelayUrl) {
+        if !self.health.block(relay.clone()) {
+            return;
+        }
+
+        tracing::info!("blocking relay {relay} for this session");
+        self.relays.remove(relay);
+    }
+
+    /// Remove a relay from the blacklist and resubscribe every subscription
+    /// that still targets it.
+    pub fn unblock_relay<W>(&mut self, relay: &NormRelayUrl, wakeup: &W)
+    where
+        W: Wakeup,
+    {
+        if !self.health.unblock(relay) {
+            return;
+        }
+
+        tracing::info!("unblocking relay {relay}");
+        let mut sessions: HashMap<NormRelayUrl, CoordinationSession> = HashMap::new();
+        for (id, sub) in self.subs.iter() {
+            if !sub.relays.contains(relay) {
+                continue;
+            }
+
+            get_session(&mut sessions, relay)
+                .subscribe(*id, sub.relay_type == RelayType::Transparent);
+        }
+
+        if sessions.is_empty() {
+            return;
+        }
+
+        let eose_ids = self.process_sessions(sessions, wakeup);
+        self.remove_completed_oneshots(eose_ids.oneshots);
+    }
+
     pub fn status(&self, id: &OutboxSubId) -> HashMap<&NormRelayUrl, RelayReqStatus> {
         let mut status = HashMap::new();
         for (url, relay) in &self.relays {
@@ -491,8 +662,17 @@ impl OutboxPool {
         's: while max_notes > 0 {
             let mut received_any = false;
 
-            for relay in self.relays.values_mut() {
-                let resp = relay.try_recv(&self.subs, &mut process);
+            for (relay_url, relay) in self.relays.iter_mut() {
+                let resp = relay.try_recv(&self.subs, self.max_event_bytes, &mut process);
+
+                if let Some((kind, detail)) = resp.incident {
+                    self.health
+                        .record(relay_url, kind, detail, SystemTime::now());
+                }
+
+                if let Some((note_id, ack)) = resp.ack {
+                    self.acks.record(note_id, relay_url, ack);
+                }
 
                 if !resp.received_event {
                     continue;
@@ -513,6 +693,12 @@ impl OutboxPool {
             }
         }
 
+        // EOSEs received above may have freed up slots for queued REQs
+        let now = Instant::now();
+        for relay in self.relays.values_mut() {
+            relay.flush_reqs(now);
+        }
+
         self.multicast.try_recv(process);
     }
 }
@@ -612,15 +798,17 @@ pub fn get_session<'a>(
     }
 }
 
-fn build_relay<W>(relay_id: NormRelayUrl, wakeup: W) -> CoordinationData
+fn build_relay<W>(relay_id: NormRelayUrl, pacing: ReqPacing, wakeup: W) -> CoordinationData
 where
     W: Wakeup,
 {
-    CoordinationData::new(
+    let mut relay = CoordinationData::new(
         RelayLimitations::default(), // TODO(kernelkind): add actual limitations
         relay_id,
         wakeup,
-    )
+    );
+    relay.set_req_pacing(pacing);
+    relay
 }
 
 fn derive_relay_limitations_from_raw(
@@ -799,6 +987,38 @@ mod tests {
         assert_eq!(limits.maximum_subs, 777);
     }
 
+    /// Only relays that advertise a NIP are returned for it.
+    #[test]
+    fn relays_supporting_uses_advertised_nips() {
+        let mut pool = OutboxPool::default();
+        let wakeup = MockWakeup::default();
+        let search = NormRelayUrl::new("wss://relay-search.example.com").unwrap();
+        let plain = NormRelayUrl::new("wss://relay-plain.example.com").unwrap();
+        let unknown = NormRelayUrl::new("wss://relay-unknown.example.com").unwrap();
+        let _ = pool.ensure_relay(&search, &wakeup);
+        let _ = pool.ensure_relay(&plain, &wakeup);
+
+        assert_eq!(
+            pool.set_supported_nips(&search, [1, 11, 50]),
+            Nip11ApplyOutcome::Applied
+        );
+        assert_eq!(
+            pool.set_supported_nips(&search, [1, 11, 50]),
+            Nip11ApplyOutcome::Unchanged
+        );
+        assert_eq!(
+            pool.set_supported_nips(&plain, [1, 11]),
+            Nip11ApplyOutcome::Applied
+        );
+        assert_eq!(
+            pool.set_supported_nips(&unknown, [50]),
+            Nip11ApplyOutcome::RelayUnknown
+        );
+
+        assert_eq!(pool.relays_supporting(50), vec![&search]);
+        assert_eq!(pool.relays_supporting(11).len(), 2);
+    }
+
     // ==================== OutboxPool tests ====================
 
     /// Default pool has no relays or subscriptions.
@@ -886,6 +1106,41 @@ mod tests {
         assert!(matches!(new_task, CoordinationTask::CompactionSub));
     }
 
+    /// Blocked relays are dropped and skipped by new subscriptions, and come
+    /// back for subscriptions that still target them once unblocked.
+    #[test]
+    fn blocked_relays_are_skipped_until_unblocked() {
+        let mut pool = OutboxPool::default();
+        let wakeup = MockWakeup::default();
+        let relay = NormRelayUrl::new("wss://relay-blocked.example.com").unwrap();
+
+        let mut urls = HashSet::new();
+        urls.insert(relay.clone());
+        {
+            let mut handler = pool.start_session(wakeup.clone());
+            handler.subscribe(trivial_filter(), RelayUrlPkgs::new(urls.clone()));
+        }
+        assert!(pool.relays.contains_key(&relay));
+
+        pool.block_relay(&relay);
+        assert!(pool.health().is_blocked(&relay));
+        assert!(!pool.relays.contains_key(&relay));
+
+        let blocked_sub = {
+            let mut handler = pool.start_session(wakeup.clone());
+            handler.subscribe(trivial_filter(), RelayUrlPkgs::new(urls))
+        };
+        assert!(!pool.relays.contains_key(&relay));
+        assert!(pool
+            .subs
+            .get(&blocked_sub)
+            .is_some_and(|sub| sub.relays.contains(&relay)));
+
+        pool.unblock_relay(&relay, &wakeup);
+        assert!(!pool.health().is_blocked(&relay));
+        assert!(pool.relays.contains_key(&relay));
+    }
+
     /// Oneshot requests route to compaction mode by default.
     #[test]
     fn oneshot_routes_to_compaction() {
@@ -938,6 +1193,35 @@ mod tests {
         assert!(matches!(task_b, Some(CoordinationTask::Unsubscribe)));
     }
 
+    /// Closing everything unsubscribes every subscription on every relay leg.
+    #[test]
+    fn close_all_subscriptions_unsubscribes_everything() {
+        let mut pool = OutboxPool::default();
+        let relay_a = NormRelayUrl::new("wss://relay-a.example.com").unwrap();
+        let relay_b = NormRelayUrl::new("wss://relay-b.example.com").unwrap();
+
+        let (first, second) = {
+            let mut handler = pool.start_session(MockWakeup::default());
+            let first = handler.subscribe(
+                trivial_filter(),
+                RelayUrlPkgs::new(HashSet::from([relay_a.clone(), relay_b.clone()])),
+            );
+            let second = handler.subscribe(
+                trivial_filter(),
+                RelayUrlPkgs::new(HashSet::from([relay_b])),
+            );
+            (first, second)
+        };
+        assert!(pool.subs.get(&first).is_some());
+        assert!(pool.subs.get(&second).is_some());
+
+        pool.close_all_subscriptions(MockWakeup::default());
+
+        assert!(pool.subs.get(&first).is_none());
+        assert!(pool.subs.get(&second).is_none());
+        assert_eq!(pool.subs.iter().count(), 0);
+    }
+
     /// Subscriptions with use_transparent=true route to transparent mode.
     #[test]
     fn subscribe_transparent_mode() {
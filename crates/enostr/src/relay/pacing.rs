@@ -0,0 +1,247 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use hashbrown::{HashMap, HashSet};
+use nostrdb::Filter;
+
+/// How many REQs may be waiting on EOSE from one relay at a time.
+pub const DEFAULT_REQ_CONCURRENCY: usize = 4;
+
+/// Minimum spacing between two REQs sent to the same relay.
+pub const DEFAULT_REQ_DELAY: Duration = Duration::from_millis(100);
+
+/// Relays that never send EOSE shouldn't hold a slot forever.
+const IN_FLIGHT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Per-relay limits for releasing REQs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReqPacing {
+    /// REQs sent but not yet answered with EOSE or CLOSED. Zero disables the
+    /// limit.
+    pub concurrency: usize,
+    /// Wait at least this long between REQs.
+    pub delay: Duration,
+}
+
+impl Default for ReqPacing {
+    fn default() -> Self {
+        Self {
+            concurrency: DEFAULT_REQ_CONCURRENCY,
+            delay: DEFAULT_REQ_DELAY,
+        }
+    }
+}
+
+/// Staggers the REQs we send to a single relay.
+///
+/// A busy deck opens dozens of subscriptions at startup. Firing them all at
+/// once trips the rate limit of many relays, which then drop REQs without
+/// telling us. Instead REQs are queued here and released a few at a time as
+/// earlier ones reach EOSE.
+#[derive(Default)]
+pub struct ReqPacer {
+    pacing: ReqPacing,
+    queue: VecDeque<(String, Vec<Filter>)>,
+    in_flight: HashMap<String, Instant>,
+    /// Subscriptions the relay knows about on the current connection
+    open: HashSet<String>,
+    last_sent: Option<Instant>,
+}
+
+impl ReqPacer {
+    pub fn pacing(&self) -> ReqPacing {
+        self.pacing
+    }
+
+    pub fn set_pacing(&mut self, pacing: ReqPacing) {
+        self.pacing = pacing;
+    }
+
+    /// Queue a REQ. A REQ still waiting for the same subscription is replaced
+    /// in place so it keeps its position.
+    pub fn push(&mut self, sid: String, filters: Vec<Filter>) {
+        if let Some(queued) = self.queue.iter_mut().find(|(id, _)| *id == sid) {
+            queued.1 = filters;
+            return;
+        }
+
+        self.queue.push_back((sid, filters));
+    }
+
+    /// Forget a subscription we're closing. Returns whether the relay has
+    /// seen its REQ, i.e. whether a CLOSE still has to be sent.
+    pub fn close(&mut self, sid: &str) -> bool {
+        self.queue.retain(|(id, _)| id != sid);
+        self.in_flight.remove(sid);
+        self.open.remove(sid)
+    }
+
+    /// The relay sent EOSE, freeing the slot
+    pub fn eose(&mut self, sid: &str) {
+        self.in_flight.remove(sid);
+    }
+
+    /// The relay closed the subscription on its own
+    pub fn closed(&mut self, sid: &str) {
+        self.in_flight.remove(sid);
+        self.open.remove(sid);
+    }
+
+    /// A new connection starts with no subscriptions on the relay. Queued
+    /// REQs are kept and go out once the connection is up.
+    pub fn reset(&mut self) {
+        self.in_flight.clear();
+        self.open.clear();
+        self.last_sent = None;
+    }
+
+    pub fn queued(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Take the next REQ that may go out now, if any.
+    pub fn pop_ready(&mut self, now: Instant) -> Option<(String, Vec<Filter>)> {
+        if self.queue.is_empty() {
+            return None;
+        }
+
+        self.in_flight
+            .retain(|_, sent| now.saturating_duration_since(*sent) < IN_FLIGHT_TIMEOUT);
+
+        if self.wait(now).is_some() {
+            return None;
+        }
+
+        let (sid, filters) = self.queue.pop_front()?;
+        self.in_flight.insert(sid.clone(), now);
+        self.open.insert(sid.clone());
+        self.last_sent = Some(now);
+
+        Some((sid, filters))
+    }
+
+    /// How long until the next queued REQ may go out. `None` when nothing is
+    /// queued.
+    pub fn next_release(&self, now: Instant) -> Option<Duration> {
+        if self.queue.is_empty() {
+            return None;
+        }
+
+        Some(self.wait(now).unwrap_or_default())
+    }
+
+    fn wait(&self, now: Instant) -> Option<Duration> {
+        let mut wait = Duration::ZERO;
+
+        if let Some(last_sent) = self.last_sent {
+            wait = wait.max((last_sent + self.pacing.delay).saturating_duration_since(now));
+        }
+
+        if self.pacing.concurrency > 0 && self.in_flight.len() >= self.pacing.concurrency {
+            let oldest = self.in_flight.values().min().copied().unwrap_or(now);
+            wait = wait.max((oldest + IN_FLIGHT_TIMEOUT).saturating_duration_since(now));
+        }
+
+        (!wait.is_zero()).then_some(wait)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pacer(concurrency: usize, delay_ms: u64) -> ReqPacer {
+        let mut pacer = ReqPacer::default();
+        pacer.set_pacing(ReqPacing {
+            concurrency,
+            delay: Duration::from_millis(delay_ms),
+        });
+        pacer
+    }
+
+    fn sids(pacer: &mut ReqPacer, now: Instant) -> Vec<String> {
+        std::iter::from_fn(|| pacer.pop_ready(now))
+            .map(|(sid, _)| sid)
+            .collect()
+    }
+
+    #[test]
+    fn concurrency_limits_reqs_awaiting_eose() {
+        let mut pacer = pacer(2, 0);
+        let now = Instant::now();
+        for sid in ["a", "b", "c"] {
+            pacer.push(sid.to_owned(), Vec::new());
+        }
+
+        assert_eq!(sids(&mut pacer, now), ["a", "b"]);
+        assert_eq!(pacer.queued(), 1);
+
+        pacer.eose("a");
+        assert_eq!(sids(&mut pacer, now), ["c"]);
+    }
+
+    #[test]
+    fn delay_spaces_out_reqs() {
+        let mut pacer = pacer(0, 100);
+        let now = Instant::now();
+        pacer.push("a".to_owned(), Vec::new());
+        pacer.push("b".to_owned(), Vec::new());
+
+        assert_eq!(sids(&mut pacer, now), ["a"]);
+        assert_eq!(pacer.next_release(now), Some(Duration::from_millis(100)));
+        assert_eq!(sids(&mut pacer, now + Duration::from_millis(100)), ["b"]);
+        assert_eq!(pacer.next_release(now), None);
+    }
+
+    #[test]
+    fn stuck_reqs_free_their_slot_after_timeout() {
+        let mut pacer = pacer(1, 0);
+        let now = Instant::now();
+        pacer.push("a".to_owned(), Vec::new());
+        pacer.push("b".to_owned(), Vec::new());
+
+        assert_eq!(sids(&mut pacer, now), ["a"]);
+        assert_eq!(pacer.next_release(now), Some(IN_FLIGHT_TIMEOUT));
+        assert_eq!(sids(&mut pacer, now + IN_FLIGHT_TIMEOUT), ["b"]);
+    }
+
+    #[test]
+    fn requeued_sub_keeps_its_place() {
+        let mut pacer = pacer(0, 0);
+        let now = Instant::now();
+        pacer.push("a".to_owned(), Vec::new());
+        pacer.push("b".to_owned(), Vec::new());
+        pacer.push("a".to_owned(), vec![Filter::new().kinds(vec![1]).build()]);
+
+        let (sid, filters) = pacer.pop_ready(now).unwrap();
+        assert_eq!(sid, "a");
+        assert_eq!(filters.len(), 1);
+        assert_eq!(pacer.queued(), 1);
+    }
+
+    #[test]
+    fn closing_an_unsent_req_skips_the_close() {
+        let mut pacer = pacer(1, 0);
+        let now = Instant::now();
+        pacer.push("a".to_owned(), Vec::new());
+        pacer.push("b".to_owned(), Vec::new());
+        assert_eq!(sids(&mut pacer, now), ["a"]);
+
+        assert!(!pacer.close("b"));
+        assert!(pacer.close("a"));
+        assert_eq!(pacer.queued(), 0);
+    }
+
+    #[test]
+    fn reset_forgets_the_old_connection() {
+        let mut pacer = pacer(1, 0);
+        let now = Instant::now();
+        pacer.push("a".to_owned(), Vec::new());
+        pacer.push("b".to_owned(), Vec::new());
+        assert_eq!(sids(&mut pacer, now), ["a"]);
+
+        pacer.reset();
+        assert!(!pacer.close("a"));
+        assert_eq!(sids(&mut pacer, now), ["b"]);
+    }
+}
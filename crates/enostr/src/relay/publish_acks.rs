@@ -0,0 +1,95 @@
+use std::collections::VecDeque;
+
+use crate::{relay::NormRelayUrl, NoteId};
+
+/// How many published notes we keep relay answers for
+const MAX_TRACKED_NOTES: usize = 32;
+
+/// A relay's answer (`OK` message) to a note we published
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublishAck {
+    pub accepted: bool,
+    /// Why the relay rejected the note, or extra detail on acceptance
+    pub message: String,
+}
+
+/// Relay answers to the most recently published notes.
+#[derive(Default)]
+pub struct PublishAcks {
+    notes: VecDeque<(NoteId, Vec<(NormRelayUrl, PublishAck)>)>,
+}
+
+impl PublishAcks {
+    pub fn record(&mut self, note_id: NoteId, relay: &NormRelayUrl, ack: PublishAck) {
+        let acks = match self.notes.iter().position(|(id, _)| *id == note_id) {
+            Some(i) => &mut self.notes[i].1,
+            None => {
+                if self.notes.len() == MAX_TRACKED_NOTES {
+                    self.notes.pop_front();
+                }
+                self.notes.push_back((note_id, Vec::new()));
+                &mut self.notes.back_mut().expect("just pushed").1
+            }
+        };
+
+        // a relay answering twice replaces its first answer
+        match acks.iter_mut().find(|(url, _)| url == relay) {
+            Some((_, existing)) => *existing = ack,
+            None => acks.push((relay.clone(), ack)),
+        }
+    }
+
+    /// Answers received so far for `note_id`, in arrival order
+    pub fn get(&self, note_id: &NoteId) -> &[(NormRelayUrl, PublishAck)] {
+        self.notes
+            .iter()
+            .find(|(id, _)| id == note_id)
+            .map(|(_, acks)| acks.as_slice())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn relay(url: &str) -> NormRelayUrl {
+        NormRelayUrl::new(url).unwrap()
+    }
+
+    fn ack(accepted: bool) -> PublishAck {
+        PublishAck {
+            accepted,
+            message: String::new(),
+        }
+    }
+
+    #[test]
+    fn later_answer_from_a_relay_replaces_the_first() {
+        let mut acks = PublishAcks::default();
+        let note = NoteId::new([1; 32]);
+
+        acks.record(note, &relay("wss://relay.damus.io"), ack(false));
+        acks.record(note, &relay("wss://nos.lol"), ack(true));
+        acks.record(note, &relay("wss://relay.damus.io"), ack(true));
+
+        let got = acks.get(&note);
+        assert_eq!(got.len(), 2);
+        assert!(got.iter().all(|(_, a)| a.accepted));
+    }
+
+    #[test]
+    fn oldest_notes_are_forgotten() {
+        let mut acks = PublishAcks::default();
+        for i in 0..=MAX_TRACKED_NOTES {
+            acks.record(
+                NoteId::new([i as u8; 32]),
+                &relay("wss://nos.lol"),
+                ack(true),
+            );
+        }
+
+        assert!(acks.get(&NoteId::new([0; 32])).is_empty());
+        assert_eq!(acks.get(&NoteId::new([1; 32])).len(), 1);
+    }
+}
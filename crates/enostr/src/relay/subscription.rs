@@ -1,7 +1,9 @@
 use hashbrown::{HashMap, HashSet};
 use nostrdb::Filter;
 
-use crate::relay::{MetadataFilters, NormRelayUrl, OutboxSubId, RelayType, RelayUrlPkgs};
+use crate::relay::{
+    EventGuard, MetadataFilters, NormRelayUrl, OutboxSubId, RelayType, RelayUrlPkgs,
+};
 
 pub struct OutboxSubscription {
     pub relays: HashSet<NormRelayUrl>,
@@ -81,6 +83,17 @@ impl OutboxSubscriptions {
             .collect()
     }
 
+    /// Filter guards of every given subscription, for validating events that
+    /// arrive on a REQ carrying them.
+    pub fn guards<'a>(
+        &'a self,
+        ids: impl IntoIterator<Item = &'a OutboxSubId>,
+    ) -> impl Iterator<Item = &'a EventGuard> {
+        ids.into_iter()
+            .filter_map(|id| self.subs.get(id))
+            .flat_map(|sub| sub.filters.guards())
+    }
+
     pub fn get_mut(&mut self, id: &OutboxSubId) -> Option<&mut OutboxSubscription> {
         self.subs.get_mut(id)
     }
@@ -89,6 +102,10 @@ impl OutboxSubscriptions {
         self.subs.get(id)
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = (&OutboxSubId, &OutboxSubscription)> {
+        self.subs.iter()
+    }
+
     pub fn remove(&mut self, id: &OutboxSubId) {
         self.subs.remove(id);
     }
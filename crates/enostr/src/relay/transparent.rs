@@ -1,13 +1,10 @@
 use hashbrown::HashMap;
 use uuid::Uuid;
 
-use crate::{
-    relay::{
-        subscription::SubscriptionView, MetadataFilters, OutboxSubId, OutboxSubscriptions,
-        QueuedTasks, RelayReqId, RelayReqStatus, RelayTask, SubPass, SubPassGuardian,
-        SubPassRevocation, WebsocketRelay,
-    },
-    ClientMessage,
+use crate::relay::{
+    subscription::SubscriptionView, MetadataFilters, OutboxSubId, OutboxSubscriptions, QueuedTasks,
+    RelayReqId, RelayReqStatus, RelayTask, SubPass, SubPassGuardian, SubPassRevocation,
+    WebsocketRelay,
 };
 
 /// TransparentData tracks the outstanding transparent REQs and their metadata.
@@ -42,7 +39,7 @@ impl TransparentData {
     }
 
     /// Returns the OutboxSubId associated with the given relay subscription ID.
-    pub fn id(&self, sid: &RelayReqId) -> Option<OutboxSubId> {
+    pub fn id(&self, sid: &str) -> Option<OutboxSubId> {
         self.sid_status.get(sid).map(|d| d.sub_req_id)
     }
 }
@@ -130,9 +127,7 @@ impl<'a> TransparentRelay<'a> {
             return;
         };
 
-        if relay.is_connected() {
-            relay.conn.send(&ClientMessage::close(sid.to_string()));
-        }
+        relay.send_close(sid.to_string());
     }
 
     #[profiling::function]
@@ -150,10 +145,7 @@ impl<'a> TransparentRelay<'a> {
                 continue;
             };
 
-            relay.conn.send(&ClientMessage::req(
-                sid.to_string(),
-                view.filters.get_filters().clone(),
-            ));
+            relay.send_req(sid.to_string(), view.filters.get_filters().clone());
         }
     }
 }
@@ -167,10 +159,7 @@ fn send_req(relay: &mut Option<&mut WebsocketRelay>, sid: &RelayReqId, filters:
         return;
     }
 
-    relay.conn.send(&ClientMessage::req(
-        sid.to_string(),
-        filters.get_filters().clone(),
-    ));
+    relay.send_req(sid.to_string(), filters.get_filters().clone());
 }
 
 #[allow(dead_code)]
@@ -204,9 +193,7 @@ pub fn revocate_transparent_subs(
             continue;
         };
 
-        if relay.is_connected() {
-            relay.conn.send(&ClientMessage::close(sid.to_string()));
-        }
+        relay.send_close(sid.to_string());
     }
 }
 
@@ -481,12 +468,12 @@ mod tests {
         let sid = data.request_to_sid.get(&OutboxSubId(0)).unwrap().clone();
 
         // id() should return the OutboxSubId for the relay subscription
-        let outbox_id = data.id(&sid);
+        let outbox_id = data.id(&sid.0);
         assert_eq!(outbox_id, Some(OutboxSubId(0)));
 
         // Unknown sid should return None
         let unknown_sid = RelayReqId::from("unknown");
-        assert!(data.id(&unknown_sid).is_none());
+        assert!(data.id(&unknown_sid.0).is_none());
     }
 
     // ==================== revocate_transparent_subs tests ====================
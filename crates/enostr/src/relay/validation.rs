@@ -0,0 +1,304 @@
+use std::borrow::Cow;
+use std::fmt;
+
+use hashbrown::HashSet;
+use nostrdb::Filter;
+use serde::de::{DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+
+/// Events larger than this are dropped before they reach ndb unless the pool
+/// is configured otherwise. Generous enough for big contact lists and
+/// long-form posts.
+pub const DEFAULT_MAX_EVENT_BYTES: usize = 512 * 1024;
+
+/// The fields of an incoming event we check before ingesting it.
+#[derive(Debug, PartialEq, Eq)]
+pub struct EventHeader<'a> {
+    pub kind: u64,
+    pub pubkey: &'a str,
+}
+
+impl<'a> EventHeader<'a> {
+    /// Parse the header out of a raw `["EVENT", <sub id>, <event>]` message.
+    /// Parsing stops once both fields are found, so the tags and content
+    /// that usually follow them aren't read.
+    pub fn from_message(msg: &'a str) -> Option<Self> {
+        let mut header = None;
+        // stopping early leaves the message unfinished, which serde_json
+        // reports as an error after the header is already out
+        let _ = serde_json::Deserializer::from_str(msg).deserialize_seq(MessageVisitor {
+            header: &mut header,
+        });
+        header
+    }
+}
+
+/// Skips to the event of a `["EVENT", <sub id>, <event>]` message
+struct MessageVisitor<'h, 'a> {
+    header: &'h mut Option<EventHeader<'a>>,
+}
+
+impl<'a> Visitor<'a> for MessageVisitor<'_, 'a> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an EVENT message")
+    }
+
+    fn visit_seq<A: SeqAccess<'a>>(self, mut seq: A) -> Result<(), A::Error> {
+        seq.next_element::<IgnoredAny>()?;
+        seq.next_element::<IgnoredAny>()?;
+        seq.next_element_seed(HeaderVisitor {
+            header: self.header,
+        })?;
+        Ok(())
+    }
+}
+
+/// Reads an event object up to its `kind` and `pubkey`
+struct HeaderVisitor<'h, 'a> {
+    header: &'h mut Option<EventHeader<'a>>,
+}
+
+impl<'a> DeserializeSeed<'a> for HeaderVisitor<'_, 'a> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'a>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'a> Visitor<'a> for HeaderVisitor<'_, 'a> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an event")
+    }
+
+    fn visit_map<A: MapAccess<'a>>(self, mut map: A) -> Result<(), A::Error> {
+        let mut kind = None;
+        let mut pubkey = None;
+
+        while let Some(key) = map.next_key::<&'a str>()? {
+            match key {
+                "kind" => kind = Some(map.next_value()?),
+                "pubkey" => pubkey = Some(map.next_value()?),
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+
+            if let (Some(kind), Some(pubkey)) = (kind, pubkey) {
+                *self.header = Some(EventHeader { kind, pubkey });
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The `kinds` and `authors` constraints of a filter, extracted once so
+/// every incoming event can be checked cheaply against the subscription it
+/// arrived on.
+#[derive(Debug, Default, Clone)]
+pub struct EventGuard {
+    kinds: Option<HashSet<u64>>,
+    authors: Option<HashSet<String>>,
+}
+
+impl EventGuard {
+    pub fn from_filter(filter: &Filter) -> Self {
+        filter
+            .json()
+            .ok()
+            .map(|json| Self::from_filter_json(&json))
+            .unwrap_or_default()
+    }
+
+    fn from_filter_json(json: &str) -> Self {
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(json) else {
+            return Self::default();
+        };
+
+        let kinds = value
+            .get("kinds")
+            .and_then(|kinds| kinds.as_array())
+            .map(|kinds| kinds.iter().filter_map(|k| k.as_u64()).collect());
+
+        let authors = value
+            .get("authors")
+            .and_then(|authors| authors.as_array())
+            .map(|authors| {
+                authors
+                    .iter()
+                    .filter_map(|a| a.as_str())
+                    .map(|a| a.to_ascii_lowercase())
+                    .collect()
+            });
+
+        Self { kinds, authors }
+    }
+
+    pub fn allows(&self, event: &EventHeader) -> bool {
+        let kind_ok = self
+            .kinds
+            .as_ref()
+            .is_none_or(|kinds| kinds.contains(&event.kind));
+
+        // authors are kept lowercase, and relays may send either case
+        let author_ok = self.authors.as_ref().is_none_or(|authors| {
+            let pubkey = if event.pubkey.bytes().any(|b| b.is_ascii_uppercase()) {
+                Cow::Owned(event.pubkey.to_ascii_lowercase())
+            } else {
+                Cow::Borrowed(event.pubkey)
+            };
+            authors.contains(pubkey.as_ref())
+        });
+
+        kind_ok && author_ok
+    }
+}
+
+/// Why an incoming event was dropped before ingestion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventRejection {
+    /// The message was larger than the configured limit.
+    Oversized(usize),
+    /// The event doesn't match any filter of the subscription it arrived on.
+    Unrequested { kind: u64 },
+}
+
+impl std::fmt::Display for EventRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EventRejection::Oversized(size) => write!(f, "event of {size} bytes is too large"),
+            EventRejection::Unrequested { kind } => {
+                write!(f, "kind {kind} event does not match its subscription")
+            }
+        }
+    }
+}
+
+/// Check a raw event message before handing it to ndb, returning its
+/// header so later checks don't have to parse it again.
+///
+/// `guards` are the filters of the subscription the event arrived on. With
+/// no guards, e.g. late events on a REQ we already closed, only the size is
+/// checked. If the event header can't be parsed we let it through and leave
+/// the rejection to ndb, which reports it as an invalid event.
+pub fn validate_event<'a, 'g>(
+    msg: &'a str,
+    max_bytes: usize,
+    guards: impl Iterator<Item = &'g EventGuard>,
+) -> Result<Option<EventHeader<'a>>, EventRejection> {
+    if msg.len() > max_bytes {
+        return Err(EventRejection::Oversized(msg.len()));
+    }
+
+    let Some(header) = EventHeader::from_message(msg) else {
+        return Ok(None);
+    };
+
+    let mut guards = guards.peekable();
+    if guards.peek().is_none() || guards.any(|guard| guard.allows(&header)) {
+        Ok(Some(header))
+    } else {
+        Err(EventRejection::Unrequested { kind: header.kind })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: &str = "32e1827635450ebb3c5a7d12c1f8e7b2b514439ac10a67eef3d9fd9c5c68e245";
+    const BOB: &str = "1a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f708192a3b4c5d6e7f809";
+
+    fn event(kind: u64, pubkey: &str) -> String {
+        format!(
+            r#"["EVENT","sub",{{"id":"00","pubkey":"{pubkey}","created_at":1,"kind":{kind},"tags":[],"content":"hi","sig":"00"}}]"#
+        )
+    }
+
+    fn guard(kinds: &[u64], authors: &[&str]) -> EventGuard {
+        let mut filter = Filter::new();
+        if !kinds.is_empty() {
+            filter = filter.kinds(kinds.to_vec());
+        }
+        if !authors.is_empty() {
+            let authors: Vec<[u8; 32]> = authors
+                .iter()
+                .map(|a| hex::decode(a).unwrap().try_into().unwrap())
+                .collect();
+            filter = filter.authors(authors.iter());
+        }
+        EventGuard::from_filter(&filter.build())
+    }
+
+    #[test]
+    fn header_is_parsed_from_relay_message() {
+        let msg = event(7, ALICE);
+        let header = EventHeader::from_message(&msg).unwrap();
+        assert_eq!(header.kind, 7);
+        assert_eq!(header.pubkey, ALICE);
+    }
+
+    #[test]
+    fn header_parse_stops_after_its_fields() {
+        let msg =
+            format!(r#"["EVENT","sub",{{"pubkey":"{ALICE}","kind":1,"content":"unterminated"#);
+        let header = EventHeader::from_message(&msg).unwrap();
+        assert_eq!(header.kind, 1);
+        assert_eq!(header.pubkey, ALICE);
+    }
+
+    #[test]
+    fn uppercase_pubkeys_match_their_authors() {
+        let guards = [guard(&[1], &[ALICE])];
+        let msg = event(1, &ALICE.to_ascii_uppercase());
+        assert!(validate_event(&msg, 10_000, guards.iter()).is_ok());
+    }
+
+    #[test]
+    fn matching_events_pass() {
+        let guards = [guard(&[1], &[BOB]), guard(&[1, 6], &[ALICE])];
+        assert!(validate_event(&event(6, ALICE), 10_000, guards.iter()).is_ok());
+        assert!(validate_event(&event(1, BOB), 10_000, guards.iter()).is_ok());
+    }
+
+    #[test]
+    fn unrequested_events_are_rejected() {
+        let guards = [guard(&[1], &[ALICE])];
+        assert_eq!(
+            validate_event(&event(4, ALICE), 10_000, guards.iter()),
+            Err(EventRejection::Unrequested { kind: 4 })
+        );
+        assert_eq!(
+            validate_event(&event(1, BOB), 10_000, guards.iter()),
+            Err(EventRejection::Unrequested { kind: 1 })
+        );
+    }
+
+    #[test]
+    fn unconstrained_filters_allow_anything() {
+        let guards = [guard(&[], &[])];
+        assert!(validate_event(&event(30023, BOB), 10_000, guards.iter()).is_ok());
+    }
+
+    #[test]
+    fn unknown_subscriptions_are_only_size_checked() {
+        let msg = event(1, ALICE);
+        assert!(validate_event(&msg, 10_000, std::iter::empty()).is_ok());
+        assert!(validate_event(&msg, 10, std::iter::empty()).is_err());
+    }
+
+    #[test]
+    fn oversized_events_are_rejected() {
+        let msg = event(1, ALICE);
+        let guards = [guard(&[1], &[])];
+        assert_eq!(
+            validate_event(&msg, msg.len() - 1, guards.iter()),
+            Err(EventRejection::Oversized(msg.len()))
+        );
+    }
+}
@@ -1,4 +1,7 @@
-use crate::{relay::RelayStatus, ClientMessage, Result, Wakeup};
+use crate::{
+    relay::{pacing::ReqPacer, RelayStatus},
+    ClientMessage, Result, Wakeup,
+};
 
 use std::{
     fmt,
@@ -7,6 +10,7 @@ use std::{
 };
 
 use ewebsock::{Options, WsMessage, WsReceiver, WsSender};
+use nostrdb::Filter;
 use tracing::{debug, error};
 
 /// WebsocketConn owns an outbound websocket connection to a relay.
@@ -123,6 +127,8 @@ pub struct WebsocketRelay {
     pub retry_connect_after: Duration,
     /// Number of consecutive failed reconnect attempts. Reset to 0 on successful connection.
     pub reconnect_attempt: u32,
+    /// REQs waiting for their turn to go out
+    pub reqs: ReqPacer,
 }
 
 impl WebsocketRelay {
@@ -135,6 +141,7 @@ impl WebsocketRelay {
             last_connect_attempt: now,
             retry_connect_after: Self::initial_reconnect_duration(),
             reconnect_attempt: 0,
+            reqs: ReqPacer::default(),
         }
     }
 
@@ -145,4 +152,28 @@ impl WebsocketRelay {
     pub fn is_connected(&self) -> bool {
         self.conn.status == RelayStatus::Connected
     }
+
+    /// Queue a REQ. It is sent by [`Self::flush_reqs`] once the relay's
+    /// pacing allows it.
+    pub fn send_req(&mut self, sid: String, filters: Vec<Filter>) {
+        self.reqs.push(sid, filters);
+    }
+
+    /// Close a subscription, dropping its REQ instead if it never went out.
+    pub fn send_close(&mut self, sid: String) {
+        if self.reqs.close(&sid) && self.is_connected() {
+            self.conn.send(&ClientMessage::close(sid));
+        }
+    }
+
+    /// Send every queued REQ the pacing allows right now.
+    pub fn flush_reqs(&mut self, now: Instant) {
+        if !self.is_connected() {
+            return;
+        }
+
+        while let Some((sub_id, filters)) = self.reqs.pop_ready(now) {
+            self.conn.send(&ClientMessage::req(sub_id, filters));
+        }
+    }
 }
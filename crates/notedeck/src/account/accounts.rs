@@ -9,17 +9,49 @@ use crate::scoped_subs::{RelaySelection, ScopedSubIdentity, SubConfig, SubKey};
 use crate::storage::AccountStorageWriter;
 use crate::user_account::UserAccountSerializable;
 use crate::{
-    AccountStorage, MuteFun, RemoteApi, ScopedSubApi, SingleUnkIdAction, SubOwnerKey, UnknownIds,
-    UserAccount, ZapWallet,
+    AccountStorage, MuteEntry, MuteFun, MuteMode, RemoteApi, ScopedSubApi, SingleUnkIdAction,
+    SubOwnerKey, UnknownIds, UserAccount, ZapWallet,
 };
 use enostr::{FilledKeypair, Keypair, NormRelayUrl, Pubkey, RelayId};
 use hashbrown::HashSet;
 use nostrdb::{Filter, Ndb, Note, Subscription, Transaction};
 
+use std::collections::BTreeMap;
 use std::slice::from_ref;
+use zeroize::Zeroize;
 // TODO: remove this
 use std::sync::Arc;
 
+/// What to erase alongside an account when it is signed out.
+///
+/// The account's key is always removed from the key store. These options
+/// cover everything else that may be left behind on this device.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct AccountScrub {
+    /// Zero the account file on disk and the in-memory wallet connection
+    /// string, and let apps drop drafts and decrypted messages.
+    pub local_data: bool,
+
+    /// Compact the database down to the notes of the remaining accounts
+    /// (and all profiles). nostrdb can't drop a single author's notes, so
+    /// this removes everyone else's too. Performed by the host app since it
+    /// owns the compaction job.
+    pub prune_db: bool,
+}
+
+/// Encoding of a secret key handed out by [`Accounts::export_key`]
+#[derive(Debug, Clone, Copy)]
+pub enum KeyExport<'a> {
+    /// Plain bech32 `nsec1...`
+    Nsec,
+
+    /// NIP-49 `ncryptsec1...`, encrypted with this passphrase
+    Ncryptsec(&'a str),
+}
+
+/// scrypt work factor for exported ncryptsec keys
+const NCRYPTSEC_LOG_N: u8 = 16;
+
 /// The interface for managing the user's accounts.
 /// Represents all user-facing operations related to account management.
 pub struct Accounts {
@@ -28,6 +60,9 @@ pub struct Accounts {
     relay_defaults: RelayDefaults,
     ndb_subs: AccountNdbSubs,
     scoped_remote_initialized: bool,
+    /// Temporary pubkey-only accounts for viewing the app as someone else.
+    /// They are never written to the key store.
+    ghosts: HashSet<Pubkey>,
 }
 
 impl Accounts {
@@ -86,6 +121,7 @@ impl Accounts {
             relay_defaults,
             ndb_subs,
             scoped_remote_initialized: false,
+            ghosts: HashSet::new(),
         }
     }
 
@@ -94,8 +130,9 @@ impl Accounts {
         pk: &Pubkey,
         ndb: &mut Ndb,
         remote: &mut RemoteApi<'_>,
+        scrub: AccountScrub,
     ) -> bool {
-        self.remove_account_internal(pk, ndb, remote)
+        self.remove_account_internal(pk, ndb, remote, scrub)
     }
 
     fn remove_account_internal(
@@ -103,19 +140,34 @@ impl Accounts {
         pk: &Pubkey,
         ndb: &mut Ndb,
         remote: &mut RemoteApi<'_>,
+        scrub: AccountScrub,
     ) -> bool {
-        let Some(resp) = self.cache.remove(pk) else {
+        let Some(mut resp) = self.cache.remove(pk) else {
             return false;
         };
 
-        if pk != self.cache.fallback() {
+        let was_ghost = self.ghosts.remove(pk);
+
+        if pk != self.cache.fallback() && !was_ghost {
             if let Some(key_store) = &self.storage_writer {
-                if let Err(e) = key_store.remove_key(&resp.deleted) {
+                let res = if scrub.local_data {
+                    key_store.scrub_key(&resp.deleted)
+                } else {
+                    key_store.remove_key(&resp.deleted)
+                };
+
+                if let Err(e) = res {
                     tracing::error!("Could not remove account {pk}: {e}");
                 }
             }
         }
 
+        if scrub.local_data {
+            if let Some(wallet) = resp.wallet.as_mut() {
+                wallet.wallet.uri.zeroize();
+            }
+        }
+
         if let Some(swap_to) = resp.swap_to {
             let old_pk = resp.deleted.pubkey;
             let txn = Transaction::new(ndb).expect("txn");
@@ -125,6 +177,10 @@ impl Accounts {
         {
             let mut scoped_subs = remote.scoped_subs(&*self);
             clear_account_remote_subs_for_account(&mut scoped_subs, resp.deleted.pubkey);
+            // timelines, threads and the rest still declare subs for the
+            // deleted account, which would otherwise be kept forever
+            let forgotten = scoped_subs.forget_account(resp.deleted.pubkey);
+            tracing::debug!("dropped {forgotten} subscriptions of removed account {pk}");
         }
 
         true
@@ -138,8 +194,11 @@ impl Accounts {
 
     #[must_use = "UnknownIdAction's must be handled. Use .process_unknown_id_action()"]
     pub fn add_account(&mut self, kp: Keypair) -> Option<AddAccountResponse> {
+        // signing in to an account that is being viewed keeps it for good
+        let was_ghost = self.ghosts.remove(&kp.pubkey);
+
         let acc = if let Some(acc) = self.cache.get_mut(&kp.pubkey) {
-            if kp.secret_key.is_none() || acc.key.secret_key.is_some() {
+            if !was_ghost && (kp.secret_key.is_none() || acc.key.secret_key.is_some()) {
                 tracing::info!("Already have account, not adding");
                 return None;
             }
@@ -166,6 +225,33 @@ impl Accounts {
         })
     }
 
+    /// Add a temporary, read-only account to see the app as `pubkey` does:
+    /// their follows, their notifications. It is never saved and is removed
+    /// when switching to another account. Returns `None` if we already have
+    /// an account for `pubkey`.
+    #[must_use = "UnknownIdAction's must be handled. Use .process_unknown_id_action()"]
+    pub fn add_ghost_account(&mut self, pubkey: Pubkey) -> Option<AddAccountResponse> {
+        if self.cache.get(&pubkey).is_some() {
+            return None;
+        }
+
+        self.cache.add(UserAccount::new(
+            Keypair::only_pubkey(pubkey),
+            AccountData::new(pubkey.bytes()),
+        ));
+        self.ghosts.insert(pubkey);
+
+        Some(AddAccountResponse {
+            switch_to: pubkey,
+            unk_id_action: SingleUnkIdAction::pubkey(pubkey),
+        })
+    }
+
+    /// Is this a temporary account added with [`Self::add_ghost_account`]?
+    pub fn is_ghost(&self, pubkey: &Pubkey) -> bool {
+        self.ghosts.contains(pubkey)
+    }
+
     /// Update the `UserAccount` via callback and save the result to disk.
     /// return true if the update was successful
     pub fn update_current_account(&mut self, update: impl FnOnce(&mut UserAccount)) -> bool {
@@ -175,6 +261,10 @@ impl Accounts {
 
         let cur_acc = self.get_selected_account();
 
+        if self.ghosts.contains(&cur_acc.key.pubkey) {
+            return false;
+        }
+
         let Some(key_store) = &self.storage_writer else {
             return false;
         };
@@ -263,7 +353,9 @@ impl Accounts {
         txn: &Transaction,
         remote: &mut RemoteApi<'_>,
     ) {
-        if let Some(key_store) = &self.storage_writer {
+        // restarting should go back to a real account
+        let store_selection = !self.ghosts.contains(pk_to_select);
+        if let Some(key_store) = self.storage_writer.as_ref().filter(|_| store_selection) {
             if let Err(e) = key_store.select_key(Some(*pk_to_select)) {
                 tracing::error!("Could not select key {:?}: {e}", pk_to_select);
             }
@@ -281,7 +373,7 @@ impl Accounts {
         let account_data = self.get_selected_account_data();
 
         let muted = Arc::clone(&account_data.muted.muted);
-        Box::new(move |note: &Note, thread: &[u8; 32]| muted.is_muted(note, thread))
+        Box::new(move |note: &Note, thread: &[u8; 32]| muted.mute_mode(note, thread))
     }
 
     pub fn mute(&self) -> Box<Arc<crate::Muted>> {
@@ -295,6 +387,17 @@ impl Accounts {
         }
     }
 
+    /// Mute or unmute `entry` for the selected account, so the next
+    /// [`Accounts::mutefun`] reflects it before the list is published
+    pub fn apply_mute_entry(&mut self, entry: &MuteEntry, mute: bool) {
+        self.cache.selected_mut().data.muted.apply(entry, mute);
+    }
+
+    /// Apply the selected account's mute modes from the settings
+    pub fn update_mute_modes(&mut self, modes: Option<&BTreeMap<String, MuteMode>>) {
+        self.cache.selected_mut().data.muted.update_modes(modes);
+    }
+
     #[profiling::function]
     pub fn update(&mut self, ndb: &mut Ndb, remote: &mut RemoteApi<'_>) {
         // IMPORTANT - This function is called in the UI update loop,
@@ -326,6 +429,41 @@ impl Accounts {
         self.cache.get(pubkey).and_then(|r| r.key.to_full())
     }
 
+    /// Keep secret keys in the OS keyring, or in the account files when it
+    /// is turned off. Keys we hold are rewritten to their new home right away.
+    pub fn set_os_keyring(&mut self, use_keyring: bool) {
+        let Some(key_store) = &mut self.storage_writer else {
+            return;
+        };
+
+        key_store.set_os_keyring(use_keyring);
+
+        for acc in self.cache.accounts() {
+            if acc.key.secret_key.is_none() {
+                continue;
+            }
+
+            if let Err(e) = key_store.write_account(&acc.into()) {
+                tracing::error!("Could not move key for {:?}: {e}", acc.key.pubkey);
+            }
+        }
+    }
+
+    /// The secret key of `pubkey` encoded for backup. `None` if we don't
+    /// hold a secret key for it.
+    pub fn export_key(
+        &self,
+        pubkey: &Pubkey,
+        format: KeyExport<'_>,
+    ) -> Option<Result<String, enostr::Error>> {
+        let kp = self.get_full(pubkey)?;
+
+        Some(match format {
+            KeyExport::Nsec => kp.to_nsec(),
+            KeyExport::Ncryptsec(pass) => kp.to_ncryptsec(pass, NCRYPTSEC_LOG_N),
+        })
+    }
+
     pub(crate) fn process_relay_action(&mut self, remote: &mut RemoteApi<'_>, action: RelayAction) {
         let acc = self.cache.selected_mut();
         modify_advertised_relays(
@@ -361,6 +499,26 @@ impl Accounts {
         )
     }
 
+    /// Use `relays` instead of the account relay lists, or go back to the
+    /// account relay lists when `None`.
+    pub fn set_relay_profile(
+        &mut self,
+        remote: &mut RemoteApi<'_>,
+        relays: Option<std::collections::BTreeSet<crate::RelaySpec>>,
+    ) {
+        self.relay_defaults.profile_relays = relays;
+        self.retarget_selected_account_read_relays(remote);
+    }
+
+    /// Start with a relay profile active, before any subscriptions exist
+    pub fn with_relay_profile(
+        mut self,
+        relays: Option<std::collections::BTreeSet<crate::RelaySpec>>,
+    ) -> Self {
+        self.relay_defaults.profile_relays = relays;
+        self
+    }
+
     fn retarget_selected_account_read_relays(&mut self, remote: &mut RemoteApi<'_>) {
         remote.retarget_selected_account_read_relays(self);
     }
@@ -1,7 +1,7 @@
 use enostr::Pubkey;
 use hashbrown::{hash_map::OccupiedEntry, HashMap};
 
-use crate::{SingleUnkIdAction, UserAccount};
+use crate::{SingleUnkIdAction, UserAccount, ZapWallet};
 
 pub struct AccountCache {
     selected: Pubkey,
@@ -74,12 +74,14 @@ impl AccountCache {
 
             return Some(AccountDeletionResponse {
                 deleted: removed.key,
+                wallet: removed.wallet,
                 swap_to: Some(*next),
             });
         }
 
         Some(AccountDeletionResponse {
             deleted: removed.key,
+            wallet: removed.wallet,
             swap_to: None,
         })
     }
@@ -130,5 +132,6 @@ impl<'a> IntoIterator for &'a AccountCache {
 
 pub struct AccountDeletionResponse {
     pub deleted: enostr::Keypair,
+    pub wallet: Option<ZapWallet>,
     pub swap_to: Option<Pubkey>,
 }
@@ -1,9 +1,10 @@
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use nostrdb::{Filter, Ndb, NoteKey, Subscription, Transaction};
 use tracing::{debug, error};
 
-use crate::Muted;
+use crate::{MuteEntry, MuteMode, Muted};
 
 #[derive(Clone)]
 pub(crate) struct AccountMutedData {
@@ -38,7 +39,8 @@ impl AccountMutedData {
             .map(|qr| qr.note_key)
             .collect::<Vec<NoteKey>>();
         let max_hashtags = self.muted.max_hashtags_per_note;
-        let muted = Self::harvest_nip51_muted(ndb, txn, &nks, max_hashtags);
+        let mut muted = Self::harvest_nip51_muted(ndb, txn, &nks, max_hashtags);
+        muted.modes = self.muted.modes.clone();
         debug!("initial muted {:?}", muted);
 
         self.muted = Arc::new(muted);
@@ -66,7 +68,7 @@ impl AccountMutedData {
                         }
                         Some("t") => {
                             if let Some(str) = tag.get(1).and_then(|f| f.variant().str()) {
-                                muted.hashtags.insert(str.to_string());
+                                muted.hashtags.insert(str.to_lowercase());
                             }
                         }
                         Some("word") => {
@@ -91,6 +93,7 @@ impl AccountMutedData {
                 }
             }
         }
+        muted.compile_words();
         muted
     }
 
@@ -103,7 +106,8 @@ impl AccountMutedData {
         }
 
         let max_hashtags = self.muted.max_hashtags_per_note;
-        let muted = AccountMutedData::harvest_nip51_muted(ndb, txn, &nks, max_hashtags);
+        let mut muted = AccountMutedData::harvest_nip51_muted(ndb, txn, &nks, max_hashtags);
+        muted.modes = self.muted.modes.clone();
         debug!("updated muted {:?}", muted);
         self.muted = Arc::new(muted);
     }
@@ -114,4 +118,31 @@ impl AccountMutedData {
         muted.max_hashtags_per_note = max_hashtags_per_note;
         self.muted = Arc::new(muted);
     }
+
+    /// Put `entry` on or off the mute list right away, without waiting for
+    /// the published list to come back through the database
+    pub fn apply(&mut self, entry: &MuteEntry, mute: bool) {
+        if self.muted.contains(entry) == mute {
+            return;
+        }
+
+        let mut muted = (*self.muted).clone();
+        muted.set(entry, mute);
+        self.muted = Arc::new(muted);
+    }
+
+    /// Update how mute list entries are muted
+    pub fn update_modes(&mut self, modes: Option<&BTreeMap<String, MuteMode>>) {
+        let unchanged = match modes {
+            Some(modes) => self.muted.modes == *modes,
+            None => self.muted.modes.is_empty(),
+        };
+        if unchanged {
+            return;
+        }
+
+        let mut muted = (*self.muted).clone();
+        muted.modes = modes.cloned().unwrap_or_default();
+        self.muted = Arc::new(muted);
+    }
 }
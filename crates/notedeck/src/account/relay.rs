@@ -1,7 +1,7 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 
-use crate::{AccountData, RelaySpec, RemoteApi};
-use enostr::{Keypair, NormRelayUrl, RelayId};
+use crate::{AccountData, RelayPolicy, RelaySpec, RemoteApi};
+use enostr::{Keypair, NormRelayUrl, Pubkey, RelayId};
 use hashbrown::HashSet;
 use nostrdb::{Filter, Ndb, Note, NoteBuilder, NoteKey, Subscription, Transaction};
 use tracing::{debug, error, info};
@@ -127,6 +127,8 @@ impl AccountRelayData {
 pub(crate) struct RelayDefaults {
     pub forced_relays: BTreeSet<RelaySpec>,
     pub bootstrap_relays: BTreeSet<RelaySpec>,
+    /// Relays of the active relay profile, used instead of the account's
+    pub profile_relays: Option<BTreeSet<RelaySpec>>,
 }
 
 impl RelayDefaults {
@@ -150,6 +152,7 @@ impl RelayDefaults {
         Self {
             forced_relays,
             bootstrap_relays,
+            profile_relays: None,
         }
     }
 }
@@ -162,8 +165,26 @@ pub fn calculate_relays(
     // If forced relays are set use them only
     let mut desired_relays = relay_defaults.forced_relays.clone();
 
-    // Compose the desired relay lists from the selected account
+    // Then the active relay profile
     if desired_relays.is_empty() {
+        if let Some(profile_relays) = &relay_defaults.profile_relays {
+            desired_relays.extend(
+                profile_relays
+                    .iter()
+                    .filter(|l| {
+                        if readable {
+                            l.is_readable()
+                        } else {
+                            l.is_writable()
+                        }
+                    })
+                    .cloned(),
+            );
+        }
+    }
+
+    // Compose the desired relay lists from the selected account
+    if desired_relays.is_empty() && relay_defaults.profile_relays.is_none() {
         desired_relays.extend(
             data.local
                 .iter()
@@ -201,9 +222,17 @@ pub fn calculate_relays(
 }
 
 // TODO(kernelkind): these should have `NormRelayUrl` instead of `String`...
+/// An edit of the selected account's advertised NIP-65 relay list
 pub enum RelayAction {
     Add(String),
     Remove(String),
+    /// Change whether an advertised relay is used for reading, writing or
+    /// both. At least one of the two has to stay on.
+    SetMarkers {
+        url: String,
+        read: bool,
+        write: bool,
+    },
 }
 
 impl RelayAction {
@@ -211,10 +240,26 @@ impl RelayAction {
         match self {
             RelayAction::Add(url) => url,
             RelayAction::Remove(url) => url,
+            RelayAction::SetMarkers { url, .. } => url,
         }
     }
 }
 
+/// A relay change kept on this device, which leaves the advertised relay
+/// list alone
+pub enum LocalRelayAction {
+    /// Blacklist the relay for the rest of the session
+    Block(String),
+    Unblock(String),
+    /// Save the relays in use as a named relay profile
+    SaveProfile(String),
+    /// Switch to a relay profile, or back to the account relays
+    UseProfile(Option<String>),
+    RemoveProfile(String),
+    /// Limit what the relay is used for
+    SetPolicy(RelayPolicy),
+}
+
 pub(super) fn modify_advertised_relays(
     kp: &Keypair,
     action: RelayAction,
@@ -230,6 +275,12 @@ pub(super) fn modify_advertised_relays(
     match action {
         RelayAction::Add(_) => info!("add advertised relay \"{relay_url_str}\""),
         RelayAction::Remove(_) => info!("remove advertised relay \"{relay_url_str}\""),
+        RelayAction::SetMarkers { read, write, .. } => {
+            if !read && !write {
+                return;
+            }
+            info!("set advertised relay \"{relay_url_str}\" read={read} write={write}")
+        }
     }
 
     // let selected = self.cache.selected_mut();
@@ -247,6 +298,13 @@ pub(super) fn modify_advertised_relays(
         RelayAction::Remove(_) => {
             advertised.remove(&RelaySpec::new(relay_url, false, false));
         }
+        RelayAction::SetMarkers { read, write, .. } => {
+            // specs compare by url only, so this swaps in the new markers
+            let spec = RelaySpec::new(relay_url, read && !write, write && !read);
+            if advertised.contains(&spec) {
+                advertised.replace(spec);
+            }
+        }
     }
 
     // If we have the secret key publish the NIP-65 relay list
@@ -270,3 +328,47 @@ pub fn write_relays(relay_defaults: &RelayDefaults, data: &AccountRelayData) ->
 
     relays
 }
+
+/// Write relays from each author's latest NIP-65 relay list in ndb. Authors
+/// we have no relay list for are left out.
+pub(crate) fn author_write_relays(
+    ndb: &Ndb,
+    txn: &Transaction,
+    authors: &[Pubkey],
+) -> Vec<HashSet<NormRelayUrl>> {
+    if authors.is_empty() {
+        return Vec::new();
+    }
+
+    let filter = Filter::new()
+        .authors(authors.iter().map(|pk| pk.bytes()))
+        .kinds([10002])
+        .build();
+    let Ok(results) = ndb.query(txn, &[filter], (authors.len() * 2) as i32) else {
+        return Vec::new();
+    };
+
+    // ordered by author so the same lists give an equal relay selection
+    let mut latest: BTreeMap<[u8; 32], (u64, NoteKey)> = BTreeMap::new();
+    for result in &results {
+        let created_at = result.note.created_at();
+        let entry = latest
+            .entry(*result.note.pubkey())
+            .or_insert((created_at, result.note_key));
+        if created_at > entry.0 {
+            *entry = (created_at, result.note_key);
+        }
+    }
+
+    latest
+        .into_values()
+        .map(|(_, nk)| {
+            AccountRelayData::harvest_nip65_relays(ndb, txn, &[nk])
+                .into_iter()
+                .filter(|spec| spec.is_writable())
+                .map(|spec| spec.url)
+                .collect::<HashSet<NormRelayUrl>>()
+        })
+        .filter(|write| !write.is_empty())
+        .collect()
+}
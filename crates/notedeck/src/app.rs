@@ -2,6 +2,8 @@ use crate::account::FALLBACK_PUBKEY;
 use crate::i18n::Localization;
 use crate::nip05::Nip05Cache;
 use crate::persist::{AppSizeHandler, SettingsHandler};
+use crate::platform::os_notifications::OsNotifications;
+use crate::profile::ProfileStatsCache;
 use crate::relay_limits::{enqueue_nip11_fetch, RelayLimitJobs};
 use crate::scoped_sub_state::ScopedSubsState;
 use crate::unknowns::unknown_id_send;
@@ -9,7 +11,8 @@ use crate::wallet::GlobalWallet;
 use crate::zaps::Zaps;
 use crate::{
     frame_history::FrameHistory, AccountStorage, Accounts, AppContext, Args, DataPath,
-    DataPathType, Directory, Images, NoteAction, NoteCache, RemoteApi, UnknownIds,
+    DataPathType, Directory, Images, NoteAction, NoteCache, RelayPolicies, RemoteApi, SigPolicy,
+    UnknownIds,
 };
 use crate::{EguiWakeup, NotedeckOptions};
 use crate::{Error, JobCache};
@@ -17,7 +20,7 @@ use crate::{JobPool, MediaJobs};
 use egui::Margin;
 use egui::ThemePreference;
 use egui_winit::clipboard::Clipboard;
-use enostr::{OutboxPool, OutboxSession, OutboxSessionHandler};
+use enostr::{OutboxPool, OutboxSession, OutboxSessionHandler, ReqPacing};
 use nostrdb::{Config, Ndb, Transaction};
 use std::cell::RefCell;
 use std::collections::BTreeSet;
@@ -75,6 +78,8 @@ pub struct Notedeck {
     unknown_ids: UnknownIds,
     pool: OutboxPool,
     scoped_sub_state: ScopedSubsState,
+    /// Signature checking in effect for this session
+    sig_policy: SigPolicy,
     note_cache: NoteCache,
     accounts: Accounts,
     global_wallet: GlobalWallet,
@@ -91,6 +96,8 @@ pub struct Notedeck {
     media_jobs: MediaJobs,
     relay_limit_jobs: RelayLimitJobs,
     nip05_cache: Nip05Cache,
+    profile_stats: ProfileStatsCache,
+    os_notifications: OsNotifications,
     i18n: Localization,
 
     #[cfg(target_os = "android")]
@@ -103,6 +110,15 @@ impl Drop for Notedeck {
     }
 }
 
+/// Wakeup for work done while shutting down, when there is nothing left
+/// to repaint
+#[derive(Clone)]
+struct ShutdownWakeup;
+
+impl enostr::Wakeup for ShutdownWakeup {
+    fn wake(&self) {}
+}
+
 /// Our chrome, which is basically nothing
 fn main_panel(style: &egui::Style) -> egui::CentralPanel {
     egui::CentralPanel::default().frame(egui::Frame {
@@ -173,6 +189,7 @@ impl Notedeck {
             return;
         };
         let app = app.clone();
+        let sig_policy = self.sig_policy;
 
         let mut app_ctx = self.app_context(ctx);
 
@@ -183,7 +200,10 @@ impl Notedeck {
             .zaps
             .process(app_ctx.accounts, app_ctx.global_wallet, app_ctx.ndb);
 
-        app_ctx.remote.process_events(ctx, app_ctx.ndb);
+        app_ctx.remote.process_events(ctx, app_ctx.ndb, sig_policy);
+        app_ctx
+            .remote
+            .refresh_outbox_relays(app_ctx.ndb, app_ctx.accounts);
 
         {
             profiling::scope!("unknown id");
@@ -200,6 +220,9 @@ impl Notedeck {
             drop(app_ctx);
         }
 
+        // after rendering, so stats asked for this frame start right away
+        self.profile_stats.poll(&self.ndb, &self.job_pool, ctx);
+
         self.settings.update_batch(|settings| {
             settings.zoom_factor = ctx.zoom_factor();
             settings.locale = self.i18n.get_current_locale().to_string();
@@ -218,6 +241,7 @@ impl Notedeck {
     /// Shuts down app-owned runtime state before dropping the host.
     pub fn shutdown_app(&mut self) {
         self.app.take();
+        self.pool.close_all_subscriptions(ShutdownWakeup);
     }
 
     pub fn set_pong_timeout(&mut self, timeout: Duration) {
@@ -261,8 +285,10 @@ impl Notedeck {
         };
 
         let settings = SettingsHandler::new(&path).load();
+        let sig_policy = settings.sig_policy();
 
         let config = Config::new()
+            .skip_validation(!sig_policy.ndb_validates())
             .set_ingester_threads(2)
             .set_mapsize(map_size)
             .set_sub_callback({
@@ -273,20 +299,35 @@ impl Notedeck {
         let keystore = if parsed_args.options.contains(NotedeckOptions::UseKeystore) {
             let keys_path = path.path(DataPathType::Keys);
             let selected_key_path = path.path(DataPathType::SelectedKey);
-            Some(AccountStorage::new(
-                Directory::new(keys_path),
-                Directory::new(selected_key_path),
-            ))
+            Some(
+                AccountStorage::new(Directory::new(keys_path), Directory::new(selected_key_path))
+                    .with_os_keyring(settings.os_keyring()),
+            )
         } else {
             None
         };
 
         let mut unknown_ids = UnknownIds::default();
+        unknown_ids.set_profile_max_age(settings.profile_max_age());
         try_swap_compacted_db(&dbpath_str);
         let mut ndb = Ndb::new(&dbpath_str, &config).expect("ndb");
         let txn = Transaction::new(&ndb).expect("txn");
-        let mut scoped_sub_state = ScopedSubsState::default();
+        let mut scoped_sub_state = ScopedSubsState::default()
+            .with_relay_policies(RelayPolicies::new(settings.relay_policies()));
         let mut pool = OutboxPool::default();
+        if let Some(max_bytes) = parsed_args.max_event_bytes {
+            pool.set_max_event_bytes(max_bytes);
+        }
+        if parsed_args.req_concurrency.is_some() || parsed_args.req_delay_ms.is_some() {
+            let mut pacing = ReqPacing::default();
+            if let Some(concurrency) = parsed_args.req_concurrency {
+                pacing.concurrency = concurrency;
+            }
+            if let Some(ms) = parsed_args.req_delay_ms {
+                pacing.delay = Duration::from_millis(ms);
+            }
+            pool.set_req_pacing(pacing);
+        }
         let outbox_session = OutboxSessionHandler::new(&mut pool, EguiWakeup::new(ctx.clone()));
 
         let mut accounts = Accounts::new(
@@ -296,7 +337,8 @@ impl Notedeck {
             &mut ndb,
             &txn,
             &mut unknown_ids,
-        );
+        )
+        .with_relay_profile(settings.active_relay_profile_relays());
 
         for key in &parsed_args.keys {
             info!("adding account: {}", &key.pubkey);
@@ -322,7 +364,8 @@ impl Notedeck {
         };
 
         let img_cache = Images::new(img_cache_dir);
-        let note_cache = NoteCache::default();
+        let note_cache =
+            NoteCache::default().with_signature_checks(sig_policy.verifies_on_display());
 
         let app_size = AppSizeHandler::new(&path);
 
@@ -364,6 +407,7 @@ impl Notedeck {
             unknown_ids,
             pool,
             scoped_sub_state,
+            sig_policy,
             note_cache,
             accounts,
             global_wallet,
@@ -379,7 +423,9 @@ impl Notedeck {
             job_pool,
             media_jobs: media_job_cache,
             relay_limit_jobs,
-            nip05_cache: Nip05Cache::new(),
+            nip05_cache: Nip05Cache::load(&path),
+            profile_stats: ProfileStatsCache::new(),
+            os_notifications: OsNotifications::default(),
             i18n,
             #[cfg(target_os = "android")]
             android_app: None,
@@ -445,6 +491,8 @@ impl Notedeck {
                 job_pool: &mut self.job_pool,
                 media_jobs: &mut self.media_jobs,
                 nip05_cache: &mut self.nip05_cache,
+                profile_stats: &mut self.profile_stats,
+                os_notifications: &mut self.os_notifications,
                 i18n: &mut self.i18n,
                 #[cfg(target_os = "android")]
                 android: self.android_app.as_ref().unwrap().clone(),
@@ -591,8 +639,9 @@ fn tick_relay_limit_jobs(
         let response = completed.response;
         let now = SystemTime::now();
         match response.result {
-            Ok(raw) => {
-                let _ = pool.apply_nip11_limits(&response.relay, raw, now);
+            Ok(info) => {
+                let _ = pool.apply_nip11_limits(&response.relay, info.limitation, now);
+                let _ = pool.set_supported_nips(&response.relay, info.supported_nips);
             }
             Err(error) => {
                 pool.record_nip11_failure(&response.relay, error.to_string(), now);
@@ -13,6 +13,12 @@ pub struct Args {
     pub options: NotedeckOptions,
     pub dbpath: Option<String>,
     pub datapath: Option<String>,
+    /// Drop relay events larger than this many bytes
+    pub max_event_bytes: Option<usize>,
+    /// How many REQs may await EOSE per relay at once
+    pub req_concurrency: Option<usize>,
+    /// Minimum delay between REQs to the same relay, in milliseconds
+    pub req_delay_ms: Option<u64>,
 }
 
 impl Args {
@@ -39,6 +45,9 @@ impl Args {
             dbpath: None,
             datapath: None,
             locale: None,
+            max_event_bytes: None,
+            req_concurrency: None,
+            req_delay_ms: None,
         };
 
         let mut i = 0;
@@ -126,6 +135,36 @@ impl Args {
                     continue;
                 };
                 res.datapath = Some(path.clone());
+            } else if arg == "--max-event-bytes" {
+                i += 1;
+                let Some(bytes) = args.get(i) else {
+                    error!("max-event-bytes argument missing?");
+                    continue;
+                };
+                match bytes.parse() {
+                    Ok(bytes) => res.max_event_bytes = Some(bytes),
+                    Err(err) => error!("invalid max-event-bytes '{bytes}': {err}"),
+                }
+            } else if arg == "--req-concurrency" {
+                i += 1;
+                let Some(count) = args.get(i) else {
+                    error!("req-concurrency argument missing?");
+                    continue;
+                };
+                match count.parse() {
+                    Ok(count) => res.req_concurrency = Some(count),
+                    Err(err) => error!("invalid req-concurrency '{count}': {err}"),
+                }
+            } else if arg == "--req-delay-ms" {
+                i += 1;
+                let Some(ms) = args.get(i) else {
+                    error!("req-delay-ms argument missing?");
+                    continue;
+                };
+                match ms.parse() {
+                    Ok(ms) => res.req_delay_ms = Some(ms),
+                    Err(err) => error!("invalid req-delay-ms '{ms}': {err}"),
+                }
             } else if arg == "-r" || arg == "--relay" {
                 i += 1;
                 let relay = if let Some(next_arg) = args.get(i) {
@@ -163,6 +202,31 @@ mod tests {
         assert!(!args.options.contains(NotedeckOptions::UseKeystore));
     }
 
+    /// Verifies `--max-event-bytes` sets the relay event size limit.
+    #[test]
+    fn parse_max_event_bytes() {
+        let (args, unrecognized) =
+            Args::parse(&["--max-event-bytes".to_owned(), "65536".to_owned()]);
+
+        assert!(unrecognized.is_empty());
+        assert_eq!(args.max_event_bytes, Some(65536));
+    }
+
+    /// Verifies the REQ pacing flags are parsed.
+    #[test]
+    fn parse_req_pacing() {
+        let (args, unrecognized) = Args::parse(&[
+            "--req-concurrency".to_owned(),
+            "2".to_owned(),
+            "--req-delay-ms".to_owned(),
+            "250".to_owned(),
+        ]);
+
+        assert!(unrecognized.is_empty());
+        assert_eq!(args.req_concurrency, Some(2));
+        assert_eq!(args.req_delay_ms, Some(250));
+    }
+
     /// Verifies the test runner path never touches the host keyring.
     #[test]
     fn parse_testrunner_disables_keystore() {
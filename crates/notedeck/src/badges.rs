@@ -0,0 +1,303 @@
+//! NIP-58 badges: definitions (kind 30009), awards (kind 8) and the list
+//! of awards a profile chose to display (kind 30008, `d` = `profile_badges`).
+//!
+//! A profile can list any award it likes, so each entry is only shown when
+//! the award was issued by the badge's author and names the profile.
+
+use enostr::{NoteId, Pubkey};
+use nostrdb::{Filter, Ndb, Note, Transaction};
+
+use crate::{NoteAddress, UnknownIds};
+
+pub const BADGE_AWARD_KIND: u32 = 8;
+pub const PROFILE_BADGES_KIND: u32 = 30008;
+pub const BADGE_DEFINITION_KIND: u32 = 30009;
+pub const PROFILE_BADGES_IDENTIFIER: &str = "profile_badges";
+
+/// A badge as described by its issuer
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BadgeDefinition {
+    pub issuer: Pubkey,
+    pub identifier: String,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub thumb: Option<String>,
+}
+
+impl BadgeDefinition {
+    pub fn from_note(note: &Note) -> Option<Self> {
+        if note.kind() != BADGE_DEFINITION_KIND {
+            return None;
+        }
+
+        let mut definition = BadgeDefinition {
+            issuer: Pubkey::new(*note.pubkey()),
+            identifier: String::new(),
+            name: None,
+            description: None,
+            image: None,
+            thumb: None,
+        };
+        let mut has_identifier = false;
+
+        for tag in note.tags() {
+            let (Some(name), Some(value)) = (tag.get_str(0), tag.get_str(1)) else {
+                continue;
+            };
+
+            match name {
+                "d" => {
+                    definition.identifier = value.to_owned();
+                    has_identifier = true;
+                }
+                "name" => definition.name = Some(value.to_owned()),
+                "description" => definition.description = Some(value.to_owned()),
+                "image" => definition.image = Some(value.to_owned()),
+                // the first thumb is the largest one, which suits us fine
+                "thumb" if definition.thumb.is_none() => definition.thumb = Some(value.to_owned()),
+                _ => {}
+            }
+        }
+
+        has_identifier.then_some(definition)
+    }
+
+    pub fn display_name(&self) -> &str {
+        self.name
+            .as_deref()
+            .filter(|n| !n.is_empty())
+            .unwrap_or(&self.identifier)
+    }
+
+    /// The image to show the badge as an icon, a thumbnail when there is one
+    pub fn icon_url(&self) -> Option<&str> {
+        self.thumb.as_deref().or(self.image.as_deref())
+    }
+}
+
+/// One `a` + `e` pair of a profile badges list
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct AcceptedBadge {
+    pub definition: NoteAddress,
+    pub award: NoteId,
+}
+
+/// The profile badges list of `pubkey`
+pub fn profile_badges_filter(pubkey: &[u8; 32]) -> Filter {
+    profile_badges_address(pubkey).filter()
+}
+
+/// Awards given to `pubkey`
+pub fn badge_awards_filter(pubkey: &[u8; 32], limit: u64) -> Filter {
+    Filter::new()
+        .kinds([BADGE_AWARD_KIND as u64])
+        .pubkeys([pubkey])
+        .limit(limit)
+        .build()
+}
+
+fn profile_badges_address(pubkey: &[u8; 32]) -> NoteAddress {
+    NoteAddress::new(
+        PROFILE_BADGES_KIND,
+        Pubkey::new(*pubkey),
+        PROFILE_BADGES_IDENTIFIER,
+    )
+}
+
+/// Parse a `30009:<issuer>:<identifier>` badge coordinate
+fn parse_badge_address(value: &str) -> Option<NoteAddress> {
+    let mut parts = value.splitn(3, ':');
+    let kind: u32 = parts.next()?.parse().ok()?;
+    if kind != BADGE_DEFINITION_KIND {
+        return None;
+    }
+    let issuer = Pubkey::from_hex(parts.next()?).ok()?;
+    let identifier = parts.next()?;
+
+    Some(NoteAddress::new(kind, issuer, identifier))
+}
+
+/// The badges a profile badges list accepts, in display order. Each `a`
+/// tag must be directly followed by the `e` tag of its award.
+pub fn accepted_badges(note: &Note) -> Vec<AcceptedBadge> {
+    let mut accepted = Vec::new();
+    let mut pending: Option<NoteAddress> = None;
+
+    for tag in note.tags() {
+        match tag.get_str(0) {
+            Some("a") => {
+                pending = tag.get_str(1).and_then(parse_badge_address);
+            }
+            Some("e") => {
+                let (Some(definition), Some(award)) = (pending.take(), tag.get_id(1)) else {
+                    continue;
+                };
+                accepted.push(AcceptedBadge {
+                    definition,
+                    award: NoteId::new(*award),
+                });
+            }
+            _ => pending = None,
+        }
+    }
+
+    accepted
+}
+
+/// Whether `award` is a real award of `definition` to `recipient`
+pub fn award_is_valid(award: &Note, definition: &NoteAddress, recipient: &[u8; 32]) -> bool {
+    if award.kind() != BADGE_AWARD_KIND || award.pubkey() != definition.pubkey.bytes() {
+        return false;
+    }
+
+    let mut names_badge = false;
+    let mut names_recipient = false;
+    for tag in award.tags() {
+        match tag.get_str(0) {
+            Some("a") => {
+                names_badge |=
+                    tag.get_str(1).and_then(parse_badge_address).as_ref() == Some(definition);
+            }
+            Some("p") => names_recipient |= tag.get_id(1) == Some(recipient),
+            _ => {}
+        }
+    }
+
+    names_badge && names_recipient
+}
+
+/// The verified badges `pubkey` displays on their profile. Awards and
+/// definitions we don't have yet are queued on `unknown_ids` and show up
+/// once they arrive.
+pub fn profile_badges(
+    ndb: &Ndb,
+    txn: &Transaction,
+    pubkey: &[u8; 32],
+    unknown_ids: &mut UnknownIds,
+) -> Vec<BadgeDefinition> {
+    let Some(list) = profile_badges_address(pubkey).lookup(ndb, txn) else {
+        return Vec::new();
+    };
+
+    let mut badges: Vec<BadgeDefinition> = Vec::new();
+    for accepted in accepted_badges(&list) {
+        let Ok(award) = ndb.get_note_by_id(txn, accepted.award.bytes()) else {
+            unknown_ids.add_note_id_if_missing(ndb, txn, accepted.award.bytes());
+            continue;
+        };
+
+        if !award_is_valid(&award, &accepted.definition, pubkey) {
+            continue;
+        }
+
+        let Some(definition) = accepted.definition.lookup(ndb, txn) else {
+            unknown_ids.add_address_if_missing(ndb, txn, &accepted.definition);
+            continue;
+        };
+
+        let Some(badge) = BadgeDefinition::from_note(&definition) else {
+            continue;
+        };
+
+        if !badges.contains(&badge) {
+            badges.push(badge);
+        }
+    }
+
+    badges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use enostr::FullKeypair;
+    use notedeck_testing::fixtures::signed_note;
+
+    #[test]
+    fn definition_prefers_the_thumb_for_icons() {
+        let issuer = FullKeypair::generate();
+        let note = signed_note(
+            &issuer,
+            BADGE_DEFINITION_KIND,
+            &[
+                &["d", "bravery"],
+                &["name", "Medal of Bravery"],
+                &["description", "Awarded to brave users"],
+                &["image", "https://example.com/bravery.png", "1024x1024"],
+                &["thumb", "https://example.com/bravery_256.png", "256x256"],
+                &["thumb", "https://example.com/bravery_64.png", "64x64"],
+            ],
+        );
+
+        let badge = BadgeDefinition::from_note(&note).unwrap();
+        assert_eq!(badge.issuer, issuer.pubkey);
+        assert_eq!(badge.display_name(), "Medal of Bravery");
+        assert_eq!(
+            badge.icon_url(),
+            Some("https://example.com/bravery_256.png")
+        );
+
+        let unnamed = signed_note(&issuer, BADGE_DEFINITION_KIND, &[&["d", "plain"]]);
+        assert_eq!(
+            BadgeDefinition::from_note(&unnamed).unwrap().display_name(),
+            "plain"
+        );
+    }
+
+    #[test]
+    fn accepted_badges_pairs_a_and_e_tags() {
+        let issuer = FullKeypair::generate();
+        let profile = FullKeypair::generate();
+        let coordinate = format!("30009:{}:bravery", issuer.pubkey.hex());
+        let award = NoteId::new([3; 32]).hex();
+        let orphan = NoteId::new([4; 32]).hex();
+
+        let list = signed_note(
+            &profile,
+            PROFILE_BADGES_KIND,
+            &[
+                &["d", PROFILE_BADGES_IDENTIFIER],
+                &["a", &coordinate],
+                &["e", &award],
+                // an `e` without its `a` is ignored
+                &["e", &orphan],
+                &["a", "30023:not-a-badge:x"],
+                &["e", &award],
+            ],
+        );
+
+        let accepted = accepted_badges(&list);
+        assert_eq!(
+            accepted,
+            vec![AcceptedBadge {
+                definition: NoteAddress::new(BADGE_DEFINITION_KIND, issuer.pubkey, "bravery"),
+                award: NoteId::new([3; 32]),
+            }]
+        );
+    }
+
+    #[test]
+    fn awards_must_come_from_the_issuer() {
+        let issuer = FullKeypair::generate();
+        let impostor = FullKeypair::generate();
+        let profile = FullKeypair::generate();
+        let coordinate = format!("30009:{}:bravery", issuer.pubkey.hex());
+        let definition = NoteAddress::new(BADGE_DEFINITION_KIND, issuer.pubkey, "bravery");
+        let tags: &[&[&str]] = &[&["a", &coordinate], &["p", &profile.pubkey.hex()]];
+
+        let award = signed_note(&issuer, BADGE_AWARD_KIND, tags);
+        assert!(award_is_valid(&award, &definition, profile.pubkey.bytes()));
+        assert!(!award_is_valid(&award, &definition, issuer.pubkey.bytes()));
+
+        let forged = signed_note(&impostor, BADGE_AWARD_KIND, tags);
+        assert!(!award_is_valid(
+            &forged,
+            &definition,
+            profile.pubkey.bytes()
+        ));
+
+        let other = NoteAddress::new(BADGE_DEFINITION_KIND, issuer.pubkey, "cowardice");
+        assert!(!award_is_valid(&award, &other, profile.pubkey.bytes()));
+    }
+}
@@ -0,0 +1,218 @@
+//! NIP-51 bookmark sets (kind 30003): named folders of bookmarked notes.
+//!
+//! Public items are regular `e` tags. Private items are the same tags,
+//! serialized as a JSON array and NIP-44 encrypted to ourselves in the
+//! note content.
+
+use enostr::{FilledKeypair, NoteId, Pubkey, SecretKey};
+use nostr::{key::PublicKey, nips::nip44};
+use nostrdb::{Filter, Ndb, Note, NoteBuildOptions, NoteBuilder, Transaction};
+
+use crate::note::publish::{builder_from_note, publish_note_builder};
+use crate::{create_nip51_set, Nip51Set, PublishApi};
+
+pub const BOOKMARK_SET_KIND: u32 = 30003;
+
+/// Where a note should be bookmarked
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BookmarkTarget {
+    /// The set's `d` tag. A new set is created when none exists yet.
+    pub identifier: String,
+    /// Store the item encrypted in the note content instead of as a public tag
+    pub private: bool,
+}
+
+/// All bookmark sets published by `author`
+pub fn bookmark_sets_filter(author: &Pubkey) -> Filter {
+    Filter::new()
+        .authors([author.bytes()])
+        .kinds([BOOKMARK_SET_KIND as u64])
+        .limit(50)
+        .build()
+}
+
+/// A single bookmark set, by author and `d` tag
+pub fn bookmark_set_filter(author: &Pubkey, identifier: &str) -> Filter {
+    Filter::new()
+        .authors([author.bytes()])
+        .kinds([BOOKMARK_SET_KIND as u64])
+        .tags([identifier], 'd')
+        .limit(1)
+        .build()
+}
+
+/// The latest version of each of `author`'s bookmark sets
+pub fn bookmark_sets(ndb: &Ndb, txn: &Transaction, author: &Pubkey) -> Vec<Nip51Set> {
+    let Ok(results) = ndb.query(txn, &[bookmark_sets_filter(author)], 50) else {
+        return vec![];
+    };
+
+    let mut sets: Vec<Nip51Set> = Vec::new();
+    for result in results {
+        let Some(set) = create_nip51_set(result.note) else {
+            continue;
+        };
+
+        match sets.iter_mut().find(|s| s.identifier == set.identifier) {
+            Some(existing) if existing.created_at() < set.created_at() => *existing = set,
+            Some(_) => {}
+            None => sets.push(set),
+        }
+    }
+
+    sets
+}
+
+/// Note ids stored privately in a bookmark set. Only readable by the set's
+/// author.
+pub fn private_bookmarks(note: &Note<'_>, secret_key: &SecretKey) -> Vec<NoteId> {
+    decrypt_private_tags(note, secret_key)
+        .map(|tags| note_ids_from_tags(&tags))
+        .unwrap_or_default()
+}
+
+/// Add `note_id` to a bookmark set, creating the set if needed.
+pub fn send_bookmark_event(
+    ndb: &Ndb,
+    txn: &Transaction,
+    publisher: &mut PublishApi<'_, '_>,
+    kp: FilledKeypair,
+    target: &BookmarkTarget,
+    note_id: &NoteId,
+) {
+    let filter = bookmark_set_filter(kp.pubkey, &target.identifier);
+    let existing = ndb
+        .query(txn, std::slice::from_ref(&filter), 1)
+        .ok()
+        .and_then(|results| results.into_iter().next())
+        .map(|result| result.note);
+
+    let mut private_tags = match &existing {
+        Some(note) if !note.content().is_empty() => {
+            let Some(tags) = decrypt_private_tags(note, kp.secret_key) else {
+                // never overwrite private items we can't read
+                tracing::error!(
+                    "could not decrypt private items of bookmark set '{}'",
+                    target.identifier
+                );
+                return;
+            };
+            tags
+        }
+        _ => vec![],
+    };
+
+    if let Some(note) = &existing {
+        let already_public = note
+            .tags()
+            .into_iter()
+            .any(|tag| tag.get_str(0) == Some("e") && tag.get_id(1) == Some(note_id.bytes()));
+        if already_public || note_ids_from_tags(&private_tags).contains(note_id) {
+            tracing::info!(
+                "note {} is already in '{}'",
+                note_id.hex(),
+                target.identifier
+            );
+            return;
+        }
+    }
+
+    let content = if target.private {
+        private_tags.push(vec!["e".to_owned(), note_id.hex()]);
+        match encrypt_private_tags(&private_tags, kp) {
+            Some(content) => content,
+            None => return,
+        }
+    } else {
+        existing
+            .as_ref()
+            .map(|note| note.content().to_owned())
+            .unwrap_or_default()
+    };
+
+    let builder = if let Some(note) = existing {
+        builder_from_note(note, None::<fn(&nostrdb::Tag<'_>) -> bool>)
+    } else {
+        NoteBuilder::new()
+            .kind(BOOKMARK_SET_KIND)
+            .options(NoteBuildOptions::default())
+            .start_tag()
+            .tag_str("d")
+            .tag_str(&target.identifier)
+            .start_tag()
+            .tag_str("title")
+            .tag_str(&target.identifier)
+    };
+
+    let builder = builder.content(&content);
+    let builder = if target.private {
+        builder
+    } else {
+        builder.start_tag().tag_str("e").tag_str(&note_id.hex())
+    };
+
+    publish_note_builder(builder, ndb, publisher, kp);
+}
+
+fn note_ids_from_tags(tags: &[Vec<String>]) -> Vec<NoteId> {
+    tags.iter()
+        .filter(|tag| tag.first().map(String::as_str) == Some("e"))
+        .filter_map(|tag| NoteId::from_hex(tag.get(1)?).ok())
+        .collect()
+}
+
+fn self_pubkey(secret_key: &SecretKey) -> PublicKey {
+    nostr::Keys::new(secret_key.clone()).public_key()
+}
+
+fn decrypt_private_tags(note: &Note<'_>, secret_key: &SecretKey) -> Option<Vec<Vec<String>>> {
+    let content = note.content();
+    if content.is_empty() {
+        return Some(vec![]);
+    }
+
+    let json = nip44::decrypt(secret_key, &self_pubkey(secret_key), content)
+        .map_err(|e| tracing::warn!("bookmark set decrypt failed: {e}"))
+        .ok()?;
+
+    serde_json::from_str(&json)
+        .map_err(|e| tracing::warn!("bookmark set private items are not a tag list: {e}"))
+        .ok()
+}
+
+fn encrypt_private_tags(tags: &[Vec<String>], kp: FilledKeypair) -> Option<String> {
+    let json = serde_json::to_string(tags).ok()?;
+    nip44::encrypt(
+        kp.secret_key,
+        &self_pubkey(kp.secret_key),
+        json,
+        nip44::Version::V2,
+    )
+    .map_err(|e| tracing::error!("bookmark set encrypt failed: {e}"))
+    .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use enostr::FullKeypair;
+
+    #[test]
+    fn private_tags_roundtrip() {
+        let keys = FullKeypair::generate();
+        let kp = keys.to_filled();
+        let id = NoteId::new([7; 32]);
+        let tags = vec![
+            vec!["e".to_owned(), id.hex()],
+            vec!["t".to_owned(), "nostr".to_owned()],
+        ];
+
+        let content = encrypt_private_tags(&tags, kp).unwrap();
+        assert!(!content.contains(&id.hex()));
+
+        let json = nip44::decrypt(kp.secret_key, &self_pubkey(kp.secret_key), content).unwrap();
+        let decrypted: Vec<Vec<String>> = serde_json::from_str(&json).unwrap();
+        assert_eq!(decrypted, tags);
+        assert_eq!(note_ids_from_tags(&decrypted), vec![id]);
+    }
+}
@@ -38,9 +38,12 @@ pub fn hybrid_contacts_filter(
             .into_query_package(ValidKind::Six, filter::default_limit()),
         filter::filter_from_tags(note, add_pk, with_hashtags)?
             .into_query_package(ValidKind::Zero, filter::default_limit()),
+        filter::filter_from_tags(note, add_pk, with_hashtags)?
+            .into_query_package(ValidKind::Poll, filter::default_limit()),
     ];
+    // deletions come along so notes their authors took back get hidden
     let remote = filter::filter_from_tags(note, add_pk, with_hashtags)?
-        .into_filter(vec![1, 0], filter::default_remote_limit());
+        .into_filter(vec![1, 0, 1068, 5], filter::default_remote_limit());
 
     Ok(HybridFilter::split(local, remote))
 }
@@ -0,0 +1,53 @@
+use nostrdb::Note;
+use serde::{Deserialize, Serialize};
+
+/// What an account does with notes marked with a NIP-36 content warning
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ContentWarningMode {
+    /// Collapse them behind a button that reveals them
+    #[default]
+    Ask,
+    AlwaysShow,
+    /// Collapse them with no way to reveal them
+    AlwaysHide,
+}
+
+impl ContentWarningMode {
+    pub const ALL: [ContentWarningMode; 3] = [
+        ContentWarningMode::Ask,
+        ContentWarningMode::AlwaysShow,
+        ContentWarningMode::AlwaysHide,
+    ];
+}
+
+/// The reason given in `note`'s `content-warning` tag, empty when the
+/// author didn't give one. `None` when the note has no content warning.
+pub fn content_warning<'a>(note: &Note<'a>) -> Option<&'a str> {
+    note.tags()
+        .into_iter()
+        .find(|tag| tag.get_str(0) == Some("content-warning"))
+        .map(|tag| tag.get_str(1).unwrap_or(""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use enostr::FullKeypair;
+    use notedeck_testing::fixtures::signed_note;
+
+    fn note(tags: &[&[&str]]) -> Note<'static> {
+        signed_note(&FullKeypair::generate(), 1, tags)
+    }
+
+    #[test]
+    fn reads_the_content_warning_reason() {
+        let with_reason = note(&[&["t", "movies"], &["content-warning", "spoilers"]]);
+        assert_eq!(content_warning(&with_reason), Some("spoilers"));
+
+        let without_reason = note(&[&["content-warning"]]);
+        assert_eq!(content_warning(&without_reason), Some(""));
+
+        let plain = note(&[&["t", "movies"]]);
+        assert_eq!(content_warning(&plain), None);
+    }
+}
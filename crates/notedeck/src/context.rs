@@ -1,10 +1,11 @@
 use crate::{
     account::accounts::Accounts, frame_history::FrameHistory, i18n::Localization,
-    nip05::Nip05Cache, wallet::GlobalWallet, zaps::Zaps, Args, DataPath, Images, JobPool,
-    MediaJobs, NoteCache, RemoteApi, SettingsHandler, UnknownIds,
+    nip05::Nip05Cache, platform::os_notifications::OsNotifications, profile::ProfileStatsCache,
+    wallet::GlobalWallet, zaps::Zaps, Args, DataPath, Images, JobPool, MediaJobs, NoteCache,
+    RemoteApi, SettingsHandler, UnknownIds,
 };
 use egui_winit::clipboard::Clipboard;
-use enostr::Pubkey;
+use enostr::{NormRelayUrl, Pubkey};
 
 use nostrdb::{Ndb, Transaction};
 
@@ -31,6 +32,8 @@ pub struct AppContext<'a> {
     pub job_pool: &'a mut JobPool,
     pub media_jobs: &'a mut MediaJobs,
     pub nip05_cache: &'a mut Nip05Cache,
+    pub profile_stats: &'a mut ProfileStatsCache,
+    pub os_notifications: &'a mut OsNotifications,
     pub i18n: &'a mut Localization,
 
     #[cfg(target_os = "android")]
@@ -58,15 +61,68 @@ impl<'a> AppContext<'a> {
             .select_account(pubkey, self.ndb, &txn, &mut self.remote);
     }
 
-    pub fn remove_account(&mut self, pubkey: &Pubkey) -> bool {
+    pub fn remove_account(&mut self, pubkey: &Pubkey, scrub: crate::AccountScrub) -> bool {
         self.accounts
-            .remove_account(pubkey, self.ndb, &mut self.remote)
+            .remove_account(pubkey, self.ndb, &mut self.remote, scrub)
     }
 
     pub fn process_relay_action(&mut self, action: crate::RelayAction) {
         self.accounts.process_relay_action(&mut self.remote, action);
     }
 
+    pub fn process_local_relay_action(&mut self, action: crate::LocalRelayAction) {
+        match action {
+            crate::LocalRelayAction::Block(url) => {
+                if let Ok(relay) = NormRelayUrl::new(&url) {
+                    self.remote.block_relay(&relay);
+                }
+            }
+            crate::LocalRelayAction::Unblock(url) => {
+                if let Ok(relay) = NormRelayUrl::new(&url) {
+                    self.remote.unblock_relay(&relay);
+                }
+            }
+            crate::LocalRelayAction::SaveProfile(name) => {
+                let profile = crate::RelayProfile::snapshot(
+                    name,
+                    &self.accounts.selected_account_read_relays(),
+                    &self.accounts.selected_account_write_relays(),
+                );
+                self.settings.save_relay_profile(profile);
+            }
+            crate::LocalRelayAction::UseProfile(name) => self.use_relay_profile(name),
+            crate::LocalRelayAction::RemoveProfile(name) => {
+                if self.settings.active_relay_profile() == Some(name.as_str()) {
+                    self.use_relay_profile(None);
+                }
+                self.settings.remove_relay_profile(&name);
+            }
+            crate::LocalRelayAction::SetPolicy(policy) => {
+                self.settings.set_relay_policy(policy);
+                let policies = crate::RelayPolicies::new(self.settings.relay_policies());
+                self.remote.set_relay_policies(self.accounts, policies);
+            }
+        }
+    }
+
+    /// Swap the relays in use for those of the named relay profile, or go
+    /// back to the account relay lists.
+    pub fn use_relay_profile(&mut self, name: Option<String>) {
+        let relays = match &name {
+            Some(name) => {
+                let Some(profile) = self.settings.relay_profile(name) else {
+                    tracing::warn!("unknown relay profile '{name}'");
+                    return;
+                };
+                Some(profile.relay_specs())
+            }
+            None => None,
+        };
+
+        self.accounts.set_relay_profile(&mut self.remote, relays);
+        self.settings.set_active_relay_profile(name);
+    }
+
     pub fn soft_keyboard_rect(&self, screen_rect: Rect, ctx: SoftKeyboardContext) -> Option<Rect> {
         match ctx {
             SoftKeyboardContext::Virtual => {
@@ -0,0 +1,175 @@
+//! NIP-30 custom emoji, as collected in a user's emoji list (kind 10030)
+//! and the emoji sets (kind 30030) it references.
+
+use enostr::Pubkey;
+use nostrdb::{Filter, Ndb, Note, Transaction};
+
+const EMOJI_LIST_KIND: u64 = 10030;
+const EMOJI_SET_KIND: u64 = 30030;
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct CustomEmoji {
+    pub shortcode: String,
+    pub url: String,
+}
+
+impl CustomEmoji {
+    /// The text used in note or reaction content, e.g. `:soapbox:`
+    pub fn content(&self) -> String {
+        format!(":{}:", self.shortcode)
+    }
+}
+
+/// The custom emoji `pubkey` has made available to themselves, resolved
+/// from the local database only.
+pub fn user_emojis(ndb: &Ndb, txn: &Transaction, pubkey: &Pubkey) -> Vec<CustomEmoji> {
+    let filter = Filter::new()
+        .authors([pubkey.bytes()])
+        .kinds([EMOJI_LIST_KIND])
+        .limit(1)
+        .build();
+
+    let Some(list) = ndb
+        .query(txn, &[filter], 1)
+        .ok()
+        .and_then(|results| results.into_iter().next())
+    else {
+        return vec![];
+    };
+
+    let mut emojis = emojis_from_tags(&list.note);
+
+    for (author, identifier) in emoji_set_refs(&list.note) {
+        let filter = Filter::new()
+            .authors([author.bytes()])
+            .kinds([EMOJI_SET_KIND])
+            .tags([identifier.as_str()], 'd')
+            .limit(1)
+            .build();
+
+        let Ok(results) = ndb.query(txn, &[filter], 1) else {
+            continue;
+        };
+
+        for result in results {
+            for emoji in emojis_from_tags(&result.note) {
+                if !emojis.contains(&emoji) {
+                    emojis.push(emoji);
+                }
+            }
+        }
+    }
+
+    emojis
+}
+
+fn emojis_from_tags(note: &Note<'_>) -> Vec<CustomEmoji> {
+    let mut emojis = Vec::new();
+
+    for tag in note.tags() {
+        if tag.count() < 3 || tag.get_str(0) != Some("emoji") {
+            continue;
+        }
+
+        let (Some(shortcode), Some(url)) = (tag.get_str(1), tag.get_str(2)) else {
+            continue;
+        };
+
+        // NIP-30 shortcodes are alphanumeric with underscores
+        if shortcode.is_empty()
+            || !shortcode
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            continue;
+        }
+
+        emojis.push(CustomEmoji {
+            shortcode: shortcode.to_owned(),
+            url: url.to_owned(),
+        });
+    }
+
+    emojis
+}
+
+/// `a` tags of the form `30030:<pubkey>:<d>`
+fn emoji_set_refs(note: &Note<'_>) -> Vec<(Pubkey, String)> {
+    let mut refs = Vec::new();
+
+    for tag in note.tags() {
+        if tag.count() < 2 || tag.get_str(0) != Some("a") {
+            continue;
+        }
+
+        let Some(coords) = tag.get_str(1) else {
+            continue;
+        };
+
+        let mut parts = coords.splitn(3, ':');
+        let (Some(kind), Some(author), Some(identifier)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            continue;
+        };
+
+        if kind != EMOJI_SET_KIND.to_string() {
+            continue;
+        }
+
+        let Ok(author) = Pubkey::from_hex(author) else {
+            continue;
+        };
+
+        refs.push((author, identifier.to_owned()));
+    }
+
+    refs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nostrdb::NoteBuilder;
+
+    #[test]
+    fn parses_emoji_and_set_tags() {
+        let author = "32e1827635450ebb3c5a7d12c1f8e7b2b514439ac10a67eef3d9fd9c5c68e245";
+        let set_ref = format!("30030:{author}:blobs");
+        let note = NoteBuilder::new()
+            .kind(10030)
+            .content("")
+            .start_tag()
+            .tag_str("emoji")
+            .tag_str("soapbox")
+            .tag_str("https://example.com/soapbox.png")
+            .start_tag()
+            .tag_str("emoji")
+            .tag_str("bad code")
+            .tag_str("https://example.com/bad.png")
+            .start_tag()
+            .tag_str("a")
+            .tag_str(&set_ref)
+            .start_tag()
+            .tag_str("a")
+            .tag_str("30000:not-a-pubkey:friends")
+            .sign(&[0x11; 32])
+            .build()
+            .unwrap();
+
+        let emojis = emojis_from_tags(&note);
+        assert_eq!(
+            emojis,
+            vec![CustomEmoji {
+                shortcode: "soapbox".to_owned(),
+                url: "https://example.com/soapbox.png".to_owned(),
+            }]
+        );
+        assert_eq!(emojis[0].content(), ":soapbox:");
+
+        let refs = emoji_set_refs(&note);
+        assert_eq!(refs.len(), 1);
+        assert_eq!(refs[0].0, Pubkey::from_hex(author).unwrap());
+        assert_eq!(refs[0].1, "blobs");
+    }
+}
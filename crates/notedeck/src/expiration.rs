@@ -0,0 +1,66 @@
+use nostrdb::Note;
+
+/// Notes expiring within this many seconds get a countdown in their header
+pub const EXPIRING_SOON_SECS: u64 = 24 * 60 * 60;
+
+/// The unix timestamp in `note`'s NIP-40 `expiration` tag, if it has one
+pub fn note_expiration(note: &Note) -> Option<u64> {
+    note.tags()
+        .into_iter()
+        .find(|tag| tag.count() >= 2 && tag.get_str(0) == Some("expiration"))
+        .and_then(|tag| tag.get_str(1))
+        .and_then(|ts| ts.trim().parse::<u64>().ok())
+}
+
+/// Whether `note` carries an expiration that has passed by `now`
+pub fn is_expired(note: &Note, now: u64) -> bool {
+    note_expiration(note).is_some_and(|expiration| expiration <= now)
+}
+
+/// The expiration of `note` when it is still live but due within
+/// [`EXPIRING_SOON_SECS`] of `now`
+pub fn expiring_soon(note: &Note, now: u64) -> Option<u64> {
+    note_expiration(note)
+        .filter(|expiration| *expiration > now && *expiration - now <= EXPIRING_SOON_SECS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use enostr::FullKeypair;
+    use notedeck_testing::fixtures::signed_note;
+
+    fn note(tags: &[&[&str]]) -> Note<'static> {
+        signed_note(&FullKeypair::generate(), 1, tags)
+    }
+
+    #[test]
+    fn reads_the_expiration_tag() {
+        assert_eq!(
+            note_expiration(&note(&[&["expiration", "1700"]])),
+            Some(1700)
+        );
+        assert_eq!(note_expiration(&note(&[&["expiration", "soon"]])), None);
+        assert_eq!(note_expiration(&note(&[&["expiration"]])), None);
+        assert_eq!(note_expiration(&note(&[&["t", "nostr"]])), None);
+    }
+
+    #[test]
+    fn expired_and_expiring_soon() {
+        let now = 1_000_000;
+        let past = note(&[&["expiration", "999999"]]);
+        assert!(is_expired(&past, now));
+        assert_eq!(expiring_soon(&past, now), None);
+
+        let soon = note(&[&["expiration", "1003600"]]);
+        assert!(!is_expired(&soon, now));
+        assert_eq!(expiring_soon(&soon, now), Some(1_003_600));
+
+        let later = (now + EXPIRING_SOON_SECS + 1).to_string();
+        let later = note(&[&["expiration", later.as_str()]]);
+        assert!(!is_expired(&later, now));
+        assert_eq!(expiring_soon(&later, now), None);
+
+        assert!(!is_expired(&note(&[]), now));
+    }
+}
@@ -218,6 +218,15 @@ pub enum ValidKind {
     Zero,
     One,
     Six,
+    Seven,
+    /// NIP-29 group chat messages
+    Nine,
+    /// NIP-72 community post approvals
+    CommunityApproval,
+    /// NIP-53 live events
+    LiveEvent,
+    /// NIP-88 polls
+    Poll,
 }
 
 impl ValidKind {
@@ -226,6 +235,11 @@ impl ValidKind {
             ValidKind::Zero => 0,
             ValidKind::One => 1,
             ValidKind::Six => 6,
+            ValidKind::Seven => 7,
+            ValidKind::Nine => 9,
+            ValidKind::CommunityApproval => 4550,
+            ValidKind::LiveEvent => 30311,
+            ValidKind::Poll => 1068,
         }
     }
 }
@@ -0,0 +1,109 @@
+//! Typo tolerant matching for short queries against names.
+
+/// How many typos we forgive for a query of this many characters. Very
+/// short queries have to match exactly, otherwise everything matches.
+fn allowed_typos(query_len: usize) -> usize {
+    match query_len {
+        0..=2 => 0,
+        3..=5 => 1,
+        _ => 2,
+    }
+}
+
+/// Optimal string alignment distance, i.e. Levenshtein plus swapping two
+/// neighbouring characters as a single edit.
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut prev2: Vec<usize> = vec![0; b.len() + 1];
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut cur: Vec<usize> = vec![0; b.len() + 1];
+
+    for i in 1..=a.len() {
+        cur[0] = i;
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            cur[j] = (prev[j] + 1).min(cur[j - 1] + 1).min(prev[j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                cur[j] = cur[j].min(prev2[j - 2] + 1);
+            }
+        }
+        std::mem::swap(&mut prev2, &mut prev);
+        std::mem::swap(&mut prev, &mut cur);
+    }
+
+    prev[b.len()]
+}
+
+/// How far `word` is from starting with `query`. The query is compared
+/// against prefixes of the word that are one character shorter or longer,
+/// so a dropped or extra letter counts as a single typo.
+fn prefix_distance(word: &[char], query: &[char]) -> usize {
+    let shortest = query.len().saturating_sub(1);
+    let longest = (query.len() + 1).min(word.len());
+
+    (shortest..=longest)
+        .map(|len| edit_distance(&word[..len], query))
+        .min()
+        .unwrap_or(query.len())
+}
+
+/// Score how well `name` matches `query`, lower is better. Any word of the
+/// name starting with the query scores 0, otherwise the score is the number
+/// of typos. `None` when it's too far off.
+pub fn fuzzy_match(name: &str, query: &str) -> Option<usize> {
+    let query: Vec<char> = query.trim().to_lowercase().chars().collect();
+    if query.is_empty() {
+        return None;
+    }
+
+    let name = name.to_lowercase();
+    let allowed = allowed_typos(query.len());
+
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let word: Vec<char> = word.chars().collect();
+            prefix_distance(&word, &query)
+        })
+        .filter(|distance| *distance <= allowed)
+        .min()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefixes_match_exactly() {
+        assert_eq!(fuzzy_match("Jack Dorsey", "jac"), Some(0));
+        assert_eq!(fuzzy_match("Jack Dorsey", "dors"), Some(0));
+    }
+
+    #[test]
+    fn typos_are_tolerated() {
+        assert_eq!(fuzzy_match("jack", "jak"), Some(1));
+        assert_eq!(fuzzy_match("jack", "jcak"), Some(1));
+        assert_eq!(fuzzy_match("william", "wiliam"), Some(1));
+        assert_eq!(fuzzy_match("satoshi_nakamoto", "nakamotto"), Some(1));
+    }
+
+    #[test]
+    fn short_queries_must_be_exact() {
+        assert_eq!(fuzzy_match("jack", "ja"), Some(0));
+        assert_eq!(fuzzy_match("jack", "jo"), None);
+    }
+
+    #[test]
+    fn unrelated_names_do_not_match() {
+        assert_eq!(fuzzy_match("fiatjaf", "jack"), None);
+        assert_eq!(fuzzy_match("", "jack"), None);
+        assert_eq!(fuzzy_match("jack", "  "), None);
+    }
+
+    #[test]
+    fn transposition_is_one_edit() {
+        let a: Vec<char> = "ab".chars().collect();
+        let b: Vec<char> = "ba".chars().collect();
+        assert_eq!(edit_distance(&a, &b), 1);
+    }
+}
@@ -5,14 +5,20 @@ mod account;
 mod app;
 mod args;
 pub mod async_loader;
+pub mod badges;
+pub mod bookmarks;
 pub mod compact;
 pub mod contacts;
+mod content_warning;
 mod context;
 pub mod debouncer;
+pub mod emoji;
 mod error;
+mod expiration;
 pub mod filter;
 pub mod fonts;
 mod frame_history;
+pub mod fuzzy;
 pub mod i18n;
 mod imgcache;
 pub mod jobs;
@@ -22,9 +28,11 @@ pub mod name;
 pub mod nav;
 pub mod nip05;
 mod nip51_set;
+mod nostr_link;
 pub mod note;
 mod notecache;
 mod oneshot_api;
+mod open_with;
 mod options;
 mod persist;
 pub mod platform;
@@ -32,6 +40,8 @@ pub mod profile;
 mod publish;
 pub mod relay_debug;
 mod relay_limits;
+mod relay_policies;
+mod relay_profiles;
 pub mod relayspec;
 mod remote_api;
 mod result;
@@ -41,6 +51,7 @@ mod scoped_sub_owners;
 mod scoped_sub_state;
 mod scoped_subs;
 mod setup;
+mod sig_policy;
 pub mod storage;
 mod style;
 pub mod theme;
@@ -57,16 +68,19 @@ mod user_account;
 mod wallet;
 mod zaps;
 
-pub use account::accounts::{AccountData, Accounts};
+pub use account::accounts::{AccountData, AccountScrub, Accounts, AddAccountResponse, KeyExport};
 pub use account::contacts::{ContactState, IsFollowing};
-pub use account::relay::RelayAction;
+pub use account::relay::{LocalRelayAction, RelayAction};
 pub use account::FALLBACK_PUBKEY;
 pub use app::{App, AppAction, AppResponse, Notedeck};
 pub use args::Args;
 pub use async_loader::{worker_count, AsyncLoader};
+pub use bookmarks::BookmarkTarget;
+pub use content_warning::{content_warning, ContentWarningMode};
 pub use context::{AppContext, SoftKeyboardContext};
 use enostr::{OutboxSessionHandler, Wakeup};
 pub use error::{show_one_error_message, Error, FilterError, ZapError};
+pub use expiration::{expiring_soon, is_expired, note_expiration, EXPIRING_SOON_SECS};
 pub use filter::{FilterState, UnifiedSubscription};
 pub use fonts::NamedFontFamily;
 pub use i18n::{CacheStats, FluentArgs, FluentValue, LanguageIdentifier, Localization};
@@ -82,24 +96,34 @@ pub use media::{
     update_imeta_blurhashes, ImageMetadata, ImageType, MediaAction, ObfuscationType,
     PixelDimensions, PointDimensions, RenderableMedia,
 };
-pub use muted::{MuteFun, Muted};
-pub use name::NostrName;
+pub use muted::{is_valid_mute_word, MuteEntry, MuteFun, MuteMode, Muted, WordMatcher};
+pub use name::{Impersonation, NostrName};
 pub use nav::DragResponse;
 pub use nip05::{Nip05Cache, Nip05Status};
 pub use nip51_set::{create_nip51_set, Nip51Set, Nip51SetCache};
+pub use nostr_link::NostrLink;
 pub use note::{
-    builder_from_note, get_p_tags, send_mute_event, send_people_list_event, send_report_event,
-    send_unmute_event, BroadcastContext, ContextSelection, NoteAction, NoteContext,
-    NoteContextSelection, NoteRef, ReportTarget, ReportType, RootIdError, RootNoteId,
-    RootNoteIdBuf, ScrollInfo, ZapAction,
+    app_handlers, builder_from_note, format_file_size, get_p_tags, note_hashtags,
+    send_deletion_event, send_mute_event, send_mute_list_update, send_people_list_event,
+    send_people_list_update, send_report_event, send_unmute_event, tokenize_text, ActionBarItem,
+    AppHandler, BroadcastContext, ContextSelection, FileMetadata, LiveEvent, LiveParticipant,
+    LiveStatus, NoteAction, NoteAddress, NoteContext, NoteContextSelection, NoteRef, Poll,
+    PollTally, PollType, PollVote, ReportTarget, ReportType, RootIdError, RootNoteId,
+    RootNoteIdBuf, ScrollInfo, TextToken, ThreadExport, ThreadExportFormat, ThreadExportTarget,
+    ZapAction, APP_HANDLER_KIND, DEFAULT_ACTION_BAR, DELETION_KIND, FILE_METADATA_KIND,
+    LIVE_EVENT_KIND, POLL_KIND, POLL_RESPONSE_KIND,
 };
 pub use notecache::{CachedNote, NoteCache};
 pub use oneshot_api::OneshotApi;
+pub use open_with::{is_open_with_template, OpenWith, OPEN_WITH_PLACEHOLDER};
 pub use options::NotedeckOptions;
 pub use persist::*;
+pub use platform::os_notifications::{OsNotificationKind, OsNotifications};
 pub use profile::*;
 pub use publish::{AccountsPublishApi, ExplicitPublishApi, PublishApi, RelayType};
 pub use relay_debug::RelayDebugView;
+pub use relay_policies::{parse_kinds, RelayPolicies, RelayPolicy};
+pub use relay_profiles::{RelayProfile, RelayProfileEntry};
 pub use relayspec::RelaySpec;
 pub use remote_api::{RelayInspectApi, RelayInspectEntry, RemoteApi};
 pub use result::Result;
@@ -108,29 +132,32 @@ pub use scoped_sub_api::ScopedSubApi;
 pub use scoped_sub_owners::SubOwnerKeyBuilder;
 pub use scoped_sub_state::ScopedSubsState;
 pub use scoped_subs::{
-    ClearSubResult, DropSlotResult, EnsureSubResult, RelaySelection, ScopedSubEoseStatus,
-    ScopedSubIdentity, ScopedSubLiveEoseStatus, SetSubResult, SubConfig, SubKey, SubKeyBuilder,
-    SubOwnerKey, SubScope,
+    ClearSubResult, DropSlotResult, EnsureSubResult, RelaySelection, ScopedSubDiagnostics,
+    ScopedSubEoseStatus, ScopedSubIdentity, ScopedSubInspect, ScopedSubLiveEoseStatus,
+    SetSubResult, SubConfig, SubKey, SubKeyBuilder, SubOwnerKey, SubScope,
 };
+pub use sig_policy::{note_signature_valid, SigPolicy};
 pub use storage::{AccountStorage, DataPath, DataPathType, Directory};
 pub use style::NotedeckTextStyle;
 pub use theme::ColorTheme;
 pub use time::{
-    is_future_timestamp, time_ago_since, time_format, unix_time_secs, MAX_FUTURE_NOTE_SKEW_SECS,
+    is_future_timestamp, time_ago_since, time_format, time_until, unix_time_secs,
+    MAX_FUTURE_NOTE_SKEW_SECS,
 };
 pub use timecache::TimeCached;
 pub use unknowns::{
     get_unknown_note_ids, unknown_id_send, NoteRefsUnkIdAction, SingleUnkIdAction, UnknownIds,
 };
-pub use urls::{supported_mime_hosted_at_url, SupportedMimeType, UrlMimes};
+pub use urls::{is_media_url, supported_mime_hosted_at_url, SupportedMimeType, UrlMimes};
 pub use user_account::UserAccount;
 pub use wallet::{
     get_current_wallet, get_current_wallet_mut, get_wallet_for, GlobalWallet, Wallet, WalletError,
     WalletType, WalletUIState, ZapWallet,
 };
 pub use zaps::{
-    get_current_default_msats, AnyZapState, DefaultZapError, DefaultZapMsats, NoteZapTarget,
-    NoteZapTargetOwned, PendingDefaultZapState, ZapTarget, ZapTargetOwned, ZappingError,
+    get_current_default_msats, get_profile_tips, note_zap_total_msats, AnyZapState,
+    DefaultZapError, DefaultZapMsats, NoteZapTarget, NoteZapTargetOwned, PendingDefaultZapState,
+    ProfileTip, ProfileTipsCache, ZapPreferences, ZapTarget, ZapTargetOwned, ZappingError,
 };
 
 /// Create a [`egui_kittest::wgpu::WgpuTestRenderer`] that only uses software
@@ -1,10 +1,147 @@
-use nostrdb::Note;
-use std::collections::BTreeSet;
+use nostrdb::{Note, Tag};
+use regex::RegexSet;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 
 //use tracing::{debug, trace};
 
-// If the note is muted return a reason string, otherwise None
-pub type MuteFun = dyn Fn(&Note, &[u8; 32]) -> bool;
+// If the note is muted return how it should be muted, otherwise None
+pub type MuteFun = dyn Fn(&Note, &[u8; 32]) -> Option<MuteMode>;
+
+/// What a mute does to the notes it matches. Variants go from strongest to
+/// weakest.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum MuteMode {
+    /// Leave the notes out entirely
+    #[default]
+    Hide,
+    /// Show a placeholder that can be expanded to see the note
+    Collapse,
+    /// Only keep the notes out of notifications
+    NotificationsOnly,
+}
+
+impl MuteMode {
+    /// Whether a note muted this way is shown in a timeline
+    pub fn applies_to(self, notifications: bool) -> bool {
+        notifications || self != MuteMode::NotificationsOnly
+    }
+}
+
+/// One entry of a mute list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MuteEntry {
+    Pubkey([u8; 32]),
+    Hashtag(String),
+    Word(String),
+    Thread([u8; 32]),
+}
+
+impl MuteEntry {
+    /// Key of the entry in [`Muted::modes`], which is how the mode is
+    /// stored in the settings
+    pub fn key(&self) -> String {
+        match self {
+            MuteEntry::Pubkey(pk) => format!("p:{}", hex::encode(pk)),
+            MuteEntry::Hashtag(tag) => format!("t:{}", tag.to_lowercase()),
+            MuteEntry::Word(word) => format!("word:{}", word.to_lowercase()),
+            MuteEntry::Thread(id) => format!("e:{}", hex::encode(id)),
+        }
+    }
+
+    /// Name and value of the entry's tag in a kind 10000 mute list
+    pub fn tag(&self) -> (&'static str, String) {
+        match self {
+            MuteEntry::Pubkey(pk) => ("p", hex::encode(pk)),
+            MuteEntry::Hashtag(tag) => ("t", tag.clone()),
+            MuteEntry::Word(word) => ("word", word.clone()),
+            MuteEntry::Thread(id) => ("e", hex::encode(id)),
+        }
+    }
+
+    /// Whether a mute list tag is this entry
+    pub fn matches_tag(&self, tag: &Tag<'_>) -> bool {
+        let (name, value) = self.tag();
+        if tag.count() < 2 || tag.get_str(0) != Some(name) {
+            return false;
+        }
+
+        match self {
+            MuteEntry::Pubkey(id) | MuteEntry::Thread(id) => {
+                tag.get_id(1) == Some(id) || tag.get_str(1) == Some(value.as_str())
+            }
+            MuteEntry::Hashtag(_) | MuteEntry::Word(_) => tag
+                .get_str(1)
+                .is_some_and(|tag_value| tag_value.to_lowercase() == value.to_lowercase()),
+        }
+    }
+}
+
+/// The regex a muted word matches with. Words wrapped in slashes are
+/// regexes of their own, anything else matches as a whole word in any case.
+/// `None` if the regex doesn't compile.
+fn word_pattern(word: &str) -> Option<String> {
+    let pattern = match word
+        .strip_prefix('/')
+        .and_then(|word| word.strip_suffix('/'))
+    {
+        Some(regex) if !regex.is_empty() => format!("(?i){regex}"),
+        _ => format!(r"(?i)(?:^|\W){}(?:\W|$)", regex::escape(word)),
+    };
+
+    regex::Regex::new(&pattern).is_ok().then_some(pattern)
+}
+
+/// Whether `word` can go on a mute list, which for `/regex/` words means
+/// the regex compiles
+pub fn is_valid_mute_word(word: &str) -> bool {
+    !word.trim().is_empty() && word_pattern(word).is_some()
+}
+
+/// The muted words compiled into one set, so checking a note is a single
+/// pass over its content. Rebuilt whenever the words change rather than
+/// per note, since it runs for every note in a timeline.
+#[derive(Clone, Default)]
+pub struct WordMatcher {
+    set: Option<RegexSet>,
+    /// The muted word behind each pattern in `set`
+    words: Vec<String>,
+}
+
+impl WordMatcher {
+    pub fn new<'a>(words: impl IntoIterator<Item = &'a String>) -> Self {
+        let (patterns, words): (Vec<String>, Vec<String>) = words
+            .into_iter()
+            .filter_map(|word| {
+                let pattern = word_pattern(word);
+                if pattern.is_none() {
+                    tracing::warn!("skipping muted word with a bad regex: {word}");
+                }
+                Some((pattern?, word.clone()))
+            })
+            .unzip();
+
+        let set = if patterns.is_empty() {
+            None
+        } else {
+            RegexSet::new(&patterns)
+                .inspect_err(|err| tracing::error!("could not compile muted words: {err}"))
+                .ok()
+        };
+
+        WordMatcher { set, words }
+    }
+
+    /// The muted words found in `content`
+    pub fn matches<'a>(&'a self, content: &str) -> impl Iterator<Item = &'a str> + 'a {
+        let matched = match &self.set {
+            Some(set) if !content.is_empty() => set.matches(content).into_iter().collect(),
+            _ => Vec::new(),
+        };
+
+        matched.into_iter().map(|i| self.words[i].as_str())
+    }
+}
 
 #[derive(Clone)]
 pub struct Muted {
@@ -12,8 +149,13 @@ pub struct Muted {
     pub pubkeys: BTreeSet<[u8; 32]>,
     pub hashtags: BTreeSet<String>,
     pub words: BTreeSet<String>,
+    /// `words`, compiled. Call [`Muted::compile_words`] after changing them.
+    pub word_matcher: WordMatcher,
     pub threads: BTreeSet<[u8; 32]>,
     pub max_hashtags_per_note: usize,
+    /// How entries are muted, by [`MuteEntry::key`]. Entries without one
+    /// are hidden.
+    pub modes: BTreeMap<String, MuteMode>,
 }
 
 impl Default for Muted {
@@ -23,7 +165,9 @@ impl Default for Muted {
             pubkeys: Default::default(),
             hashtags: Default::default(),
             words: Default::default(),
+            word_matcher: Default::default(),
             threads: Default::default(),
+            modes: Default::default(),
         }
     }
 }
@@ -42,13 +186,28 @@ impl std::fmt::Debug for Muted {
                 &self.threads.iter().map(hex::encode).collect::<Vec<_>>(),
             )
             .field("max_hashtags_per_note", &self.max_hashtags_per_note)
+            .field("modes", &self.modes)
             .finish()
     }
 }
 
 impl Muted {
-    // If the note is muted return a reason string, otherwise None
     pub fn is_muted(&self, note: &Note, thread: &[u8; 32]) -> bool {
+        self.mute_mode(note, thread).is_some()
+    }
+
+    pub fn entry_mode(&self, entry: &MuteEntry) -> MuteMode {
+        self.modes.get(&entry.key()).copied().unwrap_or_default()
+    }
+
+    // If the note is muted return how, the strongest mode wins when several
+    // entries match
+    pub fn mute_mode(&self, note: &Note, thread: &[u8; 32]) -> Option<MuteMode> {
+        let mut mode: Option<MuteMode> = None;
+        let mut matched = |entry_mode: MuteMode| {
+            mode = Some(mode.map_or(entry_mode, |mode| mode.min(entry_mode)));
+        };
+
         /*
         trace!(
             "{}: thread: {}",
@@ -65,30 +224,27 @@ impl Muted {
                 hex::encode(note.pubkey())
             );
             */
-            return true;
+            matched(self.entry_mode(&MuteEntry::Pubkey(*note.pubkey())));
         }
 
-        // Filter notes with too many hashtags (early return on limit exceeded)
+        // Filter notes with too many hashtags. This isn't a mute list entry
+        // so there is nothing to configure, they are always hidden.
         if self.max_hashtags_per_note > 0 {
             let hashtag_count = crate::note::count_hashtags(note);
             if hashtag_count > self.max_hashtags_per_note {
-                return true;
+                return Some(MuteMode::Hide);
             }
         }
 
-        // FIXME - Implement hashtag muting here
+        for hashtag in crate::note::note_hashtags(note) {
+            if self.hashtags.contains(&hashtag) {
+                matched(self.entry_mode(&MuteEntry::Hashtag(hashtag)));
+            }
+        }
 
-        // TODO - let's not add this for now, we will likely need to
-        // have an optimized data structure in nostrdb to properly
-        // mute words. this mutes substrings which is not ideal.
-        //
-        // let content = note.content().to_lowercase();
-        // for word in &self.words {
-        //     if content.contains(&word.to_lowercase()) {
-        //         debug!("{}: MUTED word: {}", hex::encode(note.id()), word);
-        //         return Some(format!("muted word {}", word));
-        //     }
-        // }
+        for word in self.word_matcher.matches(note.content()) {
+            matched(self.entry_mode(&MuteEntry::Word(word.to_owned())));
+        }
 
         if self.threads.contains(thread) {
             /*
@@ -98,13 +254,132 @@ impl Muted {
                 hex::encode(thread)
             );
             */
-            return true;
+            matched(self.entry_mode(&MuteEntry::Thread(*thread)));
         }
 
-        false
+        mode
     }
 
     pub fn is_pk_muted(&self, pk: &[u8; 32]) -> bool {
         self.pubkeys.contains(pk)
     }
+
+    /// Whether `entry` is on the mute list
+    pub fn contains(&self, entry: &MuteEntry) -> bool {
+        match entry {
+            MuteEntry::Pubkey(pk) => self.pubkeys.contains(pk),
+            MuteEntry::Hashtag(tag) => self.hashtags.contains(&tag.to_lowercase()),
+            MuteEntry::Word(word) => self.words.contains(word),
+            MuteEntry::Thread(id) => self.threads.contains(id),
+        }
+    }
+
+    /// Add `entry` when `mute`, otherwise take it off
+    pub fn set(&mut self, entry: &MuteEntry, mute: bool) {
+        fn apply<T: Ord>(set: &mut BTreeSet<T>, value: T, mute: bool) {
+            if mute {
+                set.insert(value);
+            } else {
+                set.remove(&value);
+            }
+        }
+
+        match entry {
+            MuteEntry::Pubkey(pk) => apply(&mut self.pubkeys, *pk, mute),
+            MuteEntry::Hashtag(tag) => apply(&mut self.hashtags, tag.to_lowercase(), mute),
+            MuteEntry::Word(word) => {
+                apply(&mut self.words, word.clone(), mute);
+                self.compile_words();
+            }
+            MuteEntry::Thread(id) => apply(&mut self.threads, *id, mute),
+        }
+    }
+
+    /// Rebuild the word matcher from `words`
+    pub fn compile_words(&mut self) {
+        self.word_matcher = WordMatcher::new(&self.words);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use enostr::FullKeypair;
+    use nostrdb::NoteBuilder;
+
+    #[test]
+    fn strongest_matching_mode_wins() {
+        let kp = FullKeypair::generate();
+        let note = NoteBuilder::new()
+            .kind(1)
+            .content("hi")
+            .sign(&kp.secret_key.secret_bytes())
+            .build()
+            .expect("note");
+        let author = MuteEntry::Pubkey(*note.pubkey());
+        let thread = [7; 32];
+
+        let mut muted = Muted::default();
+        assert_eq!(muted.mute_mode(&note, &thread), None);
+
+        muted.pubkeys.insert(*note.pubkey());
+        muted.modes.insert(author.key(), MuteMode::Collapse);
+        assert_eq!(muted.mute_mode(&note, &thread), Some(MuteMode::Collapse));
+
+        muted.threads.insert(thread);
+        assert_eq!(muted.mute_mode(&note, &thread), Some(MuteMode::Hide));
+    }
+
+    #[test]
+    fn muted_hashtags_match_any_case() {
+        let kp = FullKeypair::generate();
+        let note = NoteBuilder::new()
+            .kind(1)
+            .content("gm #Coffee")
+            .start_tag()
+            .tag_str("t")
+            .tag_str("Coffee")
+            .sign(&kp.secret_key.secret_bytes())
+            .build()
+            .expect("note");
+        let entry = MuteEntry::Hashtag("COFFEE".to_owned());
+
+        let mut muted = Muted::default();
+        muted.set(&entry, true);
+        assert!(muted.contains(&entry));
+        assert_eq!(muted.mute_mode(&note, &[0; 32]), Some(MuteMode::Hide));
+
+        muted.set(&entry, false);
+        assert_eq!(muted.mute_mode(&note, &[0; 32]), None);
+    }
+
+    fn muted_words<'a>(muted: &'a Muted, content: &str) -> Vec<&'a str> {
+        muted.word_matcher.matches(content).collect()
+    }
+
+    #[test]
+    fn muted_words_match_whole_words_in_any_case() {
+        let mut muted = Muted::default();
+        muted.set(&MuteEntry::Word("école".to_owned()), true);
+        muted.set(&MuteEntry::Word("c++".to_owned()), true);
+        muted.set(&MuteEntry::Word(r"/\bgm+\b/".to_owned()), true);
+
+        assert_eq!(muted_words(&muted, "L'ÉCOLE est finie"), vec!["école"]);
+        assert!(muted_words(&muted, "écoles").is_empty());
+        assert_eq!(muted_words(&muted, "writing C++, again"), vec!["c++"]);
+        assert_eq!(muted_words(&muted, "GMMM frens"), vec![r"/\bgm+\b/"]);
+        assert!(muted_words(&muted, "gmail").is_empty());
+
+        // a bad regex can't go on the list, and is skipped if it's there
+        assert!(!is_valid_mute_word("/(/"));
+        muted.set(&MuteEntry::Word("/(/".to_owned()), true);
+        assert_eq!(muted_words(&muted, "writing c++"), vec!["c++"]);
+    }
+
+    #[test]
+    fn notifications_only_mutes_stay_in_timelines() {
+        assert!(MuteMode::NotificationsOnly.applies_to(true));
+        assert!(!MuteMode::NotificationsOnly.applies_to(false));
+        assert!(MuteMode::Collapse.applies_to(false));
+    }
 }
@@ -1,5 +1,23 @@
+use std::borrow::Cow;
+
+use enostr::Pubkey;
 use nostrdb::ProfileRecord;
 
+use crate::{Nip05Cache, Nip05Status};
+
+/// Names longer than this are cut short when rendered
+pub const MAX_NAME_CHARS: usize = 64;
+
+/// Ways a profile name can pass itself off as someone else
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Impersonation {
+    /// The name is made to look like a public key
+    Npub,
+
+    /// The name carries a nip05 handle or verified badge that isn't its own
+    Handle,
+}
+
 pub struct NostrName<'a> {
     pub username: Option<&'a str>,
     pub display_name: Option<&'a str>,
@@ -30,6 +48,45 @@ impl<'a> NostrName<'a> {
         }
     }
 
+    /// Whether this profile's display name or username is dressed up as a
+    /// key or a handle it doesn't own
+    pub fn impersonation(&self) -> Option<Impersonation> {
+        let names = [self.display_name, self.username];
+        let names = names.iter().flatten();
+
+        if names.clone().any(|name| imitates_npub(name)) {
+            return Some(Impersonation::Npub);
+        }
+
+        // a handle only counts as the profile's own once it is verified
+        let own_nip05 = self.nip05.filter(|_| self.nip05_valid);
+        if names.clone().any(|name| imitates_handle(name, own_nip05)) {
+            return Some(Impersonation::Handle);
+        }
+
+        None
+    }
+
+    /// When a name shows the profile's own nip05 handle, look up whether
+    /// that handle is verified, asking for verification if it hasn't been
+    /// checked yet. Until it is, [`Self::impersonation`] flags the name.
+    pub fn check_own_handle(&mut self, pubkey: &Pubkey, nip05_cache: &mut Nip05Cache) {
+        let Some(nip05) = self.nip05 else {
+            return;
+        };
+
+        let shows_handle = [self.display_name, self.username]
+            .iter()
+            .flatten()
+            .any(|name| nip05.eq_ignore_ascii_case(&normalized(name)));
+        if !shows_handle {
+            return;
+        }
+
+        nip05_cache.request_validation(*pubkey, nip05);
+        self.nip05_valid = nip05_cache.status(pubkey, nip05) == Some(&Nip05Status::Valid);
+    }
+
     pub fn unknown() -> Self {
         Self {
             username: None,
@@ -44,6 +101,82 @@ fn is_empty(s: &str) -> bool {
     s.chars().all(|c| c.is_whitespace())
 }
 
+/// Format characters that can hide text or reorder it on screen
+fn is_hidden_char(c: char) -> bool {
+    c.is_control()
+        || matches!(
+            c,
+            '\u{061C}'
+                | '\u{200B}'..='\u{200F}'
+                | '\u{202A}'..='\u{202E}'
+                | '\u{2060}'..='\u{2069}'
+                | '\u{FEFF}'
+        )
+}
+
+/// A profile name that is safe to render: control, bidi and zero-width
+/// characters are dropped and anything past [`MAX_NAME_CHARS`] is cut off
+pub fn sanitize_name(name: &str) -> Cow<'_, str> {
+    let clean = !name.chars().any(is_hidden_char);
+    if clean && name.chars().nth(MAX_NAME_CHARS).is_none() {
+        return Cow::Borrowed(name);
+    }
+
+    let mut chars = name.chars().filter(|c| !is_hidden_char(*c));
+    let mut out: String = chars.by_ref().take(MAX_NAME_CHARS).collect();
+    if chars.next().is_some() {
+        out.pop();
+        out.push('…');
+    }
+
+    Cow::Owned(out)
+}
+
+/// What a name reads as once hidden characters, padding and a leading `@`
+/// are gone
+fn normalized(name: &str) -> String {
+    sanitize_name(name)
+        .trim()
+        .trim_start_matches('@')
+        .to_lowercase()
+}
+
+fn imitates_npub(name: &str) -> bool {
+    let name = normalized(name);
+    let name = name.strip_prefix("nostr:").unwrap_or(&name);
+
+    if name.starts_with("npub1") || name.starts_with("nprofile1") {
+        return true;
+    }
+
+    // long runs of hex, possibly abbreviated like 32e1827…2459
+    let hex = name
+        .chars()
+        .filter(|c| !matches!(c, '.' | '…' | ':'))
+        .collect::<String>();
+    hex.len() >= 16 && hex.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn imitates_handle(name: &str, own_nip05: Option<&str>) -> bool {
+    let name = normalized(name);
+
+    if name.contains(['✓', '✔', '☑', '✅', '🗸']) {
+        return true;
+    }
+
+    let Some((local, domain)) = name.split_once('@') else {
+        return false;
+    };
+    let looks_like_handle = !local.contains(char::is_whitespace)
+        && domain.contains('.')
+        && !domain.contains(char::is_whitespace);
+    if !looks_like_handle {
+        return false;
+    }
+
+    own_nip05.is_none_or(|nip05| !nip05.eq_ignore_ascii_case(&name))
+}
+
 pub fn get_display_name<'a>(record: Option<&ProfileRecord<'a>>) -> NostrName<'a> {
     let Some(record) = record else {
         return NostrName::unknown();
@@ -77,3 +210,69 @@ pub fn get_display_name<'a>(record: Option<&ProfileRecord<'a>>) -> NostrName<'a>
         nip05_valid: false,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn named(display_name: &'static str, nip05: Option<&'static str>) -> NostrName<'static> {
+        NostrName {
+            username: None,
+            display_name: Some(display_name),
+            nip05,
+            nip05_valid: false,
+        }
+    }
+
+    #[test]
+    fn sanitize_strips_hidden_chars() {
+        assert_eq!(sanitize_name("alice"), "alice");
+        assert_eq!(sanitize_name("al\u{202E}ecila\u{200B}\n"), "alecila");
+    }
+
+    #[test]
+    fn sanitize_truncates_long_names() {
+        let long = "a".repeat(MAX_NAME_CHARS * 2);
+        let name = sanitize_name(&long);
+        assert_eq!(name.chars().count(), MAX_NAME_CHARS);
+        assert!(name.ends_with('…'));
+
+        let exact = "b".repeat(MAX_NAME_CHARS);
+        assert_eq!(sanitize_name(&exact), exact);
+    }
+
+    #[test]
+    fn flags_names_posing_as_keys() {
+        assert_eq!(
+            named(
+                "npub1sg6plzptd64u62a878hep2kev88swjh3tw00gjsfl8f237lmu63q0uf63m",
+                None
+            )
+            .impersonation(),
+            Some(Impersonation::Npub)
+        );
+        assert_eq!(
+            named("@\u{200B}32e1827635450ebb", None).impersonation(),
+            Some(Impersonation::Npub)
+        );
+        assert_eq!(named("deadbeef", None).impersonation(), None);
+    }
+
+    #[test]
+    fn flags_names_posing_as_handles() {
+        assert_eq!(
+            named("jack@cash.app", None).impersonation(),
+            Some(Impersonation::Handle)
+        );
+        assert_eq!(
+            named("jack ✓", None).impersonation(),
+            Some(Impersonation::Handle)
+        );
+        // its own handle, but only once verified
+        let mut own = named("jack@cash.app", Some("jack@cash.app"));
+        assert_eq!(own.impersonation(), Some(Impersonation::Handle));
+        own.nip05_valid = true;
+        assert_eq!(own.impersonation(), None);
+        assert_eq!(named("coffee @ home", None).impersonation(), None);
+    }
+}
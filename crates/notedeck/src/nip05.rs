@@ -1,25 +1,60 @@
 use std::collections::HashMap;
 use std::sync::mpsc::{self, Receiver, Sender};
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
 use enostr::Pubkey;
+use serde::{Deserialize, Serialize};
 
-const NIP05_TTL: Duration = Duration::from_secs(8 * 3600); // 8 hours
+use crate::time::unix_time_secs;
+use crate::timed_serializer::TimedSerializer;
+use crate::{DataPath, DataPathType};
+
+const NIP05_TTL: u64 = 8 * 3600; // 8 hours
+
+/// How soon to try again when the domain couldn't be reached
+const NIP05_RETRY: u64 = 5 * 60;
+
+const NIP05_CACHE_FILE: &str = "nip05.json";
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Nip05Status {
     Pending,
     Valid,
+    /// The domain answered but doesn't list this pubkey under the name
     Invalid,
+    /// The domain couldn't be reached or sent something we can't read
+    Unreachable,
 }
 
 struct CacheEntry {
+    /// The identifier that was checked, lowercased
+    nip05: String,
     status: Nip05Status,
-    checked_at: Instant,
+    checked_at: u64,
+}
+
+impl CacheEntry {
+    fn is_fresh(&self, now: u64) -> bool {
+        let ttl = match self.status {
+            Nip05Status::Valid | Nip05Status::Invalid => NIP05_TTL,
+            Nip05Status::Pending | Nip05Status::Unreachable => NIP05_RETRY,
+        };
+        now.saturating_sub(self.checked_at) < ttl
+    }
+}
+
+/// A finished check as written to disk. Only definite answers are kept.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct SavedEntry {
+    pubkey: Pubkey,
+    nip05: String,
+    valid: bool,
+    checked_at: u64,
 }
 
 struct Completion {
     pubkey: Pubkey,
+    nip05: String,
     status: Nip05Status,
 }
 
@@ -27,6 +62,8 @@ pub struct Nip05Cache {
     cache: HashMap<Pubkey, CacheEntry>,
     tx: Sender<Completion>,
     rx: Receiver<Completion>,
+    serializer: Option<TimedSerializer<Vec<SavedEntry>>>,
+    dirty: bool,
 }
 
 impl Default for Nip05Cache {
@@ -42,16 +79,54 @@ impl Nip05Cache {
             cache: HashMap::new(),
             tx,
             rx,
+            serializer: None,
+            dirty: false,
         }
     }
 
-    pub fn status(&self, pubkey: &Pubkey) -> Option<&Nip05Status> {
-        self.cache.get(pubkey).map(|entry| &entry.status)
+    /// A cache that starts from, and keeps saving to, the results of
+    /// earlier sessions
+    pub fn load(path: &DataPath) -> Self {
+        let serializer =
+            TimedSerializer::new(path, DataPathType::Cache, NIP05_CACHE_FILE.to_owned())
+                .with_delay(Duration::from_secs(10));
+
+        let mut cache = Self::new();
+        let now = unix_time_secs();
+        for saved in serializer.get_item().unwrap_or_default() {
+            let entry = CacheEntry {
+                nip05: saved.nip05,
+                status: if saved.valid {
+                    Nip05Status::Valid
+                } else {
+                    Nip05Status::Invalid
+                },
+                checked_at: saved.checked_at,
+            };
+            if entry.is_fresh(now) {
+                cache.cache.insert(saved.pubkey, entry);
+            }
+        }
+
+        cache.serializer = Some(serializer);
+        cache
+    }
+
+    /// Status of the last check of `nip05` for `pubkey`. `None` if it was
+    /// never checked, or the profile has changed identifier since.
+    pub fn status(&self, pubkey: &Pubkey, nip05: &str) -> Option<&Nip05Status> {
+        self.cache
+            .get(pubkey)
+            .filter(|entry| entry.nip05 == nip05.to_lowercase())
+            .map(|entry| &entry.status)
     }
 
     pub fn request_validation(&mut self, pubkey: Pubkey, nip05: &str) {
+        let nip05 = nip05.to_lowercase();
+        let now = unix_time_secs();
+
         if let Some(entry) = self.cache.get(&pubkey) {
-            if entry.checked_at.elapsed() < NIP05_TTL {
+            if entry.nip05 == nip05 && entry.is_fresh(now) {
                 return;
             }
         }
@@ -59,30 +134,66 @@ impl Nip05Cache {
         self.cache.insert(
             pubkey,
             CacheEntry {
+                nip05: nip05.clone(),
                 status: Nip05Status::Pending,
-                checked_at: Instant::now(),
+                checked_at: now,
             },
         );
 
         let tx = self.tx.clone();
-        let nip05 = nip05.to_string();
 
         tokio::spawn(async move {
             let status = validate_nip05(&pubkey, &nip05).await;
-            let _ = tx.send(Completion { pubkey, status });
+            let _ = tx.send(Completion {
+                pubkey,
+                nip05,
+                status,
+            });
         });
     }
 
     pub fn poll(&mut self) {
         while let Ok(completion) = self.rx.try_recv() {
+            self.dirty |= completion.status != Nip05Status::Unreachable;
             self.cache.insert(
                 completion.pubkey,
                 CacheEntry {
+                    nip05: completion.nip05,
                     status: completion.status,
-                    checked_at: Instant::now(),
+                    checked_at: unix_time_secs(),
                 },
             );
         }
+
+        if !self.dirty {
+            return;
+        }
+
+        let Some(serializer) = self.serializer.as_mut() else {
+            return;
+        };
+
+        let saved: Vec<SavedEntry> = self
+            .cache
+            .iter()
+            .filter_map(|(pubkey, entry)| {
+                let valid = match entry.status {
+                    Nip05Status::Valid => true,
+                    Nip05Status::Invalid => false,
+                    Nip05Status::Pending | Nip05Status::Unreachable => return None,
+                };
+                Some(SavedEntry {
+                    pubkey: *pubkey,
+                    nip05: entry.nip05.clone(),
+                    valid,
+                    checked_at: entry.checked_at,
+                })
+            })
+            .collect();
+
+        if serializer.try_save(saved) {
+            self.dirty = false;
+        }
     }
 }
 
@@ -97,7 +208,7 @@ async fn validate_nip05(pubkey: &Pubkey, nip05: &str) -> Nip05Status {
         Ok(resp) => resp,
         Err(e) => {
             tracing::warn!("NIP-05 validation failed for {}: {}", nip05, e);
-            return Nip05Status::Invalid;
+            return Nip05Status::Unreachable;
         }
     };
 
@@ -105,7 +216,7 @@ async fn validate_nip05(pubkey: &Pubkey, nip05: &str) -> Nip05Status {
         Ok(v) => v,
         Err(e) => {
             tracing::warn!("NIP-05 JSON parse failed for {}: {}", nip05, e);
-            return Nip05Status::Invalid;
+            return Nip05Status::Unreachable;
         }
     };
 
@@ -136,3 +247,43 @@ fn parse_nip05(nip05: &str) -> Option<(&str, &str)> {
 
     Some((user, domain))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(nip05: &str, status: Nip05Status, checked_at: u64) -> CacheEntry {
+        CacheEntry {
+            nip05: nip05.to_owned(),
+            status,
+            checked_at,
+        }
+    }
+
+    #[test]
+    fn status_is_tied_to_the_checked_identifier() {
+        let pk = Pubkey::new([7; 32]);
+        let mut cache = Nip05Cache::new();
+        cache.cache.insert(
+            pk,
+            entry("bob@example.com", Nip05Status::Valid, unix_time_secs()),
+        );
+
+        assert_eq!(
+            cache.status(&pk, "Bob@Example.com"),
+            Some(&Nip05Status::Valid)
+        );
+        assert_eq!(cache.status(&pk, "bob@other.com"), None);
+    }
+
+    #[test]
+    fn unreachable_is_retried_sooner_than_an_answer() {
+        let now = 100_000;
+        let an_hour_ago = now - 3600;
+
+        assert!(entry("a@b.c", Nip05Status::Valid, an_hour_ago).is_fresh(now));
+        assert!(entry("a@b.c", Nip05Status::Invalid, an_hour_ago).is_fresh(now));
+        assert!(!entry("a@b.c", Nip05Status::Unreachable, an_hour_ago).is_fresh(now));
+        assert!(!entry("a@b.c", Nip05Status::Valid, now - NIP05_TTL).is_fresh(now));
+    }
+}
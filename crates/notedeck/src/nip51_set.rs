@@ -1,4 +1,4 @@
-use enostr::{OutboxSubId, Pubkey, RelayUrlPkgs};
+use enostr::{NoteId, OutboxSubId, Pubkey, RelayUrlPkgs};
 use indexmap::IndexMap;
 use nostrdb::{Filter, Ndb, Note, Subscription, Transaction};
 
@@ -11,7 +11,8 @@ pub struct Nip51SetCache {
     cached_notes: IndexMap<PackId, Nip51Set>,
 }
 
-type PackId = String;
+/// Sets are replaceable per author and `d` tag
+type PackId = (Pubkey, String);
 
 impl Nip51SetCache {
     pub fn new_accounts_read(
@@ -133,7 +134,8 @@ fn add(
             continue;
         };
 
-        if let Some(cur_cached) = cache.get(&new_pack.identifier) {
+        let id = (new_pack.author, new_pack.identifier.clone());
+        if let Some(cur_cached) = cache.get(&id) {
             if new_pack.created_at <= cur_cached.created_at {
                 continue;
             }
@@ -143,7 +145,7 @@ fn add(
             unknown_ids.add_pubkey_if_missing(ndb, txn, pk);
         }
 
-        cache.insert(new_pack.identifier.clone(), new_pack);
+        cache.insert(id, new_pack);
     }
 }
 
@@ -153,6 +155,7 @@ pub fn create_nip51_set(note: Note) -> Option<Nip51Set> {
     let mut image = None;
     let mut description = None;
     let mut pks = Vec::new();
+    let mut notes = Vec::new();
 
     for tag in note.tags() {
         if tag.count() < 2 {
@@ -171,6 +174,13 @@ pub fn create_nip51_set(note: Note) -> Option<Nip51Set> {
 
                 pks.push(Pubkey::new(*pk));
             }
+            "e" => {
+                let Some(id) = tag.get_id(1) else {
+                    continue;
+                };
+
+                notes.push(NoteId::new(*id));
+            }
             "d" => {
                 let Some(id) = tag.get_str(1) else {
                     continue;
@@ -208,33 +218,46 @@ pub fn create_nip51_set(note: Note) -> Option<Nip51Set> {
     let identifier = identifier?;
 
     Some(Nip51Set {
+        author: Pubkey::new(*note.pubkey()),
         identifier,
         title,
         image,
         description,
         pks,
+        notes,
         created_at: note.created_at(),
     })
 }
 
 /// NIP-51 Set. Read only (do not use for writing)
 pub struct Nip51Set {
+    pub author: Pubkey,
     pub identifier: String, // 'd' tag
     pub title: Option<String>,
     pub image: Option<String>,
     pub description: Option<String>,
     pub pks: Vec<Pubkey>,
+    /// Public note entries ('e' tags), as used by bookmark sets
+    pub notes: Vec<NoteId>,
     created_at: u64,
 }
 
+impl Nip51Set {
+    pub fn created_at(&self) -> u64 {
+        self.created_at
+    }
+}
+
 impl std::fmt::Debug for Nip51Set {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Nip51Set")
+            .field("author", &self.author.hex())
             .field("identifier", &self.identifier)
             .field("title", &self.title)
             .field("image", &self.image)
             .field("description", &self.description)
             .field("pks", &self.pks.len())
+            .field("notes", &self.notes.len())
             .field("created_at", &self.created_at)
             .finish()
     }
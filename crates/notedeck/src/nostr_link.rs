@@ -0,0 +1,116 @@
+use enostr::{NoteId, Pubkey};
+use nostr::nips::nip19::FromBech32;
+
+use crate::NoteAddress;
+
+/// What a `nostr:` URI (NIP-21) or bare NIP-19 string points at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NostrLink {
+    /// `npub` or `nprofile`
+    Profile(Pubkey),
+    /// `note` or `nevent`
+    Note(NoteId),
+    /// `naddr`
+    Address(NoteAddress),
+}
+
+impl NostrLink {
+    /// Parse `nostr:<bech32>`, the `nostr://` and `web+nostr:` spellings
+    /// some apps hand out, or a bare bech32 string
+    pub fn parse(link: &str) -> Option<Self> {
+        let link = link.trim();
+        let bech = ["web+nostr:", "nostr:"]
+            .iter()
+            .find_map(|scheme| link.strip_prefix(scheme))
+            .map(|rest| rest.trim_start_matches("//"))
+            .unwrap_or(link);
+
+        if bech.starts_with("npub1") {
+            Pubkey::try_from_bech32_string(bech, false)
+                .ok()
+                .map(Self::Profile)
+        } else if bech.starts_with("nprofile1") {
+            Pubkey::from_nprofile_bech(bech).map(Self::Profile)
+        } else if bech.starts_with("note1") {
+            NoteId::from_bech(bech).map(Self::Note)
+        } else if bech.starts_with("nevent1") {
+            NoteId::from_nevent_bech(bech).map(Self::Note)
+        } else if bech.starts_with("naddr1") {
+            let coordinate = nostr::nips::nip01::Coordinate::from_bech32(bech).ok()?;
+            Some(Self::Address(NoteAddress::new(
+                coordinate.kind.as_u16() as u32,
+                Pubkey::new(coordinate.public_key.to_bytes()),
+                coordinate.identifier,
+            )))
+        } else {
+            None
+        }
+    }
+
+    /// Like [`NostrLink::parse`], but only for links spelled as a URI, so
+    /// arbitrary strings that happen to be bech32 aren't taken for links
+    pub fn parse_uri(link: &str) -> Option<Self> {
+        let trimmed = link.trim();
+        if trimmed.starts_with("nostr:") || trimmed.starts_with("web+nostr:") {
+            Self::parse(trimmed)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NPUB: &str = "npub1xtscya34g58tk0z605fvr788k263gsu6cy9x0mhnm87echrgufzsevkk5s";
+    const PUBKEY: &str = "32e1827635450ebb3c5a7d12c1f8e7b2b514439ac10a67eef3d9fd9c5c68e245";
+    const NADDR: &str = "naddr1qq9x67fdv9e8g6trd3jsygpjuxp8vd29p6ancknaztql3eajk52y8xkppfn7au7elkw9c68zg5psgqqqw4rsrex0lt";
+
+    #[test]
+    fn parses_profile_links_in_every_spelling() {
+        let expected = Some(NostrLink::Profile(Pubkey::from_hex(PUBKEY).unwrap()));
+
+        assert_eq!(NostrLink::parse(NPUB), expected);
+        assert_eq!(NostrLink::parse(&format!("nostr:{NPUB}")), expected);
+        assert_eq!(NostrLink::parse(&format!("nostr://{NPUB}")), expected);
+        assert_eq!(NostrLink::parse(&format!(" web+nostr:{NPUB}\n")), expected);
+    }
+
+    #[test]
+    fn parses_note_links() {
+        let note_id = NoteId::new([7; 32]);
+        let bech = note_id.to_bech().unwrap();
+
+        assert_eq!(
+            NostrLink::parse(&format!("nostr:{bech}")),
+            Some(NostrLink::Note(note_id))
+        );
+    }
+
+    #[test]
+    fn parses_address_links() {
+        assert_eq!(
+            NostrLink::parse(&format!("nostr:{NADDR}")),
+            Some(NostrLink::Address(NoteAddress::new(
+                30023,
+                Pubkey::from_hex(PUBKEY).unwrap(),
+                "my-article"
+            )))
+        );
+    }
+
+    #[test]
+    fn uris_need_a_scheme() {
+        assert!(NostrLink::parse_uri(&format!("nostr:{NPUB}")).is_some());
+        assert_eq!(NostrLink::parse_uri(NPUB), None);
+    }
+
+    #[test]
+    fn rejects_other_strings() {
+        assert_eq!(NostrLink::parse("nostr:"), None);
+        assert_eq!(NostrLink::parse("nostr:nsec1abc"), None);
+        assert_eq!(NostrLink::parse("https://damus.io"), None);
+        assert_eq!(NostrLink::parse("npub1notreallyanpub"), None);
+    }
+}
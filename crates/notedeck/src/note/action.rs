@@ -1,7 +1,8 @@
-use super::context::ContextSelection;
-use crate::{zaps::NoteZapTargetOwned, MediaAction};
+use super::{context::ContextSelection, NoteAddress};
+use crate::{emoji::CustomEmoji, zaps::ZapTargetOwned, MediaAction};
 use egui::Vec2;
 use enostr::{NoteId, Pubkey};
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug)]
 pub struct ScrollInfo {
@@ -35,6 +36,12 @@ pub enum NoteAction {
         scroll_offset: f32,
     },
 
+    /// Open just this note with its parent, without loading the full thread
+    Permalink(NoteId),
+
+    /// User has clicked a link to an addressable note, like an article
+    Address(NoteAddress),
+
     /// User has selected some context option
     Context(ContextSelection),
 
@@ -44,6 +51,9 @@ pub enum NoteAction {
     /// User clicked on media
     Media(MediaAction),
 
+    /// User voted on a poll
+    Vote(PollVote),
+
     /// User scrolled the timeline
     Scroll(ScrollInfo),
 }
@@ -58,27 +68,83 @@ impl NoteAction {
     }
 }
 
+/// A button that can be placed on the note action bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionBarItem {
+    Reply,
+    React,
+    Repost,
+    Zap,
+    /// Add the note to a bookmark folder
+    Bookmark,
+    /// Copy a link to the note
+    Share,
+}
+
+impl ActionBarItem {
+    pub const ALL: [ActionBarItem; 6] = [
+        ActionBarItem::Reply,
+        ActionBarItem::React,
+        ActionBarItem::Repost,
+        ActionBarItem::Zap,
+        ActionBarItem::Bookmark,
+        ActionBarItem::Share,
+    ];
+}
+
+/// The action bar until the user rearranges it
+pub const DEFAULT_ACTION_BAR: [ActionBarItem; 4] = [
+    ActionBarItem::Reply,
+    ActionBarItem::React,
+    ActionBarItem::Repost,
+    ActionBarItem::Zap,
+];
+
 #[derive(Debug, Clone)]
 pub struct ReactAction {
     pub note_id: NoteId,
-    pub content: &'static str,
+    pub content: String,
+    /// Set when `content` is a NIP-30 `:shortcode:`
+    pub emoji: Option<CustomEmoji>,
 }
 
 impl ReactAction {
-    pub const fn new(note_id: NoteId, content: &'static str) -> Self {
-        Self { note_id, content }
+    pub fn new(note_id: NoteId, content: impl Into<String>) -> Self {
+        Self {
+            note_id,
+            content: content.into(),
+            emoji: None,
+        }
     }
+
+    pub fn custom(note_id: NoteId, emoji: CustomEmoji) -> Self {
+        Self {
+            note_id,
+            content: emoji.content(),
+            emoji: Some(emoji),
+        }
+    }
+}
+
+/// The options picked on a NIP-88 poll
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PollVote {
+    pub poll_id: NoteId,
+    pub options: Vec<String>,
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub enum ZapAction {
     Send(ZapTargetAmount),
-    CustomizeAmount(NoteZapTargetOwned),
-    ClearError(NoteZapTargetOwned),
+    CustomizeAmount(ZapTargetOwned),
+    ClearError(ZapTargetOwned),
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct ZapTargetAmount {
-    pub target: NoteZapTargetOwned,
+    pub target: ZapTargetOwned,
     pub specified_msats: Option<u64>, // if None use default amount
+    /// Optional message attached to the zap request (kind 9734 content)
+    pub comment: Option<String>,
 }
@@ -0,0 +1,75 @@
+use std::time::{Duration, Instant};
+
+use enostr::{Filter, Pubkey};
+use nostrdb::{Ndb, Note, NoteKey, Transaction};
+
+/// How long a resolved address is trusted before the db is asked again, so
+/// the note shows up once it arrives and newer versions replace older ones
+const REFRESH_AFTER: Duration = Duration::from_secs(5);
+
+/// The coordinate of an addressable note (`kind:pubkey:d-identifier`), as
+/// referenced by `naddr` links and `a` tags.
+#[derive(Hash, Clone, PartialEq, Eq, Debug)]
+pub struct NoteAddress {
+    pub kind: u32,
+    pub pubkey: Pubkey,
+    pub identifier: String,
+}
+
+impl NoteAddress {
+    pub fn new(kind: u32, pubkey: Pubkey, identifier: impl Into<String>) -> Self {
+        Self {
+            kind,
+            pubkey,
+            identifier: identifier.into(),
+        }
+    }
+
+    /// Filter matching the latest version of this address
+    pub fn filter(&self) -> Filter {
+        Filter::new()
+            .kinds([self.kind as u64])
+            .authors([self.pubkey.bytes()])
+            .tags([self.identifier.as_str()], 'd')
+            .limit(1)
+            .build()
+    }
+
+    /// Look up the newest local version of the addressed note
+    pub fn lookup<'a>(&self, ndb: &Ndb, txn: &'a Transaction) -> Option<Note<'a>> {
+        ndb.query(txn, &[self.filter()], 1)
+            .ok()?
+            .into_iter()
+            .map(|r| r.note)
+            .max_by_key(|n| n.created_at())
+    }
+}
+
+/// The local note an address resolved to, if any
+pub struct ResolvedAddress {
+    pub key: Option<NoteKey>,
+    checked_at: Instant,
+}
+
+impl ResolvedAddress {
+    pub fn query(ndb: &Ndb, txn: &Transaction, address: &NoteAddress) -> Self {
+        let key = ndb
+            .query(txn, &[address.filter()], 1)
+            .ok()
+            .and_then(|results| {
+                results
+                    .into_iter()
+                    .max_by_key(|r| r.note.created_at())
+                    .map(|r| r.note_key)
+            });
+
+        Self {
+            key,
+            checked_at: Instant::now(),
+        }
+    }
+
+    pub(crate) fn is_stale(&self) -> bool {
+        self.checked_at.elapsed() >= REFRESH_AFTER
+    }
+}
@@ -0,0 +1,193 @@
+use enostr::Filter;
+use nostrdb::{Ndb, Note, Transaction};
+use serde::Deserialize;
+
+/// NIP-89 announcement of an app that can open certain kinds
+pub const APP_HANDLER_KIND: u32 = 31990;
+
+/// Replaced by the event's NIP-19 id in a handler's link template
+const BECH32_PLACEHOLDER: &str = "<bech32>";
+
+/// At most this many apps are offered for a kind
+const MAX_HANDLERS: usize = 3;
+
+/// How many announcements we look through for a kind
+const MAX_ANNOUNCEMENTS: i32 = 32;
+
+/// An app that announced it can open some kind, with its web links
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppHandler {
+    pub name: String,
+    pub picture: Option<String>,
+    /// `web` link templates, with the NIP-19 entity they take when given
+    links: Vec<(String, Option<String>)>,
+}
+
+#[derive(Default, Deserialize)]
+struct HandlerMetadata {
+    name: Option<String>,
+    display_name: Option<String>,
+    picture: Option<String>,
+}
+
+impl AppHandler {
+    /// Parse a kind 31990 announcement. `None` when it has no web link
+    /// we could open.
+    pub fn from_note(note: &Note) -> Option<Self> {
+        if note.kind() != APP_HANDLER_KIND {
+            return None;
+        }
+
+        let links: Vec<(String, Option<String>)> = note
+            .tags()
+            .into_iter()
+            .filter(|tag| tag.count() >= 2 && tag.get_str(0) == Some("web"))
+            .filter_map(|tag| {
+                let url = tag.get_str(1)?;
+                url.contains(BECH32_PLACEHOLDER)
+                    .then(|| (url.to_owned(), tag.get_str(2).map(str::to_owned)))
+            })
+            .collect();
+        if links.is_empty() {
+            return None;
+        }
+
+        let metadata: HandlerMetadata = serde_json::from_str(note.content()).unwrap_or_default();
+        let name = metadata
+            .display_name
+            .or(metadata.name)
+            .map(|name| name.trim().to_owned())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| link_host(&links[0].0).to_owned());
+
+        Some(Self {
+            name,
+            picture: metadata.picture.filter(|p| !p.trim().is_empty()),
+            links,
+        })
+    }
+
+    /// The web link opening `nevent` in this app. Links marked for
+    /// `nevent` win over unmarked ones.
+    pub fn link(&self, nevent: &str) -> Option<String> {
+        let template = self
+            .links
+            .iter()
+            .find(|(_, entity)| entity.as_deref() == Some("nevent"))
+            .or_else(|| self.links.iter().find(|(_, entity)| entity.is_none()))?;
+
+        Some(template.0.replace(BECH32_PLACEHOLDER, nevent))
+    }
+}
+
+fn link_host(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    rest.split('/').next().unwrap_or(rest)
+}
+
+/// Announcements of apps handling `kind`
+pub fn app_handlers_filter(kind: u32) -> Filter {
+    Filter::new()
+        .kinds([APP_HANDLER_KIND as u64])
+        .tags([kind.to_string().as_str()], 'k')
+        .limit(MAX_ANNOUNCEMENTS as u64)
+        .build()
+}
+
+/// The apps we know of that can open notes of `kind`, newest
+/// announcements first
+pub fn app_handlers(ndb: &Ndb, txn: &Transaction, kind: u32) -> Vec<AppHandler> {
+    let Ok(results) = ndb.query(txn, &[app_handlers_filter(kind)], MAX_ANNOUNCEMENTS) else {
+        return Vec::new();
+    };
+
+    let mut notes: Vec<Note> = results.into_iter().map(|r| r.note).collect();
+    notes.sort_by_key(|note| std::cmp::Reverse(note.created_at()));
+
+    let mut handlers: Vec<AppHandler> = Vec::new();
+    for handler in notes.iter().filter_map(AppHandler::from_note) {
+        if handler.link("").is_none() || handlers.iter().any(|h| h.name == handler.name) {
+            continue;
+        }
+        handlers.push(handler);
+        if handlers.len() == MAX_HANDLERS {
+            break;
+        }
+    }
+
+    handlers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use enostr::FullKeypair;
+    use nostrdb::NoteBuilder;
+
+    fn announcement(content: &str, tags: &[&[&str]]) -> Note<'static> {
+        let mut builder = NoteBuilder::new().kind(APP_HANDLER_KIND).content(content);
+        for tag in tags {
+            builder = builder.start_tag();
+            for value in *tag {
+                builder = builder.tag_str(value);
+            }
+        }
+        builder
+            .sign(&FullKeypair::generate().secret_key.secret_bytes())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn picks_the_nevent_link() {
+        let note = announcement(
+            r#"{"name":"zapstr","picture":"https://zapstr.live/icon.png"}"#,
+            &[
+                &["d", "zapstr"],
+                &["k", "31337"],
+                &["web", "https://zapstr.live/p/<bech32>", "nprofile"],
+                &["web", "https://zapstr.live/e/<bech32>", "nevent"],
+            ],
+        );
+        let handler = AppHandler::from_note(&note).unwrap();
+        assert_eq!(handler.name, "zapstr");
+        assert_eq!(
+            handler.picture.as_deref(),
+            Some("https://zapstr.live/icon.png")
+        );
+        assert_eq!(
+            handler.link("nevent1abc").as_deref(),
+            Some("https://zapstr.live/e/nevent1abc")
+        );
+    }
+
+    #[test]
+    fn unnamed_handlers_use_the_link_host() {
+        let note = announcement(
+            "",
+            &[
+                &["k", "31337"],
+                &["web", "https://app.example.com/open/<bech32>"],
+            ],
+        );
+        let handler = AppHandler::from_note(&note).unwrap();
+        assert_eq!(handler.name, "app.example.com");
+        assert_eq!(
+            handler.link("nevent1abc").as_deref(),
+            Some("https://app.example.com/open/nevent1abc")
+        );
+    }
+
+    #[test]
+    fn handlers_without_usable_links_are_skipped() {
+        let no_web = announcement(r#"{"name":"mobile only"}"#, &[&["ios", "app://<bech32>"]]);
+        assert_eq!(AppHandler::from_note(&no_web), None);
+
+        let profiles_only = announcement(
+            r#"{"name":"profiles"}"#,
+            &[&["web", "https://example.com/<bech32>", "nprofile"]],
+        );
+        let handler = AppHandler::from_note(&profiles_only).unwrap();
+        assert_eq!(handler.link("nevent1abc"), None);
+    }
+}
@@ -1,8 +1,8 @@
 use enostr::{NoteId, Pubkey, RelayId};
-use nostrdb::{Ndb, Note, NoteKey, Transaction};
+use nostrdb::{Ndb, Note, NoteKey, NoteReply, Transaction};
 use tracing::error;
 
-use crate::{Accounts, RelayType, RemoteApi};
+use crate::{bookmarks::BookmarkTarget, Accounts, MuteEntry, OpenWith, RelayType, RemoteApi};
 
 /// When broadcasting notes, this determines whether to broadcast
 /// over the local network via multicast, or globally
@@ -12,6 +12,27 @@ pub enum BroadcastContext {
     Everywhere,
 }
 
+/// How an exported thread is written out
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ThreadExportFormat {
+    Markdown,
+    PlainText,
+}
+
+/// Where an exported thread goes
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ThreadExportTarget {
+    Clipboard,
+    /// Ask for a file to write it to
+    File,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct ThreadExport {
+    pub format: ThreadExportFormat,
+    pub target: ThreadExportTarget,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 #[allow(clippy::enum_variant_names)]
 pub enum NoteContextSelection {
@@ -22,8 +43,19 @@ pub enum NoteContextSelection {
     Broadcast(BroadcastContext),
     CopyNeventLink,
     MuteUser,
+    /// Mute the thread the note is in
+    MuteThread,
+    MuteHashtag(String),
     ReportUser,
     SummarizeThread(NoteId),
+    /// Add the note to one of our NIP-51 bookmark sets
+    Bookmark(BookmarkTarget),
+    /// Show the note in another client
+    OpenWith(OpenWith),
+    /// Flatten the note's thread into text for sharing outside nostr
+    ExportThread(ThreadExport),
+    /// Ask relays to delete one of our own notes (NIP-09)
+    Delete,
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
@@ -37,7 +69,8 @@ fn relay_hints_for_note(note: &Note<'_>, txn: &Transaction) -> Vec<String> {
     note.relays(txn).map(|relay| relay.to_owned()).collect()
 }
 
-fn note_nip19_event_bech(note: &Note<'_>, txn: &Transaction) -> Option<String> {
+/// The note as an `nevent`, with the relays it was seen on as hints
+pub fn note_nip19_event_bech(note: &Note<'_>, txn: &Transaction) -> Option<String> {
     let relay_hints = relay_hints_for_note(note, txn);
     let nip19event = nostr::nips::nip19::Nip19Event::new(
         nostr::event::EventId::from_byte_array(*note.id()),
@@ -55,7 +88,8 @@ impl NoteContextSelection {
         ndb: &Ndb,
         remote: &mut RemoteApi,
         txn: &Transaction,
-        accounts: &Accounts,
+        accounts: &mut Accounts,
+        open_with_template: Option<&str>,
     ) {
         match self {
             NoteContextSelection::Broadcast(context) => {
@@ -96,35 +130,77 @@ impl NoteContextSelection {
                 }
             }
             NoteContextSelection::MuteUser => {
-                let target = Pubkey::new(*note.pubkey());
+                let entry = MuteEntry::Pubkey(*note.pubkey());
+                toggle_mute(ndb, txn, remote, accounts, &entry);
+            }
+            NoteContextSelection::MuteThread => {
+                let root = NoteReply::new(note.tags())
+                    .root()
+                    .map_or(*note.id(), |root| *root.id);
+                toggle_mute(ndb, txn, remote, accounts, &MuteEntry::Thread(root));
+            }
+            NoteContextSelection::MuteHashtag(hashtag) => {
+                let entry = MuteEntry::Hashtag(hashtag.to_lowercase());
+                toggle_mute(ndb, txn, remote, accounts, &entry);
+            }
+            NoteContextSelection::Bookmark(target) => {
                 let Some(kp) = accounts.get_selected_account().key.to_full() else {
                     return;
                 };
-                let muted = accounts.mute();
-                if muted.is_pk_muted(target.bytes()) {
-                    super::publish::send_unmute_event(
-                        ndb,
-                        txn,
-                        &mut remote.publisher(accounts),
-                        kp,
-                        &muted,
-                        &target,
-                    );
-                } else {
-                    super::publish::send_mute_event(
-                        ndb,
-                        txn,
-                        &mut remote.publisher(accounts),
-                        kp,
-                        &muted,
-                        &target,
-                    );
+                crate::bookmarks::send_bookmark_event(
+                    ndb,
+                    txn,
+                    &mut remote.publisher(accounts),
+                    kp,
+                    target,
+                    &NoteId::new(*note.id()),
+                );
+            }
+            NoteContextSelection::OpenWith(target) => {
+                let bech =
+                    note_nip19_event_bech(note, txn).or_else(|| NoteId::new(*note.id()).to_bech());
+                if let Some(bech) = bech {
+                    target.open(ui.ctx(), &bech, open_with_template);
                 }
             }
+            NoteContextSelection::Delete => {
+                let Some(kp) = accounts.selected_filled() else {
+                    return;
+                };
+                super::send_deletion_event(ndb, &mut remote.publisher(accounts), kp, note);
+            }
             NoteContextSelection::ReportUser => {}
+            NoteContextSelection::ExportThread(_) => {
+                // Handled by Columns, which knows how to save files
+            }
             NoteContextSelection::SummarizeThread(_) => {
                 // Handled at Chrome level — routed to Dave
             }
         }
     }
 }
+
+/// Publish the mute list with `entry` flipped, and mute or unmute it locally
+/// right away so timelines don't wait on the list coming back
+fn toggle_mute(
+    ndb: &Ndb,
+    txn: &Transaction,
+    remote: &mut RemoteApi,
+    accounts: &mut Accounts,
+    entry: &MuteEntry,
+) {
+    let Some(kp) = accounts.selected_filled() else {
+        return;
+    };
+
+    let mute = !accounts.mute().contains(entry);
+    super::publish::send_mute_list_update(
+        ndb,
+        txn,
+        &mut remote.publisher(accounts),
+        kp,
+        entry,
+        mute,
+    );
+    accounts.apply_mute_entry(entry, mute);
+}
@@ -0,0 +1,184 @@
+use std::time::{Duration, Instant};
+
+use enostr::{FilledKeypair, Pubkey};
+use nostrdb::{Filter, Ndb, Note, NoteBuilder, Transaction};
+
+use super::publish::publish_note_builder;
+use crate::PublishApi;
+
+/// NIP-09 deletion request
+pub const DELETION_KIND: u32 = 5;
+
+/// How long a deletion check is trusted before the db is asked again, so
+/// deletions arriving from relays hide notes that are already on screen
+const REFRESH_AFTER: Duration = Duration::from_secs(5);
+
+/// Most deletion requests read for a single note. Authors rarely ask more
+/// than once, but an old `a` deletion shouldn't crowd out a newer one.
+const MAX_DELETIONS: i32 = 16;
+
+/// Ask relays to delete `note`. Only the author's own notes can be deleted,
+/// anything else is ignored by everyone honoring the request.
+pub fn send_deletion_event(
+    ndb: &Ndb,
+    publisher: &mut PublishApi<'_, '_>,
+    kp: FilledKeypair,
+    note: &Note,
+) {
+    if note.pubkey() != kp.pubkey.bytes() {
+        tracing::error!("refusing to delete someone else's note");
+        return;
+    }
+
+    publish_note_builder(deletion_builder(note), ndb, publisher, kp);
+}
+
+fn deletion_builder<'a>(note: &Note) -> NoteBuilder<'a> {
+    let mut builder = NoteBuilder::new()
+        .kind(DELETION_KIND)
+        .content("")
+        .start_tag()
+        .tag_str("e")
+        .tag_id(note.id());
+
+    if let Some(coordinate) = address_coordinate(note) {
+        builder = builder.start_tag().tag_str("a").tag_str(&coordinate);
+    }
+
+    builder
+        .start_tag()
+        .tag_str("k")
+        .tag_str(&note.kind().to_string())
+}
+
+/// The `kind:pubkey:d` coordinate of an addressable note, which deletes
+/// every version of it up to the deletion
+fn address_coordinate(note: &Note) -> Option<String> {
+    if !(30000..40000).contains(&note.kind()) {
+        return None;
+    }
+
+    let identifier = note
+        .tags()
+        .into_iter()
+        .find(|tag| tag.get_str(0) == Some("d"))
+        .and_then(|tag| tag.get_str(1))?;
+
+    Some(format!(
+        "{}:{}:{}",
+        note.kind(),
+        Pubkey::new(*note.pubkey()).hex(),
+        identifier
+    ))
+}
+
+/// Deletion requests for `note` made by its author. Requests from anyone
+/// else carry no weight.
+fn deletion_filters(note: &Note) -> Vec<Filter> {
+    let mut filters = vec![Filter::new()
+        .kinds([DELETION_KIND as u64])
+        .authors([note.pubkey()])
+        .event(note.id())
+        .build()];
+
+    if let Some(coordinate) = address_coordinate(note) {
+        filters.push(
+            Filter::new()
+                .kinds([DELETION_KIND as u64])
+                .authors([note.pubkey()])
+                .tags([coordinate.as_str()], 'a')
+                .build(),
+        );
+    }
+
+    filters
+}
+
+/// Whether the author of `note` asked for it to be deleted
+#[derive(Debug, Clone)]
+pub struct DeletionCheck {
+    pub deleted: bool,
+    checked_at: Instant,
+}
+
+impl DeletionCheck {
+    pub fn query(ndb: &Ndb, txn: &Transaction, note: &Note) -> Self {
+        let deleted = ndb
+            .query(txn, &deletion_filters(note), MAX_DELETIONS)
+            .map(|results| results.iter().any(|r| deletes(&r.note, note)))
+            .unwrap_or(false);
+
+        Self {
+            deleted,
+            checked_at: Instant::now(),
+        }
+    }
+
+    pub(crate) fn is_stale(&self) -> bool {
+        // once deleted a note stays deleted
+        !self.deleted && self.checked_at.elapsed() >= REFRESH_AFTER
+    }
+}
+
+/// Whether `deletion` applies to `note`. An `a` tag only deletes the
+/// versions of an address that came before it.
+fn deletes(deletion: &Note, note: &Note) -> bool {
+    if deletion.kind() != DELETION_KIND || deletion.pubkey() != note.pubkey() {
+        return false;
+    }
+
+    let coordinate = address_coordinate(note);
+    deletion.tags().into_iter().any(|tag| match tag.get_str(0) {
+        Some("e") => tag.get_id(1) == Some(note.id()),
+        Some("a") => {
+            coordinate.is_some()
+                && tag.get_str(1) == coordinate.as_deref()
+                && deletion.created_at() >= note.created_at()
+        }
+        _ => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use enostr::FullKeypair;
+    use notedeck_testing::fixtures::signed_note_at;
+
+    fn signed<'a>(keypair: &FullKeypair, builder: NoteBuilder<'a>) -> Note<'a> {
+        builder
+            .sign(&keypair.secret_key.secret_bytes())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn only_the_author_can_delete() {
+        let author = FullKeypair::generate();
+        let stranger = FullKeypair::generate();
+        let post = signed_note_at(&author, 1, 1_000, &[]);
+
+        let deletion = signed(&author, deletion_builder(&post).created_at(1_100));
+        assert!(deletes(&deletion, &post));
+
+        let forged = signed(&stranger, deletion_builder(&post).created_at(1_100));
+        assert!(!deletes(&forged, &post));
+
+        let other = signed_note_at(&author, 1, 1_000, &[&["t", "other"]]);
+        assert!(!deletes(&deletion, &other));
+    }
+
+    #[test]
+    fn address_deletions_spare_later_versions() {
+        let author = FullKeypair::generate();
+        let article = signed_note_at(&author, 30023, 1_000, &[&["d", "post"]]);
+        let deletion = signed(&author, deletion_builder(&article).created_at(1_100));
+
+        let coordinate = format!("30023:{}:post", author.pubkey.hex());
+        assert_eq!(address_coordinate(&article), Some(coordinate));
+
+        let rewritten = signed_note_at(&author, 30023, 1_200, &[&["d", "post"]]);
+        assert!(deletes(&deletion, &article));
+        assert!(!deletes(&deletion, &rewritten));
+    }
+}
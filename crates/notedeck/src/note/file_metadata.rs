@@ -0,0 +1,176 @@
+use nostrdb::Note;
+
+use crate::{ImageMetadata, MediaCacheType, ObfuscationType, PixelDimensions, RenderableMedia};
+
+/// NIP-94 file metadata
+pub const FILE_METADATA_KIND: u32 = 1063;
+
+/// The parts of a NIP-94 file metadata event we show
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileMetadata<'a> {
+    pub url: &'a str,
+    pub mime: Option<&'a str>,
+    /// Size in bytes
+    pub size: Option<u64>,
+    pub dimensions: Option<PixelDimensions>,
+    pub blurhash: Option<&'a str>,
+    /// Torrent magnet link
+    pub magnet: Option<&'a str>,
+    /// Short description of the file, the note content holds the long one
+    pub summary: Option<&'a str>,
+}
+
+impl<'a> FileMetadata<'a> {
+    pub fn from_note(note: &Note<'a>) -> Option<Self> {
+        if note.kind() != FILE_METADATA_KIND {
+            return None;
+        }
+
+        Self::from_tags(note.tags().into_iter().filter_map(|tag| {
+            if tag.count() < 2 {
+                return None;
+            }
+            Some((tag.get_str(0)?, tag.get_str(1)?))
+        }))
+    }
+
+    fn from_tags(tags: impl IntoIterator<Item = (&'a str, &'a str)>) -> Option<Self> {
+        let mut url = None;
+        let mut file = FileMetadata {
+            url: "",
+            mime: None,
+            size: None,
+            dimensions: None,
+            blurhash: None,
+            magnet: None,
+            summary: None,
+        };
+
+        for (name, value) in tags {
+            match name {
+                "url" => url = Some(value),
+                "m" => file.mime = Some(value),
+                "size" => file.size = value.parse().ok(),
+                "dim" => file.dimensions = parse_dimensions(value),
+                "blurhash" => file.blurhash = Some(value),
+                "magnet" => file.magnet = Some(value),
+                "summary" | "alt" if file.summary.is_none() => file.summary = Some(value),
+                _ => {}
+            }
+        }
+
+        file.url = url.filter(|url| !url.trim().is_empty())?;
+        Some(file)
+    }
+
+    /// The last path segment of the url, which is usually the file name
+    pub fn filename(&self) -> &'a str {
+        let path = self.url.split(['?', '#']).next().unwrap_or(self.url);
+        path.trim_end_matches('/')
+            .rsplit('/')
+            .next()
+            .filter(|name| !name.is_empty())
+            .unwrap_or(self.url)
+    }
+
+    /// Images go through the regular media pipeline, everything else is
+    /// offered as a download
+    pub fn renderable_media(&self) -> Option<RenderableMedia> {
+        let media_type = match self.mime? {
+            "image/gif" => MediaCacheType::Gif,
+            mime if mime.starts_with("image/") => MediaCacheType::Image,
+            _ => return None,
+        };
+
+        let obfuscation_type = match self.blurhash {
+            Some(blurhash) => ObfuscationType::Blurhash(ImageMetadata {
+                blurhash: blurhash.to_owned(),
+                dimensions: self.dimensions,
+            }),
+            None => ObfuscationType::Default,
+        };
+
+        Some(RenderableMedia {
+            url: self.url.to_owned(),
+            media_type,
+            obfuscation_type,
+        })
+    }
+}
+
+fn parse_dimensions(dim: &str) -> Option<PixelDimensions> {
+    let (x, y) = dim.split_once('x')?;
+    Some(PixelDimensions {
+        x: x.trim().parse().ok()?,
+        y: y.trim().parse().ok()?,
+    })
+}
+
+/// A byte count the way file managers show it, e.g. `4.2 MB`
+pub fn format_file_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1000.0 && unit < UNITS.len() - 1 {
+        size /= 1000.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_file_tags() {
+        let file = FileMetadata::from_tags([
+            ("url", "https://example.com/files/talk.mp4?dl=1"),
+            ("m", "video/mp4"),
+            ("size", "1250000"),
+            ("dim", "1920x1080"),
+            ("magnet", "magnet:?xt=urn:btih:abc"),
+            ("alt", "conference talk"),
+        ])
+        .unwrap();
+
+        assert_eq!(file.filename(), "talk.mp4");
+        assert_eq!(file.mime, Some("video/mp4"));
+        assert_eq!(file.size, Some(1_250_000));
+        assert_eq!(file.dimensions, Some(PixelDimensions { x: 1920, y: 1080 }));
+        assert_eq!(file.magnet, Some("magnet:?xt=urn:btih:abc"));
+        assert_eq!(file.summary, Some("conference talk"));
+        assert!(file.renderable_media().is_none());
+    }
+
+    #[test]
+    fn needs_a_url() {
+        assert!(FileMetadata::from_tags([("m", "image/png")]).is_none());
+        assert!(FileMetadata::from_tags([("url", " ")]).is_none());
+    }
+
+    #[test]
+    fn images_are_renderable() {
+        let file =
+            FileMetadata::from_tags([("url", "https://example.com/cat.gif"), ("m", "image/gif")])
+                .unwrap();
+
+        let media = file.renderable_media().unwrap();
+        assert_eq!(media.url, "https://example.com/cat.gif");
+        assert!(matches!(media.media_type, MediaCacheType::Gif));
+    }
+
+    #[test]
+    fn file_sizes() {
+        assert_eq!(format_file_size(999), "999 B");
+        assert_eq!(format_file_size(1_500), "1.5 KB");
+        assert_eq!(format_file_size(4_200_000), "4.2 MB");
+        assert_eq!(format_file_size(3_000_000_000_000_000), "3000.0 TB");
+    }
+}
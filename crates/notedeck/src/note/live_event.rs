@@ -0,0 +1,291 @@
+use enostr::Pubkey;
+use nostrdb::Note;
+
+use crate::{MediaCacheType, NoteAddress, ObfuscationType, RenderableMedia};
+
+/// NIP-53 live streaming event
+pub const LIVE_EVENT_KIND: u32 = 30311;
+
+/// Streams that say they're live but haven't been updated for this long
+/// are treated as ended, as NIP-53 suggests
+pub const LIVE_EVENT_STALE_SECS: u64 = 60 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiveStatus {
+    Planned,
+    Live,
+    Ended,
+}
+
+/// A `p` tag of a live event: who takes part in the stream and as what
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiveParticipant<'a> {
+    pub pubkey: &'a [u8; 32],
+    /// e.g. `Host`, `Speaker` or `Participant`
+    pub role: Option<&'a str>,
+}
+
+/// The parts of a NIP-53 live event we show
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiveEvent<'a> {
+    pub author: &'a [u8; 32],
+    pub identifier: &'a str,
+    pub title: Option<&'a str>,
+    pub summary: Option<&'a str>,
+    pub image: Option<&'a str>,
+    pub streaming: Option<&'a str>,
+    pub recording: Option<&'a str>,
+    /// The status as published, see [`LiveEvent::status_at`]
+    pub status: LiveStatus,
+    pub starts: Option<u64>,
+    pub current_participants: Option<u64>,
+    pub participants: Vec<LiveParticipant<'a>>,
+    pub created_at: u64,
+}
+
+impl<'a> LiveEvent<'a> {
+    pub fn from_note(note: &Note<'a>) -> Option<Self> {
+        if note.kind() != LIVE_EVENT_KIND {
+            return None;
+        }
+
+        let mut identifier = None;
+        let mut event = LiveEvent {
+            author: note.pubkey(),
+            identifier: "",
+            title: None,
+            summary: None,
+            image: None,
+            streaming: None,
+            recording: None,
+            status: LiveStatus::Planned,
+            starts: None,
+            current_participants: None,
+            participants: Vec::new(),
+            created_at: note.created_at(),
+        };
+
+        for tag in note.tags() {
+            if tag.count() < 2 {
+                continue;
+            }
+
+            if tag.get_str(0) == Some("p") {
+                let Some(pubkey) = tag.get_id(1) else {
+                    continue;
+                };
+                let role = tag.get_str(3).filter(|r| !r.is_empty());
+                if !event.participants.iter().any(|p| p.pubkey == pubkey) {
+                    event.participants.push(LiveParticipant { pubkey, role });
+                }
+                continue;
+            }
+
+            let (Some(name), Some(value)) = (tag.get_str(0), tag.get_str(1)) else {
+                continue;
+            };
+
+            match name {
+                "d" => identifier = Some(value),
+                "title" => event.title = Some(value),
+                "summary" => event.summary = Some(value),
+                "image" => event.image = Some(value),
+                "streaming" => event.streaming = Some(value),
+                "recording" => event.recording = Some(value),
+                "status" => event.status = parse_status(value),
+                "starts" => event.starts = value.parse().ok(),
+                "current_participants" => event.current_participants = value.parse().ok(),
+                _ => {}
+            }
+        }
+
+        event.identifier = identifier?;
+        Some(event)
+    }
+
+    /// The status at `now`, with stale live streams considered ended
+    pub fn status_at(&self, now: u64) -> LiveStatus {
+        if self.status == LiveStatus::Live
+            && now.saturating_sub(self.created_at) > LIVE_EVENT_STALE_SECS
+        {
+            return LiveStatus::Ended;
+        }
+
+        self.status
+    }
+
+    /// Where to watch: the stream while it runs, the recording after
+    pub fn watch_url(&self, now: u64) -> Option<&'a str> {
+        match self.status_at(now) {
+            LiveStatus::Ended => self.recording.or(self.streaming),
+            LiveStatus::Planned | LiveStatus::Live => self.streaming,
+        }
+    }
+
+    /// The cover image, shown through the regular media pipeline
+    pub fn cover_media(&self) -> Option<RenderableMedia> {
+        let url = self.image.filter(|url| !url.trim().is_empty())?;
+        Some(RenderableMedia {
+            url: url.to_owned(),
+            media_type: MediaCacheType::Image,
+            obfuscation_type: ObfuscationType::Default,
+        })
+    }
+
+    pub fn address(&self) -> NoteAddress {
+        NoteAddress::new(LIVE_EVENT_KIND, Pubkey::new(*self.author), self.identifier)
+    }
+}
+
+fn parse_status(status: &str) -> LiveStatus {
+    match status {
+        "live" => LiveStatus::Live,
+        "ended" => LiveStatus::Ended,
+        _ => LiveStatus::Planned,
+    }
+}
+
+/// The streams among `notes` that are live at `now`, newest first. Live
+/// events are replaceable, so only the latest version of each one counts.
+pub fn live_now<'a>(notes: impl IntoIterator<Item = Note<'a>>, now: u64) -> Vec<Note<'a>> {
+    let mut latest: Vec<(NoteAddress, Note<'a>)> = Vec::new();
+
+    for note in notes {
+        let Some(address) = LiveEvent::from_note(&note).map(|e| e.address()) else {
+            continue;
+        };
+
+        match latest.iter_mut().find(|(a, _)| *a == address) {
+            Some((_, current)) if current.created_at() < note.created_at() => *current = note,
+            Some(_) => {}
+            None => latest.push((address, note)),
+        }
+    }
+
+    let mut live: Vec<Note<'a>> = latest
+        .into_iter()
+        .map(|(_, note)| note)
+        .filter(|note| {
+            LiveEvent::from_note(note).is_some_and(|e| e.status_at(now) == LiveStatus::Live)
+        })
+        .collect();
+    live.sort_by_key(|note| std::cmp::Reverse(note.created_at()));
+    live
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use enostr::FullKeypair;
+    use nostrdb::NoteBuilder;
+
+    fn live_event(keypair: &FullKeypair, created_at: u64, tags: &[&[&str]]) -> Note<'static> {
+        let mut builder = NoteBuilder::new()
+            .kind(LIVE_EVENT_KIND)
+            .content("")
+            .created_at(created_at);
+        for tag in tags {
+            builder = builder.start_tag();
+            for value in *tag {
+                builder = builder.tag_str(value);
+            }
+        }
+        builder
+            .sign(&keypair.secret_key.secret_bytes())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn parses_live_event_tags() {
+        let service = FullKeypair::generate();
+        let host = FullKeypair::generate();
+        let speaker = FullKeypair::generate();
+        let note = live_event(
+            &service,
+            1_000,
+            &[
+                &["d", "weekly-show"],
+                &["title", "Weekly show"],
+                &["summary", "News of the week"],
+                &["image", "https://example.com/cover.jpg"],
+                &["streaming", "https://example.com/live.m3u8"],
+                &["status", "live"],
+                &["starts", "900"],
+                &["current_participants", "42"],
+                &["p", &host.pubkey.hex(), "", "Host"],
+                &["p", &speaker.pubkey.hex()],
+                &["p", &host.pubkey.hex(), "", "Speaker"],
+            ],
+        );
+
+        let event = LiveEvent::from_note(&note).unwrap();
+        assert_eq!(event.identifier, "weekly-show");
+        assert_eq!(event.title, Some("Weekly show"));
+        assert_eq!(event.status, LiveStatus::Live);
+        assert_eq!(event.starts, Some(900));
+        assert_eq!(event.current_participants, Some(42));
+        assert_eq!(
+            event.participants,
+            vec![
+                LiveParticipant {
+                    pubkey: host.pubkey.bytes(),
+                    role: Some("Host"),
+                },
+                LiveParticipant {
+                    pubkey: speaker.pubkey.bytes(),
+                    role: None,
+                },
+            ]
+        );
+        assert_eq!(
+            event.address(),
+            NoteAddress::new(LIVE_EVENT_KIND, service.pubkey, "weekly-show")
+        );
+
+        let unaddressed = live_event(&service, 1_000, &[&["title", "No d tag"]]);
+        assert!(LiveEvent::from_note(&unaddressed).is_none());
+    }
+
+    #[test]
+    fn stale_streams_have_ended() {
+        let service = FullKeypair::generate();
+        let note = live_event(
+            &service,
+            1_000,
+            &[
+                &["d", "show"],
+                &["status", "live"],
+                &["streaming", "https://example.com/live.m3u8"],
+                &["recording", "https://example.com/show.mp4"],
+            ],
+        );
+        let event = LiveEvent::from_note(&note).unwrap();
+
+        assert_eq!(event.status_at(1_000 + 60), LiveStatus::Live);
+        assert_eq!(
+            event.watch_url(1_060),
+            Some("https://example.com/live.m3u8")
+        );
+
+        let later = 1_000 + LIVE_EVENT_STALE_SECS + 1;
+        assert_eq!(event.status_at(later), LiveStatus::Ended);
+        assert_eq!(event.watch_url(later), Some("https://example.com/show.mp4"));
+    }
+
+    #[test]
+    fn live_now_keeps_the_latest_version() {
+        let service = FullKeypair::generate();
+        let started = live_event(&service, 1_000, &[&["d", "show"], &["status", "live"]]);
+        let ended = live_event(&service, 1_100, &[&["d", "show"], &["status", "ended"]]);
+        let other = live_event(&service, 1_050, &[&["d", "other"], &["status", "live"]]);
+        let planned = live_event(&service, 1_080, &[&["d", "soon"], &["status", "planned"]]);
+
+        let live = live_now([started, ended, other, planned], 1_200);
+        let ids: Vec<_> = live
+            .iter()
+            .filter_map(|n| LiveEvent::from_note(n).map(|e| e.identifier))
+            .collect();
+        assert_eq!(ids, vec!["other"]);
+    }
+}
@@ -1,16 +1,43 @@
 mod action;
+mod address;
+mod app_handler;
 mod context;
+mod deletion;
+mod file_metadata;
+mod live_event;
+mod poll;
 pub mod publish;
+mod reactions;
+mod text_tokens;
 
-pub use action::{NoteAction, ReactAction, ScrollInfo, ZapAction, ZapTargetAmount};
-pub use context::{BroadcastContext, ContextSelection, NoteContextSelection};
+pub use action::{
+    ActionBarItem, NoteAction, PollVote, ReactAction, ScrollInfo, ZapAction, ZapTargetAmount,
+    DEFAULT_ACTION_BAR,
+};
+pub use address::{NoteAddress, ResolvedAddress};
+pub use app_handler::{app_handlers, app_handlers_filter, AppHandler, APP_HANDLER_KIND};
+pub use context::{
+    note_nip19_event_bech, BroadcastContext, ContextSelection, NoteContextSelection, ThreadExport,
+    ThreadExportFormat, ThreadExportTarget,
+};
+pub use deletion::{send_deletion_event, DeletionCheck, DELETION_KIND};
+pub use file_metadata::{format_file_size, FileMetadata, FILE_METADATA_KIND};
+pub use live_event::{
+    live_now, LiveEvent, LiveParticipant, LiveStatus, LIVE_EVENT_KIND, LIVE_EVENT_STALE_SECS,
+};
+pub use poll::{
+    poll_responses_filter, Poll, PollOption, PollTally, PollType, POLL_KIND, POLL_RESPONSE_KIND,
+};
 pub use publish::{
-    builder_from_note, send_mute_event, send_people_list_event, send_report_event,
-    send_unmute_event, ReportTarget, ReportType,
+    builder_from_note, send_mute_event, send_mute_list_update, send_people_list_event,
+    send_people_list_update, send_report_event, send_unmute_event, ReportTarget, ReportType,
 };
+pub use reactions::ReactionSummary;
+pub use text_tokens::{tokenize_text, TextToken};
 
 use crate::jobs::MediaJobSender;
 use crate::nip05::Nip05Cache;
+use crate::profile::ProfileStatsCache;
 use crate::Accounts;
 use crate::GlobalWallet;
 use crate::Localization;
@@ -35,7 +62,10 @@ pub struct NoteContext<'d> {
     pub jobs: &'d MediaJobSender,
     pub unknown_ids: &'d mut UnknownIds,
     pub nip05_cache: &'d mut Nip05Cache,
+    pub profile_stats: &'d mut ProfileStatsCache,
     pub clipboard: &'d mut egui_winit::clipboard::Clipboard,
+    /// Action bar buttons in the order they are shown
+    pub action_bar: Vec<ActionBarItem>,
 }
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
@@ -246,6 +276,26 @@ pub fn count_hashtags(note: &Note) -> usize {
     count
 }
 
+/// The note's hashtags, lowercased and without duplicates
+pub fn note_hashtags(note: &Note) -> Vec<String> {
+    let mut hashtags: Vec<String> = Vec::new();
+
+    for tag in note.tags() {
+        if tag.count() < 2 || tag.get_str(0) != Some("t") {
+            continue;
+        }
+
+        let Some(hashtag) = tag.get_str(1).map(str::to_lowercase) else {
+            continue;
+        };
+        if !hashtag.is_empty() && !hashtags.contains(&hashtag) {
+            hashtags.push(hashtag);
+        }
+    }
+
+    hashtags
+}
+
 pub fn get_p_tags<'a>(note: &Note<'a>) -> Vec<&'a [u8; 32]> {
     let mut items = Vec::new();
     for tag in note.tags() {
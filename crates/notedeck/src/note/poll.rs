@@ -0,0 +1,311 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use enostr::Pubkey;
+use nostrdb::{Filter, Ndb, Note, Transaction};
+
+/// NIP-88 poll
+pub const POLL_KIND: u32 = 1068;
+
+/// NIP-88 poll response, a vote on a poll
+pub const POLL_RESPONSE_KIND: u32 = 1018;
+
+/// Most responses read from the db when tallying a single poll
+const MAX_RESPONSES: i32 = 1000;
+
+/// How long a tally is shown before it is counted again, so votes
+/// arriving from relays show up without querying on every frame
+const REFRESH_AFTER: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PollType {
+    SingleChoice,
+    MultipleChoice,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PollOption<'a> {
+    pub id: &'a str,
+    pub label: &'a str,
+}
+
+/// The parts of a NIP-88 poll we show. The question is the note content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Poll<'a> {
+    pub options: Vec<PollOption<'a>>,
+    pub poll_type: PollType,
+    /// Votes cast after this are not counted
+    pub ends_at: Option<u64>,
+    /// Where the poll author wants responses sent
+    pub relays: Vec<&'a str>,
+}
+
+impl<'a> Poll<'a> {
+    pub fn from_note(note: &Note<'a>) -> Option<Self> {
+        if note.kind() != POLL_KIND {
+            return None;
+        }
+
+        let mut poll = Poll {
+            options: Vec::new(),
+            poll_type: PollType::SingleChoice,
+            ends_at: None,
+            relays: Vec::new(),
+        };
+
+        for tag in note.tags() {
+            let (Some(name), Some(value)) = (tag.get_str(0), tag.get_str(1)) else {
+                continue;
+            };
+
+            match name {
+                "option" => {
+                    let label = tag.get_str(2).unwrap_or(value);
+                    if !poll.options.iter().any(|o| o.id == value) {
+                        poll.options.push(PollOption { id: value, label });
+                    }
+                }
+                "polltype" if value == "multiplechoice" => {
+                    poll.poll_type = PollType::MultipleChoice;
+                }
+                "endsAt" => poll.ends_at = value.parse().ok(),
+                "relay" => poll.relays.push(value),
+                _ => {}
+            }
+        }
+
+        (!poll.options.is_empty()).then_some(poll)
+    }
+
+    pub fn is_closed(&self, now: u64) -> bool {
+        self.ends_at.is_some_and(|ends_at| now > ends_at)
+    }
+
+    fn option_index(&self, id: &str) -> Option<usize> {
+        self.options.iter().position(|o| o.id == id)
+    }
+}
+
+/// Responses to the poll `poll_id`
+pub fn poll_responses_filter(poll_id: &[u8; 32]) -> Filter {
+    Filter::new()
+        .kinds([POLL_RESPONSE_KIND as u64])
+        .event(poll_id)
+        .build()
+}
+
+/// The votes on a poll that are in the local db. Everyone gets one ballot:
+/// their latest response before the poll ended.
+#[derive(Debug, Clone)]
+pub struct PollTally {
+    /// Votes for each option, in the poll's order
+    pub counts: Vec<u32>,
+    ballots: HashMap<Pubkey, Vec<usize>>,
+    built_at: Instant,
+}
+
+impl PollTally {
+    pub fn query(ndb: &Ndb, txn: &Transaction, poll_id: &[u8; 32], poll: &Poll) -> Self {
+        let responses = ndb
+            .query(txn, &[poll_responses_filter(poll_id)], MAX_RESPONSES)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|r| r.note);
+
+        Self::from_responses(poll, responses)
+    }
+
+    fn from_responses<'a>(poll: &Poll, responses: impl Iterator<Item = Note<'a>>) -> Self {
+        let mut latest: HashMap<Pubkey, Note<'a>> = HashMap::new();
+        for response in responses {
+            if poll
+                .ends_at
+                .is_some_and(|ends_at| response.created_at() > ends_at)
+            {
+                continue;
+            }
+
+            let pubkey = Pubkey::new(*response.pubkey());
+            match latest.get(&pubkey) {
+                Some(current) if current.created_at() >= response.created_at() => {}
+                _ => {
+                    latest.insert(pubkey, response);
+                }
+            }
+        }
+
+        let mut counts = vec![0; poll.options.len()];
+        let mut ballots = HashMap::new();
+        for (pubkey, response) in latest {
+            let ballot = ballot(poll, &response);
+            if ballot.is_empty() {
+                continue;
+            }
+
+            for &option in &ballot {
+                counts[option] += 1;
+            }
+            ballots.insert(pubkey, ballot);
+        }
+
+        Self {
+            counts,
+            ballots,
+            built_at: Instant::now(),
+        }
+    }
+
+    /// How many people voted
+    pub fn voters(&self) -> usize {
+        self.ballots.len()
+    }
+
+    /// The options `pubkey` voted for, as indices into the poll's options
+    pub fn ballot(&self, pubkey: &Pubkey) -> Option<&[usize]> {
+        self.ballots.get(pubkey).map(Vec::as_slice)
+    }
+
+    /// The share of voters who picked `option`, between 0 and 1
+    pub fn share(&self, option: usize) -> f32 {
+        let voters = self.voters();
+        if voters == 0 {
+            return 0.0;
+        }
+
+        self.counts.get(option).copied().unwrap_or(0) as f32 / voters as f32
+    }
+
+    pub(crate) fn is_stale(&self) -> bool {
+        self.built_at.elapsed() >= REFRESH_AFTER
+    }
+}
+
+/// The valid options of a response. Single choice polls only count the
+/// first one.
+fn ballot(poll: &Poll, response: &Note) -> Vec<usize> {
+    let mut ballot: Vec<usize> = Vec::new();
+
+    for tag in response.tags() {
+        if tag.get_str(0) != Some("response") {
+            continue;
+        }
+
+        let Some(option) = tag.get_str(1).and_then(|id| poll.option_index(id)) else {
+            continue;
+        };
+
+        if !ballot.contains(&option) {
+            ballot.push(option);
+        }
+
+        if poll.poll_type == PollType::SingleChoice {
+            break;
+        }
+    }
+
+    ballot
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use enostr::FullKeypair;
+    use notedeck_testing::fixtures::signed_note_at;
+
+    fn poll_note(poll_type: &str) -> Note<'static> {
+        signed_note_at(
+            &FullKeypair::generate(),
+            POLL_KIND,
+            1_000,
+            &[
+                &["option", "a", "Tabs"],
+                &["option", "b", "Spaces"],
+                &["option", "c", "Both"],
+                &["polltype", poll_type],
+                &["endsAt", "2000"],
+                &["relay", "wss://relay.example.com"],
+            ],
+        )
+    }
+
+    #[test]
+    fn parses_poll_tags() {
+        let note = poll_note("singlechoice");
+        let poll = Poll::from_note(&note).unwrap();
+
+        assert_eq!(poll.options.len(), 3);
+        assert_eq!(
+            poll.options[1],
+            PollOption {
+                id: "b",
+                label: "Spaces"
+            }
+        );
+        assert_eq!(poll.poll_type, PollType::SingleChoice);
+        assert_eq!(poll.relays, vec!["wss://relay.example.com"]);
+        assert!(!poll.is_closed(2_000));
+        assert!(poll.is_closed(2_001));
+
+        let multiple = poll_note("multiplechoice");
+        assert_eq!(
+            Poll::from_note(&multiple).unwrap().poll_type,
+            PollType::MultipleChoice
+        );
+    }
+
+    #[test]
+    fn one_ballot_per_voter() {
+        let note = poll_note("singlechoice");
+        let poll = Poll::from_note(&note).unwrap();
+        let alice = FullKeypair::generate();
+        let bob = FullKeypair::generate();
+
+        let responses = vec![
+            // a changed vote: only the later one counts
+            signed_note_at(&alice, POLL_RESPONSE_KIND, 1_100, &[&["response", "a"]]),
+            signed_note_at(&alice, POLL_RESPONSE_KIND, 1_200, &[&["response", "b"]]),
+            // single choice: the second option is ignored
+            signed_note_at(
+                &bob,
+                POLL_RESPONSE_KIND,
+                1_100,
+                &[&["response", "b"], &["response", "c"]],
+            ),
+            // too late
+            signed_note_at(&bob, POLL_RESPONSE_KIND, 2_100, &[&["response", "a"]]),
+        ];
+
+        let tally = PollTally::from_responses(&poll, responses.into_iter());
+        assert_eq!(tally.counts, vec![0, 2, 0]);
+        assert_eq!(tally.voters(), 2);
+        assert_eq!(tally.ballot(&alice.pubkey), Some(&[1][..]));
+        assert_eq!(tally.share(1), 1.0);
+    }
+
+    #[test]
+    fn multiple_choice_counts_every_option_once() {
+        let note = poll_note("multiplechoice");
+        let poll = Poll::from_note(&note).unwrap();
+        let alice = FullKeypair::generate();
+        let bob = FullKeypair::generate();
+
+        let responses = vec![
+            signed_note_at(
+                &alice,
+                POLL_RESPONSE_KIND,
+                1_100,
+                &[&["response", "a"], &["response", "c"], &["response", "a"]],
+            ),
+            signed_note_at(
+                &bob,
+                POLL_RESPONSE_KIND,
+                1_100,
+                &[&["response", "a"], &["response", "unknown"]],
+            ),
+        ];
+
+        let tally = PollTally::from_responses(&poll, responses.into_iter());
+        assert_eq!(tally.counts, vec![2, 0, 1]);
+        assert_eq!(tally.share(2), 0.5);
+    }
+}
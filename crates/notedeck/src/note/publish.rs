@@ -2,7 +2,7 @@ use enostr::{FilledKeypair, NoteId, Pubkey};
 use nostrdb::{Filter, Ndb, Note, NoteBuildOptions, NoteBuilder, Transaction};
 use tracing::info;
 
-use crate::{Muted, PublishApi, RelayType};
+use crate::{MuteEntry, Muted, PublishApi, RelayType};
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum ReportType {
@@ -208,6 +208,65 @@ pub fn send_mute_event(
     publish_note_builder(builder, ndb, publisher, kp);
 }
 
+/// Put `entry` on the account's kind 10000 mute list when `mute`, or take
+/// it off. The rest of the list is kept as is.
+pub fn send_mute_list_update(
+    ndb: &Ndb,
+    txn: &Transaction,
+    publisher: &mut PublishApi<'_, '_>,
+    kp: FilledKeypair,
+    entry: &MuteEntry,
+    mute: bool,
+) {
+    let filter = Filter::new()
+        .authors([kp.pubkey.bytes()])
+        .kinds([10000])
+        .limit(1)
+        .build();
+
+    let existing_note = ndb
+        .query(txn, std::slice::from_ref(&filter), 1)
+        .ok()
+        .and_then(|results| results.first().map(|qr| qr.note_key))
+        .and_then(|nk| ndb.get_note_by_key(txn, nk).ok());
+
+    if existing_note.is_none() && !mute {
+        tracing::warn!("no existing kind 10000 mute list found, nothing to unmute from");
+        return;
+    }
+
+    publish_note_builder(
+        mute_list_builder(existing_note, entry, mute),
+        ndb,
+        publisher,
+        kp,
+    );
+}
+
+fn mute_list_builder<'a>(
+    existing_note: Option<Note<'a>>,
+    entry: &MuteEntry,
+    mute: bool,
+) -> NoteBuilder<'a> {
+    // drop the entry either way so muting twice doesn't duplicate it
+    let builder = match existing_note {
+        Some(note) => {
+            builder_from_note(note, Some(|tag: &nostrdb::Tag<'_>| entry.matches_tag(tag)))
+        }
+        None => NoteBuilder::new()
+            .content("")
+            .kind(10000)
+            .options(NoteBuildOptions::default()),
+    };
+
+    if !mute {
+        return builder;
+    }
+
+    let (name, value) = entry.tag();
+    builder.start_tag().tag_str(name).tag_str(&value)
+}
+
 pub fn send_people_list_event(
     ndb: &Ndb,
     publisher: &mut PublishApi<'_, '_>,
@@ -233,6 +292,53 @@ pub fn send_people_list_event(
     publish_note_builder(builder, ndb, publisher, kp);
 }
 
+/// Republish the account's kind 30000 people list `identifier` with a new
+/// title and members. Its other tags and any private entries are kept.
+pub fn send_people_list_update(
+    ndb: &Ndb,
+    txn: &Transaction,
+    publisher: &mut PublishApi<'_, '_>,
+    kp: FilledKeypair,
+    identifier: &str,
+    title: &str,
+    members: &[Pubkey],
+) {
+    let filter = Filter::new()
+        .authors([kp.pubkey.bytes()])
+        .kinds([30000])
+        .tags([identifier], 'd')
+        .limit(1)
+        .build();
+
+    let existing_note = ndb
+        .query(txn, std::slice::from_ref(&filter), 1)
+        .ok()
+        .and_then(|results| results.into_iter().next())
+        .map(|result| result.note);
+
+    let builder = match existing_note {
+        Some(note) => builder_from_note(
+            note,
+            Some(|tag: &nostrdb::Tag<'_>| matches!(tag.get_str(0), Some("p") | Some("title"))),
+        ),
+        None => NoteBuilder::new()
+            .content("")
+            .kind(30000)
+            .options(NoteBuildOptions::default())
+            .start_tag()
+            .tag_str("d")
+            .tag_str(identifier),
+    };
+
+    let mut builder = builder.start_tag().tag_str("title").tag_str(title);
+
+    for pk in members {
+        builder = builder.start_tag().tag_str("p").tag_str(&pk.hex());
+    }
+
+    publish_note_builder(builder, ndb, publisher, kp);
+}
+
 pub fn send_report_event(
     ndb: &Ndb,
     publisher: &mut PublishApi<'_, '_>,
@@ -261,3 +367,60 @@ pub fn send_report_event(
 
     publish_note_builder(builder, ndb, publisher, kp);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use enostr::FullKeypair;
+
+    fn list_tags(note: &Note<'_>) -> Vec<(String, String)> {
+        note.tags()
+            .into_iter()
+            .map(|tag| {
+                let value = tag
+                    .get_id(1)
+                    .map(hex::encode)
+                    .or_else(|| tag.get_str(1).map(str::to_owned))
+                    .unwrap_or_default();
+                (tag.get_str(0).unwrap_or_default().to_owned(), value)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn mute_list_updates_keep_other_entries() {
+        let kp = FullKeypair::generate();
+        let seckey = kp.secret_key.secret_bytes();
+        let muted_pk = [3; 32];
+
+        let list = mute_list_builder(None, &MuteEntry::Pubkey(muted_pk), true)
+            .start_tag()
+            .tag_str("t")
+            .tag_str("Spam")
+            .sign(&seckey)
+            .build()
+            .expect("note");
+        assert_eq!(list.kind(), 10000);
+
+        let list = mute_list_builder(Some(list), &MuteEntry::Hashtag("spam".to_owned()), false)
+            .sign(&seckey)
+            .build()
+            .expect("note");
+        assert_eq!(
+            list_tags(&list),
+            vec![("p".to_owned(), hex::encode(muted_pk))]
+        );
+
+        let list = mute_list_builder(Some(list), &MuteEntry::Word("gm".to_owned()), true)
+            .sign(&seckey)
+            .build()
+            .expect("note");
+        assert_eq!(
+            list_tags(&list),
+            vec![
+                ("p".to_owned(), hex::encode(muted_pk)),
+                ("word".to_owned(), "gm".to_owned()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,101 @@
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use enostr::Pubkey;
+use nostrdb::{Filter, Ndb, Note, Transaction};
+
+/// Most reactions read from the db when summarizing a single note
+const MAX_REACTIONS: i32 = 500;
+
+/// How long a summary is shown before it is built again, so reactions
+/// arriving from relays show up without querying on every frame
+const REFRESH_AFTER: Duration = Duration::from_secs(5);
+
+/// What `+` and an empty reaction mean, see NIP-25
+const LIKE: &str = "🤙🏻";
+const DISLIKE: &str = "👎";
+
+/// The reactions to a note that are in the local db
+#[derive(Debug, Clone)]
+pub struct ReactionSummary {
+    /// Each distinct reaction and how many people used it, most used first
+    pub counts: Vec<(String, u32)>,
+    reactors: HashSet<Pubkey>,
+    built_at: Instant,
+}
+
+impl ReactionSummary {
+    pub fn query(ndb: &Ndb, txn: &Transaction, note_id: &[u8; 32]) -> Self {
+        let filter = Filter::new().kinds([7]).event(note_id).build();
+        let reactions = ndb
+            .query(txn, &[filter], MAX_REACTIONS)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|r| r.note);
+
+        Self::from_reactions(reactions)
+    }
+
+    fn from_reactions<'a>(reactions: impl Iterator<Item = Note<'a>>) -> Self {
+        // one vote per person for each distinct reaction
+        let mut seen: HashSet<(Pubkey, String)> = HashSet::new();
+        let mut counts: Vec<(String, u32)> = Vec::new();
+        let mut reactors = HashSet::new();
+
+        for reaction in reactions {
+            let pubkey = Pubkey::new(*reaction.pubkey());
+            let content = normalize(reaction.content());
+            if !seen.insert((pubkey, content.clone())) {
+                continue;
+            }
+
+            reactors.insert(pubkey);
+            match counts.iter_mut().find(|(c, _)| *c == content) {
+                Some((_, n)) => *n += 1,
+                None => counts.push((content, 1)),
+            }
+        }
+
+        counts.sort_by(|a, b| b.1.cmp(&a.1));
+
+        Self {
+            counts,
+            reactors,
+            built_at: Instant::now(),
+        }
+    }
+
+    /// Whether `pubkey` has reacted to the note with anything
+    pub fn reacted(&self, pubkey: &Pubkey) -> bool {
+        self.reactors.contains(pubkey)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    pub(crate) fn is_stale(&self) -> bool {
+        self.built_at.elapsed() >= REFRESH_AFTER
+    }
+}
+
+fn normalize(content: &str) -> String {
+    match content.trim() {
+        "" | "+" => LIKE.to_owned(),
+        "-" => DISLIKE.to_owned(),
+        other => other.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plus_and_empty_count_as_the_default_like() {
+        assert_eq!(normalize("+"), LIKE);
+        assert_eq!(normalize(""), LIKE);
+        assert_eq!(normalize("-"), DISLIKE);
+        assert_eq!(normalize(" 🔥 "), "🔥");
+    }
+}
@@ -0,0 +1,218 @@
+use enostr::{NoteId, Pubkey};
+
+/// Longest ticker we treat as a cashtag, e.g. `$BTC`
+const MAX_CASHTAG_LEN: usize = 10;
+
+/// A piece of a text block. nostrdb only splits out ASCII hashtags and
+/// `nostr:` prefixed mentions, so we look for the rest ourselves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TextToken<'a> {
+    Text(&'a str),
+    /// A hashtag without the leading `#`, in any script
+    Hashtag(&'a str),
+    /// A ticker symbol without the leading `$`
+    Cashtag(&'a str),
+    /// A bare `npub1` or `nprofile1` string
+    Profile {
+        pubkey: Pubkey,
+        bech: &'a str,
+    },
+    /// A bare `note1` or `nevent1` string
+    Note {
+        note_id: NoteId,
+        bech: &'a str,
+    },
+}
+
+fn is_tag_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Tags, cashtags and bech32 strings only start at a word boundary
+fn is_boundary(prev: Option<char>) -> bool {
+    prev.is_none_or(|c| !is_tag_char(c) && c != '$' && c != '#')
+}
+
+fn hashtag_len(rest: &str) -> Option<usize> {
+    let len: usize = rest
+        .chars()
+        .take_while(|c| is_tag_char(*c))
+        .map(char::len_utf8)
+        .sum();
+
+    // `#1` is usually a number, not a tag
+    rest[..len]
+        .chars()
+        .any(|c| c.is_alphabetic())
+        .then_some(len)
+}
+
+fn cashtag_len(rest: &str) -> Option<usize> {
+    let len = rest.chars().take_while(|c| c.is_ascii_alphabetic()).count();
+    let ends_cleanly = rest[len..].chars().next().is_none_or(|c| !is_tag_char(c));
+
+    (len > 0 && len <= MAX_CASHTAG_LEN && ends_cleanly).then_some(len)
+}
+
+fn bech_token(rest: &str) -> Option<TextToken<'_>> {
+    let len = rest
+        .chars()
+        .take_while(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        .count();
+    let bech = &rest[..len];
+
+    if bech.starts_with("npub1") {
+        let pubkey = Pubkey::try_from_bech32_string(bech, false).ok()?;
+        Some(TextToken::Profile { pubkey, bech })
+    } else if bech.starts_with("nprofile1") {
+        let pubkey = Pubkey::from_nprofile_bech(bech)?;
+        Some(TextToken::Profile { pubkey, bech })
+    } else if bech.starts_with("note1") {
+        let note_id = NoteId::from_bech(bech)?;
+        Some(TextToken::Note { note_id, bech })
+    } else if bech.starts_with("nevent1") {
+        let note_id = NoteId::from_nevent_bech(bech)?;
+        Some(TextToken::Note { note_id, bech })
+    } else {
+        None
+    }
+}
+
+impl TextToken<'_> {
+    /// How many bytes of the source text this token covers
+    fn source_len(&self) -> usize {
+        match self {
+            TextToken::Text(text) => text.len(),
+            TextToken::Hashtag(tag) | TextToken::Cashtag(tag) => tag.len() + 1,
+            TextToken::Profile { bech, .. } | TextToken::Note { bech, .. } => bech.len(),
+        }
+    }
+}
+
+/// Split a text block into plain text and the clickable pieces inside it.
+pub fn tokenize_text(text: &str) -> Vec<TextToken<'_>> {
+    let mut tokens = Vec::new();
+    let mut text_start = 0;
+    let mut prev = None;
+    let mut iter = text.char_indices();
+
+    while let Some((i, c)) = iter.next() {
+        let token = if !is_boundary(prev) {
+            None
+        } else {
+            let rest = &text[i + c.len_utf8()..];
+            match c {
+                '#' => hashtag_len(rest).map(|len| TextToken::Hashtag(&rest[..len])),
+                '$' => cashtag_len(rest).map(|len| TextToken::Cashtag(&rest[..len])),
+                'n' => bech_token(&text[i..]),
+                _ => None,
+            }
+        };
+
+        let Some(token) = token else {
+            prev = Some(c);
+            continue;
+        };
+
+        if text_start < i {
+            tokens.push(TextToken::Text(&text[text_start..i]));
+        }
+
+        let end = i + token.source_len();
+        tokens.push(token);
+        text_start = end;
+
+        // skip over the token, remembering its last char for the boundary check
+        for (j, c) in iter.by_ref() {
+            prev = Some(c);
+            if j + c.len_utf8() >= end {
+                break;
+            }
+        }
+    }
+
+    if text_start < text.len() {
+        tokens.push(TextToken::Text(&text[text_start..]));
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NPUB: &str = "npub1xtscya34g58tk0z605fvr788k263gsu6cy9x0mhnm87echrgufzsevkk5s";
+    const NOTE: &str = "note1xtscya34g58tk0z605fvr788k263gsu6cy9x0mhnm87echrgufzsgx4tdc";
+
+    #[test]
+    fn plain_text_is_one_token() {
+        assert_eq!(
+            tokenize_text("just some text, 100% plain"),
+            vec![TextToken::Text("just some text, 100% plain")]
+        );
+    }
+
+    #[test]
+    fn international_hashtags() {
+        assert_eq!(
+            tokenize_text("日本 #日本語 and #café!"),
+            vec![
+                TextToken::Text("日本 "),
+                TextToken::Hashtag("日本語"),
+                TextToken::Text(" and "),
+                TextToken::Hashtag("café"),
+                TextToken::Text("!"),
+            ]
+        );
+    }
+
+    #[test]
+    fn numbers_and_mid_word_hashes_are_not_tags() {
+        assert_eq!(
+            tokenize_text("issue #42 and a#b"),
+            vec![TextToken::Text("issue #42 and a#b")]
+        );
+    }
+
+    #[test]
+    fn cashtags() {
+        assert_eq!(
+            tokenize_text("$BTC up, $5 down, $TOOLONGTICKER, US$ and $eth."),
+            vec![
+                TextToken::Cashtag("BTC"),
+                TextToken::Text(" up, $5 down, $TOOLONGTICKER, US$ and "),
+                TextToken::Cashtag("eth"),
+                TextToken::Text("."),
+            ]
+        );
+    }
+
+    #[test]
+    fn bare_npub() {
+        let text = format!("hi {NPUB}, welcome");
+        let tokens = tokenize_text(&text);
+
+        assert_eq!(tokens.len(), 3);
+        assert_eq!(tokens[0], TextToken::Text("hi "));
+        assert!(matches!(tokens[1], TextToken::Profile { bech, .. } if bech == NPUB));
+        assert_eq!(tokens[2], TextToken::Text(", welcome"));
+    }
+
+    #[test]
+    fn bare_note_id() {
+        let text = format!("see {NOTE}");
+        let tokens = tokenize_text(&text);
+
+        assert_eq!(tokens.len(), 2);
+        assert!(matches!(tokens[1], TextToken::Note { bech, .. } if bech == NOTE));
+    }
+
+    #[test]
+    fn invalid_bech32_stays_text() {
+        assert_eq!(
+            tokenize_text("npub1notreallyakey and nothing"),
+            vec![TextToken::Text("npub1notreallyakey and nothing")]
+        );
+    }
+}
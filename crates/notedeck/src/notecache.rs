@@ -1,18 +1,123 @@
-use nostrdb::{Note, NoteKey, NoteReply, NoteReplyBuf};
+use nostrdb::{Ndb, Note, NoteKey, NoteReply, NoteReplyBuf, Transaction};
 use std::collections::HashMap;
 
+use crate::note::{DeletionCheck, NoteAddress, Poll, PollTally, ReactionSummary, ResolvedAddress};
+use crate::note_signature_valid;
+
 #[derive(Default)]
 pub struct NoteCache {
     pub cache: HashMap<NoteKey, CachedNote>,
+    /// Local notes that linked addresses resolved to
+    addresses: HashMap<NoteAddress, ResolvedAddress>,
+    /// Check signatures as notes are shown, for when nostrdb stores them
+    /// unchecked
+    verify_signatures: bool,
 }
 
 impl NoteCache {
+    pub fn with_signature_checks(mut self, verify_signatures: bool) -> Self {
+        self.verify_signatures = verify_signatures;
+        self
+    }
+
+    /// Whether the note can be shown. Each note's signature is checked at
+    /// most once, and only when we verify on display.
+    pub fn signature_valid(&mut self, note_key: NoteKey, note: &Note) -> bool {
+        if !self.verify_signatures {
+            return true;
+        }
+
+        let cached = self.cached_note_or_insert_mut(note_key, note);
+        *cached
+            .sig_valid
+            .get_or_insert_with(|| note_signature_valid(note))
+    }
+
     pub fn cached_note_or_insert_mut(&mut self, note_key: NoteKey, note: &Note) -> &mut CachedNote {
         self.cache
             .entry(note_key)
             .or_insert_with(|| CachedNote::new(note))
     }
 
+    /// Reactions to the note, rebuilt from the db once they've been shown
+    /// for a little while
+    pub fn reactions(
+        &mut self,
+        ndb: &Ndb,
+        txn: &Transaction,
+        note_key: NoteKey,
+        note: &Note,
+    ) -> &ReactionSummary {
+        let cached = self.cached_note_or_insert_mut(note_key, note);
+        if cached.reactions.as_ref().is_some_and(|r| r.is_stale()) {
+            cached.reactions = None;
+        }
+
+        cached
+            .reactions
+            .get_or_insert_with(|| ReactionSummary::query(ndb, txn, note.id()))
+    }
+
+    /// Votes on a poll, counted again once they've been shown for a
+    /// little while
+    pub fn poll_tally(
+        &mut self,
+        ndb: &Ndb,
+        txn: &Transaction,
+        note_key: NoteKey,
+        note: &Note,
+        poll: &Poll,
+    ) -> &PollTally {
+        let cached = self.cached_note_or_insert_mut(note_key, note);
+        if cached.poll_tally.as_ref().is_some_and(|t| t.is_stale()) {
+            cached.poll_tally = None;
+        }
+
+        cached
+            .poll_tally
+            .get_or_insert_with(|| PollTally::query(ndb, txn, note.id(), poll))
+    }
+
+    /// Whether the author asked for the note to be deleted (NIP-09),
+    /// checked again every little while until they have
+    pub fn deleted(
+        &mut self,
+        ndb: &Ndb,
+        txn: &Transaction,
+        note_key: NoteKey,
+        note: &Note,
+    ) -> bool {
+        let cached = self.cached_note_or_insert_mut(note_key, note);
+        if cached.deletion.as_ref().is_some_and(|d| d.is_stale()) {
+            cached.deletion = None;
+        }
+
+        cached
+            .deletion
+            .get_or_insert_with(|| DeletionCheck::query(ndb, txn, note))
+            .deleted
+    }
+
+    /// Key of the newest local version of the addressed note, looked up
+    /// again every little while
+    pub fn address_key(
+        &mut self,
+        ndb: &Ndb,
+        txn: &Transaction,
+        address: &NoteAddress,
+    ) -> Option<NoteKey> {
+        if let Some(resolved) = self.addresses.get(address) {
+            if !resolved.is_stale() {
+                return resolved.key;
+            }
+        }
+
+        let resolved = ResolvedAddress::query(ndb, txn, address);
+        let key = resolved.key;
+        self.addresses.insert(address.clone(), resolved);
+        key
+    }
+
     pub fn cached_note(&self, note_key: NoteKey) -> Option<&CachedNote> {
         self.cache.get(&note_key)
     }
@@ -33,6 +138,11 @@ pub struct CachedNote {
     //reltime: TimeCached<String>,
     pub client: Option<String>,
     pub reply: NoteReplyBuf,
+    /// Result of the signature check, when there was one
+    pub sig_valid: Option<bool>,
+    pub reactions: Option<ReactionSummary>,
+    pub poll_tally: Option<PollTally>,
+    pub deletion: Option<DeletionCheck>,
 }
 
 impl CachedNote {
@@ -54,6 +164,10 @@ impl CachedNote {
             client: client.map(|c| c.to_string()),
             //   reltime,
             reply,
+            sig_valid: None,
+            reactions: None,
+            poll_tally: None,
+            deletion: None,
         }
     }
 
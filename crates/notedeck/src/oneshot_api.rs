@@ -1,4 +1,5 @@
-use enostr::RelayUrlPkgs;
+use enostr::{NormRelayUrl, RelayUrlPkgs};
+use hashbrown::HashSet;
 use nostrdb::Filter;
 
 use crate::{Accounts, Outbox};
@@ -24,6 +25,16 @@ impl<'o, 'a> OneshotApi<'o, 'a> {
             RelayUrlPkgs::new(self.accounts.selected_account_read_relays()),
         );
     }
+
+    /// Send a one-shot request to specific relays, e.g. only the ones that
+    /// support the filter.
+    pub fn oneshot_to(&mut self, filters: Vec<Filter>, relays: HashSet<NormRelayUrl>) {
+        if relays.is_empty() {
+            return;
+        }
+
+        self.pool.oneshot(filters, RelayUrlPkgs::new(relays));
+    }
 }
 
 #[cfg(test)]
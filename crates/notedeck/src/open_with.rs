@@ -0,0 +1,80 @@
+//! Handing a note or profile off to another nostr client, for when we
+//! can't show it properly ourselves.
+
+/// Where to open a note or profile
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum OpenWith {
+    /// The njump.me web viewer, which renders most kinds
+    Njump,
+    /// Whichever app the system has registered for `nostr:` links
+    NostrClient,
+    /// The user's own link template from settings
+    Template,
+}
+
+/// Replaced by the note or profile's NIP-19 id in an open with template,
+/// e.g. `https://coracle.social/{nip19}`
+pub const OPEN_WITH_PLACEHOLDER: &str = "{nip19}";
+
+impl OpenWith {
+    /// The link to open for `nip19`. `None` for [`OpenWith::Template`] when
+    /// there's no usable template.
+    pub fn url(self, nip19: &str, template: Option<&str>) -> Option<String> {
+        match self {
+            OpenWith::Njump => Some(format!("https://njump.me/{nip19}")),
+            OpenWith::NostrClient => Some(format!("nostr:{nip19}")),
+            OpenWith::Template => {
+                let template = template?.trim();
+                template
+                    .contains(OPEN_WITH_PLACEHOLDER)
+                    .then(|| template.replace(OPEN_WITH_PLACEHOLDER, nip19))
+            }
+        }
+    }
+
+    pub fn open(self, ctx: &egui::Context, nip19: &str, template: Option<&str>) {
+        match self.url(nip19, template) {
+            Some(url) => ctx.open_url(egui::OpenUrl::new_tab(url)),
+            None => tracing::warn!("no open with link for {nip19} ({self:?})"),
+        }
+    }
+}
+
+/// Whether `template` can be used to open things
+pub fn is_open_with_template(template: &str) -> bool {
+    template.contains(OPEN_WITH_PLACEHOLDER)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NPUB: &str = "npub1xtscya34g58tk0z605fvr788k263gsu6cy9x0mhnm87echrgufzsevkk5s";
+
+    #[test]
+    fn open_with_links() {
+        assert_eq!(
+            OpenWith::Njump.url(NPUB, None).as_deref(),
+            Some(format!("https://njump.me/{NPUB}").as_str())
+        );
+        assert_eq!(
+            OpenWith::NostrClient.url(NPUB, None).as_deref(),
+            Some(format!("nostr:{NPUB}").as_str())
+        );
+        assert_eq!(
+            OpenWith::Template
+                .url(NPUB, Some(" https://coracle.social/{nip19} "))
+                .as_deref(),
+            Some(format!("https://coracle.social/{NPUB}").as_str())
+        );
+    }
+
+    #[test]
+    fn template_needs_placeholder() {
+        assert_eq!(OpenWith::Template.url(NPUB, None), None);
+        assert_eq!(
+            OpenWith::Template.url(NPUB, Some("https://coracle.social/")),
+            None
+        );
+    }
+}
@@ -6,4 +6,6 @@ pub use app_size::AppSizeHandler;
 pub use settings_handler::Settings;
 pub use settings_handler::SettingsHandler;
 pub use settings_handler::DEFAULT_MAX_HASHTAGS_PER_NOTE;
+pub use settings_handler::DEFAULT_MAX_PREVIEW_DEPTH;
+pub use settings_handler::DEFAULT_PROFILE_REFRESH_HOURS;
 pub use token_handler::TokenHandler;
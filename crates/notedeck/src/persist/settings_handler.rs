@@ -1,7 +1,13 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+
 use crate::{
-    storage::delete_file, timed_serializer::TimedSerializer, DataPath, DataPathType, Directory,
+    storage::delete_file, timed_serializer::TimedSerializer, ActionBarItem, ContentWarningMode,
+    DataPath, DataPathType, Directory, MuteEntry, MuteMode, OsNotificationKind, RelayPolicy,
+    RelayProfile, RelaySpec, SigPolicy, ZapPreferences, DEFAULT_ACTION_BAR,
 };
 use egui::ThemePreference;
+use enostr::Pubkey;
 use serde::{Deserialize, Serialize};
 use tracing::{error, info};
 
@@ -16,6 +22,8 @@ const DEFAULT_SHOW_SOURCE_CLIENT: &str = "hide";
 const DEFAULT_SHOW_REPLIES_NEWEST_FIRST: bool = false;
 const DEFAULT_TOS_VERSION: &str = "1.0";
 pub const DEFAULT_MAX_HASHTAGS_PER_NOTE: usize = 3;
+pub const DEFAULT_MAX_PREVIEW_DEPTH: u8 = 2;
+pub const DEFAULT_PROFILE_REFRESH_HOURS: u32 = 24;
 
 fn deserialize_theme(serialized_theme: &str) -> Option<ThemePreference> {
     match serialized_theme {
@@ -46,12 +54,107 @@ pub struct Settings {
     pub tos_version: String,
     #[serde(default)]
     pub age_verified: bool,
+    /// Zap preferences by account pubkey (hex)
+    #[serde(default)]
+    pub zap_preferences: BTreeMap<String, ZapPreferences>,
+    #[serde(default)]
+    pub relay_profiles: Vec<RelayProfile>,
+    /// Name of the relay profile in use, if any
+    #[serde(default)]
+    pub active_relay_profile: Option<String>,
+    /// Relays limited to some of our traffic, e.g. DM relays only used for
+    /// gift wraps
+    #[serde(default)]
+    pub relay_policies: Vec<RelayPolicy>,
+    /// Upload images with their EXIF/XMP metadata (location, camera) intact
+    #[serde(default)]
+    pub keep_media_metadata: bool,
+    /// How many levels of quoted notes are previewed inline before they
+    /// collapse behind a button
+    #[serde(default = "default_max_preview_depth")]
+    pub max_preview_depth: u8,
+    /// Users whose notes never show up in an account's notifications, by
+    /// account pubkey (hex). Unlike a mute, their notes stay visible
+    /// everywhere else.
+    #[serde(default)]
+    pub notification_mutes: BTreeMap<String, BTreeSet<Pubkey>>,
+    /// How mute list entries are muted, by account pubkey (hex) and then
+    /// [`MuteEntry::key`]. Entries without a mode are hidden.
+    #[serde(default)]
+    pub mute_modes: BTreeMap<String, BTreeMap<String, MuteMode>>,
+    /// After publishing, look for the note on relays we didn't publish to
+    /// and warn when it hasn't spread
+    #[serde(default)]
+    pub verify_publishes: bool,
+    /// When event signatures are checked. Read at startup.
+    #[serde(default)]
+    pub sig_policy: SigPolicy,
+    /// Profiles we view are refetched once our copy is this many hours old
+    #[serde(default = "default_profile_refresh_hours")]
+    pub profile_refresh_hours: u32,
+    /// Let DM partners see when we're typing a message to them
+    #[serde(default = "default_send_typing_indicators")]
+    pub send_typing_indicators: bool,
+    /// Buttons on the note action bar, in order
+    #[serde(default = "default_action_bar")]
+    pub action_bar: Vec<ActionBarItem>,
+    /// Blossom server new uploads go to, by account pubkey (hex). Accounts
+    /// without one upload to their NIP-96 server.
+    #[serde(default)]
+    pub upload_servers: BTreeMap<String, String>,
+    /// NIP-96 file server used when no Blossom server is picked, by account
+    /// pubkey (hex). Accounts without one upload to nostr.build.
+    #[serde(default)]
+    pub nip96_servers: BTreeMap<String, String>,
+    /// Link template for opening notes and profiles in another client,
+    /// see [`crate::OPEN_WITH_PLACEHOLDER`]
+    #[serde(default)]
+    pub open_with_template: String,
+    /// Leave NIP-58 badges off profiles
+    #[serde(default)]
+    pub hide_badges: bool,
+    /// Keep secret keys in the OS secure store (Keychain, Credential
+    /// Manager, secret service) rather than in the account files
+    #[serde(default = "default_os_keyring")]
+    pub os_keyring: bool,
+    /// What to show system notifications for while the app is in the
+    /// background
+    #[serde(default = "default_os_notifications")]
+    pub os_notifications: BTreeSet<OsNotificationKind>,
+    /// How notes with a NIP-36 content warning are shown, by account
+    /// pubkey (hex). Accounts without one are asked.
+    #[serde(default)]
+    pub content_warning_modes: BTreeMap<String, ContentWarningMode>,
 }
 
 fn default_animate_nav_transitions() -> bool {
     true
 }
 
+fn default_send_typing_indicators() -> bool {
+    true
+}
+
+fn default_os_keyring() -> bool {
+    true
+}
+
+fn default_os_notifications() -> BTreeSet<OsNotificationKind> {
+    OsNotificationKind::ALL.into_iter().collect()
+}
+
+fn default_action_bar() -> Vec<ActionBarItem> {
+    DEFAULT_ACTION_BAR.to_vec()
+}
+
+fn default_max_preview_depth() -> u8 {
+    DEFAULT_MAX_PREVIEW_DEPTH
+}
+
+fn default_profile_refresh_hours() -> u32 {
+    DEFAULT_PROFILE_REFRESH_HOURS
+}
+
 fn default_tos_version() -> String {
     DEFAULT_TOS_VERSION.to_string()
 }
@@ -71,6 +174,26 @@ impl Default for Settings {
             tos_accepted_at: None,
             tos_version: default_tos_version(),
             age_verified: false,
+            zap_preferences: BTreeMap::new(),
+            relay_profiles: Vec::new(),
+            active_relay_profile: None,
+            relay_policies: Vec::new(),
+            keep_media_metadata: false,
+            max_preview_depth: DEFAULT_MAX_PREVIEW_DEPTH,
+            notification_mutes: BTreeMap::new(),
+            mute_modes: BTreeMap::new(),
+            verify_publishes: false,
+            sig_policy: SigPolicy::default(),
+            profile_refresh_hours: DEFAULT_PROFILE_REFRESH_HOURS,
+            send_typing_indicators: default_send_typing_indicators(),
+            action_bar: default_action_bar(),
+            upload_servers: BTreeMap::new(),
+            nip96_servers: BTreeMap::new(),
+            open_with_template: String::new(),
+            hide_badges: false,
+            os_keyring: default_os_keyring(),
+            os_notifications: default_os_notifications(),
+            content_warning_modes: BTreeMap::new(),
         }
     }
 }
@@ -210,11 +333,71 @@ impl SettingsHandler {
         self.try_save_settings();
     }
 
+    pub fn set_keep_media_metadata(&mut self, value: bool) {
+        self.get_settings_mut().keep_media_metadata = value;
+        self.try_save_settings();
+    }
+
+    pub fn set_hide_badges(&mut self, value: bool) {
+        self.get_settings_mut().hide_badges = value;
+        self.try_save_settings();
+    }
+
+    pub fn set_verify_publishes(&mut self, value: bool) {
+        self.get_settings_mut().verify_publishes = value;
+        self.try_save_settings();
+    }
+
+    pub fn set_os_keyring(&mut self, value: bool) {
+        self.get_settings_mut().os_keyring = value;
+        self.try_save_settings();
+    }
+
+    pub fn set_os_notification(&mut self, kind: OsNotificationKind, enabled: bool) {
+        let kinds = &mut self.get_settings_mut().os_notifications;
+        if enabled {
+            kinds.insert(kind);
+        } else {
+            kinds.remove(&kind);
+        }
+        self.try_save_settings();
+    }
+
+    pub fn set_sig_policy(&mut self, value: SigPolicy) {
+        self.get_settings_mut().sig_policy = value;
+        self.try_save_settings();
+    }
+
+    pub fn set_send_typing_indicators(&mut self, value: bool) {
+        self.get_settings_mut().send_typing_indicators = value;
+        self.try_save_settings();
+    }
+
+    pub fn set_action_bar(&mut self, items: Vec<ActionBarItem>) {
+        self.get_settings_mut().action_bar = items;
+        self.try_save_settings();
+    }
+
+    pub fn set_open_with_template(&mut self, template: String) {
+        self.get_settings_mut().open_with_template = template;
+        self.try_save_settings();
+    }
+
     pub fn set_animate_nav_transitions(&mut self, value: bool) {
         self.get_settings_mut().animate_nav_transitions = value;
         self.try_save_settings();
     }
 
+    pub fn set_max_preview_depth(&mut self, value: u8) {
+        self.get_settings_mut().max_preview_depth = value;
+        self.try_save_settings();
+    }
+
+    pub fn set_profile_refresh_hours(&mut self, value: u32) {
+        self.get_settings_mut().profile_refresh_hours = value;
+        self.try_save_settings();
+    }
+
     pub fn set_max_hashtags_per_note(&mut self, value: usize) {
         self.get_settings_mut().max_hashtags_per_note = value;
         self.try_save_settings();
@@ -270,6 +453,76 @@ impl SettingsHandler {
             .unwrap_or(DEFAULT_SHOW_REPLIES_NEWEST_FIRST)
     }
 
+    pub fn keep_media_metadata(&self) -> bool {
+        self.current_settings
+            .as_ref()
+            .map(|s| s.keep_media_metadata)
+            .unwrap_or(false)
+    }
+
+    pub fn hide_badges(&self) -> bool {
+        self.current_settings
+            .as_ref()
+            .map(|s| s.hide_badges)
+            .unwrap_or(false)
+    }
+
+    pub fn verify_publishes(&self) -> bool {
+        self.current_settings
+            .as_ref()
+            .map(|s| s.verify_publishes)
+            .unwrap_or(false)
+    }
+
+    pub fn os_keyring(&self) -> bool {
+        self.current_settings
+            .as_ref()
+            .map(|s| s.os_keyring)
+            .unwrap_or_else(default_os_keyring)
+    }
+
+    pub fn os_notification(&self, kind: OsNotificationKind) -> bool {
+        self.current_settings
+            .as_ref()
+            .is_none_or(|s| s.os_notifications.contains(&kind))
+    }
+
+    pub fn sig_policy(&self) -> SigPolicy {
+        self.current_settings
+            .as_ref()
+            .map(|s| s.sig_policy)
+            .unwrap_or_default()
+    }
+
+    pub fn send_typing_indicators(&self) -> bool {
+        self.current_settings
+            .as_ref()
+            .map(|s| s.send_typing_indicators)
+            .unwrap_or_else(default_send_typing_indicators)
+    }
+
+    pub fn action_bar(&self) -> Vec<ActionBarItem> {
+        self.current_settings
+            .as_ref()
+            .map(|s| s.action_bar.clone())
+            .unwrap_or_else(default_action_bar)
+    }
+
+    /// The open with link template, if one is set up
+    pub fn open_with_template(&self) -> Option<&str> {
+        self.current_settings
+            .as_ref()
+            .map(|s| s.open_with_template.as_str())
+            .filter(|template| crate::is_open_with_template(template))
+    }
+
+    pub fn max_preview_depth(&self) -> u8 {
+        self.current_settings
+            .as_ref()
+            .map(|s| s.max_preview_depth)
+            .unwrap_or(DEFAULT_MAX_PREVIEW_DEPTH)
+    }
+
     pub fn is_loaded(&self) -> bool {
         self.current_settings.is_some()
     }
@@ -281,6 +534,201 @@ impl SettingsHandler {
             .unwrap_or(DEFAULT_MAX_HASHTAGS_PER_NOTE)
     }
 
+    pub fn profile_refresh_hours(&self) -> u32 {
+        self.current_settings
+            .as_ref()
+            .map(|s| s.profile_refresh_hours)
+            .unwrap_or(DEFAULT_PROFILE_REFRESH_HOURS)
+    }
+
+    pub fn profile_max_age(&self) -> Duration {
+        Duration::from_secs(u64::from(self.profile_refresh_hours()) * 60 * 60)
+    }
+
+    pub fn zap_preferences(&self, account: &Pubkey) -> ZapPreferences {
+        self.current_settings
+            .as_ref()
+            .and_then(|s| s.zap_preferences.get(&account.hex()))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn set_zap_preferences(&mut self, account: &Pubkey, preferences: ZapPreferences) {
+        let zap_preferences = &mut self.get_settings_mut().zap_preferences;
+        if preferences == ZapPreferences::default() {
+            zap_preferences.remove(&account.hex());
+        } else {
+            zap_preferences.insert(account.hex(), preferences);
+        }
+        self.try_save_settings();
+    }
+
+    pub fn content_warning_mode(&self, account: &Pubkey) -> ContentWarningMode {
+        self.current_settings
+            .as_ref()
+            .and_then(|s| s.content_warning_modes.get(&account.hex()))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn set_content_warning_mode(&mut self, account: &Pubkey, mode: ContentWarningMode) {
+        let modes = &mut self.get_settings_mut().content_warning_modes;
+        if mode == ContentWarningMode::default() {
+            modes.remove(&account.hex());
+        } else {
+            modes.insert(account.hex(), mode);
+        }
+        self.try_save_settings();
+    }
+
+    pub fn upload_server(&self, account: &Pubkey) -> Option<&str> {
+        self.current_settings
+            .as_ref()
+            .and_then(|s| s.upload_servers.get(&account.hex()))
+            .map(String::as_str)
+    }
+
+    pub fn set_upload_server(&mut self, account: &Pubkey, server: Option<String>) {
+        let upload_servers = &mut self.get_settings_mut().upload_servers;
+        match server {
+            Some(server) => upload_servers.insert(account.hex(), server),
+            None => upload_servers.remove(&account.hex()),
+        };
+        self.try_save_settings();
+    }
+
+    pub fn nip96_server(&self, account: &Pubkey) -> Option<&str> {
+        self.current_settings
+            .as_ref()
+            .and_then(|s| s.nip96_servers.get(&account.hex()))
+            .map(String::as_str)
+    }
+
+    pub fn set_nip96_server(&mut self, account: &Pubkey, server: Option<String>) {
+        let nip96_servers = &mut self.get_settings_mut().nip96_servers;
+        match server {
+            Some(server) => nip96_servers.insert(account.hex(), server),
+            None => nip96_servers.remove(&account.hex()),
+        };
+        self.try_save_settings();
+    }
+
+    /// Users whose notifications `account` has muted, if there are any
+    pub fn notification_mutes(&self, account: &Pubkey) -> Option<&BTreeSet<Pubkey>> {
+        self.current_settings
+            .as_ref()
+            .and_then(|s| s.notification_mutes.get(&account.hex()))
+    }
+
+    pub fn is_notification_muted(&self, account: &Pubkey, user: &Pubkey) -> bool {
+        self.notification_mutes(account)
+            .is_some_and(|users| users.contains(user))
+    }
+
+    pub fn set_notification_muted(&mut self, account: &Pubkey, user: &Pubkey, muted: bool) {
+        let mutes = &mut self.get_settings_mut().notification_mutes;
+        if muted {
+            mutes.entry(account.hex()).or_default().insert(*user);
+        } else if let Some(users) = mutes.get_mut(&account.hex()) {
+            users.remove(user);
+            if users.is_empty() {
+                mutes.remove(&account.hex());
+            }
+        }
+        self.try_save_settings();
+    }
+
+    /// Mute modes `account` set on its mute entries, if there are any
+    pub fn mute_modes(&self, account: &Pubkey) -> Option<&BTreeMap<String, MuteMode>> {
+        self.current_settings
+            .as_ref()
+            .and_then(|s| s.mute_modes.get(&account.hex()))
+    }
+
+    pub fn set_mute_mode(&mut self, account: &Pubkey, entry: &MuteEntry, mode: MuteMode) {
+        let modes = &mut self.get_settings_mut().mute_modes;
+        if mode != MuteMode::default() {
+            modes
+                .entry(account.hex())
+                .or_default()
+                .insert(entry.key(), mode);
+        } else if let Some(entries) = modes.get_mut(&account.hex()) {
+            entries.remove(&entry.key());
+            if entries.is_empty() {
+                modes.remove(&account.hex());
+            }
+        }
+        self.try_save_settings();
+    }
+
+    pub fn relay_profiles(&self) -> &[RelayProfile] {
+        self.current_settings
+            .as_ref()
+            .map(|s| s.relay_profiles.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn active_relay_profile(&self) -> Option<&str> {
+        self.current_settings
+            .as_ref()
+            .and_then(|s| s.active_relay_profile.as_deref())
+    }
+
+    pub fn relay_profile(&self, name: &str) -> Option<&RelayProfile> {
+        self.relay_profiles().iter().find(|p| p.name == name)
+    }
+
+    /// Relays of the active relay profile
+    pub fn active_relay_profile_relays(&self) -> Option<BTreeSet<RelaySpec>> {
+        self.relay_profile(self.active_relay_profile()?)
+            .map(RelayProfile::relay_specs)
+    }
+
+    /// Add a relay profile, replacing any profile with the same name
+    pub fn save_relay_profile(&mut self, profile: RelayProfile) {
+        let profiles = &mut self.get_settings_mut().relay_profiles;
+        match profiles.iter_mut().find(|p| p.name == profile.name) {
+            Some(existing) => *existing = profile,
+            None => profiles.push(profile),
+        }
+        self.try_save_settings();
+    }
+
+    pub fn remove_relay_profile(&mut self, name: &str) {
+        let settings = self.get_settings_mut();
+        settings.relay_profiles.retain(|p| p.name != name);
+        if settings.active_relay_profile.as_deref() == Some(name) {
+            settings.active_relay_profile = None;
+        }
+        self.try_save_settings();
+    }
+
+    pub fn set_active_relay_profile(&mut self, name: Option<String>) {
+        self.get_settings_mut().active_relay_profile = name;
+        self.try_save_settings();
+    }
+
+    pub fn relay_policies(&self) -> &[RelayPolicy] {
+        self.current_settings
+            .as_ref()
+            .map(|s| s.relay_policies.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn relay_policy(&self, url: &str) -> Option<&RelayPolicy> {
+        self.relay_policies().iter().find(|p| p.url == url)
+    }
+
+    /// Replace the relay's policy. An unrestricted policy removes it.
+    pub fn set_relay_policy(&mut self, policy: RelayPolicy) {
+        let policies = &mut self.get_settings_mut().relay_policies;
+        policies.retain(|p| p.url != policy.url);
+        if !policy.is_unrestricted() {
+            policies.push(policy);
+        }
+        self.try_save_settings();
+    }
+
     pub fn welcome_completed(&self) -> bool {
         self.current_settings
             .as_ref()
@@ -3,6 +3,7 @@ use crate::{platform::file::SelectedMedia, Error};
 #[cfg(target_os = "android")]
 pub mod android;
 pub mod file;
+pub mod os_notifications;
 
 pub fn get_next_selected_file() -> Option<Result<SelectedMedia, Error>> {
     file::get_next_selected_file()
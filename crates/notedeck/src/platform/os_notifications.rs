@@ -0,0 +1,129 @@
+//! Notifications shown by the operating system while notedeck is in the
+//! background. Clicking one brings the app back to what it was about.
+
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum OsNotificationKind {
+    /// A mention of, or reply to, the selected account
+    Mention,
+    DirectMessage,
+}
+
+impl OsNotificationKind {
+    pub const ALL: [OsNotificationKind; 2] = [
+        OsNotificationKind::Mention,
+        OsNotificationKind::DirectMessage,
+    ];
+}
+
+pub struct OsNotifications {
+    clicked_tx: Sender<OsNotificationKind>,
+    clicked_rx: Receiver<OsNotificationKind>,
+}
+
+impl Default for OsNotifications {
+    fn default() -> Self {
+        let (clicked_tx, clicked_rx) = mpsc::channel();
+        Self {
+            clicked_tx,
+            clicked_rx,
+        }
+    }
+}
+
+impl OsNotifications {
+    /// Whether notifications would be shown now, which is only while the
+    /// window is known to be out of focus
+    pub fn in_background(egui_ctx: &egui::Context) -> bool {
+        egui_ctx.input(|i| i.viewport().focused) == Some(false)
+    }
+
+    /// Show a notification, unless the window has focus
+    pub fn show(
+        &self,
+        egui_ctx: &egui::Context,
+        kind: OsNotificationKind,
+        title: &str,
+        body: &str,
+    ) {
+        if !Self::in_background(egui_ctx) {
+            return;
+        }
+
+        show_native(egui_ctx, self.clicked_tx.clone(), kind, title, body);
+    }
+
+    /// A notification the user clicked on since the last call
+    pub fn take_clicked(&self) -> Option<OsNotificationKind> {
+        self.clicked_rx.try_recv().ok()
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn show_native(
+    egui_ctx: &egui::Context,
+    clicked: Sender<OsNotificationKind>,
+    kind: OsNotificationKind,
+    title: &str,
+    body: &str,
+) {
+    let notification = notify_rust::Notification::new()
+        .appname("Notedeck")
+        .summary(title)
+        .body(body)
+        .action("default", "Open")
+        .finalize();
+    let egui_ctx = egui_ctx.clone();
+
+    // waiting for the click blocks until the notification goes away
+    std::thread::spawn(move || {
+        let handle = match notification.show() {
+            Ok(handle) => handle,
+            Err(e) => {
+                tracing::warn!("could not show notification: {e}");
+                return;
+            }
+        };
+
+        handle.wait_for_action(|action| {
+            if action == "default" {
+                let _ = clicked.send(kind);
+                egui_ctx.request_repaint();
+            }
+        });
+    });
+}
+
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+fn show_native(
+    _egui_ctx: &egui::Context,
+    _clicked: Sender<OsNotificationKind>,
+    _kind: OsNotificationKind,
+    title: &str,
+    body: &str,
+) {
+    let notification = notify_rust::Notification::new()
+        .appname("Notedeck")
+        .summary(title)
+        .body(body)
+        .finalize();
+
+    std::thread::spawn(move || {
+        if let Err(e) = notification.show() {
+            tracing::warn!("could not show notification: {e}");
+        }
+    });
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn show_native(
+    _egui_ctx: &egui::Context,
+    _clicked: Sender<OsNotificationKind>,
+    _kind: OsNotificationKind,
+    _title: &str,
+    _body: &str,
+) {
+}
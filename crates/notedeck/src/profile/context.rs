@@ -1,11 +1,19 @@
 use enostr::Pubkey;
 
+use crate::{MuteMode, OpenWith};
+
 pub enum ProfileContextSelection {
     AddProfileColumn,
     CopyLink,
     ViewAs,
     MuteUser,
+    /// Change how the muted user's notes are muted
+    SetMuteMode(MuteMode),
+    /// Toggle whether the user's notes show up in our notifications
+    MuteNotifications,
     ReportUser,
+    /// Show the profile in another client
+    OpenWith(OpenWith),
 }
 
 pub struct ProfileContext {
@@ -14,7 +22,7 @@ pub struct ProfileContext {
 }
 
 impl ProfileContextSelection {
-    pub fn process(&self, ctx: &egui::Context, pk: &Pubkey) {
+    pub fn process(&self, ctx: &egui::Context, pk: &Pubkey, open_with_template: Option<&str>) {
         match self {
             ProfileContextSelection::CopyLink => {
                 let Some(npub) = pk.npub() else {
@@ -23,9 +31,16 @@ impl ProfileContextSelection {
 
                 ctx.copy_text(format!("https://damus.io/{npub}"));
             }
+            ProfileContextSelection::OpenWith(target) => {
+                if let Some(npub) = pk.npub() {
+                    target.open(ctx, &npub, open_with_template);
+                }
+            }
             ProfileContextSelection::ViewAs
             | ProfileContextSelection::AddProfileColumn
             | ProfileContextSelection::MuteUser
+            | ProfileContextSelection::SetMuteMode(_)
+            | ProfileContextSelection::MuteNotifications
             | ProfileContextSelection::ReportUser => {}
         }
     }
@@ -1,5 +1,7 @@
 mod context;
+mod stats;
 mod url;
 
 pub use context::{ProfileContext, ProfileContextSelection};
+pub use stats::{ProfileStats, ProfileStatsCache};
 pub use url::{get_profile_url, no_pfp_url, unwrap_profile_url};
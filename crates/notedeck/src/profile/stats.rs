@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::{Duration, Instant};
+
+use enostr::Pubkey;
+use nostrdb::{Filter, Ndb, NoteReply, Transaction};
+
+use crate::JobPool;
+
+/// How long computed stats are shown before they are recomputed.
+const PROFILE_STATS_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Upper bound on the number of text notes scanned per profile.
+const PROFILE_STATS_QUERY_LIMIT: i32 = 50_000;
+
+/// Lightweight activity stats for a profile, computed from notes stored
+/// locally. They only reflect what this client has seen.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ProfileStats {
+    /// Text notes stored locally, including replies
+    pub notes: u64,
+    /// How many of `notes` are replies
+    pub replies: u64,
+    /// Timestamp of the earliest local text note
+    pub first_seen: Option<u64>,
+    /// Timestamp of the newest local event of any kind
+    pub last_active: Option<u64>,
+}
+
+impl ProfileStats {
+    fn add_note(&mut self, created_at: u64, is_reply: bool) {
+        self.notes += 1;
+        if is_reply {
+            self.replies += 1;
+        }
+
+        self.first_seen = Some(self.first_seen.map_or(created_at, |t| t.min(created_at)));
+        self.bump_last_active(created_at);
+    }
+
+    fn bump_last_active(&mut self, created_at: u64) {
+        self.last_active = Some(self.last_active.map_or(created_at, |t| t.max(created_at)));
+    }
+
+    /// Query the local database. This walks every stored text note by the
+    /// author, so it should not be called from the UI thread.
+    pub fn compute(ndb: &Ndb, pubkey: &Pubkey) -> Option<Self> {
+        let txn = Transaction::new(ndb).ok()?;
+        let mut stats = ProfileStats::default();
+
+        let notes = Filter::new().authors([pubkey.bytes()]).kinds([1]).build();
+        let results = ndb.query(&txn, &[notes], PROFILE_STATS_QUERY_LIMIT).ok()?;
+        for result in results {
+            let reply = NoteReply::new(result.note.tags());
+            let is_reply = reply.root().is_some() || reply.reply().is_some();
+            stats.add_note(result.note.created_at(), is_reply);
+        }
+
+        let newest = Filter::new().authors([pubkey.bytes()]).limit(1).build();
+        if let Ok(results) = ndb.query(&txn, &[newest], 1) {
+            if let Some(result) = results.first() {
+                stats.bump_last_active(result.note.created_at());
+            }
+        }
+
+        Some(stats)
+    }
+}
+
+struct CacheEntry {
+    stats: Option<ProfileStats>,
+    requested_at: Instant,
+}
+
+struct Completion {
+    pubkey: Pubkey,
+    stats: Option<ProfileStats>,
+}
+
+/// Per-pubkey cache of [`ProfileStats`]. Queries run on the job pool and
+/// land here on the next [`ProfileStatsCache::poll`].
+pub struct ProfileStatsCache {
+    cache: HashMap<Pubkey, CacheEntry>,
+    /// Stale or missing stats waiting to be scheduled by the next poll
+    queued: Vec<Pubkey>,
+    tx: Sender<Completion>,
+    rx: Receiver<Completion>,
+}
+
+impl Default for ProfileStatsCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProfileStatsCache {
+    pub fn new() -> Self {
+        let (tx, rx) = mpsc::channel();
+        Self {
+            cache: HashMap::new(),
+            queued: Vec::new(),
+            tx,
+            rx,
+        }
+    }
+
+    /// The last computed stats for `pubkey`, queueing a (re)computation
+    /// when there are none yet or they have gone stale.
+    pub fn get(&mut self, pubkey: &Pubkey) -> Option<&ProfileStats> {
+        let stale = self
+            .cache
+            .get(pubkey)
+            .is_none_or(|entry| entry.requested_at.elapsed() >= PROFILE_STATS_TTL);
+
+        if stale {
+            self.request(*pubkey);
+        }
+
+        self.cache
+            .get(pubkey)
+            .and_then(|entry| entry.stats.as_ref())
+    }
+
+    fn request(&mut self, pubkey: Pubkey) {
+        // keep showing the previous stats while the new ones are computed
+        let stats = self.cache.get(&pubkey).and_then(|entry| entry.stats);
+        self.cache.insert(
+            pubkey,
+            CacheEntry {
+                stats,
+                requested_at: Instant::now(),
+            },
+        );
+        self.queued.push(pubkey);
+    }
+
+    /// Schedule the queued computations and take in the finished ones. A
+    /// finished computation asks for a repaint so its stats show up.
+    pub fn poll(&mut self, ndb: &Ndb, job_pool: &JobPool, ctx: &egui::Context) {
+        for pubkey in self.queued.drain(..) {
+            let tx = self.tx.clone();
+            let ndb = ndb.clone();
+            let ctx = ctx.clone();
+
+            job_pool.schedule_no_output(move || {
+                let stats = ProfileStats::compute(&ndb, &pubkey);
+                let _ = tx.send(Completion { pubkey, stats });
+                ctx.request_repaint();
+            });
+        }
+
+        while let Ok(completion) = self.rx.try_recv() {
+            if let Some(entry) = self.cache.get_mut(&completion.pubkey) {
+                entry.stats = completion.stats;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aggregates_notes_and_replies() {
+        let mut stats = ProfileStats::default();
+        assert_eq!(stats.first_seen, None);
+
+        stats.add_note(200, false);
+        stats.add_note(100, true);
+        stats.add_note(300, true);
+        stats.bump_last_active(250);
+
+        assert_eq!(stats.notes, 3);
+        assert_eq!(stats.replies, 2);
+        assert_eq!(stats.first_seen, Some(100));
+        assert_eq!(stats.last_active, Some(300));
+
+        stats.bump_last_active(400);
+        assert_eq!(stats.last_active, Some(400));
+    }
+}
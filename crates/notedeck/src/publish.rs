@@ -1,7 +1,7 @@
 use enostr::RelayId;
 use nostrdb::Note;
 
-use crate::{Accounts, Outbox};
+use crate::{Accounts, Outbox, RelayPolicies};
 
 /// Relay target policy for publishing.
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -15,15 +15,23 @@ pub enum RelayType {
 /// Explicit-relay publishing API that does not depend on account state.
 pub struct ExplicitPublishApi<'o, 'a> {
     pool: &'o mut Outbox<'a>,
+    relay_policies: &'o RelayPolicies,
 }
 
 impl<'o, 'a> ExplicitPublishApi<'o, 'a> {
-    pub fn new(pool: &'o mut Outbox<'a>) -> Self {
-        Self { pool }
+    pub fn new(pool: &'o mut Outbox<'a>, relay_policies: &'o RelayPolicies) -> Self {
+        Self {
+            pool,
+            relay_policies,
+        }
     }
 
-    /// Publish a note to an explicit relay target set.
+    /// Publish a note to an explicit relay target set, minus any relays
+    /// whose policy doesn't take the note.
     pub fn publish_note(&mut self, note: &Note, relays: Vec<RelayId>) {
+        let relays = self
+            .relay_policies
+            .publish_relays(relays, note.kind() as u64);
         self.pool.broadcast_note(note, relays);
     }
 }
@@ -32,17 +40,30 @@ impl<'o, 'a> ExplicitPublishApi<'o, 'a> {
 pub struct AccountsPublishApi<'o, 'a> {
     pool: &'o mut Outbox<'a>,
     accounts: &'o Accounts,
+    relay_policies: &'o RelayPolicies,
 }
 
 impl<'o, 'a> AccountsPublishApi<'o, 'a> {
-    pub fn new(pool: &'o mut Outbox<'a>, accounts: &'o Accounts) -> Self {
-        Self { pool, accounts }
+    pub fn new(
+        pool: &'o mut Outbox<'a>,
+        accounts: &'o Accounts,
+        relay_policies: &'o RelayPolicies,
+    ) -> Self {
+        Self {
+            pool,
+            accounts,
+            relay_policies,
+        }
     }
 
-    /// Publish a note to the selected account's write relay set.
+    /// Publish a note to the selected account's write relay set, minus any
+    /// relays whose policy doesn't take the note.
     pub fn publish_note(&mut self, note: &Note) {
-        self.pool
-            .broadcast_note(note, self.accounts.selected_account_write_relays());
+        let relays = self.relay_policies.publish_relays(
+            self.accounts.selected_account_write_relays(),
+            note.kind() as u64,
+        );
+        self.pool.broadcast_note(note, relays);
     }
 }
 
@@ -50,19 +71,28 @@ impl<'o, 'a> AccountsPublishApi<'o, 'a> {
 pub struct PublishApi<'o, 'a> {
     pool: &'o mut Outbox<'a>,
     accounts: &'o Accounts,
+    relay_policies: &'o RelayPolicies,
 }
 
 impl<'o, 'a> PublishApi<'o, 'a> {
-    pub fn new(pool: &'o mut Outbox<'a>, accounts: &'o Accounts) -> Self {
-        Self { pool, accounts }
+    pub fn new(
+        pool: &'o mut Outbox<'a>,
+        accounts: &'o Accounts,
+        relay_policies: &'o RelayPolicies,
+    ) -> Self {
+        Self {
+            pool,
+            accounts,
+            relay_policies,
+        }
     }
 
     pub fn explicit(&mut self) -> ExplicitPublishApi<'_, 'a> {
-        ExplicitPublishApi::new(self.pool)
+        ExplicitPublishApi::new(self.pool, self.relay_policies)
     }
 
     pub fn accounts_write(&mut self) -> AccountsPublishApi<'_, 'a> {
-        AccountsPublishApi::new(self.pool, self.accounts)
+        AccountsPublishApi::new(self.pool, self.accounts, self.relay_policies)
     }
 
     pub fn publish_note(&mut self, note: &Note, relays: RelayType) {
@@ -124,7 +154,7 @@ mod tests {
         {
             let mut outbox =
                 OutboxSessionHandler::new(&mut pool, EguiWakeup::new(egui::Context::default()));
-            let mut publish = PublishApi::new(&mut outbox, &accounts);
+            let mut publish = PublishApi::new(&mut outbox, &accounts, &RelayPolicies::default());
 
             publish.publish_note(
                 &note,
@@ -159,7 +189,7 @@ mod tests {
         {
             let mut outbox =
                 OutboxSessionHandler::new(&mut pool, EguiWakeup::new(egui::Context::default()));
-            let mut publish = PublishApi::new(&mut outbox, &accounts);
+            let mut publish = PublishApi::new(&mut outbox, &accounts, &RelayPolicies::default());
 
             publish.publish_note(&note, RelayType::AccountsWrite);
         }
@@ -171,4 +201,26 @@ mod tests {
             .collect();
         assert_eq!(actual_relays, expected_relays);
     }
+
+    /// Verifies account-write publishing skips relays whose policy doesn't take the note's kind.
+    #[test]
+    fn publish_note_skips_relays_excluded_by_policy() {
+        let (_tmp, accounts) = test_accounts_with_forced_relay("wss://relay-dm.example.com");
+        let note = signed_note();
+        let policies = RelayPolicies::new(&[crate::RelayPolicy {
+            kinds: [1059].into(),
+            ..crate::RelayPolicy::new("wss://relay-dm.example.com")
+        }]);
+
+        let mut pool = OutboxPool::default();
+        {
+            let mut outbox =
+                OutboxSessionHandler::new(&mut pool, EguiWakeup::new(egui::Context::default()));
+            let mut publish = PublishApi::new(&mut outbox, &accounts, &policies);
+
+            publish.publish_note(&note, RelayType::AccountsWrite);
+        }
+
+        assert!(pool.websocket_statuses().is_empty());
+    }
 }
@@ -26,7 +26,14 @@ pub enum RelayLimitJobKind {
 #[derive(Debug, Clone)]
 pub struct RelayLimitJobResult {
     pub relay: NormRelayUrl,
-    pub result: Result<Nip11LimitationsRaw, Nip11FetchError>,
+    pub result: Result<Nip11Info, Nip11FetchError>,
+}
+
+/// The parts of a relay's NIP-11 document we act on.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Nip11Info {
+    pub limitation: Nip11LimitationsRaw,
+    pub supported_nips: Vec<u32>,
 }
 
 /// Errors while downloading or parsing relay NIP-11 documents.
@@ -49,6 +56,29 @@ pub enum Nip11FetchError {
 #[derive(Debug, Deserialize)]
 struct Nip11Document {
     limitation: Option<Nip11LimitationsRaw>,
+    /// Kept loose since some relays list NIPs as strings
+    #[serde(default)]
+    supported_nips: Vec<serde_json::Value>,
+}
+
+impl Nip11Document {
+    fn into_info(self) -> Nip11Info {
+        let supported_nips = self
+            .supported_nips
+            .iter()
+            .filter_map(|nip| match nip {
+                serde_json::Value::Number(n) => n.as_u64(),
+                serde_json::Value::String(s) => s.trim().parse().ok(),
+                _ => None,
+            })
+            .filter_map(|nip| u32::try_from(nip).ok())
+            .collect();
+
+        Nip11Info {
+            limitation: self.limitation.unwrap_or_default(),
+            supported_nips,
+        }
+    }
 }
 
 /// Queue a NIP-11 fetch job for the provided relay request.
@@ -56,7 +86,7 @@ pub fn enqueue_nip11_fetch(sender: &RelayLimitJobSender, req: Nip11FetchRequest)
     let id = req.relay.to_string();
     let relay = req.relay;
     let run = JobRun::Async(Box::pin(async move {
-        let result = fetch_nip11_raw_limits(&relay).await;
+        let result = fetch_nip11_info(&relay).await;
         JobOutput::complete(RelayLimitJobResult { relay, result })
     }));
 
@@ -69,9 +99,7 @@ pub fn enqueue_nip11_fetch(sender: &RelayLimitJobSender, req: Nip11FetchRequest)
     }
 }
 
-async fn fetch_nip11_raw_limits(
-    relay: &NormRelayUrl,
-) -> Result<Nip11LimitationsRaw, Nip11FetchError> {
+async fn fetch_nip11_info(relay: &NormRelayUrl) -> Result<Nip11Info, Nip11FetchError> {
     let http_url = relay_url_to_http(relay)?;
     let response = tokio::time::timeout(
         NIP11_FETCH_TIMEOUT,
@@ -85,7 +113,7 @@ async fn fetch_nip11_raw_limits(
     }
     let parsed: Nip11Document = serde_json::from_slice(&response.bytes)
         .map_err(|error| Nip11FetchError::Json(error.to_string()))?;
-    Ok(parsed.limitation.unwrap_or_default())
+    Ok(parsed.into_info())
 }
 
 fn relay_url_to_http(relay: &NormRelayUrl) -> Result<String, Nip11FetchError> {
@@ -195,7 +223,7 @@ mod tests {
 
     /// Ensures non-2xx NIP-11 HTTP responses map to a dedicated `HttpStatus` error.
     #[tokio::test(flavor = "multi_thread")]
-    async fn fetch_nip11_raw_limits_maps_non_success_status_to_http_status_error() {
+    async fn fetch_nip11_info_maps_non_success_status_to_http_status_error() {
         install_crypto_provider_for_tests();
         let (addr, server) = spawn_one_shot_http_server(
             "503 Service Unavailable",
@@ -206,7 +234,7 @@ mod tests {
         .await;
         let relay = NormRelayUrl::new(&format!("ws://{addr}")).expect("valid relay");
 
-        let result = fetch_nip11_raw_limits(&relay).await;
+        let result = fetch_nip11_info(&relay).await;
         server.await.expect("server finished");
 
         assert!(matches!(result, Err(Nip11FetchError::HttpStatus(503))));
@@ -214,7 +242,7 @@ mod tests {
 
     /// Ensures a valid 2xx NIP-11 document returns parsed raw limitation fields.
     #[tokio::test(flavor = "multi_thread")]
-    async fn fetch_nip11_raw_limits_parses_limitation_fields_on_success() {
+    async fn fetch_nip11_info_parses_limitation_fields_on_success() {
         install_crypto_provider_for_tests();
         let (addr, server) = spawn_one_shot_http_server(
             "200 OK",
@@ -225,17 +253,27 @@ mod tests {
         .await;
         let relay = NormRelayUrl::new(&format!("ws://{addr}")).expect("valid relay");
 
-        let result = fetch_nip11_raw_limits(&relay).await;
+        let result = fetch_nip11_info(&relay).await;
         server.await.expect("server finished");
 
-        let raw = result.expect("successful parse");
+        let raw = result.expect("successful parse").limitation;
         assert_eq!(raw.max_message_length, Some(16384));
         assert_eq!(raw.max_subscriptions, Some(300));
     }
 
+    /// Ensures advertised NIPs are read whether relays list them as numbers or strings.
+    #[test]
+    fn supported_nips_accept_numbers_and_strings() {
+        let doc: Nip11Document =
+            serde_json::from_str(r#"{"supported_nips":[1,"11"," 50 ",null,-2]}"#)
+                .expect("valid document");
+
+        assert_eq!(doc.into_info().supported_nips, vec![1, 11, 50]);
+    }
+
     /// Ensures invalid JSON bodies on 2xx responses map to `Json` parse errors.
     #[tokio::test(flavor = "multi_thread")]
-    async fn fetch_nip11_raw_limits_maps_invalid_json_to_json_error() {
+    async fn fetch_nip11_info_maps_invalid_json_to_json_error() {
         install_crypto_provider_for_tests();
         let (addr, server) = spawn_one_shot_http_server(
             "200 OK",
@@ -246,7 +284,7 @@ mod tests {
         .await;
         let relay = NormRelayUrl::new(&format!("ws://{addr}")).expect("valid relay");
 
-        let result = fetch_nip11_raw_limits(&relay).await;
+        let result = fetch_nip11_info(&relay).await;
         server.await.expect("server finished");
 
         assert!(matches!(result, Err(Nip11FetchError::Json(_))));
@@ -254,7 +292,7 @@ mod tests {
 
     /// Ensures NIP-11 responses without a `limitation` object produce default raw limits.
     #[tokio::test(flavor = "multi_thread")]
-    async fn fetch_nip11_raw_limits_defaults_when_limitation_is_missing() {
+    async fn fetch_nip11_info_defaults_when_limitation_is_missing() {
         install_crypto_provider_for_tests();
         let (addr, server) = spawn_one_shot_http_server(
             "200 OK",
@@ -265,16 +303,16 @@ mod tests {
         .await;
         let relay = NormRelayUrl::new(&format!("ws://{addr}")).expect("valid relay");
 
-        let result = fetch_nip11_raw_limits(&relay).await;
+        let result = fetch_nip11_info(&relay).await;
         server.await.expect("server finished");
 
-        let raw = result.expect("successful parse");
-        assert_eq!(raw, Nip11LimitationsRaw::default());
+        let info = result.expect("successful parse");
+        assert_eq!(info, Nip11Info::default());
     }
 
     /// Ensures transport-level connection failures map to the `Http` fetch error variant.
     #[tokio::test(flavor = "multi_thread")]
-    async fn fetch_nip11_raw_limits_maps_transport_failure_to_http_error() {
+    async fn fetch_nip11_info_maps_transport_failure_to_http_error() {
         install_crypto_provider_for_tests();
 
         let listener = TcpListener::bind("127.0.0.1:0")
@@ -284,7 +322,7 @@ mod tests {
         drop(listener);
 
         let relay = NormRelayUrl::new(&format!("ws://{addr}")).expect("valid relay");
-        let result = fetch_nip11_raw_limits(&relay).await;
+        let result = fetch_nip11_info(&relay).await;
         assert!(matches!(result, Err(Nip11FetchError::Http(_))));
     }
 }
@@ -0,0 +1,210 @@
+use std::collections::BTreeSet;
+
+use enostr::{NormRelayUrl, RelayId};
+use hashbrown::{HashMap, HashSet};
+use nostrdb::Filter;
+use serde::{Deserialize, Serialize};
+
+/// Limits on the traffic a relay is used for, e.g. a DM relay that should
+/// only see gift wraps, or a paid relay we only publish to. Policies are
+/// stored in the settings and apply to every account.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RelayPolicy {
+    pub url: String,
+    /// Open subscriptions on the relay
+    pub subscribe: bool,
+    /// Send our notes to the relay
+    pub publish: bool,
+    /// Only use the relay for these kinds, any kind when empty
+    pub kinds: BTreeSet<u64>,
+}
+
+impl Default for RelayPolicy {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            subscribe: true,
+            publish: true,
+            kinds: BTreeSet::new(),
+        }
+    }
+}
+
+impl RelayPolicy {
+    /// A policy that doesn't restrict `url` yet
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn is_unrestricted(&self) -> bool {
+        self.subscribe && self.publish && self.kinds.is_empty()
+    }
+
+    /// A subscription may only use a kind-limited relay when every one of
+    /// its filters asks for allowed kinds alone
+    pub fn allows_filters(&self, filters: &[Filter]) -> bool {
+        if !self.subscribe {
+            return false;
+        }
+
+        if self.kinds.is_empty() {
+            return true;
+        }
+
+        filters.iter().all(|filter| {
+            filter_kinds(filter).is_some_and(|kinds| {
+                !kinds.is_empty() && kinds.iter().all(|kind| self.kinds.contains(kind))
+            })
+        })
+    }
+
+    pub fn allows_publish(&self, kind: u64) -> bool {
+        self.publish && (self.kinds.is_empty() || self.kinds.contains(&kind))
+    }
+}
+
+/// Parse a kind list typed by the user, e.g. `"1059, 10050"`. `None` when
+/// an entry isn't a kind number.
+pub fn parse_kinds(input: &str) -> Option<BTreeSet<u64>> {
+    input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|kind| !kind.is_empty())
+        .map(|kind| kind.parse().ok())
+        .collect()
+}
+
+/// The relay policies in effect, applied when subscriptions resolve their
+/// relays and when notes are published.
+#[derive(Debug, Clone, Default)]
+pub struct RelayPolicies {
+    by_relay: HashMap<NormRelayUrl, RelayPolicy>,
+}
+
+impl RelayPolicies {
+    pub fn new(policies: &[RelayPolicy]) -> Self {
+        let by_relay = policies
+            .iter()
+            .filter(|policy| !policy.is_unrestricted())
+            .filter_map(|policy| Some((NormRelayUrl::new(&policy.url).ok()?, policy.clone())))
+            .collect();
+
+        Self { by_relay }
+    }
+
+    pub fn get(&self, relay: &NormRelayUrl) -> Option<&RelayPolicy> {
+        self.by_relay.get(relay)
+    }
+
+    /// Drop the relays a subscription with `filters` may not use
+    pub fn subscribe_relays(
+        &self,
+        mut relays: HashSet<NormRelayUrl>,
+        filters: &[Filter],
+    ) -> HashSet<NormRelayUrl> {
+        if !self.by_relay.is_empty() {
+            relays.retain(|relay| {
+                self.get(relay)
+                    .is_none_or(|policy| policy.allows_filters(filters))
+            });
+        }
+        relays
+    }
+
+    /// Drop the relays a note of `kind` may not be published to
+    pub fn publish_relays(&self, mut relays: Vec<RelayId>, kind: u64) -> Vec<RelayId> {
+        relays.retain(|relay| match relay {
+            RelayId::Websocket(url) => self
+                .get(url)
+                .is_none_or(|policy| policy.allows_publish(kind)),
+            RelayId::Multicast => true,
+        });
+        relays
+    }
+}
+
+fn filter_kinds(filter: &Filter) -> Option<Vec<u64>> {
+    let json = filter.json().ok()?;
+    let value = serde_json::from_str::<serde_json::Value>(&json).ok()?;
+    let kinds = value.get("kinds")?.as_array()?;
+    Some(kinds.iter().filter_map(|kind| kind.as_u64()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DM_RELAY: &str = "wss://dm.example.com";
+    const PAID_RELAY: &str = "wss://paid.example.com";
+    const OPEN_RELAY: &str = "wss://open.example.com";
+
+    fn relay(url: &str) -> NormRelayUrl {
+        NormRelayUrl::new(url).expect("relay")
+    }
+
+    fn policies() -> RelayPolicies {
+        RelayPolicies::new(&[
+            RelayPolicy {
+                kinds: [1059].into(),
+                ..RelayPolicy::new(DM_RELAY)
+            },
+            RelayPolicy {
+                subscribe: false,
+                ..RelayPolicy::new(PAID_RELAY)
+            },
+            RelayPolicy::new(OPEN_RELAY),
+        ])
+    }
+
+    fn all_relays() -> HashSet<NormRelayUrl> {
+        [DM_RELAY, PAID_RELAY, OPEN_RELAY]
+            .into_iter()
+            .map(relay)
+            .collect()
+    }
+
+    #[test]
+    fn subscriptions_skip_disallowed_relays() {
+        let policies = policies();
+
+        let wraps = [Filter::new().kinds([1059]).build()];
+        let expected: HashSet<NormRelayUrl> =
+            [DM_RELAY, OPEN_RELAY].into_iter().map(relay).collect();
+        assert_eq!(policies.subscribe_relays(all_relays(), &wraps), expected);
+
+        // no kinds means any kind, which a kind-limited relay can't take
+        let ids = [Filter::new().ids([&[0; 32]]).build()];
+        let expected: HashSet<NormRelayUrl> = [OPEN_RELAY].into_iter().map(relay).collect();
+        assert_eq!(policies.subscribe_relays(all_relays(), &ids), expected);
+    }
+
+    #[test]
+    fn publishes_skip_disallowed_relays() {
+        let policies = policies();
+        let relays: Vec<RelayId> = [DM_RELAY, PAID_RELAY, OPEN_RELAY]
+            .into_iter()
+            .map(|url| RelayId::Websocket(relay(url)))
+            .chain([RelayId::Multicast])
+            .collect();
+
+        assert_eq!(
+            policies.publish_relays(relays.clone(), 1),
+            vec![
+                RelayId::Websocket(relay(PAID_RELAY)),
+                RelayId::Websocket(relay(OPEN_RELAY)),
+                RelayId::Multicast,
+            ]
+        );
+        assert_eq!(policies.publish_relays(relays.clone(), 1059), relays);
+    }
+
+    #[test]
+    fn kind_lists() {
+        assert_eq!(parse_kinds("1059, 10050"), Some([1059, 10050].into()));
+        assert_eq!(parse_kinds(""), Some(BTreeSet::new()));
+        assert_eq!(parse_kinds("1059, dms"), None);
+    }
+}
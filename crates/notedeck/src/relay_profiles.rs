@@ -0,0 +1,100 @@
+use std::collections::BTreeSet;
+
+use enostr::{NormRelayUrl, RelayId};
+use hashbrown::HashSet;
+use serde::{Deserialize, Serialize};
+
+use crate::RelaySpec;
+
+/// A named set of relays (e.g. "Home", "Travel") that replaces the
+/// account relay lists while it is active. Profiles are stored in the
+/// settings and are never published.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelayProfile {
+    pub name: String,
+    pub relays: Vec<RelayProfileEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelayProfileEntry {
+    pub url: String,
+    pub read: bool,
+    pub write: bool,
+}
+
+impl RelayProfile {
+    /// Capture the relays currently in use
+    pub fn snapshot(
+        name: impl Into<String>,
+        read: &HashSet<NormRelayUrl>,
+        write: &[RelayId],
+    ) -> Self {
+        let write: BTreeSet<String> = write
+            .iter()
+            .filter_map(|relay| match relay {
+                RelayId::Websocket(url) => Some(url.to_string()),
+                RelayId::Multicast => None,
+            })
+            .collect();
+        let read: BTreeSet<String> = read.iter().map(|url| url.to_string()).collect();
+
+        let relays = read
+            .union(&write)
+            .map(|url| RelayProfileEntry {
+                url: url.clone(),
+                read: read.contains(url),
+                write: write.contains(url),
+            })
+            .collect();
+
+        Self {
+            name: name.into(),
+            relays,
+        }
+    }
+
+    pub fn relay_specs(&self) -> BTreeSet<RelaySpec> {
+        self.relays
+            .iter()
+            .filter(|entry| entry.read || entry.write)
+            .filter_map(|entry| {
+                let url = NormRelayUrl::new(&entry.url).ok()?;
+                // a relay with neither marker is used for both
+                Some(RelaySpec::new(
+                    url,
+                    entry.read && !entry.write,
+                    entry.write && !entry.read,
+                ))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_keeps_read_and_write_roles() {
+        let both = NormRelayUrl::new("wss://relay.damus.io").unwrap();
+        let read_only = NormRelayUrl::new("wss://nos.lol").unwrap();
+        let write_only = NormRelayUrl::new("wss://nostr.wine").unwrap();
+
+        let read: HashSet<NormRelayUrl> = [both.clone(), read_only.clone()].into_iter().collect();
+        let write = vec![
+            RelayId::Websocket(both.clone()),
+            RelayId::Websocket(write_only.clone()),
+            RelayId::Multicast,
+        ];
+
+        let profile = RelayProfile::snapshot("Home", &read, &write);
+        assert_eq!(profile.relays.len(), 3);
+
+        let specs = profile.relay_specs();
+        let spec = |url: &NormRelayUrl| specs.iter().find(|s| s.url == *url).unwrap().clone();
+
+        assert!(spec(&both).is_readable() && spec(&both).is_writable());
+        assert!(spec(&read_only).is_readable() && !spec(&read_only).is_writable());
+        assert!(!spec(&write_only).is_readable() && spec(&write_only).is_writable());
+    }
+}
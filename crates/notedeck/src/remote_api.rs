@@ -1,9 +1,15 @@
 use egui::Context;
-use enostr::{NormRelayUrl, OutboxSession, Pubkey, RelayImplType, RelayStatus};
+use std::time::SystemTime;
+
+use enostr::{
+    NormRelayUrl, NoteId, OutboxSession, Pubkey, PublishAck, RelayImplType, RelayIncident,
+    RelayIncidentKind, RelayStatus,
+};
 use nostrdb::Ndb;
 
 use crate::{
-    Accounts, ExplicitPublishApi, OneshotApi, Outbox, PublishApi, ScopedSubApi, ScopedSubsState,
+    sig_policy::bad_event_signature, Accounts, ExplicitPublishApi, OneshotApi, Outbox, PublishApi,
+    RelayPolicies, ScopedSubApi, ScopedSubsState, SigPolicy,
 };
 
 /// Read-only relay inspection row for relay UI surfaces.
@@ -11,6 +17,14 @@ use crate::{
 pub struct RelayInspectEntry<'a> {
     pub relay_url: &'a NormRelayUrl,
     pub status: RelayStatus,
+    /// Incidents recorded for this relay in the last few minutes
+    pub recent_incidents: usize,
+    /// The relay has misbehaved enough that we should offer to block it
+    pub needs_review: bool,
+    /// Oversized or unrequested events dropped from this relay this session
+    pub rejected_events: u64,
+    /// Events with bad signatures dropped from this relay this session
+    pub invalid_signatures: u64,
 }
 
 /// Read-only relay inspection facade.
@@ -28,6 +42,8 @@ impl<'r, 'a> RelayInspectApi<'r, 'a> {
 
     /// Snapshot websocket relay statuses for display/debug UI.
     pub fn relay_infos(&self) -> Vec<RelayInspectEntry<'_>> {
+        let now = SystemTime::now();
+        let health = self.pool.outbox.health();
         self.pool
             .outbox
             .websocket_statuses()
@@ -35,9 +51,36 @@ impl<'r, 'a> RelayInspectApi<'r, 'a> {
             .map(|(url, status)| RelayInspectEntry {
                 relay_url: url,
                 status,
+                recent_incidents: health.recent_count(url, now),
+                needs_review: health.needs_review(url, now),
+                rejected_events: health.rejected_events(url),
+                invalid_signatures: health.invalid_signatures(url),
             })
             .collect()
     }
+
+    /// Incident log for one relay, oldest first.
+    pub fn incidents(&self, relay: &NormRelayUrl) -> Vec<&RelayIncident> {
+        self.pool.outbox.health().incidents(relay).collect()
+    }
+
+    /// Relays that advertise support for `nip` in their NIP-11 document,
+    /// sorted by url.
+    pub fn relays_supporting(&self, nip: u32) -> Vec<&NormRelayUrl> {
+        self.pool.outbox.relays_supporting(nip)
+    }
+
+    /// What relays answered so far to a note we published, in arrival order.
+    pub fn publish_acks(&self, note_id: &NoteId) -> &'r [(NormRelayUrl, PublishAck)] {
+        self.pool.outbox.publish_acks().get(note_id)
+    }
+
+    /// Relays blacklisted for the current session, sorted by url.
+    pub fn blocked_relays(&self) -> Vec<&NormRelayUrl> {
+        let mut blocked: Vec<&NormRelayUrl> = self.pool.outbox.health().blocked().collect();
+        blocked.sort();
+        blocked
+    }
 }
 
 /// App-facing facade for relay/outbox transport operations.
@@ -77,17 +120,55 @@ impl<'a> RemoteApi<'a> {
 
     /// Access publishing APIs bound to the selected account.
     pub fn publisher<'o>(&'o mut self, accounts: &'o Accounts) -> PublishApi<'o, 'a> {
-        PublishApi::new(&mut self.pool, accounts)
+        PublishApi::new(
+            &mut self.pool,
+            accounts,
+            self.scoped_sub_state.relay_policies(),
+        )
     }
 
     /// Access explicit-relay publishing APIs (no account dependency).
     pub fn publisher_explicit<'o>(&'o mut self) -> ExplicitPublishApi<'o, 'a> {
-        ExplicitPublishApi::new(&mut self.pool)
+        ExplicitPublishApi::new(&mut self.pool, self.scoped_sub_state.relay_policies())
+    }
+
+    /// Relay policies applied to subscriptions and publishes.
+    pub fn relay_policies(&self) -> &RelayPolicies {
+        self.scoped_sub_state.relay_policies()
+    }
+
+    /// Replace the relay policies and move live subscriptions onto the
+    /// relays they now allow.
+    pub fn set_relay_policies(&mut self, accounts: &Accounts, relay_policies: RelayPolicies) {
+        let account_read_relays = accounts.selected_account_read_relays();
+        self.scoped_sub_state.runtime_mut().set_relay_policies(
+            &mut self.pool,
+            &account_read_relays,
+            relay_policies,
+        );
     }
 
     /// Host-only relay ingestion + keepalive maintenance.
-    pub(crate) fn process_events(&mut self, ctx: &Context, ndb: &Ndb) {
-        try_process_events(ctx, &mut self.pool, ndb);
+    pub(crate) fn process_events(&mut self, ctx: &Context, ndb: &Ndb, sig_policy: SigPolicy) {
+        self.scoped_sub_state.runtime_mut().track_eose(&self.pool);
+        try_process_events(ctx, &mut self.pool, ndb, sig_policy);
+    }
+
+    /// Host-only: retarget outbox subscriptions after new relay lists land.
+    pub(crate) fn refresh_outbox_relays(&mut self, ndb: &Ndb, accounts: &Accounts) {
+        self.scoped_sub_state
+            .refresh_outbox_relays(&mut self.pool, ndb, accounts);
+    }
+
+    /// Blacklist a relay for this session. It is disconnected and skipped by
+    /// all subscriptions and publishes until unblocked.
+    pub fn block_relay(&mut self, relay: &NormRelayUrl) {
+        self.pool.block_relay(relay);
+    }
+
+    /// Lift a session blacklist entry and resubscribe on the relay.
+    pub fn unblock_relay(&mut self, relay: &NormRelayUrl) {
+        self.pool.unblock_relay(relay);
     }
 
     /// Access read-only relay inspection data for UI rendering.
@@ -123,7 +204,7 @@ impl<'a> RemoteApi<'a> {
 }
 
 #[profiling::function]
-pub fn try_process_events(ctx: &Context, pool: &mut Outbox, ndb: &Ndb) {
+pub fn try_process_events(ctx: &Context, pool: &mut Outbox, ndb: &Ndb, sig_policy: SigPolicy) {
     let ctx2 = ctx.clone();
     let wakeup = move || {
         ctx2.request_repaint();
@@ -131,12 +212,25 @@ pub fn try_process_events(ctx: &Context, pool: &mut Outbox, ndb: &Ndb) {
 
     pool.outbox.keepalive_ping(wakeup);
 
+    let mut failed_ingests: Vec<(String, String)> = Vec::new();
+    let mut bad_signatures: Vec<(String, String)> = Vec::new();
     pool.outbox.try_recv(100, |ev| {
         let from_client = match ev.relay_type {
             RelayImplType::Websocket => false,
             enostr::RelayImplType::Multicast => true,
         };
 
+        if sig_policy != SigPolicy::TrustNdb {
+            profiling::scope!("verify event signature");
+            if let Some(err) = bad_event_signature(ev.event_json, ev.kind, sig_policy) {
+                tracing::warn!("dropping event with a bad signature from {}: {err}", ev.url);
+                if !from_client {
+                    bad_signatures.push((ev.url.to_owned(), err));
+                }
+                return;
+            }
+        }
+
         {
             profiling::scope!("ndb process event");
             if let Err(err) = ndb.process_event_with(
@@ -146,7 +240,29 @@ pub fn try_process_events(ctx: &Context, pool: &mut Outbox, ndb: &Ndb) {
                     .relay(ev.url),
             ) {
                 tracing::error!("error processing event {}: {err}", ev.event_json);
+                if matches!(ev.relay_type, RelayImplType::Websocket) {
+                    failed_ingests.push((ev.url.to_owned(), err.to_string()));
+                }
             }
         }
     });
+
+    if let Some(wait) = pool.outbox.next_req_release() {
+        ctx.request_repaint_after(wait);
+    }
+
+    let incidents = failed_ingests
+        .into_iter()
+        .map(|(url, err)| (url, RelayIncidentKind::InvalidEvent, err))
+        .chain(
+            bad_signatures
+                .into_iter()
+                .map(|(url, err)| (url, RelayIncidentKind::InvalidSignature, err)),
+        );
+    for (url, kind, err) in incidents {
+        let Ok(relay) = NormRelayUrl::new(&url) else {
+            continue;
+        };
+        pool.outbox.record_relay_incident(&relay, kind, Some(err));
+    }
 }
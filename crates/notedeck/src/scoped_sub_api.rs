@@ -3,8 +3,8 @@ use enostr::Pubkey;
 use crate::scoped_sub_owners::ScopedSubOwners;
 use crate::scoped_subs::ScopedSubRuntime;
 use crate::{
-    Accounts, ClearSubResult, EnsureSubResult, Outbox, ScopedSubEoseStatus, ScopedSubIdentity,
-    SetSubResult, SubConfig, SubOwnerKey,
+    Accounts, ClearSubResult, EnsureSubResult, Outbox, ScopedSubDiagnostics, ScopedSubEoseStatus,
+    ScopedSubIdentity, ScopedSubInspect, SetSubResult, SubConfig, SubOwnerKey,
 };
 
 /// App-facing facade over scoped subscription owner/runtime operations.
@@ -124,6 +124,14 @@ impl<'o, 'a> ScopedSubApi<'o, 'a> {
             .clear_sub_for_account(self.runtime, self.pool, account_pubkey, identity)
     }
 
+    /// Drop every account-scoped declaration for `account_pubkey`, across all owners.
+    ///
+    /// Host cleanup for a deleted account. Owners don't have to clear their
+    /// bindings for it first.
+    pub(crate) fn forget_account(&mut self, account_pubkey: Pubkey) -> usize {
+        self.runtime.forget_account(self.pool, account_pubkey)
+    }
+
     /// Query aggregate EOSE state for one scoped subscription declaration.
     ///
     /// Thread example:
@@ -142,6 +150,21 @@ impl<'o, 'a> ScopedSubApi<'o, 'a> {
             .sub_eose_status(self.runtime, self.pool, self.accounts, identity)
     }
 
+    /// Snapshot the declared filters and per-relay request state of one scoped
+    /// subscription, for developer tooling. Returns `None` if `identity` isn't declared.
+    pub fn inspect_sub(&self, identity: ScopedSubIdentity) -> Option<ScopedSubInspect> {
+        self.owners
+            .inspect_sub(self.runtime, self.pool, self.accounts, identity)
+    }
+
+    /// Diagnostics for every declared scoped subscription across all owners and
+    /// accounts, oldest first, for developer tooling.
+    ///
+    /// EOSE latencies are only measured while this keeps being called.
+    pub fn subscriptions(&mut self) -> Vec<ScopedSubDiagnostics> {
+        self.runtime.subscriptions(self.pool)
+    }
+
     /// Drop one owner lifecycle and release all scoped subscriptions declared by it.
     ///
     /// Thread example:
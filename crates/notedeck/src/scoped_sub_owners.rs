@@ -4,7 +4,7 @@ use hashbrown::HashMap;
 use crate::{
     scoped_subs::{ScopedSubRuntime, SubOwnerKey, SubSlotId},
     Accounts, ClearSubResult, EnsureSubResult, Outbox, ScopedSubEoseStatus, ScopedSubIdentity,
-    SetSubResult, SubConfig, SubKeyBuilder, SubScope,
+    ScopedSubInspect, SetSubResult, SubConfig, SubKeyBuilder, SubScope,
 };
 
 /// Incremental builder for stable owner keys.
@@ -107,6 +107,18 @@ impl ScopedSubOwners {
         runtime.sub_eose_status(pool, accounts, slot, identity.key, identity.scope)
     }
 
+    /// Snapshot one scoped subscription binding owned by `owner`.
+    pub fn inspect_sub(
+        &self,
+        runtime: &ScopedSubRuntime,
+        pool: &Outbox<'_>,
+        accounts: &Accounts,
+        identity: ScopedSubIdentity,
+    ) -> Option<ScopedSubInspect> {
+        let slot = self.slots_by_owner.get(&identity.owner).copied()?;
+        runtime.inspect_sub(pool, accounts, slot, identity.key, identity.scope)
+    }
+
     /// Drop one owner lifecycle and release all its scoped subscriptions.
     pub fn drop_owner(
         &mut self,
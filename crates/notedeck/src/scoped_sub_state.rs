@@ -1,6 +1,9 @@
 use crate::scoped_sub_owners::ScopedSubOwners;
 use crate::scoped_subs::ScopedSubRuntime;
-use crate::{Accounts, Outbox, ScopedSubApi};
+use crate::{Accounts, Outbox, RelayPolicies, ScopedSubApi};
+use enostr::Pubkey;
+use hashbrown::HashSet;
+use nostrdb::{Filter, Ndb, Subscription, Transaction};
 
 /// Host-owned scoped subscription state.
 ///
@@ -10,9 +13,23 @@ use crate::{Accounts, Outbox, ScopedSubApi};
 pub struct ScopedSubsState {
     runtime: ScopedSubRuntime,
     owners: ScopedSubOwners,
+    /// Local subscription to NIP-65 relay lists, opened on first use
+    relay_lists: Option<Subscription>,
 }
 
 impl ScopedSubsState {
+    /// Start with relay policies in place, before any subscriptions exist
+    pub(crate) fn with_relay_policies(self, relay_policies: RelayPolicies) -> Self {
+        Self {
+            runtime: self.runtime.with_relay_policies(relay_policies),
+            ..self
+        }
+    }
+
+    pub(crate) fn relay_policies(&self) -> &RelayPolicies {
+        self.runtime.relay_policies()
+    }
+
     /// Borrow owner/runtime internals for legacy callsites that still expect
     /// both references separately.
     pub(crate) fn split_mut(&mut self) -> (&mut ScopedSubOwners, &mut ScopedSubRuntime) {
@@ -33,4 +50,47 @@ impl ScopedSubsState {
     pub(crate) fn runtime_mut(&mut self) -> &mut ScopedSubRuntime {
         &mut self.runtime
     }
+
+    /// Move outbox subscriptions onto the write relays of any relay lists
+    /// ingested since the last call.
+    pub(crate) fn refresh_outbox_relays(
+        &mut self,
+        pool: &mut Outbox<'_>,
+        ndb: &Ndb,
+        accounts: &Accounts,
+    ) {
+        let sub = match self.relay_lists {
+            Some(sub) => sub,
+            None => match ndb.subscribe(&[Filter::new().kinds([10002]).build()]) {
+                Ok(sub) => *self.relay_lists.insert(sub),
+                Err(e) => {
+                    tracing::error!("relay list subscription failed: {e}");
+                    return;
+                }
+            },
+        };
+
+        let keys = ndb.poll_for_notes(sub, 500);
+        if keys.is_empty() {
+            return;
+        }
+
+        let Ok(txn) = Transaction::new(ndb) else {
+            return;
+        };
+
+        let authors: HashSet<Pubkey> = keys
+            .into_iter()
+            .filter_map(|key| ndb.get_note_by_key(&txn, key).ok())
+            .map(|note| Pubkey::new(*note.pubkey()))
+            .collect();
+
+        self.runtime.refresh_outbox_relays(
+            pool,
+            ndb,
+            &txn,
+            &accounts.selected_account_read_relays(),
+            &authors,
+        );
+    }
 }
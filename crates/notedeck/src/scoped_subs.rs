@@ -1,10 +1,12 @@
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
 
-use crate::{Accounts, Outbox};
+use crate::account::relay::author_write_relays;
+use crate::{Accounts, Outbox, RelayPolicies};
 use enostr::{NormRelayUrl, OutboxSubId, Pubkey, RelayReqStatus, RelayUrlPkgs};
 use hashbrown::{HashMap, HashSet};
-use nostrdb::Filter;
+use nostrdb::{Filter, FilterField, Ndb, Transaction};
 
 /// Stable key used by apps to identify a logical subscription.
 ///
@@ -125,6 +127,29 @@ pub enum RelaySelection {
     AccountsRead,
     /// Use an explicit relay set.
     Explicit(HashSet<NormRelayUrl>),
+    /// Resolve from the selected account's read relays, adding one of each
+    /// author's NIP-65 write relays when we don't already read from any of
+    /// them (the outbox model). One set per author with a known relay list.
+    ///
+    /// Re-resolved whenever a new relay list arrives for one of the authors.
+    Outbox(Vec<HashSet<NormRelayUrl>>),
+}
+
+impl RelaySelection {
+    /// Route a subscription to the write relays of the authors its filters
+    /// name. Authors without a relay list yet only reach the read relays.
+    pub fn outbox(ndb: &Ndb, txn: &Transaction, filters: &[Filter]) -> Self {
+        let authors: Vec<Pubkey> = filter_authors(filters).into_iter().collect();
+        RelaySelection::Outbox(author_write_relays(ndb, txn, &authors))
+    }
+
+    /// Whether the resolved relays follow the selected account's read relays
+    fn uses_account_read(&self) -> bool {
+        matches!(
+            self,
+            RelaySelection::AccountsRead | RelaySelection::Outbox(_)
+        )
+    }
 }
 
 /// Realization config for one scoped subscription identity.
@@ -213,6 +238,46 @@ pub enum ScopedSubEoseStatus {
     Live(ScopedSubLiveEoseStatus),
 }
 
+/// Developer snapshot of one scoped subscription declaration.
+#[derive(Clone, Debug)]
+pub struct ScopedSubInspect {
+    /// Filters currently declared for the subscription.
+    pub filters: Vec<Filter>,
+    /// Request state of each relay leg of the live outbox subscription.
+    /// Empty while the subscription is inactive.
+    pub relays: Vec<(NormRelayUrl, RelayReqStatus)>,
+}
+
+/// Developer diagnostics for one declared scoped subscription, shared by all
+/// of its owners.
+#[derive(Clone, Debug)]
+pub struct ScopedSubDiagnostics {
+    pub key: SubKey,
+    /// Account the subscription is scoped to, `None` when global.
+    pub account: Option<Pubkey>,
+    /// Number of owners currently declaring it.
+    pub owners: usize,
+    /// Filters currently declared for the subscription.
+    pub filters: Vec<Filter>,
+    /// Request state of each relay leg of the live outbox subscription.
+    /// Empty while the subscription is inactive.
+    pub relays: Vec<(NormRelayUrl, RelayReqStatus)>,
+    /// Time since the subscription was first declared.
+    pub age: Duration,
+    /// How long the live subscription took to reach EOSE on every relay,
+    /// once it has.
+    pub eose_latency: Option<Duration>,
+}
+
+/// When a live outbox subscription started and how long its first EOSE took.
+struct LiveTiming {
+    started: Instant,
+    eose_latency: Option<Duration>,
+    /// Every relay had already sent EOSE when we started watching, so the
+    /// latency is unknown
+    eosed_unseen: bool,
+}
+
 /// Host-owned runtime for scoped subscription desired/live state and ownership.
 ///
 /// The runtime never leaks outbox subscription ids to app code. Apps talk in
@@ -223,7 +288,13 @@ pub(crate) struct ScopedSubRuntime {
     live: HashMap<ScopedSubKey, OutboxSubId>,
     owners_by_sub: HashMap<ScopedSubKey, HashSet<SubSlotId>>,
     subs_by_slot: HashMap<SubSlotId, HashSet<ScopedSubKey>>,
+    declared_at: HashMap<ScopedSubKey, Instant>,
+    live_timing: HashMap<OutboxSubId, LiveTiming>,
+    /// Someone read [`Self::subscriptions`] since the last
+    /// [`Self::track_eose`], so EOSE timing is worth keeping
+    inspecting: bool,
     next_slot_id: u64,
+    relay_policies: RelayPolicies,
 }
 
 impl Default for ScopedSubRuntime {
@@ -233,7 +304,11 @@ impl Default for ScopedSubRuntime {
             live: HashMap::default(),
             owners_by_sub: HashMap::default(),
             subs_by_slot: HashMap::default(),
+            declared_at: HashMap::default(),
+            live_timing: HashMap::default(),
+            inspecting: false,
             next_slot_id: 1,
+            relay_policies: RelayPolicies::default(),
         }
     }
 }
@@ -243,6 +318,36 @@ impl ScopedSubRuntime {
         ScopedSubKey { scope, key }
     }
 
+    pub(crate) fn with_relay_policies(mut self, relay_policies: RelayPolicies) -> Self {
+        self.relay_policies = relay_policies;
+        self
+    }
+
+    pub(crate) fn relay_policies(&self) -> &RelayPolicies {
+        &self.relay_policies
+    }
+
+    /// Apply new relay policies, moving every live subscription onto the
+    /// relays it is now allowed to use.
+    pub(crate) fn set_relay_policies(
+        &mut self,
+        pool: &mut Outbox<'_>,
+        account_read_relays: &HashSet<NormRelayUrl>,
+        relay_policies: RelayPolicies,
+    ) {
+        self.relay_policies = relay_policies;
+        for (scoped, live_id) in &self.live {
+            let Some(spec) = self.desired.get(scoped) else {
+                continue;
+            };
+
+            pool.modify_relays(
+                *live_id,
+                live_relays(account_read_relays, &self.relay_policies, spec),
+            );
+        }
+    }
+
     /// Create one owner slot for a UI lifecycle owner.
     pub(crate) fn create_slot(&mut self) -> SubSlotId {
         let slot = self.allocate_slot();
@@ -318,6 +423,7 @@ impl ScopedSubRuntime {
 
         config.filters = normalize_filters(config.filters);
         self.desired.insert(scoped.clone(), config.clone());
+        self.declared_at.insert(scoped.clone(), Instant::now());
         self.ensure_live_sub(pool, account_read_relays, scoped, &config);
         EnsureSubResult::Created
     }
@@ -347,6 +453,7 @@ impl ScopedSubRuntime {
         let op = plan_set_sub_live_op(previous.as_ref(), &config, self.live.contains_key(&scoped));
 
         if previous.is_none() {
+            self.declared_at.insert(scoped.clone(), Instant::now());
             self.ensure_live_sub(pool, account_read_relays, scoped, &config);
             return SetSubResult::Created;
         }
@@ -360,7 +467,13 @@ impl ScopedSubRuntime {
             }
             SetSubLiveOp::ModifyExisting => {
                 if let Some(id) = self.live.get(&scoped).copied() {
-                    Self::modify_live_sub(pool, account_read_relays, id, &config);
+                    Self::modify_live_sub(
+                        pool,
+                        account_read_relays,
+                        &self.relay_policies,
+                        id,
+                        &config,
+                    );
                 }
             }
             SetSubLiveOp::RemoveExisting => {
@@ -458,6 +571,106 @@ impl ScopedSubRuntime {
         }
     }
 
+    /// Snapshot the declared filters and per-relay request state for one `(slot, key)`.
+    pub(crate) fn inspect_sub(
+        &self,
+        pool: &Outbox<'_>,
+        accounts: &Accounts,
+        slot: SubSlotId,
+        key: SubKey,
+        scope: SubScope,
+    ) -> Option<ScopedSubInspect> {
+        let selected_account_pubkey = *accounts.selected_account_pubkey();
+        self.inspect_sub_with_selected(pool, selected_account_pubkey, slot, key, scope)
+    }
+
+    /// Snapshot one `(slot, key)` using an explicit selected account.
+    pub(crate) fn inspect_sub_with_selected(
+        &self,
+        pool: &Outbox<'_>,
+        selected_account_pubkey: Pubkey,
+        slot: SubSlotId,
+        key: SubKey,
+        scope: SubScope,
+    ) -> Option<ScopedSubInspect> {
+        let resolved_scope = resolve_scope(&scope, selected_account_pubkey);
+        let scoped = Self::scoped_key(resolved_scope, key);
+
+        if !self.subs_by_slot.get(&slot)?.contains(&scoped) {
+            return None;
+        }
+
+        let config = self.desired.get(&scoped)?;
+        Some(ScopedSubInspect {
+            filters: config.filters.clone(),
+            relays: live_relay_statuses(pool, self.live.get(&scoped)),
+        })
+    }
+
+    /// Diagnostics for every declared subscription, oldest first.
+    pub(crate) fn subscriptions(&mut self, pool: &Outbox<'_>) -> Vec<ScopedSubDiagnostics> {
+        self.inspecting = true;
+        let now = Instant::now();
+        let mut subs: Vec<ScopedSubDiagnostics> = self
+            .desired
+            .iter()
+            .map(|(scoped, config)| {
+                let live_id = self.live.get(scoped);
+                ScopedSubDiagnostics {
+                    key: scoped.key,
+                    account: match &scoped.scope {
+                        ResolvedSubScope::Account(pk) => Some(*pk),
+                        ResolvedSubScope::Global => None,
+                    },
+                    owners: self.owners_by_sub.get(scoped).map_or(0, HashSet::len),
+                    filters: config.filters.clone(),
+                    relays: live_relay_statuses(pool, live_id),
+                    age: self
+                        .declared_at
+                        .get(scoped)
+                        .map_or(Duration::ZERO, |at| now.saturating_duration_since(*at)),
+                    eose_latency: live_id
+                        .and_then(|id| self.live_timing.get(id))
+                        .and_then(|timing| timing.eose_latency),
+                }
+            })
+            .collect();
+        subs.sort_by(|a, b| b.age.cmp(&a.age));
+        subs
+    }
+
+    /// Start timing new live subscriptions and note when they reach EOSE on
+    /// every relay. Called by the host before relay events are processed.
+    ///
+    /// This only runs while the diagnostics are being shown. Timings are
+    /// dropped once nobody has asked for them for a frame.
+    pub(crate) fn track_eose(&mut self, pool: &Outbox<'_>) {
+        if !std::mem::take(&mut self.inspecting) {
+            self.live_timing.clear();
+            return;
+        }
+
+        let now = Instant::now();
+        let live_ids: HashSet<OutboxSubId> = self.live.values().copied().collect();
+        self.live_timing.retain(|id, _| live_ids.contains(id));
+
+        for id in live_ids {
+            let all_eosed = aggregate_eose_status(pool.outbox.status(&id).into_values()).all_eosed;
+            let timing = self.live_timing.entry(id).or_insert(LiveTiming {
+                started: now,
+                eose_latency: None,
+                eosed_unseen: all_eosed,
+            });
+            if timing.eose_latency.is_some() || timing.eosed_unseen {
+                continue;
+            }
+
+            if all_eosed {
+                timing.eose_latency = Some(now.saturating_duration_since(timing.started));
+            }
+        }
+    }
+
     /// Drop all ownership links attached to one slot.
     pub(crate) fn drop_slot(&mut self, pool: &mut Outbox<'_>, slot: SubSlotId) -> DropSlotResult {
         let Some(scoped_keys) = self.subs_by_slot.remove(&slot) else {
@@ -471,6 +684,26 @@ impl ScopedSubRuntime {
         DropSlotResult::Dropped
     }
 
+    /// Forget every declaration scoped to a removed account, whichever slot
+    /// owns it, and unsubscribe any of them that are live.
+    ///
+    /// Slots are kept so owners that later clear their bindings just get
+    /// [`ClearSubResult::NotFound`].
+    pub(crate) fn forget_account(&mut self, pool: &mut Outbox<'_>, account_pk: Pubkey) -> usize {
+        let scope = ResolvedSubScope::Account(account_pk);
+        self.unsubscribe_scope(pool, &scope);
+
+        let before = self.desired.len();
+        self.desired.retain(|scoped, _| scoped.scope != scope);
+        self.owners_by_sub.retain(|scoped, _| scoped.scope != scope);
+        self.declared_at.retain(|scoped, _| scoped.scope != scope);
+        for scoped_keys in self.subs_by_slot.values_mut() {
+            scoped_keys.retain(|scoped| scoped.scope != scope);
+        }
+
+        before - self.desired.len()
+    }
+
     /// Handle centralized account switching using host account relay resolution.
     pub fn on_account_switched(
         &mut self,
@@ -512,7 +745,9 @@ impl ScopedSubRuntime {
                 continue;
             };
 
-            if let Some(live_id) = subscribe_live(pool, new_account_read_relays, spec) {
+            if let Some(live_id) =
+                subscribe_live(pool, new_account_read_relays, &self.relay_policies, spec)
+            {
                 self.live.insert(scoped, live_id);
             }
         }
@@ -521,7 +756,8 @@ impl ScopedSubRuntime {
     /// Retarget live subscriptions that depend on the selected account's read relay set.
     ///
     /// This updates all owned scoped subscriptions whose relay selection is
-    /// [`RelaySelection::AccountsRead`] and whose resolved scope is either:
+    /// [`RelaySelection::AccountsRead`] or [`RelaySelection::Outbox`] and whose
+    /// resolved scope is either:
     /// - the currently selected account (`SubScope::Account` resolved), or
     /// - global (`SubScope::Global`)
     ///
@@ -564,7 +800,7 @@ impl ScopedSubRuntime {
                 continue;
             };
 
-            if !matches!(spec.relays, RelaySelection::AccountsRead) {
+            if !spec.relays.uses_account_read() {
                 continue;
             }
 
@@ -578,13 +814,49 @@ impl ScopedSubRuntime {
             }
 
             if let Some(live_id) = has_live {
-                pool.modify_relays(live_id, resolve_relays(account_read_relays, &spec.relays));
+                pool.modify_relays(
+                    live_id,
+                    live_relays(account_read_relays, &self.relay_policies, &spec),
+                );
             } else {
                 self.ensure_live_sub(pool, account_read_relays, scoped, &spec);
             }
         }
     }
 
+    /// Re-resolve [`RelaySelection::Outbox`] subscriptions naming any of
+    /// `authors`, whose relay lists just changed, and move the live ones onto
+    /// the new relays.
+    pub(crate) fn refresh_outbox_relays(
+        &mut self,
+        pool: &mut Outbox<'_>,
+        ndb: &Ndb,
+        txn: &Transaction,
+        account_read_relays: &HashSet<NormRelayUrl>,
+        authors: &HashSet<Pubkey>,
+    ) {
+        for (scoped, spec) in self.desired.iter_mut() {
+            if !matches!(spec.relays, RelaySelection::Outbox(_))
+                || filter_authors(&spec.filters).is_disjoint(authors)
+            {
+                continue;
+            }
+
+            let relays = RelaySelection::outbox(ndb, txn, &spec.filters);
+            if relays == spec.relays {
+                continue;
+            }
+            spec.relays = relays;
+
+            if let Some(live_id) = self.live.get(scoped) {
+                pool.modify_relays(
+                    *live_id,
+                    live_relays(account_read_relays, &self.relay_policies, spec),
+                );
+            }
+        }
+    }
+
     #[allow(dead_code)]
     pub fn desired_len(&self) -> usize {
         self.desired.len()
@@ -618,7 +890,7 @@ impl ScopedSubRuntime {
         scoped: ScopedSubKey,
         spec: &SubConfig,
     ) {
-        if let Some(id) = subscribe_live(pool, account_read_relays, spec) {
+        if let Some(id) = subscribe_live(pool, account_read_relays, &self.relay_policies, spec) {
             self.live.insert(scoped, id);
         }
     }
@@ -637,11 +909,15 @@ impl ScopedSubRuntime {
     fn modify_live_sub(
         pool: &mut Outbox<'_>,
         account_read_relays: &HashSet<NormRelayUrl>,
+        relay_policies: &RelayPolicies,
         live_id: OutboxSubId,
         spec: &SubConfig,
     ) {
         pool.modify_filters(live_id, spec.filters.clone());
-        pool.modify_relays(live_id, resolve_relays(account_read_relays, &spec.relays));
+        pool.modify_relays(
+            live_id,
+            live_relays(account_read_relays, relay_policies, spec),
+        );
     }
 
     fn remove_live_sub(&mut self, pool: &mut Outbox<'_>, scoped: &ScopedSubKey) {
@@ -681,6 +957,7 @@ impl ScopedSubRuntime {
 
         self.owners_by_sub.remove(scoped);
         self.desired.remove(scoped);
+        self.declared_at.remove(scoped);
         if let Some(sub_id) = self.live.remove(scoped) {
             pool.unsubscribe(sub_id);
         }
@@ -768,24 +1045,101 @@ fn resolve_relays(
     match selection {
         RelaySelection::AccountsRead => account_read_relays.clone(),
         RelaySelection::Explicit(relays) => relays.clone(),
+        RelaySelection::Outbox(authors) => outbox_relays(account_read_relays, authors),
+    }
+}
+
+/// The relays a subscription goes out on once relay policies are applied
+fn live_relays(
+    account_read_relays: &HashSet<NormRelayUrl>,
+    relay_policies: &RelayPolicies,
+    spec: &SubConfig,
+) -> HashSet<NormRelayUrl> {
+    relay_policies.subscribe_relays(
+        resolve_relays(account_read_relays, &spec.relays),
+        &spec.filters,
+    )
+}
+
+/// Greedily add the write relay that covers the most still-uncovered
+/// authors until every author is reachable. This is the usual set cover
+/// approximation, so it stays close to the fewest relays that reach everyone.
+fn outbox_relays(
+    account_read_relays: &HashSet<NormRelayUrl>,
+    authors: &[HashSet<NormRelayUrl>],
+) -> HashSet<NormRelayUrl> {
+    let mut relays = account_read_relays.clone();
+    let mut uncovered: Vec<&HashSet<NormRelayUrl>> = authors
+        .iter()
+        .filter(|write| !write.is_empty() && write.is_disjoint(&relays))
+        .collect();
+
+    while !uncovered.is_empty() {
+        let mut counts: HashMap<&NormRelayUrl, usize> = HashMap::new();
+        for relay in uncovered.iter().flat_map(|write| write.iter()) {
+            *counts.entry(relay).or_default() += 1;
+        }
+
+        // ties go to the smallest url so the result is stable across frames
+        let Some(best) = counts
+            .into_iter()
+            .max_by(|(a_url, a), (b_url, b)| a.cmp(b).then_with(|| b_url.cmp(a_url)))
+            .map(|(relay, _)| relay.clone())
+        else {
+            break;
+        };
+
+        uncovered.retain(|write| !write.contains(&best));
+        relays.insert(best);
+    }
+
+    relays
+}
+
+/// Authors named by `filters`
+fn filter_authors(filters: &[Filter]) -> HashSet<Pubkey> {
+    let mut authors = HashSet::new();
+    for field in filters.iter().flat_map(|filter| filter.into_iter()) {
+        if let FilterField::Authors(pks) = field {
+            authors.extend(pks.into_iter().map(|pk| Pubkey::new(*pk)));
+        }
     }
+    authors
 }
 
 fn subscribe_live(
     pool: &mut Outbox<'_>,
     account_read_relays: &HashSet<NormRelayUrl>,
+    relay_policies: &RelayPolicies,
     spec: &SubConfig,
 ) -> Option<OutboxSubId> {
     if spec.filters.is_empty() {
         return None;
     }
 
-    let relays = resolve_relays(account_read_relays, &spec.relays);
+    let relays = live_relays(account_read_relays, relay_policies, spec);
     let mut relay_pkgs = RelayUrlPkgs::new(relays);
     relay_pkgs.use_transparent = spec.use_transparent;
     Some(pool.subscribe(spec.filters.clone(), relay_pkgs))
 }
 
+fn live_relay_statuses(
+    pool: &Outbox<'_>,
+    live_id: Option<&OutboxSubId>,
+) -> Vec<(NormRelayUrl, RelayReqStatus)> {
+    let mut relays: Vec<(NormRelayUrl, RelayReqStatus)> = live_id
+        .map(|live_id| {
+            pool.outbox
+                .status(live_id)
+                .into_iter()
+                .map(|(url, status)| (url.clone(), status))
+                .collect()
+        })
+        .unwrap_or_default();
+    relays.sort_by(|a, b| a.0.cmp(&b.0));
+    relays
+}
+
 fn aggregate_eose_status(
     relay_statuses: impl IntoIterator<Item = RelayReqStatus>,
 ) -> ScopedSubLiveEoseStatus {
@@ -926,6 +1280,123 @@ mod tests {
         assert_eq!(runtime.slot_len(), 1);
     }
 
+    /// Verifies inspect_sub reports declared filters only for keys owned by the slot.
+    #[test]
+    fn inspect_sub_reports_declared_filters() {
+        let mut runtime = ScopedSubRuntime::default();
+        let mut pool = OutboxPool::default();
+        let relays = relay_set("wss://relay-a.example.com");
+        let key = SubKey::new(("timeline", "home", 2u8));
+        let slot = runtime.create_slot();
+        let other_slot = runtime.create_slot();
+
+        let _ = runtime.set_sub_with_relays(
+            &mut outbox(&mut pool),
+            &relays,
+            account_pk(0x01),
+            slot,
+            SubScope::Account,
+            key,
+            live_config(SubScope::Account),
+        );
+
+        let inspect = runtime
+            .inspect_sub_with_selected(
+                &outbox(&mut pool),
+                account_pk(0x01),
+                slot,
+                key,
+                SubScope::Account,
+            )
+            .expect("declared sub");
+        assert_eq!(
+            inspect
+                .filters
+                .iter()
+                .map(|f| f.json().expect("filter json"))
+                .collect::<Vec<_>>(),
+            live_config(SubScope::Account)
+                .filters
+                .iter()
+                .map(|f| f.json().expect("filter json"))
+                .collect::<Vec<_>>()
+        );
+
+        // other owners and other accounts don't see it
+        assert!(runtime
+            .inspect_sub_with_selected(
+                &outbox(&mut pool),
+                account_pk(0x01),
+                other_slot,
+                key,
+                SubScope::Account,
+            )
+            .is_none());
+        assert!(runtime
+            .inspect_sub_with_selected(
+                &outbox(&mut pool),
+                account_pk(0x02),
+                slot,
+                key,
+                SubScope::Account,
+            )
+            .is_none());
+    }
+
+    /// Verifies subscriptions lists each shared declaration once with its owners.
+    #[test]
+    fn subscriptions_reports_shared_declarations() {
+        let mut runtime = ScopedSubRuntime::default();
+        let mut pool = OutboxPool::default();
+        let relays = relay_set("wss://relay-a.example.com");
+        let thread = SubKey::new(("thread", [7u8; 32]));
+        let global = SubKey::new(("global", 1u8));
+        let slot_a = runtime.create_slot();
+        let slot_b = runtime.create_slot();
+
+        for slot in [slot_a, slot_b] {
+            let _ = runtime.set_sub_with_relays(
+                &mut outbox(&mut pool),
+                &relays,
+                account_pk(0x01),
+                slot,
+                SubScope::Account,
+                thread,
+                live_config(SubScope::Account),
+            );
+        }
+        let _ = runtime.set_sub_with_relays(
+            &mut outbox(&mut pool),
+            &relays,
+            account_pk(0x01),
+            slot_a,
+            SubScope::Global,
+            global,
+            live_config(SubScope::Global),
+        );
+
+        let _ = runtime.subscriptions(&outbox(&mut pool));
+        runtime.track_eose(&outbox(&mut pool));
+        let subs = runtime.subscriptions(&outbox(&mut pool));
+        assert_eq!(subs.len(), 2);
+
+        let thread_sub = subs.iter().find(|sub| sub.key == thread).expect("thread");
+        assert_eq!(thread_sub.account, Some(account_pk(0x01)));
+        assert_eq!(thread_sub.owners, 2);
+        assert_eq!(thread_sub.filters.len(), 1);
+        // no relay has answered yet
+        assert_eq!(thread_sub.eose_latency, None);
+
+        let global_sub = subs.iter().find(|sub| sub.key == global).expect("global");
+        assert_eq!(global_sub.account, None);
+        assert_eq!(global_sub.owners, 1);
+
+        let _ = runtime.drop_slot(&mut outbox(&mut pool), slot_a);
+        let subs = runtime.subscriptions(&outbox(&mut pool));
+        assert_eq!(subs.len(), 1);
+        assert_eq!(subs[0].owners, 1);
+    }
+
     /// Verifies repeated ensure_sub calls for the same key are create-then-noop.
     #[test]
     fn ensure_sub_is_create_or_ignore_for_existing_key() {
@@ -1016,6 +1487,41 @@ mod tests {
         assert_eq!(before, after);
     }
 
+    /// Verifies outbox routing adds the fewest write relays that reach every author.
+    #[test]
+    fn outbox_relays_cover_each_author_once() {
+        let read = relay_set("wss://read.example");
+        let mut shared = relay_set("wss://shared.example");
+        shared.insert(NormRelayUrl::new("wss://alice.example").unwrap());
+        let authors = vec![
+            shared,
+            relay_set("wss://shared.example"),
+            relay_set("wss://read.example"),
+            HashSet::new(),
+        ];
+
+        let relays = outbox_relays(&read, &authors);
+
+        let mut expected = read.clone();
+        expected.insert(NormRelayUrl::new("wss://shared.example").unwrap());
+        assert_eq!(relays, expected);
+    }
+
+    /// Verifies outbox routing reaches every author, however many relays that takes.
+    #[test]
+    fn outbox_relays_reach_every_author() {
+        let read = relay_set("wss://read.example");
+        let authors: Vec<_> = (0..40)
+            .map(|i| relay_set(&format!("wss://author{i}.example")))
+            .collect();
+
+        let relays = outbox_relays(&read, &authors);
+
+        assert_eq!(relays.len(), read.len() + authors.len());
+        assert!(relays.is_superset(&read));
+        assert!(authors.iter().all(|write| !write.is_disjoint(&relays)));
+    }
+
     /// Verifies aggregate EOSE helper treats zero tracked relays as not fully EOSE'd.
     #[test]
     fn aggregate_eose_status_zero_tracked_relays_is_not_all_eosed() {
@@ -1132,6 +1638,43 @@ mod tests {
         assert!(!live.all_eosed);
     }
 
+    /// Verifies relay policies keep live subs off relays that don't allow them, and
+    /// that new policies move existing live subs.
+    #[test]
+    fn relay_policies_limit_live_sub_relays() {
+        let dm_policy = crate::RelayPolicy {
+            kinds: [1059].into(),
+            ..crate::RelayPolicy::new("wss://relay-dm.example.com")
+        };
+        let mut runtime =
+            ScopedSubRuntime::default().with_relay_policies(RelayPolicies::new(&[dm_policy]));
+        let mut pool = OutboxPool::default();
+        let mut relays = relay_set("wss://relay-a.example.com");
+        relays.insert(NormRelayUrl::new("wss://relay-dm.example.com").unwrap());
+        let slot = runtime.create_slot();
+        let key = make_key(("policy", 1u8));
+
+        let _ = runtime.set_sub_with_relays(
+            &mut outbox(&mut pool),
+            &relays,
+            account_pk(0x01),
+            slot,
+            SubScope::Global,
+            key,
+            live_config(SubScope::Global),
+        );
+
+        let scoped = ScopedSubRuntime::scoped_key(ResolvedSubScope::Global, key);
+        let live_id = runtime.live.get(&scoped).copied().expect("live sub id");
+        let live_relays = |pool: &OutboxPool| -> HashSet<NormRelayUrl> {
+            pool.status(&live_id).into_keys().cloned().collect()
+        };
+        assert_eq!(live_relays(&pool), relay_set("wss://relay-a.example.com"));
+
+        runtime.set_relay_policies(&mut outbox(&mut pool), &relays, RelayPolicies::default());
+        assert_eq!(live_relays(&pool), relays);
+    }
+
     /// Verifies account switch makes old account-scoped subs inactive and restores them on switch-back.
     #[test]
     fn account_scoped_sub_eose_status_transitions_inactive_and_restores_on_switch_back() {
@@ -1461,6 +2004,79 @@ mod tests {
         ));
     }
 
+    /// Verifies forgetting an account drops its scoped subs from every slot but leaves
+    /// global subs and other accounts alone.
+    #[test]
+    fn forget_account_drops_scoped_subs_for_that_account_only() {
+        let mut runtime = ScopedSubRuntime::default();
+        let mut pool = OutboxPool::default();
+        let removed = account_pk(0x5A);
+        let kept = account_pk(0x5B);
+        let relays = relay_set("wss://relay-a.example.com");
+        let timelines = runtime.create_slot();
+        let threads = runtime.create_slot();
+
+        let key_home = SubKey::new(("timeline", "home"));
+        let key_thread = SubKey::new(("thread", "root"));
+        let key_global = SubKey::new(("global", "discovery"));
+
+        for (slot, scope, key, account) in [
+            (timelines, SubScope::Account, key_home, removed),
+            (threads, SubScope::Account, key_thread, removed),
+            (timelines, SubScope::Account, key_home, kept),
+            (threads, SubScope::Global, key_global, removed),
+        ] {
+            let _ = runtime.set_sub_with_relays(
+                &mut outbox(&mut pool),
+                &relays,
+                account,
+                slot,
+                scope,
+                key,
+                live_config(scope),
+            );
+        }
+
+        assert_eq!(runtime.desired_len(), 4);
+
+        let removed_home =
+            ScopedSubRuntime::scoped_key(ResolvedSubScope::Account(removed), key_home);
+        let live_id = runtime
+            .live
+            .get(&removed_home)
+            .copied()
+            .expect("live home sub");
+
+        assert_eq!(runtime.forget_account(&mut outbox(&mut pool), removed), 2);
+
+        assert_eq!(runtime.desired_len(), 2);
+        assert!(runtime.live.get(&removed_home).is_none());
+        assert!(pool.filters(&live_id).is_none());
+        assert_eq!(runtime.slot_len(), 2);
+
+        assert!(matches!(
+            runtime.clear_sub_with_selected(
+                &mut outbox(&mut pool),
+                removed,
+                threads,
+                key_thread,
+                SubScope::Account
+            ),
+            ClearSubResult::NotFound
+        ));
+        assert!(matches!(
+            slot_status(
+                &runtime,
+                &mut pool,
+                kept,
+                timelines,
+                key_home,
+                SubScope::Account
+            ),
+            ScopedSubEoseStatus::Live(_)
+        ));
+    }
+
     /// Verifies account switch unsubscribes the old account scope and restores it when switching back.
     #[test]
     fn account_switch_unsubscribes_old_scope_and_restores_new_scope() {
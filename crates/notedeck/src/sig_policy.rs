@@ -0,0 +1,160 @@
+use nostrdb::Note;
+use serde::{de::IgnoredAny, Deserialize, Serialize};
+
+use crate::{FILE_METADATA_KIND, LIVE_EVENT_KIND, POLL_KIND};
+
+/// Kinds that only matter once a note view shows them, which is where
+/// [`SigPolicy::VerifyOnDisplay`] checks them. Text notes, reposts, group
+/// chat, threads, DMs, comments, articles, files, live events and polls.
+const DISPLAYED_KINDS: &[u32] = &[
+    1,
+    6,
+    9,
+    11,
+    14,
+    16,
+    1111,
+    30023,
+    FILE_METADATA_KIND,
+    LIVE_EVENT_KIND,
+    POLL_KIND,
+];
+
+/// When the signatures of events we receive are checked. The policy is read
+/// once at startup, since nostrdb's own checking can't be switched on a
+/// running database.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SigPolicy {
+    /// Check every event from a relay before it's stored. Events with bad
+    /// signatures are dropped and counted against the relay that sent them.
+    VerifyAll,
+    /// Store notes unchecked and check one the first time it's shown.
+    /// Notes with bad signatures are stored but never displayed. Everything
+    /// else, like profiles and contact lists, is checked before it's stored.
+    VerifyOnDisplay,
+    /// Leave checking to nostrdb's ingester threads, which can't tell which
+    /// relay sent a bad event
+    #[default]
+    TrustNdb,
+}
+
+impl SigPolicy {
+    /// Whether nostrdb should check signatures itself. It keeps doing so
+    /// when we verify on ingestion, since not every app ingests through
+    /// the relay pool.
+    pub fn ndb_validates(self) -> bool {
+        self != SigPolicy::VerifyOnDisplay
+    }
+
+    /// Whether we check events of `kind` ourselves before they're stored
+    pub fn verifies_on_ingest(self, kind: u32) -> bool {
+        match self {
+            SigPolicy::VerifyAll => true,
+            SigPolicy::VerifyOnDisplay => !DISPLAYED_KINDS.contains(&kind),
+            SigPolicy::TrustNdb => false,
+        }
+    }
+
+    pub fn verifies_on_display(self) -> bool {
+        self == SigPolicy::VerifyOnDisplay
+    }
+}
+
+/// The reason the event in a raw `["EVENT", ...]` message fails signature
+/// verification under `policy`. `kind` is the event's kind when the relay
+/// already parsed it, which lets events we don't check skip the parse.
+/// Messages we can't parse are left for nostrdb to reject.
+pub fn bad_event_signature(msg: &str, kind: Option<u64>, policy: SigPolicy) -> Option<String> {
+    if kind.is_some_and(|kind| !policy.verifies_on_ingest(kind as u32)) {
+        return None;
+    }
+
+    let event = event_from_message(msg)?;
+    if !policy.verifies_on_ingest(event.kind.as_u16().into()) {
+        return None;
+    }
+    event.verify().err().map(|err| err.to_string())
+}
+
+/// The event of a relay `["EVENT", <sub id>, <event>]` or client
+/// `["EVENT", <event>]` message
+fn event_from_message(msg: &str) -> Option<nostr::Event> {
+    if let Ok((_, _, event)) = serde_json::from_str::<(IgnoredAny, IgnoredAny, nostr::Event)>(msg) {
+        return Some(event);
+    }
+
+    serde_json::from_str::<(IgnoredAny, nostr::Event)>(msg)
+        .ok()
+        .map(|(_, event)| event)
+}
+
+/// Whether `note`'s id and signature check out
+pub fn note_signature_valid(note: &Note<'_>) -> bool {
+    note.json()
+        .ok()
+        .and_then(|json| serde_json::from_str::<nostr::Event>(&json).ok())
+        .is_some_and(|event| event.verify().is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use enostr::FullKeypair;
+    use nostrdb::NoteBuilder;
+
+    fn signed_note_json() -> String {
+        let kp = FullKeypair::generate();
+        NoteBuilder::new()
+            .kind(1)
+            .content("hello")
+            .sign(&kp.secret_key.secret_bytes())
+            .build()
+            .expect("note")
+            .json()
+            .expect("json")
+    }
+
+    #[test]
+    fn relay_messages_are_verified() {
+        let json = signed_note_json();
+
+        let policy = SigPolicy::VerifyAll;
+
+        assert_eq!(
+            bad_event_signature(&format!(r#"["EVENT","sub",{json}]"#), None, policy),
+            None
+        );
+        assert_eq!(
+            bad_event_signature(&format!(r#"["EVENT",{json}]"#), None, policy),
+            None
+        );
+
+        let forged = json.replace("hello", "hellp");
+        assert!(
+            bad_event_signature(&format!(r#"["EVENT","sub",{forged}]"#), None, policy).is_some()
+        );
+
+        // not ours to judge
+        assert_eq!(
+            bad_event_signature(r#"["EVENT","sub",{"kind":1}]"#, None, policy),
+            None
+        );
+    }
+
+    #[test]
+    fn verify_on_display_still_checks_other_kinds() {
+        let policy = SigPolicy::VerifyOnDisplay;
+        assert!(!policy.verifies_on_ingest(1));
+        assert!(policy.verifies_on_ingest(0));
+        assert!(policy.verifies_on_ingest(3));
+        assert!(policy.verifies_on_ingest(10002));
+
+        let forged = signed_note_json().replace("hello", "hellp");
+        let msg = format!(r#"["EVENT","sub",{forged}]"#);
+        assert_eq!(bad_event_signature(&msg, None, policy), None);
+        assert_eq!(bad_event_signature(&msg, Some(1), policy), None);
+        assert!(bad_event_signature(&msg, None, SigPolicy::VerifyAll).is_some());
+        assert!(bad_event_signature(&msg, Some(1), SigPolicy::VerifyAll).is_some());
+        assert_eq!(bad_event_signature(&msg, None, SigPolicy::TrustNdb), None);
+    }
+}
@@ -3,18 +3,22 @@ use enostr::{Keypair, Pubkey, SerializableKeypair};
 use tokenator::{TokenParser, TokenSerializable, TokenWriter};
 
 use super::{
-    file_storage::{delete_file, write_file, Directory},
+    file_storage::{delete_file, scrub_file, write_file, Directory},
     keyring_store::KeyringStore,
 };
 
 static SELECTED_PUBKEY_FILE_NAME: &str = "selected_pubkey";
 
 /// An OS agnostic key storage implementation backed by the operating system's secure store.
+///
+/// With the OS keyring turned off, secret keys are kept in the account
+/// files instead, for systems without a working secret service.
 #[derive(Debug, Clone)]
 pub struct AccountStorage {
     accounts_directory: Directory,
     selected_key_directory: Directory,
     keyring: KeyringStore,
+    use_keyring: bool,
 }
 
 impl AccountStorage {
@@ -35,9 +39,17 @@ impl AccountStorage {
             accounts_directory,
             selected_key_directory,
             keyring,
+            use_keyring: true,
         }
     }
 
+    /// Whether secret keys go in the OS keyring (the default) or the
+    /// account files. Stored keys move over as accounts are loaded.
+    pub fn with_os_keyring(mut self, use_keyring: bool) -> Self {
+        self.use_keyring = use_keyring;
+        self
+    }
+
     pub fn rw(self) -> (AccountStorageReader, AccountStorageWriter) {
         (
             AccountStorageReader::new(self.clone()),
@@ -46,6 +58,14 @@ impl AccountStorage {
     }
 
     fn persist_account(&self, account: &UserAccountSerializable) -> Result<()> {
+        if !self.use_keyring {
+            self.write_account_file(account)?;
+            if account.key.secret_key.is_some() {
+                self.forget_keyring_secret(&account.key.pubkey);
+            }
+            return Ok(());
+        }
+
         if let Some(secret) = account.key.secret_key.as_ref() {
             self.keyring.store_secret(&account.key.pubkey, secret)?;
             self.write_account_without_secret(account)?;
@@ -58,8 +78,12 @@ impl AccountStorage {
     }
 
     fn write_account_without_secret(&self, account: &UserAccountSerializable) -> Result<()> {
+        self.write_account_file(&sanitized_account(account))
+    }
+
+    fn write_account_file(&self, account: &UserAccountSerializable) -> Result<()> {
         let mut writer = TokenWriter::new("\t");
-        sanitized_account(account).serialize_tokens(&mut writer);
+        account.serialize_tokens(&mut writer);
 
         write_file(
             &self.accounts_directory.file_path,
@@ -67,6 +91,14 @@ impl AccountStorage {
             writer.str(),
         )
     }
+
+    /// Best effort removal of a secret that now lives in the account file.
+    /// The keyring may well be unusable, which is why it was turned off.
+    fn forget_keyring_secret(&self, pubkey: &Pubkey) {
+        if let Err(e) = self.keyring.remove_secret(pubkey) {
+            tracing::debug!("could not remove {pubkey:?} from the OS secure store: {e}");
+        }
+    }
 }
 
 pub struct AccountStorageWriter {
@@ -78,6 +110,12 @@ impl AccountStorageWriter {
         Self { storage }
     }
 
+    /// Switch between the OS keyring and the account files for secret keys.
+    /// Accounts written after this are stored the new way.
+    pub fn set_os_keyring(&mut self, use_keyring: bool) {
+        self.storage.use_keyring = use_keyring;
+    }
+
     pub fn write_account(&self, account: &UserAccountSerializable) -> Result<()> {
         self.storage.persist_account(account)
     }
@@ -88,6 +126,14 @@ impl AccountStorageWriter {
         Ok(())
     }
 
+    /// Like [`Self::remove_key`], but zeroes the account file on disk before
+    /// deleting it since it may hold wallet connection secrets.
+    pub fn scrub_key(&self, key: &Keypair) -> Result<()> {
+        scrub_file(&self.storage.accounts_directory.file_path, key.pubkey.hex())?;
+        self.storage.keyring.remove_secret(&key.pubkey)?;
+        Ok(())
+    }
+
     pub fn select_key(&self, pubkey: Option<Pubkey>) -> Result<()> {
         if let Some(pubkey) = pubkey {
             write_file(
@@ -136,6 +182,11 @@ impl AccountStorageReader {
             })
             // sanitize our storage of secrets & inject the secret from `keyring` into `UserAccountSerializable`
             .map(|mut account| -> Result<UserAccountSerializable> {
+                if !self.storage.use_keyring {
+                    self.move_secret_out_of_keyring(&mut account);
+                    return Ok(account);
+                }
+
                 if let Some(secret) = &account.key.secret_key {
                     match self
                         .storage
@@ -164,6 +215,27 @@ impl AccountStorageReader {
         Ok(accounts)
     }
 
+    /// With the keyring turned off, bring a secret stored there back into
+    /// the account file
+    fn move_secret_out_of_keyring(&self, account: &mut UserAccountSerializable) {
+        if account.key.secret_key.is_some() {
+            return;
+        }
+
+        let Ok(Some(secret)) = self.storage.keyring.get_secret(&account.key.pubkey) else {
+            return;
+        };
+
+        account.key.secret_key = Some(secret);
+        match self.storage.write_account_file(account) {
+            Ok(()) => self.storage.forget_keyring_secret(&account.key.pubkey),
+            Err(e) => tracing::error!(
+                "failed to write account {:?} with its secret: {e}",
+                account.key.pubkey
+            ),
+        }
+    }
+
     pub fn get_selected_key(&self) -> Result<Option<Pubkey>> {
         match self
             .storage
@@ -236,6 +308,19 @@ mod tests {
         assert_num_storage(&reader.get_accounts(), 0);
     }
 
+    #[test]
+    fn test_scrub_key() {
+        let kp = enostr::FullKeypair::generate().to_keypair();
+        let (reader, writer) = AccountStorage::mock().unwrap().rw();
+        writer
+            .write_account(&UserAccountSerializable::new(kp.clone()))
+            .unwrap();
+        assert_num_storage(&reader.get_accounts(), 1);
+
+        assert!(writer.scrub_key(&kp).is_ok());
+        assert_num_storage(&reader.get_accounts(), 0);
+    }
+
     #[test]
     fn test_secret_persisted_in_keyring_not_on_disk() {
         let kp = enostr::FullKeypair::generate().to_keypair();
@@ -291,6 +376,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_secret_on_disk_without_keyring() {
+        let kp = enostr::FullKeypair::generate().to_keypair();
+        let storage = AccountStorage::mock().unwrap();
+        let keyring = storage.keyring.clone();
+
+        // stored in the keyring, then the keyring is turned off
+        let (_, writer) = storage.clone().rw();
+        writer
+            .write_account(&UserAccountSerializable::new(kp.clone()))
+            .unwrap();
+
+        let (reader, _) = storage.clone().with_os_keyring(false).rw();
+        let accounts = reader.get_accounts().expect("accounts");
+        assert_eq!(accounts[0].key, kp);
+        assert!(keyring.get_secret(&kp.pubkey).unwrap().is_none());
+
+        // the file alone now has it
+        let (reader, _) = storage.clone().with_os_keyring(false).rw();
+        assert_eq!(reader.get_accounts().unwrap()[0].key, kp);
+
+        // and turning the keyring back on moves it back
+        let (reader, _) = storage.rw();
+        assert_eq!(reader.get_accounts().unwrap()[0].key, kp);
+        assert!(keyring.get_secret(&kp.pubkey).unwrap().is_some());
+    }
+
     fn assert_num_storage(keys_response: &Result<Vec<UserAccountSerializable>>, n: usize) {
         match keys_response {
             Ok(keys) => {
@@ -1,7 +1,7 @@
 use std::{
     collections::{HashMap, VecDeque},
     fs::{self, File},
-    io::{self, BufRead},
+    io::{self, BufRead, Write},
     path::{Path, PathBuf},
     time::SystemTime,
 };
@@ -203,12 +203,35 @@ pub fn delete_file(directory: &Path, file_name: String) -> Result<()> {
     }
 }
 
+/// Overwrite a file's contents with zeros and flush them to disk before
+/// deleting it, so secrets don't linger in the freed blocks.
+///
+/// This is best effort: copy-on-write filesystems and SSD wear levelling may
+/// still keep the old data around.
+pub fn scrub_file(directory: &Path, file_name: String) -> Result<()> {
+    let file_to_scrub = directory.join(&file_name);
+    if !file_to_scrub.is_file() {
+        return Err(Error::Generic(format!(
+            "Requested file to scrub was not found: {file_name}"
+        )));
+    }
+
+    {
+        let mut file = fs::OpenOptions::new().write(true).open(&file_to_scrub)?;
+        let len = file.metadata()?.len() as usize;
+        file.write_all(&vec![0u8; len])?;
+        file.sync_all()?;
+    }
+
+    delete_file(directory, file_name)
+}
+
 #[cfg(test)]
 mod tests {
     use std::path::PathBuf;
 
     use crate::{
-        storage::file_storage::{delete_file, write_file},
+        storage::file_storage::{delete_file, scrub_file, write_file},
         Result,
     };
 
@@ -277,4 +300,23 @@ mod tests {
             panic!("could not get interactor")
         }
     }
+
+    #[test]
+    fn test_scrub_file() {
+        let path = CREATE_TMP_DIR().expect("tmp dir");
+        let directory = Directory::new(path);
+        let file_name = "secret.txt".to_string();
+
+        write_file(
+            &directory.file_path,
+            file_name.clone(),
+            "nostr+walletconnect://",
+        )
+        .expect("write");
+        assert!(scrub_file(&directory.file_path, file_name.clone()).is_ok());
+        assert!(directory.get_file(file_name.clone()).is_err());
+
+        // scrubbing a missing file is an error, like deleting one
+        assert!(scrub_file(&directory.file_path, file_name).is_err());
+    }
 }
@@ -1,7 +1,9 @@
 mod account_storage;
 mod file_storage;
 mod keyring_store;
+mod sealed;
 
 pub use account_storage::{AccountStorage, AccountStorageReader, AccountStorageWriter};
-pub use file_storage::{delete_file, write_file, DataPath, DataPathType, Directory};
+pub use file_storage::{delete_file, scrub_file, write_file, DataPath, DataPathType, Directory};
 pub use keyring_store::KeyringStore;
+pub use sealed::{read_sealed_file, write_sealed_file, SealKey};
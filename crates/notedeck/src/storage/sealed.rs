@@ -0,0 +1,124 @@
+//! At-rest encryption for local files holding private content, like the
+//! composer's drafts.
+//!
+//! The file key is derived from the account's secret key, so a copied data
+//! directory is useless without the key store:
+//!   seal_key = hkdf_extract(ikm=account_secret, salt="notedeck-local-seal")
+//!
+//! Contents are NIP-44 v2 encrypted with `seal_key` and base64 encoded.
+
+use std::path::Path;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use enostr::SecretKey;
+use hkdf::Hkdf;
+use nostr::nips::nip44::v2::{self, ConversationKey};
+use sha2::Sha256;
+use zeroize::Zeroize;
+
+use super::file_storage::{write_file, Directory};
+use crate::{Error, Result};
+
+/// Salt used for deriving the local seal key from the account secret.
+const SEAL_SALT: &[u8] = b"notedeck-local-seal";
+
+/// Symmetric key for sealing an account's local files.
+pub struct SealKey {
+    conversation_key: ConversationKey,
+}
+
+impl SealKey {
+    /// Derive the seal key for an account. Deterministic, so files sealed in
+    /// an earlier session can be opened again.
+    pub fn from_secret(secret: &SecretKey) -> Self {
+        let mut ikm = secret.secret_bytes();
+        let (prk, _) = Hkdf::<Sha256>::extract(Some(SEAL_SALT), &ikm);
+        ikm.zeroize();
+
+        let mut key = [0u8; 32];
+        key.copy_from_slice(&prk);
+        let conversation_key = ConversationKey::new(key);
+        key.zeroize();
+
+        Self { conversation_key }
+    }
+
+    pub fn seal(&self, plaintext: &str) -> Result<String> {
+        let payload = v2::encrypt_to_bytes(&self.conversation_key, plaintext)
+            .map_err(|e| Error::Generic(format!("could not seal local data: {e}")))?;
+        Ok(BASE64.encode(payload))
+    }
+
+    pub fn unseal(&self, sealed: &str) -> Result<String> {
+        let payload = BASE64
+            .decode(sealed.trim())
+            .map_err(|e| Error::Generic(format!("sealed data is not base64: {e}")))?;
+        let plaintext = v2::decrypt_to_bytes(&self.conversation_key, &payload)
+            .map_err(|e| Error::Generic(format!("could not unseal local data: {e}")))?;
+        String::from_utf8(plaintext)
+            .map_err(|e| Error::Generic(format!("unsealed data is not UTF-8: {e}")))
+    }
+}
+
+/// Encrypt `data` with the seal key and write it to `directory/file_name`.
+pub fn write_sealed_file(
+    directory: &Path,
+    file_name: String,
+    key: &SealKey,
+    data: &str,
+) -> Result<()> {
+    write_file(directory, file_name, &key.seal(data)?)
+}
+
+/// Read and decrypt a file written with [`write_sealed_file`].
+pub fn read_sealed_file(directory: &Directory, file_name: String, key: &SealKey) -> Result<String> {
+    key.unseal(&directory.get_file(file_name)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secret(first: u8) -> SecretKey {
+        let mut bytes = [0u8; 32];
+        bytes[0] = first;
+        bytes[31] = 0x01;
+        SecretKey::from_slice(&bytes).unwrap()
+    }
+
+    #[test]
+    fn seal_roundtrip() {
+        let key = SealKey::from_secret(&secret(1));
+        let sealed = key.seal("gm, this is a draft").unwrap();
+
+        assert!(!sealed.contains("draft"));
+        assert_eq!(key.unseal(&sealed).unwrap(), "gm, this is a draft");
+
+        // derivation is stable across sessions
+        let again = SealKey::from_secret(&secret(1));
+        assert_eq!(again.unseal(&sealed).unwrap(), "gm, this is a draft");
+    }
+
+    #[test]
+    fn other_accounts_cannot_unseal() {
+        let sealed = SealKey::from_secret(&secret(1)).seal("private").unwrap();
+        assert!(SealKey::from_secret(&secret(2)).unseal(&sealed).is_err());
+    }
+
+    #[test]
+    fn sealed_file_roundtrip() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let directory = Directory::new(tmp.path().to_path_buf());
+        let key = SealKey::from_secret(&secret(3));
+
+        write_sealed_file(&directory.file_path, "draft".to_owned(), &key, "hello").unwrap();
+
+        let on_disk = directory.get_file("draft".to_owned()).unwrap();
+        assert!(!on_disk.contains("hello"));
+        assert_eq!(
+            read_sealed_file(&directory, "draft".to_owned(), &key).unwrap(),
+            "hello"
+        );
+    }
+}
@@ -116,6 +116,14 @@ pub fn time_ago_since(i18n: &mut Localization, timestamp: u64) -> String {
     time_ago_between(i18n, timestamp, now)
 }
 
+/// How long until `timestamp`, without the leading `+` that
+/// [`time_ago_since`] puts on future times.
+pub fn time_until(i18n: &mut Localization, timestamp: u64) -> String {
+    let now = unix_time_secs();
+
+    time_ago_between(i18n, now, timestamp)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,5 +1,6 @@
 use crate::debouncer::Debouncer;
-use crate::{storage, DataPath, DataPathType, Directory};
+use crate::storage::{self, read_sealed_file, write_sealed_file, SealKey};
+use crate::{DataPath, DataPathType, Directory};
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 use tracing::info;
@@ -9,6 +10,8 @@ pub struct TimedSerializer<T: PartialEq + Clone + Serialize + for<'de> Deseriali
     file_name: String,
     debouncer: Debouncer,
     saved_item: Option<T>,
+    /// Encrypts the file at rest, for private contents
+    seal: Option<SealKey>,
 }
 
 impl<T: PartialEq + Clone + Serialize + for<'de> Deserialize<'de>> TimedSerializer<T> {
@@ -22,9 +25,16 @@ impl<T: PartialEq + Clone + Serialize + for<'de> Deserialize<'de>> TimedSerializ
             file_name,
             debouncer,
             saved_item: None,
+            seal: None,
         }
     }
 
+    /// Keep the file encrypted with `key`
+    pub fn sealed(mut self, key: SealKey) -> Self {
+        self.seal = Some(key);
+        self
+    }
+
     pub fn with_delay(mut self, delay: Duration) -> Self {
         self.debouncer = self.debouncer.with_delay(delay);
         self
@@ -48,7 +58,11 @@ impl<T: PartialEq + Clone + Serialize + for<'de> Deserialize<'de>> TimedSerializ
         if let Some(ref item) = self.saved_item {
             return Some(item.clone());
         }
-        if let Ok(file_contents) = self.directory.get_file(self.file_name.clone()) {
+        let file_contents = match &self.seal {
+            Some(key) => read_sealed_file(&self.directory, self.file_name.clone(), key),
+            None => self.directory.get_file(self.file_name.clone()),
+        };
+        if let Ok(file_contents) = file_contents {
             if let Ok(item) = serde_json::from_str::<T>(&file_contents) {
                 return Some(item);
             }
@@ -60,14 +74,25 @@ impl<T: PartialEq + Clone + Serialize + for<'de> Deserialize<'de>> TimedSerializ
 
     fn save(&mut self, cur_item: T) -> bool {
         if let Ok(serialized_item) = serde_json::to_string(&cur_item) {
-            if storage::write_file(
-                &self.directory.file_path,
-                self.file_name.clone(),
-                &serialized_item,
-            )
-            .is_ok()
-            {
-                info!("wrote item {}", serialized_item);
+            let written = match &self.seal {
+                Some(key) => write_sealed_file(
+                    &self.directory.file_path,
+                    self.file_name.clone(),
+                    key,
+                    &serialized_item,
+                ),
+                None => storage::write_file(
+                    &self.directory.file_path,
+                    self.file_name.clone(),
+                    &serialized_item,
+                ),
+            };
+            if written.is_ok() {
+                if self.seal.is_some() {
+                    info!("wrote sealed {}", self.file_name);
+                } else {
+                    info!("wrote item {}", serialized_item);
+                }
                 self.debouncer.bounce();
                 self.saved_item = Some(cur_item);
                 return true;
@@ -1,12 +1,13 @@
 use crate::{
-    note::NoteRef,
+    note::{app_handlers, app_handlers_filter, NoteAddress, NoteRef},
     notecache::{CachedNote, NoteCache},
+    time::unix_time_secs,
     OneshotApi, Result,
 };
 
 use enostr::{Filter, NoteId, Pubkey};
 use nostr::RelayUrl;
-use nostrdb::{BlockType, Mention, Ndb, Note, NoteKey, Transaction};
+use nostrdb::{BlockType, Mention, Ndb, Note, NoteKey, ProfileRecord, Transaction};
 use std::collections::{HashMap, HashSet};
 use std::time::{Duration, Instant};
 use tracing::error;
@@ -79,12 +80,35 @@ impl SingleUnkIdAction {
     }
 }
 
+/// How old cached profile metadata can get before viewing it refetches it
+pub const DEFAULT_PROFILE_MAX_AGE: Duration = Duration::from_secs(60 * 60 * 24);
+
 /// Unknown Id searcher
-#[derive(Default, Debug)]
+#[derive(Debug)]
 pub struct UnknownIds {
     ids: HashMap<UnknownId, HashSet<RelayUrl>>,
     first_updated: Option<Instant>,
     last_updated: Option<Instant>,
+    profile_max_age: Duration,
+    /// When we last asked for each stale profile, so a profile relays
+    /// don't have a newer version of isn't refetched every frame
+    profile_refreshes: HashMap<Pubkey, Instant>,
+    /// Kinds we've already asked relays for NIP-89 app handlers of. Most
+    /// kinds have none, so each is only asked about once.
+    handler_requests: HashSet<u32>,
+}
+
+impl Default for UnknownIds {
+    fn default() -> Self {
+        Self {
+            ids: HashMap::default(),
+            first_updated: None,
+            last_updated: None,
+            profile_max_age: DEFAULT_PROFILE_MAX_AGE,
+            profile_refreshes: HashMap::default(),
+            handler_requests: HashSet::default(),
+        }
+    }
 }
 
 impl UnknownIds {
@@ -193,6 +217,8 @@ impl UnknownIds {
         match unk_id {
             UnknownId::Pubkey(pk) => self.add_pubkey_if_missing(ndb, txn, pk),
             UnknownId::Id(note_id) => self.add_note_id_if_missing(ndb, txn, note_id.bytes()),
+            UnknownId::Addr(addr) => self.add_address_if_missing(ndb, txn, addr),
+            UnknownId::AppHandlers(kind) => self.add_app_handlers_if_missing(ndb, txn, *kind),
         }
     }
 
@@ -210,6 +236,36 @@ impl UnknownIds {
         self.mark_updated();
     }
 
+    pub fn set_profile_max_age(&mut self, max_age: Duration) {
+        self.profile_max_age = max_age;
+    }
+
+    /// Queue a fetch for a profile the user is looking at when we don't
+    /// have it or our copy is older than the max profile age
+    pub fn refresh_profile_if_stale(&mut self, pubkey: &[u8; 32], profile: Option<&ProfileRecord>) {
+        let received_at = profile.map(|p| p.record().received_at());
+        if received_at.is_some_and(|at| !is_stale(at, unix_time_secs(), self.profile_max_age)) {
+            return;
+        }
+
+        let pubkey = Pubkey::new(*pubkey);
+        if self
+            .profile_refreshes
+            .get(&pubkey)
+            .is_some_and(|asked| asked.elapsed() < self.profile_max_age)
+        {
+            return;
+        }
+        self.profile_refreshes.insert(pubkey, Instant::now());
+
+        let unknown_id = UnknownId::Pubkey(pubkey);
+        if self.ids.contains_key(&unknown_id) {
+            return;
+        }
+        self.ids.entry(unknown_id).or_default();
+        self.mark_updated();
+    }
+
     pub fn add_note_id_if_missing(&mut self, ndb: &Ndb, txn: &Transaction, note_id: &[u8; 32]) {
         // we already have this note, skip
         if ndb.get_note_by_id(txn, note_id).is_ok() {
@@ -223,12 +279,49 @@ impl UnknownIds {
         self.ids.entry(unknown_id).or_default();
         self.mark_updated();
     }
+
+    pub fn add_address_if_missing(&mut self, ndb: &Ndb, txn: &Transaction, addr: &NoteAddress) {
+        // we already have some version of this note, skip
+        if addr.lookup(ndb, txn).is_some() {
+            return;
+        }
+
+        let unknown_id = UnknownId::Addr(addr.clone());
+        if self.ids.contains_key(&unknown_id) {
+            return;
+        }
+        self.ids.entry(unknown_id).or_default();
+        self.mark_updated();
+    }
+
+    /// Ask for the NIP-89 apps that can open notes of `kind`, unless we
+    /// know some already or asked before
+    pub fn add_app_handlers_if_missing(&mut self, ndb: &Ndb, txn: &Transaction, kind: u32) {
+        if !self.handler_requests.insert(kind) {
+            return;
+        }
+
+        if !app_handlers(ndb, txn, kind).is_empty() {
+            return;
+        }
+
+        self.ids.entry(UnknownId::AppHandlers(kind)).or_default();
+        self.mark_updated();
+    }
+}
+
+fn is_stale(received_at: u64, now: u64, max_age: Duration) -> bool {
+    now.saturating_sub(received_at) >= max_age.as_secs()
 }
 
-#[derive(Hash, Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Hash, Clone, PartialEq, Eq, Debug)]
 pub enum UnknownId {
     Pubkey(Pubkey),
     Id(NoteId),
+    /// An addressable note referenced via naddr
+    Addr(NoteAddress),
+    /// NIP-89 announcements of apps that open this kind
+    AppHandlers(u32),
 }
 
 impl UnknownId {
@@ -245,6 +338,20 @@ impl UnknownId {
             _ => None,
         }
     }
+
+    pub fn is_addr(&self) -> Option<&NoteAddress> {
+        match self {
+            UnknownId::Addr(addr) => Some(addr),
+            _ => None,
+        }
+    }
+
+    pub fn is_app_handlers(&self) -> Option<u32> {
+        match self {
+            UnknownId::AppHandlers(kind) => Some(*kind),
+            _ => None,
+        }
+    }
 }
 
 /// Look for missing notes in various parts of notes that we see:
@@ -350,6 +457,26 @@ pub fn get_unknown_note_ids<'a>(
                     }
                 }
             },
+            Mention::Addr(addr) => {
+                let relays = addr
+                    .relays_iter()
+                    .filter_map(|s| RelayUrl::parse(s).ok())
+                    .collect::<HashSet<RelayUrl>>();
+                let address =
+                    NoteAddress::new(addr.kind(), Pubkey::new(*addr.pubkey()), addr.identifier());
+
+                if address.lookup(ndb, txn).is_none() {
+                    ids.entry(UnknownId::Addr(address))
+                        .or_default()
+                        .extend(relays.clone());
+                }
+
+                if ndb.get_profile_by_pubkey(txn, addr.pubkey()).is_err() {
+                    ids.entry(UnknownId::Pubkey(Pubkey::new(*addr.pubkey())))
+                        .or_default()
+                        .extend(relays);
+                }
+            }
             _ => {}
         }
     }
@@ -382,6 +509,13 @@ fn get_unknown_ids_filter(ids: &[&UnknownId]) -> Option<Vec<Filter>> {
         filters.push(Filter::new().ids(note_ids).build());
     }
 
+    filters.extend(ids.iter().filter_map(|id| id.is_addr()).map(|a| a.filter()));
+    filters.extend(
+        ids.iter()
+            .filter_map(|id| id.is_app_handlers())
+            .map(app_handlers_filter),
+    );
+
     Some(filters)
 }
 
@@ -396,3 +530,50 @@ pub fn unknown_id_send(unknown_ids: &mut UnknownIds, oneshot: &mut OneshotApi<'_
     oneshot.oneshot(filter);
     unknown_ids.clear();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_staleness() {
+        let day = Duration::from_secs(60 * 60 * 24);
+        let now = 1_700_000_000;
+
+        assert!(!is_stale(now - 60, now, day));
+        assert!(is_stale(now - day.as_secs(), now, day));
+        // clock skew shouldn't make a profile look stale
+        assert!(!is_stale(now + 60, now, day));
+    }
+
+    #[test]
+    fn missing_profiles_are_refreshed_once() {
+        let mut unknown_ids = UnknownIds::default();
+        let pubkey = [1; 32];
+
+        unknown_ids.refresh_profile_if_stale(&pubkey, None);
+        assert_eq!(unknown_ids.ids_iter().len(), 1);
+
+        unknown_ids.clear();
+        unknown_ids.refresh_profile_if_stale(&pubkey, None);
+        assert_eq!(unknown_ids.ids_iter().len(), 0);
+    }
+    #[test]
+    fn app_handlers_are_asked_for_once() {
+        let tmp = tempfile::TempDir::new().expect("tmp dir");
+        let ndb =
+            Ndb::new(tmp.path().to_str().expect("path"), &nostrdb::Config::new()).expect("ndb");
+        let txn = Transaction::new(&ndb).expect("txn");
+        let mut unknown_ids = UnknownIds::default();
+
+        unknown_ids.add_app_handlers_if_missing(&ndb, &txn, 31337);
+        assert_eq!(
+            unknown_ids.ids_iter().next(),
+            Some(&UnknownId::AppHandlers(31337))
+        );
+
+        unknown_ids.clear();
+        unknown_ids.add_app_handlers_if_missing(&ndb, &txn, 31337);
+        assert_eq!(unknown_ids.ids_iter().len(), 0);
+    }
+}
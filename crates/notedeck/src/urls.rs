@@ -579,8 +579,43 @@ pub fn supported_mime_hosted_at_url(urls: &mut UrlMimes, url: &str) -> Option<Me
         .map(mime_to_cache_type)
 }
 
+/// Whether `url` points at an image or video going by its file extension
+/// alone, without fetching anything
+pub fn is_media_url(url: &str) -> bool {
+    let Ok(url) = Url::parse(url) else {
+        return false;
+    };
+
+    let Some(ext) = url
+        .path_segments()
+        .and_then(|mut path| path.next_back())
+        .and_then(|file_name| std::path::Path::new(file_name).extension())
+        .and_then(|ext| ext.to_str())
+    else {
+        return false;
+    };
+
+    mime_guess::from_ext(ext).first().is_some_and(|mime| {
+        mime.type_() == mime_guess::mime::IMAGE || mime.type_() == mime_guess::mime::VIDEO
+    })
+}
+
 enum MimeHostedAtUrl {
     Yes(MediaCacheType),
     Maybe,
     No,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn media_urls_by_extension() {
+        assert!(is_media_url("https://image.nostr.build/abc.jpg"));
+        assert!(is_media_url("https://v.nostr.build/clip.mp4?download=1"));
+        assert!(!is_media_url("https://example.com/post.html"));
+        assert!(!is_media_url("https://example.com/"));
+        assert!(!is_media_url("picture.png"));
+    }
+}
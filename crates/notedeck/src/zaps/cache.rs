@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use enostr::{NoteId, Pubkey};
 use nostrdb::{Ndb, Transaction};
@@ -11,7 +11,10 @@ use crate::{
     get_wallet_for,
     zaps::{
         get_users_zap_address,
-        networking::{fetch_invoice_promise, FetchedInvoiceResponse, LNUrlPayResponse, PayEntry},
+        networking::{
+            endpoint_url, fetch_invoice_promise, fetch_pay_entry_promise, FetchedInvoiceResponse,
+            FetchingPayEntry, LNUrlPayResponse, PayEntry,
+        },
     },
     Accounts, GlobalWallet, ZapError,
 };
@@ -32,6 +35,9 @@ pub struct Zaps {
     events: Vec<EventResponse>,
 
     pay_cache: PayCache,
+    // payRequest responses fetched only to learn who signs an endpoint's receipts
+    pay_fetches: HashMap<Url, FetchingPayEntry>,
+    failed_pay_fetches: HashSet<Url>,
 }
 
 /// Cache to hold LNURL payRequest responses from the desired LNURL endpoint
@@ -115,21 +121,14 @@ fn process_new_zap_event(
         });
     };
 
-    // TODO(kernelkind): support ZapTarget::Profile
-    let ZapTargetOwned::Note(note_target) = zap_ctx.key.target.clone() else {
-        return NextState::Event(EventResponse {
-            id: zap_ctx.id,
-            event: Err(ZappingError::UnsupportedOperation),
-        });
-    };
-
     let id = zap_ctx.id;
-    let m_promise = send_note_zap(
+    let m_promise = send_target_zap(
         cache,
         ndb,
         txn,
-        note_target,
+        zap_ctx.key.target.clone(),
         zap_ctx.msats,
+        zap_ctx.comment.clone(),
         &full_kp.secret_key.secret_bytes(),
         sender_relays,
     )
@@ -151,25 +150,22 @@ fn process_new_zap_event(
     NextState::Transition(promise)
 }
 
-fn send_note_zap(
+/// Resolve the recipient's zap address and start fetching an invoice for
+/// either a note zap or a profile zap (tip).
+#[allow(clippy::too_many_arguments)]
+fn send_target_zap(
     cache: &PayCache,
     ndb: &Ndb,
     txn: &Transaction,
-    note_target: NoteZapTargetOwned,
+    target: ZapTargetOwned,
     msats: u64,
+    comment: String,
     nsec: &[u8; 32],
     relays: Vec<String>,
 ) -> Result<FetchingInvoice, ZapError> {
-    let address = get_users_zap_address(txn, ndb, &note_target.zap_recipient)?;
+    let address = get_users_zap_address(txn, ndb, target.pubkey())?;
 
-    fetch_invoice_promise(
-        cache,
-        address,
-        msats,
-        *nsec,
-        ZapTargetOwned::Note(note_target),
-        relays,
-    )
+    fetch_invoice_promise(cache, address, msats, comment, *nsec, target, relays)
 }
 
 fn try_get_promise_response(
@@ -239,12 +235,15 @@ impl Zaps {
         self.insert_new_state(&id, &key, ZapState::Pending(Err(error)));
     }
 
+    /// Start a zap to a note or profile. `comment` becomes the content of
+    /// the kind 9734 zap request and may be empty.
     pub fn send_zap(
         &mut self,
         sender_pubkey: &[u8; 32],
         sender_relays: Vec<String>,
         target: ZapTarget,
         msats: u64,
+        comment: String,
     ) {
         let id = self.get_next_id();
         let key = ZapKey {
@@ -256,6 +255,7 @@ impl Zaps {
                 id,
                 key: (&key).into(),
                 msats,
+                comment,
             },
             sender_relays,
         };
@@ -301,6 +301,31 @@ impl Zaps {
             self.events.push(resp.take_as_event_response());
         }
 
+        let fetched: Vec<Url> = self
+            .pay_fetches
+            .iter()
+            .filter(|(_, promise)| promise.ready().is_some())
+            .map(|(url, _)| url.clone())
+            .collect();
+
+        for url in fetched {
+            let Some(promise) = self.pay_fetches.remove(&url) else {
+                continue;
+            };
+
+            match promise.block_and_take() {
+                Ok(Ok(entry)) => self.pay_cache.insert(entry),
+                Ok(Err(e)) => {
+                    tracing::error!("failed to fetch pay request from {url}: {e}");
+                    self.failed_pay_fetches.insert(url);
+                }
+                Err(e) => {
+                    tracing::error!("pay request task for {url} failed: {e}");
+                    self.failed_pay_fetches.insert(url);
+                }
+            }
+        }
+
         while let Some(event_resp) = self.events.pop() {
             let event = match event_resp.event {
                 Ok(ev) => ev,
@@ -335,6 +360,34 @@ impl Zaps {
         }
     }
 
+    /// The pubkey `recipient`'s LNURL endpoint signs zap receipts with.
+    ///
+    /// `None` until the endpoint's payRequest response is known; it's
+    /// fetched in the background the first time it's asked for.
+    pub fn zapper_for(
+        &mut self,
+        ndb: &Ndb,
+        txn: &Transaction,
+        recipient: &Pubkey,
+    ) -> Option<Pubkey> {
+        let address = get_users_zap_address(txn, ndb, recipient).ok()?;
+        let url = endpoint_url(address).ok()?;
+
+        if let Some(response) = self.pay_cache.get_response(&url) {
+            if !response.allow_nostr {
+                return None;
+            }
+            return response.nostr_pubkey.as_ref().ok().copied();
+        }
+
+        if !self.failed_pay_fetches.contains(&url) && !self.pay_fetches.contains_key(&url) {
+            self.pay_fetches
+                .insert(url.clone(), fetch_pay_entry_promise(url));
+        }
+
+        None
+    }
+
     pub fn get_states_for<'a>(
         &'a self,
         sender: &[u8; 32],
@@ -468,6 +521,7 @@ pub struct ZapCtx {
     id: ZapId,
     key: ZapKeyOwned,
     msats: u64,
+    comment: String,
 }
 
 #[derive(Clone, Debug)]
@@ -491,7 +545,7 @@ pub enum ZapEvent {
 pub enum ZappingError {
     InvoiceFetchFailed(ZapError),
     InvalidAccount,
-    UnsupportedOperation, // TODO(kernelkind): support profile zaps
+    UnsupportedOperation,
     InvalidZapAddress,
     SenderNoWallet,
     InvalidNWCResponse(String),
@@ -504,7 +558,7 @@ impl std::fmt::Display for ZappingError {
             ZappingError::InvoiceFetchFailed(err) => write!(f, "Failed to fetch invoice: {err}"),
             ZappingError::InvalidAccount => write!(f, "Invalid account"),
             ZappingError::UnsupportedOperation => {
-                write!(f, "Unsupported operation (e.g. profile zaps)")
+                write!(f, "Unsupported operation")
             }
             ZappingError::InvalidZapAddress => write!(f, "Invalid zap address"),
             ZappingError::SenderNoWallet => write!(f, "Sender has no wallet"),
@@ -587,7 +641,6 @@ pub enum ZapTarget<'a> {
 }
 
 impl ZapTargetOwned {
-    #[allow(dead_code)]
     pub fn pubkey(&self) -> &Pubkey {
         match &self {
             ZapTargetOwned::Profile(pubkey) => pubkey,
@@ -1,6 +1,11 @@
 mod cache;
 mod default_zap;
 mod networking;
+mod preferences;
+#[cfg(test)]
+mod test_utils;
+mod tips;
+mod totals;
 mod zap;
 
 pub use cache::{
@@ -13,6 +18,9 @@ pub use default_zap::{
 };
 use enostr::Pubkey;
 use nostrdb::{Ndb, Transaction};
+pub use preferences::ZapPreferences;
+pub use tips::{get_profile_tips, ProfileTip, ProfileTipsCache};
+pub use totals::note_zap_total_msats;
 
 use crate::ZapError;
 
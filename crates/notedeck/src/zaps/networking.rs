@@ -4,6 +4,7 @@ use crate::{
     ZapError,
 };
 use enostr::{NoteId, Pubkey};
+use lightning_invoice::Bolt11Invoice;
 use nostrdb::NoteBuilder;
 use poll_promise::Promise;
 use serde::Deserialize;
@@ -22,6 +23,8 @@ pub struct FetchedInvoiceResponse {
 
 pub type FetchingInvoice = Promise<Result<FetchedInvoiceResponse, JoinError>>;
 
+pub type FetchingPayEntry = Promise<Result<Result<PayEntry, ZapError>, JoinError>>;
+
 async fn fetch_pay_req_async(url: &Url) -> Result<LNUrlPayResponseRaw, ZapError> {
     let (sender, promise) = Promise::new();
 
@@ -99,12 +102,11 @@ fn make_kind_9734<'a>(
 
 #[derive(Debug, Deserialize)]
 pub struct LNUrlPayResponseRaw {
-    #[allow(dead_code)]
-    #[serde(rename = "allowsNostr")]
+    /// Endpoints that don't support zaps leave these out
+    #[serde(rename = "allowsNostr", default)]
     allow_nostr: bool,
 
-    #[allow(dead_code)]
-    #[serde(rename = "nostrPubkey")]
+    #[serde(rename = "nostrPubkey", default)]
     nostr_pubkey: String,
 
     #[serde(rename = "callback")]
@@ -152,12 +154,51 @@ pub struct PayEntry {
     pub response: LNUrlPayResponse,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug)]
 struct LNInvoice {
-    #[serde(rename = "pr")]
     invoice: String,
 }
 
+/// What a callback answers with: an invoice, or an LNURL error
+#[derive(Debug, Deserialize)]
+struct LNInvoiceResponse {
+    #[serde(rename = "pr")]
+    invoice: Option<String>,
+    status: Option<String>,
+    reason: Option<String>,
+}
+
+impl LNInvoiceResponse {
+    /// The invoice, once it's checked to be for the amount we asked for
+    fn into_invoice(self, msats: u64) -> Result<LNInvoice, ZapError> {
+        if self.status.as_deref() == Some("ERROR") {
+            return Err(ZapError::endpoint_error(
+                self.reason
+                    .unwrap_or_else(|| "endpoint returned an error".to_owned()),
+            ));
+        }
+
+        let Some(invoice) = self.invoice else {
+            return Err(ZapError::endpoint_error(
+                "endpoint did not return an invoice".to_owned(),
+            ));
+        };
+
+        let amount = invoice
+            .parse::<Bolt11Invoice>()
+            .map_err(|e| ZapError::endpoint_error(format!("invalid invoice: {e}")))?
+            .amount_milli_satoshis();
+
+        if amount != Some(msats) {
+            return Err(ZapError::endpoint_error(format!(
+                "invoice is for {amount:?} msats instead of the {msats} requested"
+            )));
+        }
+
+        Ok(LNInvoice { invoice })
+    }
+}
+
 fn endpoint_query_for_invoice(
     endpoint_base_url: &Url,
     msats: u64,
@@ -183,6 +224,7 @@ pub fn fetch_invoice_promise(
     cache: &PayCache,
     zap_address: ZapAddress,
     msats: u64,
+    comment: String,
     sender_nsec: [u8; 32],
     target: ZapTargetOwned,
     relays: Vec<String>,
@@ -205,6 +247,7 @@ pub fn fetch_invoice_promise(
                     &lnurl,
                     PayEntry { url, response },
                     msats,
+                    &comment,
                     &sender_nsec,
                     relays,
                     target,
@@ -227,11 +270,37 @@ pub fn fetch_invoice_promise(
                 }
             };
 
-            fetch_invoice_lnurl_async(&lnurl, pay_req, msats, &sender_nsec, relays, target).await
+            fetch_invoice_lnurl_async(
+                &lnurl,
+                pay_req,
+                msats,
+                &comment,
+                &sender_nsec,
+                relays,
+                target,
+            )
+            .await
         }))),
     }
 }
 
+/// The LNURL payRequest endpoint of `zap_address`
+pub fn endpoint_url(zap_address: ZapAddress) -> Result<Url, ZapError> {
+    match zap_address {
+        ZapAddress::Lud16(lud16) => generate_endpoint_url(&lud16),
+        ZapAddress::Lud06(lnurl) => convert_lnurl_to_endpoint_url(&lnurl),
+    }
+}
+
+/// Fetch the payRequest response of `url` without asking it for an invoice
+pub fn fetch_pay_entry_promise(url: Url) -> FetchingPayEntry {
+    Promise::spawn_async(tokio::spawn(async move {
+        tracing::info!("querying ln endpoint: {url}");
+        let response = fetch_pay_req_async(&url).await?.into();
+        Ok(PayEntry { url, response })
+    }))
+}
+
 fn convert_lnurl_to_endpoint_url(lnurl: &str) -> Result<Url, ZapError> {
     let (_, data) = bech32::decode(lnurl).map_err(|e| ZapError::Bech(e.to_string()))?;
 
@@ -246,6 +315,7 @@ async fn fetch_invoice_lnurl_async(
     lnurl: &str,
     pay_entry: PayEntry,
     msats: u64,
+    comment: &str,
     sender_nsec: &[u8; 32],
     relays: Vec<String>,
     target: ZapTargetOwned,
@@ -297,7 +367,6 @@ async fn fetch_invoice_lnurl_async(
     };
 
     let (query, noteid) = {
-        let comment: &str = "";
         let note = make_kind_9734(lnurl, msats, comment, sender_nsec, relays, target);
         let noteid = NoteId::new(*note.id());
         let query = match endpoint_query_for_invoice(&base_url, msats, lnurl, note) {
@@ -312,7 +381,9 @@ async fn fetch_invoice_lnurl_async(
         (query, noteid)
     };
 
-    let res = fetch_ln_invoice(&query).await;
+    let res = fetch_ln_invoice(&query)
+        .await
+        .and_then(|r| r.into_invoice(msats));
     FetchedInvoiceResponse {
         invoice: res.map(|r| FetchedInvoice {
             invoice: r.invoice,
@@ -322,7 +393,7 @@ async fn fetch_invoice_lnurl_async(
     }
 }
 
-async fn fetch_ln_invoice(req: &Url) -> Result<LNInvoice, ZapError> {
+async fn fetch_ln_invoice(req: &Url) -> Result<LNInvoiceResponse, ZapError> {
     let request = ehttp::Request::get(req);
     let (sender, promise) = Promise::new();
     let on_done = move |response: Result<ehttp::Response, String>| {
@@ -387,7 +458,7 @@ mod tests {
         cache::PayCache,
         networking::{
             convert_lnurl_to_endpoint_url, endpoint_url_to_lnurl, fetch_pay_req_async,
-            generate_endpoint_url,
+            generate_endpoint_url, LNInvoiceResponse, LNUrlPayResponse, LNUrlPayResponseRaw,
         },
     };
 
@@ -446,6 +517,7 @@ mod tests {
                 &mut cache,
                 crate::zaps::ZapAddress::Lud16("jb55@sendsats.lol".to_owned()),
                 1000,
+                String::new(),
                 FullKeypair::generate().secret_key.to_secret_bytes(),
                 crate::zaps::ZapTargetOwned::Note(crate::NoteZapTargetOwned {
                     note_id: NoteId::new([0; 32]),
@@ -493,6 +565,7 @@ mod tests {
                 &mut cache,
                 crate::zaps::ZapAddress::Lud06(lnurl.to_owned()),
                 1000,
+                String::new(),
                 kp.secret_key.to_secret_bytes(),
                 crate::zaps::ZapTargetOwned::Note(crate::NoteZapTargetOwned {
                     note_id: NoteId::new([0; 32]),
@@ -513,4 +586,36 @@ mod tests {
 
         assert!(inner.invoice.starts_with("lnbc"));
     }
+
+    #[test]
+    fn pay_response_without_nostr_support_parses() {
+        let raw: LNUrlPayResponseRaw = serde_json::from_str(
+            r#"{"callback":"https://example.com/cb","minSendable":1000,"maxSendable":2000}"#,
+        )
+        .expect("pay response");
+
+        let response = LNUrlPayResponse::from(raw);
+        assert!(!response.allow_nostr);
+        assert!(response.callback_url.is_ok());
+    }
+
+    #[test]
+    fn invoice_error_keeps_the_reason() {
+        let response: LNInvoiceResponse =
+            serde_json::from_str(r#"{"status":"ERROR","reason":"amount too low"}"#)
+                .expect("error response");
+
+        let Err(crate::ZapError::EndpointError(e)) = response.into_invoice(1000) else {
+            panic!("expected an endpoint error");
+        };
+        assert_eq!(e.0, "amount too low");
+    }
+
+    #[test]
+    fn unreadable_invoice_is_rejected() {
+        let response: LNInvoiceResponse =
+            serde_json::from_str(r#"{"pr":"lnbc1notaninvoice"}"#).expect("invoice response");
+
+        assert!(response.into_invoice(1000).is_err());
+    }
 }
@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+
+/// Per-account zap preferences, stored in the app settings.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ZapPreferences {
+    /// Amount for one-tap zaps. Falls back to the wallet's default zap
+    /// amount when unset.
+    #[serde(default)]
+    pub default_sats: Option<u64>,
+    /// Message attached to zaps that don't specify their own
+    #[serde(default)]
+    pub default_comment: Option<String>,
+    /// One-tap zaps above this amount open the amount sheet first, so they
+    /// can be confirmed or changed.
+    #[serde(default)]
+    pub confirm_over_sats: Option<u64>,
+}
+
+impl ZapPreferences {
+    /// The default amount to zap, given the wallet's default
+    pub fn default_msats(&self, wallet_default_msats: u64) -> u64 {
+        self.default_sats
+            .map(|sats| sats.saturating_mul(1000))
+            .unwrap_or(wallet_default_msats)
+    }
+
+    pub fn needs_confirmation(&self, msats: u64) -> bool {
+        self.confirm_over_sats
+            .is_some_and(|threshold| msats > threshold.saturating_mul(1000))
+    }
+
+    /// `comment` if one was given, otherwise the default comment
+    pub fn comment_or_default(&self, comment: Option<&str>) -> String {
+        comment
+            .or(self.default_comment.as_deref())
+            .unwrap_or_default()
+            .to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preferences_fall_back_to_wallet_and_defaults() {
+        let prefs = ZapPreferences::default();
+        assert_eq!(prefs.default_msats(21_000), 21_000);
+        assert!(!prefs.needs_confirmation(u64::MAX));
+        assert_eq!(prefs.comment_or_default(None), "");
+
+        let prefs = ZapPreferences {
+            default_sats: Some(100),
+            default_comment: Some("gm".to_owned()),
+            confirm_over_sats: Some(1_000),
+        };
+        assert_eq!(prefs.default_msats(21_000), 100_000);
+        assert!(!prefs.needs_confirmation(1_000_000));
+        assert!(prefs.needs_confirmation(1_001_000));
+        assert_eq!(prefs.comment_or_default(None), "gm");
+        assert_eq!(prefs.comment_or_default(Some("nice")), "nice");
+    }
+
+    #[test]
+    fn huge_amounts_saturate() {
+        let prefs = ZapPreferences {
+            default_sats: Some(u64::MAX),
+            default_comment: None,
+            confirm_over_sats: Some(u64::MAX),
+        };
+        assert_eq!(prefs.default_msats(21_000), u64::MAX);
+        assert!(!prefs.needs_confirmation(u64::MAX));
+    }
+}
@@ -0,0 +1,56 @@
+//! Zap receipts for tests
+
+use enostr::FullKeypair;
+use nostrdb::{Note, NoteBuilder};
+
+/// A 33 sat invoice
+pub const BOLT11: &str = "lnbc330n1pn7dlrrpp566sfk69zda849huwjw6wepw3uzxxp4mp9np54qx49ruw8cuv86ushp52te27l4jadsz0u76jvgsk5uekl04tujpjkt9cc7duu0jfzp9zdtscqzzsxqyz5vqsp5m3tzc7ryp5f9fv90v27uyrrd4qfmj5lrwv9rvmvum3v50kdph23s9qxpqysgqut2ssf0m7nmtd73cwqk7qfw4sw6zlj598sjdxmdsepmvn0ptamnhf45c425h26juzcfupegltefwsf8qav2ldell7v9fpc0y23nl0kgqtf432g";
+
+/// JSON of a signed kind 9734 zap request for `recipient`, commented "thanks"
+pub fn zap_request(recipient: &[u8; 32], amount: Option<&str>) -> String {
+    let mut builder = NoteBuilder::new()
+        .kind(9734)
+        .content("thanks")
+        .start_tag()
+        .tag_str("p")
+        .tag_str(&hex::encode(recipient));
+    if let Some(amount) = amount {
+        builder = builder.start_tag().tag_str("amount").tag_str(amount);
+    }
+    builder
+        .sign(&FullKeypair::generate().secret_key.secret_bytes())
+        .build()
+        .unwrap()
+        .json()
+        .unwrap()
+}
+
+/// A kind 9735 receipt for [`BOLT11`] signed by `zapper`
+pub fn receipt(
+    zapper: &FullKeypair,
+    created_at: u64,
+    recipient: &[u8; 32],
+    zapped_note: Option<&[u8; 32]>,
+    zap_req: &str,
+) -> Note<'static> {
+    let mut builder = NoteBuilder::new()
+        .kind(9735)
+        .content("")
+        .created_at(created_at)
+        .start_tag()
+        .tag_str("p")
+        .tag_id(recipient)
+        .start_tag()
+        .tag_str("bolt11")
+        .tag_str(BOLT11)
+        .start_tag()
+        .tag_str("description")
+        .tag_str(zap_req);
+    if let Some(zapped_note) = zapped_note {
+        builder = builder.start_tag().tag_str("e").tag_id(zapped_note);
+    }
+    builder
+        .sign(&zapper.secret_key.secret_bytes())
+        .build()
+        .unwrap()
+}
@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use enostr::Pubkey;
+use nostrdb::{Filter, Ndb, Note, Transaction};
+
+use super::zap::{Zap, ZapTarget};
+use super::Zaps;
+
+/// Receipts fetched for each tip asked for, since receipts zapping notes
+/// are mixed in with profile zaps and get dropped
+const RECEIPTS_PER_TIP: u64 = 20;
+
+/// Most receipts scanned for one profile's tips
+const MAX_TIP_RECEIPTS: u64 = 500;
+
+/// How long cached tips are shown before the db is queried again
+const PROFILE_TIPS_TTL: Duration = Duration::from_secs(10);
+
+/// A profile zap ("tip") received by a user, extracted from a kind 9735
+/// receipt that does not reference a note.
+#[derive(Debug, Clone)]
+pub struct ProfileTip {
+    pub sender: Pubkey,
+    pub msats: u64,
+    pub comment: String,
+    pub created_at: u64,
+}
+
+/// Query the local db for the most recent profile tips sent to `recipient`.
+///
+/// Only receipts signed by `zapper`, the recipient's LNURL `nostrPubkey`,
+/// that pass [`Zap::from_zap_event`] are counted. Receipts that zap a
+/// specific note (`e` tag) are skipped.
+pub fn get_profile_tips(
+    ndb: &Ndb,
+    txn: &Transaction,
+    recipient: &Pubkey,
+    zapper: &Pubkey,
+    limit: u64,
+) -> Vec<ProfileTip> {
+    let receipts = limit.saturating_mul(RECEIPTS_PER_TIP).min(MAX_TIP_RECEIPTS);
+    let filter = Filter::new()
+        .kinds([9735])
+        .pubkeys([recipient.bytes()])
+        .limit(receipts)
+        .build();
+
+    let Ok(results) = ndb.query(txn, &[filter], receipts as i32) else {
+        return Vec::new();
+    };
+
+    newest_tips(results.iter().map(|r| &r.note), zapper, limit)
+}
+
+/// The newest `limit` profile tips among `receipts`
+fn newest_tips<'a, 'n: 'a>(
+    receipts: impl Iterator<Item = &'a Note<'n>>,
+    zapper: &Pubkey,
+    limit: u64,
+) -> Vec<ProfileTip> {
+    let mut tips: Vec<ProfileTip> = receipts
+        .filter_map(|receipt| profile_tip_from_receipt(receipt, zapper))
+        .collect();
+
+    tips.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    tips.truncate(limit as usize);
+    tips
+}
+
+struct CachedTips {
+    tips: Vec<ProfileTip>,
+    queried_at: Instant,
+}
+
+/// Per-pubkey cache of [`get_profile_tips`], so profile views don't query
+/// receipts every frame
+#[derive(Default)]
+pub struct ProfileTipsCache {
+    cache: HashMap<Pubkey, CachedTips>,
+}
+
+impl ProfileTipsCache {
+    /// The newest `limit` tips sent to `recipient`, queried again once
+    /// they're a few seconds old. Empty until the recipient's zapper is
+    /// known, see [`Zaps::zapper_for`].
+    pub fn get(
+        &mut self,
+        ndb: &Ndb,
+        txn: &Transaction,
+        zaps: &mut Zaps,
+        recipient: &Pubkey,
+        limit: u64,
+    ) -> &[ProfileTip] {
+        let Some(zapper) = zaps.zapper_for(ndb, txn, recipient) else {
+            return &[];
+        };
+
+        let stale = self
+            .cache
+            .get(recipient)
+            .is_none_or(|cached| cached.queried_at.elapsed() >= PROFILE_TIPS_TTL);
+
+        if stale {
+            self.cache
+                .retain(|_, cached| cached.queried_at.elapsed() < PROFILE_TIPS_TTL);
+            self.cache.insert(
+                *recipient,
+                CachedTips {
+                    tips: get_profile_tips(ndb, txn, recipient, &zapper, limit),
+                    queried_at: Instant::now(),
+                },
+            );
+        }
+
+        self.cache
+            .get(recipient)
+            .map_or(&[], |cached| cached.tips.as_slice())
+    }
+}
+
+fn profile_tip_from_receipt(receipt: &Note, zapper: &Pubkey) -> Option<ProfileTip> {
+    let zap = Zap::from_zap_event(receipt, zapper)?;
+    if !matches!(zap.target, ZapTarget::Profile(_)) {
+        return None;
+    }
+
+    Some(ProfileTip {
+        sender: zap.requester,
+        msats: zap.msats,
+        comment: zap.comment,
+        created_at: receipt.created_at(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zaps::test_utils::{receipt, zap_request};
+    use enostr::FullKeypair;
+
+    const RECIPIENT: [u8; 32] = [1; 32];
+
+    #[test]
+    fn note_zaps_dont_crowd_out_profile_tips() {
+        let zapper = FullKeypair::generate();
+        let zap_req = zap_request(&RECIPIENT, None);
+
+        // newest first, like a query returns them
+        let receipts = [
+            receipt(&zapper, 6, &RECIPIENT, Some(&[2; 32]), &zap_req),
+            receipt(&zapper, 5, &RECIPIENT, None, &zap_req),
+            receipt(&zapper, 4, &RECIPIENT, Some(&[2; 32]), &zap_req),
+            receipt(&zapper, 3, &RECIPIENT, Some(&[3; 32]), &zap_req),
+            receipt(&zapper, 2, &RECIPIENT, None, &zap_req),
+            receipt(&zapper, 1, &RECIPIENT, None, &zap_req),
+        ];
+
+        let tips = newest_tips(receipts.iter(), &zapper.pubkey, 2);
+        let times: Vec<u64> = tips.iter().map(|tip| tip.created_at).collect();
+        assert_eq!(times, vec![5, 2]);
+        assert!(tips.iter().all(|tip| tip.msats == 33_000));
+        assert!(tips.iter().all(|tip| tip.comment == "thanks"));
+    }
+
+    #[test]
+    fn forged_receipts_are_not_tips() {
+        let zapper = FullKeypair::generate();
+        let forger = FullKeypair::generate();
+
+        let receipts = [
+            // signed by someone other than the recipient's zapper
+            receipt(&forger, 4, &RECIPIENT, None, &zap_request(&RECIPIENT, None)),
+            // the request zapped someone else
+            receipt(&zapper, 3, &RECIPIENT, None, &zap_request(&[9; 32], None)),
+            // the request asked for a different amount than was invoiced
+            receipt(
+                &zapper,
+                2,
+                &RECIPIENT,
+                None,
+                &zap_request(&RECIPIENT, Some("1000")),
+            ),
+            receipt(
+                &zapper,
+                1,
+                &RECIPIENT,
+                None,
+                &zap_request(&RECIPIENT, Some("33000")),
+            ),
+        ];
+
+        let tips = newest_tips(receipts.iter(), &zapper.pubkey, 10);
+        let times: Vec<u64> = tips.iter().map(|tip| tip.created_at).collect();
+        assert_eq!(times, vec![1]);
+    }
+}
@@ -0,0 +1,81 @@
+use enostr::Pubkey;
+use nostrdb::{Filter, Ndb, Note, Transaction};
+
+use super::zap::{Zap, ZapTarget};
+
+/// Most receipts counted toward the zap total of a note
+const MAX_NOTE_ZAP_RECEIPTS: i32 = 500;
+
+/// Query the local db for how much `note_id` was zapped, in msats.
+///
+/// Only receipts signed by `zapper`, the LNURL `nostrPubkey` of the note's
+/// author, that pass [`Zap::from_zap_event`] are counted.
+pub fn note_zap_total_msats(
+    ndb: &Ndb,
+    txn: &Transaction,
+    note_id: &[u8; 32],
+    zapper: &Pubkey,
+) -> u64 {
+    let filter = Filter::new()
+        .kinds([9735])
+        .event(note_id)
+        .limit(MAX_NOTE_ZAP_RECEIPTS as u64)
+        .build();
+
+    let Ok(results) = ndb.query(txn, &[filter], MAX_NOTE_ZAP_RECEIPTS) else {
+        return 0;
+    };
+
+    total_msats(results.iter().map(|r| &r.note), note_id, zapper)
+}
+
+fn total_msats<'a, 'n: 'a>(
+    receipts: impl Iterator<Item = &'a Note<'n>>,
+    note_id: &[u8; 32],
+    zapper: &Pubkey,
+) -> u64 {
+    receipts
+        .filter_map(|receipt| Zap::from_zap_event(receipt, zapper))
+        .filter(|zap| match &zap.target {
+            ZapTarget::Note(target) => target.note_id.bytes() == note_id,
+            ZapTarget::Profile(_) => false,
+        })
+        .map(|zap| zap.msats)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zaps::test_utils::{receipt, zap_request};
+    use enostr::FullKeypair;
+
+    const AUTHOR: [u8; 32] = [1; 32];
+    const NOTE: [u8; 32] = [2; 32];
+
+    #[test]
+    fn only_valid_receipts_count_toward_the_total() {
+        let zapper = FullKeypair::generate();
+        let forger = FullKeypair::generate();
+        let zap_req = zap_request(&AUTHOR, None);
+
+        let receipts = [
+            receipt(&zapper, 1, &AUTHOR, Some(&NOTE), &zap_req),
+            receipt(&zapper, 2, &AUTHOR, Some(&NOTE), &zap_req),
+            // signed by someone other than the author's zapper
+            receipt(&forger, 3, &AUTHOR, Some(&NOTE), &zap_req),
+            // the request zapped someone else
+            receipt(
+                &zapper,
+                4,
+                &AUTHOR,
+                Some(&NOTE),
+                &zap_request(&[9; 32], None),
+            ),
+            // zaps another note
+            receipt(&zapper, 5, &AUTHOR, Some(&[3; 32]), &zap_req),
+        ];
+
+        assert_eq!(total_msats(receipts.iter(), &NOTE, &zapper.pubkey), 66_000);
+    }
+}
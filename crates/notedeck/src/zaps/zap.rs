@@ -24,11 +24,20 @@ pub struct Zap {
     pub sender: Pubkey,
     pub target: ZapTarget,
     pub invoice: Bolt11Invoice,
+    pub msats: u64,
+    /// Author of the zap request, the person who paid
+    pub requester: Pubkey,
+    /// Content of the zap request
+    pub comment: String,
 }
 
-#[allow(dead_code)]
 impl Zap {
-    pub fn from_zap_event(zap_event: nostrdb::Note, sender: &Pubkey) -> Option<Self> {
+    /// Validate a kind 9735 receipt that `sender`, the recipient's LNURL
+    /// `nostrPubkey`, is expected to have signed.
+    ///
+    /// The embedded zap request must be signed, and its `p` tag and
+    /// `amount` (if any) must match the receipt.
+    pub fn from_zap_event(zap_event: &nostrdb::Note, sender: &Pubkey) -> Option<Self> {
         if sender.bytes() != zap_event.pubkey() {
             // Make sure that we only create a zap event if it is authorized by the profile or event
             return None;
@@ -38,7 +47,7 @@ impl Zap {
         let invoice = zap_tags.bolt11.parse::<Bolt11Invoice>().ok()?;
 
         // invoice must be specific
-        invoice.amount_milli_satoshis()?;
+        let msats = invoice.amount_milli_satoshis()?;
 
         if let Some(preimage) = zap_tags.preimage {
             if !preimage_matches_invoice(&invoice, preimage) {
@@ -50,7 +59,7 @@ impl Zap {
             return None;
         };
 
-        if !valid_zap_request(zap_req) {
+        if !valid_zap_request(&zap_req) || !request_matches(&zap_req, &zap_tags, msats) {
             return None;
         }
 
@@ -60,10 +69,44 @@ impl Zap {
             sender: *sender,
             target: zap_target,
             invoice,
+            msats,
+            requester: zap_req.pubkey,
+            comment: zap_req.content,
         })
     }
 }
 
+/// Whether the zap request asked to zap who the receipt says was paid, for
+/// what the invoice is for
+fn request_matches(zap_req: &enostr::Note, tags: &ZapTags, msats: u64) -> bool {
+    let mut recipient = None;
+    let mut amount = None;
+
+    for tag in &zap_req.tags {
+        match tag.as_slice() {
+            [name, value, ..] if name == "p" => {
+                if recipient.is_some() {
+                    // a zap request has exactly one recipient
+                    return false;
+                }
+                recipient = Some(value);
+            }
+            [name, value, ..] if name == "amount" => amount = Some(value),
+            _ => {}
+        }
+    }
+
+    let Some(recipient) = recipient.and_then(|pk| Pubkey::from_hex(pk).ok()) else {
+        return false;
+    };
+
+    if recipient.bytes() != tags.recipient {
+        return false;
+    }
+
+    amount.is_none_or(|amount| amount.parse::<u64>().ok() == Some(msats))
+}
+
 fn determine_zap_target(tags: &ZapTags) -> Option<ZapTarget> {
     if let Some(note_zapped) = tags.note_zapped {
         Some(ZapTarget::Note(NoteZapTarget {
@@ -97,15 +140,13 @@ pub fn event_commitment(
 }
 
 // TODO(kernelkind): i think we may be able to validate just with the nostrdb::Note. Not exactly sure yet how though
-fn valid_zap_request(note: enostr::Note) -> bool {
-    let sig = note.sig.clone();
-
+fn valid_zap_request(note: &enostr::Note) -> bool {
     let commitment = event_commitment(
         note.pubkey,
         note.created_at,
         note.kind,
-        note.tags,
-        note.content,
+        note.tags.clone(),
+        note.content.clone(),
     );
 
     let commitment_bytes = commitment.as_bytes();
@@ -116,7 +157,7 @@ fn valid_zap_request(note: enostr::Note) -> bool {
         return false;
     }
 
-    let Ok(sig_bytes) = hex::decode(sig) else {
+    let Ok(sig_bytes) = hex::decode(&note.sig) else {
         return false;
     };
 
@@ -175,7 +216,7 @@ struct ZapTags<'a> {
     pub recipient: &'a [u8; 32],
     pub note_zapped: Option<&'a [u8; 32]>,
 }
-fn get_zap_tags(ev: nostrdb::Note) -> Option<ZapTags> {
+fn get_zap_tags<'a>(ev: &'a nostrdb::Note) -> Option<ZapTags<'a>> {
     let mut bolt11 = None;
     let mut preimage = None;
     let mut description = None;
@@ -241,7 +282,7 @@ mod tests {
     fn test_valid_zap_req() {
         let note = enostr::Note::from_json(ZAP_REQ).unwrap();
 
-        assert!(valid_zap_request(note));
+        assert!(valid_zap_request(&note));
     }
 
     #[tokio::test]
@@ -272,7 +313,7 @@ mod tests {
                 .bytes()
         );
 
-        let zap = Zap::from_zap_event(note, &pk);
+        let zap = Zap::from_zap_event(&note, &pk);
 
         assert!(zap.is_some());
     }
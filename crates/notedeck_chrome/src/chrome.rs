@@ -20,6 +20,7 @@ use notedeck::ui::is_compiled_as_mobile;
 use notedeck::AppResponse;
 use notedeck::DrawerRouter;
 use notedeck::Error;
+use notedeck::OsNotificationKind;
 use notedeck::SoftKeyboardContext;
 use notedeck::{
     tr, App, AppAction, AppContext, Localization, Notedeck, NotedeckOptions, NotedeckTextStyle,
@@ -422,6 +423,26 @@ impl Chrome {
         }
     }
 
+    /// Bring the window forward on what a clicked system notification was about
+    fn open_os_notification(
+        &mut self,
+        kind: OsNotificationKind,
+        ctx: &mut AppContext,
+        egui_ctx: &egui::Context,
+    ) {
+        egui_ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+
+        match kind {
+            OsNotificationKind::Mention => {
+                self.process_toolbar_action(ChromeToolbarAction::Notifications, ctx);
+            }
+            OsNotificationKind::DirectMessage => {
+                #[cfg(feature = "messages")]
+                self.process_toolbar_action(ChromeToolbarAction::Chat, ctx);
+            }
+        }
+    }
+
     /// Returns which ChromeToolbarAction is currently "active" based on
     /// the active app and its route. Used to highlight the current tab.
     fn active_toolbar_tab(&self, accounts: &notedeck::Accounts) -> Option<ChromeToolbarAction> {
@@ -702,16 +723,20 @@ fn update_sidebar_item_ui(
 }
 
 impl notedeck::App for Chrome {
-    fn update(&mut self, ctx: &mut notedeck::AppContext, _egui_ctx: &egui::Context) {
+    fn update(&mut self, ctx: &mut notedeck::AppContext, egui_ctx: &egui::Context) {
         #[cfg(feature = "auto-update")]
         poll_updater(&mut self.updater, ctx);
 
+        while let Some(kind) = ctx.os_notifications.take_clicked() {
+            self.open_os_notification(kind, ctx, egui_ctx);
+        }
+
         // Update opened apps every frame so background processing
         // (relay pools, subscriptions, etc.) stays alive.
         // Apps that haven't been opened yet are skipped.
         for (i, app) in self.apps.iter_mut().enumerate() {
             if self.opened.get(i).copied().unwrap_or(false) {
-                app.update(ctx, _egui_ctx);
+                app.update(ctx, egui_ctx);
             }
         }
     }
@@ -1024,6 +1049,7 @@ fn chrome_handle_app_action(
                 ctx.accounts,
                 ctx.global_wallet,
                 ctx.zaps,
+                ctx.settings,
                 ctx.img_cache,
                 &mut columns.view_state,
                 ctx.media_jobs.sender(),
@@ -1081,6 +1107,7 @@ fn columns_route_to_profile(
         ctx.accounts,
         ctx.global_wallet,
         ctx.zaps,
+        ctx.settings,
         ctx.img_cache,
         &mut columns.view_state,
         ctx.media_jobs.sender(),
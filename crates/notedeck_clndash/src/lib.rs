@@ -10,7 +10,7 @@ use crate::invoice::Invoice;
 use crate::summary::Summary;
 use crate::watch::fetch_paid_invoices;
 
-use lnsocket::bitcoin::secp256k1::{PublicKey, SecretKey, rand};
+use lnsocket::bitcoin::secp256k1::{rand, PublicKey, SecretKey};
 use lnsocket::{CommandoClient, LNSocket};
 use nostrdb::Ndb;
 use notedeck::AppContext;
@@ -19,7 +19,7 @@ use serde_json::json;
 use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::Arc;
-use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
 
 mod channels;
 mod event;
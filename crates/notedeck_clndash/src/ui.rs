@@ -51,9 +51,11 @@ pub fn note_hover_ui(
             jobs: ctx.media_jobs.sender(),
             unknown_ids: ctx.unknown_ids,
             nip05_cache: ctx.nip05_cache,
+            profile_stats: ctx.profile_stats,
             clipboard: ctx.clipboard,
             i18n: ctx.i18n,
             global_wallet: ctx.global_wallet,
+            action_bar: ctx.settings.action_bar(),
         };
 
         let options = notedeck_ui::NoteOptions::default();
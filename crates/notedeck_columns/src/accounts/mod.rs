@@ -1,14 +1,17 @@
 use enostr::{FullKeypair, Pubkey};
 use nostrdb::{Ndb, Transaction};
 
-use notedeck::{Accounts, AppContext, DragResponse, Localization, SingleUnkIdAction, UnknownIds};
+use notedeck::{
+    AccountScrub, Accounts, AddAccountResponse, AppContext, DragResponse, Localization,
+    SingleUnkIdAction, UnknownIds,
+};
 use notedeck_ui::nip51_set::Nip51SetUiCache;
 
 pub use crate::accounts::route::AccountsResponse;
 use crate::app::get_active_columns_mut;
 use crate::decks::DecksCache;
 use crate::onboarding::{Onboarding, OnboardingEffect};
-use crate::profile::{send_default_dms_relay_list, send_new_contact_list};
+use crate::profile::{send_default_dms_relay_list, send_new_contact_list, send_new_profile};
 use crate::scoped_sub_owner_keys::onboarding_owner_key;
 use crate::ui::onboarding::{FollowPackOnboardingView, FollowPacksResponse, OnboardingResponse};
 use crate::{
@@ -62,7 +65,7 @@ impl SwitchAccountAction {
 #[derive(Debug)]
 pub enum AccountsAction {
     Switch(SwitchAccountAction),
-    Remove(Pubkey),
+    Remove(Pubkey, AccountScrub),
 }
 
 #[must_use = "You must call process_login_action on this to handle unknown ids"]
@@ -126,14 +129,34 @@ pub fn process_accounts_view_response(
     decks: &mut DecksCache,
     col: usize,
     response: AccountsViewResponse,
-) -> Option<AccountsAction> {
+) -> AddAccountAction {
+    if let AccountsViewResponse::ViewAs(pubkey) = response {
+        info!("viewing as {pubkey}");
+        let Some(resp) = accounts.add_ghost_account(pubkey) else {
+            // already one of our accounts
+            return AddAccountAction {
+                accounts_action: Some(AccountsAction::Switch(SwitchAccountAction::new(
+                    col, pubkey,
+                ))),
+                unk_id_action: SingleUnkIdAction::NoAction,
+            };
+        };
+
+        return AddAccountAction {
+            accounts_action: Some(AccountsAction::Switch(
+                SwitchAccountAction::new(col, resp.switch_to).switching_to_new(),
+            )),
+            unk_id_action: resp.unk_id_action,
+        };
+    }
+
     let router = get_active_columns_mut(i18n, accounts, decks)
         .column_mut(col)
         .router_mut();
     let mut action = None;
     match response {
-        AccountsViewResponse::RemoveAccount(pk_to_remove) => {
-            let cur_action = AccountsAction::Remove(pk_to_remove);
+        AccountsViewResponse::RemoveAccount(pk_to_remove, scrub) => {
+            let cur_action = AccountsAction::Remove(pk_to_remove, scrub);
             info!("account selection: {:?}", action);
             action = Some(cur_action);
         }
@@ -145,8 +168,13 @@ pub fn process_accounts_view_response(
         AccountsViewResponse::RouteToLogin => {
             router.route_to(Route::add_account());
         }
+        AccountsViewResponse::ViewAs(_) => {}
+    }
+
+    AddAccountAction {
+        accounts_action: action,
+        unk_id_action: SingleUnkIdAction::NoAction,
     }
-    action
 }
 
 pub fn process_login_view_response(
@@ -178,25 +206,14 @@ pub fn process_login_view_response(
             }
             FollowPacksResponse::UserSelectedPacks(nip51_sets_ui_state) => {
                 let pks_to_follow = nip51_sets_ui_state.get_all_selected();
+                let display_name = onboarding.display_name.trim().to_owned();
 
-                let kp = FullKeypair::generate();
-
-                {
-                    let mut publisher = app_ctx.remote.publisher(app_ctx.accounts);
-                    send_new_contact_list(
-                        kp.to_filled(),
-                        app_ctx.ndb,
-                        &mut publisher,
-                        pks_to_follow,
-                    );
-                    send_default_dms_relay_list(kp.to_filled(), app_ctx.ndb, &mut publisher);
-                }
                 cur_router.go_back();
                 onboarding.end_onboarding(app_ctx.ndb);
                 let mut scoped_subs = app_ctx.remote.scoped_subs(app_ctx.accounts);
                 let _ = scoped_subs.drop_owner(onboarding_owner_key(col));
 
-                app_ctx.accounts.add_account(kp.to_keypair())
+                create_account(app_ctx, pks_to_follow, &display_name)
             }
         },
     };
@@ -218,6 +235,28 @@ pub fn process_login_view_response(
     }
 }
 
+/// Generate a new keypair, publish its initial contact list, DM relay list
+/// and (if `display_name` isn't empty) profile, then store it as an account.
+pub fn create_account(
+    app_ctx: &mut AppContext,
+    pks_to_follow: Vec<Pubkey>,
+    display_name: &str,
+) -> Option<AddAccountResponse> {
+    let kp = FullKeypair::generate();
+
+    {
+        let mut publisher = app_ctx.remote.publisher(app_ctx.accounts);
+        send_new_contact_list(kp.to_filled(), app_ctx.ndb, &mut publisher, pks_to_follow);
+        send_default_dms_relay_list(kp.to_filled(), app_ctx.ndb, &mut publisher);
+
+        if !display_name.is_empty() {
+            send_new_profile(kp.to_filled(), app_ctx.ndb, &mut publisher, display_name);
+        }
+    }
+
+    app_ctx.accounts.add_account(kp.to_keypair())
+}
+
 fn process_onboarding_step(app_ctx: &mut AppContext, onboarding: &mut Onboarding, col: usize) {
     let owner = onboarding_owner_key(col);
     let effect = {
@@ -239,19 +278,13 @@ impl AccountsRouteResponse {
         col: usize,
     ) -> AddAccountAction {
         match self {
-            AccountsRouteResponse::Accounts(response) => {
-                let action = process_accounts_view_response(
-                    app_ctx.i18n,
-                    app_ctx.accounts,
-                    &mut app.decks_cache,
-                    col,
-                    response,
-                );
-                AddAccountAction {
-                    accounts_action: action,
-                    unk_id_action: notedeck::SingleUnkIdAction::no_action(),
-                }
-            }
+            AccountsRouteResponse::Accounts(response) => process_accounts_view_response(
+                app_ctx.i18n,
+                app_ctx.accounts,
+                &mut app.decks_cache,
+                col,
+                response,
+            ),
             AccountsRouteResponse::AddAccount(response) => {
                 let action = process_login_view_response(
                     app_ctx,
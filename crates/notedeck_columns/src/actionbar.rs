@@ -12,14 +12,14 @@ use crate::{
 };
 
 use egui_nav::Percent;
-use enostr::{FilledKeypair, NoteId, Pubkey};
+use enostr::{FilledKeypair, NormRelayUrl, NoteId, Pubkey, RelayId};
 use nostrdb::{IngestMetadata, Ndb, NoteBuilder, NoteKey, Transaction};
 use notedeck::{
-    get_wallet_for, is_future_timestamp,
+    get_wallet_for, is_expired, is_future_timestamp,
     note::{reaction_sent_id, ReactAction, ZapTargetAmount},
-    unix_time_secs, Accounts, GlobalWallet, Images, MediaJobSender, NoteAction, NoteCache,
-    NoteZapTargetOwned, PublishApi, RelayType, RemoteApi, UnknownIds, ZapAction, ZapTarget,
-    ZappingError, Zaps,
+    unix_time_secs, Accounts, GlobalWallet, Images, MediaJobSender, NoteAction, NoteCache, Poll,
+    PollVote, PublishApi, RelayType, RemoteApi, SettingsHandler, UnknownIds, ZapAction,
+    ZapPreferences, ZapTargetOwned, ZappingError, Zaps, POLL_RESPONSE_KIND,
 };
 use notedeck_ui::media::MediaViewerFlags;
 use tracing::error;
@@ -57,6 +57,7 @@ fn execute_note_action(
     accounts: &mut Accounts,
     global_wallet: &mut GlobalWallet,
     zaps: &mut Zaps,
+    settings: &SettingsHandler,
     images: &mut Images,
     view_state: &mut ViewState,
     router_type: RouterType,
@@ -98,6 +99,23 @@ fn execute_note_action(
                 router_action = Some(RouterAction::route_to(Route::accounts()));
             }
         }
+        NoteAction::Vote(vote) => {
+            if let Some(filled) = accounts.selected_filled() {
+                let mut publisher = remote.publisher(&*accounts);
+                match send_poll_response(ndb, txn, &mut publisher, filled, &vote) {
+                    // count the new vote right away instead of waiting for
+                    // the tally to go stale
+                    Ok(poll_key) => {
+                        if let Some(cached) = note_cache.cache_mut().get_mut(&poll_key) {
+                            cached.poll_tally = None;
+                        }
+                    }
+                    Err(err) => tracing::error!("Failed to send poll response: {err}"),
+                }
+            } else {
+                router_action = Some(RouterAction::route_to(Route::accounts()));
+            }
+        }
         NoteAction::Profile(pubkey) => {
             let kind = TimelineKind::Profile(pubkey);
             router_action = Some(RouterAction::route_to(Route::Timeline(kind.clone())));
@@ -144,6 +162,25 @@ fn execute_note_action(
                 make_new: preview,
             });
         }
+        NoteAction::Permalink(note_id) => {
+            router_action = Some(RouterAction::route_to(Route::note(note_id)));
+        }
+        NoteAction::Address(address) => {
+            let kind = TimelineKind::address(address);
+            router_action = Some(RouterAction::route_to(Route::Timeline(kind.clone())));
+            let mut scoped_subs = remote.scoped_subs(&*accounts);
+            timeline_res = timeline_cache
+                .open(
+                    ndb,
+                    note_cache,
+                    txn,
+                    &mut scoped_subs,
+                    &kind,
+                    *accounts.selected_account_pubkey(),
+                    false,
+                )
+                .map(NotesOpenResult::Timeline);
+        }
         NoteAction::Hashtag(htag) => {
             let kind = TimelineKind::Hashtag(vec![htag.clone()]);
             router_action = Some(RouterAction::route_to(Route::Timeline(kind.clone())));
@@ -181,31 +218,31 @@ fn execute_note_action(
                     else {
                         zaps.send_error(
                             sender.bytes(),
-                            ZapTarget::Note((&target.target).into()),
+                            (&target.target).into(),
                             ZappingError::SenderNoWallet,
                         );
                         break 'a;
                     };
 
+                    let prefs = settings.zap_preferences(&sender);
+                    let default_msats =
+                        prefs.default_msats(wallet.default_zap.get_default_zap_msats());
+
+                    // large one-tap zaps go through the amount sheet first
+                    if target.specified_msats.is_none() && prefs.needs_confirmation(default_msats) {
+                        router_action = Some(customize_zap_amount(&target.target));
+                        break 'a;
+                    }
+
                     if let RouterType::Sheet(_) = router_type {
                         router_action = Some(RouterAction::GoBack);
                     }
 
-                    send_zap(
-                        &sender,
-                        zaps,
-                        accounts,
-                        target,
-                        wallet.default_zap.get_default_zap_msats(),
-                    )
+                    send_zap(&sender, zaps, accounts, target, default_msats, &prefs)
                 }
                 ZapAction::ClearError(target) => clear_zap_error(&sender, zaps, target),
                 ZapAction::CustomizeAmount(target) => {
-                    let route = Route::CustomizeZapAmount(target.to_owned());
-                    router_action = Some(RouterAction::route_to_sheet(
-                        route,
-                        egui_nav::Split::PercentFromTop(Percent::new(35).expect("35 <= 100")),
-                    ));
+                    router_action = Some(customize_zap_amount(target));
                 }
             }
         }
@@ -221,10 +258,26 @@ fn execute_note_action(
                         Route::Report(target),
                         egui_nav::Split::AbsoluteFromBottom(300.0),
                     ));
+                } else if let notedeck::NoteContextSelection::ExportThread(export) = context.action
+                {
+                    crate::thread_export::export_thread(ui.ctx(), ndb, txn, &note, export);
                 } else {
-                    context
-                        .action
-                        .process_selection(ui, &note, ndb, remote, txn, accounts);
+                    context.action.process_selection(
+                        ui,
+                        &note,
+                        ndb,
+                        remote,
+                        txn,
+                        accounts,
+                        settings.open_with_template(),
+                    );
+
+                    // show the tombstone now rather than on the next check
+                    if context.action == notedeck::NoteContextSelection::Delete {
+                        if let Some(cached) = note_cache.cache_mut().get_mut(&context.note_key) {
+                            cached.deletion = None;
+                        }
+                    }
                 }
             }
         },
@@ -264,6 +317,7 @@ pub fn execute_and_process_note_action(
     accounts: &mut Accounts,
     global_wallet: &mut GlobalWallet,
     zaps: &mut Zaps,
+    settings: &SettingsHandler,
     images: &mut Images,
     view_state: &mut ViewState,
     jobs: &MediaJobSender,
@@ -290,6 +344,7 @@ pub fn execute_and_process_note_action(
         accounts,
         global_wallet,
         zaps,
+        settings,
         images,
         view_state,
         router_type,
@@ -328,7 +383,7 @@ fn send_reaction_event(
     let target_kind = note.kind();
     let d_tag_value = find_addressable_d_tag(&note);
 
-    let mut builder = NoteBuilder::new().kind(7).content(reaction.content);
+    let mut builder = NoteBuilder::new().kind(7).content(&reaction.content);
 
     builder = builder
         .start_tag()
@@ -362,6 +417,14 @@ fn send_reaction_event(
         .tag_str("k")
         .tag_str(&target_kind.to_string());
 
+    if let Some(emoji) = &reaction.emoji {
+        builder = builder
+            .start_tag()
+            .tag_str("emoji")
+            .tag_str(&emoji.shortcode)
+            .tag_str(&emoji.url);
+    }
+
     let note = builder
         .sign(&kp.secret_key.secret_bytes())
         .build()
@@ -382,6 +445,63 @@ fn send_reaction_event(
     Ok(())
 }
 
+/// Publish a NIP-88 response to a poll, returning the poll's note key
+fn send_poll_response(
+    ndb: &mut Ndb,
+    txn: &Transaction,
+    publisher: &mut PublishApi<'_, '_>,
+    kp: FilledKeypair<'_>,
+    vote: &PollVote,
+) -> Result<NoteKey, String> {
+    let Ok(poll_note) = ndb.get_note_by_id(txn, vote.poll_id.bytes()) else {
+        return Err(format!("poll {:?} not found in ndb", vote.poll_id));
+    };
+    let Some(poll) = Poll::from_note(&poll_note) else {
+        return Err(format!("note {:?} is not a poll", vote.poll_id));
+    };
+    let poll_key = poll_note.key().ok_or("poll note has no key")?;
+
+    let mut builder = NoteBuilder::new()
+        .kind(POLL_RESPONSE_KIND)
+        .content("")
+        .start_tag()
+        .tag_str("e")
+        .tag_id(vote.poll_id.bytes())
+        .start_tag()
+        .tag_str("p")
+        .tag_id(poll_note.pubkey());
+
+    for option in &vote.options {
+        builder = builder.start_tag().tag_str("response").tag_str(option);
+    }
+
+    let note = builder
+        .sign(&kp.secret_key.secret_bytes())
+        .build()
+        .ok_or_else(|| "failed to build poll response".to_owned())?;
+
+    let json = enostr::ClientMessage::event(&note)
+        .and_then(|event| event.to_json())
+        .map_err(|_| "failed to serialize poll response to json".to_owned())?;
+    let _ = ndb.process_event_with(&json, IngestMetadata::new().client(true));
+
+    // votes are counted on the relays the poll asks for, when it names any
+    let poll_relays: Vec<RelayId> = poll
+        .relays
+        .iter()
+        .filter_map(|relay| NormRelayUrl::new(relay).ok())
+        .map(RelayId::Websocket)
+        .collect();
+    let relays = if poll_relays.is_empty() {
+        RelayType::AccountsWrite
+    } else {
+        RelayType::Explicit(poll_relays)
+    };
+    publisher.publish_note(&note, relays);
+
+    Ok(poll_key)
+}
+
 fn find_addressable_d_tag(note: &nostrdb::Note<'_>) -> Option<String> {
     for tag in note.tags() {
         if tag.count() < 2 {
@@ -400,14 +520,22 @@ fn find_addressable_d_tag(note: &nostrdb::Note<'_>) -> Option<String> {
     None
 }
 
+fn customize_zap_amount(target: &ZapTargetOwned) -> RouterAction {
+    RouterAction::route_to_sheet(
+        Route::CustomizeZapAmount(target.to_owned()),
+        egui_nav::Split::PercentFromTop(Percent::new(35).expect("35 <= 100")),
+    )
+}
+
 fn send_zap(
     sender: &Pubkey,
     zaps: &mut Zaps,
     accounts: &Accounts,
     target_amount: &ZapTargetAmount,
     default_msats: u64,
+    prefs: &ZapPreferences,
 ) {
-    let zap_target = ZapTarget::Note((&target_amount.target).into());
+    let zap_target = (&target_amount.target).into();
 
     let msats = target_amount.specified_msats.unwrap_or(default_msats);
 
@@ -419,11 +547,13 @@ fn send_zap(
             enostr::RelayId::Multicast => None,
         })
         .collect();
-    zaps.send_zap(sender.bytes(), sender_relays, zap_target, msats);
+    let comment = prefs.comment_or_default(target_amount.comment.as_deref());
+
+    zaps.send_zap(sender.bytes(), sender_relays, zap_target, msats, comment);
 }
 
-fn clear_zap_error(sender: &Pubkey, zaps: &mut Zaps, target: &NoteZapTargetOwned) {
-    zaps.clear_error_for(sender.bytes(), ZapTarget::Note(target.into()));
+fn clear_zap_error(sender: &Pubkey, zaps: &mut Zaps, target: &ZapTargetOwned) {
+    zaps.clear_error_for(sender.bytes(), target.into());
 }
 
 impl TimelineOpenResult {
@@ -560,7 +690,7 @@ pub fn process_thread_notes(
             continue;
         };
 
-        if is_future_timestamp(note.created_at(), now) {
+        if is_future_timestamp(note.created_at(), now) || is_expired(&note, now) {
             continue;
         }
 
@@ -3,15 +3,25 @@ use crate::{
     column::Columns,
     decks::{Decks, DecksCache},
     draft::Drafts,
+    mention_notifier::MentionNotifier,
     nav::{self, ProcessNavResult},
     onboarding::Onboarding,
     options::AppOptions,
+    publish_check::PublishChecks,
     route::Route,
-    storage,
+    scheduled_posts::ScheduledPosts,
+    storage::{self, NotificationsSeen},
     support::Support,
     timeline::{self, kind::ListKind, thread::Threads, TimelineCache, TimelineKind},
     timeline_loader::{TimelineLoader, TimelineLoaderMsg},
-    ui::{self, DesktopSidePanel, SidePanelAction},
+    ui::{
+        self,
+        publish_status::{publish_status_ui, RecentPublish},
+        publish_warning::{publish_warning_ui, PublishWarningAction},
+        quick_post::{quick_post_ui, QuickPostAction, QUICK_POST_SHORTCUT},
+        search::FocusState,
+        DesktopSidePanel, SidePanelAction,
+    },
     view_state::ViewState,
     Result,
 };
@@ -20,8 +30,8 @@ use enostr::Pubkey;
 use nostrdb::Transaction;
 use notedeck::{
     tr, ui::is_compiled_as_mobile, ui::is_narrow, Accounts, AppAction, AppContext, AppResponse,
-    DataPath, DataPathType, FilterState, Images, Localization, MediaJobSender, NotedeckOptions,
-    SettingsHandler,
+    DataPath, DataPathType, FilterState, Images, Localization, MediaJobSender, NostrLink,
+    NotedeckOptions, SettingsHandler,
 };
 use notedeck_ui::{
     media::{MediaViewer, MediaViewerFlags, MediaViewerState},
@@ -47,6 +57,7 @@ pub struct Damus {
     pub decks_cache: DecksCache,
     pub view_state: ViewState,
     pub drafts: Drafts,
+    pub scheduled_posts: ScheduledPosts,
     pub timeline_cache: TimelineCache,
     pub support: Support,
     pub threads: Threads,
@@ -67,6 +78,17 @@ pub struct Damus {
 
     /// Track which column is hovered for mouse back/forward navigation
     hovered_column: Option<usize>,
+
+    /// Published notes we're looking for on other relays
+    pub publish_checks: PublishChecks,
+
+    pub notifications_seen: NotificationsSeen,
+
+    /// System notifications for mentions received in the background
+    mention_notifier: MentionNotifier,
+
+    /// A `nostr:` link to open once we're set up
+    pending_link: Option<NostrLink>,
 }
 
 #[profiling::function]
@@ -180,16 +202,37 @@ fn try_process_event(
     // Handle Escape separately: only consume the key if there's a route to go back to,
     // otherwise let Chrome handle it (e.g. to open the side menu)
     let can_go_back = current_columns.get_selected_router().routes().len() > 1;
-    if can_go_back && ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape)) {
+    if can_go_back
+        && !damus.view_state.quick_post
+        && ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape))
+    {
         current_columns.get_selected_router().go_back();
     }
 
+    if ctx.input_mut(|i| i.consume_shortcut(&QUICK_POST_SHORTCUT)) {
+        damus.view_state.quick_post = !damus.view_state.quick_post;
+        if damus.view_state.quick_post {
+            damus.drafts.compose_mut().focus_state = FocusState::ShouldRequestFocus;
+        }
+    }
+
     let selected_account_pk = *app_ctx.accounts.selected_account_pubkey();
+    app_ctx
+        .accounts
+        .update_mute_modes(app_ctx.settings.mute_modes(&selected_account_pk));
+    damus
+        .note_options
+        .set_content_warning_mode(app_ctx.settings.content_warning_mode(&selected_account_pk));
+
     for (kind, timeline) in &mut damus.timeline_cache {
         if timeline.subscription.dependers(&selected_account_pk) == 0 {
             continue;
         }
 
+        if let TimelineKind::Notifications(notified) = kind {
+            timeline.set_quiet_authors(app_ctx.settings.notification_mutes(notified));
+        }
+
         if let FilterState::Ready(filter) = &timeline.filter {
             if timeline.kind.should_subscribe_locally()
                 && timeline
@@ -234,6 +277,7 @@ fn try_process_event(
             // TODO: show loading?
             match kind {
                 TimelineKind::List(ListKind::Contact(_))
+                | TimelineKind::Live(_)
                 | TimelineKind::Algo(timeline::kind::AlgoTimeline::LastPerPubkey(
                     ListKind::Contact(_),
                 )) => {
@@ -244,7 +288,11 @@ fn try_process_event(
                     ListKind::PeopleList(_),
                 )) => {
                     let txn = Transaction::new(app_ctx.ndb).expect("txn");
-                    timeline::fetch_people_list(app_ctx.ndb, &txn, timeline);
+                    timeline::fetch_list_note(app_ctx.ndb, &txn, timeline);
+                }
+                TimelineKind::Bookmarks(_) => {
+                    let txn = Transaction::new(app_ctx.ndb).expect("txn");
+                    timeline::fetch_list_note(app_ctx.ndb, &txn, timeline);
                 }
                 _ => {}
             }
@@ -326,6 +374,10 @@ fn handle_timeline_loader_messages(damus: &mut Damus, app_ctx: &mut AppContext<'
 fn update_damus(damus: &mut Damus, app_ctx: &mut AppContext<'_>, ctx: &egui::Context) {
     app_ctx.img_cache.urls.cache.handle_io();
 
+    damus
+        .drafts
+        .select_account(&app_ctx.accounts.get_selected_account().key);
+
     damus
         .timeline_loader
         .start(ctx.clone(), app_ctx.ndb.clone());
@@ -359,11 +411,73 @@ fn update_damus(damus: &mut Damus, app_ctx: &mut AppContext<'_>, ctx: &egui::Con
         DamusState::Initialized => (),
     };
 
+    open_pending_link(damus, app_ctx);
+
     handle_timeline_loader_messages(damus, app_ctx);
 
+    if app_ctx.settings.verify_publishes() {
+        damus
+            .publish_checks
+            .poll(app_ctx.ndb, &mut app_ctx.remote, app_ctx.accounts, ctx);
+    }
+
+    damus
+        .view_state
+        .identity_archive
+        .poll(app_ctx.ndb, &mut app_ctx.remote, app_ctx.accounts);
+
+    damus.view_state.key_export.poll(ctx, app_ctx.clipboard);
+
+    damus.timeline_cache.parent_prefetch.poll(
+        &mut app_ctx.remote,
+        app_ctx.accounts,
+        app_ctx.unknown_ids,
+        ctx,
+    );
+
     if let Err(err) = try_process_event(damus, app_ctx, ctx) {
         error!("error processing event: {}", err);
     }
+
+    damus
+        .mention_notifier
+        .poll(&damus.timeline_cache, app_ctx, ctx);
+
+    damus.drafts.save();
+
+    let published =
+        damus
+            .scheduled_posts
+            .poll(app_ctx.ndb, app_ctx.accounts, &mut app_ctx.remote, ctx);
+    for note_id in published {
+        if app_ctx.settings.verify_publishes() {
+            damus.publish_checks.track(note_id);
+        }
+        damus.view_state.recent_publish = Some(RecentPublish::new(note_id));
+    }
+}
+
+/// Open the `nostr:` link we were launched with in the selected column
+fn open_pending_link(damus: &mut Damus, app_ctx: &mut AppContext<'_>) {
+    // don't bury the welcome screen under the link
+    if !app_ctx.settings.welcome_completed() {
+        return;
+    }
+
+    let Some(link) = damus.pending_link.take() else {
+        return;
+    };
+
+    let route = match link {
+        NostrLink::Profile(pubkey) => Route::profile(pubkey),
+        NostrLink::Note(note_id) => Route::note(note_id),
+        NostrLink::Address(address) => Route::Timeline(TimelineKind::address(address)),
+    };
+
+    damus
+        .columns_mut(app_ctx.i18n, app_ctx.accounts)
+        .get_selected_router()
+        .route_to(route);
 }
 
 pub(crate) fn setup_selected_account_timeline_subs(
@@ -397,6 +511,50 @@ fn render_damus(damus: &mut Damus, app_ctx: &mut AppContext<'_>, ui: &mut egui::
         app_ctx.media_jobs.sender(),
     );
 
+    if let Some(recent) = &damus.view_state.recent_publish {
+        let write_relays = app_ctx.accounts.selected_account_write_relays();
+        let acks = app_ctx.remote.relay_inspect().publish_acks(&recent.note_id);
+        if !publish_status_ui(ui, app_ctx.i18n, recent, &write_relays, acks) {
+            damus.view_state.recent_publish = None;
+        }
+    }
+
+    match publish_warning_ui(ui, app_ctx.i18n, damus.publish_checks.missing()) {
+        Some(PublishWarningAction::Rebroadcast(note_id)) => damus.publish_checks.rebroadcast(
+            &note_id,
+            app_ctx.ndb,
+            &mut app_ctx.remote,
+            app_ctx.accounts,
+        ),
+        Some(PublishWarningAction::Dismiss(note_id)) => damus.publish_checks.dismiss(&note_id),
+        None => {}
+    }
+
+    if damus.view_state.quick_post {
+        match quick_post_ui(ui, app_ctx, damus.drafts.compose_mut(), damus.note_options) {
+            Some(QuickPostAction::Post(post)) if post.is_scheduled() => {
+                post.schedule(&mut damus.scheduled_posts, &mut damus.drafts);
+                damus.view_state.quick_post = false;
+            }
+            Some(QuickPostAction::Post(post)) => {
+                let txn = Transaction::new(app_ctx.ndb).expect("txn");
+                let mut publisher = app_ctx.remote.publisher(app_ctx.accounts);
+                match post.execute(app_ctx.ndb, &txn, &mut publisher, &mut damus.drafts) {
+                    Err(err) => error!("error posting from quick post: {err}"),
+                    Ok(note_id) => {
+                        if app_ctx.settings.verify_publishes() {
+                            damus.publish_checks.track(note_id);
+                        }
+                        damus.view_state.recent_publish = Some(RecentPublish::new(note_id));
+                    }
+                }
+                damus.view_state.quick_post = false;
+            }
+            Some(QuickPostAction::Close) => damus.view_state.quick_post = false,
+            None => {}
+        }
+    }
+
     // We use this for keeping timestamps and things up to date
     //ui.ctx().request_repaint_after(Duration::from_secs(5));
 
@@ -529,12 +687,15 @@ impl Damus {
         };
 
         let support = Support::new(app_context.path);
+        let notifications_seen = NotificationsSeen::new(app_context.path);
+        let pending_link = parsed_args.link.clone();
         let note_options = get_note_options(parsed_args, app_context.settings);
         let threads = Threads::default();
 
         Self {
             timeline_cache,
-            drafts: Drafts::default(),
+            drafts: Drafts::load(app_context.path),
+            scheduled_posts: ScheduledPosts::load(app_context.path),
             state: DamusState::Initializing,
             note_options,
             options,
@@ -547,6 +708,10 @@ impl Damus {
             onboarding: Onboarding::default(),
             hovered_column: None,
             timeline_loader: TimelineLoader::default(),
+            publish_checks: PublishChecks::default(),
+            notifications_seen,
+            mention_notifier: MentionNotifier::default(),
+            pending_link,
         }
     }
 
@@ -574,10 +739,12 @@ impl Damus {
         let options = AppOptions::default() | AppOptions::Debug | AppOptions::TmpColumns;
 
         let support = Support::new(&path);
+        let notifications_seen = NotificationsSeen::new(&path);
 
         Self {
             timeline_cache: TimelineCache::default(),
             drafts: Drafts::default(),
+            scheduled_posts: ScheduledPosts::default(),
             state: DamusState::Initializing,
             note_options: NoteOptions::default(),
             //frame_history: FrameHistory::default(),
@@ -590,6 +757,10 @@ impl Damus {
             onboarding: Onboarding::default(),
             hovered_column: None,
             timeline_loader: TimelineLoader::default(),
+            publish_checks: PublishChecks::default(),
+            notifications_seen,
+            mention_notifier: MentionNotifier::default(),
+            pending_link: None,
         }
     }
 
@@ -661,6 +832,16 @@ fn get_note_options(args: ColumnsArgs, settings_handler: &mut SettingsHandler) -
         NoteOptions::RepliesNewestFirst,
         settings_handler.show_replies_newest_first(),
     );
+    note_options.set(
+        NoteOptions::KeepMediaMetadata,
+        settings_handler.keep_media_metadata(),
+    );
+    note_options.set_max_preview_depth(settings_handler.max_preview_depth());
+    note_options.set(
+        NoteOptions::OpenWithTemplate,
+        settings_handler.open_with_template().is_some(),
+    );
+    note_options.set(NoteOptions::HideBadges, settings_handler.hide_badges());
     note_options
 }
 
@@ -781,19 +962,30 @@ fn should_show_compose_button(decks: &DecksCache, accounts: &Accounts) -> bool {
                 TimelineKind::Universe => true,
                 TimelineKind::Generic(_) => true,
                 TimelineKind::Hashtag(_) => true,
+                TimelineKind::Bookmarks(_) => true,
 
                 // no!
                 TimelineKind::Search(_) => false,
+                // groups and communities have their own message box
+                TimelineKind::Group(_) => false,
+                TimelineKind::Community(_) => false,
                 TimelineKind::Notifications(_) => false,
+                TimelineKind::Live(_) => false,
+                // replies go through the note's reply button, like threads
+                TimelineKind::Address(_) => false,
             }
         }
 
         Route::Thread(_) => false,
+        Route::Note(_) => false,
         Route::Accounts(_) => false,
         Route::Reply(_) => false,
         Route::Quote(_) => false,
         Route::Relays => false,
         Route::Settings => false,
+        Route::MediaServers => false,
+        Route::MuteList => false,
+        Route::Drafts => false,
         Route::ComposeNote => false,
         Route::AddColumn(_) => false,
         Route::EditProfile(_) => false,
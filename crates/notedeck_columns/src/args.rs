@@ -2,6 +2,7 @@ use std::collections::BTreeSet;
 
 use crate::timeline::TimelineKind;
 use enostr::{Filter, Pubkey};
+use notedeck::NostrLink;
 use oot_bitset::{bitset_clear, bitset_get, bitset_set};
 use tracing::{debug, error, info};
 
@@ -15,6 +16,9 @@ pub enum ColumnsFlag {
 
 pub struct ColumnsArgs {
     pub columns: Vec<ArgColumn>,
+    /// A `nostr:` link we were launched with, e.g. by the OS when one is
+    /// clicked in another app
+    pub link: Option<NostrLink>,
     flags: [u16; 2],
 }
 
@@ -35,6 +39,7 @@ impl ColumnsArgs {
         let mut unrecognized_args = BTreeSet::new();
         let mut res = Self {
             columns: vec![],
+            link: None,
             flags: [0; 2],
         };
 
@@ -184,6 +189,9 @@ impl ColumnsArgs {
                 } else {
                     error!("failed to parse filter in '{}'", filter_file);
                 }
+            } else if let Some(link) = NostrLink::parse_uri(arg) {
+                info!("opening {arg}");
+                res.link = Some(link);
             } else {
                 unrecognized_args.insert(arg.clone());
             }
@@ -0,0 +1,418 @@
+//! NIP-72 moderated communities. A community is an addressable kind 34550
+//! definition naming its moderators. Anyone can post to it, but a post only
+//! shows up in the community once a moderator approves it (kind 4550).
+
+use enostr::{NoteId, Pubkey};
+use nostrdb::{Filter, Ndb, Note, NoteBuilder, Transaction};
+use notedeck::{filter::default_limit, Accounts, RelayType, RemoteApi};
+use tracing::error;
+
+use crate::timeline::kind::CommunityRef;
+
+pub const KIND_COMMUNITY: u32 = 34550;
+pub const KIND_COMMUNITY_APPROVAL: u32 = 4550;
+/// NIP-22 comments, which is what new community posts are
+pub const KIND_COMMENT: u32 = 1111;
+
+/// How many communities we ask for when browsing
+const BROWSE_LIMIT: u64 = 200;
+
+/// A community's kind 34550 definition
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommunityDefinition {
+    pub owner: Pubkey,
+    pub id: String,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub moderators: Vec<Pubkey>,
+}
+
+impl CommunityDefinition {
+    pub fn from_note(note: &Note) -> Option<Self> {
+        if note.kind() != KIND_COMMUNITY {
+            return None;
+        }
+
+        let mut definition = CommunityDefinition {
+            owner: Pubkey::new(*note.pubkey()),
+            id: String::new(),
+            name: None,
+            description: None,
+            image: None,
+            moderators: Vec::new(),
+        };
+
+        for tag in note.tags() {
+            let Some(name) = tag.get_str(0) else {
+                continue;
+            };
+
+            match name {
+                "d" => definition.id = tag.get_str(1).unwrap_or_default().to_owned(),
+                "name" => definition.name = tag.get_str(1).map(str::to_owned),
+                "description" => definition.description = tag.get_str(1).map(str::to_owned),
+                "image" => definition.image = tag.get_str(1).map(str::to_owned),
+                "p" if tag.get_str(3) == Some("moderator") => {
+                    if let Some(pk) = tag.get_id(1) {
+                        definition.moderators.push(Pubkey::new(*pk));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        (!definition.id.is_empty()).then_some(definition)
+    }
+
+    pub fn load(ndb: &Ndb, txn: &Transaction, community: &CommunityRef) -> Option<Self> {
+        community
+            .address()
+            .lookup(ndb, txn)
+            .and_then(|note| CommunityDefinition::from_note(&note))
+    }
+
+    /// The community's name, or its `d` tag when it has none
+    pub fn display_name(&self) -> &str {
+        self.name
+            .as_deref()
+            .filter(|name| !name.is_empty())
+            .unwrap_or(&self.id)
+    }
+
+    /// The owner can always approve posts, even when not listed as a
+    /// moderator
+    pub fn can_moderate(&self, pubkey: &Pubkey) -> bool {
+        self.owner == *pubkey || self.moderators.contains(pubkey)
+    }
+
+    pub fn community(&self) -> CommunityRef {
+        CommunityRef::new(self.owner, self.id.clone())
+    }
+
+    fn approvers(&self) -> Vec<[u8; 32]> {
+        let mut approvers = vec![*self.owner.bytes()];
+        for moderator in &self.moderators {
+            if !approvers.contains(moderator.bytes()) {
+                approvers.push(*moderator.bytes());
+            }
+        }
+        approvers
+    }
+}
+
+/// The community's name from its definition, for column titles
+pub fn community_name<'a>(
+    ndb: &Ndb,
+    txn: &'a Transaction,
+    community: &CommunityRef,
+) -> Option<&'a str> {
+    let note = community.address().lookup(ndb, txn)?;
+    let name = note
+        .tags()
+        .iter()
+        .find(|tag| tag.get_str(0) == Some("name"))?
+        .get_str(1)?;
+
+    (!name.is_empty()).then_some(name)
+}
+
+/// Community definitions, for browsing them
+pub fn communities_filter() -> Filter {
+    Filter::new()
+        .kinds([KIND_COMMUNITY as u64])
+        .limit(BROWSE_LIMIT)
+        .build()
+}
+
+/// The communities we know of, sorted by name
+pub fn communities(ndb: &Ndb, txn: &Transaction) -> Vec<CommunityDefinition> {
+    let results = match ndb.query(txn, &[communities_filter()], BROWSE_LIMIT as i32) {
+        Ok(results) => results,
+        Err(err) => {
+            error!("community query failed: {err}");
+            return Vec::new();
+        }
+    };
+
+    let mut communities: Vec<CommunityDefinition> = Vec::new();
+    for result in results {
+        let Some(definition) = CommunityDefinition::from_note(&result.note) else {
+            continue;
+        };
+
+        // results are newest first, so the first copy of a community wins
+        if communities
+            .iter()
+            .all(|c| c.owner != definition.owner || c.id != definition.id)
+        {
+            communities.push(definition);
+        }
+    }
+
+    communities.sort_by_key(|c| c.display_name().to_lowercase());
+    communities
+}
+
+/// Approvals by the community's owner and moderators. Anyone can publish
+/// an approval, so the ones from other authors are never asked for.
+pub fn approvals_filter(definition: &CommunityDefinition) -> Filter {
+    let coordinate = definition.community().coordinate();
+    Filter::new()
+        .kinds([KIND_COMMUNITY_APPROVAL as u64])
+        .authors(definition.approvers().iter())
+        .tags([coordinate.as_str()], 'a')
+        .limit(default_limit())
+        .build()
+}
+
+/// Posts submitted to the community, approved or not. Older clients post
+/// kind 1 notes with a lowercase `a` tag instead of NIP-22 comments.
+pub fn posts_filters(community: &CommunityRef) -> Vec<Filter> {
+    let coordinate = community.coordinate();
+    vec![
+        Filter::new()
+            .kinds([KIND_COMMENT as u64])
+            .tags([coordinate.as_str()], 'A')
+            .limit(default_limit())
+            .build(),
+        Filter::new()
+            .kinds([1])
+            .tags([coordinate.as_str()], 'a')
+            .limit(default_limit())
+            .build(),
+    ]
+}
+
+/// The id of the post an approval is for
+pub fn approved_post_id(approval: &Note) -> Option<NoteId> {
+    if approval.kind() != KIND_COMMUNITY_APPROVAL {
+        return None;
+    }
+
+    approval
+        .tags()
+        .iter()
+        .find(|tag| tag.get_str(0) == Some("e"))
+        .and_then(|tag| tag.get_id(1))
+        .map(|id| NoteId::new(*id))
+}
+
+/// Posts no moderator has approved yet, newest first
+pub fn pending_posts<'a>(
+    ndb: &Ndb,
+    txn: &'a Transaction,
+    definition: &CommunityDefinition,
+) -> Vec<Note<'a>> {
+    let limit = default_limit() as i32;
+    let approved: Vec<NoteId> = ndb
+        .query(txn, &[approvals_filter(definition)], limit)
+        .map(|results| {
+            results
+                .iter()
+                .filter_map(|r| approved_post_id(&r.note))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let Ok(results) = ndb.query(txn, &posts_filters(&definition.community()), limit) else {
+        return Vec::new();
+    };
+
+    let mut pending: Vec<Note<'a>> = results
+        .into_iter()
+        .map(|r| r.note)
+        .filter(|note| !approved.iter().any(|id| id.bytes() == note.id()))
+        .collect();
+    pending.sort_by_key(|note| std::cmp::Reverse(note.created_at()));
+    pending
+}
+
+/// Something to publish to a community
+pub enum CommunityAction {
+    /// Submit a post for the moderators to approve
+    Post {
+        community: CommunityRef,
+        content: String,
+    },
+    /// Approve a pending post, as one of the community's moderators
+    Approve {
+        community: CommunityRef,
+        post: NoteId,
+    },
+}
+
+impl CommunityAction {
+    pub fn process(self, ndb: &Ndb, accounts: &Accounts, remote: &mut RemoteApi<'_>) {
+        let Some(kp) = accounts.selected_filled() else {
+            error!("can't publish to a community without a secret key");
+            return;
+        };
+        let seckey = kp.secret_key.to_secret_bytes();
+
+        let note = match self {
+            CommunityAction::Post { community, content } => {
+                community_post(&seckey, &community, &content)
+            }
+            CommunityAction::Approve { community, post } => {
+                let txn = Transaction::new(ndb).expect("txn");
+                let Ok(post) = ndb.get_note_by_id(&txn, post.bytes()) else {
+                    error!("can't approve a post we don't have: {}", post.hex());
+                    return;
+                };
+                community_approval(&seckey, &community, &post)
+            }
+        };
+
+        let Some(note) = note else {
+            error!("failed to build community note");
+            return;
+        };
+
+        // show it right away rather than waiting for a relay to echo it
+        if let Ok(json) = enostr::ClientMessage::event(&note).and_then(|e| e.to_json()) {
+            let _ = ndb.process_event_with(&json, nostrdb::IngestMetadata::new().client(true));
+        }
+
+        remote
+            .publisher(accounts)
+            .publish_note(&note, RelayType::AccountsWrite);
+    }
+}
+
+/// A NIP-22 comment scoped to the community, per NIP-72
+fn community_post(
+    seckey: &[u8; 32],
+    community: &CommunityRef,
+    content: &str,
+) -> Option<Note<'static>> {
+    let coordinate = community.coordinate();
+    let kind = KIND_COMMUNITY.to_string();
+    let owner = community.author.hex();
+
+    NoteBuilder::new()
+        .kind(KIND_COMMENT)
+        .content(content)
+        .start_tag()
+        .tag_str("A")
+        .tag_str(&coordinate)
+        .start_tag()
+        .tag_str("a")
+        .tag_str(&coordinate)
+        .start_tag()
+        .tag_str("P")
+        .tag_str(&owner)
+        .start_tag()
+        .tag_str("p")
+        .tag_str(&owner)
+        .start_tag()
+        .tag_str("K")
+        .tag_str(&kind)
+        .start_tag()
+        .tag_str("k")
+        .tag_str(&kind)
+        .sign(seckey)
+        .build()
+}
+
+/// A kind 4550 approval carrying the approved post, so readers who don't
+/// have the post yet can still show it
+fn community_approval(
+    seckey: &[u8; 32],
+    community: &CommunityRef,
+    post: &Note,
+) -> Option<Note<'static>> {
+    let content = post.json().ok()?;
+
+    NoteBuilder::new()
+        .kind(KIND_COMMUNITY_APPROVAL)
+        .content(&content)
+        .start_tag()
+        .tag_str("a")
+        .tag_str(&community.coordinate())
+        .start_tag()
+        .tag_str("e")
+        .tag_id(post.id())
+        .start_tag()
+        .tag_str("p")
+        .tag_id(post.pubkey())
+        .start_tag()
+        .tag_str("k")
+        .tag_str(&post.kind().to_string())
+        .sign(seckey)
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use enostr::FullKeypair;
+    use notedeck_testing::fixtures::signed_note;
+
+    #[test]
+    fn definition_lists_only_moderators() {
+        let owner = FullKeypair::generate();
+        let moderator = FullKeypair::generate().pubkey;
+        let member = FullKeypair::generate().pubkey;
+        let note = signed_note(
+            &owner,
+            KIND_COMMUNITY,
+            &[
+                &["d", "rust"],
+                &["name", "Rustaceans"],
+                &["description", "all things rust"],
+                &["p", &moderator.hex(), "", "moderator"],
+                &["p", &member.hex()],
+            ],
+        );
+
+        let definition = CommunityDefinition::from_note(&note).unwrap();
+        assert_eq!(definition.display_name(), "Rustaceans");
+        assert_eq!(definition.moderators, vec![moderator]);
+        assert!(definition.can_moderate(&owner.pubkey));
+        assert!(definition.can_moderate(&moderator));
+        assert!(!definition.can_moderate(&member));
+        assert_eq!(
+            definition.approvers(),
+            vec![*owner.pubkey.bytes(), *moderator.bytes()]
+        );
+    }
+
+    #[test]
+    fn posts_are_scoped_to_the_community() {
+        let author = FullKeypair::generate();
+        let owner = FullKeypair::generate().pubkey;
+        let community = CommunityRef::new(owner, "rust");
+        let post =
+            community_post(&author.secret_key.secret_bytes(), &community, "hello crabs").unwrap();
+
+        let coordinate = format!("34550:{}:rust", owner.hex());
+        let tag = |name: &str| {
+            post.tags()
+                .iter()
+                .find(|t| t.get_str(0) == Some(name))
+                .and_then(|t| t.get_str(1).map(str::to_owned))
+        };
+        assert_eq!(post.kind(), KIND_COMMENT);
+        assert_eq!(tag("A"), Some(coordinate.clone()));
+        assert_eq!(tag("a"), Some(coordinate));
+        assert_eq!(tag("K").as_deref(), Some("34550"));
+    }
+
+    #[test]
+    fn approvals_point_at_the_post() {
+        let moderator = FullKeypair::generate();
+        let author = FullKeypair::generate();
+        let community = CommunityRef::new(FullKeypair::generate().pubkey, "rust");
+        let post = community_post(&author.secret_key.secret_bytes(), &community, "hi").unwrap();
+
+        let approval =
+            community_approval(&moderator.secret_key.secret_bytes(), &community, &post).unwrap();
+        assert_eq!(approval.kind(), KIND_COMMUNITY_APPROVAL);
+        assert_eq!(approved_post_id(&approval), Some(NoteId::new(*post.id())));
+        assert!(approval.content().contains(&NoteId::new(*post.id()).hex()));
+
+        // a post is not an approval of anything
+        assert_eq!(approved_post_id(&post), None);
+    }
+}
@@ -102,6 +102,11 @@ impl DecksCache {
             .or_insert_with(|| Decks::default_decks(i18n))
     }
 
+    /// Whether `key` already has its own decks, as opposed to falling back
+    pub fn has_decks(&self, key: &Pubkey) -> bool {
+        self.account_to_decks.contains_key(key)
+    }
+
     pub fn fallback(&self) -> &Decks {
         self.account_to_decks
             .get(&self.fallback_pubkey)
@@ -1,14 +1,31 @@
 use egui::text::LayoutJob;
+use enostr::{Keypair, NoteId, Pubkey};
 use poll_promise::Promise;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     media_upload::Nip94Event,
-    post::PostBuffer,
+    post::{PostBuffer, SavedMention},
+    route::Route,
     ui::{note::PostType, search::FocusState},
     Error,
 };
+use notedeck::{
+    debouncer::Debouncer,
+    storage::{self, SealKey},
+    timed_serializer::TimedSerializer,
+    DataPath, DataPathType,
+};
 use notedeck_ui::ProfileSearchResult;
 use std::collections::HashMap;
+use std::time::Duration;
+
+/// How long typing goes on before the drafts are written
+const SAVE_DELAY: Duration = Duration::from_secs(1);
+
+fn drafts_file(account: &Pubkey) -> String {
+    format!("drafts-{}.sealed", account.hex())
+}
 
 #[derive(Default)]
 pub struct Draft {
@@ -19,6 +36,33 @@ pub struct Draft {
     pub uploading_media: Vec<Promise<Result<Nip94Event, Error>>>, // promises that aren't ready yet
     pub upload_errors: Vec<String>,      // media upload errors to show the user
     pub focus_state: FocusState,
+    /// Publish time being typed, when the post is to be scheduled
+    pub schedule: Option<String>,
+    /// Reason being typed, when the post gets a NIP-36 content warning.
+    /// It may be left empty.
+    pub content_warning: Option<String>,
+    /// How long the post lives before a NIP-40 expiration, when it gets one
+    pub expiration: Option<ExpiresIn>,
+}
+
+/// Lifetimes the composer offers for a NIP-40 expiration
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExpiresIn {
+    Hour,
+    Day,
+    Week,
+}
+
+impl ExpiresIn {
+    pub const ALL: [ExpiresIn; 3] = [ExpiresIn::Hour, ExpiresIn::Day, ExpiresIn::Week];
+
+    pub fn secs(&self) -> u64 {
+        match self {
+            Self::Hour => 60 * 60,
+            Self::Day => 24 * 60 * 60,
+            Self::Week => 7 * 24 * 60 * 60,
+        }
+    }
 }
 
 pub struct MentionHint {
@@ -28,16 +72,282 @@ pub struct MentionHint {
     pub results: Vec<ProfileSearchResult>,
 }
 
+/// Which composer a draft belongs to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DraftTarget {
+    Compose,
+    Reply(NoteId),
+    Quote(NoteId),
+}
+
+impl DraftTarget {
+    /// The route that opens the composer for this draft
+    pub fn route(&self) -> Route {
+        match self {
+            Self::Compose => Route::ComposeNote,
+            Self::Reply(id) => Route::Reply(*id),
+            Self::Quote(id) => Route::Quote(*id),
+        }
+    }
+}
+
+impl From<&PostType> for DraftTarget {
+    fn from(post_type: &PostType) -> Self {
+        match post_type {
+            PostType::New => Self::Compose,
+            PostType::Reply(id) => Self::Reply(*id),
+            PostType::Quote(id) => Self::Quote(*id),
+        }
+    }
+}
+
+impl From<DraftTarget> for PostType {
+    fn from(target: DraftTarget) -> Self {
+        match target {
+            DraftTarget::Compose => Self::New,
+            DraftTarget::Reply(id) => Self::Reply(id),
+            DraftTarget::Quote(id) => Self::Quote(id),
+        }
+    }
+}
+
+/// The parts of a draft that are written to disk
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct SavedDraft {
+    target: DraftTarget,
+    text: String,
+    #[serde(default)]
+    mentions: Vec<SavedMention>,
+    #[serde(default)]
+    media: Vec<Nip94Event>,
+}
+
+/// The drafts of one account
 #[derive(Default)]
-pub struct Drafts {
+struct AccountDrafts {
     replies: HashMap<[u8; 32], Draft>,
     quotes: HashMap<[u8; 32], Draft>,
     compose: Draft,
 }
 
+impl AccountDrafts {
+    fn get_mut(&mut self, target: DraftTarget) -> &mut Draft {
+        match target {
+            DraftTarget::Compose => &mut self.compose,
+            DraftTarget::Reply(id) => self.replies.entry(*id.bytes()).or_default(),
+            DraftTarget::Quote(id) => self.quotes.entry(*id.bytes()).or_default(),
+        }
+    }
+
+    /// Every draft with something in it, the new note draft first
+    fn unfinished(&self) -> Vec<(DraftTarget, &Draft)> {
+        let mut replies: Vec<_> = self.replies.iter().collect();
+        replies.sort_by_key(|(id, _)| **id);
+
+        let mut quotes: Vec<_> = self.quotes.iter().collect();
+        quotes.sort_by_key(|(id, _)| **id);
+
+        std::iter::once((DraftTarget::Compose, &self.compose))
+            .chain(
+                replies
+                    .into_iter()
+                    .map(|(id, draft)| (DraftTarget::Reply(NoteId::new(*id)), draft)),
+            )
+            .chain(
+                quotes
+                    .into_iter()
+                    .map(|(id, draft)| (DraftTarget::Quote(NoteId::new(*id)), draft)),
+            )
+            .filter(|(_, draft)| !draft.is_empty())
+            .collect()
+    }
+
+    fn saved(&self) -> Vec<SavedDraft> {
+        self.unfinished()
+            .into_iter()
+            .map(|(target, draft)| SavedDraft {
+                target,
+                text: draft.buffer.text_buffer.clone(),
+                mentions: draft.buffer.saved_mentions(),
+                media: draft.uploaded_media.clone(),
+            })
+            .collect()
+    }
+
+    /// Take in `other`'s drafts, keeping ours where both have one
+    fn adopt(&mut self, other: AccountDrafts) {
+        for (id, draft) in other.replies {
+            self.replies.entry(id).or_insert(draft);
+        }
+        for (id, draft) in other.quotes {
+            self.quotes.entry(id).or_insert(draft);
+        }
+        if self.compose.is_empty() {
+            self.compose = other.compose;
+        }
+    }
+
+    fn restore(&mut self, saved: SavedDraft) {
+        let draft = self.get_mut(saved.target);
+        draft.buffer = PostBuffer::restore(saved.text, &saved.mentions);
+        draft.uploaded_media = saved.media;
+    }
+}
+
+/// Drafts of every account. The selected account's are at hand, the
+/// others are set aside until their account is selected again. Each
+/// account's drafts are saved to their own file, sealed with a key derived
+/// from its secret. Drafts of accounts without a secret key aren't saved.
+pub struct Drafts {
+    current: AccountDrafts,
+    /// Owner of `current`, once an account was selected
+    account: Option<Pubkey>,
+    others: HashMap<Pubkey, AccountDrafts>,
+    /// Where drafts are saved. `None` keeps them in memory only.
+    path: Option<DataPath>,
+    /// Draft files of the accounts with a secret key selected so far
+    files: HashMap<Pubkey, TimedSerializer<Vec<SavedDraft>>>,
+    /// Whether the selected account's drafts were handed out for editing
+    /// since they were last saved
+    dirty: bool,
+    debouncer: Debouncer,
+}
+
+impl Default for Drafts {
+    fn default() -> Self {
+        Self {
+            current: AccountDrafts::default(),
+            account: None,
+            others: HashMap::new(),
+            path: None,
+            files: HashMap::new(),
+            dirty: false,
+            debouncer: Debouncer::new(SAVE_DELAY),
+        }
+    }
+}
+
 impl Drafts {
+    /// Drafts saved to `path`. Each account's are read when it is first
+    /// selected.
+    pub fn load(path: &DataPath) -> Self {
+        Self {
+            path: Some(path.clone()),
+            ..Default::default()
+        }
+    }
+
+    /// Write the selected account's drafts if they may have changed, at
+    /// most once every [`SAVE_DELAY`]
+    pub fn save(&mut self) {
+        if self.dirty && self.debouncer.should_act() {
+            self.save_now();
+        }
+    }
+
+    fn save_now(&mut self) {
+        if !self.dirty {
+            return;
+        }
+        self.dirty = false;
+        self.debouncer.bounce();
+
+        let Some(file) = self
+            .account
+            .and_then(|account| self.files.get_mut(&account))
+        else {
+            return;
+        };
+        // the file only writes when the drafts differ from what it last wrote
+        file.try_save(self.current.saved());
+    }
+
+    /// Switch to the drafts of `account`, saving the previous account's
+    /// first. The drafts at hand before any account was selected become
+    /// its drafts.
+    pub fn select_account(&mut self, key: &Keypair) {
+        let account = &key.pubkey;
+        if self.account.as_ref() == Some(account) {
+            return;
+        }
+        self.save_now();
+
+        let mut drafts = self.others.remove(account).unwrap_or_default();
+        for saved in self.open_file(key) {
+            drafts.restore(saved);
+        }
+
+        let previous = std::mem::replace(&mut self.current, drafts);
+        match self.account.replace(*account) {
+            Some(previous_account) => {
+                self.others.insert(previous_account, previous);
+            }
+            None => self.current.adopt(previous),
+        }
+    }
+
+    /// The drafts saved for an account, the first time it is selected
+    fn open_file(&mut self, key: &Keypair) -> Vec<SavedDraft> {
+        let (Some(path), Some(secret)) = (&self.path, &key.secret_key) else {
+            return Vec::new();
+        };
+        let account = &key.pubkey;
+        if self.files.contains_key(account) {
+            return Vec::new();
+        }
+
+        // saves are debounced here, so the file writes right away
+        let file = TimedSerializer::new(path, DataPathType::Setting, drafts_file(account))
+            .with_delay(Duration::ZERO)
+            .sealed(SealKey::from_secret(secret));
+        let saved = file.get_item().unwrap_or_default();
+        self.files.insert(*account, file);
+        saved
+    }
+
+    /// Discard the drafts of an account that was signed out, and wipe
+    /// their file
+    pub fn remove_account(&mut self, account: &Pubkey) {
+        if self.account.as_ref() == Some(account) {
+            self.current = AccountDrafts::default();
+            self.dirty = false;
+        } else {
+            self.others.remove(account);
+        }
+
+        self.files.remove(account);
+        if let Some(path) = &self.path {
+            let _ = storage::scrub_file(&path.path(DataPathType::Setting), drafts_file(account));
+        }
+    }
+
+    /// Every draft of the selected account with something in it, the new
+    /// note draft first
+    pub fn unfinished(&self) -> Vec<(DraftTarget, &Draft)> {
+        self.current.unfinished()
+    }
+
+    pub fn get_mut(&mut self, target: DraftTarget) -> &mut Draft {
+        self.dirty = true;
+        self.current.get_mut(target)
+    }
+
+    pub fn discard(&mut self, target: DraftTarget) {
+        self.dirty = true;
+        match target {
+            DraftTarget::Compose => self.current.compose.clear(),
+            DraftTarget::Reply(id) => {
+                self.current.replies.remove(id.bytes());
+            }
+            DraftTarget::Quote(id) => {
+                self.current.quotes.remove(id.bytes());
+            }
+        }
+    }
+
     pub fn compose_mut(&mut self) -> &mut Draft {
-        &mut self.compose
+        self.dirty = true;
+        &mut self.current.compose
     }
 
     pub fn get_from_post_type(&mut self, post_type: &PostType) -> &mut Draft {
@@ -49,11 +359,13 @@ impl Drafts {
     }
 
     pub fn reply_mut(&mut self, id: &[u8; 32]) -> &mut Draft {
-        self.replies.entry(*id).or_default()
+        self.dirty = true;
+        self.current.replies.entry(*id).or_default()
     }
 
     pub fn quote_mut(&mut self, id: &[u8; 32]) -> &mut Draft {
-        self.quotes.entry(*id).or_default()
+        self.dirty = true;
+        self.current.quotes.entry(*id).or_default()
     }
 }
 
@@ -62,10 +374,105 @@ impl Draft {
         Draft::default()
     }
 
+    /// Nothing typed or attached yet
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty() && self.uploaded_media.is_empty()
+    }
+
     pub fn clear(&mut self) {
         self.buffer = PostBuffer::default();
         self.upload_errors = Vec::new();
         self.uploaded_media = Vec::new();
         self.uploading_media = Vec::new();
+        self.schedule = None;
+        self.content_warning = None;
+        self.expiration = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use enostr::FullKeypair;
+
+    fn write(drafts: &mut Drafts, text: &str) {
+        drafts.compose_mut().buffer = PostBuffer::restore(text.to_owned(), &[]);
+    }
+
+    fn compose_text(drafts: &mut Drafts) -> String {
+        drafts.compose_mut().buffer.text_buffer.clone()
+    }
+
+    #[test]
+    fn drafts_follow_the_selected_account() {
+        let alice = Keypair::only_pubkey(Pubkey::new([1; 32]));
+        let bob = Keypair::only_pubkey(Pubkey::new([2; 32]));
+        let mut drafts = Drafts::default();
+
+        // drafts from before accounts were tracked go to the first one
+        write(&mut drafts, "old draft");
+        drafts.select_account(&alice);
+        assert_eq!(compose_text(&mut drafts), "old draft");
+
+        drafts.select_account(&bob);
+        assert!(drafts.unfinished().is_empty());
+        write(&mut drafts, "bob's draft");
+
+        drafts.select_account(&alice);
+        assert_eq!(compose_text(&mut drafts), "old draft");
+
+        drafts.remove_account(&bob.pubkey);
+        drafts.select_account(&bob);
+        assert!(drafts.unfinished().is_empty());
+
+        drafts.select_account(&alice);
+        assert_eq!(compose_text(&mut drafts), "old draft");
+    }
+
+    #[test]
+    fn drafts_are_saved_per_account() {
+        let tmp = tempfile::TempDir::new().expect("tmp dir");
+        let path = DataPath::new(tmp.path());
+        let alice = FullKeypair::generate().to_keypair();
+        let bob = FullKeypair::generate().to_keypair();
+
+        let mut drafts = Drafts::load(&path);
+        drafts.select_account(&alice);
+        write(&mut drafts, "alice's draft");
+        // switching accounts saves right away
+        drafts.select_account(&bob);
+        write(&mut drafts, "bob's draft");
+        drafts.select_account(&alice);
+
+        let mut reloaded = Drafts::load(&path);
+        reloaded.select_account(&bob);
+        assert_eq!(compose_text(&mut reloaded), "bob's draft");
+        reloaded.select_account(&alice);
+        assert_eq!(compose_text(&mut reloaded), "alice's draft");
+
+        let file = drafts_file(&alice.pubkey);
+        let on_disk = std::fs::read_to_string(tmp.path().join("settings").join(&file));
+        assert!(!on_disk.expect("drafts file").contains("alice's draft"));
+
+        reloaded.remove_account(&alice.pubkey);
+        let mut after_removal = Drafts::load(&path);
+        after_removal.select_account(&alice);
+        assert!(after_removal.unfinished().is_empty());
+    }
+
+    #[test]
+    fn drafts_without_a_secret_stay_in_memory() {
+        let tmp = tempfile::TempDir::new().expect("tmp dir");
+        let path = DataPath::new(tmp.path());
+        let watcher = Keypair::only_pubkey(Pubkey::new([3; 32]));
+
+        let mut drafts = Drafts::load(&path);
+        drafts.select_account(&watcher);
+        write(&mut drafts, "can't seal this");
+        drafts.save();
+
+        let mut reloaded = Drafts::load(&path);
+        reloaded.select_account(&watcher);
+        assert!(reloaded.unfinished().is_empty());
     }
 }
@@ -0,0 +1,415 @@
+//! NIP-29 relay-based groups. A group lives on one relay, which enforces
+//! who may post and publishes the group's metadata, admins and members
+//! under its own key.
+
+use enostr::{NormRelayUrl, Pubkey, RelayId};
+use nostrdb::{Filter, Ndb, Note, NoteBuilder, Transaction};
+use notedeck::{filter::default_limit, Accounts, RelayType, RemoteApi};
+use tracing::error;
+
+use crate::timeline::kind::GroupRef;
+
+pub const KIND_GROUP_CHAT: u64 = 9;
+pub const KIND_GROUP_JOIN_REQUEST: u64 = 9021;
+pub const KIND_GROUP_METADATA: u64 = 39000;
+pub const KIND_GROUP_ADMINS: u64 = 39001;
+pub const KIND_GROUP_MEMBERS: u64 = 39002;
+
+/// How many groups we ask a relay for when browsing it
+const BROWSE_LIMIT: u64 = 500;
+
+/// Add Column state for browsing the groups of a relay
+#[derive(Default)]
+pub struct GroupBrowseState {
+    /// Relay whose groups are listed
+    pub relay: Option<NormRelayUrl>,
+    pub error: Option<String>,
+}
+
+/// A group's kind 39000 metadata
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GroupMetadata {
+    pub id: String,
+    pub name: Option<String>,
+    pub about: Option<String>,
+    pub picture: Option<String>,
+    /// Only members can read the group
+    pub private: bool,
+    /// Join requests need an admin's approval
+    pub closed: bool,
+}
+
+impl GroupMetadata {
+    pub fn from_note(note: &Note) -> Option<Self> {
+        if note.kind() as u64 != KIND_GROUP_METADATA {
+            return None;
+        }
+
+        let mut metadata = GroupMetadata::default();
+        for tag in note.tags() {
+            let Some(name) = tag.get_str(0) else {
+                continue;
+            };
+
+            let value = tag.get_str(1).map(str::to_owned);
+            match name {
+                "d" => metadata.id = value.unwrap_or_default(),
+                "name" => metadata.name = value,
+                "about" => metadata.about = value,
+                "picture" => metadata.picture = value,
+                "private" => metadata.private = true,
+                "closed" => metadata.closed = true,
+                _ => {}
+            }
+        }
+
+        (!metadata.id.is_empty()).then_some(metadata)
+    }
+
+    pub fn load(ndb: &Ndb, txn: &Transaction, group: &GroupRef) -> Option<Self> {
+        latest_group_note(ndb, txn, group, KIND_GROUP_METADATA)
+            .and_then(|note| GroupMetadata::from_note(&note))
+    }
+
+    /// The group's name, or its id when it has none
+    pub fn display_name(&self) -> &str {
+        self.name
+            .as_deref()
+            .filter(|name| !name.is_empty())
+            .unwrap_or(&self.id)
+    }
+}
+
+/// A group admin with the roles the relay gave them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupAdmin {
+    pub pubkey: Pubkey,
+    pub roles: Vec<String>,
+}
+
+/// What the group's relay has told us about it
+#[derive(Debug, Default)]
+pub struct GroupInfo {
+    pub metadata: Option<GroupMetadata>,
+    pub admins: Vec<GroupAdmin>,
+    /// Empty when the relay keeps its member list to itself
+    pub members: Vec<Pubkey>,
+}
+
+impl GroupInfo {
+    pub fn load(ndb: &Ndb, txn: &Transaction, group: &GroupRef) -> Self {
+        let latest = |kind| latest_group_note(ndb, txn, group, kind);
+
+        GroupInfo {
+            metadata: GroupMetadata::load(ndb, txn, group),
+            admins: latest(KIND_GROUP_ADMINS)
+                .map(|n| group_admins(&n))
+                .unwrap_or_default(),
+            members: latest(KIND_GROUP_MEMBERS)
+                .map(|n| group_members(&n))
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn is_member(&self, pubkey: &Pubkey) -> bool {
+        self.members.contains(pubkey) || self.admins.iter().any(|a| a.pubkey == *pubkey)
+    }
+}
+
+/// The group's name from its metadata, for column titles
+pub fn group_name<'a>(ndb: &Ndb, txn: &'a Transaction, group: &GroupRef) -> Option<&'a str> {
+    let note = latest_group_note(ndb, txn, group, KIND_GROUP_METADATA)?;
+    let name = note
+        .tags()
+        .iter()
+        .find(|tag| tag.get_str(0) == Some("name"))?
+        .get_str(1)?;
+    (!name.is_empty()).then_some(name)
+}
+
+/// A relay typed with or without its `wss://` scheme
+pub fn parse_group_relay(input: &str) -> Option<NormRelayUrl> {
+    let input = input.trim();
+    if input.is_empty() {
+        return None;
+    }
+
+    if input.contains("://") {
+        NormRelayUrl::new(input).ok()
+    } else {
+        NormRelayUrl::new(&format!("wss://{input}")).ok()
+    }
+}
+
+/// A `host'group-id` group address, the form groups are shared in
+pub fn parse_group_address(address: &str) -> Option<GroupRef> {
+    let (host, id) = address.trim().split_once('\'')?;
+    if id.is_empty() {
+        return None;
+    }
+
+    Some(GroupRef::new(parse_group_relay(host)?, id))
+}
+
+/// Chat messages posted to the group
+pub fn group_messages_filter(group: &GroupRef) -> Filter {
+    Filter::new()
+        .kinds([KIND_GROUP_CHAT])
+        .tags([group.id.as_str()], 'h')
+        .limit(default_limit())
+        .build()
+}
+
+/// The relay-signed metadata, admin and member lists of the group
+pub fn group_state_filter(group: &GroupRef) -> Filter {
+    Filter::new()
+        .kinds([KIND_GROUP_METADATA, KIND_GROUP_ADMINS, KIND_GROUP_MEMBERS])
+        .tags([group.id.as_str()], 'd')
+        .build()
+}
+
+/// Every group a relay hosts, for browsing it
+pub fn relay_groups_filter() -> Filter {
+    Filter::new()
+        .kinds([KIND_GROUP_METADATA])
+        .limit(BROWSE_LIMIT)
+        .build()
+}
+
+/// The groups we've seen from `relay`, sorted by name
+pub fn relay_groups(ndb: &Ndb, txn: &Transaction, relay: &NormRelayUrl) -> Vec<GroupMetadata> {
+    let results = match ndb.query(txn, &[relay_groups_filter()], BROWSE_LIMIT as i32) {
+        Ok(results) => results,
+        Err(err) => {
+            error!("group metadata query failed: {err}");
+            return Vec::new();
+        }
+    };
+
+    let mut groups: Vec<GroupMetadata> = Vec::new();
+    for result in results {
+        if !seen_on(&result.note, txn, relay) {
+            continue;
+        }
+
+        let Some(metadata) = GroupMetadata::from_note(&result.note) else {
+            continue;
+        };
+
+        // results are newest first, so the first copy of a group wins
+        if groups.iter().all(|g| g.id != metadata.id) {
+            groups.push(metadata);
+        }
+    }
+
+    groups.sort_by_key(|g| g.display_name().to_lowercase());
+    groups
+}
+
+/// Something to send to a group's relay
+pub enum GroupAction {
+    Send {
+        group: GroupRef,
+        content: String,
+    },
+    /// Ask the relay to add us to the group (kind 9021)
+    Join(GroupRef),
+}
+
+impl GroupAction {
+    pub fn process(self, ndb: &Ndb, accounts: &Accounts, remote: &mut RemoteApi<'_>) {
+        let Some(kp) = accounts.selected_filled() else {
+            error!("can't send to a group without a secret key");
+            return;
+        };
+        let seckey = kp.secret_key.to_secret_bytes();
+
+        let (group, note) = match self {
+            GroupAction::Send { group, content } => {
+                let note = group_note(&seckey, KIND_GROUP_CHAT, &group, &content);
+                (group, note)
+            }
+            GroupAction::Join(group) => {
+                let note = group_note(&seckey, KIND_GROUP_JOIN_REQUEST, &group, "");
+                (group, note)
+            }
+        };
+
+        let Some(note) = note else {
+            error!("failed to build group note for {}", group.id);
+            return;
+        };
+
+        if note.kind() as u64 == KIND_GROUP_CHAT {
+            if let Ok(json) = enostr::ClientMessage::event(&note).and_then(|e| e.to_json()) {
+                let _ = ndb.process_event_with(&json, nostrdb::IngestMetadata::new().client(true));
+            }
+        }
+
+        // group notes only mean something to the group's relay
+        remote.publisher(accounts).publish_note(
+            &note,
+            RelayType::Explicit(vec![RelayId::Websocket(group.relay)]),
+        );
+    }
+}
+
+/// A note addressed to the group with its `h` tag
+fn group_note(
+    seckey: &[u8; 32],
+    kind: u64,
+    group: &GroupRef,
+    content: &str,
+) -> Option<Note<'static>> {
+    NoteBuilder::new()
+        .kind(kind as u32)
+        .content(content)
+        .start_tag()
+        .tag_str("h")
+        .tag_str(&group.id)
+        .sign(seckey)
+        .build()
+}
+
+fn latest_group_note<'a>(
+    ndb: &Ndb,
+    txn: &'a Transaction,
+    group: &GroupRef,
+    kind: u64,
+) -> Option<Note<'a>> {
+    let filter = Filter::new()
+        .kinds([kind])
+        .tags([group.id.as_str()], 'd')
+        .limit(10)
+        .build();
+
+    // other relays may host a group with the same id
+    ndb.query(txn, &[filter], 10)
+        .ok()?
+        .into_iter()
+        .map(|r| r.note)
+        .find(|note| seen_on(note, txn, &group.relay))
+}
+
+fn seen_on(note: &Note, txn: &Transaction, relay: &NormRelayUrl) -> bool {
+    note.relays(txn)
+        .any(|url| NormRelayUrl::new(url).is_ok_and(|url| url == *relay))
+}
+
+fn group_admins(note: &Note) -> Vec<GroupAdmin> {
+    note.tags()
+        .iter()
+        .filter(|tag| tag.get_str(0) == Some("p"))
+        .filter_map(|tag| {
+            let pubkey = Pubkey::new(*tag.get_id(1)?);
+            let roles = (2..tag.count())
+                .filter_map(|i| tag.get_str(i))
+                .map(str::to_owned)
+                .collect();
+            Some(GroupAdmin { pubkey, roles })
+        })
+        .collect()
+}
+
+fn group_members(note: &Note) -> Vec<Pubkey> {
+    note.tags()
+        .iter()
+        .filter(|tag| tag.get_str(0) == Some("p"))
+        .filter_map(|tag| tag.get_id(1).map(|id| Pubkey::new(*id)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use enostr::FullKeypair;
+    use notedeck_testing::fixtures::signed_note;
+
+    #[test]
+    fn metadata_reads_the_moderation_flags() {
+        let note = signed_note(
+            &FullKeypair::generate(),
+            KIND_GROUP_METADATA as u32,
+            &[
+                &["d", "pizza"],
+                &["name", "Pizza Lovers"],
+                &["about", "slices"],
+                &["private"],
+                &["closed"],
+            ],
+        );
+
+        let metadata = GroupMetadata::from_note(&note).unwrap();
+        assert_eq!(metadata.id, "pizza");
+        assert_eq!(metadata.display_name(), "Pizza Lovers");
+        assert_eq!(metadata.about.as_deref(), Some("slices"));
+        assert!(metadata.private);
+        assert!(metadata.closed);
+
+        let open = signed_note(
+            &FullKeypair::generate(),
+            KIND_GROUP_METADATA as u32,
+            &[&["d", "open"], &["public"]],
+        );
+        let open = GroupMetadata::from_note(&open).unwrap();
+        assert_eq!(open.display_name(), "open");
+        assert!(!open.private && !open.closed);
+    }
+
+    #[test]
+    fn admins_keep_their_roles() {
+        let admin = "32e1827635450ebb3c5a7d12c1f8e7b2b514439ac10a67eef3d9fd9c5c68e245";
+        let note = signed_note(
+            &FullKeypair::generate(),
+            KIND_GROUP_ADMINS as u32,
+            &[&["d", "pizza"], &["p", admin, "ceo", "moderator"]],
+        );
+
+        let admins = group_admins(&note);
+        assert_eq!(admins.len(), 1);
+        assert_eq!(admins[0].pubkey, Pubkey::from_hex(admin).unwrap());
+        assert_eq!(admins[0].roles, ["ceo", "moderator"]);
+    }
+
+    #[test]
+    fn group_addresses_parse() {
+        let group = parse_group_address("groups.example.com'pizza").unwrap();
+        assert_eq!(group.id, "pizza");
+        assert_eq!(
+            group.relay,
+            NormRelayUrl::new("wss://groups.example.com").unwrap()
+        );
+
+        assert_eq!(
+            parse_group_address("wss://groups.example.com'pizza"),
+            Some(group)
+        );
+        assert_eq!(parse_group_address("groups.example.com"), None);
+        assert_eq!(parse_group_address("groups.example.com'"), None);
+        assert_eq!(parse_group_address("'pizza"), None);
+    }
+
+    #[test]
+    fn group_notes_carry_the_h_tag() {
+        let keypair = FullKeypair::generate();
+        let group = GroupRef::new(
+            NormRelayUrl::new("wss://groups.example.com").unwrap(),
+            "pizza",
+        );
+        let note = group_note(
+            &keypair.secret_key.secret_bytes(),
+            KIND_GROUP_CHAT,
+            &group,
+            "hi",
+        )
+        .unwrap();
+
+        let h = note
+            .tags()
+            .iter()
+            .find(|tag| tag.get_str(0) == Some("h"))
+            .and_then(|tag| tag.get_str(1));
+        assert_eq!(h, Some("pizza"));
+        assert_eq!(note.kind() as u64, KIND_GROUP_CHAT);
+    }
+}
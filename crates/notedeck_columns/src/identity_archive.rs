@@ -0,0 +1,567 @@
+//! Backing up an account's whole nostr identity into a single zip and
+//! restoring it, for moving to another device or client without depending
+//! on relays still having everything.
+//!
+//! An archive holds the account's npub, its secret key encrypted with a
+//! password (NIP-49) when we have one, and every event of its own we have
+//! locally as JSON lines.
+//!
+//! Files are picked on the UI thread; reading, writing and the key
+//! encryption run on the job pool.
+
+use std::io::{Cursor, Read, Write};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use enostr::{FullKeypair, Keypair, NormRelayUrl, Pubkey, RelayId};
+use hashbrown::HashMap;
+use nostrdb::{Filter, IngestMetadata, Ndb, Transaction};
+use notedeck::{Accounts, AddAccountResponse, JobPool, RelayType, RemoteApi};
+use tokio::sync::oneshot::{self, Receiver};
+use tracing::{error, info};
+use zip::{result::ZipError, write::SimpleFileOptions, ZipArchive, ZipWriter};
+
+const NPUB_FILE: &str = "npub.txt";
+const KEY_FILE: &str = "key.ncryptsec";
+const EVENTS_FILE: &str = "events.jsonl";
+
+/// The account's lists: profile, contacts, mute list, relay list,
+/// bookmarks and DM relay list. The newest of each is republished after an
+/// import.
+const LIST_KINDS: [u64; 6] = [0, 3, 10000, 10002, 10003, 10050];
+
+/// Most of the account's own events we'll put in an archive
+const MAX_ARCHIVE_EVENTS: i32 = 100_000;
+
+/// NIP-49 scrypt cost of the archived key. Decrypting takes a moment, which
+/// is the point.
+const KEY_LOG_N: u8 = 16;
+
+/// How long we wait for the database to take the imported lists before
+/// giving up on republishing them
+const REPUBLISH_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, thiserror::Error)]
+pub enum ArchiveError {
+    #[error("a password is needed to protect the secret key")]
+    MissingPassword,
+
+    #[error("wrong password")]
+    WrongPassword,
+
+    #[error("not an identity archive")]
+    NotAnArchive,
+
+    #[error("zip error: {0}")]
+    Zip(#[from] ZipError),
+
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("key error: {0}")]
+    Key(#[from] enostr::Error),
+
+    #[error("database error: {0}")]
+    Db(#[from] nostrdb::Error),
+
+    #[error("the backup job was dropped")]
+    Dropped,
+}
+
+/// What the last export or import did, for the keys section
+pub enum ArchiveStatus {
+    Exported(PathBuf),
+    Imported(usize),
+    Failed(String),
+}
+
+/// State of the identity archive controls in the keys section
+#[derive(Default)]
+pub struct IdentityArchiveState {
+    pub password: String,
+    pub status: Option<ArchiveStatus>,
+    job: Option<ArchiveJob>,
+    restored: Option<RestoredIdentity>,
+    republish: Option<PendingRepublish>,
+}
+
+/// An export or import running on the job pool
+enum ArchiveJob {
+    Export(Receiver<Result<PathBuf, ArchiveError>>),
+    Import(Receiver<Result<RestoredIdentity, ArchiveError>>),
+}
+
+impl IdentityArchiveState {
+    pub fn finished(&mut self, result: Result<ArchiveStatus, ArchiveError>) {
+        self.password.clear();
+        self.status = Some(result.unwrap_or_else(|err| ArchiveStatus::Failed(err.to_string())));
+    }
+
+    pub fn is_exporting(&self) -> bool {
+        matches!(self.job, Some(ArchiveJob::Export(_)))
+    }
+
+    pub fn is_importing(&self) -> bool {
+        matches!(self.job, Some(ArchiveJob::Import(_)))
+    }
+
+    /// Finish a running export or import, and republish imported lists once
+    /// the database has them
+    pub fn poll(&mut self, ndb: &Ndb, remote: &mut RemoteApi<'_>, accounts: &Accounts) {
+        self.poll_job();
+
+        let Some(pending) = &mut self.republish else {
+            return;
+        };
+
+        if pending.poll(ndb, remote, accounts) {
+            self.republish = None;
+        }
+    }
+
+    fn poll_job(&mut self) {
+        let result = match &mut self.job {
+            None => return,
+            Some(ArchiveJob::Export(rx)) => match rx.try_recv() {
+                Err(oneshot::error::TryRecvError::Empty) => return,
+                Err(oneshot::error::TryRecvError::Closed) => Err(ArchiveError::Dropped),
+                Ok(result) => result.map(ArchiveStatus::Exported),
+            },
+            Some(ArchiveJob::Import(rx)) => match rx.try_recv() {
+                Err(oneshot::error::TryRecvError::Empty) => return,
+                Err(oneshot::error::TryRecvError::Closed) => Err(ArchiveError::Dropped),
+                Ok(result) => result.map(|restored| {
+                    let events = restored.events;
+                    self.restored = Some(restored);
+                    ArchiveStatus::Imported(events)
+                }),
+            },
+        };
+
+        self.job = None;
+        self.finished(result);
+    }
+
+    /// Add the account of a finished import and queue its lists to be
+    /// republished. The caller switches to it.
+    pub fn take_restored(&mut self, accounts: &mut Accounts) -> Option<ImportedIdentity> {
+        let restored = self.restored.take()?;
+        let pubkey = restored.keypair.pubkey;
+        self.republish = Some(restored.republish);
+
+        Some(ImportedIdentity {
+            pubkey,
+            added: accounts.add_account(restored.keypair),
+            events: restored.events,
+        })
+    }
+}
+
+/// An archive whose events are in the database, waiting for its account to
+/// be added
+struct RestoredIdentity {
+    keypair: Keypair,
+    events: usize,
+    republish: PendingRepublish,
+}
+
+/// An identity read back out of an archive
+struct IdentityArchive {
+    keypair: Keypair,
+    /// Raw event json, one per event
+    events: Vec<String>,
+}
+
+/// Imported lists waiting for nostrdb to finish ingesting them
+struct PendingRepublish {
+    ids: Vec<[u8; 32]>,
+    relays: RelayType,
+    started: Instant,
+}
+
+impl PendingRepublish {
+    /// Publish whichever lists arrived. Returns true once there's nothing
+    /// left to do.
+    fn poll(&mut self, ndb: &Ndb, remote: &mut RemoteApi<'_>, accounts: &Accounts) -> bool {
+        let Ok(txn) = Transaction::new(ndb) else {
+            return false;
+        };
+        let mut publisher = remote.publisher(accounts);
+
+        self.ids.retain(|id| {
+            let Ok(note) = ndb.get_note_by_id(&txn, id) else {
+                return true;
+            };
+
+            publisher.publish_note(&note, self.relays.clone());
+            false
+        });
+
+        if !self.ids.is_empty() && self.started.elapsed() > REPUBLISH_TIMEOUT {
+            error!("gave up republishing {} imported lists", self.ids.len());
+            return true;
+        }
+
+        self.ids.is_empty()
+    }
+}
+
+/// Write the selected account's archive to a file the user picks, in the
+/// background. Does nothing if they cancel or a job is already running.
+pub fn export_identity(
+    ndb: &Ndb,
+    accounts: &Accounts,
+    job_pool: &JobPool,
+    state: &mut IdentityArchiveState,
+) {
+    if state.job.is_some() {
+        return;
+    }
+
+    let key = accounts.get_selected_account().key.clone();
+    if key.secret_key.is_some() && state.password.is_empty() {
+        state.finished(Err(ArchiveError::MissingPassword));
+        return;
+    }
+
+    let Some(path) = pick_save_path(&key.pubkey) else {
+        return;
+    };
+
+    let ndb = ndb.clone();
+    let password = state.password.clone();
+    let rx = job_pool.schedule_receivable(move || -> Result<PathBuf, ArchiveError> {
+        let txn = Transaction::new(&ndb)?;
+        let events = own_events(&ndb, &txn, &key.pubkey);
+        let archive = write_archive(&key, &password, &events)?;
+        std::fs::write(&path, archive)?;
+
+        info!("exported {} events to {}", events.len(), path.display());
+        Ok(path)
+    });
+    state.job = Some(ArchiveJob::Export(rx));
+}
+
+pub struct ImportedIdentity {
+    pub pubkey: Pubkey,
+    /// Set if the account wasn't already signed in
+    pub added: Option<AddAccountResponse>,
+    pub events: usize,
+}
+
+/// Restore an archive the user picks, in the background: store its events
+/// and find its lists to republish. Once done,
+/// [`IdentityArchiveState::take_restored`] adds its account. Does nothing if
+/// they cancel or a job is already running.
+pub fn import_identity(ndb: &Ndb, job_pool: &JobPool, state: &mut IdentityArchiveState) {
+    if state.job.is_some() {
+        return;
+    }
+
+    let Some(path) = pick_open_path() else {
+        return;
+    };
+
+    let ndb = ndb.clone();
+    let password = state.password.clone();
+    let rx = job_pool.schedule_receivable(move || restore_archive(&ndb, &path, &password));
+    state.job = Some(ArchiveJob::Import(rx));
+}
+
+fn restore_archive(
+    ndb: &Ndb,
+    path: &std::path::Path,
+    password: &str,
+) -> Result<RestoredIdentity, ArchiveError> {
+    let bytes = std::fs::read(path)?;
+    let archive = read_archive(&bytes, password)?;
+    let pubkey = archive.keypair.pubkey;
+
+    for event in &archive.events {
+        let msg = format!(r#"["EVENT",{event}]"#);
+        if let Err(err) = ndb.process_event_with(&msg, IngestMetadata::new().client(true)) {
+            error!("could not import event: {err}");
+        }
+    }
+
+    let lists = newest_lists(&pubkey, &archive.events);
+    let relays = write_relays(lists.iter().find(|list| list.kind == 10002));
+    let republish = PendingRepublish {
+        ids: lists.iter().map(|list| list.id).collect(),
+        relays: if relays.is_empty() {
+            RelayType::AccountsWrite
+        } else {
+            RelayType::Explicit(relays)
+        },
+        started: Instant::now(),
+    };
+
+    info!(
+        "imported {} events for {}",
+        archive.events.len(),
+        pubkey.hex()
+    );
+    Ok(RestoredIdentity {
+        keypair: archive.keypair,
+        events: archive.events.len(),
+        republish,
+    })
+}
+
+fn own_events(ndb: &Ndb, txn: &Transaction, pubkey: &Pubkey) -> Vec<String> {
+    let filter = Filter::new()
+        .authors([pubkey.bytes()])
+        .limit(MAX_ARCHIVE_EVENTS as u64)
+        .build();
+
+    ndb.query(txn, &[filter], MAX_ARCHIVE_EVENTS)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|result| result.note.json().ok())
+        .collect()
+}
+
+fn write_archive(
+    key: &Keypair,
+    password: &str,
+    events: &[String],
+) -> Result<Vec<u8>, ArchiveError> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default();
+
+    let npub = key.pubkey.npub().ok_or(enostr::Error::InvalidPublicKey)?;
+    zip.start_file(NPUB_FILE, options)?;
+    zip.write_all(npub.as_bytes())?;
+
+    if let Some(filled) = key.to_full() {
+        zip.start_file(KEY_FILE, options)?;
+        zip.write_all(filled.to_ncryptsec(password, KEY_LOG_N)?.as_bytes())?;
+    }
+
+    zip.start_file(EVENTS_FILE, options)?;
+    for event in events {
+        zip.write_all(event.as_bytes())?;
+        zip.write_all(b"\n")?;
+    }
+
+    Ok(zip.finish()?.into_inner())
+}
+
+fn read_archive(bytes: &[u8], password: &str) -> Result<IdentityArchive, ArchiveError> {
+    let mut zip = ZipArchive::new(Cursor::new(bytes)).map_err(|_| ArchiveError::NotAnArchive)?;
+
+    let npub = read_file(&mut zip, NPUB_FILE)?.ok_or(ArchiveError::NotAnArchive)?;
+    let pubkey = Pubkey::parse(npub.trim()).map_err(|_| ArchiveError::NotAnArchive)?;
+
+    let keypair = match read_file(&mut zip, KEY_FILE)? {
+        Some(ncryptsec) => {
+            let full =
+                FullKeypair::from_ncryptsec(&ncryptsec, password).map_err(|err| match err {
+                    enostr::Error::DecodeFailed(_) => ArchiveError::WrongPassword,
+                    _ => ArchiveError::NotAnArchive,
+                })?;
+            if full.pubkey != pubkey {
+                return Err(ArchiveError::NotAnArchive);
+            }
+            full.to_keypair()
+        }
+        None => Keypair::only_pubkey(pubkey),
+    };
+
+    let events = read_file(&mut zip, EVENTS_FILE)?
+        .unwrap_or_default()
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(str::to_owned)
+        .collect();
+
+    Ok(IdentityArchive { keypair, events })
+}
+
+fn read_file(
+    zip: &mut ZipArchive<Cursor<&[u8]>>,
+    name: &str,
+) -> Result<Option<String>, ArchiveError> {
+    let mut file = match zip.by_name(name) {
+        Ok(file) => file,
+        Err(ZipError::FileNotFound) => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)?;
+    Ok(Some(contents))
+}
+
+/// The parts of an archived list event we need to republish it
+struct ArchivedList {
+    id: [u8; 32],
+    kind: u64,
+    created_at: u64,
+    tags: Vec<Vec<String>>,
+}
+
+/// The newest of each of the account's lists among `events`
+fn newest_lists(pubkey: &Pubkey, events: &[String]) -> Vec<ArchivedList> {
+    let mut newest: HashMap<u64, ArchivedList> = HashMap::new();
+
+    for event in events {
+        let Ok(event) = serde_json::from_str::<serde_json::Value>(event) else {
+            continue;
+        };
+        if event["pubkey"].as_str() != Some(&pubkey.hex()) {
+            continue;
+        }
+
+        let Some(kind) = event["kind"].as_u64().filter(|k| LIST_KINDS.contains(k)) else {
+            continue;
+        };
+        let Some(id) = event["id"]
+            .as_str()
+            .and_then(|id| hex::decode(id).ok())
+            .and_then(|id| <[u8; 32]>::try_from(id).ok())
+        else {
+            continue;
+        };
+        let created_at = event["created_at"].as_u64().unwrap_or_default();
+        let tags = serde_json::from_value(event["tags"].clone()).unwrap_or_default();
+
+        if newest
+            .get(&kind)
+            .is_some_and(|list| list.created_at >= created_at)
+        {
+            continue;
+        }
+        newest.insert(
+            kind,
+            ArchivedList {
+                id,
+                kind,
+                created_at,
+                tags,
+            },
+        );
+    }
+
+    newest.into_values().collect()
+}
+
+/// Where a NIP-65 relay list says the account writes
+fn write_relays(relay_list: Option<&ArchivedList>) -> Vec<RelayId> {
+    let Some(relay_list) = relay_list else {
+        return Vec::new();
+    };
+
+    relay_list
+        .tags
+        .iter()
+        .filter(|tag| tag.first().map(String::as_str) == Some("r"))
+        .filter(|tag| tag.get(2).is_none_or(|marker| marker == "write"))
+        .filter_map(|tag| NormRelayUrl::new(tag.get(1)?).ok())
+        .map(RelayId::Websocket)
+        .collect()
+}
+
+fn pick_save_path(pubkey: &Pubkey) -> Option<PathBuf> {
+    #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+    {
+        rfd::FileDialog::new()
+            .add_filter("Zip", &["zip"])
+            .set_file_name(format!("nostr-identity-{}.zip", &pubkey.hex()[..8]))
+            .save_file()
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+    {
+        let _ = pubkey;
+        error!("saving an identity archive needs a desktop file dialog");
+        None
+    }
+}
+
+fn pick_open_path() -> Option<PathBuf> {
+    #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+    {
+        rfd::FileDialog::new()
+            .add_filter("Zip", &["zip"])
+            .pick_file()
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+    {
+        error!("opening an identity archive needs a desktop file dialog");
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(pubkey: &Pubkey, id: u8, kind: u64, created_at: u64, tags: &str) -> String {
+        format!(
+            r#"{{"id":"{}","pubkey":"{}","created_at":{created_at},"kind":{kind},"tags":{tags},"content":"","sig":""}}"#,
+            hex::encode([id; 32]),
+            pubkey.hex()
+        )
+    }
+
+    #[test]
+    fn archives_round_trip() {
+        let kp = FullKeypair::generate();
+        let events = vec![
+            event(&kp.pubkey, 1, 1, 1, "[]"),
+            event(&kp.pubkey, 2, 3, 2, "[]"),
+        ];
+        let bytes = write_archive(&kp.clone().to_keypair(), "hunter2", &events).expect("archive");
+
+        assert!(matches!(
+            read_archive(&bytes, "hunter3"),
+            Err(ArchiveError::WrongPassword)
+        ));
+
+        let archive = read_archive(&bytes, "hunter2").expect("read");
+        assert_eq!(archive.keypair, kp.clone().to_keypair());
+        assert_eq!(archive.events, events);
+
+        // read-only accounts archive without a key
+        let pubkey_only = Keypair::only_pubkey(kp.pubkey);
+        let bytes = write_archive(&pubkey_only, "", &events).expect("archive");
+        assert_eq!(read_archive(&bytes, "").expect("read").keypair, pubkey_only);
+
+        assert!(matches!(
+            read_archive(b"not a zip", ""),
+            Err(ArchiveError::NotAnArchive)
+        ));
+    }
+
+    #[test]
+    fn republishes_newest_lists_to_write_relays() {
+        let pubkey = FullKeypair::generate().pubkey;
+        let other = FullKeypair::generate().pubkey;
+        let events = vec![
+            event(&pubkey, 1, 10002, 1, r#"[["r","wss://old.example.com"]]"#),
+            event(
+                &pubkey,
+                2,
+                10002,
+                2,
+                r#"[["r","wss://both.example.com"],["r","wss://write.example.com","write"],["r","wss://read.example.com","read"]]"#,
+            ),
+            event(&pubkey, 3, 1, 3, "[]"),
+            event(&other, 4, 3, 4, "[]"),
+        ];
+
+        let lists = newest_lists(&pubkey, &events);
+        assert_eq!(lists.len(), 1);
+        assert_eq!(lists[0].id, [2; 32]);
+
+        assert_eq!(
+            write_relays(lists.first()),
+            vec![
+                RelayId::Websocket(NormRelayUrl::new("wss://both.example.com").unwrap()),
+                RelayId::Websocket(NormRelayUrl::new("wss://write.example.com").unwrap()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,61 @@
+//! Copying an account's secret key out of the settings keys section, as
+//! an nsec or a passphrase encrypted ncryptsec.
+//!
+//! A copied key only stays on the clipboard for a short while so it isn't
+//! left around for whatever gets pasted next.
+
+use std::time::{Duration, Instant};
+
+use egui_winit::clipboard::Clipboard;
+
+/// How long a copied secret key is left on the clipboard
+const CLIPBOARD_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Default)]
+pub struct KeyExportState {
+    pub passphrase: String,
+    pub passphrase_confirm: String,
+    pub error: Option<String>,
+    copied: Option<CopiedSecret>,
+}
+
+struct CopiedSecret {
+    secret: String,
+    at: Instant,
+}
+
+impl KeyExportState {
+    /// Put `secret` on the clipboard and schedule it to be cleared
+    pub fn copy(&mut self, clipboard: &mut Clipboard, secret: String) {
+        clipboard.set_text(secret.clone());
+        self.error = None;
+        self.copied = Some(CopiedSecret {
+            secret,
+            at: Instant::now(),
+        });
+    }
+
+    pub fn is_copied(&self) -> bool {
+        self.copied.is_some()
+    }
+
+    /// Clear the clipboard once the timeout has passed, unless something
+    /// else was copied over the key in the meantime.
+    pub fn poll(&mut self, ctx: &egui::Context, clipboard: &mut Clipboard) {
+        let Some(copied) = &self.copied else {
+            return;
+        };
+
+        let elapsed = copied.at.elapsed();
+        if elapsed < CLIPBOARD_TIMEOUT {
+            ctx.request_repaint_after(CLIPBOARD_TIMEOUT - elapsed);
+            return;
+        }
+
+        if clipboard.get().as_deref() == Some(copied.secret.as_str()) {
+            clipboard.set_text(String::new());
+        }
+
+        self.copied = None;
+    }
+}
@@ -3,7 +3,7 @@ use std::str::FromStr;
 
 use crate::Error;
 use ehttp::{Request, Response};
-use enostr::{Keypair, Pubkey, SecretKey};
+use enostr::{FullKeypair, Keypair, Pubkey, SecretKey};
 use poll_promise::Promise;
 use serde::{Deserialize, Serialize};
 use tracing::error;
@@ -12,6 +12,8 @@ use tracing::error;
 pub enum AcquireKeyError {
     InvalidKey,
     Nip05Failed(String),
+    NeedsPassphrase,
+    WrongPassphrase,
 }
 
 impl std::fmt::Display for AcquireKeyError {
@@ -21,6 +23,10 @@ impl std::fmt::Display for AcquireKeyError {
             AcquireKeyError::Nip05Failed(e) => {
                 write!(f, "Failed to get pubkey from Nip05 address: {e}")
             }
+            AcquireKeyError::NeedsPassphrase => {
+                write!(f, "Enter the passphrase for this encrypted key.")
+            }
+            AcquireKeyError::WrongPassphrase => write!(f, "Wrong passphrase."),
         }
     }
 }
@@ -116,6 +122,34 @@ fn nip05_promise_wrapper(id: &str) -> Promise<Result<Keypair, AcquireKeyError>>
     promise
 }
 
+/// Whether `key` is a NIP-49 encrypted secret key, which needs a passphrase
+/// to log in with
+pub fn is_ncryptsec(key: &str) -> bool {
+    key.trim().starts_with("ncryptsec1")
+}
+
+/// Decrypt a NIP-49 `ncryptsec1...` key. The key derivation is deliberately
+/// slow, so this runs off the UI thread.
+pub fn perform_ncryptsec_retrieval(
+    ncryptsec: &str,
+    passphrase: &str,
+) -> Promise<Result<Keypair, AcquireKeyError>> {
+    let (sender, promise) = Promise::new();
+    let ncryptsec = ncryptsec.to_owned();
+    let passphrase = passphrase.to_owned();
+
+    std::thread::spawn(move || {
+        let result = match FullKeypair::from_ncryptsec(&ncryptsec, &passphrase) {
+            Ok(kp) => Ok(kp.to_keypair()),
+            Err(enostr::Error::InvalidBech32) => Err(AcquireKeyError::InvalidKey),
+            Err(_) => Err(AcquireKeyError::WrongPassphrase),
+        };
+        sender.send(result);
+    });
+
+    promise
+}
+
 /// Attempts to turn a string slice key from the user into a Nostr-Sdk Keypair object.
 /// The `key` can be in any of the following formats:
 /// - Public Bech32 key (prefix "npub"): "npub1xyz..."
@@ -124,6 +158,8 @@ fn nip05_promise_wrapper(id: &str) -> Promise<Result<Keypair, AcquireKeyError>>
 /// - Private hex key: "5dab..."
 /// - NIP-05 address: "example@nostr.com"
 ///
+/// Encrypted `ncryptsec1...` keys are rejected with
+/// [`AcquireKeyError::NeedsPassphrase`], see [`perform_ncryptsec_retrieval`].
 pub fn perform_key_retrieval(key: &str) -> Promise<Result<Keypair, AcquireKeyError>> {
     let tmp_key: &str = if let Some(stripped) = key.strip_prefix('@') {
         stripped
@@ -131,7 +167,9 @@ pub fn perform_key_retrieval(key: &str) -> Promise<Result<Keypair, AcquireKeyErr
         key
     };
 
-    if retrieving_nip05_pubkey(tmp_key) {
+    if is_ncryptsec(tmp_key) {
+        Promise::from_ready(Err(AcquireKeyError::NeedsPassphrase))
+    } else if retrieving_nip05_pubkey(tmp_key) {
         nip05_promise_wrapper(tmp_key)
     } else {
         let res = if let Ok(pubkey) = Pubkey::try_from_bech32_string(tmp_key, true) {
@@ -206,6 +244,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_ncryptsec() {
+        let kp = FullKeypair::generate();
+        let ncryptsec = kp.to_filled().to_ncryptsec("hunter2", 4).unwrap();
+
+        promise_assert!(
+            assert_eq,
+            Err(AcquireKeyError::NeedsPassphrase),
+            &perform_key_retrieval(&ncryptsec)
+        );
+        promise_assert!(
+            assert_eq,
+            Err(AcquireKeyError::WrongPassphrase),
+            &perform_ncryptsec_retrieval(&ncryptsec, "hunter3")
+        );
+        promise_assert!(
+            assert_eq,
+            Ok(kp.to_keypair()),
+            &perform_ncryptsec_retrieval(&ncryptsec, "hunter2")
+        );
+    }
+
     #[test]
     fn test_nip05() {
         let nip05_str = "damus@damus.io";
@@ -0,0 +1,163 @@
+//! Moving an account to a freshly generated key, for when the old secret
+//! key may have leaked. Nostr has no way to revoke a key, so the best we can
+//! do is carry the account's public state over and point people at the new
+//! key from the old one.
+
+use enostr::{FilledKeypair, FullKeypair, Pubkey};
+use nostrdb::{Filter, Ndb, Note, NoteBuilder, Tag, Transaction};
+use notedeck::{
+    builder_from_note, note::publish::publish_note_builder, tr, Accounts, AddAccountResponse,
+    Localization, PublishApi, RemoteApi,
+};
+use tracing::info;
+
+/// Replaceable events that make up an account: profile, contacts, relay
+/// list and DM relay list
+const CARRIED_KINDS: [u64; 4] = [0, 3, 10002, 10050];
+
+/// Generate a new key for the selected account, republish its profile and
+/// lists from it, announce the move from the old key and add the new key as
+/// an account. The caller still has to switch to it.
+pub fn rotate_selected_key(
+    ndb: &Ndb,
+    accounts: &mut Accounts,
+    remote: &mut RemoteApi<'_>,
+    i18n: &mut Localization,
+) -> Option<AddAccountResponse> {
+    let old = accounts.selected_filled()?.to_full();
+    let new = FullKeypair::generate();
+    info!("rotating key {} to {}", old.pubkey.hex(), new.pubkey.hex());
+
+    {
+        // everything goes out to the old account's write relays, which the
+        // new key takes over along with the relay list
+        let mut publisher = remote.publisher(accounts);
+        let txn = Transaction::new(ndb).expect("txn");
+
+        for kind in CARRIED_KINDS {
+            let Some(note) = latest_note(ndb, &txn, &old.pubkey, kind) else {
+                continue;
+            };
+
+            // signing with the new key makes it the author
+            let builder = builder_from_note(note, None::<fn(&Tag<'_>) -> bool>);
+            publish_note_builder(builder, ndb, &mut publisher, new.to_filled());
+        }
+
+        if let Some(contacts) = latest_note(ndb, &txn, &old.pubkey, 3) {
+            let builder = contacts_following(contacts, &new.pubkey);
+            publish_note_builder(builder, ndb, &mut publisher, old.to_filled());
+        }
+
+        publish_migration_notice(ndb, &mut publisher, old.to_filled(), &new.pubkey, i18n);
+    }
+
+    accounts.add_account(new.to_keypair())
+}
+
+fn latest_note<'a>(
+    ndb: &Ndb,
+    txn: &'a Transaction,
+    pubkey: &Pubkey,
+    kind: u64,
+) -> Option<Note<'a>> {
+    let filter = Filter::new()
+        .authors([pubkey.bytes()])
+        .kinds([kind])
+        .limit(1)
+        .build();
+    let result = ndb.query(txn, &[filter], 1).ok()?.into_iter().next()?;
+    Some(result.note)
+}
+
+/// The old contact list with the new key followed, so the old account's
+/// followers can find it from there too
+fn contacts_following<'a>(contacts: Note<'a>, new: &Pubkey) -> NoteBuilder<'a> {
+    let new_hex = new.hex();
+    let already_following = contacts.tags().into_iter().any(|tag| {
+        tag.count() >= 2
+            && tag.get_str(0) == Some("p")
+            && (tag.get_id(1) == Some(new.bytes()) || tag.get_str(1) == Some(&new_hex))
+    });
+
+    let builder = builder_from_note(contacts, None::<fn(&Tag<'_>) -> bool>);
+    if already_following {
+        return builder;
+    }
+
+    builder.start_tag().tag_str("p").tag_str(&new_hex)
+}
+
+fn publish_migration_notice(
+    ndb: &Ndb,
+    publisher: &mut PublishApi<'_, '_>,
+    old: FilledKeypair<'_>,
+    new: &Pubkey,
+    i18n: &mut Localization,
+) {
+    let Some(npub) = new.npub() else {
+        return;
+    };
+
+    let content = tr!(
+        i18n,
+        "This account has moved to a new key. Please follow me at nostr:{npub}",
+        "Note published from an old key pointing followers to the account's new key",
+        npub = npub
+    );
+
+    let builder = NoteBuilder::new()
+        .kind(1)
+        .content(&content)
+        .start_tag()
+        .tag_str("p")
+        .tag_str(&new.hex());
+
+    publish_note_builder(builder, ndb, publisher, old);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contact_list(follows: &[Pubkey]) -> Note<'static> {
+        let kp = FullKeypair::generate();
+        let mut builder = NoteBuilder::new().kind(3).content("");
+        for pk in follows {
+            builder = builder.start_tag().tag_str("p").tag_str(&pk.hex());
+        }
+        builder
+            .sign(&kp.secret_key.secret_bytes())
+            .build()
+            .expect("note")
+    }
+
+    fn follows(note: &Note<'_>) -> usize {
+        note.tags()
+            .into_iter()
+            .filter(|tag| tag.get_str(0) == Some("p"))
+            .count()
+    }
+
+    #[test]
+    fn old_contacts_follow_new_key_once() {
+        let friend = FullKeypair::generate().pubkey;
+        let new = FullKeypair::generate().pubkey;
+        let signer = FullKeypair::generate();
+        let sec = signer.secret_key.secret_bytes();
+
+        let contacts = contact_list(&[friend]);
+        let updated = contacts_following(contacts, &new)
+            .sign(&sec)
+            .build()
+            .expect("note");
+        assert_eq!(follows(&updated), 2);
+
+        let contacts = contact_list(&[friend, new]);
+        let updated = contacts_following(contacts, &new)
+            .sign(&sec)
+            .build()
+            .expect("note");
+        assert_eq!(follows(&updated), 2);
+    }
+}
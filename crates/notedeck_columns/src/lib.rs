@@ -9,23 +9,35 @@ pub mod app_creation;
 mod app_style;
 mod args;
 pub mod column;
+mod community;
 mod deck_state;
 mod decks;
 mod draft;
+mod group;
+mod identity_archive;
+mod key_export;
 mod key_parsing;
+mod key_rotation;
 pub mod login_manager;
+mod media_metadata;
+mod media_servers;
 mod media_upload;
+mod mention_notifier;
+mod mute_list;
 mod nav;
 mod onboarding;
 pub mod options;
 mod post;
 mod profile;
+mod publish_check;
 mod repost;
 mod route;
+mod scheduled_posts;
 mod scoped_sub_owner_keys;
 mod search;
 mod support;
 mod test_data;
+mod thread_export;
 pub mod timeline;
 mod timeline_loader;
 mod toolbar;
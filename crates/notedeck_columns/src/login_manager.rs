@@ -1,5 +1,6 @@
 use crate::key_parsing::perform_key_retrieval;
 use crate::key_parsing::AcquireKeyError;
+use crate::key_parsing::{is_ncryptsec, perform_ncryptsec_retrieval};
 use egui::{TextBuffer, TextEdit};
 use enostr::Keypair;
 use notedeck::{tr, Localization};
@@ -9,6 +10,7 @@ use poll_promise::Promise;
 #[derive(Default)]
 pub struct AcquireKeyState {
     desired_key: String,
+    passphrase: String,
     promise_query: Option<(String, Promise<Result<Keypair, AcquireKeyError>>)>,
     error: Option<AcquireKeyError>,
     key_on_error: Option<String>,
@@ -33,8 +35,29 @@ impl<'a> AcquireKeyState {
         &mut self.desired_key
     }
 
+    pub fn passphrase_buffer(&mut self) -> &mut String {
+        &mut self.passphrase
+    }
+
+    /// Whether the entered key is encrypted and needs a passphrase
+    pub fn needs_passphrase(&self) -> bool {
+        is_ncryptsec(&self.desired_key)
+    }
+
     /// User pressed the 'acquire' button
     pub fn apply_acquire(&'a mut self) {
+        // the key stays the same while the passphrase is retried
+        if self.needs_passphrase() && !self.passphrase.is_empty() {
+            if !self.is_awaiting_network() {
+                self.error = None;
+                self.promise_query = Some((
+                    self.desired_key.clone(),
+                    perform_ncryptsec_retrieval(self.desired_key.trim(), &self.passphrase),
+                ));
+            }
+            return;
+        }
+
         let new_promise = match &self.promise_query {
             Some((query, _)) => {
                 if query != &self.desired_key {
@@ -145,6 +168,22 @@ fn show_error(ui: &mut egui::Ui, i18n: &mut Localization, err: &AcquireKeyError)
             AcquireKeyError::Nip05Failed(e) => {
                 egui::Label::new(egui::RichText::new(e).color(ui.visuals().error_fg_color))
             }
+            AcquireKeyError::NeedsPassphrase => egui::Label::new(
+                egui::RichText::new(tr!(
+                    i18n,
+                    "Enter the passphrase for this encrypted key.",
+                    "Error message when an encrypted (ncryptsec) key is entered without its passphrase"
+                ))
+                .color(ui.visuals().error_fg_color),
+            ),
+            AcquireKeyError::WrongPassphrase => egui::Label::new(
+                egui::RichText::new(tr!(
+                    i18n,
+                    "Wrong passphrase.",
+                    "Error message when an encrypted (ncryptsec) key can't be decrypted"
+                ))
+                .color(ui.visuals().error_fg_color),
+            ),
         };
         ui.add(error_label.truncate());
     });
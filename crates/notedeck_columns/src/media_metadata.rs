@@ -0,0 +1,319 @@
+#![cfg_attr(target_os = "android", allow(dead_code))]
+
+//! Removes EXIF, XMP and text metadata from images before they are
+//! uploaded, so photos don't leak GPS coordinates, device details or
+//! timestamps. Only the container is rewritten; pixel data is copied as is.
+
+/// Remove metadata from an image of the given mime type. Types we don't
+/// know how to clean are returned unchanged.
+pub fn strip_metadata(mime: &str, bytes: Vec<u8>) -> Result<Vec<u8>, String> {
+    match mime {
+        "image/jpeg" => strip_jpeg(&bytes),
+        "image/png" => strip_png(&bytes),
+        "image/webp" => strip_webp(&bytes),
+        _ => Ok(bytes),
+    }
+}
+
+const JPEG_SOI: u8 = 0xD8;
+const JPEG_SOS: u8 = 0xDA;
+const JPEG_APP1: u8 = 0xE1;
+const JPEG_APP13: u8 = 0xED;
+const JPEG_COM: u8 = 0xFE;
+
+const EXIF_HEADER: &[u8] = b"Exif\0\0";
+const EXIF_ORIENTATION_TAG: u16 = 0x0112;
+
+fn strip_jpeg(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    if bytes.len() < 4 || bytes[0] != 0xFF || bytes[1] != JPEG_SOI {
+        return Err("not a jpeg".to_owned());
+    }
+
+    let mut out = Vec::with_capacity(bytes.len());
+    out.extend_from_slice(&bytes[..2]);
+
+    let mut orientation = None;
+    let mut pos = 2;
+
+    loop {
+        if pos + 1 >= bytes.len() || bytes[pos] != 0xFF {
+            return Err("truncated jpeg".to_owned());
+        }
+
+        let marker = bytes[pos + 1];
+
+        // fill bytes
+        if marker == 0xFF {
+            pos += 1;
+            continue;
+        }
+
+        // standalone markers have no length
+        if marker == 0x01 || (0xD0..=0xD7).contains(&marker) {
+            out.extend_from_slice(&bytes[pos..pos + 2]);
+            pos += 2;
+            continue;
+        }
+
+        if pos + 4 > bytes.len() {
+            return Err("truncated jpeg segment".to_owned());
+        }
+
+        let len = u16::from_be_bytes([bytes[pos + 2], bytes[pos + 3]]) as usize;
+        let end = pos + 2 + len;
+        if len < 2 || end > bytes.len() {
+            return Err("invalid jpeg segment length".to_owned());
+        }
+
+        match marker {
+            JPEG_APP1 => {
+                // Exif or XMP. Only the orientation survives, otherwise
+                // photos from phones would show up rotated.
+                let payload = &bytes[pos + 4..end];
+                orientation = orientation.or_else(|| exif_orientation(payload));
+            }
+            JPEG_APP13 | JPEG_COM => {}
+            JPEG_SOS => {
+                if let Some(orientation) = orientation {
+                    write_orientation_segment(&mut out, orientation);
+                }
+                // entropy coded data and everything after it is kept
+                out.extend_from_slice(&bytes[pos..]);
+                return Ok(out);
+            }
+            _ => out.extend_from_slice(&bytes[pos..end]),
+        }
+
+        pos = end;
+    }
+}
+
+/// The orientation tag of an APP1 Exif payload, if it has one
+fn exif_orientation(payload: &[u8]) -> Option<u16> {
+    let tiff = payload.strip_prefix(EXIF_HEADER)?;
+
+    let big_endian = match tiff.get(..2)? {
+        b"MM" => true,
+        b"II" => false,
+        _ => return None,
+    };
+
+    let u16_at = |offset: usize| -> Option<u16> {
+        let b = tiff.get(offset..offset + 2)?;
+        Some(if big_endian {
+            u16::from_be_bytes([b[0], b[1]])
+        } else {
+            u16::from_le_bytes([b[0], b[1]])
+        })
+    };
+    let u32_at = |offset: usize| -> Option<u32> {
+        let b = tiff.get(offset..offset + 4)?;
+        Some(if big_endian {
+            u32::from_be_bytes([b[0], b[1], b[2], b[3]])
+        } else {
+            u32::from_le_bytes([b[0], b[1], b[2], b[3]])
+        })
+    };
+
+    let ifd0 = u32_at(4)? as usize;
+    let entries = u16_at(ifd0)? as usize;
+
+    (0..entries)
+        .map(|i| ifd0 + 2 + i * 12)
+        .find(|&entry| u16_at(entry) == Some(EXIF_ORIENTATION_TAG))
+        .and_then(|entry| u16_at(entry + 8))
+        .filter(|orientation| (2..=8).contains(orientation))
+}
+
+/// An APP1 segment holding a minimal Exif block with only the orientation
+fn write_orientation_segment(out: &mut Vec<u8>, orientation: u16) {
+    let mut tiff = Vec::with_capacity(26);
+    tiff.extend_from_slice(b"MM\0\x2A");
+    tiff.extend_from_slice(&8u32.to_be_bytes()); // IFD0 offset
+    tiff.extend_from_slice(&1u16.to_be_bytes()); // one entry
+    tiff.extend_from_slice(&EXIF_ORIENTATION_TAG.to_be_bytes());
+    tiff.extend_from_slice(&3u16.to_be_bytes()); // SHORT
+    tiff.extend_from_slice(&1u32.to_be_bytes()); // count
+    tiff.extend_from_slice(&orientation.to_be_bytes());
+    tiff.extend_from_slice(&[0, 0]); // value padding
+    tiff.extend_from_slice(&0u32.to_be_bytes()); // no next IFD
+
+    let len = (2 + EXIF_HEADER.len() + tiff.len()) as u16;
+    out.extend_from_slice(&[0xFF, JPEG_APP1]);
+    out.extend_from_slice(&len.to_be_bytes());
+    out.extend_from_slice(EXIF_HEADER);
+    out.extend_from_slice(&tiff);
+}
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+const PNG_METADATA_CHUNKS: [&[u8; 4]; 5] = [b"eXIf", b"tEXt", b"zTXt", b"iTXt", b"tIME"];
+
+fn strip_png(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    if !bytes.starts_with(PNG_SIGNATURE) {
+        return Err("not a png".to_owned());
+    }
+
+    let mut out = Vec::with_capacity(bytes.len());
+    out.extend_from_slice(PNG_SIGNATURE);
+
+    let mut pos = PNG_SIGNATURE.len();
+    while pos < bytes.len() {
+        let header = bytes
+            .get(pos..pos + 8)
+            .ok_or_else(|| "truncated png chunk".to_owned())?;
+        let len = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        let end = pos + 12 + len; // length, type, data, crc
+        if end > bytes.len() {
+            return Err("invalid png chunk length".to_owned());
+        }
+
+        let chunk_type = &header[4..8];
+        if !PNG_METADATA_CHUNKS.iter().any(|t| t[..] == *chunk_type) {
+            out.extend_from_slice(&bytes[pos..end]);
+        }
+
+        pos = end;
+    }
+
+    Ok(out)
+}
+
+const WEBP_VP8X_EXIF: u8 = 0x08;
+const WEBP_VP8X_XMP: u8 = 0x04;
+
+fn strip_webp(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    if bytes.len() < 12 || &bytes[..4] != b"RIFF" || &bytes[8..12] != b"WEBP" {
+        return Err("not a webp".to_owned());
+    }
+
+    let mut out = Vec::with_capacity(bytes.len());
+    out.extend_from_slice(&bytes[..12]);
+
+    let mut pos = 12;
+    while pos < bytes.len() {
+        let header = bytes
+            .get(pos..pos + 8)
+            .ok_or_else(|| "truncated webp chunk".to_owned())?;
+        let fourcc = &header[..4];
+        let len = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        // chunks are padded to an even size
+        let end = (pos + 8 + len + (len & 1)).min(bytes.len());
+        if pos + 8 + len > bytes.len() {
+            return Err("invalid webp chunk length".to_owned());
+        }
+
+        match fourcc {
+            b"EXIF" | b"XMP " => {}
+            b"VP8X" => {
+                let start = out.len();
+                out.extend_from_slice(&bytes[pos..end]);
+                if let Some(flags) = out.get_mut(start + 8) {
+                    *flags &= !(WEBP_VP8X_EXIF | WEBP_VP8X_XMP);
+                }
+            }
+            _ => out.extend_from_slice(&bytes[pos..end]),
+        }
+
+        pos = end;
+    }
+
+    let riff_size = (out.len() - 8) as u32;
+    out[4..8].copy_from_slice(&riff_size.to_le_bytes());
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jpeg_segment(marker: u8, payload: &[u8]) -> Vec<u8> {
+        let mut seg = vec![0xFF, marker];
+        seg.extend_from_slice(&((payload.len() + 2) as u16).to_be_bytes());
+        seg.extend_from_slice(payload);
+        seg
+    }
+
+    fn exif_with_gps_and_orientation(orientation: u16) -> Vec<u8> {
+        let mut payload = EXIF_HEADER.to_vec();
+        payload.extend_from_slice(b"II\x2A\0");
+        payload.extend_from_slice(&8u32.to_le_bytes());
+        payload.extend_from_slice(&2u16.to_le_bytes());
+        // GPS IFD pointer
+        payload.extend_from_slice(&0x8825u16.to_le_bytes());
+        payload.extend_from_slice(&4u16.to_le_bytes());
+        payload.extend_from_slice(&1u32.to_le_bytes());
+        payload.extend_from_slice(&1234u32.to_le_bytes());
+        // orientation
+        payload.extend_from_slice(&EXIF_ORIENTATION_TAG.to_le_bytes());
+        payload.extend_from_slice(&3u16.to_le_bytes());
+        payload.extend_from_slice(&1u32.to_le_bytes());
+        payload.extend_from_slice(&orientation.to_le_bytes());
+        payload.extend_from_slice(&[0, 0]);
+        payload.extend_from_slice(&0u32.to_le_bytes());
+        payload.extend_from_slice(b"secret location");
+        payload
+    }
+
+    #[test]
+    fn jpeg_keeps_only_orientation() {
+        let jfif = jpeg_segment(0xE0, b"JFIF\0\x01\x01");
+        let scan = [
+            0xFF, JPEG_SOS, 0x00, 0x04, 0x01, 0x02, 0xAB, 0xCD, 0xFF, 0xD9,
+        ];
+
+        let mut jpeg = vec![0xFF, JPEG_SOI];
+        jpeg.extend(&jfif);
+        jpeg.extend(jpeg_segment(JPEG_APP1, &exif_with_gps_and_orientation(6)));
+        jpeg.extend(jpeg_segment(JPEG_COM, b"taken at home"));
+        jpeg.extend(scan);
+
+        let stripped = strip_metadata("image/jpeg", jpeg).unwrap();
+
+        let find = |needle: &[u8]| stripped.windows(needle.len()).any(|w| w == needle);
+        assert!(!find(b"secret location"));
+        assert!(!find(b"taken at home"));
+        assert!(find(&jfif));
+        assert!(stripped.ends_with(&scan));
+
+        let mut expected_exif = Vec::new();
+        write_orientation_segment(&mut expected_exif, 6);
+        assert!(find(&expected_exif));
+        assert_eq!(exif_orientation(&expected_exif[4..]), Some(6));
+    }
+
+    #[test]
+    fn png_drops_metadata_chunks() {
+        let chunk = |kind: &[u8; 4], data: &[u8]| {
+            let mut c = (data.len() as u32).to_be_bytes().to_vec();
+            c.extend_from_slice(kind);
+            c.extend_from_slice(data);
+            c.extend_from_slice(&[0; 4]);
+            c
+        };
+
+        let ihdr = chunk(b"IHDR", &[0; 13]);
+        let idat = chunk(b"IDAT", &[1, 2, 3]);
+        let iend = chunk(b"IEND", &[]);
+
+        let mut png = PNG_SIGNATURE.to_vec();
+        png.extend(&ihdr);
+        png.extend(chunk(b"tEXt", b"Comment\0gps"));
+        png.extend(chunk(b"eXIf", b"MM\0\x2A"));
+        png.extend(&idat);
+        png.extend(&iend);
+
+        let mut expected = PNG_SIGNATURE.to_vec();
+        expected.extend(ihdr);
+        expected.extend(idat);
+        expected.extend(iend);
+
+        assert_eq!(strip_metadata("image/png", png).unwrap(), expected);
+    }
+
+    #[test]
+    fn truncated_images_are_rejected() {
+        assert!(strip_metadata("image/jpeg", vec![0xFF, JPEG_SOI, 0xFF]).is_err());
+        assert!(strip_metadata("image/gif", vec![1, 2, 3]).is_ok());
+    }
+}
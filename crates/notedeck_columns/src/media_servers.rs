@@ -0,0 +1,186 @@
+//! The Blossom media servers an account uploads to. They're kept in the
+//! account's kind `10063` server list (BUD-03), most preferred first.
+
+use std::collections::HashMap;
+
+use enostr::{FilledKeypair, Pubkey};
+use nostrdb::{Filter, Ndb, Note, NoteBuildOptions, NoteBuilder, Transaction};
+use notedeck::{
+    note::publish::publish_note_builder, PublishApi, RelaySelection, ScopedSubApi,
+    ScopedSubIdentity, SubConfig, SubKey,
+};
+use poll_promise::Promise;
+use url::Url;
+
+use crate::{scoped_sub_owner_keys::media_servers_owner_key, Error};
+
+pub const SERVER_LIST_KIND: u64 = 10063;
+
+/// State of the media servers page
+#[derive(Default)]
+pub struct MediaServersState {
+    /// Url typed into the add server field
+    pub new_server: String,
+    pub add_error: Option<String>,
+    /// Url typed into the NIP-96 server field
+    pub new_nip96_server: String,
+    pub nip96_error: Option<String>,
+    pub probes: HashMap<String, ServerProbe>,
+}
+
+/// Result of asking a server whether it would accept our uploads
+pub enum ServerProbe {
+    Running(Promise<Result<(), Error>>),
+    Accepted,
+    Rejected(String),
+}
+
+impl MediaServersState {
+    pub fn poll_probes(&mut self) {
+        for probe in self.probes.values_mut() {
+            let ServerProbe::Running(promise) = probe else {
+                continue;
+            };
+
+            let Some(result) = promise.ready() else {
+                continue;
+            };
+
+            *probe = match result {
+                Ok(()) => ServerProbe::Accepted,
+                Err(e) => ServerProbe::Rejected(e.to_string()),
+            };
+        }
+    }
+}
+
+fn server_list_filter(pubkey: &Pubkey) -> Filter {
+    Filter::new()
+        .authors([pubkey.bytes()])
+        .kinds([SERVER_LIST_KIND])
+        .limit(1)
+        .build()
+}
+
+/// Keep the selected account's server list coming in while the page is open
+pub fn subscribe_server_list(scoped_subs: &mut ScopedSubApi<'_, '_>, pubkey: &Pubkey, col: usize) {
+    let identity =
+        ScopedSubIdentity::account(media_servers_owner_key(col), SubKey::new(SERVER_LIST_KIND));
+    let _ = scoped_subs.ensure_sub(
+        identity,
+        SubConfig {
+            relays: RelaySelection::AccountsRead,
+            filters: vec![server_list_filter(pubkey)],
+            use_transparent: false,
+        },
+    );
+}
+
+/// The account's servers from its latest server list, most preferred first
+pub fn account_servers(ndb: &Ndb, txn: &Transaction, pubkey: &Pubkey) -> Vec<String> {
+    let Ok(results) = ndb.query(txn, &[server_list_filter(pubkey)], 1) else {
+        return Vec::new();
+    };
+
+    results
+        .first()
+        .map(|result| servers_from_note(&result.note))
+        .unwrap_or_default()
+}
+
+fn servers_from_note(note: &Note<'_>) -> Vec<String> {
+    let mut servers: Vec<String> = Vec::new();
+    for tag in note.tags() {
+        if tag.count() < 2 || tag.get_str(0) != Some("server") {
+            continue;
+        }
+
+        let Some(server) = tag.get_str(1).and_then(normalize_server_url) else {
+            continue;
+        };
+
+        if !servers.contains(&server) {
+            servers.push(server);
+        }
+    }
+    servers
+}
+
+/// Clean up a server url typed by the user or found in a list. Bare hosts
+/// are taken to mean https.
+pub fn normalize_server_url(input: &str) -> Option<String> {
+    let input = input.trim();
+    let url = if input.contains("://") {
+        Url::parse(input).ok()?
+    } else {
+        Url::parse(&format!("https://{input}")).ok()?
+    };
+
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+        return None;
+    }
+
+    Some(url.as_str().trim_end_matches('/').to_owned())
+}
+
+fn server_list_builder<'a>(servers: &[String]) -> NoteBuilder<'a> {
+    let mut builder = NoteBuilder::new()
+        .content("")
+        .kind(SERVER_LIST_KIND as u32)
+        .options(NoteBuildOptions::default());
+
+    for server in servers {
+        builder = builder.start_tag().tag_str("server").tag_str(server);
+    }
+
+    builder
+}
+
+pub fn publish_server_list(
+    ndb: &Ndb,
+    publisher: &mut PublishApi<'_, '_>,
+    kp: FilledKeypair<'_>,
+    servers: &[String],
+) {
+    publish_note_builder(server_list_builder(servers), ndb, publisher, kp);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use enostr::FullKeypair;
+
+    #[test]
+    fn server_urls_are_normalized() {
+        assert_eq!(
+            normalize_server_url(" blossom.example.com/ ").as_deref(),
+            Some("https://blossom.example.com")
+        );
+        assert_eq!(
+            normalize_server_url("http://localhost:3000").as_deref(),
+            Some("http://localhost:3000")
+        );
+        assert_eq!(normalize_server_url("wss://relay.example.com"), None);
+        assert_eq!(normalize_server_url(""), None);
+    }
+
+    #[test]
+    fn server_list_roundtrip_skips_duplicates() {
+        let kp = FullKeypair::generate();
+        let servers = vec![
+            "https://cdn.example.com".to_owned(),
+            "https://cdn.example.com/".to_owned(),
+            "https://media.example.net".to_owned(),
+        ];
+
+        let note = server_list_builder(&servers)
+            .sign(&kp.secret_key.secret_bytes())
+            .build()
+            .expect("note");
+
+        assert_eq!(
+            servers_from_note(&note),
+            vec!["https://cdn.example.com", "https://media.example.net"]
+        );
+    }
+}
@@ -2,13 +2,18 @@
 
 use std::io;
 
-use crate::Error;
-use base64::{prelude::BASE64_URL_SAFE, Engine};
+use crate::{media_metadata, Error};
+use base64::{
+    prelude::{BASE64_STANDARD, BASE64_URL_SAFE},
+    Engine,
+};
 use ehttp::Request;
+use enostr::Pubkey;
 use nostrdb::{Note, NoteBuilder};
 use notedeck::{
     media::images::fetch_binary_from_disk,
     platform::file::{MediaFrom, SelectedMedia},
+    unix_time_secs, SettingsHandler,
 };
 use poll_promise::Promise;
 use sha2::{Digest, Sha256};
@@ -72,10 +77,6 @@ fn get_upload_url_from_provider(mut provider_url: Url) -> Promise<Result<String,
     get_upload_url(provider_url)
 }
 
-pub fn get_nostr_build_upload_url() -> Promise<Result<String, Error>> {
-    get_upload_url_from_provider(NOSTR_BUILD_URL())
-}
-
 fn create_nip98_note(seckey: &[u8; 32], upload_url: String, payload_hash: String) -> Note<'_> {
     NoteBuilder::new()
         .kind(27235)
@@ -136,27 +137,40 @@ pub fn nip96_upload(
     seckey: [u8; 32],
     upload_url: String,
     selected_media: SelectedMedia,
+    strip_metadata: bool,
 ) -> Promise<Result<Nip94Event, Error>> {
-    internal_nip96_upload(seckey, upload_url, selected_media)
+    internal_nip96_upload(seckey, upload_url, selected_media, strip_metadata)
 }
 
 pub fn nostrbuild_nip96_upload(
     seckey: [u8; 32],
     selected_media: SelectedMedia,
+    strip_metadata: bool,
+) -> Promise<Result<Nip94Event, Error>> {
+    provider_nip96_upload(seckey, NOSTR_BUILD_URL(), selected_media, strip_metadata)
+}
+
+/// Upload to a NIP-96 server, looking up its upload endpoint first
+pub fn provider_nip96_upload(
+    seckey: [u8; 32],
+    provider_url: Url,
+    selected_media: SelectedMedia,
+    strip_metadata: bool,
 ) -> Promise<Result<Nip94Event, Error>> {
     let (sender, promise) = Promise::new();
     std::thread::spawn(move || {
-        let upload_url = match get_nostr_build_upload_url().block_and_take() {
+        let host = provider_url.host_str().unwrap_or_default().to_owned();
+        let upload_url = match get_upload_url_from_provider(provider_url).block_and_take() {
             Ok(url) => url,
             Err(e) => {
                 sender.send(Err(Error::Generic(format!(
-                    "could not get nostrbuild upload url: {e}"
+                    "could not get {host} upload url: {e}"
                 ))));
                 return;
             }
         };
 
-        let res = nip96_upload(seckey, upload_url, selected_media).block_and_take();
+        let res = nip96_upload(seckey, upload_url, selected_media, strip_metadata).block_and_take();
         sender.send(res);
     });
     promise
@@ -166,18 +180,13 @@ fn internal_nip96_upload(
     seckey: [u8; 32],
     upload_url: String,
     selected_media: SelectedMedia,
+    strip_metadata: bool,
 ) -> Promise<Result<Nip94Event, Error>> {
-    let file_name = selected_media.file_name;
+    let file_name = selected_media.file_name.clone();
     let mime_type = selected_media.media_type.to_mime();
-    let bytes_res = bytes_from_media(selected_media.from);
-
-    let file_contents = match bytes_res {
+    let file_contents = match upload_contents(selected_media, strip_metadata) {
         Ok(bytes) => bytes,
-        Err(e) => {
-            return Promise::from_ready(Err(Error::Generic(format!(
-                "could not read contents of file to upload: {e}"
-            ))));
-        }
+        Err(e) => return Promise::from_ready(Err(e)),
     };
 
     let file_hash = sha256_hex(&file_contents);
@@ -229,6 +238,228 @@ fn internal_nip96_upload(
     promise
 }
 
+/// The bytes of a file about to be uploaded, with its metadata stripped
+/// unless the user asked to keep it
+fn upload_contents(selected_media: SelectedMedia, strip_metadata: bool) -> Result<Vec<u8>, Error> {
+    let file_name = selected_media.file_name;
+    let mime_type = selected_media.media_type.to_mime();
+    let file_contents = bytes_from_media(selected_media.from)
+        .map_err(|e| Error::Generic(format!("could not read contents of file to upload: {e}")))?;
+
+    if !strip_metadata {
+        return Ok(file_contents);
+    }
+
+    // refuse to upload rather than risk leaking location data
+    media_metadata::strip_metadata(mime_type, file_contents)
+        .map_err(|e| Error::Generic(format!("could not remove metadata from {file_name}: {e}")))
+}
+
+/// Where the composer sends new media
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum UploadTarget {
+    #[default]
+    NostrBuild,
+    Blossom(String),
+    Nip96(String),
+}
+
+impl UploadTarget {
+    /// The account's chosen Blossom server, then its NIP-96 server, then
+    /// nostr.build
+    pub fn for_account(settings: &SettingsHandler, account: &Pubkey) -> Self {
+        if let Some(server) = settings.upload_server(account) {
+            Self::Blossom(server.to_owned())
+        } else if let Some(server) = settings.nip96_server(account) {
+            Self::Nip96(server.to_owned())
+        } else {
+            Self::NostrBuild
+        }
+    }
+
+    /// Name of the server, for showing while uploading
+    pub fn host(&self) -> &str {
+        match self {
+            Self::NostrBuild => "nostr.build",
+            Self::Blossom(server) | Self::Nip96(server) => server
+                .split_once("://")
+                .map_or(server.as_str(), |(_, host)| host),
+        }
+    }
+}
+
+pub fn upload_media(
+    seckey: [u8; 32],
+    target: &UploadTarget,
+    selected_media: SelectedMedia,
+    strip_metadata: bool,
+) -> Promise<Result<Nip94Event, Error>> {
+    match target {
+        UploadTarget::NostrBuild => nostrbuild_nip96_upload(seckey, selected_media, strip_metadata),
+        UploadTarget::Blossom(server) => {
+            blossom_upload(seckey, server, selected_media, strip_metadata)
+        }
+        UploadTarget::Nip96(server) => match Url::parse(server) {
+            Ok(url) => provider_nip96_upload(seckey, url, selected_media, strip_metadata),
+            Err(e) => Promise::from_ready(Err(Error::Generic(format!(
+                "bad NIP-96 server url {server}: {e}"
+            )))),
+        },
+    }
+}
+
+/// How long a Blossom authorization stays valid
+const BLOSSOM_AUTH_SECS: u64 = 5 * 60;
+
+/// A kind `24242` event allowing the upload of one blob (BUD-01), encoded
+/// for an `Authorization` header
+fn blossom_upload_auth(seckey: &[u8; 32], file_hash: &str) -> Result<String, Error> {
+    let expiration = (unix_time_secs() + BLOSSOM_AUTH_SECS).to_string();
+    let note = NoteBuilder::new()
+        .kind(24242)
+        .content("Upload blob")
+        .start_tag()
+        .tag_str("t")
+        .tag_str("upload")
+        .start_tag()
+        .tag_str("x")
+        .tag_str(file_hash)
+        .start_tag()
+        .tag_str("expiration")
+        .tag_str(&expiration)
+        .sign(seckey)
+        .build()
+        .ok_or_else(|| Error::Generic("could not build blossom auth event".to_owned()))?;
+
+    let json = note.json().map_err(|e| Error::Generic(e.to_string()))?;
+    Ok(format!("Nostr {}", BASE64_STANDARD.encode(json)))
+}
+
+fn blossom_endpoint(server: &str) -> String {
+    format!("{}/upload", server.trim_end_matches('/'))
+}
+
+/// Why a Blossom server turned us down. Servers explain rejections in the
+/// `X-Reason` header.
+fn blossom_rejection(response: &ehttp::Response) -> Error {
+    let reason = response
+        .headers
+        .get("x-reason")
+        .unwrap_or(&response.status_text);
+    Error::Generic(format!("{} {reason}", response.status))
+}
+
+pub fn blossom_upload(
+    seckey: [u8; 32],
+    server: &str,
+    selected_media: SelectedMedia,
+    strip_metadata: bool,
+) -> Promise<Result<Nip94Event, Error>> {
+    let mime_type = selected_media.media_type.to_mime();
+    let file_contents = match upload_contents(selected_media, strip_metadata) {
+        Ok(bytes) => bytes,
+        Err(e) => return Promise::from_ready(Err(e)),
+    };
+
+    let auth = match blossom_upload_auth(&seckey, &sha256_hex(&file_contents)) {
+        Ok(auth) => auth,
+        Err(e) => return Promise::from_ready(Err(e)),
+    };
+
+    let request = Request {
+        method: "PUT".to_owned(),
+        url: blossom_endpoint(server),
+        headers: ehttp::Headers::new(&[
+            ("Content-Type", mime_type),
+            ("Authorization", auth.as_str()),
+        ]),
+        body: file_contents,
+    };
+
+    let (sender, promise) = Promise::new();
+    ehttp::fetch(request, move |response| {
+        let result = match response {
+            Ok(response) if response.ok => match response.text() {
+                Some(text) => nip94_from_blob_descriptor(text),
+                None => Err(Error::Generic(
+                    "blossom blob descriptor is not text".to_owned(),
+                )),
+            },
+            Ok(response) => Err(blossom_rejection(&response)),
+            Err(e) => Err(Error::Generic(e)),
+        };
+
+        sender.send(result);
+    });
+
+    promise
+}
+
+/// Ask a Blossom server whether it would take an upload from us, without
+/// sending one (BUD-06)
+pub fn blossom_probe(seckey: [u8; 32], server: &str) -> Promise<Result<(), Error>> {
+    const PROBE: &[u8] = b"notedeck upload probe";
+
+    let file_hash = sha256_hex(&PROBE.to_vec());
+    let auth = match blossom_upload_auth(&seckey, &file_hash) {
+        Ok(auth) => auth,
+        Err(e) => return Promise::from_ready(Err(e)),
+    };
+
+    let request = Request {
+        method: "HEAD".to_owned(),
+        url: blossom_endpoint(server),
+        headers: ehttp::Headers::new(&[
+            ("X-SHA-256", file_hash.as_str()),
+            ("X-Content-Length", PROBE.len().to_string().as_str()),
+            ("X-Content-Type", "text/plain"),
+            ("Authorization", auth.as_str()),
+        ]),
+        body: Vec::new(),
+    };
+
+    let (sender, promise) = Promise::new();
+    ehttp::fetch(request, move |response| {
+        let result = match response {
+            Ok(response) if response.ok => Ok(()),
+            Ok(response) => Err(blossom_rejection(&response)),
+            Err(e) => Err(Error::Generic(e)),
+        };
+
+        sender.send(result);
+    });
+
+    promise
+}
+
+/// Servers that support it send a full NIP-94 tag list along with the blob
+/// descriptor (BUD-08); otherwise we make do with the descriptor itself
+fn nip94_from_blob_descriptor(json: &str) -> Result<Nip94Event, Error> {
+    let v = serde_json::from_str::<serde_json::Value>(json)
+        .map_err(|e| Error::Generic(e.to_string()))?;
+
+    if let Ok(tags) = serde_json::from_value::<Vec<Vec<String>>>(v["nip94"].clone()) {
+        if let Ok(event) = Nip94Event::from_tags_and_content(tags, String::new()) {
+            return Ok(event);
+        }
+    }
+
+    let url = v["url"]
+        .as_str()
+        .ok_or_else(|| Error::Generic("blob descriptor has no url".to_owned()))?;
+
+    Ok(Nip94Event {
+        url: url.to_owned(),
+        ox: None,
+        x: v["sha256"].as_str().map(str::to_owned),
+        media_type: v["type"].as_str().map(str::to_owned),
+        dimensions: None,
+        blurhash: None,
+        thumb: None,
+        content: String::new(),
+    })
+}
+
 fn find_nip94_ev_in_json(json: String) -> Result<Nip94Event, Error> {
     match serde_json::from_str::<serde_json::Value>(&json) {
         Ok(v) => {
@@ -254,7 +485,91 @@ pub fn bytes_from_media(media: MediaFrom) -> Result<Vec<u8>, notedeck::Error> {
     }
 }
 
-#[derive(Clone, Debug, serde::Deserialize)]
+/// A file dropped onto the window, from its path or its contents
+pub fn selected_media_from_drop(
+    file: &egui::DroppedFile,
+) -> Result<SelectedMedia, notedeck::Error> {
+    if let Some(path) = &file.path {
+        SelectedMedia::from_path(path.clone())
+    } else if let Some(bytes) = &file.bytes {
+        SelectedMedia::from_bytes(file.name.clone(), bytes.to_vec())
+    } else {
+        Err(notedeck::Error::Generic(format!(
+            "dropped file {} has no contents",
+            file.name
+        )))
+    }
+}
+
+/// An image read off the system clipboard, not encoded yet
+pub struct ClipboardImage {
+    width: u32,
+    height: u32,
+    rgba: Vec<u8>,
+}
+
+impl ClipboardImage {
+    /// Encode as a png. This takes a while for screenshot-sized images, so
+    /// it happens off the UI thread.
+    fn into_media(self) -> Result<SelectedMedia, notedeck::Error> {
+        let rgba =
+            image::RgbaImage::from_raw(self.width, self.height, self.rgba).ok_or_else(|| {
+                notedeck::Error::Generic("clipboard image has invalid dimensions".to_owned())
+            })?;
+
+        let mut png = Vec::new();
+        rgba.write_to(&mut io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .map_err(|e| {
+                notedeck::Error::Generic(format!("could not encode clipboard image: {e}"))
+            })?;
+
+        SelectedMedia::from_bytes("pasted-image.png".to_owned(), png)
+    }
+}
+
+/// The image on the system clipboard
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+pub fn clipboard_image() -> Result<ClipboardImage, notedeck::Error> {
+    let mut clipboard = arboard::Clipboard::new()
+        .map_err(|e| notedeck::Error::Generic(format!("could not open clipboard: {e}")))?;
+    let img = clipboard
+        .get_image()
+        .map_err(|e| notedeck::Error::Generic(format!("no image in clipboard: {e}")))?;
+
+    Ok(ClipboardImage {
+        width: img.width as u32,
+        height: img.height as u32,
+        rgba: img.bytes.into_owned(),
+    })
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+pub fn clipboard_image() -> Result<ClipboardImage, notedeck::Error> {
+    Err(notedeck::Error::Generic(
+        "pasting images is not supported on this platform".to_owned(),
+    ))
+}
+
+/// Encode a pasted image and upload it, both on a background thread
+pub fn upload_clipboard_image(
+    seckey: [u8; 32],
+    target: &UploadTarget,
+    image: ClipboardImage,
+    strip_metadata: bool,
+) -> Promise<Result<Nip94Event, Error>> {
+    let (sender, promise) = Promise::new();
+    let target = target.clone();
+    std::thread::spawn(move || {
+        let res = match image.into_media() {
+            Ok(media) => upload_media(seckey, &target, media, strip_metadata).block_and_take(),
+            Err(e) => Err(e.into()),
+        };
+        sender.send(res);
+    });
+    promise
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct Nip94Event {
     pub url: String,
     pub ox: Option<String>,
@@ -341,7 +656,8 @@ mod tests {
     use enostr::FullKeypair;
 
     use crate::media_upload::{
-        get_upload_url_from_provider, nostrbuild_nip96_upload, SelectedMedia, NOSTR_BUILD_URL,
+        get_upload_url_from_provider, nip94_from_blob_descriptor, nostrbuild_nip96_upload,
+        SelectedMedia, UploadTarget, NOSTR_BUILD_URL,
     };
 
     use super::internal_nip96_upload;
@@ -355,6 +671,44 @@ mod tests {
         assert!(url.is_ok());
     }
 
+    #[test]
+    fn blob_descriptor_prefers_nip94_tags() {
+        let plain = r#"{
+            "url": "https://cdn.example.com/b1674191.png",
+            "sha256": "b1674191",
+            "size": 184292,
+            "type": "image/png"
+        }"#;
+        let event = nip94_from_blob_descriptor(plain).unwrap();
+        assert_eq!(event.url, "https://cdn.example.com/b1674191.png");
+        assert_eq!(event.x.as_deref(), Some("b1674191"));
+        assert_eq!(event.media_type.as_deref(), Some("image/png"));
+        assert_eq!(event.dimensions, None);
+
+        let with_nip94 = r#"{
+            "url": "https://cdn.example.com/b1674191.png",
+            "sha256": "b1674191",
+            "nip94": [["url", "https://cdn.example.com/b1674191.png"], ["dim", "640x480"]]
+        }"#;
+        let event = nip94_from_blob_descriptor(with_nip94).unwrap();
+        assert_eq!(event.dimensions, Some((640, 480)));
+
+        assert!(nip94_from_blob_descriptor(r#"{"sha256": "b1674191"}"#).is_err());
+    }
+
+    #[test]
+    fn upload_target_host_drops_the_scheme() {
+        assert_eq!(UploadTarget::NostrBuild.host(), "nostr.build");
+        assert_eq!(
+            UploadTarget::Nip96("https://files.example.com".to_owned()).host(),
+            "files.example.com"
+        );
+        assert_eq!(
+            UploadTarget::Blossom("blossom.example.com".to_owned()).host(),
+            "blossom.example.com"
+        );
+    }
+
     #[test]
     #[ignore] // this test should not run automatically since it sends data to a real server
     fn test_internal_nip96() {
@@ -370,6 +724,7 @@ mod tests {
                 kp.secret_key.secret_bytes(),
                 upload_url.to_string(),
                 selected_media,
+                true,
             );
             let res = promise.block_until_ready();
             assert!(res.is_ok())
@@ -389,7 +744,7 @@ mod tests {
         let kp = FullKeypair::generate();
         println!("Using pubkey: {:?}", kp.pubkey);
 
-        let promise = nostrbuild_nip96_upload(kp.secret_key.secret_bytes(), selected_media);
+        let promise = nostrbuild_nip96_upload(kp.secret_key.secret_bytes(), selected_media, true);
 
         let out = promise.block_and_take();
         assert!(out.is_ok());
@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+
+use enostr::Pubkey;
+use nostrdb::Transaction;
+use notedeck::{name::get_display_name, tr, AppContext, OsNotificationKind, OsNotifications};
+
+use crate::timeline::{NoteUnit, TimelineCache, TimelineKind};
+
+/// Most notifications raised for a single batch of new mentions, so
+/// catching up after a while doesn't flood the desktop
+const MAX_PER_BATCH: usize = 3;
+
+const MAX_BODY_CHARS: usize = 140;
+
+/// Raises system notifications for mentions arriving in the selected
+/// account's notifications timeline while the window is in the background.
+#[derive(Default)]
+pub struct MentionNotifier {
+    /// `created_at` of the newest notification already considered, per account
+    notified_up_to: HashMap<Pubkey, u64>,
+}
+
+impl MentionNotifier {
+    pub fn poll(
+        &mut self,
+        timeline_cache: &TimelineCache,
+        app_ctx: &mut AppContext<'_>,
+        egui_ctx: &egui::Context,
+    ) {
+        let pk = *app_ctx.accounts.selected_account_pubkey();
+        let Some(tl) = timeline_cache.get(&TimelineKind::Notifications(pk)) else {
+            return;
+        };
+
+        let units = tl.all_or_any_entries();
+        let Some(latest) = units.latest().map(|r| r.created_at) else {
+            return;
+        };
+
+        // whatever is there the first time we look was loaded, not received
+        let Some(since) = self.notified_up_to.insert(pk, latest) else {
+            return;
+        };
+
+        if latest <= since
+            || !OsNotifications::in_background(egui_ctx)
+            || !app_ctx
+                .settings
+                .os_notification(OsNotificationKind::Mention)
+        {
+            return;
+        }
+
+        let Ok(txn) = Transaction::new(app_ctx.ndb) else {
+            return;
+        };
+
+        let mut shown = 0;
+        for i in 0..units.len() {
+            if shown == MAX_PER_BATCH {
+                break;
+            }
+
+            let Some(unit) = units.get(i) else {
+                break;
+            };
+
+            if unit.get_latest_ref().created_at <= since {
+                break;
+            }
+
+            let NoteUnit::Single(note_ref) = unit else {
+                continue;
+            };
+
+            let Ok(note) = app_ctx.ndb.get_note_by_key(&txn, note_ref.key) else {
+                continue;
+            };
+
+            if note.kind() != 1 || note.pubkey() == pk.bytes() {
+                continue;
+            }
+
+            let profile = app_ctx.ndb.get_profile_by_pubkey(&txn, note.pubkey()).ok();
+            let title = tr!(
+                app_ctx.i18n,
+                "{name} mentioned you",
+                "Title of the system notification for a mention or reply",
+                name = get_display_name(profile.as_ref()).name()
+            );
+
+            app_ctx.os_notifications.show(
+                egui_ctx,
+                OsNotificationKind::Mention,
+                &title,
+                &truncate_body(note.content()),
+            );
+            shown += 1;
+        }
+    }
+}
+
+fn truncate_body(content: &str) -> String {
+    let mut chars = content.chars();
+    let mut body: String = chars.by_ref().take(MAX_BODY_CHARS).collect();
+    if chars.next().is_some() {
+        body.push('…');
+    }
+    body
+}
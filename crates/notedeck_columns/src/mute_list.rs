@@ -0,0 +1,104 @@
+//! Editing the account's kind `10000` mute list (NIP-51) by hand.
+
+use enostr::{NoteId, Pubkey};
+use notedeck::{is_valid_mute_word, MuteEntry};
+
+/// The kinds of entries that can be added to a mute list
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MuteEntryKind {
+    #[default]
+    User,
+    Hashtag,
+    Word,
+    Thread,
+}
+
+/// State of the mute list page
+#[derive(Default)]
+pub struct MuteListState {
+    /// What the add field is adding
+    pub new_kind: MuteEntryKind,
+    /// Text typed into the add field
+    pub new_entry: String,
+    pub add_error: Option<String>,
+}
+
+/// Turn what the user typed into a mute list entry. Users and threads take
+/// hex or bech32 ids, with or without a `nostr:` prefix. Words wrapped in
+/// slashes are regexes and are kept as typed.
+pub fn parse_mute_entry(kind: MuteEntryKind, input: &str) -> Option<MuteEntry> {
+    let input = input.trim();
+    let id = input.strip_prefix("nostr:").unwrap_or(input);
+
+    match kind {
+        MuteEntryKind::User => Pubkey::parse(id)
+            .ok()
+            .map(|pk| MuteEntry::Pubkey(*pk.bytes())),
+        MuteEntryKind::Thread => NoteId::from_hex(id)
+            .ok()
+            .or_else(|| NoteId::from_bech(id))
+            .or_else(|| NoteId::from_nevent_bech(id))
+            .map(|id| MuteEntry::Thread(*id.bytes())),
+        MuteEntryKind::Hashtag => {
+            let tag = input.trim_start_matches('#');
+            (!tag.is_empty() && !tag.contains(char::is_whitespace))
+                .then(|| MuteEntry::Hashtag(tag.to_lowercase()))
+        }
+        MuteEntryKind::Word => {
+            let is_regex = input.len() > 1 && input.starts_with('/') && input.ends_with('/');
+            let word = if is_regex {
+                input.to_owned()
+            } else {
+                input.to_lowercase()
+            };
+            is_valid_mute_word(&word).then_some(MuteEntry::Word(word))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NPUB: &str = "npub1xtscya34g58tk0z605fvr788k263gsu6cy9x0mhnm87echrgufzsevkk5s";
+
+    #[test]
+    fn parses_users_and_threads() {
+        let pk = Pubkey::parse(NPUB).expect("npub");
+        assert_eq!(
+            parse_mute_entry(MuteEntryKind::User, &format!(" nostr:{NPUB} ")),
+            Some(MuteEntry::Pubkey(*pk.bytes()))
+        );
+        assert_eq!(
+            parse_mute_entry(MuteEntryKind::User, &pk.hex()),
+            Some(MuteEntry::Pubkey(*pk.bytes()))
+        );
+        assert_eq!(parse_mute_entry(MuteEntryKind::User, "jack"), None);
+
+        let id = NoteId::new([5; 32]);
+        let bech = id.to_bech().expect("note1");
+        assert_eq!(
+            parse_mute_entry(MuteEntryKind::Thread, &bech),
+            Some(MuteEntry::Thread([5; 32]))
+        );
+    }
+
+    #[test]
+    fn parses_hashtags_and_words() {
+        assert_eq!(
+            parse_mute_entry(MuteEntryKind::Hashtag, "#Bitcoin"),
+            Some(MuteEntry::Hashtag("bitcoin".to_owned()))
+        );
+        assert_eq!(parse_mute_entry(MuteEntryKind::Hashtag, "two words"), None);
+        assert_eq!(
+            parse_mute_entry(MuteEntryKind::Word, " GM "),
+            Some(MuteEntry::Word("gm".to_owned()))
+        );
+        assert_eq!(parse_mute_entry(MuteEntryKind::Word, "  "), None);
+        assert_eq!(
+            parse_mute_entry(MuteEntryKind::Word, r"/\bGM+\b/"),
+            Some(MuteEntry::Word(r"/\bGM+\b/".to_owned()))
+        );
+        assert_eq!(parse_mute_entry(MuteEntryKind::Word, "/(/"), None);
+    }
+}
@@ -1,25 +1,36 @@
 use crate::{
-    accounts::{render_accounts_route, AccountsAction, AccountsResponse, AccountsRoute},
+    accounts::{
+        render_accounts_route, AccountsAction, AccountsResponse, AccountsRoute, SwitchAccountAction,
+    },
     app::{get_active_columns_mut, get_decks_mut, setup_selected_account_timeline_subs},
     column::ColumnsAction,
     deck_state::DeckState,
     decks::{Deck, DecksAction, DecksCache},
+    identity_archive, key_rotation, media_servers,
+    media_upload::UploadTarget,
     options::AppOptions,
     profile::{ProfileAction, SaveProfileChanges},
     repost::RepostAction,
     route::{cleanup_popped_route, ColumnsRouter, Route, SingletonRouter},
+    storage::SharedDeck,
     timeline::{
+        inspect_timeline,
         route::{render_thread_route, render_timeline_route},
-        TimelineCache,
+        RelayUsage, TimelineCache, TimelineInspect,
     },
     ui::{
         self,
         add_column::render_add_column_routes,
         column::NavTitle,
         configure_deck::ConfigureDeckView,
-        edit_deck::{EditDeckResponse, EditDeckView},
+        drafts::{DraftsAction, DraftsView},
+        edit_deck::{import_deck_button, EditDeckResponse, EditDeckView, SHOW_FILE_BUTTONS},
+        media_servers::MediaServersView,
+        mute_list::MuteListView,
         note::{custom_zap::CustomZapView, NewPostAction, PostAction, PostType},
         profile::EditProfileView,
+        publish_status::RecentPublish,
+        relay::RelayViewAction,
         repost::RepostDecisionView,
         search::{FocusState, SearchView},
         settings::SettingsAction,
@@ -36,8 +47,8 @@ use egui_nav::{
 use enostr::ProfileState;
 use nostrdb::{Filter, Ndb, Transaction};
 use notedeck::{
-    get_current_default_msats, nav::DragResponse, tr, ui::is_narrow, Accounts, AppContext,
-    FilterState, NoteAction, NoteCache, NoteContext, RelayAction,
+    get_current_default_msats, nav::DragResponse, tr, ui::is_narrow, AccountScrub, Accounts,
+    AppContext, FilterState, NoteAction, NoteCache, NoteContext,
 };
 use notedeck_ui::{ContactsListAction, ContactsListView, NoteOptions};
 use tracing::error;
@@ -68,12 +79,15 @@ pub enum RenderNavAction {
     ProfileAction(ProfileAction),
     SwitchingAction(SwitchingAction),
     WalletAction(WalletAction),
-    RelayAction(RelayAction),
+    RelayAction(RelayViewAction),
     SettingsAction(SettingsAction),
     RepostAction(RepostAction),
     ShowFollowing(enostr::Pubkey),
     ShowFollowers(enostr::Pubkey),
+    ResumeDraft(crate::draft::DraftTarget),
     RefreshTimeline(crate::timeline::TimelineKind),
+    GroupAction(crate::group::GroupAction),
+    CommunityAction(crate::community::CommunityAction),
 }
 
 pub enum SwitchingAction {
@@ -93,9 +107,29 @@ impl SwitchingAction {
         match &self {
             SwitchingAction::Accounts(account_action) => match account_action {
                 AccountsAction::Switch(switch_action) => {
+                    let prev = *ctx.accounts.selected_account_pubkey();
                     ctx.select_account(&switch_action.switch_to);
 
-                    if switch_action.switching_to_new {
+                    // viewing as someone else ends when switching away
+                    if prev != switch_action.switch_to
+                        && ctx.accounts.is_ghost(&prev)
+                        && ctx.remove_account(&prev, AccountScrub::default())
+                    {
+                        let mut scoped_subs = ctx.remote.scoped_subs(ctx.accounts);
+                        decks_cache.remove(
+                            ctx.i18n,
+                            &prev,
+                            timeline_cache,
+                            ctx.ndb,
+                            &mut scoped_subs,
+                        );
+                    }
+
+                    // an account we already had, like a read-only one we now
+                    // have the secret key for, keeps the columns it had
+                    if switch_action.switching_to_new
+                        && !decks_cache.has_decks(&switch_action.switch_to)
+                    {
                         decks_cache.add_deck_default(ctx, timeline_cache, switch_action.switch_to);
                     }
 
@@ -107,8 +141,8 @@ impl SwitchingAction {
                         .router_mut()
                         .go_back();
                 }
-                AccountsAction::Remove(to_remove) => 's: {
-                    if !ctx.remove_account(to_remove) {
+                AccountsAction::Remove(to_remove, scrub) => 's: {
+                    if !ctx.remove_account(to_remove, *scrub) {
                         break 's;
                     }
 
@@ -507,6 +541,22 @@ impl RouterAction {
     }
 }
 
+/// Erase what the columns app keeps for an account that was just signed out
+fn scrub_removed_account(
+    app: &mut Damus,
+    ctx: &mut AppContext<'_>,
+    removed: &enostr::Pubkey,
+    scrub: AccountScrub,
+) {
+    if scrub.local_data {
+        app.drafts.remove_account(removed);
+    }
+
+    if scrub.prune_db {
+        crate::ui::settings::compact_database(ctx, &mut app.view_state.compact);
+    }
+}
+
 #[profiling::function]
 fn process_render_nav_action(
     app: &mut Damus,
@@ -530,12 +580,22 @@ fn process_render_nav_action(
 
             return Some(ProcessNavResult::SwitchOccurred);
         }
+        RenderNavAction::PostAction(new_post_action) if new_post_action.is_scheduled() => {
+            new_post_action.schedule(&mut app.scheduled_posts, &mut app.drafts);
+            Some(RouterAction::GoBack)
+        }
         RenderNavAction::PostAction(new_post_action) => {
             let txn = Transaction::new(ctx.ndb).expect("txn");
             let mut publisher = ctx.remote.publisher(ctx.accounts);
             match new_post_action.execute(ctx.ndb, &txn, &mut publisher, &mut app.drafts) {
                 Err(err) => tracing::error!("Error executing post action: {err}"),
-                Ok(_) => tracing::debug!("Post action executed"),
+                Ok(note_id) => {
+                    tracing::debug!("Post action executed");
+                    if ctx.settings.verify_publishes() {
+                        app.publish_checks.track(note_id);
+                    }
+                    app.view_state.recent_publish = Some(RecentPublish::new(note_id));
+                }
             }
 
             Some(RouterAction::GoBack)
@@ -567,6 +627,7 @@ fn process_render_nav_action(
                 ctx.accounts,
                 ctx.global_wallet,
                 ctx.zaps,
+                ctx.settings,
                 ctx.img_cache,
                 &mut app.view_state,
                 ctx.media_jobs.sender(),
@@ -574,7 +635,18 @@ fn process_render_nav_action(
             )
         }
         RenderNavAction::SwitchingAction(switching_action) => {
-            if switching_action.process(&mut app.timeline_cache, &mut app.decks_cache, ctx) {
+            let switched =
+                switching_action.process(&mut app.timeline_cache, &mut app.decks_cache, ctx);
+
+            if let SwitchingAction::Accounts(AccountsAction::Remove(removed, scrub)) =
+                &switching_action
+            {
+                if ctx.accounts.cache.get(removed).is_none() {
+                    scrub_removed_account(app, ctx, removed, *scrub);
+                }
+            }
+
+            if switched {
                 return Some(ProcessNavResult::SwitchOccurred);
             } else {
                 return None;
@@ -588,12 +660,16 @@ fn process_render_nav_action(
             ctx.ndb,
             &mut ctx.remote,
             ctx.accounts,
+            ctx.settings,
         ),
         RenderNavAction::WalletAction(wallet_action) => {
             wallet_action.process(ctx.accounts, ctx.global_wallet)
         }
         RenderNavAction::RelayAction(action) => {
-            ctx.process_relay_action(action);
+            match action {
+                RelayViewAction::Advertised(action) => ctx.process_relay_action(action),
+                RelayViewAction::Local(action) => ctx.process_local_relay_action(action),
+            }
             None
         }
         RenderNavAction::SettingsAction(action) => {
@@ -613,6 +689,17 @@ fn process_render_nav_action(
             crate::route::Route::FollowedBy(pubkey),
             RouterType::Stack,
         )),
+        RenderNavAction::GroupAction(action) => {
+            action.process(ctx.ndb, ctx.accounts, &mut ctx.remote);
+            None
+        }
+        RenderNavAction::CommunityAction(action) => {
+            action.process(ctx.ndb, ctx.accounts, &mut ctx.remote);
+            None
+        }
+        RenderNavAction::ResumeDraft(target) => {
+            Some(RouterAction::RouteTo(target.route(), RouterType::Stack))
+        }
         RenderNavAction::RefreshTimeline(kind) => {
             let txn = Transaction::new(ctx.ndb).expect("txn");
 
@@ -629,6 +716,7 @@ fn process_render_nav_action(
                 if let FilterState::Ready(ref filter) = new_filter {
                     let mut scoped_subs = ctx.remote.scoped_subs(ctx.accounts);
                     crate::timeline::update_remote_timeline_subscription(
+                        ctx.ndb,
                         timeline,
                         filter.remote().to_vec(),
                         &mut scoped_subs,
@@ -670,9 +758,11 @@ fn render_nav_body(
         jobs: ctx.media_jobs.sender(),
         unknown_ids: ctx.unknown_ids,
         nip05_cache: ctx.nip05_cache,
+        profile_stats: ctx.profile_stats,
         clipboard: ctx.clipboard,
         i18n: ctx.i18n,
         global_wallet: ctx.global_wallet,
+        action_bar: ctx.settings.action_bar(),
     };
     match top {
         Route::Timeline(kind) => {
@@ -683,6 +773,13 @@ fn render_nav_body(
                 .is_some_and(|ind| ind == col)
                 && app.options.contains(AppOptions::ScrollToTop);
 
+            let notifications_muted = match kind {
+                crate::timeline::TimelineKind::Profile(pk) => ctx
+                    .settings
+                    .is_notification_muted(ctx.accounts.selected_account_pubkey(), pk),
+                _ => false,
+            };
+
             let resp = render_timeline_route(
                 &mut app.timeline_cache,
                 kind,
@@ -692,9 +789,19 @@ fn render_nav_body(
                 ui,
                 &mut note_context,
                 scroll_to_top,
+                notifications_muted,
             );
 
             app.timeline_cache.set_fresh(kind);
+            if let crate::timeline::TimelineKind::Notifications(pk) = kind {
+                if let Some(latest) = app
+                    .timeline_cache
+                    .get(kind)
+                    .and_then(|tl| tl.all_or_any_entries().latest())
+                {
+                    app.notifications_seen.mark_seen(*pk, latest.created_at);
+                }
+            }
 
             // always clear the scroll_to_top request
             if scroll_to_top {
@@ -703,14 +810,29 @@ fn render_nav_body(
 
             resp
         }
-        Route::Thread(selection) => render_thread_route(
-            &mut app.threads,
-            selection,
-            col,
-            app.note_options,
-            ui,
-            &mut note_context,
-        ),
+        Route::Thread(selection) => {
+            let resp = render_thread_route(
+                &mut app.threads,
+                selection,
+                col,
+                app.note_options,
+                ui,
+                &mut note_context,
+            );
+
+            let txn = Transaction::new(ctx.ndb).expect("txn");
+            let mut scoped_subs = ctx.remote.scoped_subs(ctx.accounts);
+            app.threads
+                .watch_reactions(ctx.ndb, &txn, &mut scoped_subs, col, selection);
+            app.threads.watch_subtrees(&mut scoped_subs, col, selection);
+
+            resp
+        }
+        Route::Note(note_id) => {
+            ui::PermalinkView::new(note_id, app.note_options, &mut note_context, col)
+                .ui(ui)
+                .map_output(RenderNavAction::NoteAction)
+        }
         Route::Accounts(amr) => {
             let resp = render_accounts_route(
                 ui,
@@ -736,25 +858,167 @@ fn render_nav_body(
                 }
             })
         }
-        Route::Relays => RelayView::new(
-            ctx.remote.relay_inspect(),
-            ctx.accounts.selected_account_advertised_relays(),
-            &mut app.view_state.id_string_map,
-            ctx.i18n,
-        )
-        .ui(ui)
-        .map_output(RenderNavAction::RelayAction),
+        Route::Relays => {
+            let subscriptions = app
+                .options
+                .contains(AppOptions::Debug)
+                .then(|| ctx.remote.scoped_subs(ctx.accounts).subscriptions());
+
+            RelayView::new(
+                ctx.remote.relay_inspect(),
+                ctx.accounts.selected_account_advertised_relays(),
+                ctx.settings.relay_profiles(),
+                ctx.settings.active_relay_profile(),
+                &mut app.view_state.id_string_map,
+                ctx.i18n,
+            )
+            .policies(ctx.settings.relay_policies())
+            .subscriptions(subscriptions)
+            .ui(ui)
+            .map_output(RenderNavAction::RelayAction)
+        }
 
         Route::Settings => {
             let db_path = ctx.args.db_path(ctx.path);
-            SettingsView::new(
+            let mut resp = SettingsView::new(
                 ctx.settings.get_settings_mut(),
                 &mut note_context,
                 &db_path,
                 &mut app.view_state.compact,
+                &mut app.view_state.identity_archive,
+                &mut app.view_state.key_export,
             )
             .ui(ui)
-            .map_output(RenderNavAction::SettingsAction)
+            .map_output_maybe(|action| match action {
+                SettingsAction::RotateKey => {
+                    let resp = key_rotation::rotate_selected_key(
+                        ctx.ndb,
+                        ctx.accounts,
+                        &mut ctx.remote,
+                        ctx.i18n,
+                    )?;
+
+                    let txn = Transaction::new(ctx.ndb).expect("txn");
+                    resp.unk_id_action
+                        .process_action(ctx.unknown_ids, ctx.ndb, &txn);
+
+                    Some(RenderNavAction::SwitchingAction(SwitchingAction::Accounts(
+                        AccountsAction::Switch(
+                            SwitchAccountAction::new(col, resp.switch_to).switching_to_new(),
+                        ),
+                    )))
+                }
+                SettingsAction::ImportIdentity => {
+                    identity_archive::import_identity(
+                        ctx.ndb,
+                        ctx.job_pool,
+                        &mut app.view_state.identity_archive,
+                    );
+                    None
+                }
+                action => Some(RenderNavAction::SettingsAction(action)),
+            });
+
+            // a finished import switches to the restored account
+            if let Some(imported) = app.view_state.identity_archive.take_restored(ctx.accounts) {
+                let switch = match imported.added {
+                    Some(resp) => {
+                        let txn = Transaction::new(ctx.ndb).expect("txn");
+                        resp.unk_id_action
+                            .process_action(ctx.unknown_ids, ctx.ndb, &txn);
+                        SwitchAccountAction::new(col, resp.switch_to).switching_to_new()
+                    }
+                    None => SwitchAccountAction::new(col, imported.pubkey),
+                };
+
+                resp.set_output(RenderNavAction::SwitchingAction(SwitchingAction::Accounts(
+                    AccountsAction::Switch(switch),
+                )));
+            }
+
+            resp
+        }
+
+        Route::MediaServers => {
+            let pubkey = *ctx.accounts.selected_account_pubkey();
+            media_servers::subscribe_server_list(
+                &mut ctx.remote.scoped_subs(ctx.accounts),
+                &pubkey,
+                col,
+            );
+
+            let txn = Transaction::new(ctx.ndb).expect("txn");
+            let servers = media_servers::account_servers(ctx.ndb, &txn, &pubkey);
+            let state = &mut app.view_state.media_servers;
+            state.poll_probes();
+
+            let resp = MediaServersView::new(
+                &servers,
+                ctx.settings.upload_server(&pubkey),
+                ctx.settings.nip96_server(&pubkey),
+                ctx.accounts.selected_filled().is_some(),
+                state,
+                note_context.i18n,
+            )
+            .ui(ui, &pubkey);
+
+            resp.map_output_maybe(|action| {
+                action.process(
+                    ctx.ndb,
+                    ctx.accounts,
+                    &mut ctx.remote,
+                    ctx.settings,
+                    &mut app.view_state.media_servers,
+                    &servers,
+                );
+                None
+            })
+        }
+
+        Route::MuteList => {
+            let pubkey = *ctx.accounts.selected_account_pubkey();
+            let muted = ctx.accounts.mute();
+            let txn = Transaction::new(ctx.ndb).expect("txn");
+
+            let resp = MuteListView::new(
+                ctx.ndb,
+                &txn,
+                &muted,
+                ctx.accounts.selected_filled().is_some(),
+                &mut app.view_state.mute_list,
+                note_context.i18n,
+            )
+            .ui(ui, &pubkey);
+
+            resp.map_output_maybe(|action| {
+                action.process(ctx.ndb, ctx.accounts, &mut ctx.remote, ctx.settings);
+                None
+            })
+        }
+
+        Route::Drafts => {
+            let txn = Transaction::new(ctx.ndb).expect("txn");
+
+            let resp = DraftsView::new(
+                ctx.ndb,
+                &txn,
+                &app.drafts,
+                app.scheduled_posts.posts(),
+                note_context.i18n,
+            )
+            .ui(ui);
+
+            resp.map_output_maybe(|action| match action {
+                DraftsAction::Resume(target) => Some(RenderNavAction::ResumeDraft(target)),
+                DraftsAction::Discard(target) => {
+                    app.drafts.discard(target);
+                    None
+                }
+                DraftsAction::CancelScheduled(id) => {
+                    app.scheduled_posts.cancel(id);
+                    None
+                }
+            })
         }
 
         Route::Reply(id) => {
@@ -799,6 +1063,7 @@ fn render_nav_body(
                     options,
                     col,
                 )
+                .upload_target(UploadTarget::for_account(ctx.settings, poster.pubkey))
                 .show(ui)
             };
 
@@ -833,6 +1098,7 @@ fn render_nav_body(
                 app.note_options,
                 col,
             )
+            .upload_target(UploadTarget::for_account(ctx.settings, poster.pubkey))
             .show(ui);
 
             response.map_output_maybe(|o| Some(o.action?.into()))
@@ -854,6 +1120,7 @@ fn render_nav_body(
                 draft.focus_state = FocusState::ShouldRequestFocus;
             }
 
+            let upload_target = UploadTarget::for_account(ctx.settings, kp.pubkey);
             let txn = Transaction::new(ctx.ndb).expect("txn");
             let post_response = ui::PostView::new(
                 &mut note_context,
@@ -863,6 +1130,7 @@ fn render_nav_body(
                 inner_rect,
                 app.note_options,
             )
+            .upload_target(upload_target)
             .ui(&txn, ui);
 
             post_response.map_output_maybe(|o| Some(o.action?.into()))
@@ -897,18 +1165,39 @@ fn render_nav_body(
                 tracing::debug!("requesting search focus");
             }
 
-            SearchView::new(&txn, app.note_options, search_buffer, &mut note_context)
+            let resp = SearchView::new(&txn, app.note_options, search_buffer, &mut note_context)
                 .show(ui)
-                .map_output(RenderNavAction::NoteAction)
+                .map_output(RenderNavAction::NoteAction);
+
+            drop(txn);
+            crate::ui::search::send_relay_search(
+                search_buffer,
+                ctx.ndb,
+                &mut ctx.remote,
+                ctx.accounts,
+            );
+
+            resp
         }
         Route::NewDeck => {
             let id = ui.id().with("new-deck");
             let new_deck_state = app.view_state.id_to_deck_state.entry(id).or_default();
+            let cur_acc = *ctx.accounts.selected_account_pubkey();
             let mut resp = None;
+            let mut new_deck = None;
+
+            if SHOW_FILE_BUTTONS && ui.add(import_deck_button(ctx.i18n)).clicked() {
+                new_deck = SharedDeck::import_from_file()
+                    .map(|shared| shared.into_deck(ctx, &mut app.timeline_cache, cur_acc));
+            }
+
             if let Some(config_resp) = ConfigureDeckView::new(new_deck_state, ctx.i18n).ui(ui) {
-                let cur_acc = ctx.accounts.selected_account_pubkey();
-                app.decks_cache
-                    .add_deck(*cur_acc, Deck::new(config_resp.icon, config_resp.name));
+                new_deck = Some(Deck::new(config_resp.icon, config_resp.name));
+                new_deck_state.clear();
+            }
+
+            if let Some(deck) = new_deck {
+                app.decks_cache.add_deck(cur_acc, deck);
 
                 // set new deck as active
                 let cur_index = get_decks_mut(ctx.i18n, ctx.accounts, &mut app.decks_cache)
@@ -919,7 +1208,6 @@ fn render_nav_body(
                     DecksAction::Switch(cur_index),
                 )));
 
-                new_deck_state.clear();
                 get_active_columns_mut(ctx.i18n, ctx.accounts, &mut app.decks_cache)
                     .get_selected_router()
                     .go_back();
@@ -942,19 +1230,39 @@ fn render_nav_body(
                 .entry(id)
                 .or_insert_with(|| DeckState::from_deck(cur_deck));
             if let Some(resp) = EditDeckView::new(deck_state, ctx.i18n).ui(ui) {
-                match resp {
+                let done = match resp {
                     EditDeckResponse::Edit(configure_deck_response) => {
                         cur_deck.edit(configure_deck_response);
+                        true
                     }
                     EditDeckResponse::Delete => {
                         action = Some(RenderNavAction::SwitchingAction(SwitchingAction::Decks(
                             DecksAction::Removing(*index),
                         )));
+                        true
                     }
+                    EditDeckResponse::Export => {
+                        SharedDeck::from_deck(cur_deck, ctx.accounts.selected_account_pubkey())
+                            .export_to_file();
+                        false
+                    }
+                    EditDeckResponse::Publish => {
+                        let shared =
+                            SharedDeck::from_deck(cur_deck, ctx.accounts.selected_account_pubkey());
+                        if let Some(kp) = ctx.accounts.get_selected_account().key.to_full() {
+                            shared.publish(ctx.ndb, &mut ctx.remote.publisher(ctx.accounts), kp);
+                        } else {
+                            error!("can't publish a deck without the account's secret key");
+                        }
+                        false
+                    }
+                };
+
+                if done {
+                    get_active_columns_mut(ctx.i18n, ctx.accounts, &mut app.decks_cache)
+                        .get_selected_router()
+                        .go_back();
                 }
-                get_active_columns_mut(ctx.i18n, ctx.accounts, &mut app.decks_cache)
-                    .get_selected_router()
-                    .go_back();
             }
 
             DragResponse::output(action)
@@ -1153,20 +1461,25 @@ fn render_nav_body(
         }
         Route::CustomizeZapAmount(target) => {
             let txn = Transaction::new(ctx.ndb).expect("txn");
-            let default_msats = get_current_default_msats(ctx.accounts, ctx.global_wallet);
+            let prefs = ctx
+                .settings
+                .zap_preferences(ctx.accounts.selected_account_pubkey());
+            let default_msats =
+                prefs.default_msats(get_current_default_msats(ctx.accounts, ctx.global_wallet));
             DragResponse::output(
                 CustomZapView::new(
                     ctx.i18n,
                     ctx.img_cache,
                     ctx.ndb,
                     &txn,
-                    &target.zap_recipient,
+                    target.pubkey(),
                     default_msats,
                     ctx.media_jobs.sender(),
                 )
+                .default_comment(prefs.default_comment.as_deref())
                 .ui(ui),
             )
-            .map_output(|msats| {
+            .map_output(|custom| {
                 get_active_columns_mut(ctx.i18n, ctx.accounts, &mut app.decks_cache)
                     .column_mut(col)
                     .router_mut()
@@ -1174,7 +1487,8 @@ fn render_nav_body(
                 RenderNavAction::NoteAction(NoteAction::Zap(notedeck::ZapAction::Send(
                     notedeck::note::ZapTargetAmount {
                         target: target.clone(),
-                        specified_msats: Some(msats),
+                        specified_msats: Some(custom.msats),
+                        comment: custom.comment,
                     },
                 )))
             })
@@ -1208,6 +1522,56 @@ fn render_nav_body(
     }
 }
 
+/// Subscription details for the developer column inspector. Only gathered in
+/// debug mode, and only for timeline routes.
+fn column_inspect(
+    app: &Damus,
+    ctx: &mut AppContext<'_>,
+    route: Option<&Route>,
+) -> Option<TimelineInspect> {
+    if !app.options.contains(AppOptions::Debug) {
+        return None;
+    }
+
+    let Some(Route::Timeline(kind)) = route else {
+        return None;
+    };
+
+    let timeline = app.timeline_cache.get(kind)?;
+    let scoped_subs = ctx.remote.scoped_subs(ctx.accounts);
+    Some(inspect_timeline(timeline, &scoped_subs))
+}
+
+/// Relay breakdown for a timeline column, only gathered while its popup is
+/// open since it looks up every loaded note.
+fn column_relay_usage(
+    app: &Damus,
+    ctx: &AppContext<'_>,
+    ui: &egui::Ui,
+    col: usize,
+    route: Option<&Route>,
+) -> Option<RelayUsage> {
+    let open = ui
+        .data(|d| d.get_temp::<bool>(NavTitle::relay_usage_id(col)))
+        .unwrap_or(false);
+    if !open {
+        return None;
+    }
+
+    let Some(Route::Timeline(kind)) = route else {
+        return None;
+    };
+
+    let timeline = app.timeline_cache.get(kind)?;
+    let txn = Transaction::new(ctx.ndb).ok()?;
+    Some(RelayUsage::new(
+        ctx.ndb,
+        &txn,
+        timeline.all_or_any_entries(),
+        ctx.accounts.selected_account_read_relays(),
+    ))
+}
+
 #[must_use = "RenderNavResponse must be handled by calling .process_render_nav_response(..)"]
 #[profiling::function]
 pub fn render_nav(
@@ -1292,6 +1656,8 @@ pub fn render_nav(
         .animate_transitions(ctx.settings.get_settings_mut().animate_nav_transitions)
         .show_mut(ui, |ui, render_type, nav| match render_type {
             NavUiType::Title => {
+                let inspect = column_inspect(app, ctx, nav.routes().last());
+                let relay_usage = column_relay_usage(app, ctx, ui, col, nav.routes().last());
                 let action = NavTitle::new(
                     ctx.ndb,
                     ctx.img_cache,
@@ -1303,6 +1669,8 @@ pub fn render_nav(
                 )
                 .show_move_button(!narrow)
                 .show_delete_button(!narrow)
+                .inspector(inspect)
+                .relay_usage(relay_usage)
                 .show(ui);
                 RouteResponse {
                     response: action,
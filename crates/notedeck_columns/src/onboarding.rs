@@ -22,6 +22,9 @@ enum OnboardingState {
 pub struct Onboarding {
     state: Option<Result<OnboardingState, OnboardingError>>,
     pub list: Rc<RefCell<VirtualList>>,
+
+    /// Optional name published as the new account's first profile
+    pub display_name: String,
 }
 
 /// Side effects emitted by one `Onboarding::process` pass.
@@ -107,6 +110,8 @@ impl Onboarding {
 
     // Unsubscribe and clear state
     pub fn end_onboarding(&mut self, ndb: &mut Ndb) {
+        self.display_name.clear();
+
         let Some(Ok(OnboardingState::HaveFollowPacks { packs })) = &mut self.state else {
             self.state = None;
             return;
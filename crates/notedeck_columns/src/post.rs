@@ -4,7 +4,8 @@ use egui::{
     TextBuffer, TextEdit, TextFormat,
 };
 use enostr::{FullKeypair, Pubkey};
-use nostrdb::{Note, NoteBuilder, NoteReply};
+use nostrdb::{Note, NoteBuilder, NoteReply, Transaction};
+use notedeck::note::note_nip19_event_bech;
 use std::{
     any::TypeId,
     collections::{BTreeMap, HashMap, HashSet},
@@ -20,6 +21,10 @@ pub struct NewPost {
     pub account: FullKeypair,
     pub media: Vec<Nip94Event>,
     pub mentions: Vec<Pubkey>,
+    /// Reason for a NIP-36 content warning, empty when none is given
+    pub content_warning: Option<String>,
+    /// Unix time of the NIP-40 expiration, if the post expires
+    pub expiration: Option<u64>,
 }
 
 fn client_variant() -> &'static str {
@@ -53,9 +58,21 @@ impl NewPost {
             account,
             media,
             mentions,
+            content_warning: None,
+            expiration: None,
         }
     }
 
+    pub fn with_content_warning(mut self, reason: Option<String>) -> Self {
+        self.content_warning = reason.map(|r| r.trim().to_owned());
+        self
+    }
+
+    pub fn with_expiration(mut self, expiration: Option<u64>) -> Self {
+        self.expiration = expiration;
+        self
+    }
+
     /// creates a NoteBuilder with all the shared data between note, reply & quote reply
     fn builder_with_shared_tags<'a>(&self, mut content: String) -> NoteBuilder<'a> {
         append_urls(&mut content, &self.media);
@@ -75,6 +92,20 @@ impl NewPost {
             builder = add_mention_tags(builder, &self.mentions);
         }
 
+        if let Some(reason) = &self.content_warning {
+            builder = builder.start_tag().tag_str("content-warning");
+            if !reason.is_empty() {
+                builder = builder.tag_str(reason);
+            }
+        }
+
+        if let Some(expiration) = self.expiration {
+            builder = builder
+                .start_tag()
+                .tag_str("expiration")
+                .tag_str(&expiration.to_string());
+        }
+
         builder
     }
 
@@ -114,14 +145,15 @@ impl NewPost {
                 .sign(seckey)
         };
 
-        let mut seen_p: HashSet<&[u8; 32]> = HashSet::new();
-
-        builder = builder
-            .start_tag()
-            .tag_str("p")
-            .tag_str(&hex::encode(replying_to.pubkey()));
+        // mentions were already tagged with the shared tags
+        let mut seen_p: HashSet<&[u8; 32]> = self.mentions.iter().map(|pk| pk.bytes()).collect();
 
-        seen_p.insert(replying_to.pubkey());
+        if seen_p.insert(replying_to.pubkey()) {
+            builder = builder
+                .start_tag()
+                .tag_str("p")
+                .tag_str(&hex::encode(replying_to.pubkey()));
+        }
 
         for tag in replying_to.tags() {
             if tag.count() < 2 {
@@ -153,12 +185,12 @@ impl NewPost {
             .expect("expected build to work")
     }
 
-    pub fn to_quote(&self, seckey: &[u8; 32], quoting: &Note) -> Note<'_> {
-        let new_content = format!(
-            "{}\nnostr:{}",
-            self.content,
-            enostr::NoteId::new(*quoting.id()).to_bech().unwrap()
-        );
+    pub fn to_quote(&self, seckey: &[u8; 32], quoting: &Note, txn: &Transaction) -> Note<'_> {
+        let bech = note_nip19_event_bech(quoting, txn)
+            .or_else(|| enostr::NoteId::new(*quoting.id()).to_bech())
+            .expect("note id to bech");
+        let new_content = format!("{}\nnostr:{}", self.content, bech);
+        let relay_hint = quoting.relays(txn).next().unwrap_or("");
 
         let builder = self.builder_with_shared_tags(new_content);
 
@@ -166,6 +198,8 @@ impl NewPost {
             .start_tag()
             .tag_str("q")
             .tag_str(&hex::encode(quoting.id()))
+            .tag_str(relay_hint)
+            .tag_str(&hex::encode(quoting.pubkey()))
             .start_tag()
             .tag_str("p")
             .tag_str(&hex::encode(quoting.pubkey()))
@@ -371,6 +405,55 @@ impl PostBuffer {
         self.text_buffer.is_empty()
     }
 
+    /// A buffer holding `text` with the mentions saved from an earlier one.
+    /// Mentions that no longer fit the text are dropped.
+    pub fn restore(text: String, mentions: &[SavedMention]) -> Self {
+        let mut buf = PostBuffer {
+            text_buffer: text,
+            ..Default::default()
+        };
+        let len = buf.text_buffer.chars().count();
+
+        for mention in mentions {
+            if mention.start_index >= mention.end_index || mention.end_index > len {
+                continue;
+            }
+
+            let key = buf.get_new_mentions_key();
+            buf.mentions.insert(
+                key,
+                MentionInfo {
+                    start_index: mention.start_index,
+                    end_index: mention.end_index,
+                    mention_type: MentionType::Finalized(mention.pubkey),
+                },
+            );
+            buf.mention_starts.insert(mention.start_index, key);
+            buf.mention_ends.insert(mention.end_index, key);
+        }
+
+        buf
+    }
+
+    /// The finalized mentions, in order, for saving the buffer to disk.
+    /// Pending mentions are kept only as the text typed so far.
+    pub fn saved_mentions(&self) -> Vec<SavedMention> {
+        self.mention_starts
+            .values()
+            .filter_map(|key| {
+                let info = self.mentions.get(key)?;
+                let MentionType::Finalized(pubkey) = info.mention_type else {
+                    return None;
+                };
+                Some(SavedMention {
+                    start_index: info.start_index,
+                    end_index: info.end_index,
+                    pubkey,
+                })
+            })
+            .collect()
+    }
+
     pub fn output(&self) -> PostOutput {
         let mut out = self.text_buffer.clone();
         let mut mentions = Vec::new();
@@ -509,6 +592,14 @@ pub struct MentionIndex<'a> {
     pub info: &'a MentionInfo,
 }
 
+/// A finalized mention as it is written to disk with a draft
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SavedMention {
+    pub start_index: usize,
+    pub end_index: usize,
+    pub pubkey: Pubkey,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum MentionType {
     Pending,
@@ -851,6 +942,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn restored_draft_keeps_its_mentions() {
+        let mut buf = PostBuffer::default();
+        apply_mention_example(&mut buf);
+        buf.insert_text(" ", 55);
+        buf.insert_text("@pend", 56);
+        assert_eq!(buf.mentions.len(), 5);
+
+        let saved = buf.saved_mentions();
+        assert_eq!(saved.len(), 4);
+
+        let mut restored = PostBuffer::restore(buf.text_buffer.clone(), &saved);
+        assert_eq!(restored.output().text, buf.output().text);
+        assert_eq!(restored.output().mentions, buf.output().mentions);
+
+        // restored mentions still move with edits before them
+        restored.insert_text("hi ", 0);
+        let first = restored.mentions.get(&0).unwrap();
+        assert_eq!(first.bounds(), 8..13);
+        assert_eq!(restored.get_new_mentions_key(), 4);
+    }
+
     #[test]
     fn test_extract_hashtags() {
         let test_cases = vec![
@@ -879,6 +992,65 @@ mod tests {
         }
     }
 
+    #[test]
+    fn reply_tags_a_mentioned_author_once() {
+        let author = FullKeypair::generate();
+        let replying_to = NoteBuilder::new()
+            .kind(1)
+            .content("hello")
+            .sign(&author.secret_key.secret_bytes())
+            .build()
+            .expect("note");
+
+        let post = NewPost::new(
+            "hi".to_owned(),
+            FullKeypair::generate(),
+            vec![],
+            vec![author.pubkey],
+        );
+        let reply = post.to_reply(&post.account.secret_key.secret_bytes(), &replying_to);
+
+        let p_tags = reply
+            .tags()
+            .iter()
+            .filter(|t| t.get_str(0) == Some("p"))
+            .count();
+        assert_eq!(p_tags, 1);
+    }
+
+    #[test]
+    fn content_warning_is_tagged() {
+        let kp = FullKeypair::generate();
+        let seckey = kp.secret_key.secret_bytes();
+
+        let warned = NewPost::new("plot twist".to_owned(), kp.clone(), vec![], vec![])
+            .with_content_warning(Some(" spoilers ".to_owned()));
+        let note = warned.to_note(&seckey);
+        assert_eq!(notedeck::content_warning(&note), Some("spoilers"));
+
+        let unexplained = NewPost::new("plot twist".to_owned(), kp.clone(), vec![], vec![])
+            .with_content_warning(Some(String::new()));
+        let note = unexplained.to_note(&seckey);
+        assert_eq!(notedeck::content_warning(&note), Some(""));
+
+        let plain = NewPost::new("plot twist".to_owned(), kp, vec![], vec![]);
+        assert_eq!(notedeck::content_warning(&plain.to_note(&seckey)), None);
+    }
+
+    #[test]
+    fn expiration_is_tagged() {
+        let kp = FullKeypair::generate();
+        let seckey = kp.secret_key.secret_bytes();
+
+        let expiring = NewPost::new("gone soon".to_owned(), kp.clone(), vec![], vec![])
+            .with_expiration(Some(1_700_000_000));
+        let note = expiring.to_note(&seckey);
+        assert_eq!(notedeck::note_expiration(&note), Some(1_700_000_000));
+
+        let plain = NewPost::new("here to stay".to_owned(), kp, vec![], vec![]);
+        assert_eq!(notedeck::note_expiration(&plain.to_note(&seckey)), None);
+    }
+
     #[test]
     fn test_insert_single_mention() {
         let mut buf = PostBuffer::default();
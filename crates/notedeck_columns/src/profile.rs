@@ -3,7 +3,8 @@ use nostrdb::{Ndb, Note, NoteBuildOptions, NoteBuilder, Transaction};
 
 use notedeck::{
     builder_from_note, note::publish::publish_note_builder, send_mute_event, Accounts,
-    ContactState, DataPath, Localization, ProfileContext, PublishApi, RelayType, RemoteApi,
+    ContactState, DataPath, Localization, MuteEntry, ProfileContext, PublishApi, RelayType,
+    RemoteApi, SettingsHandler,
 };
 use tracing::info;
 
@@ -20,9 +21,11 @@ impl SaveProfileChanges {
     }
     pub fn to_note(&self) -> Note<'_> {
         let sec = &self.kp.secret_key.to_secret_bytes();
+        let mut state = self.state.clone();
+        state.remove_empty_strings();
         add_client_tag(NoteBuilder::new())
             .kind(0)
-            .content(&self.state.to_json())
+            .content(&state.to_json())
             .options(NoteBuildOptions::default().created_at(true).sign(sec))
             .build()
             .expect("should build")
@@ -55,6 +58,7 @@ impl ProfileAction {
         ndb: &Ndb,
         remote: &mut RemoteApi<'_>,
         accounts: &Accounts,
+        settings: &mut SettingsHandler,
     ) -> Option<RouterAction> {
         match self {
             ProfileAction::Edit(kp) => Some(RouterAction::route_to(Route::EditProfile(kp.pubkey))),
@@ -144,6 +148,21 @@ impl ProfileAction {
                         }
                         None
                     }
+                    ProfileContextSelection::SetMuteMode(mode) => {
+                        settings.set_mute_mode(
+                            accounts.selected_account_pubkey(),
+                            &MuteEntry::Pubkey(*profile_context.profile.bytes()),
+                            *mode,
+                        );
+                        None
+                    }
+                    ProfileContextSelection::MuteNotifications => {
+                        let account = accounts.selected_account_pubkey();
+                        let muted =
+                            settings.is_notification_muted(account, &profile_context.profile);
+                        settings.set_notification_muted(account, &profile_context.profile, !muted);
+                        None
+                    }
                     ProfileContextSelection::ReportUser => {
                         let target = notedeck::ReportTarget {
                             pubkey: profile_context.profile,
@@ -155,9 +174,11 @@ impl ProfileAction {
                         ))
                     }
                     _ => {
-                        profile_context
-                            .selection
-                            .process(ctx, &profile_context.profile);
+                        profile_context.selection.process(
+                            ctx,
+                            &profile_context.profile,
+                            settings.open_with_template(),
+                        );
                         None
                     }
                 }
@@ -285,6 +306,25 @@ fn construct_new_contact_list<'a>(pks: Vec<Pubkey>) -> NoteBuilder<'a> {
     builder
 }
 
+/// Publish the first kind 0 profile of a freshly generated account
+pub fn send_new_profile(
+    kp: FilledKeypair<'_>,
+    ndb: &Ndb,
+    publisher: &mut PublishApi<'_, '_>,
+    display_name: &str,
+) {
+    let mut state = ProfileState::default();
+    *state.str_mut("name") = display_name.to_owned();
+    *state.str_mut("display_name") = display_name.to_owned();
+
+    let builder = add_client_tag(NoteBuilder::new())
+        .kind(0)
+        .content(&state.to_json())
+        .options(NoteBuildOptions::default());
+
+    publish_note_builder(builder, ndb, publisher, kp);
+}
+
 pub fn send_default_dms_relay_list(
     kp: FilledKeypair<'_>,
     ndb: &Ndb,
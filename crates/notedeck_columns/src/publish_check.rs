@@ -0,0 +1,221 @@
+//! Optional check that published notes propagate: a little while after
+//! publishing we ask a couple of relays we did not publish to for the note,
+//! and warn when none of them has it.
+
+use std::time::{Duration, Instant};
+
+use enostr::{NormRelayUrl, NoteId, RelayId};
+use hashbrown::HashSet;
+use nostrdb::{Filter, Ndb, Transaction};
+use notedeck::{Accounts, RelayType, RemoteApi};
+use tracing::{debug, info};
+
+/// How long relays get to pass a note around before we look for it
+const VERIFY_DELAY: Duration = Duration::from_secs(10);
+
+/// How long the relays we ask get to answer
+const VERIFY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How many independent relays we ask
+const VERIFY_RELAYS: usize = 2;
+
+enum CheckStage {
+    Waiting,
+    Querying {
+        relays: Vec<NormRelayUrl>,
+        sent_at: Instant,
+    },
+}
+
+struct PendingCheck {
+    note_id: NoteId,
+    published_at: Instant,
+    stage: CheckStage,
+}
+
+#[derive(Default)]
+pub struct PublishChecks {
+    pending: Vec<PendingCheck>,
+    /// Notes none of the relays we asked had
+    missing: Vec<NoteId>,
+}
+
+/// Read relays we don't publish to, so finding the note there means it
+/// actually spread
+fn independent_relays(read: HashSet<NormRelayUrl>, write: &[RelayId]) -> Vec<NormRelayUrl> {
+    let mut relays: Vec<NormRelayUrl> = read
+        .into_iter()
+        .filter(|relay| !write.contains(&RelayId::Websocket(relay.clone())))
+        .collect();
+    relays.sort();
+    relays.truncate(VERIFY_RELAYS);
+    relays
+}
+
+fn seen_on_any<'a>(seen: impl IntoIterator<Item = &'a str>, relays: &[NormRelayUrl]) -> bool {
+    seen.into_iter()
+        .filter_map(|url| NormRelayUrl::new(url).ok())
+        .any(|url| relays.contains(&url))
+}
+
+impl PublishChecks {
+    /// Start checking a note we just published
+    pub fn track(&mut self, note_id: NoteId) {
+        self.pending.push(PendingCheck {
+            note_id,
+            published_at: Instant::now(),
+            stage: CheckStage::Waiting,
+        });
+    }
+
+    pub fn missing(&self) -> &[NoteId] {
+        &self.missing
+    }
+
+    pub fn dismiss(&mut self, note_id: &NoteId) {
+        self.missing.retain(|id| id != note_id);
+    }
+
+    /// Publish a missing note to our write relays again
+    pub fn rebroadcast(
+        &mut self,
+        note_id: &NoteId,
+        ndb: &Ndb,
+        remote: &mut RemoteApi<'_>,
+        accounts: &Accounts,
+    ) {
+        self.dismiss(note_id);
+
+        let txn = Transaction::new(ndb).expect("txn");
+        let Ok(note) = ndb.get_note_by_id(&txn, note_id.bytes()) else {
+            return;
+        };
+
+        info!("rebroadcasting note {}", note_id.hex());
+        remote
+            .publisher(accounts)
+            .publish_note(&note, RelayType::AccountsWrite);
+        self.track(*note_id);
+    }
+
+    /// Query relays for notes whose delay passed and settle the checks
+    /// whose relays had time to answer
+    pub fn poll(
+        &mut self,
+        ndb: &Ndb,
+        remote: &mut RemoteApi<'_>,
+        accounts: &Accounts,
+        ctx: &egui::Context,
+    ) {
+        if self.pending.is_empty() {
+            return;
+        }
+
+        let now = Instant::now();
+        let mut next_wakeup: Option<Duration> = None;
+        let missing = &mut self.missing;
+
+        self.pending.retain_mut(|check| match &check.stage {
+            CheckStage::Waiting => {
+                let elapsed = now.duration_since(check.published_at);
+                if elapsed < VERIFY_DELAY {
+                    next_wakeup = sooner(next_wakeup, VERIFY_DELAY - elapsed);
+                    return true;
+                }
+
+                let relays = independent_relays(
+                    accounts.selected_account_read_relays(),
+                    &accounts.selected_account_write_relays(),
+                );
+                if relays.is_empty() {
+                    debug!(
+                        "no independent relays to verify note {}",
+                        check.note_id.hex()
+                    );
+                    return false;
+                }
+
+                let filter = Filter::new().ids([check.note_id.bytes()]).limit(1).build();
+                remote
+                    .oneshot(accounts)
+                    .oneshot_to(vec![filter], relays.iter().cloned().collect());
+
+                check.stage = CheckStage::Querying {
+                    relays,
+                    sent_at: now,
+                };
+                next_wakeup = sooner(next_wakeup, VERIFY_TIMEOUT);
+                true
+            }
+
+            CheckStage::Querying { relays, sent_at } => {
+                let elapsed = now.duration_since(*sent_at);
+                if elapsed < VERIFY_TIMEOUT {
+                    next_wakeup = sooner(next_wakeup, VERIFY_TIMEOUT - elapsed);
+                    return true;
+                }
+
+                let txn = Transaction::new(ndb).expect("txn");
+                let found = ndb
+                    .get_note_by_id(&txn, check.note_id.bytes())
+                    .is_ok_and(|note| seen_on_any(note.relays(&txn), relays));
+
+                if !found {
+                    info!("note {} not found on other relays", check.note_id.hex());
+                    missing.push(check.note_id);
+                }
+                false
+            }
+        });
+
+        if let Some(wait) = next_wakeup {
+            ctx.request_repaint_after(wait);
+        }
+    }
+}
+
+fn sooner(current: Option<Duration>, wait: Duration) -> Option<Duration> {
+    Some(current.map_or(wait, |current| current.min(wait)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn relay(url: &str) -> NormRelayUrl {
+        NormRelayUrl::new(url).unwrap()
+    }
+
+    #[test]
+    fn skips_relays_we_published_to() {
+        let read: HashSet<NormRelayUrl> = [
+            relay("wss://relay.damus.io"),
+            relay("wss://nos.lol"),
+            relay("wss://relay.primal.net"),
+        ]
+        .into_iter()
+        .collect();
+        let write = vec![
+            RelayId::Websocket(relay("wss://relay.damus.io")),
+            RelayId::Multicast,
+        ];
+
+        let relays = independent_relays(read, &write);
+        assert_eq!(
+            relays,
+            vec![relay("wss://nos.lol"), relay("wss://relay.primal.net")]
+        );
+    }
+
+    #[test]
+    fn matches_relays_regardless_of_formatting() {
+        let asked = vec![relay("wss://nos.lol")];
+
+        assert!(seen_on_any(
+            ["wss://relay.damus.io", "wss://nos.lol/"],
+            &asked
+        ));
+        assert!(!seen_on_any(["wss://relay.damus.io"], &asked));
+        assert!(!seen_on_any(Vec::<&str>::new(), &asked));
+    }
+}
@@ -2,15 +2,15 @@ use egui_nav::{Percent, ReturnType};
 use enostr::{NoteId, Pubkey};
 use nostrdb::Ndb;
 use notedeck::{
-    tr, Localization, NoteZapTargetOwned, ReplacementType, ReportTarget, RootNoteIdBuf, Router,
-    ScopedSubApi, WalletType,
+    tr, Localization, ReplacementType, ReportTarget, RootNoteIdBuf, Router, ScopedSubApi,
+    WalletType, ZapTargetOwned,
 };
 use std::ops::Range;
 
 use crate::{
     accounts::AccountsRoute,
     onboarding::Onboarding,
-    scoped_sub_owner_keys::onboarding_owner_key,
+    scoped_sub_owner_keys::{media_servers_owner_key, onboarding_owner_key},
     timeline::{kind::ColumnTitle, thread::Threads, ThreadSelection, TimelineCache, TimelineKind},
     ui::add_column::{AddAlgoRoute, AddColumnRoute},
     view_state::ViewState,
@@ -23,12 +23,17 @@ use tokenator::{ParseError, TokenParser, TokenSerializable, TokenWriter};
 pub enum Route {
     Timeline(TimelineKind),
     Thread(ThreadSelection),
+    /// A single note with its parent, without the rest of the thread
+    Note(NoteId),
     Accounts(AccountsRoute),
     Reply(NoteId),
     Quote(NoteId),
     RepostDecision(NoteId),
     Relays,
     Settings,
+    MediaServers,
+    MuteList,
+    Drafts,
     ComposeNote,
     AddColumn(AddColumnRoute),
     EditProfile(Pubkey),
@@ -37,7 +42,7 @@ pub enum Route {
     Search,
     EditDeck(usize),
     Wallet(WalletType),
-    CustomizeZapAmount(NoteZapTargetOwned),
+    CustomizeZapAmount(ZapTargetOwned),
     Following(Pubkey),
     FollowedBy(Pubkey),
     TosAcceptance,
@@ -70,6 +75,10 @@ impl Route {
         Route::Thread(thread_selection)
     }
 
+    pub fn note(note_id: NoteId) -> Self {
+        Route::Note(note_id)
+    }
+
     pub fn profile(pubkey: Pubkey) -> Self {
         Route::Timeline(TimelineKind::profile(pubkey))
     }
@@ -105,6 +114,10 @@ impl Route {
                     writer.write_token(&NoteId::new(*selection.root_id.bytes()).hex());
                 }
             }
+            Route::Note(note_id) => {
+                writer.write_token("note");
+                writer.write_token(&note_id.hex());
+            }
             Route::Accounts(routes) => routes.serialize_tokens(writer),
             Route::AddColumn(routes) => routes.serialize_tokens(writer),
             Route::Search => writer.write_token("search"),
@@ -132,6 +145,15 @@ impl Route {
             Route::Settings => {
                 writer.write_token("settings");
             }
+            Route::MediaServers => {
+                writer.write_token("media_servers");
+            }
+            Route::MuteList => {
+                writer.write_token("mute_list");
+            }
+            Route::Drafts => {
+                writer.write_token("drafts");
+            }
             Route::ComposeNote => {
                 writer.write_token("compose");
             }
@@ -222,6 +244,24 @@ impl Route {
                         Ok(Route::Settings)
                     })
                 },
+                |p| {
+                    p.parse_all(|p| {
+                        p.parse_token("media_servers")?;
+                        Ok(Route::MediaServers)
+                    })
+                },
+                |p| {
+                    p.parse_all(|p| {
+                        p.parse_token("mute_list")?;
+                        Ok(Route::MuteList)
+                    })
+                },
+                |p| {
+                    p.parse_all(|p| {
+                        p.parse_token("drafts")?;
+                        Ok(Route::Drafts)
+                    })
+                },
                 |p| {
                     p.parse_all(|p| {
                         p.parse_token("repost_decision")?;
@@ -292,6 +332,12 @@ impl Route {
                         )))
                     })
                 },
+                |p| {
+                    p.parse_all(|p| {
+                        p.parse_token("note")?;
+                        Ok(Route::Note(NoteId::new(tokenator::parse_hex_id(p)?)))
+                    })
+                },
                 |p| {
                     p.parse_all(|p| {
                         p.parse_token("following")?;
@@ -339,6 +385,9 @@ impl Route {
             Route::Thread(_) => {
                 ColumnTitle::formatted(tr!(i18n, "Thread", "Column title for note thread view"))
             }
+            Route::Note(_) => {
+                ColumnTitle::formatted(tr!(i18n, "Note", "Column title for a single note"))
+            }
             Route::Reply(_id) => {
                 ColumnTitle::formatted(tr!(i18n, "Reply", "Column title for reply composition"))
             }
@@ -351,6 +400,21 @@ impl Route {
             Route::Settings => {
                 ColumnTitle::formatted(tr!(i18n, "Settings", "Column title for app settings"))
             }
+            Route::MediaServers => ColumnTitle::formatted(tr!(
+                i18n,
+                "Media Servers",
+                "Column title for managing the Blossom servers media is uploaded to"
+            )),
+            Route::MuteList => ColumnTitle::formatted(tr!(
+                i18n,
+                "Mute List",
+                "Column title for managing the mute list"
+            )),
+            Route::Drafts => ColumnTitle::formatted(tr!(
+                i18n,
+                "Drafts",
+                "Column title for the list of unfinished notes"
+            )),
             Route::Accounts(amr) => match amr {
                 AccountsRoute::Accounts => ColumnTitle::formatted(tr!(
                     i18n,
@@ -426,6 +490,21 @@ impl Route {
                     "Create People List",
                     "Column title for creating a people list"
                 )),
+                AddColumnRoute::Bookmarks => ColumnTitle::formatted(tr!(
+                    i18n,
+                    "Select a Bookmark Folder",
+                    "Column title for selecting a bookmark set"
+                )),
+                AddColumnRoute::Group => ColumnTitle::formatted(tr!(
+                    i18n,
+                    "Select a Group",
+                    "Column title for selecting a NIP-29 group"
+                )),
+                AddColumnRoute::Community => ColumnTitle::formatted(tr!(
+                    i18n,
+                    "Select a Community",
+                    "Column title for selecting a NIP-72 community"
+                )),
             },
             Route::Support => {
                 ColumnTitle::formatted(tr!(i18n, "Damus Support", "Column title for support page"))
@@ -707,6 +786,21 @@ impl fmt::Display for Route {
             }
             Route::Relays => write!(f, "{}", tr!("Relays", "Display name for relay management")),
             Route::Settings => write!(f, "{}", tr!("Settings", "Display name for settings management")),
+            Route::MediaServers => write!(
+                f,
+                "{}",
+                tr!("Media Servers", "Display name for media server management")
+            ),
+            Route::MuteList => write!(
+                f,
+                "{}",
+                tr!("Mute List", "Display name for mute list management")
+            ),
+            Route::Drafts => write!(
+                f,
+                "{}",
+                tr!("Drafts", "Display name for the list of unfinished notes")
+            ),
             Route::Accounts(amr) => match amr {
                 AccountsRoute::Accounts => write!(
                     f,
@@ -818,6 +912,13 @@ pub fn cleanup_popped_route(
         Route::EditProfile(pk) => {
             view_state.pubkey_to_profile_state.remove(pk);
         }
+        Route::MediaServers => {
+            view_state.media_servers = Default::default();
+            let _ = scoped_subs.drop_owner(media_servers_owner_key(col_index));
+        }
+        Route::MuteList => {
+            view_state.mute_list = Default::default();
+        }
         Route::Accounts(AccountsRoute::Onboarding) => {
             onboarding.end_onboarding(ndb);
             let _ = scoped_subs.drop_owner(onboarding_owner_key(col_index));
@@ -828,10 +929,13 @@ pub fn cleanup_popped_route(
 
 #[cfg(test)]
 mod tests {
-    use enostr::NoteId;
+    use enostr::{NormRelayUrl, NoteId};
     use tokenator::{TokenParser, TokenWriter};
 
-    use crate::{timeline::ThreadSelection, Route};
+    use crate::{
+        timeline::{ThreadSelection, TimelineKind},
+        Route,
+    };
     use enostr::Pubkey;
     use notedeck::RootNoteIdBuf;
 
@@ -851,4 +955,99 @@ mod tests {
         assert_eq!(expected, parsed);
         assert_eq!(token_writer.str(), data_str);
     }
+
+    #[test]
+    fn test_note_route_serialize() {
+        let note_id_hex = "1c54e5b0c386425f7e017d9e068ddef8962eb2ce1bb08ed27e24b93411c12e60";
+        let note_id = NoteId::from_hex(note_id_hex).unwrap();
+        let data_str = format!("note:{}", note_id_hex);
+        let data = &data_str.split(":").collect::<Vec<&str>>();
+        let mut token_writer = TokenWriter::default();
+        let mut parser = TokenParser::new(&data);
+        let parsed = Route::parse(&mut parser, &Pubkey::new(*note_id.bytes())).unwrap();
+        parsed.serialize_tokens(&mut token_writer);
+        assert_eq!(Route::Note(note_id), parsed);
+        assert_eq!(token_writer.str(), data_str);
+    }
+
+    #[test]
+    fn test_group_route_serialize() {
+        let pk_hex = "32e1827635450ebb3c5a7d12c1f8e7b2b514439ac10a67eef3d9fd9c5c68e245";
+        let author = Pubkey::from_hex(pk_hex).unwrap();
+        let relay = NormRelayUrl::new("wss://groups.example.com:4443").unwrap();
+        let expected = Route::Timeline(TimelineKind::group(relay, "pizza"));
+
+        let mut token_writer = TokenWriter::default();
+        expected.serialize_tokens(&mut token_writer);
+        let data_str = token_writer.str().to_owned();
+        let data = &data_str.split(":").collect::<Vec<&str>>();
+        let mut parser = TokenParser::new(&data);
+        let parsed = Route::parse(&mut parser, &author).unwrap();
+        assert_eq!(expected, parsed);
+    }
+
+    #[test]
+    fn test_community_route_serialize() {
+        let pk_hex = "32e1827635450ebb3c5a7d12c1f8e7b2b514439ac10a67eef3d9fd9c5c68e245";
+        let author = Pubkey::from_hex(pk_hex).unwrap();
+        let data_str = format!("community:{pk_hex}:rust");
+        let data = &data_str.split(":").collect::<Vec<&str>>();
+        let mut token_writer = TokenWriter::default();
+        let mut parser = TokenParser::new(&data);
+        let parsed = Route::parse(&mut parser, &author).unwrap();
+        parsed.serialize_tokens(&mut token_writer);
+        assert_eq!(
+            Route::Timeline(TimelineKind::community(author, "rust")),
+            parsed
+        );
+        assert_eq!(token_writer.str(), data_str);
+    }
+
+    #[test]
+    fn test_live_route_serialize() {
+        let pk_hex = "32e1827635450ebb3c5a7d12c1f8e7b2b514439ac10a67eef3d9fd9c5c68e245";
+        let author = Pubkey::from_hex(pk_hex).unwrap();
+        let data_str = format!("live:{pk_hex}");
+        let data = &data_str.split(":").collect::<Vec<&str>>();
+        let mut token_writer = TokenWriter::default();
+        let mut parser = TokenParser::new(&data);
+        let parsed = Route::parse(&mut parser, &author).unwrap();
+        parsed.serialize_tokens(&mut token_writer);
+        assert_eq!(Route::Timeline(TimelineKind::live(author)), parsed);
+        assert_eq!(token_writer.str(), data_str);
+    }
+
+    #[test]
+    fn test_address_route_serialize() {
+        let pk_hex = "32e1827635450ebb3c5a7d12c1f8e7b2b514439ac10a67eef3d9fd9c5c68e245";
+        let author = Pubkey::from_hex(pk_hex).unwrap();
+        let data_str = format!("naddr:30023:{pk_hex}:my-article");
+        let data = &data_str.split(":").collect::<Vec<&str>>();
+        let mut token_writer = TokenWriter::default();
+        let mut parser = TokenParser::new(&data);
+        let parsed = Route::parse(&mut parser, &author).unwrap();
+        let expected = Route::Timeline(TimelineKind::address(notedeck::NoteAddress::new(
+            30023,
+            author,
+            "my-article",
+        )));
+        parsed.serialize_tokens(&mut token_writer);
+        assert_eq!(expected, parsed);
+        assert_eq!(token_writer.str(), data_str);
+    }
+
+    #[test]
+    fn test_bookmarks_route_serialize() {
+        let pk_hex = "32e1827635450ebb3c5a7d12c1f8e7b2b514439ac10a67eef3d9fd9c5c68e245";
+        let author = Pubkey::from_hex(pk_hex).unwrap();
+        let data_str = format!("bookmarks:{pk_hex}:reading");
+        let data = &data_str.split(":").collect::<Vec<&str>>();
+        let mut token_writer = TokenWriter::default();
+        let mut parser = TokenParser::new(&data);
+        let parsed = Route::parse(&mut parser, &author).unwrap();
+        let expected = Route::Timeline(TimelineKind::bookmarks(author, "reading".to_owned()));
+        parsed.serialize_tokens(&mut token_writer);
+        assert_eq!(expected, parsed);
+        assert_eq!(token_writer.str(), data_str);
+    }
 }
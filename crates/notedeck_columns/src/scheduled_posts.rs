@@ -0,0 +1,285 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chrono::{Local, NaiveDateTime, TimeZone};
+use enostr::{NoteId, Pubkey};
+use nostrdb::{Ndb, Transaction};
+use notedeck::{storage, unix_time_secs, Accounts, DataPath, DataPathType, Directory, RemoteApi};
+use serde::{Deserialize, Serialize};
+use tracing::error;
+
+use crate::{
+    draft::DraftTarget,
+    media_upload::Nip94Event,
+    post::NewPost,
+    ui::note::{NewPostAction, PostType},
+};
+
+static SCHEDULED_POSTS_FILE: &str = "scheduled_posts.json";
+
+/// How publish times are typed into the composer and shown in lists
+const PUBLISH_TIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// A post written now to be published later. It's signed when it goes
+/// out, so replies and quotes pick up their target when published.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledPost {
+    /// When it was scheduled, in nanoseconds, which also identifies it
+    pub id: u64,
+    pub author: Pubkey,
+    pub target: DraftTarget,
+    pub content: String,
+    #[serde(default)]
+    pub mentions: Vec<Pubkey>,
+    #[serde(default)]
+    pub media: Vec<Nip94Event>,
+    #[serde(default)]
+    pub content_warning: Option<String>,
+    /// Unix time of the NIP-40 expiration, if the post expires
+    #[serde(default)]
+    pub expiration: Option<u64>,
+    /// Unix time to publish at
+    pub publish_at: u64,
+    /// Why publishing failed. The post is kept so it isn't lost.
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+impl ScheduledPost {
+    pub fn new(target: DraftTarget, post: &NewPost, publish_at: u64) -> Self {
+        let id = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+
+        Self {
+            id,
+            author: post.account.pubkey,
+            target,
+            content: post.content.clone(),
+            mentions: post.mentions.clone(),
+            media: post.media.clone(),
+            content_warning: post.content_warning.clone(),
+            expiration: post.expiration,
+            publish_at,
+            error: None,
+        }
+    }
+
+    /// Whether it should go out now. Posts only go out while their author
+    /// is the selected account, since that's whose write relays we know.
+    fn is_due(&self, now: u64, selected: &Pubkey) -> bool {
+        self.error.is_none() && self.publish_at <= now && self.author == *selected
+    }
+
+    fn publish(
+        &self,
+        ndb: &Ndb,
+        txn: &Transaction,
+        accounts: &Accounts,
+        remote: &mut RemoteApi<'_>,
+    ) -> Result<NoteId, String> {
+        let Some(kp) = accounts.get_full(&self.author) else {
+            return Err("the account's secret key is no longer available".to_owned());
+        };
+
+        let post = NewPost::new(
+            self.content.clone(),
+            kp.to_full(),
+            self.media.clone(),
+            self.mentions.clone(),
+        )
+        .with_content_warning(self.content_warning.clone())
+        .with_expiration(self.expiration);
+
+        NewPostAction::new(PostType::from(self.target), post)
+            .publish(ndb, txn, &mut remote.publisher(accounts))
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Posts waiting for their publish time, kept in the data directory so
+/// they survive a restart. They're published by the running app; posts
+/// whose time passed while it was closed go out on the next start.
+#[derive(Default)]
+pub struct ScheduledPosts {
+    directory: Option<Directory>,
+    posts: Vec<ScheduledPost>,
+}
+
+impl ScheduledPosts {
+    pub fn load(path: &DataPath) -> Self {
+        let directory = Directory::new(path.path(DataPathType::Setting));
+        let posts = directory
+            .get_file(SCHEDULED_POSTS_FILE.to_owned())
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+
+        Self {
+            directory: Some(directory),
+            posts,
+        }
+    }
+
+    /// Pending posts, soonest first
+    pub fn posts(&self) -> &[ScheduledPost] {
+        &self.posts
+    }
+
+    pub fn add(&mut self, post: ScheduledPost) {
+        let i = self
+            .posts
+            .partition_point(|p| p.publish_at <= post.publish_at);
+        self.posts.insert(i, post);
+        self.save();
+    }
+
+    pub fn cancel(&mut self, id: u64) {
+        self.posts.retain(|p| p.id != id);
+        self.save();
+    }
+
+    /// Publish the posts whose time has come, returning the ids of the
+    /// notes that went out
+    pub fn poll(
+        &mut self,
+        ndb: &Ndb,
+        accounts: &Accounts,
+        remote: &mut RemoteApi<'_>,
+        egui_ctx: &egui::Context,
+    ) -> Vec<NoteId> {
+        let now = unix_time_secs();
+        let selected = *accounts.selected_account_pubkey();
+
+        if let Some(next) = self.posts.iter().find(|p| p.error.is_none()) {
+            if next.publish_at > now {
+                egui_ctx.request_repaint_after(Duration::from_secs(next.publish_at - now));
+            }
+        }
+
+        if !self.posts.iter().any(|p| p.is_due(now, &selected)) {
+            return Vec::new();
+        }
+
+        let Ok(txn) = Transaction::new(ndb) else {
+            return Vec::new();
+        };
+
+        let mut published = Vec::new();
+        self.posts.retain_mut(|post| {
+            if !post.is_due(now, &selected) {
+                return true;
+            }
+
+            match post.publish(ndb, &txn, accounts, remote) {
+                Ok(note_id) => {
+                    published.push(note_id);
+                    false
+                }
+                Err(e) => {
+                    error!("could not publish scheduled post: {e}");
+                    post.error = Some(e);
+                    true
+                }
+            }
+        });
+
+        self.save();
+        published
+    }
+
+    fn save(&self) {
+        let Some(directory) = &self.directory else {
+            return;
+        };
+
+        let serialized = match serde_json::to_string(&self.posts) {
+            Ok(s) => s,
+            Err(e) => {
+                error!("Could not serialize scheduled posts: {e}");
+                return;
+            }
+        };
+
+        if let Err(e) = storage::write_file(
+            &directory.file_path,
+            SCHEDULED_POSTS_FILE.to_owned(),
+            &serialized,
+        ) {
+            error!("Could not write {SCHEDULED_POSTS_FILE}: {e}");
+        }
+    }
+}
+
+/// A local time typed as `YYYY-MM-DD HH:MM`, as unix time
+pub fn parse_publish_time(text: &str) -> Option<u64> {
+    let time = NaiveDateTime::parse_from_str(text.trim(), PUBLISH_TIME_FORMAT).ok()?;
+    let local = Local.from_local_datetime(&time).earliest()?;
+    u64::try_from(local.timestamp()).ok()
+}
+
+pub fn format_publish_time(publish_at: u64) -> String {
+    Local
+        .timestamp_opt(publish_at as i64, 0)
+        .single()
+        .map(|t| t.format(PUBLISH_TIME_FORMAT).to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scheduled(author: Pubkey, publish_at: u64) -> ScheduledPost {
+        ScheduledPost {
+            id: publish_at,
+            author,
+            target: DraftTarget::Compose,
+            content: "later".to_owned(),
+            mentions: Vec::new(),
+            media: Vec::new(),
+            content_warning: None,
+            expiration: None,
+            publish_at,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn publish_time_round_trips() {
+        let at = parse_publish_time(" 2030-01-02 03:04 ").unwrap();
+        assert_eq!(format_publish_time(at), "2030-01-02 03:04");
+
+        assert_eq!(parse_publish_time("tomorrow"), None);
+        assert_eq!(parse_publish_time("2030-01-02"), None);
+    }
+
+    #[test]
+    fn only_the_selected_authors_due_posts_go_out() {
+        let me = Pubkey::new([1; 32]);
+        let other = Pubkey::new([2; 32]);
+
+        assert!(scheduled(me, 100).is_due(100, &me));
+        assert!(!scheduled(me, 101).is_due(100, &me));
+        assert!(!scheduled(other, 100).is_due(100, &me));
+
+        let mut failed = scheduled(me, 100);
+        failed.error = Some("no key".to_owned());
+        assert!(!failed.is_due(100, &me));
+    }
+
+    #[test]
+    fn posts_are_kept_soonest_first() {
+        let me = Pubkey::new([1; 32]);
+        let mut posts = ScheduledPosts::default();
+        posts.add(scheduled(me, 300));
+        posts.add(scheduled(me, 100));
+        posts.add(scheduled(me, 200));
+
+        let times: Vec<u64> = posts.posts().iter().map(|p| p.publish_at).collect();
+        assert_eq!(times, [100, 200, 300]);
+
+        posts.cancel(200);
+        assert_eq!(posts.posts().len(), 2);
+    }
+}
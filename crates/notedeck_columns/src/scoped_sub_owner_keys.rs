@@ -5,6 +5,7 @@ use crate::timeline::TimelineKind;
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 enum ColumnsOwner {
+    MediaServers,
     OnboardingFollowPacks,
     ThreadScope,
     TimelineRemote,
@@ -17,6 +18,13 @@ pub fn onboarding_owner_key(col: usize) -> SubOwnerKey {
         .finish()
 }
 
+/// Stable owner key for the media servers page in one column.
+pub fn media_servers_owner_key(col: usize) -> SubOwnerKey {
+    SubOwnerKey::builder(ColumnsOwner::MediaServers)
+        .with(col)
+        .finish()
+}
+
 /// Stable owner key for one thread scope within one column and account.
 pub fn thread_scope_owner_key(
     account_pk: Pubkey,
@@ -40,9 +40,7 @@ pub fn load_decks_cache(
     let serializable_decks_cache =
         serde_json::from_str::<SerializableDecksCache>(&decks_cache_str).ok()?;
 
-    serializable_decks_cache
-        .decks_cache(ndb, timeline_cache, i18n)
-        .ok()
+    Some(serializable_decks_cache.decks_cache(ndb, timeline_cache, i18n))
 }
 
 pub fn save_decks_cache(path: &DataPath, decks_cache: &DecksCache) {
@@ -93,18 +91,24 @@ impl SerializableDecksCache {
         ndb: &Ndb,
         timeline_cache: &mut TimelineCache,
         i18n: &mut Localization,
-    ) -> Result<DecksCache, Error> {
+    ) -> DecksCache {
+        // one account's unreadable decks shouldn't cost every other
+        // account its layout
         let account_to_decks = self
             .decks_cache
             .into_iter()
-            .map(|(pubkey, serializable_decks)| {
-                serializable_decks
-                    .decks(ndb, timeline_cache, &pubkey)
-                    .map(|decks| (pubkey, decks))
+            .filter_map(|(pubkey, serializable_decks)| {
+                match serializable_decks.decks(ndb, timeline_cache, &pubkey) {
+                    Ok(decks) => Some((pubkey, decks)),
+                    Err(e) => {
+                        error!("Could not load decks for {pubkey}: {e}");
+                        None
+                    }
+                }
             })
-            .collect::<Result<HashMap<Pubkey, Decks>, Error>>()?;
+            .collect::<HashMap<Pubkey, Decks>>();
 
-        Ok(DecksCache::new(account_to_decks, i18n))
+        DecksCache::new(account_to_decks, i18n)
     }
 }
 
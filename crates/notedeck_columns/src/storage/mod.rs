@@ -1,3 +1,7 @@
 mod decks;
+mod notifications_seen;
+mod shared_deck;
 
 pub use decks::{load_decks_cache, save_decks_cache, DECKS_CACHE_FILE};
+pub use notifications_seen::NotificationsSeen;
+pub use shared_deck::{SharedDeck, DECK_SET_KIND};
@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+
+use enostr::Pubkey;
+use tracing::error;
+
+use notedeck::{storage, DataPath, DataPathType, Directory};
+
+static NOTIFICATIONS_SEEN_FILE: &str = "notifications_seen.json";
+
+/// The newest notification each account has looked at, so what's unread
+/// survives a restart instead of everything loaded at startup counting
+/// as new.
+pub struct NotificationsSeen {
+    directory: Directory,
+    seen: HashMap<Pubkey, u64>,
+}
+
+impl NotificationsSeen {
+    pub fn new(path: &DataPath) -> Self {
+        let directory = Directory::new(path.path(DataPathType::Setting));
+        let seen = directory
+            .get_file(NOTIFICATIONS_SEEN_FILE.to_owned())
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+
+        Self { directory, seen }
+    }
+
+    /// `created_at` of the newest notification `pk` has seen, if we know
+    pub fn last_seen(&self, pk: &Pubkey) -> Option<u64> {
+        self.seen.get(pk).copied()
+    }
+
+    /// Everything up to `created_at` has been seen by `pk`
+    pub fn mark_seen(&mut self, pk: Pubkey, created_at: u64) {
+        if self.last_seen(&pk).is_some_and(|seen| seen >= created_at) {
+            return;
+        }
+
+        self.seen.insert(pk, created_at);
+        self.save();
+    }
+
+    pub fn is_unseen(&self, pk: &Pubkey, created_at: u64) -> Option<bool> {
+        self.last_seen(pk).map(|seen| created_at > seen)
+    }
+
+    fn save(&self) {
+        let serialized = match serde_json::to_string(&self.seen) {
+            Ok(s) => s,
+            Err(e) => {
+                error!("Could not serialize seen notifications: {e}");
+                return;
+            }
+        };
+
+        if let Err(e) = storage::write_file(
+            &self.directory.file_path,
+            NOTIFICATIONS_SEEN_FILE.to_owned(),
+            &serialized,
+        ) {
+            error!("Could not write {NOTIFICATIONS_SEEN_FILE}: {e}");
+        }
+    }
+}
@@ -0,0 +1,335 @@
+use enostr::{FilledKeypair, Pubkey};
+use nostrdb::{Ndb, NoteBuildOptions, NoteBuilder, Transaction};
+use notedeck::{note::publish::publish_note_builder, AppContext, PublishApi};
+use serde::{Deserialize, Serialize};
+use tokenator::{TokenParser, TokenWriter};
+use tracing::{error, warn};
+
+use crate::{
+    column::Columns,
+    decks::Deck,
+    route::Route,
+    timeline::{TimelineCache, TimelineKind},
+    Error,
+};
+
+/// NIP-51 has no kind for decks, so shared decks are published as
+/// addressable app data laid out like a NIP-51 set
+pub const DECK_SET_KIND: u32 = 30078;
+
+/// `d` tag prefix that tells our deck events apart from other app data
+const DECK_SET_PREFIX: &str = "notedeck-deck:";
+
+const SHARED_DECK_VERSION: u32 = 1;
+
+/// Written in place of the exporting account's pubkey, so a shared home or
+/// notifications column shows the importing account's feed
+const DECK_AUTHOR_TOKEN: &str = "deck_author";
+
+/// A deck layout that can be handed to someone else: its name, icon and the
+/// timeline shown by each column. Accounts, wallets and other settings
+/// routes are never included.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SharedDeck {
+    pub icon: char,
+    pub name: String,
+    columns: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SharedDeckFile {
+    version: u32,
+    icon: char,
+    name: String,
+    columns: Vec<String>,
+}
+
+fn portable_route(serialized: &str, author_hex: &str) -> String {
+    serialized
+        .split(':')
+        .map(|token| {
+            if token == author_hex {
+                DECK_AUTHOR_TOKEN
+            } else {
+                token
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+impl SharedDeck {
+    pub fn from_deck(deck: &Deck, author: &Pubkey) -> Self {
+        let author_hex = author.hex();
+        let columns = deck
+            .columns()
+            .columns()
+            .iter()
+            .filter_map(|column| match column.router().routes().first()? {
+                Route::Timeline(TimelineKind::Generic(_)) => None,
+                Route::Timeline(kind) => {
+                    let mut writer = TokenWriter::default();
+                    kind.serialize_tokens(&mut writer);
+                    Some(portable_route(writer.str(), &author_hex))
+                }
+                _ => None,
+            })
+            .collect();
+
+        Self {
+            icon: deck.icon,
+            name: deck.name.clone(),
+            columns,
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, Error> {
+        serde_json::to_string_pretty(&SharedDeckFile {
+            version: SHARED_DECK_VERSION,
+            icon: self.icon,
+            name: self.name.clone(),
+            columns: self.columns.clone(),
+        })
+        .map_err(|e| Error::Generic(format!("could not serialize deck: {e}")))
+    }
+
+    /// Read a deck from an exported deck file, or from the json of a
+    /// published deck event
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        if let Ok(file) = serde_json::from_str::<SharedDeckFile>(json) {
+            if file.version > SHARED_DECK_VERSION {
+                return Err(Error::Generic(format!(
+                    "deck file version {} is newer than we understand",
+                    file.version
+                )));
+            }
+
+            return Ok(Self {
+                icon: file.icon,
+                name: file.name,
+                columns: file.columns,
+            });
+        }
+
+        serde_json::from_str::<serde_json::Value>(json)
+            .ok()
+            .and_then(|event| Self::from_event(&event))
+            .ok_or_else(|| Error::Generic("not a deck file or deck event".to_owned()))
+    }
+
+    fn from_event(event: &serde_json::Value) -> Option<Self> {
+        if event.get("kind")?.as_u64()? != DECK_SET_KIND as u64 {
+            return None;
+        }
+
+        let mut is_deck = false;
+        let mut name = None;
+        let mut icon = None;
+        let mut columns = Vec::new();
+
+        for tag in event.get("tags")?.as_array()? {
+            let Some(tag) = tag.as_array() else {
+                continue;
+            };
+            let tag: Vec<&str> = tag.iter().filter_map(|t| t.as_str()).collect();
+
+            match tag.as_slice() {
+                ["d", identifier, ..] => is_deck = identifier.starts_with(DECK_SET_PREFIX),
+                ["title", title, ..] => name = Some(title.to_string()),
+                ["icon", glyph, ..] => icon = glyph.chars().next(),
+                ["column", column, ..] => columns.push(column.to_string()),
+                _ => {}
+            }
+        }
+
+        if !is_deck {
+            return None;
+        }
+
+        Some(Self {
+            icon: icon.unwrap_or_else(Deck::default_icon),
+            name: name?,
+            columns,
+        })
+    }
+
+    /// An unsigned deck event, replacing any earlier one with the same name
+    pub fn note_builder(&self) -> NoteBuilder<'_> {
+        let mut icon = [0u8; 4];
+        let mut builder = NoteBuilder::new()
+            .kind(DECK_SET_KIND)
+            .options(NoteBuildOptions::default())
+            .content("")
+            .start_tag()
+            .tag_str("d")
+            .tag_str(&format!("{DECK_SET_PREFIX}{}", self.name))
+            .start_tag()
+            .tag_str("title")
+            .tag_str(&self.name)
+            .start_tag()
+            .tag_str("icon")
+            .tag_str(self.icon.encode_utf8(&mut icon));
+
+        for column in &self.columns {
+            builder = builder.start_tag().tag_str("column").tag_str(column);
+        }
+
+        builder
+    }
+
+    pub fn publish(&self, ndb: &Ndb, publisher: &mut PublishApi<'_, '_>, kp: FilledKeypair) {
+        publish_note_builder(self.note_builder(), ndb, publisher, kp);
+    }
+
+    /// Ask where to save the deck and write it there
+    pub fn export_to_file(&self) {
+        #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+        {
+            let Some(path) = rfd::FileDialog::new()
+                .add_filter("Deck", &["json"])
+                .set_file_name(format!("{}.json", self.name))
+                .save_file()
+            else {
+                return;
+            };
+
+            let written = self
+                .to_json()
+                .and_then(|json| std::fs::write(&path, json).map_err(Error::from));
+            if let Err(err) = written {
+                error!("could not export deck to {}: {err}", path.display());
+            }
+        }
+    }
+
+    /// Ask for a deck file to import
+    pub fn import_from_file() -> Option<Self> {
+        #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+        {
+            let path = rfd::FileDialog::new()
+                .add_filter("Deck", &["json"])
+                .pick_file()?;
+
+            let deck = std::fs::read_to_string(&path)
+                .map_err(Error::from)
+                .and_then(|json| Self::from_json(&json));
+            match deck {
+                Ok(deck) => Some(deck),
+                Err(err) => {
+                    error!("could not import deck from {}: {err}", path.display());
+                    None
+                }
+            }
+        }
+
+        #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+        None
+    }
+
+    /// Build the deck for `deck_user`, opening a timeline for each column.
+    /// Columns we can't parse are skipped.
+    pub fn into_deck(
+        self,
+        ctx: &mut AppContext,
+        timeline_cache: &mut TimelineCache,
+        deck_user: Pubkey,
+    ) -> Deck {
+        let mut columns = Columns::new();
+        let txn = Transaction::new(ctx.ndb).expect("txn");
+
+        for column in &self.columns {
+            let tokens: Vec<&str> = column.split(':').collect();
+            let mut parser = TokenParser::new(&tokens);
+            let kind = match TimelineKind::parse(&mut parser, &deck_user) {
+                Ok(kind) => kind,
+                Err(err) => {
+                    warn!("skipping shared deck column '{column}': {err:?}");
+                    continue;
+                }
+            };
+
+            let mut scoped_subs = ctx.remote.scoped_subs(ctx.accounts);
+            if let Some(results) = columns.add_new_timeline_column(
+                timeline_cache,
+                &txn,
+                ctx.ndb,
+                ctx.note_cache,
+                &mut scoped_subs,
+                &kind,
+                deck_user,
+            ) {
+                results.process(
+                    ctx.ndb,
+                    ctx.note_cache,
+                    &txn,
+                    timeline_cache,
+                    ctx.unknown_ids,
+                );
+            }
+        }
+
+        Deck::new_with_columns(self.icon, self.name, columns)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AUTHOR: &str = "aa733081e4f0f79dd43023d8983265593f2b41a988671cfcef3f489b91ad93fe";
+    const OTHER: &str = "32e1827635450ebb3c5a7d12c1f8e7b2b514439ac10a67eef3d9fd9c5c68e245";
+
+    #[test]
+    fn own_pubkey_becomes_deck_author() {
+        assert_eq!(
+            portable_route(&format!("contact:{AUTHOR}"), AUTHOR),
+            "contact:deck_author"
+        );
+        assert_eq!(
+            portable_route(&format!("profile:{OTHER}"), AUTHOR),
+            format!("profile:{OTHER}")
+        );
+    }
+
+    #[test]
+    fn file_roundtrip() {
+        let deck = SharedDeck {
+            icon: '🇩',
+            name: "News".to_owned(),
+            columns: vec!["contact:deck_author".to_owned(), "hashtag:nostr".to_owned()],
+        };
+
+        let json = deck.to_json().unwrap();
+        assert_eq!(SharedDeck::from_json(&json).unwrap(), deck);
+    }
+
+    #[test]
+    fn reads_deck_events() {
+        let event = serde_json::json!({
+            "kind": DECK_SET_KIND,
+            "content": "",
+            "tags": [
+                ["d", "notedeck-deck:News"],
+                ["title", "News"],
+                ["icon", "📰"],
+                ["column", "universe"],
+                ["column", "hashtag:nostr"],
+            ],
+        });
+
+        let deck = SharedDeck::from_json(&event.to_string()).unwrap();
+        assert_eq!(deck.name, "News");
+        assert_eq!(deck.icon, '📰');
+        assert_eq!(deck.columns, vec!["universe", "hashtag:nostr"]);
+    }
+
+    #[test]
+    fn other_app_data_is_not_a_deck() {
+        let event = serde_json::json!({
+            "kind": DECK_SET_KIND,
+            "tags": [["d", "some-other-app"], ["title", "News"]],
+        });
+
+        assert!(SharedDeck::from_json(&event.to_string()).is_err());
+    }
+}
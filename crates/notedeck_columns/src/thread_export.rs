@@ -0,0 +1,248 @@
+//! Flattening a thread into Markdown or plain text, for sharing it
+//! outside of nostr.
+
+use chrono::DateTime;
+use hashbrown::HashMap;
+use nostrdb::{Filter, Ndb, Note, NoteReply, Transaction};
+use notedeck::{name::get_display_name, ThreadExport, ThreadExportFormat, ThreadExportTarget};
+use tracing::error;
+
+/// Most replies we'll put in an export
+const MAX_EXPORT_REPLIES: i32 = 1000;
+
+/// One note of the thread, ready to be written out
+struct ExportNote {
+    id: [u8; 32],
+    /// The note it replies to, if that is part of the export
+    parent: Option<[u8; 32]>,
+    author: String,
+    created_at: u64,
+    content: String,
+}
+
+pub fn export_thread(
+    ctx: &egui::Context,
+    ndb: &Ndb,
+    txn: &Transaction,
+    note: &Note<'_>,
+    export: ThreadExport,
+) {
+    let text = format_thread(&thread_notes(ndb, txn, note), export.format);
+
+    match export.target {
+        ThreadExportTarget::Clipboard => ctx.copy_text(text),
+        ThreadExportTarget::File => save_export(note, export.format, text),
+    }
+}
+
+fn save_export(note: &Note<'_>, format: ThreadExportFormat, text: String) {
+    #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+    {
+        let (name, extension) = match format {
+            ThreadExportFormat::Markdown => ("Markdown", "md"),
+            ThreadExportFormat::PlainText => ("Text", "txt"),
+        };
+
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter(name, &[extension])
+            .set_file_name(format!(
+                "thread-{}.{extension}",
+                &hex::encode(note.id())[..8]
+            ))
+            .save_file()
+        else {
+            return;
+        };
+
+        if let Err(err) = std::fs::write(&path, text) {
+            error!("could not export thread to {}: {err}", path.display());
+        }
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+    {
+        let _ = (note, format, text);
+        error!("saving a thread export needs a desktop file dialog");
+    }
+}
+
+/// The notes of `note`'s thread that we have locally, starting from its
+/// root
+fn thread_notes(ndb: &Ndb, txn: &Transaction, note: &Note<'_>) -> Vec<ExportNote> {
+    let root_id = NoteReply::new(note.tags())
+        .root()
+        .map(|root| *root.id)
+        .unwrap_or(*note.id());
+
+    let filters = [
+        Filter::new().ids([&root_id]).limit(1).build(),
+        Filter::new()
+            .kinds([1])
+            .event(&root_id)
+            .limit(MAX_EXPORT_REPLIES as u64)
+            .build(),
+    ];
+    let results = ndb
+        .query(txn, &filters, MAX_EXPORT_REPLIES + 1)
+        .unwrap_or_default();
+
+    let mut notes: Vec<ExportNote> = results
+        .iter()
+        .map(|result| export_note(ndb, txn, &result.note, &root_id))
+        .collect();
+    if !notes.iter().any(|n| n.id == *note.id()) {
+        notes.push(export_note(ndb, txn, note, &root_id));
+    }
+    notes
+}
+
+fn export_note(ndb: &Ndb, txn: &Transaction, note: &Note<'_>, root_id: &[u8; 32]) -> ExportNote {
+    let parent = if note.id() == root_id {
+        None
+    } else {
+        let reply = NoteReply::new(note.tags());
+        reply.reply().or(reply.root()).map(|parent| *parent.id)
+    };
+
+    let profile = ndb.get_profile_by_pubkey(txn, note.pubkey()).ok();
+    ExportNote {
+        id: *note.id(),
+        parent,
+        author: get_display_name(profile.as_ref()).name().to_owned(),
+        created_at: note.created_at(),
+        content: note.content().to_owned(),
+    }
+}
+
+/// Write the thread out depth first, oldest replies first. Notes whose
+/// parent we don't have start their own branch at the top level.
+fn format_thread(notes: &[ExportNote], format: ThreadExportFormat) -> String {
+    let mut children: HashMap<Option<[u8; 32]>, Vec<&ExportNote>> = HashMap::new();
+    for note in notes {
+        let parent = note
+            .parent
+            .filter(|parent| notes.iter().any(|n| n.id == *parent));
+        children.entry(parent).or_default().push(note);
+    }
+    for siblings in children.values_mut() {
+        siblings.sort_by_key(|note| (note.created_at, note.id));
+    }
+
+    let mut out = String::new();
+    let mut stack: Vec<(&ExportNote, usize)> = children
+        .get(&None)
+        .map(|top| top.iter().rev().map(|note| (*note, 0)).collect())
+        .unwrap_or_default();
+
+    while let Some((note, depth)) = stack.pop() {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        write_note(&mut out, note, depth, format);
+
+        if let Some(replies) = children.get(&Some(note.id)) {
+            stack.extend(replies.iter().rev().map(|reply| (*reply, depth + 1)));
+        }
+    }
+
+    out
+}
+
+fn write_note(out: &mut String, note: &ExportNote, depth: usize, format: ThreadExportFormat) {
+    let timestamp = DateTime::from_timestamp(note.created_at as i64, 0)
+        .map(|time| time.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_default();
+
+    // markdown nests replies as block quotes, plain text indents them
+    let (prefix, header) = match format {
+        ThreadExportFormat::Markdown => (
+            "> ".repeat(depth),
+            format!("**{}** · {timestamp}", note.author),
+        ),
+        ThreadExportFormat::PlainText => (
+            "    ".repeat(depth),
+            format!("{} · {timestamp}", note.author),
+        ),
+    };
+
+    let mut lines = vec![header];
+    if format == ThreadExportFormat::Markdown {
+        lines.push(String::new());
+    }
+    lines.extend(note.content.lines().map(str::to_owned));
+
+    for line in lines {
+        // no trailing whitespace on blank lines
+        if line.is_empty() {
+            out.push_str(prefix.trim_end());
+        } else {
+            out.push_str(&prefix);
+        }
+        out.push_str(&line);
+        out.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(id: u8, parent: Option<u8>, author: &str, content: &str) -> ExportNote {
+        ExportNote {
+            id: [id; 32],
+            parent: parent.map(|p| [p; 32]),
+            author: author.to_owned(),
+            created_at: 1_700_000_000 + id as u64 * 60,
+            content: content.to_owned(),
+        }
+    }
+
+    fn thread() -> Vec<ExportNote> {
+        vec![
+            note(3, Some(1), "carol", "second reply"),
+            note(2, Some(1), "bob", "first reply"),
+            note(1, None, "alice", "hello\nworld"),
+            note(4, Some(2), "alice", "nested"),
+        ]
+    }
+
+    #[test]
+    fn plain_text_indents_replies() {
+        assert_eq!(
+            format_thread(&thread(), ThreadExportFormat::PlainText),
+            "alice · 2023-11-14 22:14 UTC\n\
+             hello\n\
+             world\n\
+             \n    bob · 2023-11-14 22:15 UTC\n\
+             \x20   first reply\n\
+             \n        alice · 2023-11-14 22:17 UTC\n\
+             \x20       nested\n\
+             \n    carol · 2023-11-14 22:16 UTC\n\
+             \x20   second reply\n"
+        );
+    }
+
+    #[test]
+    fn markdown_quotes_replies() {
+        let notes = vec![
+            note(1, None, "alice", "hello"),
+            note(2, Some(1), "bob", "hi"),
+        ];
+        assert_eq!(
+            format_thread(&notes, ThreadExportFormat::Markdown),
+            "**alice** · 2023-11-14 22:14 UTC\n\nhello\n\n\
+             > **bob** · 2023-11-14 22:15 UTC\n>\n> hi\n"
+        );
+    }
+
+    #[test]
+    fn orphans_start_their_own_branch() {
+        let notes = vec![
+            note(5, Some(9), "dave", "lost"),
+            note(1, None, "alice", "op"),
+        ];
+        let text = format_thread(&notes, ThreadExportFormat::PlainText);
+        assert!(text.starts_with("alice"));
+        assert!(text.contains("\ndave · "));
+    }
+}
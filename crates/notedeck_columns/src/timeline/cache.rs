@@ -2,13 +2,13 @@ use crate::{
     actionbar::TimelineOpenResult,
     error::Error,
     timeline::{
-        drop_timeline_remote_owner, ensure_remote_timeline_subscription, Timeline, TimelineKind,
-        UnknownPksOwned,
+        drop_timeline_remote_owner, ensure_remote_timeline_subscription, ParentPrefetch, Timeline,
+        TimelineKind, UnknownPksOwned,
     },
 };
 
-use notedeck::ScopedSubApi;
 use notedeck::{filter, FilterState, NoteCache, NoteRef};
+use notedeck::{ProfileTipsCache, ScopedSubApi};
 
 use enostr::Pubkey;
 use nostrdb::{Filter, Ndb, Transaction};
@@ -18,6 +18,10 @@ use tracing::{debug, error, info, warn};
 #[derive(Default)]
 pub struct TimelineCache {
     timelines: HashMap<TimelineKind, Timeline>,
+    /// Ancestors of the replies on screen, fetched ahead of opening them
+    pub parent_prefetch: ParentPrefetch,
+    /// Recent tips shown on profiles
+    pub profile_tips: ProfileTipsCache,
 }
 
 pub enum Vitality<'a, M> {
@@ -217,6 +221,7 @@ impl TimelineCache {
                 debug!("got open with subscription for {:?}", &timeline.kind);
                 timeline.subscription.try_add_local(account_pk, ndb, filter);
                 ensure_remote_timeline_subscription(
+                    ndb,
                     timeline,
                     account_pk,
                     filter.remote().to_vec(),
@@ -262,6 +267,7 @@ impl TimelineCache {
             debug!("got open with *new* subscription for {:?}", &timeline.kind);
             timeline.subscription.try_add_local(account_pk, ndb, filter);
             ensure_remote_timeline_subscription(
+                ndb,
                 timeline,
                 account_pk,
                 filter.remote().to_vec(),
@@ -295,6 +301,24 @@ impl TimelineCache {
         self.timelines.get_mut(id)
     }
 
+    /// A timeline along with the prefetcher for the replies it shows
+    pub fn get_with_prefetch(
+        &mut self,
+        id: &TimelineKind,
+    ) -> Option<(&Timeline, &mut ParentPrefetch)> {
+        let timeline = self.timelines.get(id)?;
+        Some((timeline, &mut self.parent_prefetch))
+    }
+
+    /// A profile timeline along with the tips shown above it
+    pub fn get_with_tips(
+        &mut self,
+        id: &TimelineKind,
+    ) -> Option<(&mut Timeline, &mut ProfileTipsCache)> {
+        let timeline = self.timelines.get_mut(id)?;
+        Some((timeline, &mut self.profile_tips))
+    }
+
     pub fn num_timelines(&self) -> usize {
         self.timelines.len()
     }
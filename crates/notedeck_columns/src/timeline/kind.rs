@@ -1,13 +1,18 @@
+use crate::community::{
+    approvals_filter, community_name, posts_filters, CommunityDefinition, KIND_COMMUNITY,
+};
 use crate::error::Error;
+use crate::group::{group_messages_filter, group_name, group_state_filter};
 use crate::search::SearchQuery;
-use crate::timeline::{Timeline, TimelineTab};
-use enostr::{Filter, NoteId, Pubkey};
-use nostrdb::{Ndb, Transaction};
+use crate::timeline::{Timeline, TimelineTab, ViewFilter};
+use enostr::{Filter, NormRelayUrl, NoteId, Pubkey};
+use nostrdb::{Ndb, Note, Transaction};
 use notedeck::filter::{NdbQueryPackage, ValidKind};
 use notedeck::{
     contacts::{contacts_filter, hybrid_contacts_filter, hybrid_last_per_pubkey_filter},
     filter::{self, default_limit, default_remote_limit, HybridFilter},
-    tr, FilterError, FilterState, Localization, NoteCache, RootIdError, RootNoteIdBuf,
+    tr, FilterError, FilterState, Localization, NoteAddress, NoteCache, RootIdError, RootNoteIdBuf,
+    LIVE_EVENT_KIND,
 };
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
@@ -29,6 +34,54 @@ pub struct PeopleListRef {
     pub identifier: String,
 }
 
+/// Reference to a NIP-51 bookmark set (kind 30003), identified by author + "d" tag
+#[derive(Debug, Clone, PartialEq, Hash, Eq)]
+pub struct BookmarkSetRef {
+    pub author: Pubkey,
+    pub identifier: String,
+}
+
+/// Reference to a NIP-29 group, identified by its relay + group id
+#[derive(Debug, Clone, PartialEq, Hash, Eq)]
+pub struct GroupRef {
+    pub relay: NormRelayUrl,
+    pub id: String,
+}
+
+impl GroupRef {
+    pub fn new(relay: NormRelayUrl, id: impl Into<String>) -> Self {
+        Self {
+            relay,
+            id: id.into(),
+        }
+    }
+}
+
+/// Reference to a NIP-72 community (kind 34550), identified by owner + "d" tag
+#[derive(Debug, Clone, PartialEq, Hash, Eq)]
+pub struct CommunityRef {
+    pub author: Pubkey,
+    pub identifier: String,
+}
+
+impl CommunityRef {
+    pub fn new(author: Pubkey, identifier: impl Into<String>) -> Self {
+        Self {
+            author,
+            identifier: identifier.into(),
+        }
+    }
+
+    /// The `34550:<owner>:<d>` coordinate posts and approvals are tagged with
+    pub fn coordinate(&self) -> String {
+        format!("{KIND_COMMUNITY}:{}:{}", self.author.hex(), self.identifier)
+    }
+
+    pub fn address(&self) -> NoteAddress {
+        NoteAddress::new(KIND_COMMUNITY, self.author, self.identifier.clone())
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Hash, Eq)]
 pub enum ListKind {
     Contact(Pubkey),
@@ -234,10 +287,31 @@ pub enum TimelineKind {
     Generic(u64),
 
     Hashtag(Vec<String>),
+
+    /// Notes saved in a NIP-51 bookmark set
+    Bookmarks(BookmarkSetRef),
+
+    /// Chat messages of a NIP-29 group
+    Group(GroupRef),
+
+    /// Approved posts of a NIP-72 community
+    Community(CommunityRef),
+
+    /// NIP-53 live streams hosted by or featuring the people a user follows
+    Live(Pubkey),
+
+    /// An addressable note (kind 3xxxx), such as an article or a list,
+    /// followed by its replies
+    Address(NoteAddress),
 }
 
 const NOTIFS_TOKEN_DEPRECATED: &str = "notifs";
 const NOTIFS_TOKEN: &str = "notifications";
+const BOOKMARKS_TOKEN: &str = "bookmarks";
+const GROUP_TOKEN: &str = "group";
+const COMMUNITY_TOKEN: &str = "community";
+const LIVE_TOKEN: &str = "live";
+const ADDRESS_TOKEN: &str = "naddr";
 
 /// Hardcoded algo timelines
 #[derive(Debug, Hash, Clone, PartialEq, Eq)]
@@ -335,6 +409,11 @@ impl TimelineKind {
             TimelineKind::Generic(_) => None,
             TimelineKind::Hashtag(_ht) => None,
             TimelineKind::Search(query) => query.author(),
+            TimelineKind::Bookmarks(bsr) => Some(&bsr.author),
+            TimelineKind::Group(_group) => None,
+            TimelineKind::Community(_community) => None,
+            TimelineKind::Live(pk) => Some(pk),
+            TimelineKind::Address(address) => Some(&address.pubkey),
         }
     }
 
@@ -350,6 +429,11 @@ impl TimelineKind {
             TimelineKind::Generic(_) => true,
             TimelineKind::Hashtag(_ht) => true,
             TimelineKind::Search(_q) => true,
+            TimelineKind::Bookmarks(_bsr) => true,
+            TimelineKind::Group(_group) => true,
+            TimelineKind::Community(_community) => true,
+            TimelineKind::Live(_pk) => true,
+            TimelineKind::Address(_address) => true,
         }
     }
 
@@ -388,6 +472,32 @@ impl TimelineKind {
                 writer.write_token("hashtag");
                 writer.write_token(&ht.join(" "));
             }
+            TimelineKind::Bookmarks(bsr) => {
+                writer.write_token(BOOKMARKS_TOKEN);
+                PubkeySource::pubkey(bsr.author).serialize_tokens(writer);
+                writer.write_token(&bsr.identifier);
+            }
+            TimelineKind::Group(group) => {
+                writer.write_token(GROUP_TOKEN);
+                // relay urls contain the token delimiter
+                writer.write_token(&hex::encode(group.relay.to_string()));
+                writer.write_token(&group.id);
+            }
+            TimelineKind::Community(community) => {
+                writer.write_token(COMMUNITY_TOKEN);
+                PubkeySource::pubkey(community.author).serialize_tokens(writer);
+                writer.write_token(&community.identifier);
+            }
+            TimelineKind::Live(pk) => {
+                writer.write_token(LIVE_TOKEN);
+                PubkeySource::pubkey(*pk).serialize_tokens(writer);
+            }
+            TimelineKind::Address(address) => {
+                writer.write_token(ADDRESS_TOKEN);
+                writer.write_token(&address.kind.to_string());
+                PubkeySource::pubkey(address.pubkey).serialize_tokens(writer);
+                writer.write_token(&address.identifier);
+            }
         }
     }
 
@@ -414,6 +524,73 @@ impl TimelineKind {
             return notifications;
         }
 
+        let bookmarks = parser.try_parse(|p| {
+            p.parse_token(BOOKMARKS_TOKEN)?;
+            let pk_src = PubkeySource::parse_from_tokens(p)?;
+            let identifier = p.pull_token()?.to_string();
+            Ok(TimelineKind::bookmarks(
+                *pk_src.as_pubkey(deck_author),
+                identifier,
+            ))
+        });
+        if bookmarks.is_ok() {
+            return bookmarks;
+        }
+
+        let group = parser.try_parse(|p| {
+            p.parse_token(GROUP_TOKEN)?;
+            let relay = hex::decode(p.pull_token()?).map_err(|_| ParseError::HexDecodeFailed)?;
+            let relay = String::from_utf8(relay)
+                .ok()
+                .and_then(|url| NormRelayUrl::new(&url).ok())
+                .ok_or(ParseError::DecodeFailed)?;
+            let id = p.pull_token()?;
+            Ok(TimelineKind::group(relay, id))
+        });
+        if group.is_ok() {
+            return group;
+        }
+
+        let community = parser.try_parse(|p| {
+            p.parse_token(COMMUNITY_TOKEN)?;
+            let pk_src = PubkeySource::parse_from_tokens(p)?;
+            let identifier = p.pull_token()?;
+            Ok(TimelineKind::community(
+                *pk_src.as_pubkey(deck_author),
+                identifier,
+            ))
+        });
+        if community.is_ok() {
+            return community;
+        }
+
+        let live = parser.try_parse(|p| {
+            p.parse_token(LIVE_TOKEN)?;
+            let pk_src = PubkeySource::parse_from_tokens(p)?;
+            Ok(TimelineKind::Live(*pk_src.as_pubkey(deck_author)))
+        });
+        if live.is_ok() {
+            return live;
+        }
+
+        let address = parser.try_parse(|p| {
+            p.parse_token(ADDRESS_TOKEN)?;
+            let kind = p
+                .pull_token()?
+                .parse::<u32>()
+                .map_err(|_| ParseError::DecodeFailed)?;
+            let pk_src = PubkeySource::parse_from_tokens(p)?;
+            let identifier = p.pull_token()?;
+            Ok(TimelineKind::address(NoteAddress::new(
+                kind,
+                *pk_src.as_pubkey(deck_author),
+                identifier,
+            )))
+        });
+        if address.is_ok() {
+            return address;
+        }
+
         let list_tl =
             parser.try_parse(|p| Ok(TimelineKind::List(ListKind::parse(p, deck_author)?)));
         if list_tl.is_ok() {
@@ -469,6 +646,26 @@ impl TimelineKind {
         TimelineKind::List(ListKind::people_list(author, identifier))
     }
 
+    pub fn bookmarks(author: Pubkey, identifier: String) -> Self {
+        TimelineKind::Bookmarks(BookmarkSetRef { author, identifier })
+    }
+
+    pub fn group(relay: NormRelayUrl, id: impl Into<String>) -> Self {
+        TimelineKind::Group(GroupRef::new(relay, id))
+    }
+
+    pub fn community(author: Pubkey, identifier: impl Into<String>) -> Self {
+        TimelineKind::Community(CommunityRef::new(author, identifier))
+    }
+
+    pub fn live(pk: Pubkey) -> Self {
+        TimelineKind::Live(pk)
+    }
+
+    pub fn address(address: NoteAddress) -> Self {
+        TimelineKind::Address(address)
+    }
+
     pub fn search(s: String) -> Self {
         TimelineKind::Search(SearchQuery::new(s))
     }
@@ -547,6 +744,18 @@ impl TimelineKind {
             }
 
             TimelineKind::Profile(pk) => FilterState::ready_hybrid(profile_filter(pk.bytes())),
+
+            // private items can only be read with the author's key, so the
+            // filter is built once the set is ready (see is_timeline_ready)
+            TimelineKind::Bookmarks(_bsr) => FilterState::needs_remote(),
+
+            TimelineKind::Group(group) => FilterState::ready_hybrid(group_filter(group)),
+
+            TimelineKind::Community(community) => community_filter_state(txn, ndb, community),
+
+            TimelineKind::Live(pk) => live_filter_state(txn, ndb, pk),
+
+            TimelineKind::Address(address) => FilterState::ready(address_replies_filters(address)),
         }
     }
 
@@ -614,7 +823,7 @@ impl TimelineKind {
                 Some(Timeline::new(
                     TimelineKind::profile(pk),
                     FilterState::ready_hybrid(filter),
-                    TimelineTab::full_tabs(),
+                    TimelineTab::profile_tabs(),
                 ))
             }
 
@@ -630,6 +839,42 @@ impl TimelineKind {
 
             TimelineKind::Hashtag(hashtag) => Some(Timeline::hashtag(hashtag)),
 
+            TimelineKind::Bookmarks(bsr) => Some(Timeline::new(
+                TimelineKind::Bookmarks(bsr),
+                FilterState::needs_remote(),
+                TimelineTab::only_notes_and_replies(),
+            )),
+
+            TimelineKind::Group(group) => {
+                let filter = group_filter(&group);
+                Some(Timeline::new(
+                    TimelineKind::Group(group),
+                    FilterState::ready_hybrid(filter),
+                    vec![TimelineTab::new(ViewFilter::All)],
+                ))
+            }
+
+            TimelineKind::Community(community) => Some(Timeline::new(
+                TimelineKind::Community(community.clone()),
+                community_filter_state(txn, ndb, &community),
+                vec![TimelineTab::new(ViewFilter::All)],
+            )),
+
+            TimelineKind::Live(pk) => Some(Timeline::new(
+                TimelineKind::Live(pk),
+                live_filter_state(txn, ndb, &pk),
+                vec![TimelineTab::new(ViewFilter::All)],
+            )),
+
+            TimelineKind::Address(address) => {
+                let filter = FilterState::ready(address_replies_filters(&address));
+                Some(Timeline::new(
+                    TimelineKind::Address(address),
+                    filter,
+                    vec![TimelineTab::new(ViewFilter::All)],
+                ))
+            }
+
             TimelineKind::List(ListKind::Contact(pk)) => Some(Timeline::new(
                 TimelineKind::contact_list(pk),
                 contact_filter_state(txn, ndb, &pk),
@@ -710,6 +955,15 @@ impl TimelineKind {
                 ColumnTitle::formatted(tr!(i18n, "Custom", "Column title for custom timelines"))
             }
             TimelineKind::Hashtag(hashtag) => ColumnTitle::formatted(hashtag.join(" ").to_string()),
+            TimelineKind::Bookmarks(bsr) => ColumnTitle::formatted(bsr.identifier.clone()),
+            TimelineKind::Group(_group) => ColumnTitle::needs_db(self),
+            TimelineKind::Community(_community) => ColumnTitle::needs_db(self),
+            TimelineKind::Live(_pk) => ColumnTitle::formatted(tr!(
+                i18n,
+                "Live now",
+                "Column title for live streams from followed users"
+            )),
+            TimelineKind::Address(_address) => ColumnTitle::needs_db(self),
         }
     }
 }
@@ -736,7 +990,10 @@ impl<'a> TitleNeedsDb<'a> {
         TitleNeedsDb { kind }
     }
 
-    pub fn title<'txn>(&self, txn: &'txn Transaction, ndb: &Ndb) -> &'txn str {
+    pub fn title<'txn>(&self, txn: &'txn Transaction, ndb: &Ndb) -> &'txn str
+    where
+        'a: 'txn,
+    {
         if let TimelineKind::Profile(pubkey) = self.kind {
             let profile = ndb.get_profile_by_pubkey(txn, pubkey);
             let m_name = profile
@@ -745,6 +1002,15 @@ impl<'a> TitleNeedsDb<'a> {
                 .map(|p| notedeck::name::get_display_name(Some(p)).name());
 
             m_name.unwrap_or("Profile")
+        } else if let TimelineKind::Group(group) = self.kind {
+            group_name(ndb, txn, group).unwrap_or(&group.id)
+        } else if let TimelineKind::Community(community) = self.kind {
+            community_name(ndb, txn, community).unwrap_or(&community.identifier)
+        } else if let TimelineKind::Address(address) = self.kind {
+            address
+                .lookup(ndb, txn)
+                .and_then(|note| address_title(&note))
+                .unwrap_or(&address.identifier)
         } else {
             "Unknown"
         }
@@ -831,6 +1097,138 @@ fn last_per_pubkey_filter_state(txn: &Transaction, ndb: &Ndb, pk: &Pubkey) -> Fi
     }
 }
 
+/// Build the filter state for a live streams timeline. Streams are found
+/// through the follows in `pk`'s contact list.
+pub fn live_filter_state(txn: &Transaction, ndb: &Ndb, pk: &Pubkey) -> FilterState {
+    let contact_filter = contacts_filter(pk.bytes());
+
+    let results = match ndb.query(txn, std::slice::from_ref(&contact_filter), 1) {
+        Ok(results) => results,
+        Err(err) => {
+            error!("contact query failed: {err}");
+            return FilterState::Broken(FilterError::EmptyContactList);
+        }
+    };
+
+    let Some(contacts) = results.first() else {
+        return FilterState::needs_remote();
+    };
+
+    let follows = notedeck::get_p_tags(&contacts.note);
+    if follows.is_empty() {
+        return FilterState::needs_remote();
+    }
+
+    FilterState::ready_hybrid(live_filter(&follows))
+}
+
+/// Streams are usually published by the streaming service, with the host
+/// as a `p` tag, so we look for follows both as authors and participants
+fn live_filter(follows: &[&[u8; 32]]) -> HybridFilter {
+    let filters = vec![
+        Filter::new()
+            .kinds([LIVE_EVENT_KIND as u64])
+            .authors(follows.iter().copied())
+            .limit(default_limit())
+            .build(),
+        Filter::new()
+            .kinds([LIVE_EVENT_KIND as u64])
+            .pubkeys(follows.iter().copied())
+            .limit(default_limit())
+            .build(),
+    ];
+
+    HybridFilter::split(
+        vec![NdbQueryPackage {
+            filters: filters.clone(),
+            kind: ValidKind::LiveEvent,
+        }],
+        filters,
+    )
+}
+
+const PROFILE_TIPS_REMOTE_LIMIT: u64 = 50;
+const PROFILE_BADGE_AWARDS_REMOTE_LIMIT: u64 = 100;
+
+/// Chat messages are what the column shows; the group's metadata, admins
+/// and members come along on the same subscription for its header.
+fn group_filter(group: &GroupRef) -> HybridFilter {
+    let messages = group_messages_filter(group);
+    HybridFilter::split(
+        vec![NdbQueryPackage {
+            filters: vec![messages.clone()],
+            kind: ValidKind::Nine,
+        }],
+        vec![messages, group_state_filter(group)],
+    )
+}
+
+/// A community column follows moderator approvals, which stand in for the
+/// posts they approve. Only the community's current moderators are known
+/// once we have its definition, so until then the filter waits for it.
+fn community_filter_state(txn: &Transaction, ndb: &Ndb, community: &CommunityRef) -> FilterState {
+    match CommunityDefinition::load(ndb, txn, community) {
+        Some(definition) => FilterState::ready_hybrid(community_filter(&definition)),
+        None => FilterState::needs_remote(),
+    }
+}
+
+/// Pending posts and the definition come along on the remote side, for
+/// moderators to review and for the column header.
+pub fn community_filter(definition: &CommunityDefinition) -> HybridFilter {
+    let community = definition.community();
+    let approvals = approvals_filter(definition);
+
+    let mut remote = vec![approvals.clone(), community.address().filter()];
+    remote.extend(posts_filters(&community));
+
+    HybridFilter::split(
+        vec![NdbQueryPackage {
+            filters: vec![approvals],
+            kind: ValidKind::CommunityApproval,
+        }],
+        remote,
+    )
+}
+
+/// Kind 1 replies reference an addressable note in an `a` tag. NIP-22
+/// comments do too when replying to it directly, and carry it in an `A`
+/// tag anywhere further down.
+pub fn address_replies_filters(address: &NoteAddress) -> Vec<Filter> {
+    let coordinate = format!(
+        "{}:{}:{}",
+        address.kind,
+        address.pubkey.hex(),
+        address.identifier
+    );
+
+    vec![
+        Filter::new()
+            .kinds([1, 1111])
+            .tags([coordinate.as_str()], 'a')
+            .limit(default_limit())
+            .build(),
+        Filter::new()
+            .kinds([1111])
+            .tags([coordinate.as_str()], 'A')
+            .limit(default_limit())
+            .build(),
+    ]
+}
+
+/// The title an addressable note gives itself, if any
+fn address_title<'a>(note: &Note<'a>) -> Option<&'a str> {
+    note.tags()
+        .into_iter()
+        .find_map(|tag| {
+            if tag.count() < 2 || !matches!(tag.get_str(0), Some("title" | "name")) {
+                return None;
+            }
+            tag.get_str(1)
+        })
+        .filter(|title| !title.trim().is_empty())
+}
+
 fn profile_filter(pk: &[u8; 32]) -> HybridFilter {
     let local = vec![
         NdbQueryPackage {
@@ -849,13 +1247,33 @@ fn profile_filter(pk: &[u8; 32]) -> HybridFilter {
                 .build()],
             kind: ValidKind::Six,
         },
+        // reactions, for the likes tab
+        NdbQueryPackage {
+            filters: vec![Filter::new()
+                .authors([pk])
+                .kinds([7])
+                .limit(default_limit())
+                .build()],
+            kind: ValidKind::Seven,
+        },
     ];
 
-    let remote = vec![Filter::new()
-        .authors([pk])
-        .kinds([1, 6, 0, 3])
-        .limit(default_remote_limit())
-        .build()];
+    let remote = vec![
+        Filter::new()
+            .authors([pk])
+            .kinds([1, 6, 7, 0, 3])
+            .limit(default_remote_limit())
+            .build(),
+        // zap receipts to this user, so we can show recent profile tips
+        Filter::new()
+            .kinds([9735])
+            .pubkeys([pk])
+            .limit(PROFILE_TIPS_REMOTE_LIMIT)
+            .build(),
+        // the badges shown on the profile and the awards backing them
+        notedeck::badges::profile_badges_filter(pk),
+        notedeck::badges::badge_awards_filter(pk, PROFILE_BADGE_AWARDS_REMOTE_LIMIT),
+    ];
 
     HybridFilter::split(local, remote)
 }
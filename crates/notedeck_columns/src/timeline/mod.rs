@@ -1,8 +1,12 @@
 use crate::{
+    community::CommunityDefinition,
     error::Error,
     scoped_sub_owner_keys::timeline_remote_owner_key,
     timeline::{
-        kind::{people_list_note_filter, AlgoTimeline, ListKind, PeopleListRef},
+        kind::{
+            community_filter, live_filter_state, people_list_note_filter, AlgoTimeline,
+            BookmarkSetRef, CommunityRef, ListKind,
+        },
         note_units::InsertManyResponse,
         sub::TimelineSub,
         timeline_units::NotePayload,
@@ -11,33 +15,44 @@ use crate::{
 };
 
 use notedeck::{
+    bookmarks::{bookmark_set_filter, private_bookmarks},
     contacts::{hybrid_contacts_filter, hybrid_last_per_pubkey_filter},
+    create_nip51_set,
     filter::{self},
-    is_future_timestamp, tr, unix_time_secs, Accounts, CachedNote, ContactState, FilterError,
-    FilterState, Localization, NoteCache, NoteRef, RelaySelection, ScopedSubApi, ScopedSubIdentity,
-    SubConfig, SubKey, UnknownIds,
+    is_expired, is_future_timestamp, is_media_url, tr, unix_time_secs, Accounts, CachedNote,
+    ContactState, FilterError, FilterState, Localization, NoteCache, NoteRef, RelaySelection,
+    ScopedSubApi, ScopedSubIdentity, ScopedSubInspect, SubConfig, SubKey, UnknownIds,
 };
 
 use egui_virtual_list::VirtualList;
 use enostr::Pubkey;
 use nostrdb::{Filter, Ndb, Note, NoteKey, Transaction};
 use std::rc::Rc;
-use std::{cell::RefCell, collections::HashSet};
+use std::{
+    cell::RefCell,
+    collections::{BTreeSet, HashSet},
+};
 
 use tracing::{debug, error, info, warn};
 
 pub mod cache;
 pub mod kind;
 mod note_units;
+mod parent_prefetch;
+mod relay_usage;
 pub mod route;
 mod sub;
 pub mod thread;
+mod throughput;
 mod timeline_units;
 mod unit;
 
 pub use cache::TimelineCache;
 pub use kind::{ColumnTitle, PubkeySource, ThreadSelection, TimelineKind};
 pub use note_units::{CompositeType, InsertionResponse, NoteUnits};
+pub use parent_prefetch::ParentPrefetch;
+pub use relay_usage::RelayUsage;
+pub use throughput::NoteThroughput;
 pub use timeline_units::{TimelineUnits, UnknownPks};
 pub use unit::{CompositeUnit, NoteUnit, ReactionUnit, RepostUnit};
 
@@ -52,15 +67,30 @@ fn timeline_remote_sub_key(kind: &TimelineKind) -> SubKey {
         .finish()
 }
 
-fn timeline_remote_sub_config(remote_filters: Vec<Filter>, use_transparent: bool) -> SubConfig {
+/// Author timelines also go to the authors' write relays, so profiles and
+/// follow feeds load even when we don't read from where those people post.
+/// Groups only exist on their own relay.
+fn timeline_remote_sub_config(
+    ndb: &Ndb,
+    kind: &TimelineKind,
+    remote_filters: Vec<Filter>,
+) -> SubConfig {
+    let relays = if let TimelineKind::Group(group) = kind {
+        RelaySelection::Explicit(hashbrown::HashSet::from([group.relay.clone()]))
+    } else {
+        let txn = Transaction::new(ndb).expect("txn");
+        RelaySelection::outbox(ndb, &txn, &remote_filters)
+    };
+
     SubConfig {
-        relays: RelaySelection::AccountsRead,
+        relays,
         filters: remote_filters,
-        use_transparent,
+        use_transparent: matches!(kind, TimelineKind::Notifications(_)),
     }
 }
 
 pub(crate) fn ensure_remote_timeline_subscription(
+    ndb: &Ndb,
     timeline: &mut Timeline,
     account_pk: Pubkey,
     remote_filters: Vec<Filter>,
@@ -68,31 +98,54 @@ pub(crate) fn ensure_remote_timeline_subscription(
 ) {
     let owner = timeline_remote_owner_key(account_pk, &timeline.kind);
     let identity = ScopedSubIdentity::account(owner, timeline_remote_sub_key(&timeline.kind));
-    let config = timeline_remote_sub_config(
-        remote_filters,
-        matches!(&timeline.kind, TimelineKind::Notifications(_)),
-    );
+    let config = timeline_remote_sub_config(ndb, &timeline.kind, remote_filters);
     let _ = scoped_subs.ensure_sub(identity, config);
     timeline.subscription.mark_remote_seeded(account_pk);
 }
 
 pub(crate) fn update_remote_timeline_subscription(
+    ndb: &Ndb,
     timeline: &mut Timeline,
     remote_filters: Vec<Filter>,
     scoped_subs: &mut ScopedSubApi<'_, '_>,
 ) {
     let owner = timeline_remote_owner_key(scoped_subs.selected_account_pubkey(), &timeline.kind);
     let identity = ScopedSubIdentity::account(owner, timeline_remote_sub_key(&timeline.kind));
-    let config = timeline_remote_sub_config(
-        remote_filters,
-        matches!(&timeline.kind, TimelineKind::Notifications(_)),
-    );
+    let config = timeline_remote_sub_config(ndb, &timeline.kind, remote_filters);
     let _ = scoped_subs.set_sub(identity, config);
     timeline
         .subscription
         .mark_remote_seeded(scoped_subs.selected_account_pubkey());
 }
 
+/// What a timeline column is subscribed to, for the developer inspector.
+pub struct TimelineInspect {
+    /// Filters of the local nostrdb subscription, if the filter is ready.
+    pub local_filters: Vec<Filter>,
+    /// The remote scoped subscription, if one is declared.
+    pub remote: Option<ScopedSubInspect>,
+    pub notes_per_minute: usize,
+}
+
+pub fn inspect_timeline(
+    timeline: &Timeline,
+    scoped_subs: &ScopedSubApi<'_, '_>,
+) -> TimelineInspect {
+    let local_filters = match &timeline.filter {
+        FilterState::Ready(filter) => filter.local().combined(),
+        _ => Vec::new(),
+    };
+
+    let owner = timeline_remote_owner_key(scoped_subs.selected_account_pubkey(), &timeline.kind);
+    let identity = ScopedSubIdentity::account(owner, timeline_remote_sub_key(&timeline.kind));
+
+    TimelineInspect {
+        local_filters,
+        remote: scoped_subs.inspect_sub(identity),
+        notes_per_minute: timeline.throughput.per_minute(std::time::Instant::now()),
+    }
+}
+
 pub fn drop_timeline_remote_owner(
     timeline: &Timeline,
     account_pk: Pubkey,
@@ -111,6 +164,15 @@ pub enum ViewFilter {
     NotesAndReplies,
 
     All,
+
+    /// Replies without the top level notes
+    Replies,
+
+    /// Notes with an image or video link
+    Media,
+
+    /// Notes reacted to, from the reactions in the timeline
+    Likes,
 }
 
 impl ViewFilter {
@@ -128,11 +190,14 @@ impl ViewFilter {
             ViewFilter::MentionsOnly => {
                 tr!(i18n, "Mentions", "Filter label for mentions only view")
             }
+            ViewFilter::Replies => tr!(i18n, "Replies", "Filter label for replies only view"),
+            ViewFilter::Media => tr!(i18n, "Media", "Filter label for notes with media view"),
+            ViewFilter::Likes => tr!(i18n, "Likes", "Filter label for liked notes view"),
         }
     }
 
     pub fn filter_notes(cache: &CachedNote, note: &Note) -> bool {
-        note.kind() == 6 || !cache.reply.borrow(note.tags()).is_reply()
+        note.kind() == 6 || (note.kind() == 1 && !cache.reply.borrow(note.tags()).is_reply())
     }
 
     fn identity(_cache: &CachedNote, _note: &Note) -> bool {
@@ -153,12 +218,27 @@ impl ViewFilter {
         note_reply.is_reply() || note_reply.mention().is_some()
     }
 
+    fn replies(cache: &CachedNote, note: &Note) -> bool {
+        note.kind() == 1 && cache.reply.borrow(note.tags()).is_reply()
+    }
+
+    fn media(_cache: &CachedNote, note: &Note) -> bool {
+        note.kind() == 1 && note.content().split_whitespace().any(is_media_url)
+    }
+
+    fn likes(_cache: &CachedNote, note: &Note) -> bool {
+        note.kind() == 7
+    }
+
     pub fn filter(&self) -> fn(&CachedNote, &Note) -> bool {
         match self {
             ViewFilter::Notes => ViewFilter::filter_notes,
             ViewFilter::NotesAndReplies => ViewFilter::notes_and_replies,
             ViewFilter::All => ViewFilter::identity,
             ViewFilter::MentionsOnly => ViewFilter::mentions_only,
+            ViewFilter::Replies => ViewFilter::replies,
+            ViewFilter::Media => ViewFilter::media,
+            ViewFilter::Likes => ViewFilter::likes,
         }
     }
 }
@@ -195,6 +275,15 @@ impl TimelineTab {
         ]
     }
 
+    pub fn profile_tabs() -> Vec<Self> {
+        vec![
+            TimelineTab::new(ViewFilter::Notes),
+            TimelineTab::new(ViewFilter::Replies),
+            TimelineTab::new(ViewFilter::Media),
+            TimelineTab::new(ViewFilter::Likes),
+        ]
+    }
+
     pub fn notifications() -> Vec<Self> {
         vec![
             TimelineTab::new(ViewFilter::All),
@@ -328,6 +417,13 @@ pub struct Timeline {
 
     /// Whether the initial async load has been completed for this timeline.
     pub initial_load: InitialLoadState,
+
+    /// Notes arriving from the local subscription, for the column inspector.
+    pub throughput: NoteThroughput,
+
+    /// Authors whose notes are kept out of this timeline. Used for users
+    /// the account doesn't want notifications from.
+    pub quiet_authors: BTreeSet<Pubkey>,
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
@@ -416,9 +512,27 @@ impl Timeline {
             seen_latest_notes: false,
             contact_list_timestamp: None,
             initial_load: InitialLoadState::Pending,
+            throughput: NoteThroughput::default(),
+            quiet_authors: BTreeSet::new(),
         }
     }
 
+    /// Keep notes by these authors out of the timeline. A change reloads
+    /// the timeline so notes already in it are dropped or brought back.
+    pub fn set_quiet_authors(&mut self, authors: Option<&BTreeSet<Pubkey>>) {
+        let unchanged = match authors {
+            Some(authors) => self.quiet_authors == *authors,
+            None => self.quiet_authors.is_empty(),
+        };
+        if unchanged {
+            return;
+        }
+
+        self.quiet_authors = authors.cloned().unwrap_or_default();
+        self.reset_views();
+        self.initial_load = InitialLoadState::Pending;
+    }
+
     pub fn current_view(&self) -> &TimelineTab {
         &self.views[self.selected_view]
     }
@@ -493,6 +607,10 @@ impl Timeline {
 
             for (view, filter) in filters.iter().enumerate() {
                 if let Ok(note) = ndb.get_note_by_key(txn, note_ref.key) {
+                    if self.quiet_authors.contains(note.pubkey()) || is_expired(&note, now) {
+                        break;
+                    }
+
                     if filter(
                         note_cache.cached_note_or_insert_mut(note_ref.key, &note),
                         &note,
@@ -528,6 +646,8 @@ impl Timeline {
     /// The main function used for inserting notes into timelines. Handles
     /// inserting into multiple views if we have them. All timeline note
     /// insertions should use this function.
+    ///
+    /// Returns how many of the notes were accepted into the timeline.
     #[profiling::function]
     pub fn insert(
         &mut self,
@@ -537,7 +657,7 @@ impl Timeline {
         unknown_ids: &mut UnknownIds,
         note_cache: &mut NoteCache,
         reversed: bool,
-    ) -> Result<()> {
+    ) -> Result<usize> {
         let mut payloads: Vec<NotePayload> = Vec::with_capacity(new_note_ids.len());
         let now = unix_time_secs();
 
@@ -552,7 +672,11 @@ impl Timeline {
                 continue;
             };
 
-            if is_future_timestamp(note.created_at(), now) {
+            if is_future_timestamp(note.created_at(), now) || is_expired(&note, now) {
+                continue;
+            }
+
+            if self.quiet_authors.contains(note.pubkey()) {
                 continue;
             }
 
@@ -585,7 +709,7 @@ impl Timeline {
             }
         }
 
-        Ok(())
+        Ok(payloads.len())
     }
 
     #[profiling::function]
@@ -614,11 +738,17 @@ impl Timeline {
         };
         if new_note_ids.is_empty() {
             return Ok(());
-        } else {
+        }
+
+        self.throughput
+            .record(std::time::Instant::now(), new_note_ids.len());
+
+        let inserted = self.insert(&new_note_ids, ndb, txn, unknown_ids, note_cache, reversed)?;
+        if inserted > 0 {
             self.seen_latest_notes = false;
         }
 
-        self.insert(&new_note_ids, ndb, txn, unknown_ids, note_cache, reversed)
+        Ok(())
     }
 
     /// Invalidate the timeline, forcing a rebuild on the next check.
@@ -767,15 +897,18 @@ pub fn send_initial_timeline_filter(
                 filter
             }).collect();
 
-            update_remote_timeline_subscription(timeline, new_filters, scoped_subs);
+            update_remote_timeline_subscription(ndb, timeline, new_filters, scoped_subs);
         }
 
         // we need some data first
         FilterState::NeedsRemote => match &timeline.kind {
             TimelineKind::List(ListKind::PeopleList(_))
-            | TimelineKind::Algo(AlgoTimeline::LastPerPubkey(ListKind::PeopleList(_))) => {
-                fetch_people_list(ndb, txn, timeline);
+            | TimelineKind::Algo(AlgoTimeline::LastPerPubkey(ListKind::PeopleList(_)))
+            | TimelineKind::Bookmarks(_) => {
+                fetch_list_note(ndb, txn, timeline);
             }
+            // is_timeline_ready fetches the community definition
+            TimelineKind::Community(_) => {}
             _ => fetch_contact_list(timeline, accounts),
         },
     }
@@ -798,23 +931,23 @@ pub fn fetch_contact_list(timeline: &mut Timeline, accounts: &Accounts) {
     timeline.filter = new_filter_state;
 }
 
-pub fn fetch_people_list(ndb: &Ndb, txn: &Transaction, timeline: &mut Timeline) {
+/// Wait for the NIP-51 list note (people list or bookmark set) backing
+/// this timeline to show up locally.
+pub fn fetch_list_note(ndb: &Ndb, txn: &Transaction, timeline: &mut Timeline) {
     if matches!(&timeline.filter, FilterState::Ready(_)) {
         return;
     }
 
-    let Some(plr) = people_list_ref(&timeline.kind) else {
-        error!("fetch_people_list called for non-people-list timeline");
+    let Some(filter) = list_note_filter(&timeline.kind) else {
+        error!("fetch_list_note called for non-list timeline");
         timeline.filter = FilterState::broken(FilterError::EmptyList);
         return;
     };
 
-    let filter = people_list_note_filter(plr);
-
     let results = match ndb.query(txn, std::slice::from_ref(&filter), 1) {
         Ok(results) => results,
         Err(err) => {
-            error!("people list query failed in fetch_people_list: {err}");
+            error!("list query failed in fetch_list_note: {err}");
             timeline.filter = FilterState::broken(FilterError::EmptyList);
             return;
         }
@@ -885,11 +1018,22 @@ pub fn is_timeline_ready(
         if timeline.subscription.dependers(&account_pk) > 0
             && !timeline.subscription.remote_seeded(&account_pk)
         {
-            ensure_remote_timeline_subscription(timeline, account_pk, remote_filters, scoped_subs);
+            ensure_remote_timeline_subscription(
+                ndb,
+                timeline,
+                account_pk,
+                remote_filters,
+                scoped_subs,
+            );
         }
         return true;
     }
 
+    if let TimelineKind::Community(community) = &timeline.kind {
+        let community = community.clone();
+        return community_ready(ndb, scoped_subs, timeline, accounts, &community);
+    }
+
     if !matches!(&timeline.filter, FilterState::GotRemote) {
         return false;
     }
@@ -928,6 +1072,14 @@ pub fn is_timeline_ready(
             info!("found people list note after GotRemote!");
             results[0].note_key
         }
+        TimelineKind::Bookmarks(bsr) => {
+            let bsr = bsr.clone();
+            return bookmarks_ready(ndb, scoped_subs, timeline, accounts, &bsr);
+        }
+        TimelineKind::Live(pk) => {
+            let pk = *pk;
+            return live_ready(ndb, scoped_subs, timeline, &pk);
+        }
         _ => return false,
     };
 
@@ -965,16 +1117,133 @@ pub fn is_timeline_ready(
             info!("Found list note! Setting up remote timeline query");
             timeline.filter = FilterState::ready_hybrid(filter.clone());
 
-            update_remote_timeline_subscription(timeline, filter.remote().to_vec(), scoped_subs);
+            update_remote_timeline_subscription(
+                ndb,
+                timeline,
+                filter.remote().to_vec(),
+                scoped_subs,
+            );
             true
         }
     }
 }
 
-fn people_list_ref(kind: &TimelineKind) -> Option<&PeopleListRef> {
+/// Build the note id filter of a bookmarks timeline once its set is in
+/// the database. Private items are included when we hold the author's key.
+fn bookmarks_ready(
+    ndb: &Ndb,
+    scoped_subs: &mut ScopedSubApi<'_, '_>,
+    timeline: &mut Timeline,
+    accounts: &Accounts,
+    bsr: &BookmarkSetRef,
+) -> bool {
+    let txn = Transaction::new(ndb).expect("txn");
+    let set_filter = bookmark_set_filter(&bsr.author, &bsr.identifier);
+    let set_note = match ndb.query(&txn, std::slice::from_ref(&set_filter), 1) {
+        Ok(results) => results.into_iter().next().map(|r| r.note),
+        Err(err) => {
+            error!("bookmark set query failed in is_timeline_ready: {err}");
+            return false;
+        }
+    };
+
+    let Some(set_note) = set_note else {
+        debug!("bookmark set not yet in ndb for {:?}", bsr);
+        return false;
+    };
+
+    let private = accounts
+        .get_full(&bsr.author)
+        .map(|kp| private_bookmarks(&set_note, kp.secret_key))
+        .unwrap_or_default();
+    let mut ids = create_nip51_set(set_note)
+        .map(|set| set.notes)
+        .unwrap_or_default();
+    for id in private {
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+
+    if ids.is_empty() {
+        timeline.filter = FilterState::broken(FilterError::EmptyList);
+        return false;
+    }
+
+    let filter = vec![Filter::new()
+        .ids(ids.iter().map(|id| id.bytes()))
+        .limit(ids.len() as u64)
+        .build()];
+
+    timeline.filter = FilterState::ready(filter.clone());
+    update_remote_timeline_subscription(ndb, timeline, filter, scoped_subs);
+    true
+}
+
+/// Build a community timeline's filter once its definition is in the
+/// database, asking for the definition until then
+fn community_ready(
+    ndb: &Ndb,
+    scoped_subs: &mut ScopedSubApi<'_, '_>,
+    timeline: &mut Timeline,
+    accounts: &Accounts,
+    community: &CommunityRef,
+) -> bool {
+    let definition = {
+        let txn = Transaction::new(ndb).expect("txn");
+        CommunityDefinition::load(ndb, &txn, community)
+    };
+
+    let Some(definition) = definition else {
+        debug!("community definition not yet in ndb for {:?}", community);
+        let account_pk = *accounts.selected_account_pubkey();
+        ensure_remote_timeline_subscription(
+            ndb,
+            timeline,
+            account_pk,
+            vec![community.address().filter()],
+            scoped_subs,
+        );
+        return false;
+    };
+
+    let filter = community_filter(&definition);
+    timeline.filter = FilterState::ready_hybrid(filter.clone());
+    update_remote_timeline_subscription(ndb, timeline, filter.remote().to_vec(), scoped_subs);
+    true
+}
+
+/// Build a live streams timeline's filter once the contact list it follows
+/// is in the database
+fn live_ready(
+    ndb: &Ndb,
+    scoped_subs: &mut ScopedSubApi<'_, '_>,
+    timeline: &mut Timeline,
+    pk: &Pubkey,
+) -> bool {
+    let filter_state = {
+        let txn = Transaction::new(ndb).expect("txn");
+        live_filter_state(&txn, ndb, pk)
+    };
+
+    let FilterState::Ready(filter) = filter_state else {
+        debug!("contact list not yet in ndb for live timeline of {:?}", pk);
+        return false;
+    };
+
+    info!("Found contact list! Setting up live streams query");
+    timeline.filter = FilterState::ready_hybrid(filter.clone());
+    update_remote_timeline_subscription(ndb, timeline, filter.remote().to_vec(), scoped_subs);
+    true
+}
+
+fn list_note_filter(kind: &TimelineKind) -> Option<Filter> {
     match kind {
         TimelineKind::List(ListKind::PeopleList(plr))
-        | TimelineKind::Algo(AlgoTimeline::LastPerPubkey(ListKind::PeopleList(plr))) => Some(plr),
+        | TimelineKind::Algo(AlgoTimeline::LastPerPubkey(ListKind::PeopleList(plr))) => {
+            Some(people_list_note_filter(plr))
+        }
+        TimelineKind::Bookmarks(bsr) => Some(bookmark_set_filter(&bsr.author, &bsr.identifier)),
         _ => None,
     }
 }
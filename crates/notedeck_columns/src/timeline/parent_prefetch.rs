@@ -0,0 +1,217 @@
+//! Fetching the ancestors of replies while they're on screen, so opening
+//! their thread doesn't have to wait on relays.
+
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use nostrdb::{Filter, Ndb, Note, NoteKey, NoteReply, Transaction};
+use notedeck::{Accounts, RemoteApi, UnknownIds};
+
+/// How long scrolling has to pause before we ask relays, so flinging
+/// through a timeline doesn't request every reply that went past
+const SETTLE_DELAY: Duration = Duration::from_millis(500);
+
+/// Most ids in one request. Anything over waits for another pause.
+const MAX_BATCH: usize = 64;
+
+/// How far up a reply chain we walk looking for a note we don't have
+const MAX_DEPTH: usize = 16;
+
+/// Forget what we already asked for once this many ids piled up
+const MAX_REQUESTED: usize = 4096;
+
+/// Forget which notes we already looked at once this many piled up
+const MAX_CHECKED: usize = 16384;
+
+/// Missing parents and roots of the replies visible in timelines, waiting
+/// to be fetched with a one-shot request
+#[derive(Default)]
+pub struct ParentPrefetch {
+    pending: HashSet<[u8; 32]>,
+    /// Ids we asked relays for already, whether or not they had them
+    requested: HashSet<[u8; 32]>,
+    /// Notes whose ancestors were already looked at. Ancestors further up
+    /// than the ones we fetch for them are left to the thread view.
+    checked: HashSet<NoteKey>,
+    last_queued: Option<Instant>,
+}
+
+impl ParentPrefetch {
+    /// Queue whatever `note`'s thread view would be missing: its root and
+    /// the first ancestor we don't have locally
+    pub fn visible_note(&mut self, ndb: &Ndb, txn: &Transaction, note: &Note<'_>) {
+        if note.kind() != 1 {
+            return;
+        }
+
+        if let Some(key) = note.key() {
+            if self.checked.len() >= MAX_CHECKED {
+                self.checked.clear();
+            }
+            if !self.checked.insert(key) {
+                return;
+            }
+        }
+
+        for id in missing_ancestors(ndb, txn, note) {
+            self.queue(id, Instant::now());
+        }
+    }
+
+    fn queue(&mut self, id: [u8; 32], now: Instant) {
+        if self.requested.contains(&id) || !self.pending.insert(id) {
+            return;
+        }
+
+        self.last_queued = Some(now);
+    }
+
+    /// The next ids to request, once nothing new was queued for a while
+    fn take_batch(&mut self, now: Instant) -> Result<Vec<[u8; 32]>, Duration> {
+        let Some(last_queued) = self.last_queued else {
+            return Ok(Vec::new());
+        };
+
+        let elapsed = now.duration_since(last_queued);
+        if elapsed < SETTLE_DELAY {
+            return Err(SETTLE_DELAY - elapsed);
+        }
+
+        let batch: Vec<[u8; 32]> = self.pending.iter().take(MAX_BATCH).copied().collect();
+        for id in &batch {
+            self.pending.remove(id);
+        }
+
+        // pace the rest so they don't crowd out what the user asked for
+        self.last_queued = (!self.pending.is_empty()).then_some(now);
+
+        if self.requested.len() + batch.len() > MAX_REQUESTED {
+            self.requested.clear();
+        }
+        self.requested.extend(batch.iter().copied());
+
+        Ok(batch)
+    }
+
+    /// Ask the selected account's read relays for a batch of queued
+    /// ancestors. Ids the unknown id fetcher is about to request are left
+    /// to it.
+    pub fn poll(
+        &mut self,
+        remote: &mut RemoteApi<'_>,
+        accounts: &Accounts,
+        unknown_ids: &UnknownIds,
+        ctx: &egui::Context,
+    ) {
+        let batch = match self.take_batch(Instant::now()) {
+            Ok(batch) => batch,
+            Err(wait) => {
+                ctx.request_repaint_after(wait);
+                return;
+            }
+        };
+
+        let ids: Vec<&[u8; 32]> = batch
+            .iter()
+            .filter(|id| {
+                !unknown_ids.ids_iter().any(|unknown| {
+                    unknown
+                        .is_id()
+                        .is_some_and(|note_id| note_id.bytes() == *id)
+                })
+            })
+            .collect();
+
+        if ids.is_empty() {
+            return;
+        }
+
+        tracing::debug!("prefetching {} thread ancestors", ids.len());
+        let filter = Filter::new()
+            .ids(ids.iter().copied())
+            .limit(ids.len() as u64)
+            .build();
+        remote.oneshot(accounts).oneshot(vec![filter]);
+
+        if self.last_queued.is_some() {
+            ctx.request_repaint_after(SETTLE_DELAY);
+        }
+    }
+}
+
+/// The root of `note`'s thread if we don't have it, and the closest
+/// ancestor up its reply chain that we don't have
+fn missing_ancestors(ndb: &Ndb, txn: &Transaction, note: &Note<'_>) -> Vec<[u8; 32]> {
+    let reply = NoteReply::new(note.tags());
+    let mut missing = Vec::new();
+
+    if let Some(root) = reply.root() {
+        if ndb.get_note_by_id(txn, root.id).is_err() {
+            missing.push(*root.id);
+        }
+    }
+
+    let mut parent = reply.reply().or(reply.root()).map(|parent| *parent.id);
+    for _ in 0..MAX_DEPTH {
+        let Some(id) = parent else {
+            break;
+        };
+
+        match ndb.get_note_by_id(txn, &id) {
+            Ok(ancestor) => {
+                let reply = NoteReply::new(ancestor.tags());
+                parent = reply.reply().or(reply.root()).map(|parent| *parent.id);
+            }
+            Err(_) => {
+                if !missing.contains(&id) {
+                    missing.push(id);
+                }
+                break;
+            }
+        }
+    }
+
+    missing
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batches_wait_for_scrolling_to_settle() {
+        let mut prefetch = ParentPrefetch::default();
+        let start = Instant::now();
+
+        prefetch.queue([1; 32], start);
+        prefetch.queue([2; 32], start);
+        prefetch.queue([1; 32], start);
+
+        assert!(prefetch.take_batch(start).is_err());
+        let mut batch = prefetch.take_batch(start + SETTLE_DELAY).expect("settled");
+        batch.sort_unstable();
+        assert_eq!(batch, vec![[1; 32], [2; 32]]);
+
+        // already asked for, so scrolling past it again is a no-op
+        prefetch.queue([2; 32], start + SETTLE_DELAY);
+        assert_eq!(prefetch.take_batch(start + SETTLE_DELAY * 2), Ok(vec![]));
+    }
+
+    #[test]
+    fn large_backlogs_go_out_in_paced_batches() {
+        let mut prefetch = ParentPrefetch::default();
+        let start = Instant::now();
+
+        for i in 0..(MAX_BATCH + 1) {
+            prefetch.queue([i as u8; 32], start);
+        }
+
+        let settled = start + SETTLE_DELAY;
+        assert_eq!(prefetch.take_batch(settled).map(|b| b.len()), Ok(MAX_BATCH));
+        assert!(prefetch.take_batch(settled).is_err());
+        assert_eq!(
+            prefetch.take_batch(settled + SETTLE_DELAY).map(|b| b.len()),
+            Ok(1)
+        );
+    }
+}
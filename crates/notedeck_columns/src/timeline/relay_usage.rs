@@ -0,0 +1,96 @@
+use std::collections::{HashMap, HashSet};
+
+use enostr::NormRelayUrl;
+use nostrdb::{Ndb, Transaction};
+
+use crate::timeline::TimelineUnits;
+
+/// How many of a column's loaded notes each relay delivered, according to
+/// nostrdb's seen-on tracking. Read relays that delivered nothing are kept
+/// with a count of zero so they stand out as candidates for removal.
+#[derive(Debug, Default)]
+pub struct RelayUsage {
+    pub notes: usize,
+    /// Sorted by most notes delivered first
+    pub relays: Vec<(NormRelayUrl, usize)>,
+}
+
+impl RelayUsage {
+    pub fn new(
+        ndb: &Ndb,
+        txn: &Transaction,
+        units: &TimelineUnits,
+        read_relays: HashSet<NormRelayUrl>,
+    ) -> Self {
+        let notes = units.units.values().filter_map(|unit| {
+            let key = unit.get_underlying_noteref().key;
+            ndb.get_note_by_key(txn, key).ok()
+        });
+
+        Self::tally(
+            notes.map(|note| note.relays(txn).collect::<Vec<_>>()),
+            read_relays,
+        )
+    }
+
+    fn tally<'a, N>(notes: impl Iterator<Item = N>, read_relays: HashSet<NormRelayUrl>) -> Self
+    where
+        N: IntoIterator<Item = &'a str>,
+    {
+        let mut counts: HashMap<NormRelayUrl, usize> =
+            read_relays.into_iter().map(|relay| (relay, 0)).collect();
+        let mut total = 0;
+
+        for seen in notes {
+            total += 1;
+
+            // the same relay can be recorded under slightly different urls
+            let seen: HashSet<NormRelayUrl> = seen
+                .into_iter()
+                .filter_map(|url| NormRelayUrl::new(url).ok())
+                .collect();
+            for relay in seen {
+                *counts.entry(relay).or_default() += 1;
+            }
+        }
+
+        let mut relays: Vec<(NormRelayUrl, usize)> = counts.into_iter().collect();
+        relays.sort_by(|(a_url, a), (b_url, b)| b.cmp(a).then_with(|| a_url.cmp(b_url)));
+
+        Self {
+            notes: total,
+            relays,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(s: &str) -> NormRelayUrl {
+        NormRelayUrl::new(s).unwrap()
+    }
+
+    #[test]
+    fn counts_each_relay_once_per_note() {
+        let notes = vec![
+            vec!["wss://relay.damus.io", "wss://relay.damus.io/"],
+            vec!["wss://nos.lol"],
+            vec!["wss://relay.damus.io", "wss://nos.lol"],
+        ];
+        let read = HashSet::from([url("wss://relay.damus.io"), url("wss://quiet.example")]);
+
+        let usage = RelayUsage::tally(notes.into_iter(), read);
+
+        assert_eq!(usage.notes, 3);
+        assert_eq!(
+            usage.relays,
+            vec![
+                (url("wss://nos.lol"), 2),
+                (url("wss://relay.damus.io"), 2),
+                (url("wss://quiet.example"), 0),
+            ]
+        );
+    }
+}
@@ -2,7 +2,7 @@ use crate::{
     nav::RenderNavAction,
     profile::ProfileAction,
     timeline::{thread::Threads, ThreadSelection, TimelineCache, TimelineKind},
-    ui::{self, ProfileView},
+    ui::{self, community::CommunityView, group::GroupView, live::LiveView, ProfileView},
 };
 
 use enostr::Pubkey;
@@ -19,6 +19,7 @@ pub fn render_timeline_route(
     ui: &mut egui::Ui,
     note_context: &mut NoteContext,
     scroll_to_top: bool,
+    notifications_muted: bool,
 ) -> DragResponse<RenderNavAction> {
     match kind {
         TimelineKind::List(_)
@@ -27,6 +28,8 @@ pub fn render_timeline_route(
         | TimelineKind::Notifications(_)
         | TimelineKind::Universe
         | TimelineKind::Hashtag(_)
+        | TimelineKind::Bookmarks(_)
+        | TimelineKind::Address(_)
         | TimelineKind::Generic(_) => {
             let resp =
                 ui::TimelineView::new(kind, timeline_cache, note_context, note_options, col).ui(ui);
@@ -34,9 +37,61 @@ pub fn render_timeline_route(
             resp.map_output(RenderNavAction::NoteAction)
         }
 
+        TimelineKind::Group(group) => {
+            let group_action = GroupView::new(
+                group,
+                note_context.ndb,
+                note_context.accounts,
+                note_context.i18n,
+            )
+            .ui(ui);
+
+            let mut resp =
+                ui::TimelineView::new(kind, timeline_cache, note_context, note_options, col)
+                    .ui(ui)
+                    .map_output(RenderNavAction::NoteAction);
+            if let Some(action) = group_action {
+                resp.set_output(RenderNavAction::GroupAction(action));
+            }
+            resp
+        }
+
+        TimelineKind::Community(community) => {
+            let community_action = CommunityView::new(
+                community,
+                note_context.ndb,
+                note_context.accounts,
+                note_context.i18n,
+            )
+            .ui(ui);
+
+            let mut resp =
+                ui::TimelineView::new(kind, timeline_cache, note_context, note_options, col)
+                    .ui(ui)
+                    .map_output(RenderNavAction::NoteAction);
+            if let Some(action) = community_action {
+                resp.set_output(RenderNavAction::CommunityAction(action));
+            }
+            resp
+        }
+
+        TimelineKind::Live(_pk) => {
+            LiveView::new(kind, timeline_cache, note_context, note_options, col)
+                .ui(ui)
+                .map_output(RenderNavAction::NoteAction)
+        }
+
         TimelineKind::Profile(pubkey) => {
             if depth > 1 {
-                render_profile_route(pubkey, timeline_cache, col, ui, note_options, note_context)
+                render_profile_route(
+                    pubkey,
+                    timeline_cache,
+                    col,
+                    ui,
+                    note_options,
+                    note_context,
+                    notifications_muted,
+                )
             } else {
                 // we render profiles like timelines if they are at the root
                 let resp =
@@ -85,9 +140,11 @@ pub fn render_profile_route(
     ui: &mut egui::Ui,
     note_options: NoteOptions,
     note_context: &mut NoteContext,
+    notifications_muted: bool,
 ) -> DragResponse<RenderNavAction> {
-    let profile_view =
-        ProfileView::new(pubkey, col, timeline_cache, note_options, note_context).ui(ui);
+    let profile_view = ProfileView::new(pubkey, col, timeline_cache, note_options, note_context)
+        .notifications_muted(notifications_muted)
+        .ui(ui);
 
     profile_view.map_output_maybe(|action| match action {
         ui::profile::ProfileViewAction::EditProfile => note_context
@@ -3,7 +3,7 @@ use nostrdb::{Filter, Ndb, Subscription};
 mod thread_sub;
 mod timeline_sub;
 
-pub use thread_sub::ThreadSubs;
+pub use thread_sub::{ShownNotes, ThreadSubs};
 pub use timeline_sub::TimelineSub;
 
 pub fn ndb_sub(ndb: &Ndb, filter: &[Filter], id: impl std::fmt::Debug) -> Option<Subscription> {
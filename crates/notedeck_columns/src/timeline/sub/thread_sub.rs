@@ -4,6 +4,7 @@ use hashbrown::HashMap;
 use nostrdb::{Ndb, Subscription};
 use notedeck::{
     Accounts, RelaySelection, ScopedSubApi, ScopedSubIdentity, SubConfig, SubKey, SubOwnerKey,
+    DELETION_KIND, POLL_RESPONSE_KIND,
 };
 
 use crate::scoped_sub_owner_keys::thread_scope_owner_key;
@@ -14,6 +15,9 @@ use crate::timeline::{
 
 type RootNoteId = NoteId;
 
+/// Most notes in one thread whose reactions we follow
+const MAX_REACTION_TARGETS: usize = 256;
+
 // column id
 type MetaId = usize;
 
@@ -43,9 +47,27 @@ struct AccountThreadSubs {
     scopes: HashMap<MetaId, Vec<Scope>>,
 }
 
+/// What a thread view showed when its reaction targets were last gathered.
+/// They are only gathered again once this changes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ShownNotes {
+    pub selected: [u8; 32],
+    pub parents: usize,
+    pub replies: usize,
+    pub expanded: usize,
+    /// Bumped when replies or receipts arrive for a shown reply
+    pub generation: u64,
+}
+
 struct Scope {
     root_id: NoteId,
     stack: Vec<Sub>,
+    /// What the view showed when `reaction_targets` were gathered
+    reactions_shown: Option<ShownNotes>,
+    /// Sorted ids of the notes in view whose reactions are subscribed to
+    reaction_targets: Vec<[u8; 32]>,
+    /// Sorted ids of the expanded notes whose replies are subscribed to
+    subtree_targets: Vec<[u8; 32]>,
 }
 
 struct Sub {
@@ -156,6 +178,113 @@ impl ThreadSubs {
         }
     }
 
+    /// Follow reactions to the notes currently shown in the thread scope,
+    /// votes on the polls among them, zaps and requests to delete them.
+    /// `targets` is in priority order, only the first
+    /// [`MAX_REACTION_TARGETS`] are followed. The remote sub belongs to the
+    /// scope's owner, so it goes away with the scope.
+    /// Follow reactions to `targets` in the scope of `thread`. `targets` is
+    /// only called when what the view shows changed since the last call.
+    pub fn watch_reactions(
+        &mut self,
+        scoped_subs: &mut ScopedSubApi<'_, '_>,
+        meta_id: usize,
+        thread: &ThreadSelection,
+        shown: ShownNotes,
+        targets: impl FnOnce() -> Vec<[u8; 32]>,
+    ) {
+        let account_pk = scoped_subs.selected_account_pubkey();
+        let Some(scopes) = self
+            .by_account
+            .get_mut(&account_pk)
+            .and_then(|subs| subs.scopes.get_mut(&meta_id))
+        else {
+            return;
+        };
+
+        let Some((scope_depth, scope)) = thread_scope(scopes, thread) else {
+            return;
+        };
+
+        if scope.reactions_shown == Some(shown) {
+            return;
+        }
+        scope.reactions_shown = Some(shown);
+
+        let targets = reaction_targets(targets());
+        if targets == scope.reaction_targets {
+            return;
+        }
+
+        let owner = thread_scope_owner_key(account_pk, meta_id, &scope.root_id, scope_depth);
+        let identity = ScopedSubIdentity::account(owner, thread_reactions_sub_key(owner));
+        let config = SubConfig {
+            relays: RelaySelection::AccountsRead,
+            filters: vec![
+                Filter::new().kinds([7]).events(targets.iter()).build(),
+                Filter::new()
+                    .kinds([POLL_RESPONSE_KIND as u64])
+                    .events(targets.iter())
+                    .build(),
+                Filter::new()
+                    .kinds([DELETION_KIND as u64])
+                    .events(targets.iter())
+                    .build(),
+                // receipts, for sorting replies by how much they were zapped
+                Filter::new().kinds([9735]).events(targets.iter()).build(),
+            ],
+            use_transparent: false,
+        };
+        let _ = scoped_subs.set_sub(identity, config);
+
+        scope.reaction_targets = targets;
+    }
+
+    /// Follow replies to the notes expanded in the thread scope, so
+    /// subtrees show up even when their replies don't tag the root. Like
+    /// reactions, the remote sub goes away with the scope.
+    pub fn watch_subtrees(
+        &mut self,
+        scoped_subs: &mut ScopedSubApi<'_, '_>,
+        meta_id: usize,
+        thread: &ThreadSelection,
+        mut targets: Vec<[u8; 32]>,
+    ) {
+        let account_pk = scoped_subs.selected_account_pubkey();
+        let Some(scopes) = self
+            .by_account
+            .get_mut(&account_pk)
+            .and_then(|subs| subs.scopes.get_mut(&meta_id))
+        else {
+            return;
+        };
+
+        let Some((scope_depth, scope)) = thread_scope(scopes, thread) else {
+            return;
+        };
+
+        targets.sort_unstable();
+        targets.dedup();
+        if targets == scope.subtree_targets {
+            return;
+        }
+
+        let owner = thread_scope_owner_key(account_pk, meta_id, &scope.root_id, scope_depth);
+        let identity = ScopedSubIdentity::account(owner, thread_subtrees_sub_key(owner));
+        if targets.is_empty() {
+            let _ = scoped_subs.clear_sub(identity);
+        } else {
+            let config = SubConfig {
+                relays: RelaySelection::AccountsRead,
+                filters: vec![Filter::new().kinds([1]).events(targets.iter()).build()],
+                use_transparent: false,
+            };
+            let _ = scoped_subs.set_sub(identity, config);
+        }
+
+        scope.subtree_targets = targets;
+    }
+
     pub fn get_local(&self, account_pk: &Pubkey, meta_id: usize) -> Option<&Subscription> {
         self.by_account
             .get(account_pk)?
@@ -231,6 +360,18 @@ fn unsubscribe_click(
     Some(UnsubscribeOutcome::DropOwner(scope.root_id))
 }
 
+/// The innermost scope of the column showing `thread`'s root, with its
+/// depth in the column
+fn thread_scope<'a>(
+    scopes: &'a mut [Scope],
+    thread: &ThreadSelection,
+) -> Option<(usize, &'a mut Scope)> {
+    let depth = scopes
+        .iter()
+        .rposition(|scope| scope.root_id.bytes() == thread.root_id.bytes())?;
+    Some((depth, &mut scopes[depth]))
+}
+
 fn log_scope_root_mismatch(scope: &Scope, id: &ThreadSelection) {
     if scope.root_id.bytes() != id.root_id.bytes() {
         tracing::error!(
@@ -244,6 +385,8 @@ fn log_scope_root_mismatch(scope: &Scope, id: &ThreadSelection) {
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 enum ThreadScopedSub {
     RepliesByRoot,
+    ReactionsByOwner,
+    SubtreesByOwner,
 }
 
 fn thread_remote_sub_key(root_id: &RootNoteId) -> SubKey {
@@ -252,6 +395,33 @@ fn thread_remote_sub_key(root_id: &RootNoteId) -> SubKey {
         .finish()
 }
 
+/// Reactions follow what one thread view shows, so they aren't shared
+/// between views of the same root
+fn thread_reactions_sub_key(owner: SubOwnerKey) -> SubKey {
+    SubKey::builder(ThreadScopedSub::ReactionsByOwner)
+        .with(owner.as_u64())
+        .finish()
+}
+
+fn thread_subtrees_sub_key(owner: SubOwnerKey) -> SubKey {
+    SubKey::builder(ThreadScopedSub::SubtreesByOwner)
+        .with(owner.as_u64())
+        .finish()
+}
+
+/// Drop duplicates and anything past the cap, keeping the earliest ids,
+/// then sort so the set can be compared with the one we subscribed to
+fn reaction_targets(targets: Vec<[u8; 32]>) -> Vec<[u8; 32]> {
+    let mut seen = hashbrown::HashSet::new();
+    let mut targets: Vec<[u8; 32]> = targets
+        .into_iter()
+        .filter(|id| seen.insert(*id))
+        .take(MAX_REACTION_TARGETS)
+        .collect();
+    targets.sort_unstable();
+    targets
+}
+
 fn sub_current_scope(
     ndb: &mut Ndb,
     selection: &ThreadSelection,
@@ -331,7 +501,31 @@ fn local_sub_new_scope(
             sub,
             _filters: local_sub_filter,
         }],
+        reactions_shown: None,
+        reaction_targets: Vec::new(),
+        subtree_targets: Vec::new(),
     });
 
     true
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reaction_targets_keep_the_first_ids() {
+        let mut ids: Vec<[u8; 32]> = vec![[9; 32], [1; 32], [9; 32]];
+        ids.extend((0..MAX_REACTION_TARGETS as u16).map(|i| {
+            let mut id = [0xff; 32];
+            id[..2].copy_from_slice(&i.to_be_bytes());
+            id
+        }));
+
+        let targets = reaction_targets(ids);
+        assert_eq!(targets.len(), MAX_REACTION_TARGETS);
+        assert!(targets.contains(&[9; 32]));
+        assert!(targets.contains(&[1; 32]));
+        assert!(targets.windows(2).all(|w| w[0] < w[1]));
+    }
+}
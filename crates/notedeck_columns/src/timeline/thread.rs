@@ -1,15 +1,17 @@
 use egui_nav::ReturnType;
 use egui_virtual_list::VirtualList;
-use enostr::NoteId;
-use hashbrown::{hash_map::RawEntryMut, HashMap};
-use nostrdb::{Filter, Ndb, Note, NoteKey, NoteReplyBuf, Transaction};
-use notedeck::{Accounts, NoteCache, NoteRef, ScopedSubApi, UnknownIds};
+use enostr::{NoteId, Pubkey};
+use hashbrown::{hash_map::RawEntryMut, HashMap, HashSet};
+use nostrdb::{Filter, Ndb, Note, NoteKey, NoteReplyBuf, Subscription, Transaction};
+use notedeck::{
+    note_zap_total_msats, Accounts, NoteCache, NoteRef, ScopedSubApi, UnknownIds, Zaps,
+};
 
 use crate::{
     actionbar::{process_thread_notes, NewThreadNotes},
     timeline::{
         note_units::{NoteUnits, UnitKey},
-        sub::ThreadSubs,
+        sub::{ndb_sub, ShownNotes, ThreadSubs},
         unit::NoteUnit,
         InsertionResponse,
     },
@@ -23,6 +25,141 @@ pub struct ThreadNode {
     pub have_all_ancestors: bool,
     pub list: VirtualList,
     pub set_scroll_offset: Option<f32>,
+    pub participant_filter: ParticipantFilter,
+    /// Replies whose own replies are shown inline below them
+    pub expanded: HashSet<NoteId>,
+    /// Replies folded down to a single line
+    pub collapsed: HashSet<NoteId>,
+    pub reply_sort: ReplySort,
+    pub stats: ReplyStats,
+}
+
+/// Reply counts and zap totals of the notes shown in a thread, so sorting
+/// and drawing don't query them every frame. Entries are dropped by
+/// [`Threads::update`] when replies or receipts for the note arrive.
+#[derive(Default)]
+pub struct ReplyStats {
+    replies: HashMap<NoteKey, usize>,
+    zaps: HashMap<NoteKey, u64>,
+}
+
+impl ReplyStats {
+    pub fn reply_count(&mut self, ndb: &Ndb, txn: &Transaction, note: &Note<'_>) -> usize {
+        let Some(key) = note.key() else {
+            return count_direct_replies(ndb, txn, note.id());
+        };
+
+        *self
+            .replies
+            .entry(key)
+            .or_insert_with(|| count_direct_replies(ndb, txn, note.id()))
+    }
+
+    /// Zero until the author's zapper is known, see [`Zaps::zapper_for`]
+    pub fn zap_total_msats(
+        &mut self,
+        ndb: &Ndb,
+        txn: &Transaction,
+        zaps: &mut Zaps,
+        note: &Note<'_>,
+    ) -> u64 {
+        let Some(zapper) = zaps.zapper_for(ndb, txn, &Pubkey::new(*note.pubkey())) else {
+            return 0;
+        };
+
+        let Some(key) = note.key() else {
+            return note_zap_total_msats(ndb, txn, note.id(), &zapper);
+        };
+
+        *self
+            .zaps
+            .entry(key)
+            .or_insert_with(|| note_zap_total_msats(ndb, txn, note.id(), &zapper))
+    }
+
+    /// Returns whether anything was cached for `key`
+    fn invalidate(&mut self, key: &NoteKey) -> bool {
+        let replies = self.replies.remove(key).is_some();
+        let zaps = self.zaps.remove(key).is_some();
+        replies || zaps
+    }
+}
+
+/// Most replies shown under an expanded note
+pub const MAX_SUBTREE_REPLIES: i32 = 100;
+
+/// Narrows the replies shown in a thread to some of its participants. The
+/// parent chain and the selected note are always shown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ParticipantFilter {
+    #[default]
+    All,
+    /// Replies by the author of the thread root
+    Op,
+    /// Replies by people the selected account follows
+    Following,
+    Author(Pubkey),
+}
+
+impl ParticipantFilter {
+    pub fn matches(
+        &self,
+        author: &[u8; 32],
+        op: Option<&[u8; 32]>,
+        is_following: impl Fn(&[u8; 32]) -> bool,
+    ) -> bool {
+        match self {
+            ParticipantFilter::All => true,
+            ParticipantFilter::Op => op == Some(author),
+            ParticipantFilter::Following => is_following(author),
+            ParticipantFilter::Author(pubkey) => pubkey.bytes() == author,
+        }
+    }
+}
+
+/// Order of the replies in a thread, at every level
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReplySort {
+    /// In the order they were posted, following
+    /// [`notedeck_ui::NoteOptions::RepliesNewestFirst`]
+    #[default]
+    Chronological,
+    /// Replies with the most replies of their own first
+    MostReplied,
+    /// Replies zapped the most first
+    MostZapped,
+}
+
+impl ReplySort {
+    /// Sort `replies`, which are siblings. Ties are broken oldest first.
+    pub fn sort(
+        &self,
+        replies: &mut [Note<'_>],
+        newest_first: bool,
+        stats: &mut ReplyStats,
+        zaps: &mut Zaps,
+        ndb: &Ndb,
+        txn: &Transaction,
+    ) {
+        match self {
+            ReplySort::Chronological if newest_first => {
+                replies.sort_by_key(|note| std::cmp::Reverse(note.created_at()))
+            }
+            ReplySort::Chronological => replies.sort_by_key(|note| note.created_at()),
+            ReplySort::MostReplied => replies.sort_by_cached_key(|note| {
+                (
+                    std::cmp::Reverse(stats.reply_count(ndb, txn, note)),
+                    note.created_at(),
+                )
+            }),
+            ReplySort::MostZapped => replies.sort_by_cached_key(|note| {
+                (
+                    std::cmp::Reverse(stats.zap_total_msats(ndb, txn, zaps, note)),
+                    note.created_at(),
+                )
+            }),
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -40,6 +177,11 @@ impl ThreadNode {
             have_all_ancestors: false,
             list: VirtualList::new(),
             set_scroll_offset: None,
+            participant_filter: ParticipantFilter::default(),
+            expanded: HashSet::new(),
+            collapsed: HashSet::new(),
+            reply_sort: ReplySort::default(),
+            stats: ReplyStats::default(),
         }
     }
 
@@ -55,6 +197,12 @@ pub struct Threads {
     pub subs: ThreadSubs,
 
     pub seen_flags: NoteSeenFlags,
+
+    /// Local sub for replies and zap receipts, to keep
+    /// [`ThreadNode::stats`] fresh
+    stats_sub: Option<Subscription>,
+    /// Bumped when replies or receipts arrive for a shown reply
+    stats_generation: u64,
 }
 
 impl Threads {
@@ -142,6 +290,81 @@ impl Threads {
             .unsubscribe(ndb, scoped_subs, id, thread, return_type);
     }
 
+    /// Keep reactions to the shown notes live: the root, the selected note,
+    /// its parents, its direct replies and the replies in expanded subtrees
+    pub fn watch_reactions(
+        &mut self,
+        ndb: &Ndb,
+        txn: &Transaction,
+        scoped_subs: &mut ScopedSubApi<'_, '_>,
+        col: usize,
+        thread: &ThreadSelection,
+    ) {
+        let selected = thread.selected_or_root();
+        let Some(node) = self.threads.get(&selected) else {
+            return;
+        };
+
+        let mut parents = Vec::new();
+        let mut parent = node.prev.clone();
+        while let ParentState::Parent(id) = parent {
+            parents.push(*id.bytes());
+            parent = self
+                .threads
+                .get(&id.bytes())
+                .map(|node| node.prev.clone())
+                .unwrap_or(ParentState::Unknown);
+        }
+
+        let shown = ShownNotes {
+            selected: *selected,
+            parents: parents.len(),
+            replies: node.replies.len(),
+            expanded: node.expanded.len(),
+            generation: self.stats_generation,
+        };
+
+        self.subs
+            .watch_reactions(scoped_subs, col, thread, shown, || {
+                let mut targets = vec![*thread.root_id.bytes(), *selected];
+                targets.extend(parents);
+                targets.extend(
+                    node.replies
+                        .values()
+                        .filter_map(|note_ref| ndb.get_note_by_key(txn, note_ref.key).ok())
+                        .map(|note| *note.id()),
+                );
+
+                for id in &node.expanded {
+                    targets.extend(
+                        direct_replies(ndb, txn, id.bytes())
+                            .iter()
+                            .map(|note| *note.id()),
+                    );
+                }
+
+                targets
+            });
+    }
+
+    /// Ask relays for replies to the notes expanded in the selected note's
+    /// view. Deep replies usually come with the thread's root, but not all
+    /// clients tag it.
+    pub fn watch_subtrees(
+        &mut self,
+        scoped_subs: &mut ScopedSubApi<'_, '_>,
+        col: usize,
+        thread: &ThreadSelection,
+    ) {
+        let targets = self
+            .threads
+            .get(&thread.selected_or_root())
+            .map(|node| node.expanded.iter().map(|id| *id.bytes()).collect())
+            .unwrap_or_default();
+
+        self.subs.watch_subtrees(scoped_subs, col, thread, targets);
+    }
+
     /// Responsible for making sure the chain and the direct replies are up to date
     #[allow(clippy::too_many_arguments)]
     #[profiling::function]
@@ -165,6 +388,8 @@ impl Threads {
             .reply;
 
         self.fill_reply_chain_recursive(selected, &reply, note_cache, ndb, txn, unknown_ids);
+        self.invalidate_stats(ndb, txn);
+
         let node = self
             .threads
             .get_mut(&selected.id())
@@ -194,6 +419,52 @@ impl Threads {
         );
     }
 
+    /// Drop the cached stats of every note that got new replies or zap
+    /// receipts since the last call
+    fn invalidate_stats(&mut self, ndb: &Ndb, txn: &Transaction) {
+        if self.stats_sub.is_none() {
+            let filter = Filter::new().kinds([1, 9735]).build();
+            self.stats_sub = ndb_sub(ndb, &[filter], "thread reply stats");
+        }
+        let Some(sub) = self.stats_sub else {
+            return;
+        };
+
+        let keys = ndb.poll_for_notes(sub, 100);
+        if keys.is_empty() {
+            return;
+        }
+
+        let mut targets = HashSet::new();
+        for key in keys {
+            let Ok(note) = ndb.get_note_by_key(txn, key) else {
+                continue;
+            };
+            for tag in note.tags() {
+                if tag.count() < 2 || tag.get_str(0) != Some("e") {
+                    continue;
+                }
+                let Some(target) = tag.get_id(1) else {
+                    continue;
+                };
+                if let Ok(target) = ndb.get_notekey_by_id(txn, target) {
+                    targets.insert(target);
+                }
+            }
+        }
+
+        let mut changed = false;
+        for node in self.threads.values_mut() {
+            for target in &targets {
+                changed |= node.stats.invalidate(target);
+            }
+        }
+
+        if changed {
+            self.stats_generation += 1;
+        }
+    }
+
     fn fill_reply_chain_recursive(
         &mut self,
         cur_note: &Note<'_>,
@@ -323,6 +594,53 @@ pub fn selected_has_at_least_n_replies(
     res.len() >= n.into()
 }
 
+/// Whether `note` replies directly to `parent_id`. Only the NIP-10 reply
+/// (or, for direct replies to the root, root) reference counts, so notes
+/// merely mentioning the parent are left out.
+pub fn is_direct_reply(note: &Note<'_>, parent_id: &[u8; 32]) -> bool {
+    let reply = nostrdb::NoteReply::new(note.tags());
+    reply
+        .reply()
+        .or_else(|| reply.reply_to_root())
+        .is_some_and(|r| r.id == parent_id)
+}
+
+/// Direct replies to `parent_id`, whether or not they tag the thread root
+fn direct_replies_filter(parent_id: &[u8; 32]) -> nostrdb::Filter {
+    let moved_parent_id = *parent_id;
+    nostrdb::Filter::new()
+        .kinds([1])
+        .custom(move |note: nostrdb::Note<'_>| is_direct_reply(&note, &moved_parent_id))
+        .event(parent_id)
+        .build()
+}
+
+/// The direct replies to `parent_id` we have locally, oldest first
+pub fn direct_replies<'a>(ndb: &Ndb, txn: &'a Transaction, parent_id: &[u8; 32]) -> Vec<Note<'a>> {
+    let Ok(results) = ndb.query(
+        txn,
+        &[direct_replies_filter(parent_id)],
+        MAX_SUBTREE_REPLIES,
+    ) else {
+        return Vec::new();
+    };
+
+    let mut replies: Vec<Note<'a>> = results.into_iter().map(|r| r.note).collect();
+    replies.sort_by_key(|note| note.created_at());
+    replies
+}
+
+/// How many direct replies to `parent_id` we have locally, counting up to
+/// [`MAX_SUBTREE_REPLIES`]
+pub fn count_direct_replies(ndb: &Ndb, txn: &Transaction, parent_id: &[u8; 32]) -> usize {
+    ndb.query(
+        txn,
+        &[direct_replies_filter(parent_id)],
+        MAX_SUBTREE_REPLIES,
+    )
+    .map_or(0, |results| results.len())
+}
+
 fn direct_replies_filter_non_root(
     selected_note_id: &[u8; 32],
     root_id: &[u8; 32],
@@ -433,4 +751,76 @@ impl SingleNoteUnits {
     pub fn contains_key(&self, k: &NoteKey) -> bool {
         self.units.contains_key(&UnitKey::Single(*k))
     }
+    pub fn len(&self) -> usize {
+        self.units.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.units.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use enostr::FullKeypair;
+    use nostrdb::NoteBuilder;
+
+    /// A kind 1 note with marked `e` tags
+    fn reply(refs: &[(&[u8; 32], &str)]) -> Note<'static> {
+        let mut builder = NoteBuilder::new().kind(1).content("reply");
+        for (id, marker) in refs {
+            builder = builder
+                .start_tag()
+                .tag_str("e")
+                .tag_id(id)
+                .tag_str("")
+                .tag_str(marker);
+        }
+        builder
+            .sign(&FullKeypair::generate().secret_key.secret_bytes())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn direct_replies_follow_markers() {
+        let root = [1; 32];
+        let parent = [2; 32];
+        let other = [3; 32];
+
+        let to_parent = reply(&[(&root, "root"), (&parent, "reply")]);
+        assert!(is_direct_reply(&to_parent, &parent));
+        assert!(!is_direct_reply(&to_parent, &root));
+
+        let to_root = reply(&[(&root, "root")]);
+        assert!(is_direct_reply(&to_root, &root));
+
+        let mentions_parent = reply(&[(&root, "root"), (&other, "reply"), (&parent, "mention")]);
+        assert!(!is_direct_reply(&mentions_parent, &parent));
+        assert!(is_direct_reply(&mentions_parent, &other));
+    }
+
+    #[test]
+    fn participant_filter_matches() {
+        let op = [1; 32];
+        let friend = [2; 32];
+        let stranger = [3; 32];
+        let is_following = |pk: &[u8; 32]| *pk == friend;
+
+        for author in [&op, &friend, &stranger] {
+            assert!(ParticipantFilter::All.matches(author, Some(&op), is_following));
+        }
+
+        assert!(ParticipantFilter::Op.matches(&op, Some(&op), is_following));
+        assert!(!ParticipantFilter::Op.matches(&friend, Some(&op), is_following));
+        assert!(!ParticipantFilter::Op.matches(&op, None, is_following));
+
+        assert!(ParticipantFilter::Following.matches(&friend, Some(&op), is_following));
+        assert!(!ParticipantFilter::Following.matches(&stranger, Some(&op), is_following));
+
+        let author = ParticipantFilter::Author(Pubkey::new(stranger));
+        assert!(author.matches(&stranger, Some(&op), is_following));
+        assert!(!author.matches(&op, Some(&op), is_following));
+    }
 }
@@ -0,0 +1,65 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// How far back note arrivals are counted.
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// Rolling count of notes polled into a timeline, shown by the developer
+/// column inspector.
+#[derive(Debug, Default)]
+pub struct NoteThroughput {
+    samples: VecDeque<(Instant, usize)>,
+}
+
+impl NoteThroughput {
+    pub fn record(&mut self, now: Instant, count: usize) {
+        if count == 0 {
+            return;
+        }
+
+        self.expire(now);
+        self.samples.push_back((now, count));
+    }
+
+    /// Notes received during the last minute.
+    pub fn per_minute(&self, now: Instant) -> usize {
+        self.samples
+            .iter()
+            .filter(|(at, _)| now.saturating_duration_since(*at) <= WINDOW)
+            .map(|(_, count)| count)
+            .sum()
+    }
+
+    fn expire(&mut self, now: Instant) {
+        while self
+            .samples
+            .front()
+            .is_some_and(|(at, _)| now.saturating_duration_since(*at) > WINDOW)
+        {
+            self.samples.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_only_the_last_minute() {
+        let start = Instant::now();
+        let mut throughput = NoteThroughput::default();
+
+        throughput.record(start, 3);
+        throughput.record(start + Duration::from_secs(30), 2);
+        throughput.record(start + Duration::from_secs(40), 0);
+        assert_eq!(throughput.per_minute(start + Duration::from_secs(30)), 5);
+
+        let later = start + Duration::from_secs(75);
+        assert_eq!(throughput.per_minute(later), 2);
+
+        throughput.record(later, 1);
+        assert_eq!(throughput.samples.len(), 2);
+        assert_eq!(throughput.per_minute(later), 3);
+    }
+}
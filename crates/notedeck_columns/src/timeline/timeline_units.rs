@@ -1,10 +1,11 @@
 use std::collections::HashSet;
 
 use enostr::Pubkey;
-use nostrdb::{Ndb, Note, NoteKey, Transaction};
-use notedeck::NoteRef;
+use nostrdb::{IngestMetadata, Ndb, Note, NoteKey, Transaction};
+use notedeck::{NoteRef, POLL_KIND};
 use notedeck_ui::note::get_reposted_note;
 
+use crate::community::{approved_post_id, KIND_COMMUNITY_APPROVAL};
 use crate::timeline::{
     note_units::{InsertManyResponse, NoteUnits},
     unit::{
@@ -118,7 +119,8 @@ fn to_fragment<'a>(
     txn: &Transaction,
 ) -> Option<NoteUnitFragmentResponse<'a>> {
     match payload.note.kind() {
-        1 => Some(NoteUnitFragmentResponse {
+        // 9 is a NIP-29 group chat message
+        1 | 9 | POLL_KIND => Some(NoteUnitFragmentResponse {
             fragment: NoteUnitFragment::Single(NoteRef {
                 key: payload.key,
                 created_at: payload.note.created_at(),
@@ -130,10 +132,34 @@ fn to_fragment<'a>(
             unknown_pk: Some(r.pk),
         }),
         6 => to_repost(payload, ndb, txn).map(RepostResponse::into),
+        KIND_COMMUNITY_APPROVAL => to_approved_post(payload, ndb, txn),
         _ => None,
     }
 }
 
+/// Community columns show the post a moderator approved rather than the
+/// approval itself
+fn to_approved_post<'a>(
+    payload: &'a NotePayload,
+    ndb: &Ndb,
+    txn: &Transaction,
+) -> Option<NoteUnitFragmentResponse<'a>> {
+    let post_id = approved_post_id(&payload.note)?;
+    let Ok(post) = ndb.get_note_by_id(txn, post_id.bytes()) else {
+        // approvals carry the post like reposts do
+        ingest_embedded_note(ndb, &payload.note);
+        return None;
+    };
+
+    Some(NoteUnitFragmentResponse {
+        fragment: NoteUnitFragment::Single(NoteRef {
+            key: post.key()?,
+            created_at: post.created_at(),
+        }),
+        unknown_pk: None,
+    })
+}
+
 fn to_reaction<'a>(
     payload: &'a NotePayload,
     ndb: &Ndb,
@@ -220,6 +246,7 @@ fn to_repost<'a>(
                 "Could not get reposted note for note id {}",
                 enostr::NoteId::new(*payload.note.id()).hex()
             );
+            ingest_embedded_note(ndb, &payload.note);
             return None;
         }
     };
@@ -247,3 +274,18 @@ fn to_repost<'a>(
         reposter_pk: payload.note.pubkey(),
     })
 }
+
+/// Reposts carry the reposted note as their content. When we don't have
+/// it yet, ingest that copy so the repost can be shown the next time the
+/// timeline is built. nostrdb checks the signature like any other note.
+fn ingest_embedded_note(ndb: &Ndb, repost: &Note) {
+    let content = repost.content().trim();
+    if !content.starts_with('{') {
+        return;
+    }
+
+    let event = format!("[\"EVENT\",{content}]");
+    if let Err(e) = ndb.process_event_with(&event, IngestMetadata::new().client(true)) {
+        tracing::debug!("could not ingest note embedded in repost: {e}");
+    }
+}
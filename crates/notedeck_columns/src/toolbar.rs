@@ -24,11 +24,20 @@ pub fn unseen_notification(
     }
 
     let notif_kind = TimelineKind::Notifications(*current_pk);
-    let Some(tl) = columns.timeline_cache.get_mut(&notif_kind) else {
+    let Some(tl) = columns.timeline_cache.get(&notif_kind) else {
         return false;
     };
 
-    !tl.seen_latest_notes
+    let Some(latest) = tl.all_or_any_entries().latest() else {
+        return false;
+    };
+
+    // accounts that haven't opened notifications since this was tracked
+    // go by what was loaded this session
+    columns
+        .notifications_seen
+        .is_unseen(current_pk, latest.created_at)
+        .unwrap_or(!tl.seen_latest_notes)
 }
 
 /// When you click the toolbar button, these actions
@@ -65,6 +65,21 @@ impl<'a> AccountLoginView<'a> {
                         self.manager.toggle_password_visibility();
                     }
                 });
+                if self.manager.needs_passphrase() {
+                    ui.add(
+                        TextEdit::singleline(self.manager.passphrase_buffer())
+                            .password(true)
+                            .hint_text(tr!(
+                                self.i18n,
+                                "Passphrase for your encrypted key",
+                                "Placeholder for the passphrase of an encrypted (ncryptsec) key"
+                            ))
+                            .vertical_align(Align::Center)
+                            .min_size(Vec2::new(0.0, 40.0))
+                            .margin(Margin::same(12)),
+                    );
+                }
+
                 ui.with_layout(Layout::left_to_right(Align::TOP), |ui| {
                 let help_text_style = NotedeckTextStyle::Small;
                 ui.add(egui::Label::new(
@@ -3,7 +3,7 @@ use egui::{
 };
 use enostr::Pubkey;
 use nostrdb::{Ndb, Transaction};
-use notedeck::{tr, Accounts, DragResponse, Images, Localization, MediaJobSender};
+use notedeck::{tr, AccountScrub, Accounts, DragResponse, Images, Localization, MediaJobSender};
 use notedeck_ui::colors::PINK;
 use notedeck_ui::profile::preview::SimpleProfilePreview;
 
@@ -20,13 +20,15 @@ pub struct AccountsView<'a> {
 #[derive(Clone, Debug)]
 pub enum AccountsViewResponse {
     SelectAccount(Pubkey),
-    RemoveAccount(Pubkey),
+    RemoveAccount(Pubkey, AccountScrub),
     RouteToLogin,
+    /// Browse read-only from another user's perspective
+    ViewAs(Pubkey),
 }
 
 #[derive(Debug)]
 enum ProfilePreviewAction {
-    RemoveAccount,
+    RemoveAccount(AccountScrub),
     SwitchTo,
 }
 
@@ -104,6 +106,7 @@ impl<'a> AccountsView<'a> {
                     let profile = ndb.get_profile_by_pubkey(&txn, pk).ok();
                     let is_selected = *pk == selected.key.pubkey;
                     let has_nsec = account.key.secret_key.is_some();
+                    let is_ghost = accounts.is_ghost(pk);
 
                     let profile_peview_view = {
                         let max_size = egui::vec2(ui.available_width(), 77.0);
@@ -116,7 +119,15 @@ impl<'a> AccountsView<'a> {
                                 i18n,
                                 has_nsec,
                             );
-                            show_profile_card(ui, preview, max_size, is_selected, resp)
+                            show_profile_card(
+                                ui,
+                                preview,
+                                max_size,
+                                is_selected,
+                                is_ghost,
+                                *pk,
+                                resp,
+                            )
                         })
                         .inner
                     };
@@ -126,8 +137,8 @@ impl<'a> AccountsView<'a> {
                             ProfilePreviewAction::SwitchTo => {
                                 AccountsViewResponse::SelectAccount(*pk)
                             }
-                            ProfilePreviewAction::RemoveAccount => {
-                                AccountsViewResponse::RemoveAccount(*pk)
+                            ProfilePreviewAction::RemoveAccount(scrub) => {
+                                AccountsViewResponse::RemoveAccount(*pk, scrub)
                             }
                         });
                     }
@@ -145,21 +156,72 @@ impl<'a> AccountsView<'a> {
             Vec2::new(ui.available_size_before_wrap().x, 32.0),
             Layout::left_to_right(egui::Align::Center),
             |ui| {
+                let mut resp = None;
                 if ui.add(add_account_button(i18n)).clicked() {
-                    Some(AccountsViewResponse::RouteToLogin)
-                } else {
-                    None
+                    resp = Some(AccountsViewResponse::RouteToLogin);
                 }
+
+                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    if let Some(pubkey) = view_as_widget(ui, i18n) {
+                        resp = Some(AccountsViewResponse::ViewAs(pubkey));
+                    }
+                });
+
+                resp
             },
         )
     }
 }
 
+/// An npub or hex pubkey field for browsing as someone else without keys.
+fn view_as_widget(ui: &mut egui::Ui, i18n: &mut Localization) -> Option<Pubkey> {
+    let id = ui.id().with("view_as_input");
+    let mut input: String = ui.data_mut(|d| d.get_temp(id).unwrap_or_default());
+    let parsed = Pubkey::parse(input.trim()).ok();
+
+    let button = ui
+        .add_enabled(
+            parsed.is_some(),
+            Button::new(tr!(
+                i18n,
+                "View as",
+                "Button to browse read-only as the entered npub"
+            )),
+        )
+        .on_hover_text(tr!(
+            i18n,
+            "Browse this user's feed and notifications without their keys",
+            "Tooltip for the view as button on the accounts screen"
+        ));
+
+    let text_resp = ui.add(
+        egui::TextEdit::singleline(&mut input)
+            .hint_text(tr!(
+                i18n,
+                "npub to view as",
+                "Placeholder for the npub field of the view as feature"
+            ))
+            .desired_width(160.0),
+    );
+
+    let submitted = text_resp.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+    let chosen = parsed.filter(|_| button.clicked() || submitted);
+
+    if chosen.is_some() {
+        input.clear();
+    }
+    ui.data_mut(|d| d.insert_temp(id, input));
+
+    chosen
+}
+
 fn show_profile_card(
     ui: &mut egui::Ui,
     preview: SimpleProfilePreview,
     max_size: egui::Vec2,
     is_selected: bool,
+    is_ghost: bool,
+    pubkey: Pubkey,
     card_resp: egui::Response,
 ) -> Option<ProfilePreviewAction> {
     let mut op: Option<ProfilePreviewAction> = None;
@@ -177,15 +239,38 @@ fn show_profile_card(
             .inner_margin(8.0)
             .show(ui, |ui| {
                 ui.horizontal(|ui| {
-                    let btn = sign_out_button(preview.i18n);
+                    let btn = if is_ghost {
+                        stop_viewing_button(preview.i18n)
+                    } else {
+                        sign_out_button(preview.i18n)
+                    };
+                    let labels = SignOutLabels::new(preview.i18n);
+                    let viewing_as = is_ghost.then(|| {
+                        tr!(
+                            preview.i18n,
+                            "Viewing as",
+                            "Label on an account that is only being browsed read-only"
+                        )
+                    });
                     ui.add(preview);
 
                     ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
                         if card_resp.clicked() {
                             op = Some(ProfilePreviewAction::SwitchTo);
                         }
-                        if ui.add_sized(egui::Vec2::new(84.0, 32.0), btn).clicked() {
-                            op = Some(ProfilePreviewAction::RemoveAccount)
+                        let sign_out_resp = ui.add_sized(egui::Vec2::new(84.0, 32.0), btn);
+                        if let Some(viewing_as) = viewing_as {
+                            ui.weak(viewing_as);
+                            // nothing is stored for these, so there's nothing to scrub
+                            if sign_out_resp.clicked() {
+                                op = Some(ProfilePreviewAction::RemoveAccount(
+                                    AccountScrub::default(),
+                                ));
+                            }
+                        } else if let Some(scrub) =
+                            sign_out_confirmation(ui, &labels, pubkey, sign_out_resp)
+                        {
+                            op = Some(ProfilePreviewAction::RemoveAccount(scrub))
                         }
                     });
                 });
@@ -196,6 +281,117 @@ fn show_profile_card(
     op
 }
 
+/// Translated strings for the sign out confirmation, resolved before the
+/// profile preview takes the localizer.
+struct SignOutLabels {
+    erase_local: String,
+    prune_db: String,
+    prune_warning: String,
+    confirm: String,
+    confirm_prune: String,
+    cancel: String,
+}
+
+impl SignOutLabels {
+    fn new(i18n: &mut Localization) -> Self {
+        Self {
+            erase_local: tr!(
+                i18n,
+                "Erase drafts, cached messages and wallet connection",
+                "Checkbox to erase an account's local data when signing out"
+            ),
+            prune_db: tr!(
+                i18n,
+                "Keep only my remaining accounts' notes in the database",
+                "Checkbox to compact the database down to the remaining accounts' notes when signing out"
+            ),
+            prune_warning: tr!(
+                i18n,
+                "This also removes the notes of everyone you follow and everything else cached. Profiles are kept.",
+                "Warning shown when pruning the database on sign out is selected"
+            ),
+            confirm: tr!(
+                i18n,
+                "Sign out",
+                "Button label to confirm signing out of an account"
+            ),
+            confirm_prune: tr!(
+                i18n,
+                "Sign out and prune",
+                "Button label to confirm signing out of an account and pruning the database"
+            ),
+            cancel: tr!(i18n, "Cancel", "Button label to cancel an action"),
+        }
+    }
+}
+
+/// Ask whether the account's local data should be erased along with it.
+/// Returns the chosen options once the sign out is confirmed.
+fn sign_out_confirmation(
+    ui: &mut egui::Ui,
+    labels: &SignOutLabels,
+    pubkey: Pubkey,
+    sign_out_resp: egui::Response,
+) -> Option<AccountScrub> {
+    let id = ui.id().with(("sign_out", pubkey));
+    let scrub_id = id.with("scrub");
+
+    if sign_out_resp.clicked() {
+        ui.data_mut(|d| {
+            d.insert_temp(id, true);
+            d.insert_temp(scrub_id, AccountScrub::default());
+        });
+    }
+
+    if !ui.data_mut(|d| *d.get_temp_mut_or_default::<bool>(id)) {
+        return None;
+    }
+
+    let mut scrub: AccountScrub = ui.data_mut(|d| *d.get_temp_mut_or_default(scrub_id));
+    let mut confirmed = None;
+    let mut popup_rect = None;
+
+    sign_out_resp.show_tooltip_ui(|ui| {
+        ui.checkbox(&mut scrub.local_data, labels.erase_local.as_str());
+        ui.checkbox(&mut scrub.prune_db, labels.prune_db.as_str());
+        if scrub.prune_db {
+            ui.colored_label(ui.visuals().warn_fg_color, labels.prune_warning.as_str());
+        }
+
+        ui.horizontal(|ui| {
+            let confirm = if scrub.prune_db {
+                &labels.confirm_prune
+            } else {
+                &labels.confirm
+            };
+            let confirm_resp = ui.button(confirm.as_str());
+            if confirm_resp.clicked() {
+                confirmed = Some(scrub);
+            }
+
+            if confirm_resp.clicked() || ui.button(labels.cancel.as_str()).clicked() {
+                ui.data_mut(|d| d.insert_temp(id, false));
+            }
+        });
+
+        popup_rect = Some(ui.min_rect().expand(8.0));
+    });
+
+    ui.data_mut(|d| d.insert_temp(scrub_id, scrub));
+
+    // toggling the checkboxes counts as a click elsewhere, so ignore clicks
+    // landing inside the popup
+    let clicked_in_popup = popup_rect.is_some_and(|rect| {
+        ui.input(|i| i.pointer.interact_pos())
+            .is_some_and(|pos| rect.contains(pos))
+    });
+    if confirmed.is_none() && !clicked_in_popup && sign_out_resp.clicked_elsewhere() {
+        ui.data_mut(|d| d.insert_temp(id, false));
+    }
+
+    confirmed
+}
+
 fn scroll_area() -> ScrollArea {
     egui::ScrollArea::vertical()
         .scroll_bar_visibility(egui::scroll_area::ScrollBarVisibility::AlwaysHidden)
@@ -224,3 +420,11 @@ fn sign_out_button(i18n: &mut Localization) -> egui::Button<'static> {
         "Button label to sign out of account"
     )))
 }
+
+fn stop_viewing_button(i18n: &mut Localization) -> egui::Button<'static> {
+    egui::Button::new(RichText::new(tr!(
+        i18n,
+        "Stop viewing",
+        "Button label to stop browsing as another user"
+    )))
+}
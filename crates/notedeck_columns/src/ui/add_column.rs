@@ -1,15 +1,17 @@
 use core::f32;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use egui::{
     pos2, vec2, Align, Color32, FontId, Id, Image, Margin, Pos2, Rect, RichText, ScrollArea,
     Separator, Ui, Vec2, Widget,
 };
 use enostr::Pubkey;
-use nostrdb::{Filter, Ndb, Transaction};
+use nostrdb::{Filter, Ndb, ProfileRecord, Transaction};
 use tracing::error;
 
 use crate::{
+    community::{communities, communities_filter},
+    group::{parse_group_address, parse_group_relay, relay_groups, relay_groups_filter},
     login_manager::AcquireKeyState,
     options::AppOptions,
     route::Route,
@@ -39,7 +41,11 @@ pub enum AddColumnResponse {
     ExternalIndividual,
     PeopleList,
     CreatePeopleList,
+    EditPeopleList(String),
     FinishCreatePeopleList,
+    Bookmarks,
+    Group,
+    Community,
 }
 
 struct SelectionHandler<'a> {
@@ -77,11 +83,15 @@ enum AddColumnOption {
     Algo(AlgoOption),
     Notification(PubkeySource),
     Contacts(PubkeySource),
+    Live(PubkeySource),
     UndecidedHashtag,
     UndecidedIndividual,
     ExternalIndividual,
     Individual(PubkeySource),
     UndecidedPeopleList,
+    UndecidedBookmarks,
+    UndecidedGroup,
+    UndecidedCommunity,
 }
 
 #[derive(Clone, Copy, Eq, PartialEq, Debug, Default, Hash)]
@@ -102,6 +112,9 @@ pub enum AddColumnRoute {
     ExternalIndividual,
     PeopleList,
     CreatePeopleList,
+    Bookmarks,
+    Group,
+    Community,
 }
 
 // Parser for the common case without any payloads
@@ -133,6 +146,9 @@ impl AddColumnRoute {
             }
             Self::PeopleList => &["column", "people_list"],
             Self::CreatePeopleList => &["column", "create_people_list"],
+            Self::Bookmarks => &["column", "bookmarks"],
+            Self::Group => &["column", "group"],
+            Self::Community => &["column", "community"],
             // NOTE!!! When adding to this, update the parser for TokenSerializable below
         }
     }
@@ -161,6 +177,9 @@ impl TokenSerializable for AddColumnRoute {
                 |p| parse_column_route(p, AddColumnRoute::Algo(AddAlgoRoute::LastPerPubkey)),
                 |p| parse_column_route(p, AddColumnRoute::PeopleList),
                 |p| parse_column_route(p, AddColumnRoute::CreatePeopleList),
+                |p| parse_column_route(p, AddColumnRoute::Bookmarks),
+                |p| parse_column_route(p, AddColumnRoute::Group),
+                |p| parse_column_route(p, AddColumnRoute::Community),
             ],
         )
     }
@@ -178,6 +197,9 @@ impl AddColumnOption {
             AddColumnOption::Contacts(pk_src) => AddColumnResponse::Timeline(
                 TimelineKind::contact_list(*pk_src.as_pubkey(&cur_account.key.pubkey)),
             ),
+            AddColumnOption::Live(pk_src) => AddColumnResponse::Timeline(TimelineKind::live(
+                *pk_src.as_pubkey(&cur_account.key.pubkey),
+            )),
             AddColumnOption::ExternalNotification => AddColumnResponse::ExternalNotification,
             AddColumnOption::UndecidedHashtag => AddColumnResponse::Hashtag,
             AddColumnOption::UndecidedIndividual => AddColumnResponse::UndecidedIndividual,
@@ -186,6 +208,9 @@ impl AddColumnOption {
                 TimelineKind::profile(*pubkey_source.as_pubkey(&cur_account.key.pubkey)),
             ),
             AddColumnOption::UndecidedPeopleList => AddColumnResponse::PeopleList,
+            AddColumnOption::UndecidedBookmarks => AddColumnResponse::Bookmarks,
+            AddColumnOption::UndecidedGroup => AddColumnResponse::Group,
+            AddColumnOption::UndecidedCommunity => AddColumnResponse::Community,
         }
     }
 }
@@ -201,6 +226,8 @@ pub struct AddColumnView<'a> {
     jobs: &'a MediaJobSender,
     unknown_ids: &'a mut notedeck::UnknownIds,
     people_lists: &'a mut Option<notedeck::Nip51SetCache>,
+    followed_people_lists: &'a mut Option<notedeck::Nip51SetCache>,
+    bookmark_sets: &'a mut Option<notedeck::Nip51SetCache>,
 }
 
 impl<'a> AddColumnView<'a> {
@@ -216,6 +243,8 @@ impl<'a> AddColumnView<'a> {
         jobs: &'a MediaJobSender,
         unknown_ids: &'a mut notedeck::UnknownIds,
         people_lists: &'a mut Option<notedeck::Nip51SetCache>,
+        followed_people_lists: &'a mut Option<notedeck::Nip51SetCache>,
+        bookmark_sets: &'a mut Option<notedeck::Nip51SetCache>,
     ) -> Self {
         Self {
             key_state_map,
@@ -228,6 +257,8 @@ impl<'a> AddColumnView<'a> {
             jobs,
             unknown_ids,
             people_lists,
+            followed_people_lists,
+            bookmark_sets,
         }
     }
 
@@ -372,6 +403,10 @@ impl<'a> AddColumnView<'a> {
             cache.poll_for_notes(self.ndb, self.unknown_ids);
         }
 
+        if let Some(cache) = self.followed_people_lists.as_mut() {
+            cache.poll_for_notes(self.ndb, self.unknown_ids);
+        }
+
         padding(16.0, ui, |ui| {
             // Always show "New List" button at the top
             if ui.button("+ New List").clicked() {
@@ -380,23 +415,122 @@ impl<'a> AddColumnView<'a> {
 
             ui.add_space(8.0);
 
-            let Some(cache) = self.people_lists.as_ref() else {
-                ui.label("Loading lists from relays...");
+            let mut response = None;
+
+            match self.people_lists.as_ref() {
+                None => {
+                    ui.label("Loading lists from relays...");
+                }
+                Some(cache) if cache.is_empty() => {
+                    ui.label("No people lists found.");
+                }
+                Some(cache) => {
+                    for set in cache.iter() {
+                        let title = set.title.as_deref().unwrap_or(&set.identifier);
+                        let label = format!("{} ({} members)", title, set.pks.len());
+
+                        ui.horizontal(|ui| {
+                            if ui.button(&label).clicked() {
+                                response =
+                                    Some(AddColumnResponse::Timeline(TimelineKind::people_list(
+                                        self.cur_account.key.pubkey,
+                                        set.identifier.clone(),
+                                    )));
+                            }
+
+                            if ui.small_button("Edit").clicked() {
+                                response =
+                                    Some(AddColumnResponse::EditPeopleList(set.identifier.clone()));
+                            }
+                        });
+
+                        ui.add(Separator::default().spacing(4.0));
+                    }
+                }
+            }
+
+            let followed_sets: Vec<&notedeck::Nip51Set> = self
+                .followed_people_lists
+                .as_ref()
+                .map(|cache| {
+                    cache
+                        .iter()
+                        .into_iter()
+                        .filter(|set| set.author != self.cur_account.key.pubkey)
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            if followed_sets.is_empty() {
+                return response;
+            }
+
+            ui.add_space(16.0);
+            ui.label(
+                RichText::new("From people you follow")
+                    .text_style(NotedeckTextStyle::Body.text_style())
+                    .strong(),
+            );
+            ui.add_space(8.0);
+
+            let txn = Transaction::new(self.ndb).expect("txn");
+            for set in followed_sets {
+                let title = set.title.as_deref().unwrap_or(&set.identifier);
+                let profile = self
+                    .ndb
+                    .get_profile_by_pubkey(&txn, set.author.bytes())
+                    .ok();
+                let author = notedeck::name::get_display_name(profile.as_ref());
+                let label = format!("{} by {} ({} members)", title, author.name(), set.pks.len());
+
+                if ui.button(&label).clicked() {
+                    response = Some(AddColumnResponse::Timeline(TimelineKind::people_list(
+                        set.author,
+                        set.identifier.clone(),
+                    )));
+                }
+
+                ui.add(Separator::default().spacing(4.0));
+            }
+
+            response
+        })
+        .inner
+    }
+
+    fn bookmarks_ui(&mut self, ui: &mut Ui) -> Option<AddColumnResponse> {
+        if self.bookmark_sets.is_none() {
+            let txn = Transaction::new(self.ndb).expect("txn");
+            let filter = notedeck::bookmarks::bookmark_sets_filter(&self.cur_account.key.pubkey);
+            *self.bookmark_sets =
+                notedeck::Nip51SetCache::new_local(self.ndb, &txn, self.unknown_ids, vec![filter]);
+        }
+
+        if let Some(cache) = self.bookmark_sets.as_mut() {
+            cache.poll_for_notes(self.ndb, self.unknown_ids);
+        }
+
+        padding(16.0, ui, |ui| {
+            let Some(cache) = self.bookmark_sets.as_ref() else {
                 return None;
             };
 
             if cache.is_empty() {
-                ui.label("No people lists found.");
+                ui.label(tr!(
+                    self.i18n,
+                    "No bookmark folders yet. Add a note to one from its context menu.",
+                    "Shown when the user has no NIP-51 bookmark sets"
+                ));
                 return None;
             }
 
             let mut response = None;
             for set in cache.iter() {
                 let title = set.title.as_deref().unwrap_or(&set.identifier);
-                let label = format!("{} ({} members)", title, set.pks.len());
+                let label = format!("{} ({})", title, set.notes.len());
 
                 if ui.button(&label).clicked() {
-                    response = Some(AddColumnResponse::Timeline(TimelineKind::people_list(
+                    response = Some(AddColumnResponse::Timeline(TimelineKind::bookmarks(
                         self.cur_account.key.pubkey,
                         set.identifier.clone(),
                     )));
@@ -684,6 +818,52 @@ impl<'a> AddColumnView<'a> {
             icon: app_images::home_image(),
             option: AddColumnOption::UndecidedPeopleList,
         });
+        if self.cur_account.key.secret_key.is_some() {
+            vec.push(ColumnOptionData {
+                title: tr!(self.i18n, "Bookmarks", "Title for bookmarks column"),
+                description: tr!(
+                    self.i18n,
+                    "Browse one of your bookmark folders",
+                    "Description for bookmarks column"
+                ),
+                icon: app_images::home_image(),
+                option: AddColumnOption::UndecidedBookmarks,
+            });
+        }
+        vec.push(ColumnOptionData {
+            title: tr!(self.i18n, "Group", "Title for NIP-29 group column"),
+            description: tr!(
+                self.i18n,
+                "Chat in a group hosted on a group relay",
+                "Description for NIP-29 group column"
+            ),
+            icon: app_images::home_image(),
+            option: AddColumnOption::UndecidedGroup,
+        });
+        vec.push(ColumnOptionData {
+            title: tr!(self.i18n, "Community", "Title for NIP-72 community column"),
+            description: tr!(
+                self.i18n,
+                "Posts approved by a community's moderators",
+                "Description for NIP-72 community column"
+            ),
+            icon: app_images::home_image(),
+            option: AddColumnOption::UndecidedCommunity,
+        });
+        vec.push(ColumnOptionData {
+            title: tr!(self.i18n, "Live now", "Title for live streams column"),
+            description: tr!(
+                self.i18n,
+                "Streams from people you follow that are live right now",
+                "Description for live streams column"
+            ),
+            icon: app_images::home_image(),
+            option: AddColumnOption::Live(if self.cur_account.key.secret_key.is_some() {
+                PubkeySource::DeckAuthor
+            } else {
+                PubkeySource::Explicit(self.cur_account.key.pubkey)
+            }),
+        });
         vec.push(ColumnOptionData {
             title: tr!(self.i18n, "Algo", "Title for algorithmic feeds column"),
             description: tr!(
@@ -1000,12 +1180,29 @@ pub fn render_add_column_routes(
     col: usize,
     route: &AddColumnRoute,
 ) {
-    // Hashtag and CreatePeopleList are handled separately because they
-    // borrow ViewState fields directly (conflicting with AddColumnView)
+    // Hashtag, CreatePeopleList, Group and Community are handled separately because
+    // they borrow ViewState fields directly (conflicting with AddColumnView)
     let resp = match route {
         AddColumnRoute::Hashtag => hashtag_ui(ui, ctx.i18n, &mut app.view_state.id_string_map),
         AddColumnRoute::CreatePeopleList => create_people_list_ui(ui, app, ctx),
+        AddColumnRoute::Group => group_ui(ui, app, ctx),
+        AddColumnRoute::Community => community_ui(ui, ctx, &mut app.view_state.id_string_map),
         _ => {
+            if *route == AddColumnRoute::PeopleList
+                && app.view_state.followed_people_lists.is_none()
+            {
+                if let ContactState::Received { contacts, .. } = ctx
+                    .accounts
+                    .get_selected_account()
+                    .data
+                    .contacts
+                    .get_state()
+                {
+                    let contacts: Vec<Pubkey> = contacts.iter().copied().collect();
+                    app.view_state.followed_people_lists = followed_people_lists(ctx, &contacts);
+                }
+            }
+
             let account = ctx.accounts.get_selected_account();
             let contacts = account.data.contacts.get_state();
             let mut add_column_view = AddColumnView::new(
@@ -1019,6 +1216,8 @@ pub fn render_add_column_routes(
                 ctx.media_jobs.sender(),
                 ctx.unknown_ids,
                 &mut app.view_state.people_lists,
+                &mut app.view_state.followed_people_lists,
+                &mut app.view_state.bookmark_sets,
             );
             match route {
                 AddColumnRoute::Base => add_column_view.ui(ui),
@@ -1035,7 +1234,11 @@ pub fn render_add_column_routes(
                 AddColumnRoute::UndecidedIndividual => add_column_view.individual_ui(ui),
                 AddColumnRoute::ExternalIndividual => add_column_view.external_individual_ui(ui),
                 AddColumnRoute::PeopleList => add_column_view.people_list_ui(ui),
-                AddColumnRoute::Hashtag | AddColumnRoute::CreatePeopleList => unreachable!(),
+                AddColumnRoute::Bookmarks => add_column_view.bookmarks_ui(ui),
+                AddColumnRoute::Hashtag
+                | AddColumnRoute::CreatePeopleList
+                | AddColumnRoute::Group
+                | AddColumnRoute::Community => unreachable!(),
             }
         }
     };
@@ -1121,6 +1324,34 @@ pub fn render_add_column_routes(
                     .route_to(crate::route::Route::AddColumn(AddColumnRoute::PeopleList));
             }
             AddColumnResponse::CreatePeopleList => {
+                reset_people_list_editor(app);
+                app.columns_mut(ctx.i18n, ctx.accounts)
+                    .column_mut(col)
+                    .router_mut()
+                    .route_to(crate::route::Route::AddColumn(
+                        AddColumnRoute::CreatePeopleList,
+                    ));
+            }
+            AddColumnResponse::EditPeopleList(identifier) => {
+                let Some(set) = app.view_state.people_lists.as_ref().and_then(|cache| {
+                    cache
+                        .iter()
+                        .into_iter()
+                        .find(|set| set.identifier == identifier)
+                }) else {
+                    return;
+                };
+
+                let title = set.title.clone().unwrap_or_else(|| identifier.clone());
+                let members = set.pks.iter().copied().collect();
+
+                reset_people_list_editor(app);
+                app.view_state
+                    .id_string_map
+                    .insert(Id::new("create_people_list_name"), title);
+                app.view_state.create_people_list.selected_members = members;
+                app.view_state.create_people_list.editing = Some(identifier);
+
                 app.columns_mut(ctx.i18n, ctx.accounts)
                     .column_mut(col)
                     .router_mut()
@@ -1131,10 +1362,62 @@ pub fn render_add_column_routes(
             AddColumnResponse::FinishCreatePeopleList => {
                 handle_create_people_list(app, ctx, col);
             }
+            AddColumnResponse::Bookmarks => {
+                app.columns_mut(ctx.i18n, ctx.accounts)
+                    .column_mut(col)
+                    .router_mut()
+                    .route_to(crate::route::Route::AddColumn(AddColumnRoute::Bookmarks));
+            }
+            AddColumnResponse::Group => {
+                app.view_state.group_browse = Default::default();
+                app.columns_mut(ctx.i18n, ctx.accounts)
+                    .column_mut(col)
+                    .router_mut()
+                    .route_to(crate::route::Route::AddColumn(AddColumnRoute::Group));
+            }
+            AddColumnResponse::Community => {
+                ctx.remote
+                    .oneshot(ctx.accounts)
+                    .oneshot(vec![communities_filter()]);
+                app.columns_mut(ctx.i18n, ctx.accounts)
+                    .column_mut(col)
+                    .router_mut()
+                    .route_to(crate::route::Route::AddColumn(AddColumnRoute::Community));
+            }
         };
     }
 }
 
+/// Local cache of the kind 30000 lists published by `contacts`, with a
+/// oneshot request to fetch them from the account's read relays.
+fn followed_people_lists(
+    ctx: &mut AppContext<'_>,
+    contacts: &[Pubkey],
+) -> Option<notedeck::Nip51SetCache> {
+    let filter = Filter::new()
+        .authors(contacts.iter().map(|pk| pk.bytes()))
+        .kinds([30000])
+        .limit(500)
+        .build();
+
+    ctx.remote
+        .oneshot(ctx.accounts)
+        .oneshot(vec![filter.clone()]);
+
+    let txn = Transaction::new(ctx.ndb).expect("txn");
+    notedeck::Nip51SetCache::new_local(ctx.ndb, &txn, ctx.unknown_ids, vec![filter])
+}
+
+fn reset_people_list_editor(app: &mut Damus) {
+    app.view_state
+        .id_string_map
+        .remove(&Id::new("create_people_list_name"));
+    app.view_state
+        .id_string_map
+        .remove(&Id::new("create_people_list_search"));
+    app.view_state.create_people_list = Default::default();
+}
+
 fn handle_create_people_list(app: &mut Damus, ctx: &mut AppContext<'_>, col: usize) {
     let name_id = Id::new("create_people_list_name");
     let name = app
@@ -1165,6 +1448,27 @@ fn handle_create_people_list(app: &mut Damus, ctx: &mut AppContext<'_>, col: usi
         return;
     };
 
+    if let Some(identifier) = app.view_state.create_people_list.editing.clone() {
+        let txn = Transaction::new(ctx.ndb).expect("txn");
+        notedeck::send_people_list_update(
+            ctx.ndb,
+            &txn,
+            &mut ctx.remote.publisher(ctx.accounts),
+            kp,
+            &identifier,
+            &name,
+            &members,
+        );
+
+        app.view_state.people_lists = None;
+        reset_people_list_editor(app);
+        app.columns_mut(ctx.i18n, ctx.accounts)
+            .column_mut(col)
+            .router_mut()
+            .go_back();
+        return;
+    }
+
     notedeck::send_people_list_event(
         ctx.ndb,
         &mut ctx.remote.publisher(ctx.accounts),
@@ -1177,10 +1481,7 @@ fn handle_create_people_list(app: &mut Damus, ctx: &mut AppContext<'_>, col: usi
     app.view_state.people_lists = None;
 
     // Clear creation state
-    app.view_state.id_string_map.remove(&name_id);
-    let search_id = Id::new("create_people_list_search");
-    app.view_state.id_string_map.remove(&search_id);
-    app.view_state.create_people_list.selected_members.clear();
+    reset_people_list_editor(app);
 
     // Create the timeline column immediately
     let pubkey = ctx.accounts.get_selected_account().key.pubkey;
@@ -1265,6 +1566,187 @@ pub fn hashtag_ui(
     .inner
 }
 
+/// Browse the groups a NIP-29 relay hosts, or open one directly by its
+/// `host'group-id` address
+pub fn group_ui(
+    ui: &mut Ui,
+    app: &mut Damus,
+    ctx: &mut AppContext<'_>,
+) -> Option<AddColumnResponse> {
+    padding(16.0, ui, |ui| {
+        let id = Id::new("add_column_group");
+        let text_buffer = app.view_state.id_string_map.entry(id).or_default();
+
+        let text_edit = egui::TextEdit::singleline(text_buffer)
+            .hint_text(
+                RichText::new(tr!(
+                    ctx.i18n,
+                    "Group relay, or a group address like groups.example.com'pizza",
+                    "Placeholder for the NIP-29 group relay input field"
+                ))
+                .text_style(NotedeckTextStyle::Body.text_style()),
+            )
+            .vertical_align(Align::Center)
+            .desired_width(f32::INFINITY)
+            .min_size(Vec2::new(0.0, 40.0))
+            .margin(Margin::same(12));
+        ui.add(text_edit);
+
+        ui.add_space(8.0);
+
+        let browse_label = tr!(ctx.i18n, "Browse", "Button to list the groups of a relay");
+        let submitted = ui.input(|i| i.key_released(egui::Key::Enter))
+            || ui
+                .add_sized(
+                    egui::vec2(50.0, 40.0),
+                    styled_button(&browse_label, notedeck_ui::colors::PINK),
+                )
+                .clicked();
+
+        let input = text_buffer.trim().to_owned();
+        let browse = &mut app.view_state.group_browse;
+        if submitted && !input.is_empty() {
+            if let Some(group) = parse_group_address(&input) {
+                app.view_state.id_string_map.remove(&id);
+                return Some(AddColumnResponse::Timeline(TimelineKind::Group(group)));
+            }
+
+            if let Some(relay) = parse_group_relay(&input) {
+                ctx.remote.oneshot(ctx.accounts).oneshot_to(
+                    vec![relay_groups_filter()],
+                    hashbrown::HashSet::from([relay.clone()]),
+                );
+                browse.relay = Some(relay);
+                browse.error = None;
+            } else {
+                browse.error = Some(tr!(
+                    ctx.i18n,
+                    "Not a relay or group address",
+                    "Error when the group relay input can't be parsed"
+                ));
+            }
+        }
+
+        if let Some(error) = &browse.error {
+            ui.label(RichText::new(error).color(ui.visuals().error_fg_color));
+            return None;
+        }
+
+        let relay = browse.relay.clone()?;
+        let txn = Transaction::new(ctx.ndb).expect("txn");
+        let groups = relay_groups(ctx.ndb, &txn, &relay);
+
+        ui.add_space(8.0);
+        if groups.is_empty() {
+            ui.label(tr!(
+                ctx.i18n,
+                "Looking for groups on {relay}…",
+                "Shown while waiting for a relay's NIP-29 groups",
+                relay = relay.to_string()
+            ));
+            return None;
+        }
+
+        let mut response = None;
+        for group in groups {
+            if ui.button(group.display_name()).clicked() {
+                response = Some(AddColumnResponse::Timeline(TimelineKind::group(
+                    relay.clone(),
+                    group.id.clone(),
+                )));
+            }
+
+            if let Some(about) = group.about.as_deref().filter(|a| !a.is_empty()) {
+                ui.label(
+                    RichText::new(about)
+                        .text_style(NotedeckTextStyle::Small.text_style())
+                        .weak(),
+                );
+            }
+
+            ui.add(Separator::default().spacing(4.0));
+        }
+
+        response
+    })
+    .inner
+}
+
+/// Pick one of the communities we've seen, narrowed down by name
+pub fn community_ui(
+    ui: &mut Ui,
+    ctx: &mut AppContext<'_>,
+    id_string_map: &mut HashMap<Id, String>,
+) -> Option<AddColumnResponse> {
+    padding(16.0, ui, |ui| {
+        let id = Id::new("add_column_community");
+        let text_buffer = id_string_map.entry(id).or_default();
+
+        let text_edit = egui::TextEdit::singleline(text_buffer)
+            .hint_text(
+                RichText::new(tr!(
+                    ctx.i18n,
+                    "Search communities",
+                    "Placeholder for the NIP-72 community search field"
+                ))
+                .text_style(NotedeckTextStyle::Body.text_style()),
+            )
+            .vertical_align(Align::Center)
+            .desired_width(f32::INFINITY)
+            .min_size(Vec2::new(0.0, 40.0))
+            .margin(Margin::same(12));
+        ui.add(text_edit);
+
+        ui.add_space(8.0);
+
+        let query = text_buffer.trim().to_lowercase();
+        let txn = Transaction::new(ctx.ndb).expect("txn");
+        let communities: Vec<_> = communities(ctx.ndb, &txn)
+            .into_iter()
+            .filter(|c| query.is_empty() || c.display_name().to_lowercase().contains(&query))
+            .collect();
+
+        if communities.is_empty() {
+            ui.label(tr!(
+                ctx.i18n,
+                "Looking for communities…",
+                "Shown while waiting for NIP-72 communities"
+            ));
+            return None;
+        }
+
+        let mut response = None;
+        ScrollArea::vertical().show(ui, |ui| {
+            for community in communities {
+                if ui.button(community.display_name()).clicked() {
+                    response = Some(AddColumnResponse::Timeline(TimelineKind::community(
+                        community.owner,
+                        community.id.clone(),
+                    )));
+                }
+
+                if let Some(description) =
+                    community.description.as_deref().filter(|d| !d.is_empty())
+                {
+                    ui.label(
+                        RichText::new(description)
+                            .text_style(NotedeckTextStyle::Small.text_style())
+                            .weak(),
+                    );
+                }
+
+                ui.add(Separator::default().spacing(4.0));
+            }
+        });
+
+        if response.is_some() {
+            id_string_map.remove(&id);
+        }
+        response
+    })
+    .inner
+}
+
 pub fn create_people_list_ui(
     ui: &mut Ui,
     app: &mut Damus,
@@ -1321,46 +1803,52 @@ pub fn create_people_list_ui(
             .unwrap_or_default();
 
         ScrollArea::vertical().show(ui, |ui| {
+            let selected = &mut app.view_state.create_people_list.selected_members;
+            let jobs = ctx.media_jobs.sender();
+            let (img_cache, i18n) = (&mut *ctx.img_cache, &mut *ctx.i18n);
+
             if search_query.is_empty() {
-                // Show contacts
-                if let ContactState::Received {
-                    contacts: contact_set,
-                    ..
-                } = contacts
-                {
+                let contact_set = match contacts {
+                    ContactState::Received { contacts, .. } => Some(contacts),
+                    ContactState::Unreceived => None,
+                };
+
+                // Members of an edited list that aren't contacts would
+                // otherwise only be reachable through search
+                let mut others: Vec<Pubkey> = selected
+                    .iter()
+                    .filter(|pk| contact_set.is_none_or(|c| !c.contains(*pk)))
+                    .copied()
+                    .collect();
+                others.sort();
+
+                for pk in others {
+                    let profile = ctx.ndb.get_profile_by_pubkey(&txn, pk.bytes()).ok();
+                    member_row(
+                        ui,
+                        selected,
+                        pk,
+                        profile.as_ref(),
+                        false,
+                        img_cache,
+                        jobs,
+                        i18n,
+                    );
+                }
+
+                if let Some(contact_set) = contact_set {
                     for pk in contact_set {
                         let profile = ctx.ndb.get_profile_by_pubkey(&txn, pk.bytes()).ok();
-                        let is_selected = app
-                            .view_state
-                            .create_people_list
-                            .selected_members
-                            .contains(pk);
-
-                        ui.horizontal(|ui| {
-                            let mut checked = is_selected;
-                            ui.checkbox(&mut checked, "");
-                            let clicked = profile_row(
-                                ui,
-                                profile.as_ref(),
-                                false,
-                                ctx.img_cache,
-                                ctx.media_jobs.sender(),
-                                ctx.i18n,
-                            );
-                            if clicked || checked != is_selected {
-                                if is_selected {
-                                    app.view_state
-                                        .create_people_list
-                                        .selected_members
-                                        .remove(pk);
-                                } else {
-                                    app.view_state
-                                        .create_people_list
-                                        .selected_members
-                                        .insert(*pk);
-                                }
-                            }
-                        });
+                        member_row(
+                            ui,
+                            selected,
+                            *pk,
+                            profile.as_ref(),
+                            false,
+                            img_cache,
+                            jobs,
+                            i18n,
+                        );
                     }
                 } else {
                     ui.label(RichText::new("No contacts loaded yet.").weak());
@@ -1376,37 +1864,16 @@ pub fn create_people_list_ui(
                     for result in &results {
                         let pk = Pubkey::new(result.pk);
                         let profile = ctx.ndb.get_profile_by_pubkey(&txn, &result.pk).ok();
-                        let is_selected = app
-                            .view_state
-                            .create_people_list
-                            .selected_members
-                            .contains(&pk);
-
-                        ui.horizontal(|ui| {
-                            let mut checked = is_selected;
-                            ui.checkbox(&mut checked, "");
-                            let clicked = profile_row(
-                                ui,
-                                profile.as_ref(),
-                                result.is_contact,
-                                ctx.img_cache,
-                                ctx.media_jobs.sender(),
-                                ctx.i18n,
-                            );
-                            if clicked || checked != is_selected {
-                                if is_selected {
-                                    app.view_state
-                                        .create_people_list
-                                        .selected_members
-                                        .remove(&pk);
-                                } else {
-                                    app.view_state
-                                        .create_people_list
-                                        .selected_members
-                                        .insert(pk);
-                                }
-                            }
-                        });
+                        member_row(
+                            ui,
+                            selected,
+                            pk,
+                            profile.as_ref(),
+                            result.is_contact,
+                            img_cache,
+                            jobs,
+                            i18n,
+                        );
                     }
                 }
             }
@@ -1423,7 +1890,11 @@ pub fn create_people_list_ui(
             .unwrap_or_default();
         let can_create = !name_text.is_empty() && member_count > 0;
 
-        let create_btn = egui::Button::new("Create List");
+        let create_btn = if app.view_state.create_people_list.editing.is_some() {
+            egui::Button::new("Save List")
+        } else {
+            egui::Button::new("Create List")
+        };
         let resp = ui.add_enabled(can_create, create_btn);
         if resp.clicked() {
             return Some(AddColumnResponse::FinishCreatePeopleList);
@@ -1434,6 +1905,33 @@ pub fn create_people_list_ui(
     .inner
 }
 
+/// A checkbox and profile row that toggles `pk` in the list being built.
+fn member_row(
+    ui: &mut Ui,
+    selected: &mut HashSet<Pubkey>,
+    pk: Pubkey,
+    profile: Option<&ProfileRecord<'_>>,
+    is_contact: bool,
+    img_cache: &mut Images,
+    jobs: &MediaJobSender,
+    i18n: &mut Localization,
+) {
+    let is_selected = selected.contains(&pk);
+
+    ui.horizontal(|ui| {
+        let mut checked = is_selected;
+        ui.checkbox(&mut checked, "");
+        let clicked = profile_row(ui, profile, is_contact, img_cache, jobs, i18n);
+        if clicked || checked != is_selected {
+            if is_selected {
+                selected.remove(&pk);
+            } else {
+                selected.insert(pk);
+            }
+        }
+    });
+}
+
 fn sanitize_hashtag(raw_hashtag: &str) -> String {
     raw_hashtag
         .chars()
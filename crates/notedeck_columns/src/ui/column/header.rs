@@ -1,11 +1,15 @@
 use crate::column::ColumnsAction;
 use crate::nav::RenderNavAction;
 use crate::nav::SwitchingAction;
-use crate::timeline::ThreadSelection;
 use crate::{
     column::Columns,
+    community::CommunityDefinition,
+    group::GroupMetadata,
     route::Route,
-    timeline::{ColumnTitle, TimelineKind},
+    timeline::{
+        kind::{CommunityRef, GroupRef},
+        ColumnTitle, RelayUsage, TimelineInspect, TimelineKind,
+    },
     ui::{self},
 };
 
@@ -13,7 +17,7 @@ use egui::UiBuilder;
 use egui::{Margin, Response, RichText, Sense, Stroke};
 use enostr::Pubkey;
 use nostrdb::{Ndb, Transaction};
-use notedeck::tr;
+use notedeck::{tr, tr_plural};
 use notedeck::{Images, Localization, MediaJobSender, NotedeckTextStyle};
 use notedeck_ui::app_images;
 use notedeck_ui::header::chevron;
@@ -31,6 +35,8 @@ pub struct NavTitle<'a> {
     options: u32,
     i18n: &'a mut Localization,
     jobs: &'a MediaJobSender,
+    inspect: Option<TimelineInspect>,
+    relay_usage: Option<RelayUsage>,
 }
 
 struct HeaderAnim {
@@ -69,9 +75,17 @@ impl<'a> NavTitle<'a> {
             options,
             i18n,
             jobs,
+            inspect: None,
+            relay_usage: None,
         }
     }
 
+    /// Memory id of the flag that keeps a column's relay usage popup open.
+    /// The breakdown is only worth computing while it is.
+    pub fn relay_usage_id(col: usize) -> egui::Id {
+        egui::Id::new(("relay-usage", col))
+    }
+
     pub fn show(&mut self, ui: &mut egui::Ui) -> Option<RenderNavAction> {
         let anim = header_anim();
 
@@ -304,6 +318,38 @@ impl<'a> NavTitle<'a> {
         })
     }
 
+    fn relay_usage_section(&mut self, ui: &mut egui::Ui) {
+        let id = Self::relay_usage_id(self.col_id);
+        let mut resp = ui
+            .add(relay_usage_button())
+            .on_hover_cursor(egui::CursorIcon::PointingHand);
+
+        let open = ui.data(|d| d.get_temp::<bool>(id)).unwrap_or(false);
+        if !open {
+            resp = resp.on_hover_text(tr!(
+                self.i18n,
+                "Relays delivering this column",
+                "Tooltip for the button showing which relays delivered a column's notes"
+            ));
+        }
+
+        if resp.clicked() {
+            ui.data_mut(|d| d.insert_temp(id, !open));
+        } else if open && resp.clicked_elsewhere() {
+            ui.data_mut(|d| d.remove_temp::<bool>(id));
+        }
+
+        if !open {
+            return;
+        }
+
+        let Some(usage) = &self.relay_usage else {
+            return;
+        };
+
+        resp.show_tooltip_ui(|ui| relay_usage_ui(ui, self.i18n, usage));
+    }
+
     fn move_tooltip_col_presentation(&mut self, ui: &mut egui::Ui, col: usize) -> egui::Response {
         ui.horizontal(|ui| {
             self.title_presentation(ui, self.columns.column(col).router().top(), 32.0);
@@ -493,6 +539,39 @@ impl<'a> NavTitle<'a> {
         }
     }
 
+    fn group_pfp(&mut self, ui: &mut egui::Ui, group: &GroupRef, pfp_size: f32) -> Response {
+        let txn = Transaction::new(self.ndb).unwrap();
+        let picture = GroupMetadata::load(self.ndb, &txn, group).and_then(|m| m.picture);
+        let url = picture
+            .as_deref()
+            .unwrap_or_else(notedeck::profile::no_pfp_url);
+
+        ui.add(
+            &mut ProfilePic::new(self.img_cache, self.jobs, url)
+                .size(pfp_size)
+                .sense(Sense::click()),
+        )
+    }
+
+    fn community_pfp(
+        &mut self,
+        ui: &mut egui::Ui,
+        community: &CommunityRef,
+        pfp_size: f32,
+    ) -> Response {
+        let txn = Transaction::new(self.ndb).unwrap();
+        let image = CommunityDefinition::load(self.ndb, &txn, community).and_then(|c| c.image);
+        let url = image
+            .as_deref()
+            .unwrap_or_else(notedeck::profile::no_pfp_url);
+
+        ui.add(
+            &mut ProfilePic::new(self.img_cache, self.jobs, url)
+                .size(pfp_size)
+                .sense(Sense::click()),
+        )
+    }
+
     fn title_pfp(&mut self, ui: &mut egui::Ui, top: &Route, pfp_size: f32) -> Option<Response> {
         match top {
             Route::Timeline(kind) => match kind {
@@ -502,6 +581,10 @@ impl<'a> NavTitle<'a> {
 
                 TimelineKind::Profile(pubkey) => Some(self.show_profile(ui, pubkey, pfp_size)),
 
+                TimelineKind::Address(address) => {
+                    Some(self.show_profile(ui, &address.pubkey, pfp_size))
+                }
+
                 TimelineKind::Search(_sq) => {
                     // TODO: show author pfp if author field set?
 
@@ -512,7 +595,15 @@ impl<'a> NavTitle<'a> {
                 | TimelineKind::Algo(_)
                 | TimelineKind::Notifications(_)
                 | TimelineKind::Generic(_)
+                | TimelineKind::Bookmarks(_)
+                | TimelineKind::Live(_)
                 | TimelineKind::List(_) => Some(self.timeline_pfp(ui, kind, pfp_size)),
+
+                TimelineKind::Group(group) => Some(self.group_pfp(ui, group, pfp_size)),
+
+                TimelineKind::Community(community) => {
+                    Some(self.community_pfp(ui, community, pfp_size))
+                }
             },
             Route::Reply(_) => None,
             Route::Quote(_) => None,
@@ -522,6 +613,9 @@ impl<'a> NavTitle<'a> {
             Route::Support => None,
             Route::Relays => None,
             Route::Settings => None,
+            Route::MediaServers => None,
+            Route::MuteList => None,
+            Route::Drafts => None,
             Route::NewDeck => None,
             Route::EditDeck(_) => None,
             Route::EditProfile(pubkey) => Some(self.show_profile(ui, pubkey, pfp_size)),
@@ -529,8 +623,9 @@ impl<'a> NavTitle<'a> {
             Route::Wallet(_) => None,
             Route::CustomizeZapAmount(_) => None,
             Route::Thread(thread_selection) => {
-                Some(self.thread_pfp(ui, thread_selection, pfp_size))
+                Some(self.note_pfp(ui, thread_selection.selected_or_root(), pfp_size))
             }
+            Route::Note(note_id) => Some(self.note_pfp(ui, note_id.bytes(), pfp_size)),
             Route::RepostDecision(_) => None,
             Route::Following(pubkey) => Some(self.show_profile(ui, pubkey, pfp_size)),
             Route::FollowedBy(pubkey) => Some(self.show_profile(ui, pubkey, pfp_size)),
@@ -558,15 +653,10 @@ impl<'a> NavTitle<'a> {
         }
     }
 
-    fn thread_pfp(
-        &mut self,
-        ui: &mut egui::Ui,
-        selection: &ThreadSelection,
-        pfp_size: f32,
-    ) -> egui::Response {
+    fn note_pfp(&mut self, ui: &mut egui::Ui, note_id: &[u8; 32], pfp_size: f32) -> egui::Response {
         let txn = Transaction::new(self.ndb).unwrap();
 
-        if let Ok(note) = self.ndb.get_note_by_id(&txn, selection.selected_or_root()) {
+        if let Ok(note) = self.ndb.get_note_by_id(&txn, note_id) {
             if let Some(mut pfp) = self.pubkey_pfp(&txn, note.pubkey(), pfp_size) {
                 return ui.add(&mut pfp);
             }
@@ -617,6 +707,18 @@ impl<'a> NavTitle<'a> {
         self
     }
 
+    /// Show the developer subscription inspector for this column
+    pub fn inspector(&mut self, inspect: Option<TimelineInspect>) -> &mut Self {
+        self.inspect = inspect;
+        self
+    }
+
+    /// Which relays delivered the notes loaded in this column
+    pub fn relay_usage(&mut self, usage: Option<RelayUsage>) -> &mut Self {
+        self.relay_usage = usage;
+        self
+    }
+
     fn should_show_move_button(&self) -> bool {
         (self.options & Self::SHOW_MOVE) == Self::SHOW_MOVE
     }
@@ -647,8 +749,14 @@ impl<'a> NavTitle<'a> {
                     remove_col = self.delete_button_section(ui);
                 }
 
+                if let Some(inspect) = &self.inspect {
+                    super::inspector::inspector_badge(ui, self.i18n, inspect);
+                }
+
                 // Show refresh button for one-shot feeds (e.g. algo feeds)
                 if let Route::Timeline(kind) = top {
+                    self.relay_usage_section(ui);
+
                     if kind.needs_refresh_button() {
                         let resp = ui
                             .add(refresh_button())
@@ -759,6 +867,80 @@ fn refresh_button() -> impl egui::Widget {
     }
 }
 
+fn relay_usage_ui(ui: &mut egui::Ui, i18n: &mut Localization, usage: &RelayUsage) {
+    ui.label(
+        RichText::new(tr_plural!(
+            i18n,
+            "Relays that delivered {count} loaded note", // one
+            "Relays that delivered {count} loaded notes", // other
+            "Heading of the relay usage breakdown for a column",
+            usage.notes,
+        ))
+        .strong(),
+    );
+
+    if usage.relays.is_empty() {
+        ui.weak(tr!(
+            i18n,
+            "No relays yet",
+            "Shown in the relay usage breakdown when no relays are known"
+        ));
+        return;
+    }
+
+    let bar_width = 80.0;
+    let accent = ui.visuals().selection.bg_fill;
+
+    egui::Grid::new("relay-usage-grid")
+        .num_columns(3)
+        .spacing([8.0, 4.0])
+        .show(ui, |ui| {
+            for (relay, count) in &usage.relays {
+                let share = if usage.notes == 0 {
+                    0.0
+                } else {
+                    *count as f32 / usage.notes as f32
+                };
+
+                let (rect, _) = ui.allocate_exact_size(egui::vec2(bar_width, 8.0), Sense::hover());
+                let painter = ui.painter_at(rect);
+                painter.rect_filled(rect, 2.0, ui.visuals().faint_bg_color);
+                let mut filled = rect;
+                filled.set_width(bar_width * share);
+                painter.rect_filled(filled, 2.0, accent.gamma_multiply(0.4 + 0.6 * share));
+
+                let url = RichText::new(relay.to_string()).small();
+                if *count == 0 {
+                    ui.label(url.weak());
+                } else {
+                    ui.label(url);
+                }
+                ui.label(RichText::new(count.to_string()).small().monospace());
+                ui.end_row();
+            }
+        });
+}
+
+fn relay_usage_button() -> impl egui::Widget {
+    |ui: &mut egui::Ui| -> egui::Response {
+        let max_size = egui::vec2(20.0, 20.0);
+        let helper = AnimationHelper::new(ui, "relay-usage", max_size);
+        let painter = ui.painter_at(helper.get_animation_rect());
+        let color = ui.style().visuals.noninteractive().fg_stroke.color;
+        let stroke = Stroke::new(helper.scale_1d_pos(2.0), color);
+
+        // three bars of a small bar chart, resting on a common baseline
+        let base = 5.0;
+        for (x, height) in [(-4.0, 4.0), (0.0, 10.0), (4.0, 7.0)] {
+            let bottom = helper.scale_from_center(x, base);
+            let top = helper.scale_from_center(x, base - height);
+            painter.line_segment([bottom, top], stroke);
+        }
+
+        helper.take_animation_response()
+    }
+}
+
 fn grab_button() -> impl egui::Widget {
     |ui: &mut egui::Ui| -> egui::Response {
         let max_size = egui::vec2(20.0, 20.0);
@@ -0,0 +1,240 @@
+use egui::{RichText, Ui};
+use enostr::RelayReqStatus;
+use nostrdb::Filter;
+use notedeck::{tr, tr_plural, Localization, ScopedSubDiagnostics};
+
+use crate::timeline::TimelineInspect;
+
+/// Compact developer badge for a column header: relays at EOSE and note
+/// throughput, with the full subscription details on hover.
+pub fn inspector_badge(
+    ui: &mut Ui,
+    i18n: &mut Localization,
+    inspect: &TimelineInspect,
+) -> egui::Response {
+    let (eosed, total) = inspect.remote.as_ref().map_or((0, 0), |remote| {
+        let eosed = remote
+            .relays
+            .iter()
+            .filter(|(_, status)| *status == RelayReqStatus::Eose)
+            .count();
+        (eosed, remote.relays.len())
+    });
+
+    let summary = tr!(
+        i18n,
+        "{eosed}/{total} eose · {rate}/min",
+        "Column inspector badge: relays that sent EOSE out of all relays, and notes per minute",
+        eosed = eosed,
+        total = total,
+        rate = inspect.notes_per_minute
+    );
+
+    ui.add(egui::Label::new(RichText::new(summary).small().weak()).selectable(false))
+        .on_hover_ui(|ui| inspector_details(ui, i18n, inspect))
+}
+
+fn inspector_details(ui: &mut Ui, i18n: &mut Localization, inspect: &TimelineInspect) {
+    ui.set_max_width(480.0);
+
+    ui.label(
+        RichText::new(tr!(
+            i18n,
+            "local filters",
+            "Column inspector heading for the nostrdb filters of a column"
+        ))
+        .strong(),
+    );
+    filter_list(ui, i18n, &inspect.local_filters);
+
+    ui.separator();
+
+    let Some(remote) = &inspect.remote else {
+        ui.label(
+            RichText::new(tr!(
+                i18n,
+                "no remote subscription",
+                "Column inspector note for a column that doesn't subscribe to relays"
+            ))
+            .weak(),
+        );
+        return;
+    };
+
+    ui.label(
+        RichText::new(tr!(
+            i18n,
+            "remote filters",
+            "Column inspector heading for the relay filters of a column"
+        ))
+        .strong(),
+    );
+    filter_list(ui, i18n, &remote.filters);
+
+    ui.separator();
+
+    ui.label(
+        RichText::new(tr!(
+            i18n,
+            "relays",
+            "Column inspector heading for the relays a column subscribes to"
+        ))
+        .strong(),
+    );
+    if remote.relays.is_empty() {
+        ui.label(RichText::new(inactive(i18n)).weak());
+    }
+    for (relay, status) in &remote.relays {
+        ui.horizontal(|ui| {
+            ui.label(RichText::new(status_label(i18n, *status)).monospace());
+            ui.label(relay.to_string());
+        });
+    }
+
+    ui.separator();
+    ui.label(tr_plural!(
+        i18n,
+        "{count} note in the last minute",  // one
+        "{count} notes in the last minute", // other
+        "Column inspector note throughput",
+        inspect.notes_per_minute,
+    ));
+}
+
+/// Every declared remote subscription, for the relay view in debug mode.
+/// Each row summarizes one subscription and expands into its filters and
+/// relay legs.
+pub fn subscriptions_ui(ui: &mut Ui, i18n: &mut Localization, subs: &[ScopedSubDiagnostics]) {
+    ui.label(
+        RichText::new(tr_plural!(
+            i18n,
+            "{count} subscription",  // one
+            "{count} subscriptions", // other
+            "Heading of the relay subscription list in debug mode",
+            subs.len(),
+        ))
+        .strong(),
+    );
+
+    for sub in subs {
+        let eosed = sub
+            .relays
+            .iter()
+            .filter(|(_, status)| *status == RelayReqStatus::Eose)
+            .count();
+        let state = if sub.relays.is_empty() {
+            inactive(i18n)
+        } else {
+            tr!(
+                i18n,
+                "{eosed}/{total} eose",
+                "Relays that sent EOSE for a subscription out of all its relays",
+                eosed = eosed,
+                total = sub.relays.len()
+            )
+        };
+        let latency = sub.eose_latency.map_or_else(String::new, |latency| {
+            tr!(
+                i18n,
+                " in {ms}ms",
+                "How long a subscription took to reach EOSE, appended to its state",
+                ms = latency.as_millis() as u64
+            )
+        });
+        let account = sub.account.map_or_else(
+            || {
+                tr!(
+                    i18n,
+                    "global",
+                    "Owner of a subscription that isn't tied to an account"
+                )
+            },
+            |pk| pk.hex()[..8].to_owned(),
+        );
+        let age = tr!(
+            i18n,
+            "{secs}s old",
+            "How long ago a subscription was declared",
+            secs = sub.age.as_secs()
+        );
+        let summary = format!(
+            "{:016x} · {account} · {state}{latency} · {age}",
+            sub.key.as_u64()
+        );
+
+        egui::CollapsingHeader::new(RichText::new(summary).monospace().small())
+            .id_salt(("subscription", sub.key.as_u64(), sub.account))
+            .show(ui, |ui| {
+                ui.label(tr_plural!(
+                    i18n,
+                    "{count} owner",  // one
+                    "{count} owners", // other
+                    "How many views share a subscription",
+                    sub.owners,
+                ));
+                filter_list(ui, i18n, &sub.filters);
+                for (relay, status) in &sub.relays {
+                    ui.horizontal(|ui| {
+                        ui.label(RichText::new(status_label(i18n, *status)).monospace());
+                        ui.label(relay.to_string());
+                    });
+                }
+            });
+    }
+}
+
+fn filter_list(ui: &mut Ui, i18n: &mut Localization, filters: &[Filter]) {
+    if filters.is_empty() {
+        ui.label(
+            RichText::new(tr!(
+                i18n,
+                "none",
+                "Shown in the inspector when there are no filters"
+            ))
+            .weak(),
+        );
+        return;
+    }
+
+    for filter in filters {
+        let json = filter.json().unwrap_or_else(|e| {
+            tr!(
+                i18n,
+                "<invalid filter: {error}>",
+                "Shown in the inspector for a filter that can't be printed",
+                error = e.to_string()
+            )
+        });
+        ui.label(RichText::new(json).monospace().small());
+    }
+}
+
+fn inactive(i18n: &mut Localization) -> String {
+    tr!(
+        i18n,
+        "inactive",
+        "State of a subscription that isn't sent to any relay"
+    )
+}
+
+/// Padded so the relay urls after it line up
+fn status_label(i18n: &mut Localization, status: RelayReqStatus) -> String {
+    let label = match status {
+        RelayReqStatus::InitialQuery => tr!(
+            i18n,
+            "query",
+            "Relay subscription state: waiting for stored notes"
+        ),
+        RelayReqStatus::Eose => tr!(
+            i18n,
+            "eose",
+            "Relay subscription state: stored notes were all sent"
+        ),
+        RelayReqStatus::Closed => tr!(
+            i18n,
+            "closed",
+            "Relay subscription state: closed by the relay"
+        ),
+    };
+    format!("{label:<6}")
+}
@@ -1,3 +1,5 @@
 mod header;
+mod inspector;
 
 pub use header::NavTitle;
+pub use inspector::subscriptions_ui;
@@ -0,0 +1,229 @@
+use egui::{Align, Button, Frame, Id, Layout, Margin, RichText, TextEdit, Ui};
+use enostr::NoteId;
+use nostrdb::{Ndb, Note, Transaction};
+use notedeck::{name::get_display_name, tr, Accounts, Localization, NotedeckTextStyle};
+
+use crate::{
+    community::{pending_posts, CommunityAction, CommunityDefinition},
+    timeline::kind::CommunityRef,
+};
+
+/// How much of a pending post moderators see before approving it
+const PREVIEW_CHARS: usize = 280;
+
+/// What a community column shows above its approved posts: the community's
+/// description and moderators, a post box and, for moderators, the posts
+/// waiting for approval
+pub struct CommunityView<'a> {
+    community: &'a CommunityRef,
+    ndb: &'a Ndb,
+    accounts: &'a Accounts,
+    i18n: &'a mut Localization,
+}
+
+impl<'a> CommunityView<'a> {
+    pub fn new(
+        community: &'a CommunityRef,
+        ndb: &'a Ndb,
+        accounts: &'a Accounts,
+        i18n: &'a mut Localization,
+    ) -> Self {
+        Self {
+            community,
+            ndb,
+            accounts,
+            i18n,
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui) -> Option<CommunityAction> {
+        let Ok(txn) = Transaction::new(self.ndb) else {
+            return None;
+        };
+
+        Frame::new()
+            .inner_margin(Margin::symmetric(10, 8))
+            .show(ui, |ui| {
+                let Some(definition) = CommunityDefinition::load(self.ndb, &txn, self.community)
+                else {
+                    ui.label(
+                        RichText::new(tr!(
+                            self.i18n,
+                            "Looking for this community…",
+                            "Shown while waiting for a NIP-72 community's definition"
+                        ))
+                        .weak(),
+                    );
+                    return None;
+                };
+
+                self.show_definition(ui, &txn, &definition);
+                ui.add_space(8.0);
+
+                let pending = pending_posts(self.ndb, &txn, &definition);
+                let mut action = self.show_pending(ui, &txn, &definition, &pending);
+                action = action.or(self.show_post_box(ui, &definition, &pending));
+                action
+            })
+            .inner
+    }
+
+    fn show_definition(
+        &mut self,
+        ui: &mut Ui,
+        txn: &Transaction,
+        definition: &CommunityDefinition,
+    ) {
+        if let Some(description) = definition.description.as_deref().filter(|d| !d.is_empty()) {
+            ui.label(RichText::new(description).text_style(NotedeckTextStyle::Small.text_style()));
+        }
+
+        if definition.moderators.is_empty() {
+            return;
+        }
+
+        let title = tr!(
+            self.i18n,
+            "Moderators ({count})",
+            "Collapsible list of a NIP-72 community's moderators",
+            count = definition.moderators.len()
+        );
+        egui::CollapsingHeader::new(title)
+            .id_salt(("community_moderators", self.community))
+            .show(ui, |ui| {
+                for moderator in &definition.moderators {
+                    let profile = self.ndb.get_profile_by_pubkey(txn, moderator.bytes());
+                    let name = get_display_name(profile.as_ref().ok()).name();
+                    ui.label(RichText::new(name).strong());
+                }
+            });
+    }
+
+    /// Posts waiting for a moderator, with a button to approve each one.
+    /// Only moderators see these.
+    fn show_pending(
+        &mut self,
+        ui: &mut Ui,
+        txn: &Transaction,
+        definition: &CommunityDefinition,
+        pending: &[Note],
+    ) -> Option<CommunityAction> {
+        let kp = self.accounts.selected_filled()?;
+        if !definition.can_moderate(kp.pubkey) || pending.is_empty() {
+            return None;
+        }
+
+        let title = tr!(
+            self.i18n,
+            "Pending posts ({count})",
+            "Collapsible list of community posts waiting for a moderator's approval",
+            count = pending.len()
+        );
+        let approve = tr!(
+            self.i18n,
+            "Approve",
+            "Button to approve a post to a NIP-72 community"
+        );
+
+        let mut action = None;
+        egui::CollapsingHeader::new(title)
+            .id_salt(("community_pending", self.community))
+            .show(ui, |ui| {
+                for post in pending {
+                    let profile = self.ndb.get_profile_by_pubkey(txn, post.pubkey());
+                    let name = get_display_name(profile.as_ref().ok()).name();
+                    let mut preview: String = post.content().chars().take(PREVIEW_CHARS).collect();
+                    if preview.len() < post.content().len() {
+                        preview.push('…');
+                    }
+
+                    ui.horizontal(|ui| {
+                        ui.label(RichText::new(name).strong());
+                        ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                            if ui.button(&approve).clicked() {
+                                action = Some(CommunityAction::Approve {
+                                    community: self.community.clone(),
+                                    post: NoteId::new(*post.id()),
+                                });
+                            }
+                        });
+                    });
+                    ui.label(
+                        RichText::new(preview).text_style(NotedeckTextStyle::Small.text_style()),
+                    );
+                    ui.separator();
+                }
+            });
+
+        action
+    }
+
+    fn show_post_box(
+        &mut self,
+        ui: &mut Ui,
+        definition: &CommunityDefinition,
+        pending: &[Note],
+    ) -> Option<CommunityAction> {
+        let kp = self.accounts.selected_filled()?;
+
+        let waiting = pending
+            .iter()
+            .filter(|post| post.pubkey() == kp.pubkey.bytes())
+            .count();
+        if waiting > 0 {
+            ui.label(
+                RichText::new(tr!(
+                    self.i18n,
+                    "{count} of your posts are waiting for a moderator",
+                    "Number of our own community posts that aren't approved yet",
+                    count = waiting
+                ))
+                .text_style(NotedeckTextStyle::Small.text_style())
+                .weak(),
+            );
+        }
+
+        let id = Id::new(("community_post", self.community));
+        let mut text = ui.data_mut(|d| d.get_temp::<String>(id).unwrap_or_default());
+
+        let hint = tr!(
+            self.i18n,
+            "Post to {name}",
+            "Placeholder of the post box of a NIP-72 community",
+            name = definition.display_name()
+        );
+
+        let mut send = false;
+        ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+            send = ui
+                .add_enabled(
+                    !text.trim().is_empty(),
+                    Button::new(tr!(
+                        self.i18n,
+                        "Post",
+                        "Button to post to a NIP-72 community"
+                    )),
+                )
+                .clicked();
+
+            let resp = ui.add(
+                TextEdit::singleline(&mut text)
+                    .hint_text(hint)
+                    .desired_width(f32::INFINITY),
+            );
+            send |= resp.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+        });
+
+        let content = text.trim().to_owned();
+        if send && !content.is_empty() {
+            ui.data_mut(|d| d.remove::<String>(id));
+            return Some(CommunityAction::Post {
+                community: self.community.clone(),
+                content,
+            });
+        }
+
+        ui.data_mut(|d| d.insert_temp(id, text));
+        None
+    }
+}
@@ -0,0 +1,227 @@
+use egui::{Align, Button, Frame, Layout, Margin, RichText, Ui};
+use nostrdb::{Ndb, Transaction};
+use notedeck::{name::get_display_name, tr, DragResponse, Localization, NotedeckTextStyle};
+
+use crate::{
+    draft::{DraftTarget, Drafts},
+    scheduled_posts::{format_publish_time, ScheduledPost},
+};
+
+/// Characters of a draft shown in the list
+const PREVIEW_CHARS: usize = 200;
+
+pub enum DraftsAction {
+    /// Open the composer the draft belongs to
+    Resume(DraftTarget),
+    Discard(DraftTarget),
+    /// Drop a scheduled post before it goes out
+    CancelScheduled(u64),
+}
+
+/// The unfinished notes, replies and quotes kept from earlier sessions,
+/// and the posts waiting for their publish time
+pub struct DraftsView<'a> {
+    ndb: &'a Ndb,
+    txn: &'a Transaction,
+    drafts: &'a Drafts,
+    scheduled: &'a [ScheduledPost],
+    i18n: &'a mut Localization,
+}
+
+impl<'a> DraftsView<'a> {
+    pub fn new(
+        ndb: &'a Ndb,
+        txn: &'a Transaction,
+        drafts: &'a Drafts,
+        scheduled: &'a [ScheduledPost],
+        i18n: &'a mut Localization,
+    ) -> Self {
+        Self {
+            ndb,
+            txn,
+            drafts,
+            scheduled,
+            i18n,
+        }
+    }
+
+    pub fn scroll_id() -> egui::Id {
+        egui::Id::new("drafts_scroll")
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui) -> DragResponse<DraftsAction> {
+        let scroll_out = Frame::new()
+            .inner_margin(Margin::symmetric(10, 0))
+            .show(ui, |ui| {
+                ui.add_space(24.0);
+
+                ui.label(
+                    RichText::new(tr!(
+                        self.i18n,
+                        "Drafts",
+                        "Heading of the page listing unfinished notes"
+                    ))
+                    .text_style(NotedeckTextStyle::Heading2.text_style()),
+                );
+
+                ui.add_space(8.0);
+
+                egui::ScrollArea::vertical()
+                    .id_salt(Self::scroll_id())
+                    .auto_shrink([false; 2])
+                    .show(ui, |ui| {
+                        let action = self.show_scheduled(ui);
+                        action.or(self.show_drafts(ui))
+                    })
+            })
+            .inner;
+
+        DragResponse::scroll(scroll_out)
+    }
+
+    fn show_drafts(&mut self, ui: &mut Ui) -> Option<DraftsAction> {
+        let drafts = self.drafts.unfinished();
+        if drafts.is_empty() {
+            ui.label(
+                RichText::new(tr!(
+                    self.i18n,
+                    "No unfinished notes",
+                    "Shown when there are no saved drafts"
+                ))
+                .weak(),
+            );
+            return None;
+        }
+
+        let mut action = None;
+        for (target, draft) in drafts {
+            let title = self.title(&target);
+            let preview = preview(&draft.buffer.text_buffer);
+
+            ui.label(RichText::new(title).strong());
+            if !preview.is_empty() {
+                ui.label(RichText::new(preview).text_style(NotedeckTextStyle::Small.text_style()));
+            }
+
+            ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                if ui
+                    .add(Button::new(tr!(
+                        self.i18n,
+                        "Discard",
+                        "Button to delete a saved draft"
+                    )))
+                    .clicked()
+                {
+                    action = Some(DraftsAction::Discard(target));
+                }
+
+                if ui
+                    .add(Button::new(tr!(
+                        self.i18n,
+                        "Resume",
+                        "Button to keep writing a saved draft"
+                    )))
+                    .clicked()
+                {
+                    action = Some(DraftsAction::Resume(target));
+                }
+            });
+
+            ui.add_space(12.0);
+        }
+
+        action
+    }
+
+    fn show_scheduled(&mut self, ui: &mut Ui) -> Option<DraftsAction> {
+        if self.scheduled.is_empty() {
+            return None;
+        }
+
+        ui.label(
+            RichText::new(tr!(
+                self.i18n,
+                "Scheduled",
+                "Section of the drafts page listing posts waiting for their publish time"
+            ))
+            .text_style(NotedeckTextStyle::Body.text_style())
+            .strong(),
+        );
+        ui.add_space(4.0);
+
+        let mut action = None;
+        for post in self.scheduled {
+            let title = self.title(&post.target);
+            let when = format_publish_time(post.publish_at);
+
+            ui.label(RichText::new(format!("{when} · {title}")).strong());
+            ui.label(
+                RichText::new(preview(&post.content))
+                    .text_style(NotedeckTextStyle::Small.text_style()),
+            );
+            if let Some(error) = &post.error {
+                ui.label(RichText::new(error).color(ui.visuals().error_fg_color));
+            }
+
+            ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                if ui
+                    .add(Button::new(tr!(
+                        self.i18n,
+                        "Cancel",
+                        "Button to drop a scheduled post before it is published"
+                    )))
+                    .clicked()
+                {
+                    action = Some(DraftsAction::CancelScheduled(post.id));
+                }
+            });
+
+            ui.add_space(12.0);
+        }
+
+        ui.add_space(12.0);
+        action
+    }
+
+    fn title(&mut self, target: &DraftTarget) -> String {
+        let (note_id, is_reply) = match target {
+            DraftTarget::Compose => {
+                return tr!(self.i18n, "New note", "Title of the draft of a new note");
+            }
+            DraftTarget::Reply(id) => (id, true),
+            DraftTarget::Quote(id) => (id, false),
+        };
+
+        let profile = self
+            .ndb
+            .get_note_by_id(self.txn, note_id.bytes())
+            .ok()
+            .and_then(|note| self.ndb.get_profile_by_pubkey(self.txn, note.pubkey()).ok());
+        let name = get_display_name(profile.as_ref()).name();
+
+        if is_reply {
+            tr!(
+                self.i18n,
+                "Reply to {name}",
+                "Title of the draft of a reply",
+                name = name
+            )
+        } else {
+            tr!(
+                self.i18n,
+                "Quote of {name}",
+                "Title of the draft of a quote",
+                name = name
+            )
+        }
+    }
+}
+
+fn preview(text: &str) -> String {
+    let mut chars = text.chars();
+    let mut preview: String = chars.by_ref().take(PREVIEW_CHARS).collect();
+    if chars.next().is_some() {
+        preview.push('…');
+    }
+    preview
+}
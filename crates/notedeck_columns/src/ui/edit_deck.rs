@@ -6,6 +6,13 @@ use super::configure_deck::{ConfigureDeckResponse, ConfigureDeckView};
 use notedeck::{tr, Localization};
 use notedeck_ui::padding;
 
+/// Only desktop builds have a file dialog for exporting and importing decks
+pub const SHOW_FILE_BUTTONS: bool = cfg!(any(
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "linux"
+));
+
 pub struct EditDeckView<'a> {
     config_view: ConfigureDeckView<'a>,
 }
@@ -13,6 +20,10 @@ pub struct EditDeckView<'a> {
 pub enum EditDeckResponse {
     Edit(ConfigureDeckResponse),
     Delete,
+    /// Save the deck layout to a file
+    Export,
+    /// Publish the deck layout as an event
+    Publish,
 }
 
 impl<'a> EditDeckView<'a> {
@@ -31,6 +42,28 @@ impl<'a> EditDeckView<'a> {
             }
         });
 
+        padding(egui::Margin::symmetric(16, 4), ui, |ui| {
+            ui.horizontal(|ui| {
+                let export = tr!(
+                    self.config_view.i18n,
+                    "Export Deck",
+                    "Button label to save a deck layout to a file"
+                );
+                if SHOW_FILE_BUTTONS && ui.button(export).clicked() {
+                    edit_deck_resp = Some(EditDeckResponse::Export);
+                }
+
+                let publish = tr!(
+                    self.config_view.i18n,
+                    "Publish Deck",
+                    "Button label to share a deck layout as a nostr event"
+                );
+                if ui.button(publish).clicked() {
+                    edit_deck_resp = Some(EditDeckResponse::Publish);
+                }
+            });
+        });
+
         if let Some(config_resp) = self.config_view.ui(ui) {
             edit_deck_resp = Some(EditDeckResponse::Edit(config_resp))
         }
@@ -39,6 +72,20 @@ impl<'a> EditDeckView<'a> {
     }
 }
 
+/// Shown above the new deck form to add a deck someone exported instead
+pub fn import_deck_button<'a>(i18n: &'a mut Localization) -> impl Widget + 'a {
+    |ui: &mut egui::Ui| {
+        padding(egui::Margin::symmetric(16, 4), ui, |ui| {
+            ui.button(tr!(
+                i18n,
+                "Import Deck",
+                "Button label to add a deck from an exported deck file"
+            ))
+        })
+        .inner
+    }
+}
+
 fn delete_button<'a>(i18n: &'a mut Localization) -> impl Widget + 'a {
     |ui: &mut egui::Ui| {
         let size = egui::vec2(108.0, 40.0);
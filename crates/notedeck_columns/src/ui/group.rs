@@ -0,0 +1,193 @@
+use egui::{Align, Button, Frame, Id, Layout, Margin, RichText, TextEdit, Ui};
+use nostrdb::{Ndb, Transaction};
+use notedeck::{name::get_display_name, tr, Accounts, Localization, NotedeckTextStyle};
+
+use crate::{
+    group::{GroupAction, GroupInfo},
+    timeline::kind::GroupRef,
+};
+
+/// What a group column shows above its messages: the group's moderation
+/// metadata, a join button and the message box
+pub struct GroupView<'a> {
+    group: &'a GroupRef,
+    ndb: &'a Ndb,
+    accounts: &'a Accounts,
+    i18n: &'a mut Localization,
+}
+
+impl<'a> GroupView<'a> {
+    pub fn new(
+        group: &'a GroupRef,
+        ndb: &'a Ndb,
+        accounts: &'a Accounts,
+        i18n: &'a mut Localization,
+    ) -> Self {
+        Self {
+            group,
+            ndb,
+            accounts,
+            i18n,
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui) -> Option<GroupAction> {
+        let Ok(txn) = Transaction::new(self.ndb) else {
+            return None;
+        };
+        let info = GroupInfo::load(self.ndb, &txn, self.group);
+
+        Frame::new()
+            .inner_margin(Margin::symmetric(10, 8))
+            .show(ui, |ui| {
+                self.show_metadata(ui, &txn, &info);
+                ui.add_space(8.0);
+
+                let mut action = self.show_join(ui, &info);
+                action = action.or(self.show_message_box(ui, &info));
+                action
+            })
+            .inner
+    }
+
+    fn show_metadata(&mut self, ui: &mut Ui, txn: &Transaction, info: &GroupInfo) {
+        let metadata = info.metadata.clone().unwrap_or_default();
+
+        if let Some(about) = metadata.about.as_deref().filter(|a| !a.is_empty()) {
+            ui.label(RichText::new(about).text_style(NotedeckTextStyle::Small.text_style()));
+        }
+
+        let visibility = if metadata.private {
+            tr!(self.i18n, "Private", "A NIP-29 group only members can read")
+        } else {
+            tr!(self.i18n, "Public", "A NIP-29 group anyone can read")
+        };
+        let joining = if metadata.closed {
+            tr!(
+                self.i18n,
+                "Closed",
+                "A NIP-29 group where admins approve join requests"
+            )
+        } else {
+            tr!(self.i18n, "Open", "A NIP-29 group anyone can join")
+        };
+        let relay = self.group.relay.to_string();
+        ui.label(
+            RichText::new(format!("{visibility} · {joining} · {relay}"))
+                .text_style(NotedeckTextStyle::Small.text_style())
+                .weak(),
+        );
+
+        if info.admins.is_empty() {
+            return;
+        }
+
+        let title = tr!(
+            self.i18n,
+            "Moderators ({count})",
+            "Collapsible list of a group's admins",
+            count = info.admins.len()
+        );
+        egui::CollapsingHeader::new(title)
+            .id_salt(("group_admins", &self.group.relay, &self.group.id))
+            .show(ui, |ui| {
+                for admin in &info.admins {
+                    let profile = self.ndb.get_profile_by_pubkey(txn, admin.pubkey.bytes());
+                    let name = get_display_name(profile.as_ref().ok()).name();
+                    let roles = admin.roles.join(", ");
+                    ui.horizontal_wrapped(|ui| {
+                        ui.label(RichText::new(name).strong());
+                        if !roles.is_empty() {
+                            ui.label(RichText::new(roles).weak());
+                        }
+                    });
+                }
+            });
+    }
+
+    fn show_join(&mut self, ui: &mut Ui, info: &GroupInfo) -> Option<GroupAction> {
+        let kp = self.accounts.selected_filled()?;
+        if info.is_member(kp.pubkey) {
+            return None;
+        }
+
+        let requested_id = Id::new(("group_join_requested", &self.group.relay, &self.group.id));
+        if ui.data_mut(|d| *d.get_temp_mut_or_default::<bool>(requested_id)) {
+            ui.label(
+                RichText::new(tr!(
+                    self.i18n,
+                    "Join request sent",
+                    "Shown after asking a group's relay to join"
+                ))
+                .weak(),
+            );
+            return None;
+        }
+
+        let closed = info.metadata.as_ref().is_some_and(|m| m.closed);
+        let label = if closed {
+            tr!(
+                self.i18n,
+                "Request to join",
+                "Button to ask the admins of a closed group to let us in"
+            )
+        } else {
+            tr!(self.i18n, "Join group", "Button to join a NIP-29 group")
+        };
+
+        if ui.add(Button::new(label)).clicked() {
+            ui.data_mut(|d| d.insert_temp(requested_id, true));
+            return Some(GroupAction::Join(self.group.clone()));
+        }
+
+        None
+    }
+
+    fn show_message_box(&mut self, ui: &mut Ui, info: &GroupInfo) -> Option<GroupAction> {
+        self.accounts.selected_filled()?;
+
+        let id = Id::new(("group_message", &self.group.relay, &self.group.id));
+        let mut text = ui.data_mut(|d| d.get_temp::<String>(id).unwrap_or_default());
+
+        let name = info
+            .metadata
+            .as_ref()
+            .map(|m| m.display_name().to_owned())
+            .unwrap_or_else(|| self.group.id.clone());
+        let hint = tr!(
+            self.i18n,
+            "Message {name}",
+            "Placeholder of the message box of a group",
+            name = name
+        );
+
+        let mut send = false;
+        ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+            send = ui
+                .add_enabled(
+                    !text.trim().is_empty(),
+                    Button::new(tr!(self.i18n, "Send", "Button to send a group message")),
+                )
+                .clicked();
+
+            let resp = ui.add(
+                TextEdit::singleline(&mut text)
+                    .hint_text(hint)
+                    .desired_width(f32::INFINITY),
+            );
+            send |= resp.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+        });
+
+        let content = text.trim().to_owned();
+        if send && !content.is_empty() {
+            ui.data_mut(|d| d.remove::<String>(id));
+            return Some(GroupAction::Send {
+                group: self.group.clone(),
+                content,
+            });
+        }
+
+        ui.data_mut(|d| d.insert_temp(id, text));
+        None
+    }
+}
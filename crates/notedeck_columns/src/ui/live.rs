@@ -0,0 +1,107 @@
+use nostrdb::Transaction;
+use notedeck::{
+    filter::default_limit, live_now, tr, unix_time_secs, DragResponse, FilterState, LiveEvent,
+    NoteAction, NoteContext,
+};
+use notedeck_ui::{note::live_event_card, NoteOptions};
+
+use crate::timeline::{TimelineCache, TimelineKind};
+
+/// The streams of a live column that are live right now. Live events are
+/// replaced as they go on, so rather than a timeline of every version
+/// this shows the latest one of each stream still running.
+pub struct LiveView<'a, 'd> {
+    kind: &'a TimelineKind,
+    timeline_cache: &'a TimelineCache,
+    note_context: &'a mut NoteContext<'d>,
+    note_options: NoteOptions,
+    col: usize,
+}
+
+impl<'a, 'd> LiveView<'a, 'd> {
+    pub fn new(
+        kind: &'a TimelineKind,
+        timeline_cache: &'a TimelineCache,
+        note_context: &'a mut NoteContext<'d>,
+        note_options: NoteOptions,
+        col: usize,
+    ) -> Self {
+        Self {
+            kind,
+            timeline_cache,
+            note_context,
+            note_options,
+            col,
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> DragResponse<NoteAction> {
+        let txn = Transaction::new(self.note_context.ndb).expect("txn");
+
+        let output = egui::ScrollArea::vertical()
+            .id_salt(("livescroll", self.col))
+            .animated(false)
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                egui::Frame::new()
+                    .inner_margin(egui::Margin::symmetric(8, 4))
+                    .show(ui, |ui| self.streams(ui, &txn))
+                    .inner
+            });
+
+        DragResponse::output(output.inner).scroll_raw(output.id)
+    }
+
+    fn streams(&mut self, ui: &mut egui::Ui, txn: &Transaction) -> Option<NoteAction> {
+        let filters = match self.timeline_cache.get(self.kind).map(|tl| &tl.filter) {
+            Some(FilterState::Ready(filter)) => filter.local().combined(),
+            _ => {
+                ui.add_space(16.0);
+                ui.vertical_centered(|ui| {
+                    ui.spinner();
+                    ui.label(tr!(
+                        self.note_context.i18n,
+                        "Looking for the people you follow…",
+                        "Shown while a live streams column waits for the contact list"
+                    ));
+                });
+                return None;
+            }
+        };
+
+        let notes = self
+            .note_context
+            .ndb
+            .query(txn, &filters, default_limit() as i32)
+            .map(|results| results.into_iter().map(|r| r.note).collect::<Vec<_>>())
+            .unwrap_or_default();
+        let live = live_now(notes, unix_time_secs());
+
+        if live.is_empty() {
+            ui.add_space(16.0);
+            ui.vertical_centered(|ui| {
+                ui.label(tr!(
+                    self.note_context.i18n,
+                    "Nobody you follow is live right now",
+                    "Shown in a live streams column when no stream is running"
+                ));
+            });
+            return None;
+        }
+
+        let mut action = None;
+        for note in &live {
+            let Some(event) = LiveEvent::from_note(note) else {
+                continue;
+            };
+
+            ui.add_space(4.0);
+            let resp = live_event_card(ui, self.note_context, txn, note, &event, self.note_options);
+            if let Some(card_action) = resp.inner {
+                action = Some(card_action);
+            }
+        }
+
+        action
+    }
+}
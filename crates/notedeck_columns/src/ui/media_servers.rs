@@ -0,0 +1,388 @@
+use egui::{Align, Button, Frame, Layout, Margin, RichText, Ui};
+use enostr::Pubkey;
+use nostrdb::Ndb;
+use notedeck::{
+    tr, Accounts, DragResponse, Localization, NotedeckTextStyle, RemoteApi, SettingsHandler,
+};
+use notedeck_ui::colors::PINK;
+
+use crate::{
+    media_servers::{normalize_server_url, publish_server_list, MediaServersState, ServerProbe},
+    media_upload::blossom_probe,
+};
+
+const NOSTR_BUILD: &str = "nostr.build";
+
+pub enum MediaServersAction {
+    Add(String),
+    Remove(String),
+    Probe(String),
+    /// Where the composer uploads to, the NIP-96 server when `None`
+    SetUploadServer(Option<String>),
+    /// NIP-96 server used when no Blossom server is picked, nostr.build
+    /// when `None`
+    SetNip96Server(Option<String>),
+}
+
+impl MediaServersAction {
+    pub fn process(
+        self,
+        ndb: &Ndb,
+        accounts: &Accounts,
+        remote: &mut RemoteApi<'_>,
+        settings: &mut SettingsHandler,
+        state: &mut MediaServersState,
+        servers: &[String],
+    ) {
+        let Some(kp) = accounts.selected_filled() else {
+            return;
+        };
+
+        match self {
+            Self::Add(server) => {
+                if servers.contains(&server) {
+                    return;
+                }
+
+                let mut servers = servers.to_vec();
+                servers.push(server);
+                publish_server_list(ndb, &mut remote.publisher(accounts), kp, &servers);
+            }
+            Self::Remove(server) => {
+                let remaining: Vec<String> =
+                    servers.iter().filter(|s| **s != server).cloned().collect();
+                publish_server_list(ndb, &mut remote.publisher(accounts), kp, &remaining);
+                state.probes.remove(&server);
+
+                if settings.upload_server(kp.pubkey) == Some(server.as_str()) {
+                    settings.set_upload_server(kp.pubkey, None);
+                }
+            }
+            Self::Probe(server) => {
+                let promise = blossom_probe(kp.secret_key.secret_bytes(), &server);
+                state.probes.insert(server, ServerProbe::Running(promise));
+            }
+            Self::SetUploadServer(server) => settings.set_upload_server(kp.pubkey, server),
+            Self::SetNip96Server(server) => {
+                settings.set_nip96_server(kp.pubkey, server);
+                // picking a NIP-96 server means uploading to it
+                settings.set_upload_server(kp.pubkey, None);
+            }
+        }
+    }
+}
+
+pub struct MediaServersView<'a> {
+    servers: &'a [String],
+    upload_server: Option<&'a str>,
+    nip96_server: Option<&'a str>,
+    /// Only accounts with a secret key can publish a server list or sign
+    /// upload probes
+    can_sign: bool,
+    state: &'a mut MediaServersState,
+    i18n: &'a mut Localization,
+}
+
+impl<'a> MediaServersView<'a> {
+    pub fn new(
+        servers: &'a [String],
+        upload_server: Option<&'a str>,
+        nip96_server: Option<&'a str>,
+        can_sign: bool,
+        state: &'a mut MediaServersState,
+        i18n: &'a mut Localization,
+    ) -> Self {
+        Self {
+            servers,
+            upload_server,
+            nip96_server,
+            can_sign,
+            state,
+            i18n,
+        }
+    }
+
+    pub fn scroll_id(pubkey: &Pubkey) -> egui::Id {
+        egui::Id::new(("media_servers_scroll", pubkey))
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui, pubkey: &Pubkey) -> DragResponse<MediaServersAction> {
+        let scroll_out = Frame::new()
+            .inner_margin(Margin::symmetric(10, 0))
+            .show(ui, |ui| {
+                ui.add_space(24.0);
+
+                ui.label(
+                    RichText::new(tr!(
+                        self.i18n,
+                        "Media Servers",
+                        "Heading of the page for managing Blossom media servers"
+                    ))
+                    .text_style(NotedeckTextStyle::Heading2.text_style()),
+                );
+
+                ui.add_space(4.0);
+                ui.label(
+                    RichText::new(tr!(
+                        self.i18n,
+                        "Blossom servers that host the images and videos you post. Other clients use this list to find your media if a server goes away.",
+                        "Explanation of the Blossom media server list"
+                    ))
+                    .text_style(NotedeckTextStyle::Small.text_style())
+                    .weak(),
+                );
+
+                ui.add_space(8.0);
+
+                egui::ScrollArea::vertical()
+                    .id_salt(Self::scroll_id(pubkey))
+                    .auto_shrink([false; 2])
+                    .show(ui, |ui| {
+                        let mut action = self.show_servers(ui);
+                        ui.add_space(8.0);
+                        action = action.or(self.show_add_server(ui));
+                        ui.add_space(16.0);
+                        action = action.or(self.show_upload_target(ui));
+                        ui.add_space(8.0);
+                        action.or(self.show_nip96_server(ui))
+                    })
+            })
+            .inner;
+
+        DragResponse::scroll(scroll_out)
+    }
+
+    fn show_servers(&mut self, ui: &mut Ui) -> Option<MediaServersAction> {
+        if self.servers.is_empty() {
+            ui.label(
+                RichText::new(tr!(
+                    self.i18n,
+                    "No media servers yet",
+                    "Shown when the account has no Blossom server list"
+                ))
+                .weak(),
+            );
+            return None;
+        }
+
+        let mut action = None;
+        for server in self.servers {
+            ui.horizontal(|ui| {
+                ui.label(
+                    RichText::new(server).text_style(NotedeckTextStyle::Monospace.text_style()),
+                );
+
+                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    if ui
+                        .add_enabled(
+                            self.can_sign,
+                            Button::new(tr!(
+                                self.i18n,
+                                "Remove",
+                                "Button to remove a server from the media server list"
+                            )),
+                        )
+                        .clicked()
+                    {
+                        action = Some(MediaServersAction::Remove(server.clone()));
+                    }
+
+                    let testing =
+                        matches!(self.state.probes.get(server), Some(ServerProbe::Running(_)));
+                    let test = Button::new(tr!(
+                        self.i18n,
+                        "Test upload",
+                        "Button to check whether a media server accepts our uploads"
+                    ));
+                    if ui.add_enabled(self.can_sign && !testing, test).clicked() {
+                        action = Some(MediaServersAction::Probe(server.clone()));
+                    }
+
+                    self.show_probe(ui, server);
+                });
+            });
+        }
+
+        action
+    }
+
+    fn show_probe(&mut self, ui: &mut Ui, server: &str) {
+        match self.state.probes.get(server) {
+            None => {}
+            Some(ServerProbe::Running(_)) => {
+                ui.spinner();
+            }
+            Some(ServerProbe::Accepted) => {
+                ui.label(RichText::new("✔").color(ui.visuals().hyperlink_color))
+                    .on_hover_text(tr!(
+                        self.i18n,
+                        "This server accepts uploads from you",
+                        "Tooltip when a media server passed the upload test"
+                    ));
+            }
+            Some(ServerProbe::Rejected(reason)) => {
+                ui.label(RichText::new("✖").color(PINK)).on_hover_text(tr!(
+                    self.i18n,
+                    "This server won't take uploads from you: {reason}",
+                    "Tooltip when a media server failed the upload test",
+                    reason = reason
+                ));
+            }
+        }
+    }
+
+    fn show_add_server(&mut self, ui: &mut Ui) -> Option<MediaServersAction> {
+        if !self.can_sign {
+            return None;
+        }
+
+        let mut action = None;
+        ui.horizontal(|ui| {
+            let input = ui.add(
+                egui::TextEdit::singleline(&mut self.state.new_server)
+                    .hint_text("https://blossom.example.com")
+                    .desired_width(240.0),
+            );
+            if input.changed() {
+                self.state.add_error = None;
+            }
+
+            let submitted = input.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            let add = ui.add_enabled(
+                !self.state.new_server.trim().is_empty(),
+                Button::new(tr!(
+                    self.i18n,
+                    "Add",
+                    "Button to add a server to the media server list"
+                )),
+            );
+            if !add.clicked() && !submitted {
+                return;
+            }
+
+            match normalize_server_url(&self.state.new_server) {
+                Some(server) => {
+                    self.state.new_server.clear();
+                    action = Some(MediaServersAction::Add(server));
+                }
+                None => {
+                    self.state.add_error = Some(tr!(
+                        self.i18n,
+                        "That doesn't look like a server address",
+                        "Error when the media server url can't be parsed"
+                    ));
+                }
+            }
+        });
+
+        if let Some(error) = &self.state.add_error {
+            ui.label(RichText::new(error).color(PINK));
+        }
+
+        action
+    }
+
+    fn show_upload_target(&mut self, ui: &mut Ui) -> Option<MediaServersAction> {
+        ui.label(
+            RichText::new(tr!(
+                self.i18n,
+                "Upload new media to",
+                "Section header for choosing the default upload server"
+            ))
+            .text_style(NotedeckTextStyle::Body.text_style())
+            .strong(),
+        );
+        ui.add_space(4.0);
+
+        let mut action = None;
+        let nip96 = self.nip96_server.unwrap_or(NOSTR_BUILD);
+        if ui
+            .radio(self.upload_server.is_none(), format!("{nip96} (NIP-96)"))
+            .clicked()
+            && self.upload_server.is_some()
+        {
+            action = Some(MediaServersAction::SetUploadServer(None));
+        }
+
+        for server in self.servers {
+            let selected = self.upload_server == Some(server.as_str());
+            if ui.radio(selected, server).clicked() && !selected {
+                action = Some(MediaServersAction::SetUploadServer(Some(server.clone())));
+            }
+        }
+
+        action
+    }
+    /// Pick the NIP-96 file server used when no Blossom server is chosen
+    fn show_nip96_server(&mut self, ui: &mut Ui) -> Option<MediaServersAction> {
+        if !self.can_sign {
+            return None;
+        }
+
+        ui.label(
+            RichText::new(tr!(
+                self.i18n,
+                "NIP-96 file server",
+                "Section header for choosing the NIP-96 upload server"
+            ))
+            .text_style(NotedeckTextStyle::Body.text_style())
+            .strong(),
+        );
+        ui.add_space(4.0);
+
+        let mut action = None;
+        ui.horizontal(|ui| {
+            let input = ui.add(
+                egui::TextEdit::singleline(&mut self.state.new_nip96_server)
+                    .hint_text(self.nip96_server.unwrap_or("https://nostr.build"))
+                    .desired_width(240.0),
+            );
+            if input.changed() {
+                self.state.nip96_error = None;
+            }
+
+            let submitted = input.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            let set = ui.add_enabled(
+                !self.state.new_nip96_server.trim().is_empty(),
+                Button::new(tr!(
+                    self.i18n,
+                    "Use",
+                    "Button to upload to the NIP-96 server typed in"
+                )),
+            );
+            if set.clicked() || submitted {
+                match normalize_server_url(&self.state.new_nip96_server) {
+                    Some(server) => {
+                        self.state.new_nip96_server.clear();
+                        action = Some(MediaServersAction::SetNip96Server(Some(server)));
+                    }
+                    None => {
+                        self.state.nip96_error = Some(tr!(
+                            self.i18n,
+                            "That doesn't look like a server address",
+                            "Error when the media server url can't be parsed"
+                        ));
+                    }
+                }
+            }
+
+            if self.nip96_server.is_some()
+                && ui
+                    .button(tr!(
+                        self.i18n,
+                        "Use nostr.build",
+                        "Button to go back to uploading to nostr.build"
+                    ))
+                    .clicked()
+            {
+                action = Some(MediaServersAction::SetNip96Server(None));
+            }
+        });
+
+        if let Some(error) = &self.state.nip96_error {
+            ui.label(RichText::new(error).color(PINK));
+        }
+
+        action
+    }
+}
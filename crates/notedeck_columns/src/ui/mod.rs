@@ -2,15 +2,25 @@ pub mod account_login_view;
 pub mod accounts;
 pub mod add_column;
 pub mod column;
+pub mod community;
 pub mod configure_deck;
+pub mod drafts;
 pub mod edit_deck;
+pub mod group;
 pub mod images;
+pub mod live;
+pub mod media_servers;
 pub mod mentions_picker;
+pub mod mute_list;
 pub mod note;
 pub mod onboarding;
+pub mod permalink;
 pub mod post;
 pub mod preview;
 pub mod profile;
+pub mod publish_status;
+pub mod publish_warning;
+pub mod quick_post;
 pub mod relay;
 pub mod report;
 pub mod repost;
@@ -27,6 +37,7 @@ pub mod widgets;
 
 pub use accounts::AccountsView;
 pub use note::{PostReplyView, PostView};
+pub use permalink::PermalinkView;
 pub use preview::{Preview, PreviewApp, PreviewConfig};
 pub use profile::ProfileView;
 pub use relay::RelayView;
@@ -0,0 +1,403 @@
+use egui::{Align, Button, ComboBox, Frame, Layout, Margin, RichText, Ui};
+use enostr::{NoteId, Pubkey};
+use nostrdb::{Ndb, Transaction};
+use notedeck::{
+    name::get_display_name, send_mute_list_update, tr, Accounts, DragResponse, Localization,
+    MuteEntry, MuteMode, Muted, NotedeckTextStyle, RemoteApi, SettingsHandler,
+};
+use notedeck_ui::colors::PINK;
+
+use crate::mute_list::{parse_mute_entry, MuteEntryKind, MuteListState};
+
+pub enum MuteListAction {
+    Add(MuteEntry),
+    Remove(MuteEntry),
+    /// How notes matching the entry are muted
+    SetMode(MuteEntry, MuteMode),
+}
+
+impl MuteListAction {
+    pub fn process(
+        self,
+        ndb: &Ndb,
+        accounts: &Accounts,
+        remote: &mut RemoteApi<'_>,
+        settings: &mut SettingsHandler,
+    ) {
+        let account = *accounts.selected_account_pubkey();
+
+        match self {
+            Self::Add(entry) => publish_mute_list(ndb, accounts, remote, &entry, true),
+            Self::Remove(entry) => {
+                publish_mute_list(ndb, accounts, remote, &entry, false);
+                settings.set_mute_mode(&account, &entry, MuteMode::default());
+            }
+            Self::SetMode(entry, mode) => settings.set_mute_mode(&account, &entry, mode),
+        }
+    }
+}
+
+fn publish_mute_list(
+    ndb: &Ndb,
+    accounts: &Accounts,
+    remote: &mut RemoteApi<'_>,
+    entry: &MuteEntry,
+    mute: bool,
+) {
+    let Some(kp) = accounts.selected_filled() else {
+        return;
+    };
+
+    let txn = Transaction::new(ndb).expect("txn");
+    send_mute_list_update(ndb, &txn, &mut remote.publisher(accounts), kp, entry, mute);
+}
+
+pub struct MuteListView<'a> {
+    ndb: &'a Ndb,
+    txn: &'a Transaction,
+    muted: &'a Muted,
+    /// Only accounts with a secret key can publish a mute list
+    can_sign: bool,
+    state: &'a mut MuteListState,
+    i18n: &'a mut Localization,
+}
+
+impl<'a> MuteListView<'a> {
+    pub fn new(
+        ndb: &'a Ndb,
+        txn: &'a Transaction,
+        muted: &'a Muted,
+        can_sign: bool,
+        state: &'a mut MuteListState,
+        i18n: &'a mut Localization,
+    ) -> Self {
+        Self {
+            ndb,
+            txn,
+            muted,
+            can_sign,
+            state,
+            i18n,
+        }
+    }
+
+    pub fn scroll_id(pubkey: &Pubkey) -> egui::Id {
+        egui::Id::new(("mute_list_scroll", pubkey))
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui, pubkey: &Pubkey) -> DragResponse<MuteListAction> {
+        let scroll_out = Frame::new()
+            .inner_margin(Margin::symmetric(10, 0))
+            .show(ui, |ui| {
+                ui.add_space(24.0);
+
+                ui.label(
+                    RichText::new(tr!(
+                        self.i18n,
+                        "Mute List",
+                        "Heading of the page for managing the mute list"
+                    ))
+                    .text_style(NotedeckTextStyle::Heading2.text_style()),
+                );
+
+                ui.add_space(4.0);
+                ui.label(
+                    RichText::new(tr!(
+                        self.i18n,
+                        "Users, hashtags, words and threads you don't want to see. The list is published so your other clients mute them too.",
+                        "Explanation of the mute list"
+                    ))
+                    .text_style(NotedeckTextStyle::Small.text_style())
+                    .weak(),
+                );
+
+                ui.add_space(8.0);
+
+                egui::ScrollArea::vertical()
+                    .id_salt(Self::scroll_id(pubkey))
+                    .auto_shrink([false; 2])
+                    .show(ui, |ui| {
+                        let mut action = self.show_add_entry(ui);
+                        ui.add_space(16.0);
+                        action = action.or(self.show_entries(ui));
+                        action
+                    })
+            })
+            .inner;
+
+        DragResponse::scroll(scroll_out)
+    }
+
+    fn show_entries(&mut self, ui: &mut Ui) -> Option<MuteListAction> {
+        let muted = self.muted;
+        let users: Vec<(MuteEntry, String)> = muted
+            .pubkeys
+            .iter()
+            .map(|pk| (MuteEntry::Pubkey(*pk), self.user_label(pk)))
+            .collect();
+        let hashtags: Vec<(MuteEntry, String)> = muted
+            .hashtags
+            .iter()
+            .map(|tag| (MuteEntry::Hashtag(tag.clone()), format!("#{tag}")))
+            .collect();
+        let words: Vec<(MuteEntry, String)> = muted
+            .words
+            .iter()
+            .map(|word| (MuteEntry::Word(word.clone()), word.clone()))
+            .collect();
+        let threads: Vec<(MuteEntry, String)> = muted
+            .threads
+            .iter()
+            .map(|id| (MuteEntry::Thread(*id), thread_label(id)))
+            .collect();
+
+        if users.is_empty() && hashtags.is_empty() && words.is_empty() && threads.is_empty() {
+            ui.label(
+                RichText::new(tr!(
+                    self.i18n,
+                    "Nothing muted yet",
+                    "Shown when the account's mute list is empty"
+                ))
+                .weak(),
+            );
+            return None;
+        }
+
+        let sections = [
+            (
+                tr!(self.i18n, "Users", "Mute list section of muted users"),
+                users,
+            ),
+            (
+                tr!(self.i18n, "Hashtags", "Mute list section of muted hashtags"),
+                hashtags,
+            ),
+            (
+                tr!(self.i18n, "Words", "Mute list section of muted words"),
+                words,
+            ),
+            (
+                tr!(self.i18n, "Threads", "Mute list section of muted threads"),
+                threads,
+            ),
+        ];
+
+        let mut action = None;
+        for (title, entries) in sections {
+            if entries.is_empty() {
+                continue;
+            }
+
+            ui.label(
+                RichText::new(title)
+                    .text_style(NotedeckTextStyle::Body.text_style())
+                    .strong(),
+            );
+            ui.add_space(4.0);
+
+            for (entry, label) in entries {
+                action = self.show_entry(ui, entry, &label).or(action);
+            }
+            ui.add_space(12.0);
+        }
+
+        action
+    }
+
+    fn show_entry(&mut self, ui: &mut Ui, entry: MuteEntry, label: &str) -> Option<MuteListAction> {
+        let mut action = None;
+
+        ui.horizontal(|ui| {
+            ui.label(label);
+
+            ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                if ui
+                    .add_enabled(
+                        self.can_sign,
+                        Button::new(tr!(
+                            self.i18n,
+                            "Remove",
+                            "Button to take an entry off the mute list"
+                        )),
+                    )
+                    .clicked()
+                {
+                    action = Some(MuteListAction::Remove(entry.clone()));
+                }
+
+                // hashtag and word mutes aren't applied to notes yet, so
+                // there's no mode to pick for them
+                if matches!(entry, MuteEntry::Pubkey(_) | MuteEntry::Thread(_)) {
+                    if let Some(mode) = self.mode_picker(ui, &entry) {
+                        action = Some(MuteListAction::SetMode(entry.clone(), mode));
+                    }
+                }
+            });
+        });
+
+        action
+    }
+
+    fn mode_picker(&mut self, ui: &mut Ui, entry: &MuteEntry) -> Option<MuteMode> {
+        let current = self.muted.entry_mode(entry);
+        let modes = [
+            (
+                MuteMode::Hide,
+                tr!(
+                    self.i18n,
+                    "Hide",
+                    "Mute mode that leaves the notes out entirely"
+                ),
+            ),
+            (
+                MuteMode::Collapse,
+                tr!(
+                    self.i18n,
+                    "Collapse",
+                    "Mute mode that shows an expandable placeholder instead of the notes"
+                ),
+            ),
+            (
+                MuteMode::NotificationsOnly,
+                tr!(
+                    self.i18n,
+                    "Notifications only",
+                    "Mute mode that only keeps the notes out of notifications"
+                ),
+            ),
+        ];
+
+        let selected_text = modes
+            .iter()
+            .find(|(mode, _)| *mode == current)
+            .map(|(_, label)| label.clone())
+            .unwrap_or_default();
+
+        let mut selected = None;
+        ComboBox::from_id_salt(("mute_mode", entry.key()))
+            .selected_text(selected_text)
+            .show_ui(ui, |ui| {
+                for (mode, label) in modes {
+                    if ui.selectable_label(current == mode, label).clicked() && current != mode {
+                        selected = Some(mode);
+                    }
+                }
+            });
+
+        selected
+    }
+
+    fn show_add_entry(&mut self, ui: &mut Ui) -> Option<MuteListAction> {
+        if !self.can_sign {
+            return None;
+        }
+
+        let kinds = [
+            (
+                MuteEntryKind::User,
+                tr!(self.i18n, "User", "Kind of mute list entry to add"),
+            ),
+            (
+                MuteEntryKind::Hashtag,
+                tr!(self.i18n, "Hashtag", "Kind of mute list entry to add"),
+            ),
+            (
+                MuteEntryKind::Word,
+                tr!(self.i18n, "Word", "Kind of mute list entry to add"),
+            ),
+            (
+                MuteEntryKind::Thread,
+                tr!(self.i18n, "Thread", "Kind of mute list entry to add"),
+            ),
+        ];
+
+        let mut action = None;
+        ui.horizontal(|ui| {
+            let selected_text = kinds
+                .iter()
+                .find(|(kind, _)| *kind == self.state.new_kind)
+                .map(|(_, label)| label.clone())
+                .unwrap_or_default();
+
+            ComboBox::from_id_salt("mute_entry_kind")
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    for (kind, label) in &kinds {
+                        if ui
+                            .selectable_value(&mut self.state.new_kind, *kind, label)
+                            .changed()
+                        {
+                            self.state.add_error = None;
+                        }
+                    }
+                });
+
+            let hint = match self.state.new_kind {
+                MuteEntryKind::User => "npub…",
+                MuteEntryKind::Hashtag => "#hashtag",
+                MuteEntryKind::Word => "word or /regex/",
+                MuteEntryKind::Thread => "note…",
+            };
+            let input = ui.add(
+                egui::TextEdit::singleline(&mut self.state.new_entry)
+                    .hint_text(hint)
+                    .desired_width(200.0),
+            );
+            if input.changed() {
+                self.state.add_error = None;
+            }
+
+            let submitted = input.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            let add = ui.add_enabled(
+                !self.state.new_entry.trim().is_empty(),
+                Button::new(tr!(
+                    self.i18n,
+                    "Mute",
+                    "Button to add an entry to the mute list"
+                )),
+            );
+            if !add.clicked() && !submitted {
+                return;
+            }
+
+            match parse_mute_entry(self.state.new_kind, &self.state.new_entry) {
+                Some(entry) => {
+                    self.state.new_entry.clear();
+                    action = Some(MuteListAction::Add(entry));
+                }
+                None => {
+                    self.state.add_error = Some(tr!(
+                        self.i18n,
+                        "That doesn't look like something we can mute",
+                        "Error when a mute list entry can't be parsed"
+                    ));
+                }
+            }
+        });
+
+        if let Some(error) = &self.state.add_error {
+            ui.label(RichText::new(error).color(PINK));
+        }
+
+        action
+    }
+
+    fn user_label(&self, pk: &[u8; 32]) -> String {
+        let profile = self.ndb.get_profile_by_pubkey(self.txn, pk).ok();
+        let name = get_display_name(profile.as_ref()).name().to_owned();
+        let npub = Pubkey::new(*pk).npub().unwrap_or_else(|| hex::encode(pk));
+        format!("{name} ({})", abbreviate(&npub))
+    }
+}
+
+fn thread_label(id: &[u8; 32]) -> String {
+    let id = NoteId::new(*id);
+    abbreviate(&id.to_bech().unwrap_or_else(|| id.hex()))
+}
+
+fn abbreviate(id: &str) -> String {
+    match id.get(..16) {
+        Some(start) if start.len() < id.len() => format!("{start}…"),
+        _ => id.to_owned(),
+    }
+}
@@ -13,12 +13,19 @@ use notedeck_ui::{
     AnimationHelper, ProfilePic,
 };
 
+/// The amount and optional message picked in the [`CustomZapView`].
+pub struct CustomZap {
+    pub msats: u64,
+    pub comment: Option<String>,
+}
+
 pub struct CustomZapView<'a> {
     images: &'a mut Images,
     ndb: &'a Ndb,
     txn: &'a Transaction,
     target_pubkey: &'a Pubkey,
     default_msats: u64,
+    default_comment: Option<&'a str>,
     i18n: &'a mut Localization,
     jobs: &'a MediaJobSender,
 }
@@ -40,19 +47,26 @@ impl<'a> CustomZapView<'a> {
             ndb,
             txn,
             default_msats,
+            default_comment: None,
             i18n,
             jobs,
         }
     }
 
-    pub fn ui(&mut self, ui: &mut egui::Ui) -> Option<u64> {
+    /// Prefill the message field
+    pub fn default_comment(mut self, comment: Option<&'a str>) -> Self {
+        self.default_comment = comment;
+        self
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> Option<CustomZap> {
         egui::Frame::NONE
             .inner_margin(egui::Margin::same(8))
             .show(ui, |ui| self.ui_internal(ui))
             .inner
     }
 
-    fn ui_internal(&mut self, ui: &mut egui::Ui) -> Option<u64> {
+    fn ui_internal(&mut self, ui: &mut egui::Ui) -> Option<CustomZap> {
         show_title(ui, self.i18n);
 
         ui.add_space(16.0);
@@ -114,17 +128,39 @@ impl<'a> CustomZapView<'a> {
 
             ui.data_mut(|d| d.insert_temp(id, cur_amount));
 
+            let comment_id = id.with("comment");
+            let mut comment: String = ui
+                .data(|d| d.get_temp(comment_id))
+                .unwrap_or_else(|| self.default_comment.unwrap_or_default().to_owned());
+            ui.add(
+                egui::TextEdit::singleline(&mut comment)
+                    .desired_width(slider_width)
+                    .hint_text(tr!(
+                        self.i18n,
+                        "Add a message (optional)",
+                        "Placeholder for the optional message attached to a zap"
+                    )),
+            );
+            ui.data_mut(|d| d.insert_temp(comment_id, comment.clone()));
+
             let resp = ui.add(styled_button_toggleable(
                 &tr!(self.i18n, "Send", "Button label to send a zap"),
                 colors::PINK,
                 is_valid_zap(maybe_sats),
             ));
 
-            if resp.clicked() {
-                maybe_sats.map(|i| i * 1000)
-            } else {
-                None
+            if !resp.clicked() {
+                return None;
             }
+
+            let msats = maybe_sats? * 1000;
+            ui.data_mut(|d| d.remove::<String>(comment_id));
+            let comment = comment.trim();
+
+            Some(CustomZap {
+                msats,
+                comment: (!comment.is_empty()).then(|| comment.to_owned()),
+            })
         })
         .inner
     }
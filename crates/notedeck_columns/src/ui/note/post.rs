@@ -1,6 +1,12 @@
-use crate::draft::{Draft, Drafts, MentionHint};
-use crate::media_upload::nostrbuild_nip96_upload;
+use crate::draft::{Draft, DraftTarget, Drafts, ExpiresIn, MentionHint};
+use crate::media_upload::{
+    clipboard_image, selected_media_from_drop, upload_clipboard_image, upload_media,
+    ClipboardImage, UploadTarget,
+};
 use crate::post::{downcast_post_buffer, MentionType, NewPost};
+use crate::scheduled_posts::{
+    format_publish_time, parse_publish_time, ScheduledPost, ScheduledPosts,
+};
 use crate::ui::mentions_picker::MentionPickerView;
 use crate::ui::{self, Preview, PreviewConfig};
 use crate::Result;
@@ -16,21 +22,21 @@ use notedeck::media::latest::LatestImageTex;
 use notedeck::media::AnimationMode;
 #[cfg(target_os = "android")]
 use notedeck::platform::android::try_open_file_picker;
-use notedeck::platform::get_next_selected_file;
+#[cfg(not(target_os = "android"))]
+use notedeck::platform::file::emit_selected_file;
+use notedeck::platform::{file::SelectedMedia, get_next_selected_file};
 use notedeck::{
     name::get_display_name, supported_mime_hosted_at_url, tr, Localization, NoteAction,
     NoteContext, PublishApi, RelayType,
 };
-use notedeck::{DragResponse, PixelDimensions};
+use notedeck::{unix_time_secs, DragResponse, PixelDimensions};
 use notedeck_ui::{
     app_images,
-    context_menu::{input_context, PasteBehavior},
+    context_menu::{input_context_with, PasteBehavior},
     note::render_note_preview,
     search_profiles, NoteOptions, ProfilePic,
 };
 use tracing::error;
-#[cfg(not(target_os = "android"))]
-use {notedeck::platform::file::emit_selected_file, notedeck::platform::file::SelectedMedia};
 
 pub struct PostView<'a, 'd> {
     note_context: &'a mut NoteContext<'d>,
@@ -40,6 +46,9 @@ pub struct PostView<'a, 'd> {
     inner_rect: egui::Rect,
     note_options: NoteOptions,
     animation_mode: AnimationMode,
+    /// Where new media is uploaded: a Blossom server, a NIP-96 server or
+    /// nostr.build
+    upload_target: UploadTarget,
 }
 
 #[derive(Clone)]
@@ -60,20 +69,58 @@ pub enum PostAction {
 pub struct NewPostAction {
     post_type: PostType,
     post: NewPost,
+    publish_at: Option<u64>,
 }
 
 impl NewPostAction {
     pub fn new(post_type: PostType, post: NewPost) -> Self {
-        NewPostAction { post_type, post }
+        NewPostAction {
+            post_type,
+            post,
+            publish_at: None,
+        }
+    }
+
+    /// Hold the post back until `publish_at` instead of publishing it now
+    pub fn scheduled_for(mut self, publish_at: u64) -> Self {
+        self.publish_at = Some(publish_at);
+        self
     }
 
+    pub fn is_scheduled(&self) -> bool {
+        self.publish_at.is_some()
+    }
+
+    /// Publish the post and discard its draft
     pub fn execute(
         &self,
         ndb: &Ndb,
         txn: &Transaction,
         publisher: &mut PublishApi<'_, '_>,
         drafts: &mut Drafts,
-    ) -> Result<()> {
+    ) -> Result<NoteId> {
+        let note_id = self.publish(ndb, txn, publisher)?;
+        drafts.get_from_post_type(&self.post_type).clear();
+        Ok(note_id)
+    }
+
+    /// Queue the post for its publish time and discard its draft
+    pub fn schedule(&self, scheduled: &mut ScheduledPosts, drafts: &mut Drafts) {
+        let Some(publish_at) = self.publish_at else {
+            return;
+        };
+
+        let target = DraftTarget::from(&self.post_type);
+        scheduled.add(ScheduledPost::new(target, &self.post, publish_at));
+        drafts.get_mut(target).clear();
+    }
+
+    pub fn publish(
+        &self,
+        ndb: &Ndb,
+        txn: &Transaction,
+        publisher: &mut PublishApi<'_, '_>,
+    ) -> Result<NoteId> {
         let seckey = self.post.account.secret_key.to_secret_bytes();
 
         let note = match self.post_type {
@@ -86,7 +133,7 @@ impl NewPostAction {
 
             PostType::Quote(target) => {
                 let quoting = ndb.get_note_by_id(txn, target.bytes())?;
-                self.post.to_quote(&seckey, &quoting)
+                self.post.to_quote(&seckey, &quoting, txn)
             }
         };
 
@@ -98,9 +145,8 @@ impl NewPostAction {
         }
 
         publisher.publish_note(&note, RelayType::AccountsWrite);
-        drafts.get_from_post_type(&self.post_type).clear();
 
-        Ok(())
+        Ok(NoteId::new(*note.id()))
     }
 }
 
@@ -132,6 +178,7 @@ impl<'a, 'd> PostView<'a, 'd> {
             inner_rect,
             note_options,
             animation_mode,
+            upload_target: UploadTarget::default(),
         }
     }
 
@@ -148,6 +195,11 @@ impl<'a, 'd> PostView<'a, 'd> {
         self
     }
 
+    pub fn upload_target(mut self, upload_target: UploadTarget) -> Self {
+        self.upload_target = upload_target;
+        self
+    }
+
     fn editbox(&mut self, txn: &nostrdb::Transaction, ui: &mut egui::Ui) -> EditBoxResponse {
         ui.spacing_mut().item_spacing.x = 12.0;
 
@@ -226,14 +278,45 @@ impl<'a, 'd> PostView<'a, 'd> {
             self.draft.focus_state = crate::ui::search::FocusState::Navigating;
         }
 
-        input_context(
+        let mut paste_image = false;
+        input_context_with(
             ui,
             &out.response,
             self.note_context.clipboard,
             &mut self.draft.buffer.text_buffer,
             PasteBehavior::Append,
+            |ui| {
+                if cfg!(any(
+                    target_os = "windows",
+                    target_os = "macos",
+                    target_os = "linux"
+                )) && ui
+                    .button(tr!(
+                        self.note_context.i18n,
+                        "Paste image",
+                        "Context menu item to upload the image on the clipboard"
+                    ))
+                    .clicked()
+                {
+                    paste_image = true;
+                    ui.close_menu();
+                }
+            },
         );
 
+        if paste_image {
+            match clipboard_image() {
+                Ok(image) => self.upload_pasted_image(image),
+                Err(e) => self.upload_failed(e),
+            }
+        } else if out.response.has_focus() && paste_requested(ui) {
+            // most pastes are plain text, so a clipboard without an image
+            // isn't an error here
+            if let Ok(image) = clipboard_image() {
+                self.upload_pasted_image(image);
+            }
+        }
+
         if updated_layout {
             self.draft.buffer.selected_mention = false;
         }
@@ -411,19 +494,7 @@ impl<'a, 'd> PostView<'a, 'd> {
         ui: &mut egui::Ui,
     ) -> DragResponse<PostResponse> {
         while let Some(selected_file) = get_next_selected_file() {
-            match selected_file {
-                Ok(selected_media) => {
-                    let promise = nostrbuild_nip96_upload(
-                        self.poster.secret_key.secret_bytes(),
-                        selected_media,
-                    );
-                    self.draft.uploading_media.push(promise);
-                }
-                Err(e) => {
-                    error!("{e}");
-                    self.draft.upload_errors.push(e.to_string());
-                }
-            }
+            self.queue_upload(selected_file);
         }
 
         let focused = self.focused(ui);
@@ -449,9 +520,69 @@ impl<'a, 'd> PostView<'a, 'd> {
             });
         }
 
-        frame
-            .show(ui, |ui| ui.vertical(|ui| self.input_ui(txn, ui)).inner)
-            .inner
+        let resp = frame.show(ui, |ui| ui.vertical(|ui| self.input_ui(txn, ui)).inner);
+        self.handle_dropped_files(ui, resp.response.rect);
+
+        resp.inner
+    }
+
+    fn queue_upload(&mut self, selected_media: notedeck::Result<SelectedMedia>) {
+        match selected_media {
+            Ok(selected_media) => {
+                let promise = upload_media(
+                    self.poster.secret_key.secret_bytes(),
+                    &self.upload_target,
+                    selected_media,
+                    !self.note_options.contains(NoteOptions::KeepMediaMetadata),
+                );
+                self.draft.uploading_media.push(promise);
+            }
+            Err(e) => self.upload_failed(e),
+        }
+    }
+
+    fn upload_pasted_image(&mut self, image: ClipboardImage) {
+        let promise = upload_clipboard_image(
+            self.poster.secret_key.secret_bytes(),
+            &self.upload_target,
+            image,
+            !self.note_options.contains(NoteOptions::KeepMediaMetadata),
+        );
+        self.draft.uploading_media.push(promise);
+    }
+
+    fn upload_failed(&mut self, e: notedeck::Error) {
+        error!("{e}");
+        self.draft.upload_errors.push(e.to_string());
+    }
+
+    /// Upload files dropped onto the composer, and highlight it while
+    /// files are dragged over it
+    fn handle_dropped_files(&mut self, ui: &mut egui::Ui, rect: egui::Rect) {
+        // some platforms don't report the pointer while dragging files in
+        let over = ui
+            .ctx()
+            .pointer_latest_pos()
+            .map_or_else(|| self.focused(ui), |pos| rect.contains(pos));
+        if !over {
+            return;
+        }
+
+        let (hovering, dropped) =
+            ui.input(|i| (!i.raw.hovered_files.is_empty(), i.raw.dropped_files.clone()));
+
+        if hovering {
+            ui.painter().rect_stroke(
+                rect,
+                notedeck::tokens::RADIUS_LG,
+                egui::Stroke::new(2.0, ui.visuals().selection.stroke.color),
+                egui::StrokeKind::Inside,
+            );
+        }
+
+        for file in &dropped {
+            self.queue_upload(selected_media_from_drop(file));
+        }
     }
 
     fn input_ui(&mut self, txn: &Transaction, ui: &mut egui::Ui) -> DragResponse<PostResponse> {
@@ -473,6 +604,7 @@ impl<'a, 'd> PostView<'a, 'd> {
                                     id.bytes(),
                                     nostrdb::NoteKey::new(0),
                                     self.note_options,
+                                    1,
                                 )
                             })
                             .inner
@@ -499,6 +631,10 @@ impl<'a, 'd> PostView<'a, 'd> {
         self.transfer_uploads(ui);
         self.show_upload_errors(ui);
 
+        self.show_schedule(ui);
+        self.show_content_warning(ui);
+        self.show_expiration(ui);
+
         let post_action = ui.horizontal(|ui| self.input_buttons(ui)).inner;
 
         let action = note_response
@@ -521,28 +657,81 @@ impl<'a, 'd> PostView<'a, 'd> {
         });
 
         ui.with_layout(egui::Layout::right_to_left(egui::Align::BOTTOM), |ui| {
+            let scheduling = self.draft.schedule.is_some();
+            let schedule_time = self.schedule_time();
+            let can_post =
+                !self.draft.buffer.is_empty() && (!scheduling || schedule_time.is_some());
+
             let post_button_clicked = ui
                 .add_sized(
                     [91.0, 32.0],
-                    post_button(self.note_context.i18n, !self.draft.buffer.is_empty()),
+                    post_button(self.note_context.i18n, can_post, scheduling),
                 )
                 .clicked();
 
+            if ui
+                .selectable_label(scheduling, "🕓")
+                .on_hover_text(tr!(
+                    self.note_context.i18n,
+                    "Schedule for later",
+                    "Tooltip of the button that opens the publish time field"
+                ))
+                .clicked()
+            {
+                self.draft.schedule = if scheduling {
+                    None
+                } else {
+                    Some(format_publish_time(unix_time_secs() + 60 * 60))
+                };
+            }
+
+            let warning = self.draft.content_warning.is_some();
+            if ui
+                .selectable_label(warning, "⚠")
+                .on_hover_text(tr!(
+                    self.note_context.i18n,
+                    "Add a content warning",
+                    "Tooltip of the button that marks a note as sensitive"
+                ))
+                .clicked()
+            {
+                self.draft.content_warning = if warning { None } else { Some(String::new()) };
+            }
+
+            let expiring = self.draft.expiration.is_some();
+            if ui
+                .selectable_label(expiring, "⏳")
+                .on_hover_text(tr!(
+                    self.note_context.i18n,
+                    "Expire after a while",
+                    "Tooltip of the button that gives a note a NIP-40 expiration"
+                ))
+                .clicked()
+            {
+                self.draft.expiration = if expiring { None } else { Some(ExpiresIn::Day) };
+            }
+
             let shortcut_pressed = ui.input(|i| {
                 (i.modifiers.ctrl || i.modifiers.command) && i.key_pressed(egui::Key::Enter)
             });
 
-            if post_button_clicked
-                || (!self.draft.buffer.is_empty() && shortcut_pressed && self.focused(ui))
-            {
+            if post_button_clicked || (can_post && shortcut_pressed && self.focused(ui)) {
                 let output = self.draft.buffer.output();
                 let new_post = NewPost::new(
                     output.text,
                     self.poster.to_full(),
                     self.draft.uploaded_media.clone(),
                     output.mentions,
-                );
-                Some(NewPostAction::new(self.post_type.clone(), new_post))
+                )
+                .with_content_warning(self.draft.content_warning.clone())
+                .with_expiration(self.draft.expiration.map(|expires_in| {
+                    schedule_time.unwrap_or_else(unix_time_secs) + expires_in.secs()
+                }));
+                let action = NewPostAction::new(self.post_type.clone(), new_post);
+                Some(match schedule_time {
+                    Some(at) => action.scheduled_for(at),
+                    None => action,
+                })
             } else {
                 None
             }
@@ -550,6 +739,111 @@ impl<'a, 'd> PostView<'a, 'd> {
         .inner
     }
 
+    /// The publish time typed in, once it's a valid time in the future
+    fn schedule_time(&self) -> Option<u64> {
+        let text = self.draft.schedule.as_ref()?;
+        parse_publish_time(text).filter(|at| *at > unix_time_secs())
+    }
+
+    fn show_schedule(&mut self, ui: &mut egui::Ui) {
+        if self.draft.schedule.is_none() {
+            return;
+        }
+        let schedule_time = self.schedule_time();
+
+        ui.horizontal(|ui| {
+            ui.label(tr!(
+                self.note_context.i18n,
+                "Publish at",
+                "Label of the field for the time a scheduled note is published"
+            ));
+
+            if let Some(text) = &mut self.draft.schedule {
+                ui.add(
+                    egui::TextEdit::singleline(text)
+                        .hint_text("YYYY-MM-DD HH:MM")
+                        .desired_width(140.0),
+                );
+            }
+
+            let hint = if schedule_time.is_some() {
+                tr!(
+                    self.note_context.i18n,
+                    "Published then if Notedeck is running, otherwise when it next starts",
+                    "Explains when a scheduled note goes out"
+                )
+            } else {
+                tr!(
+                    self.note_context.i18n,
+                    "Pick a time in the future",
+                    "Shown when the publish time of a scheduled note isn't valid"
+                )
+            };
+            ui.label(egui::RichText::new(hint).small().weak());
+        });
+    }
+
+    fn show_content_warning(&mut self, ui: &mut egui::Ui) {
+        let Some(reason) = &mut self.draft.content_warning else {
+            return;
+        };
+
+        ui.horizontal(|ui| {
+            ui.label(tr!(
+                self.note_context.i18n,
+                "Content warning",
+                "Label of the field for why a note is marked as sensitive"
+            ));
+
+            let hint = tr!(
+                self.note_context.i18n,
+                "Reason (optional)",
+                "Placeholder of the content warning reason field"
+            );
+            ui.add(
+                egui::TextEdit::singleline(reason)
+                    .hint_text(hint)
+                    .desired_width(200.0),
+            );
+        });
+    }
+
+    fn show_expiration(&mut self, ui: &mut egui::Ui) {
+        let Some(expires_in) = self.draft.expiration else {
+            return;
+        };
+
+        ui.horizontal(|ui| {
+            ui.label(tr!(
+                self.note_context.i18n,
+                "Expires after",
+                "Label of the picker for how long a note lives before it expires"
+            ));
+
+            let i18n = &mut *self.note_context.i18n;
+            let mut selected = expires_in;
+            egui::ComboBox::from_id_salt("post_expiration")
+                .selected_text(expires_in_label(i18n, expires_in))
+                .show_ui(ui, |ui| {
+                    for option in ExpiresIn::ALL {
+                        let label = expires_in_label(i18n, option);
+                        ui.selectable_value(&mut selected, option, label);
+                    }
+                });
+            self.draft.expiration = Some(selected);
+
+            ui.label(
+                egui::RichText::new(tr!(
+                    self.note_context.i18n,
+                    "Relays that honor expirations drop it after that",
+                    "Explains what happens to a note once its expiration passes"
+                ))
+                .small()
+                .weak(),
+            );
+        });
+    }
+
     fn show_media(&mut self, ui: &mut egui::Ui) {
         let mut to_remove = Vec::new();
         for (i, media) in self.draft.uploaded_media.iter().enumerate() {
@@ -630,9 +924,15 @@ impl<'a, 'd> PostView<'a, 'd> {
                 Some(Err(e)) => {
                     self.draft.upload_errors.push(e.to_string());
                     error!("{e}");
+                    indexes_to_remove.push(i);
                 }
                 None => {
-                    ui.spinner();
+                    ui.spinner().on_hover_text(tr!(
+                        self.note_context.i18n,
+                        "Uploading to {server}",
+                        "Tooltip on the spinner shown while media is uploading",
+                        server = self.upload_target.host()
+                    ));
                 }
             }
         }
@@ -718,9 +1018,22 @@ fn render_post_view_media(
     }
 }
 
-fn post_button<'a>(i18n: &'a mut Localization, interactive: bool) -> impl egui::Widget + 'a {
+fn post_button<'a>(
+    i18n: &'a mut Localization,
+    interactive: bool,
+    scheduling: bool,
+) -> impl egui::Widget + 'a {
     move |ui: &mut egui::Ui| {
-        let button = egui::Button::new(tr!(i18n, "Post now", "Button label to post a note"));
+        let label = if scheduling {
+            tr!(
+                i18n,
+                "Schedule",
+                "Button label to schedule a note for later"
+            )
+        } else {
+            tr!(i18n, "Post now", "Button label to post a note")
+        };
+        let button = egui::Button::new(label);
         if interactive {
             ui.add(button)
         } else {
@@ -735,6 +1048,26 @@ fn post_button<'a>(i18n: &'a mut Localization, interactive: bool) -> impl egui::
     }
 }
 
+fn expires_in_label(i18n: &mut Localization, expires_in: ExpiresIn) -> String {
+    match expires_in {
+        ExpiresIn::Hour => tr!(
+            i18n,
+            "1 hour",
+            "Composer option for a note that expires in an hour"
+        ),
+        ExpiresIn::Day => tr!(
+            i18n,
+            "1 day",
+            "Composer option for a note that expires in a day"
+        ),
+        ExpiresIn::Week => tr!(
+            i18n,
+            "1 week",
+            "Composer option for a note that expires in a week"
+        ),
+    }
+}
+
 fn media_upload_button() -> impl egui::Widget {
     |ui: &mut egui::Ui| -> egui::Response {
         let resp = ui.allocate_response(egui::vec2(32.0, 32.0), egui::Sense::click());
@@ -817,6 +1150,24 @@ fn show_remove_upload_button(ui: &mut egui::Ui, desired_rect: egui::Rect) -> egu
     resp
 }
 
+/// Whether a paste shortcut reached the composer this frame. An image-only
+/// clipboard doesn't produce a paste event everywhere, so the key press
+/// counts too
+fn paste_requested(ui: &egui::Ui) -> bool {
+    ui.input(|i| {
+        i.events.iter().any(|event| match event {
+            egui::Event::Paste(_) => true,
+            egui::Event::Key {
+                key: egui::Key::V,
+                pressed: true,
+                modifiers,
+                ..
+            } => modifiers.command,
+            _ => false,
+        })
+    })
+}
+
 fn get_cursor_index(cursor: &Option<CCursorRange>) -> Option<usize> {
     let range = cursor.as_ref()?;
 
@@ -910,8 +1261,10 @@ mod preview {
                 jobs: app.media_jobs.sender(),
                 unknown_ids: app.unknown_ids,
                 nip05_cache: app.nip05_cache,
+                profile_stats: app.profile_stats,
                 clipboard: app.clipboard,
                 i18n: app.i18n,
+                action_bar: app.settings.action_bar(),
             };
 
             PostView::new(
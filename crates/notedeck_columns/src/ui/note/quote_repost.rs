@@ -1,6 +1,7 @@
 use super::{PostResponse, PostType};
 use crate::{
     draft::Draft,
+    media_upload::UploadTarget,
     ui::{self},
 };
 
@@ -17,6 +18,7 @@ pub struct QuoteRepostView<'a, 'd> {
     scroll_id: egui::Id,
     inner_rect: egui::Rect,
     note_options: NoteOptions,
+    upload_target: UploadTarget,
 }
 
 impl<'a, 'd> QuoteRepostView<'a, 'd> {
@@ -38,9 +40,15 @@ impl<'a, 'd> QuoteRepostView<'a, 'd> {
             scroll_id: QuoteRepostView::scroll_id(col, quoting_note.id()),
             inner_rect,
             note_options,
+            upload_target: UploadTarget::default(),
         }
     }
 
+    pub fn upload_target(mut self, upload_target: UploadTarget) -> Self {
+        self.upload_target = upload_target;
+        self
+    }
+
     fn id(col: usize, note_id: &[u8; 32]) -> egui::Id {
         egui::Id::new(("quote_repost", col, note_id))
     }
@@ -75,6 +83,7 @@ impl<'a, 'd> QuoteRepostView<'a, 'd> {
             self.inner_rect,
             self.note_options,
         )
+        .upload_target(self.upload_target.clone())
         .ui_no_scroll(self.quoting_note.txn().unwrap(), ui);
         post_resp
     }
@@ -1,4 +1,5 @@
 use crate::draft::Draft;
+use crate::media_upload::UploadTarget;
 use crate::ui::{
     self,
     note::{PostAction, PostResponse, PostType},
@@ -17,6 +18,7 @@ pub struct PostReplyView<'a, 'd> {
     scroll_id: egui::Id,
     inner_rect: egui::Rect,
     note_options: NoteOptions,
+    upload_target: UploadTarget,
 }
 
 impl<'a, 'd> PostReplyView<'a, 'd> {
@@ -38,9 +40,15 @@ impl<'a, 'd> PostReplyView<'a, 'd> {
             scroll_id: PostReplyView::scroll_id(col, note.id()),
             inner_rect,
             note_options,
+            upload_target: UploadTarget::default(),
         }
     }
 
+    pub fn upload_target(mut self, upload_target: UploadTarget) -> Self {
+        self.upload_target = upload_target;
+        self
+    }
+
     fn id(col: usize, note_id: &[u8; 32]) -> egui::Id {
         egui::Id::new(("reply_view", col, note_id))
     }
@@ -103,6 +111,7 @@ impl<'a, 'd> PostReplyView<'a, 'd> {
                     self.inner_rect,
                     self.note_options,
                 )
+                .upload_target(self.upload_target.clone())
                 .ui_no_scroll(self.note.txn().unwrap(), ui)
             };
 
@@ -54,6 +54,18 @@ impl<'a> FollowPackOnboardingView<'a> {
     }
 
     pub fn ui(&mut self, ui: &mut egui::Ui) -> DragResponse<OnboardingResponse> {
+        egui::Frame::new().inner_margin(8.0).show(ui, |ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.onboarding.display_name)
+                    .hint_text(tr!(
+                        self.loc,
+                        "Display name (optional)",
+                        "Placeholder for the name of a newly created account"
+                    ))
+                    .desired_width(f32::INFINITY),
+            );
+        });
+
         let Some(follow_pack_state) = self.onboarding.get_follow_packs() else {
             return DragResponse::output(Some(OnboardingResponse::FollowPacks(
                 FollowPacksResponse::NoFollowPacks,
@@ -0,0 +1,116 @@
+use enostr::NoteId;
+use nostrdb::Transaction;
+use notedeck::{tr, DragResponse, NoteAction, NoteContext};
+use notedeck_ui::{NoteOptions, NoteView};
+
+/// A single note with the note it replies to. Unlike [`super::ThreadView`]
+/// this doesn't subscribe to the thread, so opening a notification or a
+/// link stays cheap.
+pub struct PermalinkView<'a, 'd> {
+    note_id: &'a NoteId,
+    note_options: NoteOptions,
+    note_context: &'a mut NoteContext<'d>,
+    col: usize,
+}
+
+impl<'a, 'd> PermalinkView<'a, 'd> {
+    pub fn new(
+        note_id: &'a NoteId,
+        note_options: NoteOptions,
+        note_context: &'a mut NoteContext<'d>,
+        col: usize,
+    ) -> Self {
+        PermalinkView {
+            note_id,
+            note_options,
+            note_context,
+            col,
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> DragResponse<NoteAction> {
+        let txn = Transaction::new(self.note_context.ndb).expect("txn");
+
+        let output = egui::ScrollArea::vertical()
+            .id_salt(("permalinkscroll", self.note_id.bytes(), self.col))
+            .animated(false)
+            .auto_shrink([false, false])
+            .show(ui, |ui| self.notes(ui, &txn));
+
+        DragResponse::output(output.inner).scroll_raw(output.id)
+    }
+
+    fn notes(&mut self, ui: &mut egui::Ui, txn: &Transaction) -> Option<NoteAction> {
+        let ndb = self.note_context.ndb;
+
+        let Ok(note) = ndb.get_note_by_id(txn, self.note_id.bytes()) else {
+            self.note_context
+                .unknown_ids
+                .add_note_id_if_missing(ndb, txn, self.note_id.bytes());
+
+            ui.add_space(16.0);
+            ui.vertical_centered(|ui| {
+                ui.spinner();
+                ui.label(tr!(
+                    self.note_context.i18n,
+                    "Looking for this note...",
+                    "Shown while a linked note is fetched from relays"
+                ));
+            });
+            return None;
+        };
+
+        let parent_id = note.key().and_then(|key| {
+            let cached = self
+                .note_context
+                .note_cache
+                .cached_note_or_insert(key, &note);
+            cached
+                .reply
+                .borrow(note.tags())
+                .reply()
+                .map(|parent| *parent.id)
+        });
+
+        let mut action = None;
+
+        if let Some(parent_id) = parent_id {
+            if let Ok(parent) = ndb.get_note_by_id(txn, &parent_id) {
+                let resp = NoteView::new(self.note_context, &parent, self.note_options).show(ui);
+                action = resp.action;
+                notedeck_ui::hline(ui);
+            } else {
+                self.note_context
+                    .unknown_ids
+                    .add_note_id_if_missing(ndb, txn, &parent_id);
+            }
+        }
+
+        let mut options = self.note_options;
+        options.set(NoteOptions::Truncate, false);
+        options.set(NoteOptions::FullCreatedDate, true);
+
+        let resp = NoteView::new(self.note_context, &note, options)
+            .selected_style(true)
+            .show(ui);
+        action = action.or(resp.action);
+
+        notedeck_ui::hline(ui);
+        ui.add_space(8.0);
+
+        ui.vertical_centered(|ui| {
+            if ui
+                .button(tr!(
+                    self.note_context.i18n,
+                    "Show full thread",
+                    "Button to open the whole thread from a single note view"
+                ))
+                .clicked()
+            {
+                action = Some(NoteAction::note(*self.note_id));
+            }
+        });
+
+        action
+    }
+}
@@ -14,8 +14,8 @@ use crate::{
     ui::timeline::{tabs_ui, TimelineTabView},
 };
 use notedeck::{
-    name::get_display_name, profile::get_profile_url, IsFollowing, NoteAction, NoteContext,
-    NotedeckTextStyle,
+    name::get_display_name, profile::get_profile_url, IsFollowing, MuteEntry, NoteAction,
+    NoteContext, NotedeckTextStyle, ProfileTipsCache, ZapAction, ZapTargetOwned,
 };
 use notedeck_ui::{
     app_images,
@@ -29,6 +29,8 @@ pub struct ProfileView<'a, 'd> {
     timeline_cache: &'a mut TimelineCache,
     note_options: NoteOptions,
     note_context: &'a mut NoteContext<'d>,
+    /// The selected account gets no notifications from this profile
+    notifications_muted: bool,
 }
 
 pub enum ProfileViewAction {
@@ -61,9 +63,15 @@ impl<'a, 'd> ProfileView<'a, 'd> {
             timeline_cache,
             note_options,
             note_context,
+            notifications_muted: false,
         }
     }
 
+    pub fn notifications_muted(mut self, muted: bool) -> Self {
+        self.notifications_muted = muted;
+        self
+    }
+
     pub fn scroll_id(col_id: usize, profile_pubkey: &Pubkey) -> egui::Id {
         egui::Id::new(("profile_scroll", col_id, profile_pubkey))
     }
@@ -72,9 +80,9 @@ impl<'a, 'd> ProfileView<'a, 'd> {
         let scroll_id = ProfileView::scroll_id(self.col_id, self.pubkey);
         let scroll_area = ScrollArea::vertical().id_salt(scroll_id).animated(false);
 
-        let Some(profile_timeline) = self
+        let Some((profile_timeline, tips)) = self
             .timeline_cache
-            .get_mut(&TimelineKind::Profile(*self.pubkey))
+            .get_with_tips(&TimelineKind::Profile(*self.pubkey))
         else {
             return DragResponse::none();
         };
@@ -87,10 +95,20 @@ impl<'a, 'd> ProfileView<'a, 'd> {
                 .ndb
                 .get_profile_by_pubkey(&txn, self.pubkey.bytes())
                 .ok();
+            self.note_context
+                .unknown_ids
+                .refresh_profile_if_stale(self.pubkey.bytes(), profile.as_ref());
 
-            if let Some(profile_view_action) =
-                profile_body(ui, self.pubkey, self.note_context, profile.as_ref(), &txn)
-            {
+            if let Some(profile_view_action) = profile_body(
+                ui,
+                self.pubkey,
+                self.note_context,
+                profile.as_ref(),
+                &txn,
+                self.notifications_muted,
+                self.note_options,
+                tips,
+            ) {
                 action = Some(profile_view_action);
             }
 
@@ -139,12 +157,16 @@ impl<'a, 'd> ProfileView<'a, 'd> {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn profile_body(
     ui: &mut egui::Ui,
     pubkey: &Pubkey,
     note_context: &mut NoteContext,
     profile: Option<&ProfileRecord<'_>>,
     txn: &Transaction,
+    notifications_muted: bool,
+    note_options: NoteOptions,
+    tips: &mut ProfileTipsCache,
 ) -> Option<ProfileViewAction> {
     let mut action = None;
     ui.vertical(|ui| {
@@ -173,13 +195,21 @@ fn profile_body(
             .key
             .secret_key
             .is_some();
-        let is_muted = note_context.accounts.mute().is_pk_muted(pubkey.bytes());
+        let muted = note_context.accounts.mute();
+        let mute_mode = muted
+            .is_pk_muted(pubkey.bytes())
+            .then(|| muted.entry_mode(&MuteEntry::Pubkey(*pubkey.bytes())));
+        // we never notify ourselves, so there's nothing to toggle there
+        let notifications_muted = (note_context.accounts.selected_account_pubkey() != pubkey)
+            .then_some(notifications_muted);
         if let Some(selection) = ProfileContextWidget::context_menu(
             ui,
             note_context.i18n,
             context_resp,
             can_sign,
-            is_muted,
+            mute_mode,
+            notifications_muted,
+            note_options.contains(NoteOptions::OpenWithTemplate),
         ) {
             action = Some(ProfileViewAction::Context(ProfileContext {
                 profile: *pubkey,
@@ -268,6 +298,7 @@ fn profile_body(
             ui.add_space(18.0);
 
             let mut name = get_display_name(profile);
+            let mut nip05_mismatch = None;
             if let Some(raw_nip05) = profile
                 .and_then(|p| p.record().profile())
                 .and_then(|p| p.nip05())
@@ -275,22 +306,39 @@ fn profile_body(
                 note_context
                     .nip05_cache
                     .request_validation(*pubkey, raw_nip05);
-                if note_context.nip05_cache.status(pubkey) == Some(&notedeck::Nip05Status::Valid) {
-                    name.nip05_valid = true;
+                match note_context.nip05_cache.status(pubkey, raw_nip05) {
+                    Some(notedeck::Nip05Status::Valid) => name.nip05_valid = true,
+                    Some(notedeck::Nip05Status::Invalid) => nip05_mismatch = Some(raw_nip05),
+                    _ => {}
                 }
             }
             ui.add(display_name_widget(&name, false));
 
+            if let Some(nip05) = nip05_mismatch {
+                ui.label(RichText::new(format!("⚠ {nip05}")).color(ui.visuals().warn_fg_color))
+                    .on_hover_text(tr!(
+                        note_context.i18n,
+                        "This address doesn't list this profile's key, so it can't be verified",
+                        "Tooltip on a nip05 address that failed verification on a profile"
+                    ));
+            }
+
             ui.add_space(8.0);
 
             ui.add(about_section_widget(profile));
 
             ui.add_space(8.0);
 
+            if !note_options.contains(NoteOptions::HideBadges) {
+                profile_badges(ui, pubkey, note_context, txn);
+            }
+
             if let Some(stats_action) = profile_stats(ui, pubkey, note_context, txn) {
                 action = Some(stats_action);
             }
 
+            activity_stats(ui, pubkey, note_context);
+
             ui.horizontal_wrapped(|ui| {
                 let website_url = profile
                     .as_ref()
@@ -312,11 +360,20 @@ fn profile_body(
                     if website_url.is_some() {
                         ui.end_row();
                     }
+                    let selected = note_context.accounts.get_selected_account();
+                    let can_zap =
+                        selected.key.secret_key.is_some() && &selected.key.pubkey != pubkey;
                     ui.horizontal_wrapped(|ui| {
-                        handle_lud16(ui, lud16);
+                        if handle_lud16(ui, note_context.i18n, lud16, can_zap) {
+                            action = Some(ProfileViewAction::Note(NoteAction::Zap(
+                                ZapAction::CustomizeAmount(ZapTargetOwned::Profile(*pubkey)),
+                            )));
+                        }
                     });
                 }
             });
+
+            recent_tips(ui, pubkey, note_context, txn, tips);
         });
     });
 
@@ -329,6 +386,50 @@ enum ProfileType {
     Followable(IsFollowing),
 }
 
+/// A row of the verified NIP-58 badges the profile chose to display, with
+/// the badge's name and description on hover
+fn profile_badges(
+    ui: &mut egui::Ui,
+    pubkey: &Pubkey,
+    note_context: &mut NoteContext,
+    txn: &Transaction,
+) {
+    let badges = notedeck::badges::profile_badges(
+        note_context.ndb,
+        txn,
+        pubkey.bytes(),
+        note_context.unknown_ids,
+    );
+    if badges.is_empty() {
+        return;
+    }
+
+    ui.horizontal_wrapped(|ui| {
+        ui.spacing_mut().item_spacing.x = 4.0;
+        for badge in &badges {
+            let mut hover = badge.display_name().to_owned();
+            if let Some(description) = badge.description.as_deref().filter(|d| !d.is_empty()) {
+                hover.push('\n');
+                hover.push_str(description);
+            }
+
+            ui.add(
+                &mut ProfilePic::new(
+                    note_context.img_cache,
+                    note_context.jobs,
+                    badge
+                        .icon_url()
+                        .unwrap_or_else(notedeck::profile::no_pfp_url),
+                )
+                .size(24.0),
+            )
+            .on_hover_text(hover);
+        }
+    });
+
+    ui.add_space(8.0);
+}
+
 fn profile_stats(
     ui: &mut egui::Ui,
     pubkey: &Pubkey,
@@ -416,6 +517,59 @@ fn profile_stats(
     action
 }
 
+/// Local activity summary: stored notes and replies, when we first saw the
+/// author and when they were last active. Computed off-thread, so nothing is
+/// shown until the first result arrives.
+fn activity_stats(ui: &mut egui::Ui, pubkey: &Pubkey, note_context: &mut NoteContext) {
+    let Some(stats) = note_context.profile_stats.get(pubkey).copied() else {
+        return;
+    };
+
+    let i18n = &mut *note_context.i18n;
+    let mut parts = vec![tr!(
+        i18n,
+        "{notes} notes · {replies} replies",
+        "Number of notes and replies by a profile stored locally",
+        notes = stats.notes,
+        replies = stats.replies
+    )];
+
+    if let Some(first_seen) = stats.first_seen {
+        let ago = notedeck::time_ago_since(i18n, first_seen);
+        parts.push(tr!(
+            i18n,
+            "first seen {ago} ago",
+            "Age of the earliest locally stored note by a profile",
+            ago = ago
+        ));
+    }
+
+    if let Some(last_active) = stats.last_active {
+        let ago = notedeck::time_ago_since(i18n, last_active);
+        parts.push(tr!(
+            i18n,
+            "active {ago} ago",
+            "Age of the newest locally stored event by a profile",
+            ago = ago
+        ));
+    }
+
+    ui.add_space(4.0);
+    ui.label(
+        RichText::new(parts.join(" · "))
+            .size(notedeck::fonts::get_font_size(
+                ui.ctx(),
+                &NotedeckTextStyle::Small,
+            ))
+            .color(ui.visuals().weak_text_color()),
+    )
+    .on_hover_text(tr!(
+        i18n,
+        "Based on notes stored on this device",
+        "Tooltip explaining that profile activity stats are computed locally"
+    ));
+}
+
 fn handle_link(ui: &mut egui::Ui, website_url: &str) {
     let img = if ui.visuals().dark_mode {
         app_images::link_dark_image()
@@ -437,12 +591,100 @@ fn handle_link(ui: &mut egui::Ui, website_url: &str) {
     }
 }
 
-fn handle_lud16(ui: &mut egui::Ui, lud16: &str) {
+/// Show the lightning address. Returns true when the user clicked it to
+/// zap the profile, which is only possible when `can_zap` is set.
+fn handle_lud16(ui: &mut egui::Ui, i18n: &mut Localization, lud16: &str, can_zap: bool) -> bool {
     ui.add(app_images::filled_zap_image());
 
-    let _ = ui
+    let resp = ui
         .label(RichText::new(lud16).color(notedeck_ui::colors::PINK))
         .on_hover_text(lud16);
+
+    if !can_zap {
+        return false;
+    }
+
+    resp.on_hover_cursor(egui::CursorIcon::PointingHand)
+        .on_hover_text(tr!(
+            i18n,
+            "Zap this profile",
+            "Tooltip for zapping (tipping) a profile via its lightning address"
+        ))
+        .interact(Sense::click())
+        .clicked()
+}
+
+const RECENT_TIPS_LIMIT: u64 = 5;
+
+/// A short list of the most recent profile zaps this user received
+fn recent_tips(
+    ui: &mut egui::Ui,
+    pubkey: &Pubkey,
+    note_context: &mut NoteContext,
+    txn: &Transaction,
+    tips: &mut ProfileTipsCache,
+) {
+    let tips = tips.get(
+        note_context.ndb,
+        txn,
+        note_context.zaps,
+        pubkey,
+        RECENT_TIPS_LIMIT,
+    );
+    if tips.is_empty() {
+        return;
+    }
+
+    ui.add_space(8.0);
+    ui.label(
+        RichText::new(tr!(
+            note_context.i18n,
+            "Recent tips",
+            "Heading for the list of recent zaps sent to a profile"
+        ))
+        .size(notedeck::fonts::get_font_size(
+            ui.ctx(),
+            &NotedeckTextStyle::Small,
+        ))
+        .color(ui.visuals().weak_text_color()),
+    );
+
+    for tip in tips {
+        let sender = note_context
+            .ndb
+            .get_profile_by_pubkey(txn, tip.sender.bytes())
+            .ok();
+        let sender_name = get_display_name(sender.as_ref()).name();
+        let sats = tip.msats / 1000;
+
+        ui.horizontal_wrapped(|ui| {
+            ui.add(app_images::filled_zap_image());
+            ui.label(
+                RichText::new(tr!(
+                    note_context.i18n,
+                    "{sats} sats from {name}",
+                    "Amount and sender of a zap sent to a profile",
+                    sats = sats,
+                    name = sender_name
+                ))
+                .size(notedeck::fonts::get_font_size(
+                    ui.ctx(),
+                    &NotedeckTextStyle::Small,
+                )),
+            );
+
+            if !tip.comment.is_empty() {
+                ui.label(
+                    RichText::new(&tip.comment)
+                        .size(notedeck::fonts::get_font_size(
+                            ui.ctx(),
+                            &NotedeckTextStyle::Small,
+                        ))
+                        .color(ui.visuals().weak_text_color()),
+                );
+            }
+        });
+    }
 }
 
 fn copy_key_widget<'a>(
@@ -0,0 +1,93 @@
+use std::time::{Duration, Instant};
+
+use egui::{Align2, Frame, RichText};
+use enostr::{NormRelayUrl, NoteId, PublishAck, RelayId};
+use notedeck::{tr, ui::richtext_small, Localization};
+
+/// How long relay answers stay on screen after publishing
+const SHOW_FOR: Duration = Duration::from_secs(8);
+
+/// A note just published from the composer
+pub struct RecentPublish {
+    pub note_id: NoteId,
+    published_at: Instant,
+}
+
+impl RecentPublish {
+    pub fn new(note_id: NoteId) -> Self {
+        Self {
+            note_id,
+            published_at: Instant::now(),
+        }
+    }
+}
+
+/// Floating list of the relays a note was sent to and whether each
+/// accepted it. Returns false once it should no longer be shown.
+pub fn publish_status_ui(
+    ui: &mut egui::Ui,
+    i18n: &mut Localization,
+    recent: &RecentPublish,
+    write_relays: &[RelayId],
+    acks: &[(NormRelayUrl, PublishAck)],
+) -> bool {
+    let shown_for = recent.published_at.elapsed();
+    if shown_for >= SHOW_FOR {
+        return false;
+    }
+    ui.ctx().request_repaint_after(SHOW_FOR - shown_for);
+
+    let mut relays: Vec<&NormRelayUrl> = write_relays
+        .iter()
+        .filter_map(|r| match r {
+            RelayId::Websocket(url) => Some(url),
+            RelayId::Multicast => None,
+        })
+        .collect();
+    for (url, _) in acks {
+        if !relays.contains(&url) {
+            relays.push(url);
+        }
+    }
+
+    let accepted = acks.iter().filter(|(_, ack)| ack.accepted).count();
+
+    egui::Area::new(egui::Id::new("publish_status"))
+        .order(egui::Order::Foreground)
+        .anchor(Align2::RIGHT_BOTTOM, egui::vec2(-24.0, -24.0))
+        .show(ui.ctx(), |ui| {
+            Frame::popup(ui.style())
+                .show(ui, |ui| {
+                    let dismissed = ui
+                        .horizontal(|ui| {
+                            ui.label(tr!(
+                                i18n,
+                                "Accepted by {accepted} of {total} relays",
+                                "Heading of the relay answers shown after publishing a note",
+                                accepted = accepted,
+                                total = relays.len()
+                            ));
+                            ui.small_button("✕").clicked()
+                        })
+                        .inner;
+
+                    for relay in relays {
+                        let ack = acks.iter().find(|(url, _)| url == relay).map(|(_, a)| a);
+                        let (mark, color) = match ack {
+                            Some(a) if a.accepted => ("✔", ui.visuals().text_color()),
+                            Some(_) => ("✖", ui.visuals().error_fg_color),
+                            None => ("…", ui.visuals().weak_text_color()),
+                        };
+
+                        let resp = ui.label(richtext_small(format!("{mark} {relay}")).color(color));
+                        if let Some(a) = ack.filter(|a| !a.message.is_empty()) {
+                            resp.on_hover_text(RichText::new(&a.message));
+                        }
+                    }
+
+                    !dismissed
+                })
+                .inner
+        })
+        .inner
+}
@@ -0,0 +1,75 @@
+use egui::{Align2, Frame, RichText};
+use enostr::NoteId;
+use notedeck::{tr, tr_plural, ui::richtext_small, Localization};
+
+pub enum PublishWarningAction {
+    Rebroadcast(NoteId),
+    Dismiss(NoteId),
+}
+
+/// Floating warning for the oldest published note that other relays
+/// didn't have, offering to publish it again.
+pub fn publish_warning_ui(
+    ui: &mut egui::Ui,
+    i18n: &mut Localization,
+    missing: &[NoteId],
+) -> Option<PublishWarningAction> {
+    let note_id = *missing.first()?;
+
+    egui::Area::new(egui::Id::new("publish_warning"))
+        .order(egui::Order::Foreground)
+        .anchor(Align2::CENTER_BOTTOM, egui::vec2(0.0, -24.0))
+        .show(ui.ctx(), |ui| {
+            Frame::popup(ui.style())
+                .show(ui, |ui| {
+                    let mut action = None;
+
+                    ui.label(
+                        RichText::new(tr!(
+                            i18n,
+                            "Your note was not found on other relays",
+                            "Warning shown when a published note did not reach other relays"
+                        ))
+                        .color(ui.visuals().warn_fg_color),
+                    );
+
+                    if missing.len() > 1 {
+                        ui.label(richtext_small(tr_plural!(
+                            i18n,
+                            "{count} more note is missing",   // one
+                            "{count} more notes are missing", // other
+                            "How many other published notes did not reach other relays", // comment
+                            missing.len() - 1                 // count
+                        )));
+                    }
+
+                    ui.horizontal(|ui| {
+                        if ui
+                            .button(tr!(
+                                i18n,
+                                "Rebroadcast",
+                                "Button to publish a note to our relays again"
+                            ))
+                            .clicked()
+                        {
+                            action = Some(PublishWarningAction::Rebroadcast(note_id));
+                        }
+
+                        if ui
+                            .button(tr!(
+                                i18n,
+                                "Dismiss",
+                                "Button to hide the missing note warning"
+                            ))
+                            .clicked()
+                        {
+                            action = Some(PublishWarningAction::Dismiss(note_id));
+                        }
+                    });
+
+                    action
+                })
+                .inner
+        })
+        .inner
+}
@@ -0,0 +1,100 @@
+use egui::{Key, KeyboardShortcut, Modifiers, RichText};
+use nostrdb::Transaction;
+use notedeck::{tr, AppContext, NoteContext, NotedeckTextStyle};
+use notedeck_ui::NoteOptions;
+
+use crate::{
+    draft::Draft,
+    media_upload::UploadTarget,
+    ui::{
+        note::{NewPostAction, PostAction, PostType},
+        PostView,
+    },
+};
+
+/// Opens the quick post composer from anywhere in the app, even while
+/// typing somewhere else
+pub const QUICK_POST_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(
+    Modifiers {
+        shift: true,
+        ..Modifiers::COMMAND
+    },
+    Key::N,
+);
+
+const QUICK_POST_WIDTH: f32 = 520.0;
+
+pub enum QuickPostAction {
+    Post(NewPostAction),
+    Close,
+}
+
+/// A composer floating over the columns, so posting doesn't navigate away
+/// from whatever is open. It shares its draft with the compose column.
+pub fn quick_post_ui(
+    ui: &mut egui::Ui,
+    app_ctx: &mut AppContext<'_>,
+    draft: &mut Draft,
+    note_options: NoteOptions,
+) -> Option<QuickPostAction> {
+    let Some(kp) = app_ctx.accounts.get_selected_account().key.to_full() else {
+        return Some(QuickPostAction::Close);
+    };
+
+    let upload_target = UploadTarget::for_account(app_ctx.settings, kp.pubkey);
+    let mut note_context = NoteContext {
+        ndb: app_ctx.ndb,
+        accounts: app_ctx.accounts,
+        img_cache: app_ctx.img_cache,
+        note_cache: app_ctx.note_cache,
+        zaps: app_ctx.zaps,
+        jobs: app_ctx.media_jobs.sender(),
+        unknown_ids: app_ctx.unknown_ids,
+        nip05_cache: app_ctx.nip05_cache,
+        profile_stats: app_ctx.profile_stats,
+        clipboard: app_ctx.clipboard,
+        i18n: app_ctx.i18n,
+        global_wallet: app_ctx.global_wallet,
+        action_bar: app_ctx.settings.action_bar(),
+    };
+
+    // keep escape from reaching the side menu once we've closed
+    let escape = ui
+        .ctx()
+        .input_mut(|i| i.consume_key(Modifiers::NONE, Key::Escape));
+
+    let modal = egui::Modal::new(egui::Id::new("quick_post")).show(ui.ctx(), |ui| {
+        let width = QUICK_POST_WIDTH.min(ui.ctx().screen_rect().width() * 0.9);
+        ui.set_width(width);
+
+        ui.label(
+            RichText::new(tr!(
+                note_context.i18n,
+                "New note",
+                "Heading of the quick post composer"
+            ))
+            .text_style(NotedeckTextStyle::Heading3.text_style()),
+        );
+
+        let txn = Transaction::new(note_context.ndb).expect("txn");
+        let inner_rect = ui.available_rect_before_wrap();
+        PostView::new(
+            &mut note_context,
+            draft,
+            PostType::New,
+            kp,
+            inner_rect,
+            note_options,
+        )
+        .upload_target(upload_target)
+        .ui_no_scroll(&txn, ui)
+        .output
+        .and_then(|resp| resp.action)
+    });
+
+    if let Some(PostAction::NewPostAction(post)) = modal.inner {
+        return Some(QuickPostAction::Post(post));
+    }
+
+    (escape || modal.should_close()).then_some(QuickPostAction::Close)
+}
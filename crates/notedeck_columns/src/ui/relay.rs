@@ -3,28 +3,47 @@ use std::collections::{HashMap, HashSet};
 use egui::{Align, Button, CornerRadius, Frame, Id, Layout, Margin, Rgba, RichText, Ui, Vec2};
 use enostr::{NormRelayUrl, RelayStatus};
 use notedeck::{
-    tr, DragResponse, Localization, NotedeckTextStyle, RelayAction, RelayInspectApi, RelaySpec,
+    parse_kinds, tr, DragResponse, LocalRelayAction, Localization, NotedeckTextStyle, RelayAction,
+    RelayInspectApi, RelayPolicy, RelayProfile, RelaySpec, ScopedSubDiagnostics,
 };
 use notedeck_ui::app_images;
 use notedeck_ui::{colors::PINK, padding};
 use tracing::debug;
 
-use super::widgets::styled_button;
+use super::{column::subscriptions_ui, widgets::styled_button};
 
 pub struct RelayView<'r, 'a> {
     relay_inspect: RelayInspectApi<'r, 'a>,
     advertised_relays: &'a std::collections::BTreeSet<RelaySpec>,
+    profiles: &'a [RelayProfile],
+    active_profile: Option<&'a str>,
+    policies: &'a [RelayPolicy],
     id_string_map: &'a mut HashMap<Id, String>,
     i18n: &'a mut Localization,
+    /// Every declared subscription, only gathered in debug mode
+    subscriptions: Option<Vec<ScopedSubDiagnostics>>,
+}
+
+/// What the relay view asks for: an edit of the advertised relay list, or
+/// a relay change kept on this device
+pub enum RelayViewAction {
+    Advertised(RelayAction),
+    Local(LocalRelayAction),
 }
 
 struct RelayRow {
     relay_url: String,
     status: RelayStatus,
+    recent_incidents: usize,
+    needs_review: bool,
+    rejected_events: u64,
+    invalid_signatures: u64,
+    /// NIP-65 read/write usage, only known for advertised relays
+    markers: Option<(bool, bool)>,
 }
 
 impl RelayView<'_, '_> {
-    pub fn ui(&mut self, ui: &mut egui::Ui) -> DragResponse<RelayAction> {
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> DragResponse<RelayViewAction> {
         let scroll_out = Frame::new()
             .inner_margin(Margin::symmetric(10, 0))
             .show(ui, |ui| {
@@ -46,13 +65,18 @@ impl RelayView<'_, '_> {
                     .scroll_bar_visibility(egui::scroll_area::ScrollBarVisibility::AlwaysHidden)
                     .auto_shrink([false; 2])
                     .show(ui, |ui| {
-                        let mut action = None;
-                        if let Some(relay_to_remove) = self.show_relays(ui) {
-                            action = Some(RelayAction::Remove(relay_to_remove));
-                        }
+                        let mut action = self.show_relay_profiles(ui).map(RelayViewAction::Local);
+                        action = action.or(self.show_relays(ui));
+                        action =
+                            action.or(self.show_blocked_relays(ui).map(RelayViewAction::Local));
                         ui.add_space(8.0);
                         if let Some(relay_to_add) = self.show_add_relay_ui(ui) {
-                            action = Some(RelayAction::Add(relay_to_add));
+                            action =
+                                Some(RelayViewAction::Advertised(RelayAction::Add(relay_to_add)));
+                        }
+                        if let Some(subs) = &self.subscriptions {
+                            ui.add_space(16.0);
+                            subscriptions_ui(ui, self.i18n, subs);
                         }
                         action
                     })
@@ -71,28 +95,44 @@ impl<'r, 'a> RelayView<'r, 'a> {
     pub fn new(
         relay_inspect: RelayInspectApi<'r, 'a>,
         advertised_relays: &'a std::collections::BTreeSet<RelaySpec>,
+        profiles: &'a [RelayProfile],
+        active_profile: Option<&'a str>,
         id_string_map: &'a mut HashMap<Id, String>,
         i18n: &'a mut Localization,
     ) -> Self {
         RelayView {
             relay_inspect,
             advertised_relays,
+            profiles,
+            active_profile,
+            policies: &[],
             id_string_map,
             i18n,
+            subscriptions: None,
         }
     }
 
+    pub fn policies(mut self, policies: &'a [RelayPolicy]) -> Self {
+        self.policies = policies;
+        self
+    }
+
+    pub fn subscriptions(mut self, subscriptions: Option<Vec<ScopedSubDiagnostics>>) -> Self {
+        self.subscriptions = subscriptions;
+        self
+    }
+
     pub fn panel(&mut self, ui: &mut egui::Ui) {
         egui::CentralPanel::default().show(ui.ctx(), |ui| self.ui(ui));
     }
 
     /// Show the selected account's advertised relays and
     /// any other currently-connected outbox relays.
-    fn show_relays(&mut self, ui: &mut Ui) -> Option<String> {
+    fn show_relays(&mut self, ui: &mut Ui) -> Option<RelayViewAction> {
         let relay_infos = self.relay_inspect.relay_infos();
-        let status_by_url: HashMap<String, RelayStatus> = relay_infos
+        let info_by_url: HashMap<String, _> = relay_infos
             .iter()
-            .map(|relay_info| (relay_info.relay_url.to_string(), relay_info.status))
+            .map(|relay_info| (relay_info.relay_url.to_string(), *relay_info))
             .collect();
 
         let advertised_urls: HashSet<String> = self
@@ -105,14 +145,16 @@ impl<'r, 'a> RelayView<'r, 'a> {
 
         for relay in self.advertised_relays {
             let url = relay.url.to_string();
-            let status = status_by_url
-                .get(&url)
-                .copied()
-                .unwrap_or(RelayStatus::Disconnected);
+            let info = info_by_url.get(&url);
 
             advertised.push(RelayRow {
+                status: info.map_or(RelayStatus::Disconnected, |i| i.status),
+                recent_incidents: info.map_or(0, |i| i.recent_incidents),
+                needs_review: info.is_some_and(|i| i.needs_review),
+                rejected_events: info.map_or(0, |i| i.rejected_events),
+                invalid_signatures: info.map_or(0, |i| i.invalid_signatures),
+                markers: Some((relay.is_readable(), relay.is_writable())),
                 relay_url: url,
-                status,
             });
         }
 
@@ -125,10 +167,15 @@ impl<'r, 'a> RelayView<'r, 'a> {
             outbox_other.push(RelayRow {
                 relay_url: url,
                 status: relay_info.status,
+                recent_incidents: relay_info.recent_incidents,
+                needs_review: relay_info.needs_review,
+                rejected_events: relay_info.rejected_events,
+                invalid_signatures: relay_info.invalid_signatures,
+                markers: None,
             });
         }
 
-        let mut relay_to_remove = None;
+        let mut action = None;
         let advertised_label = tr!(
             self.i18n,
             "Advertised",
@@ -140,10 +187,10 @@ impl<'r, 'a> RelayView<'r, 'a> {
             "Section header for non-advertised connected relays"
         );
 
-        relay_to_remove = relay_to_remove.or_else(|| {
+        action = action.or_else(|| {
             self.show_relay_section(ui, &advertised_label, &advertised, true, "relay-advertised")
         });
-        relay_to_remove = relay_to_remove.or_else(|| {
+        action = action.or_else(|| {
             self.show_relay_section(
                 ui,
                 &outbox_other_label,
@@ -153,7 +200,150 @@ impl<'r, 'a> RelayView<'r, 'a> {
             )
         });
 
-        relay_to_remove
+        action
+    }
+
+    /// Named relay sets that can be swapped in with one click, and a way to
+    /// save the relays in use as a new one.
+    fn show_relay_profiles(&mut self, ui: &mut Ui) -> Option<LocalRelayAction> {
+        let mut action = None;
+
+        ui.label(
+            RichText::new(tr!(
+                self.i18n,
+                "Relay profiles",
+                "Section header for saved relay profiles"
+            ))
+            .text_style(NotedeckTextStyle::Body.text_style())
+            .strong(),
+        );
+        ui.add_space(4.0);
+
+        ui.horizontal_wrapped(|ui| {
+            let account_relays = tr!(
+                self.i18n,
+                "Account relays",
+                "Relay profile option to use the account's own relay lists"
+            );
+            if ui
+                .selectable_label(self.active_profile.is_none(), account_relays)
+                .clicked()
+                && self.active_profile.is_some()
+            {
+                action = Some(LocalRelayAction::UseProfile(None));
+            }
+
+            for profile in self.profiles {
+                let active = self.active_profile == Some(profile.name.as_str());
+                let resp = ui
+                    .selectable_label(active, &profile.name)
+                    .on_hover_text(tr!(
+                        self.i18n,
+                        "{count} relays. Right click to delete.",
+                        "Hover text on a relay profile",
+                        count = profile.relays.len()
+                    ));
+
+                if resp.clicked() && !active {
+                    action = Some(LocalRelayAction::UseProfile(Some(profile.name.clone())));
+                }
+
+                resp.context_menu(|ui| {
+                    if ui
+                        .button(tr!(
+                            self.i18n,
+                            "Delete profile",
+                            "Context menu item to delete a relay profile"
+                        ))
+                        .clicked()
+                    {
+                        action = Some(LocalRelayAction::RemoveProfile(profile.name.clone()));
+                        ui.close_menu();
+                    }
+                });
+            }
+        });
+
+        ui.add_space(4.0);
+
+        let id = ui.id().with("new-relay-profile");
+        ui.horizontal(|ui| {
+            let name = self.id_string_map.entry(id).or_default();
+            ui.add(
+                egui::TextEdit::singleline(name)
+                    .hint_text(tr!(
+                        self.i18n,
+                        "Profile name",
+                        "Placeholder for the name of a new relay profile"
+                    ))
+                    .desired_width(160.0),
+            );
+
+            let name = name.trim().to_owned();
+            if ui
+                .add_enabled(
+                    !name.is_empty(),
+                    Button::new(tr!(
+                        self.i18n,
+                        "Save current relays",
+                        "Button label to save the relays in use as a relay profile"
+                    )),
+                )
+                .clicked()
+            {
+                self.id_string_map.remove(&id);
+                action = Some(LocalRelayAction::SaveProfile(name));
+            }
+        });
+
+        ui.add_space(8.0);
+
+        action
+    }
+
+    /// Relays blacklisted for this session, each with an unblock button.
+    fn show_blocked_relays(&mut self, ui: &mut Ui) -> Option<LocalRelayAction> {
+        let blocked: Vec<String> = self
+            .relay_inspect
+            .blocked_relays()
+            .into_iter()
+            .map(|r| r.to_string())
+            .collect();
+
+        if blocked.is_empty() {
+            return None;
+        }
+
+        ui.add_space(8.0);
+        ui.label(
+            RichText::new(tr!(
+                self.i18n,
+                "Blocked this session",
+                "Section header for relays temporarily blocked until restart"
+            ))
+            .text_style(NotedeckTextStyle::Body.text_style())
+            .strong(),
+        );
+        ui.add_space(4.0);
+
+        let mut action = None;
+        for url in blocked {
+            ui.horizontal(|ui| {
+                ui.label(
+                    RichText::new(&url)
+                        .text_style(NotedeckTextStyle::Monospace.text_style())
+                        .weak(),
+                );
+                if ui
+                    .button(tr!(self.i18n, "Unblock", "Button label to unblock a relay"))
+                    .clicked()
+                {
+                    action = Some(LocalRelayAction::Unblock(url.clone()));
+                }
+            });
+        }
+
+        action
     }
 
     fn show_relay_section(
@@ -163,8 +353,8 @@ impl<'r, 'a> RelayView<'r, 'a> {
         rows: &[RelayRow],
         allow_delete: bool,
         id_prefix: &'static str,
-    ) -> Option<String> {
-        let mut relay_to_remove = None;
+    ) -> Option<RelayViewAction> {
+        let mut action = None;
 
         ui.add_space(8.0);
         ui.label(
@@ -184,11 +374,11 @@ impl<'r, 'a> RelayView<'r, 'a> {
         }
 
         for (index, relay_row) in rows.iter().enumerate() {
-            relay_to_remove = relay_to_remove
+            action = action
                 .or_else(|| self.show_relay_row(ui, relay_row, allow_delete, (id_prefix, index)));
         }
 
-        relay_to_remove
+        action
     }
 
     fn show_relay_row(
@@ -197,8 +387,8 @@ impl<'r, 'a> RelayView<'r, 'a> {
         relay_row: &RelayRow,
         allow_delete: bool,
         id_salt: impl std::hash::Hash,
-    ) -> Option<String> {
-        let mut relay_to_remove = None;
+    ) -> Option<RelayViewAction> {
+        let mut action = None;
 
         ui.add_space(8.0);
         ui.vertical_centered_justified(|ui| {
@@ -236,16 +426,204 @@ impl<'r, 'a> RelayView<'r, 'a> {
 
                     ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
                         if allow_delete && ui.add(delete_button(ui.visuals().dark_mode)).clicked() {
-                            relay_to_remove = Some(relay_row.relay_url.clone());
+                            action = Some(RelayViewAction::Advertised(RelayAction::Remove(
+                                relay_row.relay_url.clone(),
+                            )));
                         }
 
                         show_connection_status(ui, self.i18n, relay_row.status);
                     });
                 });
+
+                if let Some(markers) = relay_row.markers {
+                    action = action.or(self
+                        .show_relay_markers(ui, &relay_row.relay_url, markers)
+                        .map(RelayViewAction::Advertised));
+                }
+
+                if relay_row.rejected_events > 0 {
+                    ui.label(
+                        RichText::new(tr!(
+                            self.i18n,
+                            "{count} unrequested or oversized events dropped",
+                            "Shown on a relay that sent events we didn't ask for or that were too large",
+                            count = relay_row.rejected_events
+                        ))
+                        .small()
+                        .weak(),
+                    );
+                }
+
+                if relay_row.invalid_signatures > 0 {
+                    ui.label(
+                        RichText::new(tr!(
+                            self.i18n,
+                            "{count} events with forged signatures dropped",
+                            "Shown on a relay that sent events whose signatures don't check out",
+                            count = relay_row.invalid_signatures
+                        ))
+                        .small()
+                        .color(ui.visuals().warn_fg_color),
+                    );
+                }
+
+                if relay_row.needs_review {
+                    ui.horizontal_wrapped(|ui| {
+                        ui.label(
+                            RichText::new(tr!(
+                                self.i18n,
+                                "{count} failures in the last few minutes",
+                                "Warning shown on a relay that keeps failing or misbehaving",
+                                count = relay_row.recent_incidents
+                            ))
+                            .color(ui.visuals().warn_fg_color),
+                        );
+
+                        if ui
+                            .button(tr!(
+                                self.i18n,
+                                "Block for this session",
+                                "Button label to temporarily block a misbehaving relay"
+                            ))
+                            .clicked()
+                        {
+                            action = Some(RelayViewAction::Local(LocalRelayAction::Block(
+                                relay_row.relay_url.clone(),
+                            )));
+                        }
+                    });
+                }
+
+                action = action.or(self
+                    .show_relay_policy(ui, &relay_row.relay_url)
+                    .map(RelayViewAction::Local));
             });
         });
 
-        relay_to_remove
+        action
+    }
+
+    /// Whether the relay is advertised in our NIP-65 list as one we read
+    /// from, write to, or both. Turning one off is only allowed while the
+    /// other is on.
+    fn show_relay_markers(
+        &mut self,
+        ui: &mut Ui,
+        relay_url: &str,
+        (mut read, mut write): (bool, bool),
+    ) -> Option<RelayAction> {
+        let mut changed = false;
+
+        ui.horizontal(|ui| {
+            ui.label(
+                RichText::new(tr!(
+                    self.i18n,
+                    "Advertised for",
+                    "Label before the read/write checkboxes of an advertised relay"
+                ))
+                .small()
+                .weak(),
+            );
+
+            let read_label = tr!(
+                self.i18n,
+                "Inbox (read)",
+                "Checkbox to advertise a relay as one we read mentions and replies from"
+            );
+            let write_label = tr!(
+                self.i18n,
+                "Outbox (write)",
+                "Checkbox to advertise a relay as one we publish our notes to"
+            );
+
+            changed |= ui
+                .add_enabled(write, egui::Checkbox::new(&mut read, read_label))
+                .changed();
+            changed |= ui
+                .add_enabled(read, egui::Checkbox::new(&mut write, write_label))
+                .changed();
+        });
+
+        changed.then(|| RelayAction::SetMarkers {
+            url: relay_url.to_owned(),
+            read,
+            write,
+        })
+    }
+
+    /// Controls limiting what the relay is used for, collapsed under its summary
+    fn show_relay_policy(&mut self, ui: &mut Ui, relay_url: &str) -> Option<LocalRelayAction> {
+        let mut policy = self
+            .policies
+            .iter()
+            .find(|p| p.url == relay_url)
+            .cloned()
+            .unwrap_or_else(|| RelayPolicy::new(relay_url));
+        let summary = policy_summary(self.i18n, &policy);
+        let mut action = None;
+
+        egui::CollapsingHeader::new(RichText::new(summary).small().weak())
+            .id_salt(("relay-policy", relay_url))
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    let read = tr!(
+                        self.i18n,
+                        "Read",
+                        "Checkbox to allow subscribing to events from a relay"
+                    );
+                    let publish = tr!(
+                        self.i18n,
+                        "Publish",
+                        "Checkbox to allow sending our notes to a relay"
+                    );
+                    if ui.checkbox(&mut policy.subscribe, read).changed()
+                        | ui.checkbox(&mut policy.publish, publish).changed()
+                    {
+                        action = Some(LocalRelayAction::SetPolicy(policy.clone()));
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label(tr!(
+                        self.i18n,
+                        "Only for kinds",
+                        "Label for the list of event kinds a relay is limited to"
+                    ));
+
+                    let id = ui.id().with(("relay-policy-kinds", relay_url));
+                    let text = self
+                        .id_string_map
+                        .entry(id)
+                        .or_insert_with(|| join_kinds(&policy.kinds));
+                    let response = ui.add(
+                        egui::TextEdit::singleline(text)
+                            .hint_text("1059, 10050")
+                            .desired_width(160.0),
+                    );
+
+                    match parse_kinds(text) {
+                        Some(kinds) => {
+                            if response.lost_focus() && kinds != policy.kinds {
+                                policy.kinds = kinds;
+                                action = Some(LocalRelayAction::SetPolicy(policy.clone()));
+                            }
+                        }
+                        None => {
+                            ui.label(
+                                RichText::new(tr!(
+                                    self.i18n,
+                                    "Enter kind numbers separated by commas",
+                                    "Warning when the relay kind list can't be parsed"
+                                ))
+                                .small()
+                                .color(ui.visuals().warn_fg_color),
+                            );
+                        }
+                    }
+                });
+            });
+
+        action
     }
 
     const RELAY_PREFILL: &'static str = "wss://";
@@ -311,6 +689,67 @@ impl<'r, 'a> RelayView<'r, 'a> {
     }
 }
 
+fn join_kinds(kinds: &std::collections::BTreeSet<u64>) -> String {
+    kinds
+        .iter()
+        .map(|kind| kind.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// What the relay is used for, collapsed to a one line summary
+fn policy_summary(i18n: &mut Localization, policy: &RelayPolicy) -> String {
+    if !policy.subscribe && !policy.publish {
+        return tr!(
+            i18n,
+            "Not used",
+            "Relay policy summary when a relay is neither read from nor published to"
+        );
+    }
+
+    if policy.kinds.is_empty() {
+        return match (policy.subscribe, policy.publish) {
+            (true, false) => tr!(
+                i18n,
+                "Read only",
+                "Relay policy summary when we never publish to a relay"
+            ),
+            (false, true) => tr!(
+                i18n,
+                "Publish only",
+                "Relay policy summary when we never subscribe on a relay"
+            ),
+            _ => tr!(
+                i18n,
+                "Used for everything",
+                "Relay policy summary when a relay is unrestricted"
+            ),
+        };
+    }
+
+    let kinds = join_kinds(&policy.kinds);
+    match (policy.subscribe, policy.publish) {
+        (true, false) => tr!(
+            i18n,
+            "Read only, kinds {kinds}",
+            "Relay policy summary for a read-only relay limited to some event kinds",
+            kinds = kinds
+        ),
+        (false, true) => tr!(
+            i18n,
+            "Publish only, kinds {kinds}",
+            "Relay policy summary for a publish-only relay limited to some event kinds",
+            kinds = kinds
+        ),
+        _ => tr!(
+            i18n,
+            "Only kinds {kinds}",
+            "Relay policy summary for a relay limited to some event kinds",
+            kinds = kinds
+        ),
+    }
+}
+
 fn add_relay_button(i18n: &mut Localization) -> Button<'static> {
     Button::image_and_text(
         app_images::add_relay_image().fit_to_exact_size(Vec2::new(48.0, 48.0)),
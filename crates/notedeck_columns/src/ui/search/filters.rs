@@ -0,0 +1,433 @@
+use chrono::{Local, NaiveDate, NaiveTime, TimeZone};
+use egui::{RichText, TextEdit};
+use enostr::Pubkey;
+use nostrdb::{FilterBuilder, Transaction};
+use notedeck::{name::get_display_name, tr, Localization, NoteContext};
+use notedeck_ui::{profile_row_widget, search_profiles, ProfileRowOptions, ProfileSearchResult};
+
+/// How many profiles we suggest while picking an author
+const MAX_AUTHOR_SUGGESTIONS: usize = 5;
+
+/// Which kinds of notes a search matches
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SearchKind {
+    #[default]
+    Notes,
+    Articles,
+    Highlights,
+    Files,
+    Any,
+}
+
+impl SearchKind {
+    pub const ALL: [SearchKind; 5] = [
+        SearchKind::Notes,
+        SearchKind::Articles,
+        SearchKind::Highlights,
+        SearchKind::Files,
+        SearchKind::Any,
+    ];
+
+    /// The nostr kinds to match, `None` for all of them
+    pub fn kinds(&self) -> Option<&'static [u64]> {
+        match self {
+            SearchKind::Notes => Some(&[1]),
+            SearchKind::Articles => Some(&[30023]),
+            SearchKind::Highlights => Some(&[9802]),
+            SearchKind::Files => Some(&[notedeck::FILE_METADATA_KIND as u64]),
+            SearchKind::Any => None,
+        }
+    }
+
+    fn label(&self, i18n: &mut Localization) -> String {
+        match self {
+            SearchKind::Notes => tr!(i18n, "Notes", "Search filter option for short text notes"),
+            SearchKind::Articles => tr!(
+                i18n,
+                "Articles",
+                "Search filter option for long-form articles"
+            ),
+            SearchKind::Highlights => {
+                tr!(i18n, "Highlights", "Search filter option for highlights")
+            }
+            SearchKind::Files => tr!(i18n, "Files", "Search filter option for shared files"),
+            SearchKind::Any => tr!(i18n, "All kinds", "Search filter option for any note kind"),
+        }
+    }
+}
+
+/// Structured constraints composed into the ndb filter alongside the
+/// search text
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchFilters {
+    pub kind: SearchKind,
+    pub author: Option<Pubkey>,
+    /// Only match notes created at or after this unix timestamp
+    pub since: Option<u64>,
+    /// Only match notes created at or before this unix timestamp
+    pub until: Option<u64>,
+}
+
+impl SearchFilters {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn apply(&self, mut filter: FilterBuilder) -> FilterBuilder {
+        if let Some(kinds) = self.kind.kinds() {
+            filter = filter.kinds(kinds.iter().copied());
+        }
+
+        if let Some(author) = &self.author {
+            filter = filter.authors([author.bytes()]);
+        }
+
+        if let Some(since) = self.since {
+            filter = filter.since(since);
+        }
+
+        if let Some(until) = self.until {
+            filter = filter.until(until);
+        }
+
+        filter
+    }
+}
+
+/// Text buffers behind the filter controls. The parsed values live in
+/// [`SearchFilters`].
+#[derive(Debug, Default)]
+pub struct SearchFiltersEditor {
+    pub open: bool,
+    author_query: String,
+    author_results: Vec<ProfileSearchResult>,
+    since_text: String,
+    until_text: String,
+}
+
+/// Parse a `YYYY-MM-DD` date into a unix timestamp for the start or the
+/// end of that day in local time.
+fn parse_day(text: &str, end_of_day: bool) -> Option<u64> {
+    let date = NaiveDate::parse_from_str(text.trim(), "%Y-%m-%d").ok()?;
+    let time = if end_of_day {
+        NaiveTime::from_hms_opt(23, 59, 59)?
+    } else {
+        NaiveTime::MIN
+    };
+    let local = Local.from_local_datetime(&date.and_time(time)).earliest()?;
+    u64::try_from(local.timestamp()).ok()
+}
+
+pub struct SearchFiltersView<'a, 'd> {
+    filters: &'a mut SearchFilters,
+    editor: &'a mut SearchFiltersEditor,
+    txn: &'a Transaction,
+    note_context: &'a mut NoteContext<'d>,
+}
+
+impl<'a, 'd> SearchFiltersView<'a, 'd> {
+    pub fn new(
+        filters: &'a mut SearchFilters,
+        editor: &'a mut SearchFiltersEditor,
+        txn: &'a Transaction,
+        note_context: &'a mut NoteContext<'d>,
+    ) -> Self {
+        Self {
+            filters,
+            editor,
+            txn,
+            note_context,
+        }
+    }
+
+    /// Returns true when the filters changed this frame
+    pub fn show(&mut self, ui: &mut egui::Ui) -> bool {
+        let before = self.filters.clone();
+
+        ui.horizontal(|ui| {
+            let toggle = if self.filters.is_default() {
+                tr!(
+                    self.note_context.i18n,
+                    "Filters",
+                    "Button to show the search filter controls"
+                )
+            } else {
+                tr!(
+                    self.note_context.i18n,
+                    "Filters (active)",
+                    "Button to show the search filter controls when some are set"
+                )
+            };
+
+            if ui.selectable_label(self.editor.open, toggle).clicked() {
+                self.editor.open = !self.editor.open;
+            }
+
+            if !self.filters.is_default()
+                && ui
+                    .small_button(tr!(
+                        self.note_context.i18n,
+                        "Clear",
+                        "Button to reset all search filters"
+                    ))
+                    .clicked()
+            {
+                *self.filters = SearchFilters::default();
+                *self.editor = SearchFiltersEditor {
+                    open: self.editor.open,
+                    ..Default::default()
+                };
+            }
+        });
+
+        if self.editor.open {
+            ui.vertical(|ui| {
+                ui.spacing_mut().item_spacing = egui::vec2(8.0, 6.0);
+                self.kind_picker(ui);
+                self.author_picker(ui);
+                self.date_range(ui);
+            });
+        }
+
+        *self.filters != before
+    }
+
+    fn kind_picker(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(tr!(
+                self.note_context.i18n,
+                "Kind",
+                "Label for the note kind search filter"
+            ));
+
+            let selected = self.filters.kind.label(self.note_context.i18n);
+            egui::ComboBox::from_id_salt(ui.id().with("search-kind-filter"))
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    for kind in SearchKind::ALL {
+                        let label = kind.label(self.note_context.i18n);
+                        ui.selectable_value(&mut self.filters.kind, kind, label);
+                    }
+                });
+        });
+    }
+
+    fn author_picker(&mut self, ui: &mut egui::Ui) {
+        if let Some(author) = self.filters.author {
+            let profile = self
+                .note_context
+                .ndb
+                .get_profile_by_pubkey(self.txn, author.bytes())
+                .ok();
+            let name = get_display_name(profile.as_ref()).name().to_owned();
+
+            ui.horizontal(|ui| {
+                ui.label(tr!(
+                    self.note_context.i18n,
+                    "Author",
+                    "Label for the author search filter"
+                ));
+                ui.label(RichText::new(name).strong());
+                if ui
+                    .small_button(tr!(
+                        self.note_context.i18n,
+                        "Remove",
+                        "Button to remove the author search filter"
+                    ))
+                    .clicked()
+                {
+                    self.filters.author = None;
+                }
+            });
+            return;
+        }
+
+        let hint = tr!(
+            self.note_context.i18n,
+            "Author name or npub",
+            "Placeholder for the author search filter"
+        );
+        let resp = ui.add(TextEdit::singleline(&mut self.editor.author_query).hint_text(hint));
+
+        if resp.changed() {
+            self.editor.author_results = if self.editor.author_query.trim().is_empty() {
+                Vec::new()
+            } else {
+                let contacts = self
+                    .note_context
+                    .accounts
+                    .get_selected_account()
+                    .data
+                    .contacts
+                    .get_state();
+                search_profiles(
+                    self.note_context.ndb,
+                    self.txn,
+                    self.editor.author_query.trim(),
+                    contacts,
+                    MAX_AUTHOR_SUGGESTIONS,
+                )
+            };
+        }
+
+        let mut picked = None;
+        for result in &self.editor.author_results {
+            let profile = self
+                .note_context
+                .ndb
+                .get_profile_by_pubkey(self.txn, &result.pk)
+                .ok();
+            let options = ProfileRowOptions::new().contact_badge(result.is_contact);
+            let resp = ui.add(profile_row_widget(
+                profile.as_ref(),
+                self.note_context.img_cache,
+                self.note_context.jobs,
+                self.note_context.i18n,
+                options,
+            ));
+
+            if resp.clicked() {
+                picked = Some(Pubkey::new(result.pk));
+            }
+        }
+
+        if let Some(author) = picked {
+            self.filters.author = Some(author);
+            self.editor.author_query.clear();
+            self.editor.author_results.clear();
+        }
+    }
+
+    fn date_range(&mut self, ui: &mut egui::Ui) {
+        let after = tr!(
+            self.note_context.i18n,
+            "After",
+            "Label for the earliest date search filter"
+        );
+        let before = tr!(
+            self.note_context.i18n,
+            "Before",
+            "Label for the latest date search filter"
+        );
+
+        ui.horizontal(|ui| {
+            let since_ok = date_input(
+                ui,
+                &after,
+                &mut self.editor.since_text,
+                &mut self.filters.since,
+                false,
+            );
+            let until_ok = date_input(
+                ui,
+                &before,
+                &mut self.editor.until_text,
+                &mut self.filters.until,
+                true,
+            );
+
+            if !since_ok || !until_ok {
+                ui.colored_label(
+                    ui.visuals().error_fg_color,
+                    tr!(
+                        self.note_context.i18n,
+                        "Use YYYY-MM-DD",
+                        "Shown when a search date filter can't be parsed"
+                    ),
+                );
+            }
+        });
+    }
+}
+
+/// A date text field that keeps `value` in sync with what was typed.
+/// Returns false while the text isn't a valid date.
+fn date_input(
+    ui: &mut egui::Ui,
+    label: &str,
+    text: &mut String,
+    value: &mut Option<u64>,
+    end_of_day: bool,
+) -> bool {
+    ui.label(label);
+    let resp = ui.add(
+        TextEdit::singleline(text)
+            .hint_text("YYYY-MM-DD")
+            .desired_width(96.0),
+    );
+
+    if text.trim().is_empty() {
+        if resp.changed() {
+            *value = None;
+        }
+        return true;
+    }
+
+    let parsed = parse_day(text, end_of_day);
+    if resp.changed() {
+        if let Some(ts) = parsed {
+            *value = Some(ts);
+        }
+    }
+
+    parsed.is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nostrdb::Filter;
+
+    #[test]
+    fn days_cover_their_whole_range() {
+        let start = parse_day("2024-03-01", false).unwrap();
+        let end = parse_day("2024-03-01", true).unwrap();
+        assert_eq!(end - start, 24 * 60 * 60 - 1);
+        assert_eq!(parse_day(" 2024-03-02 ", false), Some(end + 1));
+    }
+
+    #[test]
+    fn invalid_days_are_rejected() {
+        assert_eq!(parse_day("", false), None);
+        assert_eq!(parse_day("2024-13-01", false), None);
+        assert_eq!(parse_day("yesterday", true), None);
+    }
+
+    #[test]
+    fn default_filters_only_match_notes() {
+        let filters = SearchFilters::default();
+        assert!(filters.is_default());
+
+        let json = filters.apply(Filter::new()).build().json().unwrap();
+        assert!(json.contains(r#""kinds":[1]"#), "{json}");
+        assert!(!json.contains("authors"), "{json}");
+    }
+
+    #[test]
+    fn filters_are_composed_into_the_query() {
+        let author = Pubkey::new([7; 32]);
+        let filters = SearchFilters {
+            kind: SearchKind::Articles,
+            author: Some(author),
+            since: Some(100),
+            until: Some(200),
+        };
+
+        let json = filters.apply(Filter::new()).build().json().unwrap();
+        assert!(json.contains(r#""kinds":[30023]"#), "{json}");
+        assert!(json.contains(&author.hex()), "{json}");
+        assert!(json.contains(r#""since":100"#), "{json}");
+        assert!(json.contains(r#""until":200"#), "{json}");
+    }
+
+    #[test]
+    fn any_kind_leaves_kinds_unconstrained() {
+        let filters = SearchFilters {
+            kind: SearchKind::Any,
+            ..Default::default()
+        };
+
+        let json = filters.apply(Filter::new()).build().json().unwrap();
+        assert!(!json.contains("kinds"), "{json}");
+    }
+}
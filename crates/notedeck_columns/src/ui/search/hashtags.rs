@@ -0,0 +1,123 @@
+use hashbrown::{HashMap, HashSet};
+use nostrdb::{Filter, Ndb, Transaction};
+
+/// How many of the newest notes we harvest hashtags from
+const HASHTAG_SCAN_LIMIT: u64 = 2000;
+
+/// How many hashtags the autocomplete dropdown shows
+pub const MAX_HASHTAG_SUGGESTIONS: usize = 32;
+
+/// A hashtag seen in recent notes, along with how many of them used it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HashtagCount {
+    pub tag: String,
+    pub count: usize,
+}
+
+/// Count hashtags per note, lowercased so `#Nostr` and `#nostr` are the same
+/// tag. Most used first.
+fn count_hashtags<'a>(notes: impl IntoIterator<Item = Vec<&'a str>>) -> Vec<HashtagCount> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+
+    for tags in notes {
+        let unique: HashSet<String> = tags
+            .into_iter()
+            .map(|tag| tag.trim_start_matches('#').to_lowercase())
+            .filter(|tag| !tag.is_empty())
+            .collect();
+
+        for tag in unique {
+            *counts.entry(tag).or_default() += 1;
+        }
+    }
+
+    let mut hashtags: Vec<HashtagCount> = counts
+        .into_iter()
+        .map(|(tag, count)| HashtagCount { tag, count })
+        .collect();
+    hashtags.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.tag.cmp(&b.tag)));
+    hashtags
+}
+
+/// Hashtags from the `t` tags of the newest notes in ndb
+pub fn recent_hashtags(ndb: &Ndb, txn: &Transaction) -> Vec<HashtagCount> {
+    let filter = Filter::new().kinds([1]).limit(HASHTAG_SCAN_LIMIT).build();
+    let Ok(results) = ndb.query(txn, &[filter], HASHTAG_SCAN_LIMIT as i32) else {
+        return Vec::new();
+    };
+
+    count_hashtags(results.iter().map(|result| {
+        result
+            .note
+            .tags()
+            .into_iter()
+            .filter(|tag| tag.count() >= 2 && tag.get_str(0) == Some("t"))
+            .filter_map(|tag| tag.get_str(1))
+            .collect()
+    }))
+}
+
+/// Hashtags starting with the query, followed by ones that only contain it.
+/// Both groups keep the most used tags first.
+pub fn matching_hashtags(
+    hashtags: &[HashtagCount],
+    query: &str,
+    max_results: usize,
+) -> Vec<HashtagCount> {
+    let query = query.trim().trim_start_matches('#').to_lowercase();
+
+    let (mut prefixed, contained): (Vec<&HashtagCount>, Vec<&HashtagCount>) = hashtags
+        .iter()
+        .filter(|h| h.tag.contains(&query))
+        .partition(|h| h.tag.starts_with(&query));
+
+    prefixed.extend(contained);
+    prefixed.into_iter().take(max_results).cloned().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(counts: &[(&str, usize)]) -> Vec<HashtagCount> {
+        counts
+            .iter()
+            .map(|(tag, count)| HashtagCount {
+                tag: tag.to_string(),
+                count: *count,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn counts_each_note_once_ignoring_case() {
+        let counted = count_hashtags(vec![
+            vec!["nostr", "Nostr", "zaps"],
+            vec!["#nostr"],
+            vec!["", "plebchain"],
+        ]);
+
+        assert_eq!(
+            counted,
+            tags(&[("nostr", 2), ("plebchain", 1), ("zaps", 1)])
+        );
+    }
+
+    #[test]
+    fn prefix_matches_come_first() {
+        let hashtags = tags(&[
+            ("bitcoin", 9),
+            ("nostr", 5),
+            ("asknostr", 7),
+            ("nostrdb", 2),
+        ]);
+
+        let matched: Vec<String> = matching_hashtags(&hashtags, "#Nos", 10)
+            .into_iter()
+            .map(|h| h.tag)
+            .collect();
+
+        assert_eq!(matched, vec!["nostr", "nostrdb", "asknostr"]);
+        assert_eq!(matching_hashtags(&hashtags, "#", 2).len(), 2);
+    }
+}
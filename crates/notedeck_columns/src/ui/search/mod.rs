@@ -1,20 +1,23 @@
 use egui::{vec2, Align, Key, RichText, TextEdit};
-use enostr::{NoteId, Pubkey};
+use enostr::{NormRelayUrl, NoteId, Pubkey};
+use hashbrown::HashSet;
 use state::TypingType;
 
 use crate::{
     timeline::{TimelineTab, TimelineUnits},
-    ui::timeline::TimelineTabView,
+    ui::timeline::{labeled_tabs_ui, TimelineTabView},
 };
 use egui_winit::clipboard::Clipboard;
 use nostrdb::{Filter, Ndb, Transaction};
 use notedeck::{
-    fonts::get_font_size, tr, tr_plural, DragResponse, IsFollowing, Localization, NoteAction,
-    NoteContext, NoteRef, NotedeckTextStyle,
+    fonts::get_font_size, tr, tr_plural, unix_time_secs, Accounts, DragResponse, IsFollowing,
+    Localization, NostrLink, NoteAction, NoteAddress, NoteContext, NoteRef, NotedeckTextStyle,
+    RemoteApi,
 };
 
 use notedeck_ui::{
     context_menu::{input_context, PasteBehavior},
+    fuzzy_search_profiles,
     icons::search_icon,
     padding, parse_pubkey_query, profile_row_widget, search_input_frame, search_profiles,
     NoteOptions, ProfileRowOptions, SEARCH_INPUT_HEIGHT,
@@ -22,12 +25,39 @@ use notedeck_ui::{
 use std::time::{Duration, Instant};
 use tracing::{error, info, warn};
 
+mod filters;
+mod hashtags;
+mod profiles;
+mod ranking;
 mod state;
 
-pub use state::{FocusState, RecentSearchItem, SearchQueryState, SearchState};
+pub use filters::{SearchFilters, SearchFiltersEditor, SearchFiltersView};
+pub use hashtags::HashtagCount;
+pub use profiles::SearchTab;
+pub use ranking::SearchSort;
+pub use state::{
+    FocusState, RecentSearchItem, RelaySearch, SearchPaging, SearchQueryState, SearchState,
+};
 
 use super::mentions_picker::{MentionPickerResponse, MentionPickerView};
 
+const MAX_RESULTS: u64 = 500;
+
+/// How many local results we load per frame
+const SEARCH_PAGE_SIZE: u64 = 50;
+
+/// NIP-50: search capability
+const NIP_SEARCH: u32 = 50;
+
+/// How many notes we ask each relay for
+const RELAY_SEARCH_LIMIT: u64 = 100;
+
+/// How long we keep picking up relay results for a search
+const RELAY_SEARCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How often we check for relay results that have arrived
+const RELAY_SEARCH_REFRESH: Duration = Duration::from_secs(2);
+
 pub struct SearchView<'a, 'd> {
     query: &'a mut SearchQueryState,
     note_options: NoteOptions,
@@ -72,11 +102,25 @@ impl<'a, 'd> SearchView<'a, 'd> {
 
         search_resp.process_search_response(self.query);
 
-        let keyboard_resp = handle_keyboard_navigation(
-            ui,
-            &mut self.query.selected_index,
-            &self.query.user_results,
-        );
+        let filters_changed = SearchFiltersView::new(
+            &mut self.query.filters,
+            &mut self.query.filters_editor,
+            self.txn,
+            self.note_context,
+        )
+        .show(ui);
+
+        if filters_changed && self.query.state == SearchState::Searched {
+            self.query.state = SearchState::PerformSearch(SearchType::get_type(&self.query.string));
+        }
+
+        let result_count = if self.query.string.starts_with('#') {
+            self.query.hashtag_results.len()
+        } else {
+            self.query.user_results.len()
+        };
+        let keyboard_resp =
+            handle_keyboard_navigation(ui, &mut self.query.selected_index, result_count);
 
         let mut search_action = None;
         let mut body_resp = DragResponse::none();
@@ -84,22 +128,29 @@ impl<'a, 'd> SearchView<'a, 'd> {
             SearchState::New
             | SearchState::Navigating
             | SearchState::Typing(TypingType::Mention(_)) => {
-                if !self.query.string.is_empty() && !self.query.string.starts_with('@') {
-                    self.query.user_results = search_profiles(
-                        self.note_context.ndb,
-                        self.txn,
-                        &self.query.string,
-                        self.note_context
-                            .accounts
-                            .get_selected_account()
-                            .data
-                            .contacts
-                            .get_state(),
-                        128,
-                    )
-                    .into_iter()
-                    .map(|r| r.pk.to_vec())
-                    .collect();
+                if self.query.string.starts_with('#') {
+                    if let Some(action) = self.show_hashtag_suggestions(ui, keyboard_resp) {
+                        search_action = Some(action);
+                    }
+                } else if !self.query.string.is_empty() && !self.query.string.starts_with('@') {
+                    if self.query.last_user_query != self.query.string {
+                        self.query.user_results = fuzzy_search_profiles(
+                            self.note_context.ndb,
+                            self.txn,
+                            &self.query.string,
+                            self.note_context
+                                .accounts
+                                .get_selected_account()
+                                .data
+                                .contacts
+                                .get_state(),
+                            128,
+                        )
+                        .into_iter()
+                        .map(|r| r.pk.to_vec())
+                        .collect();
+                        self.query.last_user_query = self.query.string.clone();
+                    }
                     if let Some(action) = self.show_search_suggestions(ui, keyboard_resp) {
                         search_action = Some(action);
                     }
@@ -107,6 +158,8 @@ impl<'a, 'd> SearchView<'a, 'd> {
                     self.handle_mention_search(ui, &mut search_action);
                 } else {
                     self.query.user_results.clear();
+                    self.query.last_user_query.clear();
+                    self.query.clear_hashtag_suggestions();
                     self.query.selected_index = -1;
                     if let Some(action) = self.show_recent_searches(ui, keyboard_resp) {
                         search_action = Some(action);
@@ -118,10 +171,36 @@ impl<'a, 'd> SearchView<'a, 'd> {
                     ui.ctx(),
                     search_type,
                     &self.query.string,
+                    &self.query.filters,
                     self.note_context.ndb,
                     self.txn,
                     &mut self.query.notes,
+                    &mut self.query.paging,
                 );
+                self.query.relay_search =
+                    if self.query.search_relays && *search_type == SearchType::String {
+                        RelaySearch::Pending
+                    } else {
+                        RelaySearch::Idle
+                    };
+                self.query.profile_results = if *search_type == SearchType::String {
+                    profiles::search_profile_results(
+                        self.note_context.ndb,
+                        self.txn,
+                        &self.query.string,
+                        self.note_context
+                            .accounts
+                            .get_selected_account()
+                            .data
+                            .contacts
+                            .get_state(),
+                        profiles::MAX_PROFILE_RESULTS,
+                    )
+                } else {
+                    self.query.tab = SearchTab::Notes;
+                    Vec::new()
+                };
+                self.rerank();
                 search_action = Some(SearchAction::Searched);
                 body_resp.insert(
                     self.show_search_results(ui)
@@ -129,18 +208,18 @@ impl<'a, 'd> SearchView<'a, 'd> {
                 );
             }
             SearchState::Searched => {
-                ui.label(tr_plural!(
-                    self.note_context.i18n,
-                    "Got {count} result for '{query}'",  // one
-                    "Got {count} results for '{query}'", // other
-                    "Search results count",              // comment
-                    self.query.notes.units.len(),        // count
-                    query = &self.query.string
-                ));
-                body_resp.insert(
-                    self.show_search_results(ui)
-                        .map_output(SearchViewAction::NoteAction),
-                );
+                self.load_next_page(ui.ctx());
+                self.refresh_relay_results(ui.ctx());
+
+                if SearchType::get_type(&self.query.string) == SearchType::String {
+                    self.results_tabs(ui);
+                }
+
+                let results = match self.query.tab {
+                    SearchTab::Notes => self.show_note_results(ui),
+                    SearchTab::Profiles => self.show_profile_results(ui),
+                };
+                body_resp.insert(results);
             }
         };
 
@@ -217,6 +296,82 @@ impl<'a, 'd> SearchView<'a, 'd> {
         }
     }
 
+    fn show_hashtag_suggestions(
+        &mut self,
+        ui: &mut egui::Ui,
+        keyboard_resp: KeyboardResponse,
+    ) -> Option<SearchAction> {
+        let (ndb, txn) = (self.note_context.ndb, self.txn);
+        // an empty harvest is kept too, so we don't rescan every frame
+        let recent = self
+            .query
+            .recent_hashtags
+            .get_or_insert_with(|| hashtags::recent_hashtags(ndb, txn));
+
+        if self.query.last_hashtag_query != self.query.string {
+            self.query.hashtag_results = hashtags::matching_hashtags(
+                recent,
+                &self.query.string,
+                hashtags::MAX_HASHTAG_SUGGESTIONS,
+            );
+            self.query.last_hashtag_query = self.query.string.clone();
+        }
+
+        ui.add_space(8.0);
+
+        if self.query.string.len() > 1 {
+            let is_selected = self.query.selected_index == 0;
+            let search_posts_clicked = ui
+                .add(search_posts_button(
+                    &self.query.string,
+                    is_selected,
+                    ui.available_width(),
+                ))
+                .clicked()
+                || (is_selected && keyboard_resp.enter_pressed);
+
+            if search_posts_clicked {
+                return Some(SearchAction::NewSearch {
+                    search_type: SearchType::get_type(&self.query.string),
+                    new_search_text: self.query.string.clone(),
+                });
+            }
+        }
+
+        if keyboard_resp.enter_pressed && self.query.selected_index > 0 {
+            let hashtag_idx = (self.query.selected_index - 1) as usize;
+            if let Some(hashtag) = self.query.hashtag_results.get(hashtag_idx) {
+                return Some(SearchAction::OpenHashtag(hashtag.tag.clone()));
+            }
+        }
+
+        let mut action = None;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (i, hashtag) in self.query.hashtag_results.iter().enumerate() {
+                let uses = tr_plural!(
+                    self.note_context.i18n,
+                    "{count} note",  // one
+                    "{count} notes", // other
+                    "How many recent notes used a suggested hashtag",
+                    hashtag.count,
+                );
+                let is_selected = self.query.selected_index == (i + 1) as i32;
+                let resp = ui.add(hashtag_suggestion_item(
+                    &hashtag.tag,
+                    uses,
+                    is_selected,
+                    ui.available_width(),
+                ));
+
+                if resp.clicked() {
+                    action = Some(SearchAction::OpenHashtag(hashtag.tag.clone()));
+                }
+            }
+        });
+
+        action
+    }
+
     fn show_search_suggestions(
         &mut self,
         ui: &mut egui::Ui,
@@ -234,15 +389,20 @@ impl<'a, 'd> SearchView<'a, 'd> {
             .clicked()
             || (is_selected && keyboard_resp.enter_pressed);
 
+        self.relay_search_toggle(ui);
+
         if search_posts_clicked {
             let search_type = SearchType::get_type(&self.query.string);
-            // If it's a profile (npub), navigate to the profile instead of searching posts
-            if let SearchType::Profile(pubkey) = search_type {
-                return Some(SearchAction::NavigateToProfile(pubkey));
-            }
-            return Some(SearchAction::NewSearch {
-                search_type,
-                new_search_text: self.query.string.clone(),
+            // If it's a profile (npub), navigate to the profile instead of searching
+            // posts. Likewise, note and address links open what they point at
+            return Some(match search_type {
+                SearchType::Profile(pubkey) => SearchAction::NavigateToProfile(pubkey),
+                SearchType::NoteId(note_id) => SearchAction::OpenNote(note_id),
+                SearchType::Address(address) => SearchAction::OpenAddress(address),
+                search_type => SearchAction::NewSearch {
+                    search_type,
+                    new_search_text: self.query.string.clone(),
+                },
             });
         }
 
@@ -388,13 +548,135 @@ impl<'a, 'd> SearchView<'a, 'd> {
         let scroll_out = egui::ScrollArea::vertical()
             .id_salt(SearchView::scroll_id())
             .show(ui, |ui| {
-                TimelineTabView::new(
+                let view = TimelineTabView::new(
                     &self.query.notes,
                     self.note_options,
                     self.txn,
                     self.note_context,
-                )
-                .show(ui)
+                );
+
+                match self.query.sort {
+                    SearchSort::Relevance => view.order(&self.query.ranked).show(ui),
+                    SearchSort::Newest => view.show(ui),
+                }
+            });
+
+        DragResponse::scroll(scroll_out)
+    }
+
+    fn sort_picker(&mut self, ui: &mut egui::Ui) {
+        let before = self.query.sort;
+        let selected = before.label(self.note_context.i18n);
+
+        egui::ComboBox::from_id_salt(ui.id().with("search-sort"))
+            .selected_text(selected)
+            .show_ui(ui, |ui| {
+                for sort in SearchSort::ALL {
+                    let label = sort.label(self.note_context.i18n);
+                    ui.selectable_value(&mut self.query.sort, sort, label);
+                }
+            });
+
+        if self.query.sort != before {
+            self.rerank();
+            self.query.notes.list.borrow_mut().reset();
+        }
+    }
+
+    /// Recompute the relevance order of the current results
+    fn rerank(&mut self) {
+        if self.query.sort != SearchSort::Relevance {
+            self.query.ranked.clear();
+            return;
+        }
+
+        let contacts = &self
+            .note_context
+            .accounts
+            .get_selected_account()
+            .data
+            .contacts;
+
+        self.query.ranked = ranking::rank_units(
+            &self.query.notes.units,
+            &self.query.string,
+            self.note_context.ndb,
+            self.txn,
+            unix_time_secs(),
+            |pk| contacts.is_following(pk) == IsFollowing::Yes,
+        );
+    }
+
+    fn show_note_results(&mut self, ui: &mut egui::Ui) -> DragResponse<SearchViewAction> {
+        ui.horizontal(|ui| {
+            ui.label(tr_plural!(
+                self.note_context.i18n,
+                "Got {count} result for '{query}'",  // one
+                "Got {count} results for '{query}'", // other
+                "Search results count",              // comment
+                self.query.notes.units.len(),        // count
+                query = &self.query.string
+            ));
+
+            ui.with_layout(egui::Layout::right_to_left(Align::Center), |ui| {
+                self.relay_search_toggle(ui);
+                self.sort_picker(ui);
+            });
+        });
+        self.relay_search_status(ui);
+
+        self.show_search_results(ui)
+            .map_output(SearchViewAction::NoteAction)
+    }
+
+    fn results_tabs(&mut self, ui: &mut egui::Ui) {
+        let labels: Vec<String> = SearchTab::ALL
+            .iter()
+            .map(|tab| tab.label(self.note_context.i18n))
+            .collect();
+
+        // the tab strip tightens item spacing, keep that out of the results
+        let selected = ui
+            .scope(|ui| labeled_tabs_ui(ui, self.query.tab.index(), &labels).inner)
+            .inner;
+        self.query.tab = SearchTab::ALL[selected];
+    }
+
+    fn show_profile_results(&mut self, ui: &mut egui::Ui) -> DragResponse<SearchViewAction> {
+        if self.query.profile_results.is_empty() {
+            ui.label(tr!(
+                self.note_context.i18n,
+                "No profiles found for '{query}'",
+                "Shown when a search matches no profiles",
+                query = &self.query.string
+            ));
+            return DragResponse::none();
+        }
+
+        let scroll_out = egui::ScrollArea::vertical()
+            .id_salt(SearchView::scroll_id().with("profiles"))
+            .show(ui, |ui| {
+                let mut action = None;
+                for result in &self.query.profile_results {
+                    let profile = self
+                        .note_context
+                        .ndb
+                        .get_profile_by_pubkey(self.txn, &result.pk)
+                        .ok();
+                    let options = ProfileRowOptions::new().contact_badge(result.is_contact);
+                    let resp = ui.add(profile_row_widget(
+                        profile.as_ref(),
+                        self.note_context.img_cache,
+                        self.note_context.jobs,
+                        self.note_context.i18n,
+                        options,
+                    ));
+
+                    if resp.clicked() {
+                        action = Some(SearchViewAction::NavigateToProfile(Pubkey::new(result.pk)));
+                    }
+                }
+                action
             });
 
         DragResponse::scroll(scroll_out)
@@ -403,31 +685,260 @@ impl<'a, 'd> SearchView<'a, 'd> {
     pub fn scroll_id() -> egui::Id {
         egui::Id::new("search_results")
     }
+
+    fn relay_search_toggle(&mut self, ui: &mut egui::Ui) {
+        let label = tr!(
+            self.note_context.i18n,
+            "Search relays too",
+            "Checkbox to also send post searches to relays that support search"
+        );
+
+        if !ui.checkbox(&mut self.query.search_relays, label).changed() {
+            return;
+        }
+
+        let searched_posts = self.query.state == SearchState::Searched
+            && SearchType::get_type(&self.query.string) == SearchType::String;
+
+        self.query.relay_search = if self.query.search_relays && searched_posts {
+            RelaySearch::Pending
+        } else {
+            RelaySearch::Idle
+        };
+    }
+
+    fn relay_search_status(&mut self, ui: &mut egui::Ui) {
+        match self.query.relay_search {
+            RelaySearch::Running { relays, .. } => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.weak(tr_plural!(
+                        self.note_context.i18n,
+                        "Searching {count} relay",  // one
+                        "Searching {count} relays", // other
+                        "Shown while relays are being searched",
+                        relays,
+                    ));
+                });
+            }
+            RelaySearch::Unsupported => {
+                ui.weak(tr!(
+                    self.note_context.i18n,
+                    "None of your relays support search",
+                    "Shown when relay search is on but no connected relay supports it"
+                ));
+            }
+            RelaySearch::Idle | RelaySearch::Pending | RelaySearch::Done => {}
+        }
+    }
+
+    /// Add the next page of local results. Older notes land at the bottom,
+    /// so the list only needs resetting when sorted by relevance.
+    fn load_next_page(&mut self, ctx: &egui::Context) {
+        let SearchPaging::More { until, seen } = self.query.paging else {
+            return;
+        };
+        let limit = SEARCH_PAGE_SIZE + seen as u64;
+
+        let filters = SearchFilters {
+            until: Some(self.query.filters.until.map_or(until, |u| u.min(until))),
+            ..self.query.filters.clone()
+        };
+
+        let page = SearchType::get_type(&self.query.string)
+            .search(
+                &self.query.string,
+                &filters,
+                self.note_context.ndb,
+                self.txn,
+                limit,
+            )
+            .unwrap_or_default();
+
+        let before = self.query.notes.units.len();
+        for note_ref in &page {
+            self.query.notes.units.merge_single_note(*note_ref);
+        }
+        let added = self.query.notes.units.len() - before;
+
+        self.query.paging = next_page(&page, limit, added, self.query.notes.units.len());
+
+        if added > 0 && self.query.sort == SearchSort::Relevance {
+            self.rerank();
+            self.query.notes.list.borrow_mut().reset();
+        }
+
+        ctx.request_repaint();
+    }
+
+    /// Relay results are ingested into ndb like any other note. The local
+    /// subscription mirroring the relay search hands us each one as it
+    /// arrives, so we show those directly.
+    fn refresh_relay_results(&mut self, ctx: &egui::Context) {
+        let RelaySearch::Running { started, .. } = self.query.relay_search else {
+            return;
+        };
+
+        if started.elapsed() > RELAY_SEARCH_TIMEOUT {
+            self.query.relay_search = RelaySearch::Done;
+            return;
+        }
+
+        ctx.request_repaint_after(RELAY_SEARCH_REFRESH);
+
+        let Some(sub) = self.query.relay_sub else {
+            return;
+        };
+
+        let keys = self
+            .note_context
+            .ndb
+            .poll_for_notes(sub, RELAY_SEARCH_LIMIT as u32);
+        if keys.is_empty() {
+            return;
+        }
+
+        // the local mirror can't apply the search itself, so drop notes other
+        // feeds happened to ingest that share none of the query's words
+        let terms = ranking::query_terms(&self.query.string);
+        let before = self.query.notes.units.len();
+        for key in keys {
+            let Ok(note) = self.note_context.ndb.get_note_by_key(self.txn, key) else {
+                continue;
+            };
+
+            let content = note.content().to_lowercase();
+            if terms.iter().any(|term| content.contains(term.as_str())) {
+                self.query
+                    .notes
+                    .units
+                    .merge_single_note(NoteRef::from_note(&note));
+            }
+        }
+
+        let added = self.query.notes.units.len() - before;
+        if added > 0 {
+            info!("added {added} relay results for '{}'", self.query.string);
+            self.query.notes.list.borrow_mut().reset();
+            self.rerank();
+            ctx.request_repaint();
+        }
+    }
+}
+
+/// Send a queued relay search to the relays that advertise NIP-50 support,
+/// and drop the local subscription of a relay search that has finished.
+pub fn send_relay_search(
+    query: &mut SearchQueryState,
+    ndb: &mut Ndb,
+    remote: &mut RemoteApi,
+    accounts: &Accounts,
+) {
+    if !matches!(query.relay_search, RelaySearch::Running { .. }) {
+        if let Some(sub) = query.relay_sub.take() {
+            if let Err(e) = ndb.unsubscribe(sub) {
+                error!("relay search: ndb unsubscribe failed: {e}");
+            }
+        }
+    }
+
+    if query.relay_search != RelaySearch::Pending {
+        return;
+    }
+
+    let relays: HashSet<NormRelayUrl> = remote
+        .relay_inspect()
+        .relays_supporting(NIP_SEARCH)
+        .into_iter()
+        .cloned()
+        .collect();
+
+    if relays.is_empty() {
+        query.relay_search = RelaySearch::Unsupported;
+        return;
+    }
+
+    let local = query.filters.apply(Filter::new()).build();
+    query.relay_sub = match ndb.subscribe(&[local]) {
+        Ok(sub) => Some(sub),
+        Err(e) => {
+            error!("relay search: ndb subscribe failed: {e}");
+            None
+        }
+    };
+
+    let filter = query
+        .filters
+        .apply(Filter::new().search(&query.string))
+        .limit(RELAY_SEARCH_LIMIT)
+        .build();
+
+    info!("searching {} relays for '{}'", relays.len(), query.string);
+
+    let num_relays = relays.len();
+    remote.oneshot(accounts).oneshot_to(vec![filter], relays);
+
+    query.relay_search = RelaySearch::Running {
+        relays: num_relays,
+        started: Instant::now(),
+    };
 }
 
 fn execute_search(
     ctx: &egui::Context,
     search_type: &SearchType,
     raw_input: &String,
+    filters: &SearchFilters,
     ndb: &Ndb,
     txn: &Transaction,
     tab: &mut TimelineTab,
+    paging: &mut SearchPaging,
 ) {
+    *paging = SearchPaging::Done;
     if raw_input.is_empty() {
         return;
     }
 
-    let max_results = 500;
-
-    let Some(note_refs) = search_type.search(raw_input, ndb, txn, max_results) else {
+    let Some(note_refs) = search_type.search(raw_input, filters, ndb, txn, SEARCH_PAGE_SIZE) else {
         return;
     };
 
+    *paging = next_page(
+        &note_refs,
+        SEARCH_PAGE_SIZE,
+        note_refs.len(),
+        note_refs.len(),
+    );
     tab.units = TimelineUnits::from_refs_single(note_refs);
     tab.list.borrow_mut().reset();
     ctx.request_repaint();
 }
 
+/// Whether to keep paging after a page of at most `limit` results, `added`
+/// of which were new, leaving us with `total` results
+fn next_page(page: &[NoteRef], limit: u64, added: usize, total: usize) -> SearchPaging {
+    let full_page = page.len() as u64 >= limit;
+    let Some(oldest) = page.iter().map(|note_ref| note_ref.created_at).min() else {
+        return SearchPaging::Done;
+    };
+
+    // `until` is inclusive, so the next page starts with the notes created
+    // at `oldest` that we already have. Many notes can share a timestamp,
+    // so we count them to ask for past them rather than stepping over the
+    // second. A full page with nothing new would come back again forever.
+    if !full_page || added == 0 || total as u64 >= MAX_RESULTS {
+        SearchPaging::Done
+    } else {
+        SearchPaging::More {
+            until: oldest,
+            seen: page
+                .iter()
+                .filter(|note_ref| note_ref.created_at == oldest)
+                .count(),
+        }
+    }
+}
+
 enum SearchViewAction {
     NoteAction(NoteAction),
     NavigateToProfile(Pubkey),
@@ -439,6 +950,11 @@ enum SearchAction {
         new_search_text: String,
     },
     NavigateToProfile(Pubkey),
+    /// A pasted note link
+    OpenNote(NoteId),
+    /// A pasted `naddr` link
+    OpenAddress(NoteAddress),
+    OpenHashtag(String),
     Searched,
     CloseMention,
 }
@@ -461,6 +977,25 @@ impl SearchAction {
                 state.selected_index = -1;
                 Some(SearchViewAction::NavigateToProfile(pubkey))
             }
+            SearchAction::OpenNote(note_id) => {
+                state.add_recent_query(state.string.clone());
+                state.string.clear();
+                state.selected_index = -1;
+                Some(SearchViewAction::NoteAction(NoteAction::Permalink(note_id)))
+            }
+            SearchAction::OpenAddress(address) => {
+                state.add_recent_query(state.string.clone());
+                state.string.clear();
+                state.selected_index = -1;
+                Some(SearchViewAction::NoteAction(NoteAction::Address(address)))
+            }
+            SearchAction::OpenHashtag(hashtag) => {
+                state.add_recent_query(format!("#{hashtag}"));
+                state.string.clear();
+                state.clear_hashtag_suggestions();
+                state.selected_index = -1;
+                Some(SearchViewAction::NoteAction(NoteAction::Hashtag(hashtag)))
+            }
             SearchAction::CloseMention => {
                 state.state = SearchState::New;
                 state.selected_index = -1;
@@ -470,6 +1005,8 @@ impl SearchAction {
                 state.state = SearchState::Searched;
                 state.selected_index = -1;
                 state.user_results.clear();
+                state.last_user_query.clear();
+                state.clear_hashtag_suggestions();
                 state.add_recent_query(state.string.clone());
                 None
             }
@@ -514,12 +1051,12 @@ struct KeyboardResponse {
 fn handle_keyboard_navigation(
     ui: &mut egui::Ui,
     selected_index: &mut i32,
-    user_results: &[Vec<u8>],
+    result_count: usize,
 ) -> KeyboardResponse {
-    let max_index = if user_results.is_empty() {
+    let max_index = if result_count == 0 {
         -1
     } else {
-        user_results.len() as i32
+        result_count as i32
     };
 
     if ui.input(|i| i.key_pressed(Key::ArrowDown)) {
@@ -601,20 +1138,23 @@ pub enum SearchType {
     String,
     NoteId(NoteId),
     Profile(Pubkey),
+    /// An addressable note, from a pasted naddr
+    Address(NoteAddress),
     Hashtag(String),
 }
 
 impl SearchType {
     fn get_type(query: &str) -> Self {
-        if query.starts_with("nevent1") {
-            if let Some(noteid) = NoteId::from_nevent_bech(query) {
-                return SearchType::NoteId(noteid);
-            }
-        } else if query.len() == 63 && query.starts_with("note1") {
-            if let Some(noteid) = NoteId::from_bech(query) {
-                return SearchType::NoteId(noteid);
-            }
-        } else if let Some(pk) = parse_pubkey_query(query) {
+        // bech32 ids, bare or pasted as `nostr:` links
+        if let Some(link) = NostrLink::parse(query) {
+            return match link {
+                NostrLink::Profile(pubkey) => SearchType::Profile(pubkey),
+                NostrLink::Note(note_id) => SearchType::NoteId(note_id),
+                NostrLink::Address(address) => SearchType::Address(address),
+            };
+        }
+
+        if let Some(pk) = parse_pubkey_query(query) {
             return SearchType::Profile(Pubkey::new(pk));
         } else if query.chars().nth(0).is_some_and(|c| c == '#') {
             if let Some(hashtag) = query.get(1..) {
@@ -628,28 +1168,32 @@ impl SearchType {
     fn search(
         &self,
         raw_query: &String,
+        filters: &SearchFilters,
         ndb: &Ndb,
         txn: &Transaction,
         max_results: u64,
     ) -> Option<Vec<NoteRef>> {
         match self {
-            SearchType::String => search_string(raw_query, ndb, txn, max_results),
+            SearchType::String => search_string(raw_query, filters, ndb, txn, max_results),
             SearchType::NoteId(noteid) => search_note(noteid, ndb, txn).map(|n| vec![n]),
-            SearchType::Profile(pk) => search_pk(pk, ndb, txn, max_results),
-            SearchType::Hashtag(hashtag) => search_hashtag(hashtag, ndb, txn, max_results),
+            SearchType::Profile(pk) => search_pk(pk, filters, ndb, txn, max_results),
+            SearchType::Address(address) => address
+                .lookup(ndb, txn)
+                .map(|note| vec![NoteRef::from_note(&note)]),
+            SearchType::Hashtag(hashtag) => search_hashtag(hashtag, filters, ndb, txn, max_results),
         }
     }
 }
 
 fn search_string(
     query: &String,
+    filters: &SearchFilters,
     ndb: &Ndb,
     txn: &Transaction,
     max_results: u64,
 ) -> Option<Vec<NoteRef>> {
-    let filter = Filter::new()
-        .search(query)
-        .kinds([1])
+    let filter = filters
+        .apply(Filter::new().search(query))
         .limit(max_results)
         .build();
 
@@ -688,12 +1232,19 @@ fn search_note(noteid: &NoteId, ndb: &Ndb, txn: &Transaction) -> Option<NoteRef>
         .map(|n| NoteRef::from_note(&n))
 }
 
-fn search_pk(pk: &Pubkey, ndb: &Ndb, txn: &Transaction, max_results: u64) -> Option<Vec<NoteRef>> {
-    let filter = Filter::new()
-        .authors([pk.bytes()])
-        .kinds([1])
-        .limit(max_results)
-        .build();
+fn search_pk(
+    pk: &Pubkey,
+    filters: &SearchFilters,
+    ndb: &Ndb,
+    txn: &Transaction,
+    max_results: u64,
+) -> Option<Vec<NoteRef>> {
+    // the profile being searched takes the place of the author filter
+    let filters = SearchFilters {
+        author: Some(*pk),
+        ..filters.clone()
+    };
+    let filter = filters.apply(Filter::new()).limit(max_results).build();
 
     let qrs = ndb.query(txn, &[filter], max_results as i32).ok()?;
     Some(qrs.into_iter().map(NoteRef::from_query_result).collect())
@@ -701,12 +1252,13 @@ fn search_pk(pk: &Pubkey, ndb: &Ndb, txn: &Transaction, max_results: u64) -> Opt
 
 fn search_hashtag(
     hashtag_name: &str,
+    filters: &SearchFilters,
     ndb: &Ndb,
     txn: &Transaction,
     max_results: u64,
 ) -> Option<Vec<NoteRef>> {
-    let filter = Filter::new()
-        .kinds([1])
+    let filter = filters
+        .apply(Filter::new())
         .limit(max_results)
         .tags([hashtag_name], 't')
         .build();
@@ -787,6 +1339,56 @@ fn recent_search_item(
     }
 }
 
+fn hashtag_suggestion_item<'a>(
+    hashtag: &'a str,
+    uses: String,
+    is_selected: bool,
+    width: f32,
+) -> impl egui::Widget + 'a {
+    move |ui: &mut egui::Ui| -> egui::Response {
+        let height = 40.0;
+        let spacing = 8.0;
+        let body_font_size = get_font_size(ui.ctx(), &NotedeckTextStyle::Body);
+
+        let (rect, resp) = ui.allocate_exact_size(vec2(width, height), egui::Sense::click());
+
+        if is_selected {
+            ui.painter()
+                .rect_filled(rect, 4.0, ui.visuals().selection.bg_fill);
+        }
+
+        if resp.hovered() {
+            ui.painter()
+                .rect_filled(rect, 4.0, ui.visuals().widgets.hovered.bg_fill);
+        }
+
+        let font = egui::FontId::new(body_font_size, NotedeckTextStyle::Body.font_family());
+        let painter = ui.painter();
+        let uses_galley =
+            painter.layout_no_wrap(uses, font.clone(), ui.visuals().weak_text_color());
+        let tag_galley = painter.layout(
+            format!("#{hashtag}"),
+            font,
+            ui.visuals().text_color(),
+            width - uses_galley.rect.width() - 3.0 * spacing,
+        );
+
+        let uses_pos = egui::Pos2::new(
+            rect.right() - spacing - uses_galley.rect.width(),
+            rect.center().y - (uses_galley.rect.height() / 2.0),
+        );
+        let tag_pos = egui::Pos2::new(
+            rect.left() + spacing,
+            rect.center().y - (tag_galley.rect.height() / 2.0),
+        );
+
+        painter.galley(tag_pos, tag_galley, ui.visuals().text_color());
+        painter.galley(uses_pos, uses_galley, ui.visuals().weak_text_color());
+
+        resp
+    }
+}
+
 fn search_posts_button(query: &str, is_selected: bool, width: f32) -> impl egui::Widget + '_ {
     move |ui: &mut egui::Ui| -> egui::Response {
         let min_img_size = 48.0;
@@ -831,3 +1433,100 @@ fn search_posts_button(query: &str, is_selected: bool, width: f32) -> impl egui:
         resp
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nostrdb::NoteKey;
+
+    fn page(created_at: impl Iterator<Item = u64>) -> Vec<NoteRef> {
+        created_at
+            .enumerate()
+            .map(|(i, created_at)| NoteRef {
+                key: NoteKey::new(i as u64 + 1),
+                created_at,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn full_pages_continue_from_the_oldest_note() {
+        let full = page((0..SEARCH_PAGE_SIZE).map(|i| 1_000 - i));
+        let oldest = 1_000 - (SEARCH_PAGE_SIZE - 1);
+
+        assert_eq!(
+            next_page(&full, SEARCH_PAGE_SIZE, full.len(), full.len()),
+            SearchPaging::More {
+                until: oldest,
+                seen: 1
+            }
+        );
+    }
+
+    #[test]
+    fn paging_gets_past_notes_sharing_a_timestamp() {
+        // a whole page created in the same second
+        let same = page(std::iter::repeat_n(1_000, SEARCH_PAGE_SIZE as usize));
+        let next = next_page(&same, SEARCH_PAGE_SIZE, same.len(), same.len());
+        assert_eq!(
+            next,
+            SearchPaging::More {
+                until: 1_000,
+                seen: SEARCH_PAGE_SIZE as usize
+            }
+        );
+
+        // the next page asks for the notes we have plus a page more
+        let limit = 2 * SEARCH_PAGE_SIZE;
+        let mixed = page(
+            std::iter::repeat_n(1_000, 70).chain(std::iter::repeat_n(999, limit as usize - 70)),
+        );
+        assert_eq!(
+            next_page(&mixed, limit, 50, 100),
+            SearchPaging::More {
+                until: 999,
+                seen: limit as usize - 70
+            }
+        );
+    }
+
+    #[test]
+    fn paging_stops() {
+        let full = page((0..SEARCH_PAGE_SIZE).map(|i| 1_000 - i));
+        let short = page([1_000, 999].into_iter());
+
+        // the last page
+        assert_eq!(
+            next_page(&short, SEARCH_PAGE_SIZE, 2, 2),
+            SearchPaging::Done
+        );
+        // nothing new, we'd be asking for the same page again
+        assert_eq!(
+            next_page(&full, SEARCH_PAGE_SIZE, 0, 120),
+            SearchPaging::Done
+        );
+        // we have enough
+        assert_eq!(
+            next_page(&full, SEARCH_PAGE_SIZE, full.len(), MAX_RESULTS as usize),
+            SearchPaging::Done
+        );
+        assert_eq!(next_page(&[], SEARCH_PAGE_SIZE, 0, 0), SearchPaging::Done);
+    }
+
+    #[test]
+    fn pasted_links_are_recognized() {
+        let note_id = NoteId::new([7; 32]);
+        let bech = note_id.to_bech().unwrap();
+
+        assert_eq!(SearchType::get_type(&bech), SearchType::NoteId(note_id));
+        assert_eq!(
+            SearchType::get_type(&format!("nostr:{bech}")),
+            SearchType::NoteId(note_id)
+        );
+        assert_eq!(
+            SearchType::get_type("#nostr"),
+            SearchType::Hashtag("nostr".to_owned())
+        );
+        assert_eq!(SearchType::get_type("nostr"), SearchType::String);
+    }
+}
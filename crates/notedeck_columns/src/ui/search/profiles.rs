@@ -0,0 +1,119 @@
+use hashbrown::HashSet;
+use nostrdb::{Filter, Ndb, Transaction};
+use notedeck::{tr, ContactState, Localization};
+use notedeck_ui::{fuzzy_search_profiles, ProfileSearchResult};
+
+/// How many profiles the profiles tab shows
+pub const MAX_PROFILE_RESULTS: usize = 64;
+
+/// How many of the newest profiles we look through for nip05 matches. The
+/// ndb profile index only covers names.
+const NIP05_SCAN_LIMIT: u64 = 5000;
+
+/// Which kind of result the search view is showing
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SearchTab {
+    #[default]
+    Notes,
+    Profiles,
+}
+
+impl SearchTab {
+    pub const ALL: [SearchTab; 2] = [SearchTab::Notes, SearchTab::Profiles];
+
+    pub fn index(&self) -> usize {
+        match self {
+            SearchTab::Notes => 0,
+            SearchTab::Profiles => 1,
+        }
+    }
+
+    pub fn label(&self, i18n: &mut Localization) -> String {
+        match self {
+            SearchTab::Notes => tr!(i18n, "Notes", "Search results tab for notes"),
+            SearchTab::Profiles => tr!(i18n, "Profiles", "Search results tab for profiles"),
+        }
+    }
+}
+
+fn nip05_matches(nip05: &str, query: &str) -> bool {
+    nip05.to_lowercase().contains(&query.to_lowercase())
+}
+
+fn looks_like_nip05(query: &str) -> bool {
+    query.contains('@') || query.contains('.')
+}
+
+/// Profiles matching the query by name or display name, allowing for typos,
+/// plus nip05 matches when the query looks like an identifier. Followed
+/// profiles come first.
+pub fn search_profile_results(
+    ndb: &Ndb,
+    txn: &Transaction,
+    query: &str,
+    contacts: &ContactState,
+    max_results: usize,
+) -> Vec<ProfileSearchResult> {
+    let query = query.trim();
+    let mut results = fuzzy_search_profiles(ndb, txn, query, contacts, max_results);
+
+    if results.len() >= max_results || !looks_like_nip05(query) {
+        return results;
+    }
+
+    let mut seen: HashSet<[u8; 32]> = results.iter().map(|r| r.pk).collect();
+    let filter = Filter::new().kinds([0]).limit(NIP05_SCAN_LIMIT).build();
+    let Ok(profiles) = ndb.query(txn, &[filter], NIP05_SCAN_LIMIT as i32) else {
+        return results;
+    };
+
+    for result in profiles {
+        let pk = *result.note.pubkey();
+        if seen.contains(&pk) {
+            continue;
+        }
+
+        let nip05 = ndb.get_profile_by_pubkey(txn, &pk).ok().and_then(|p| {
+            p.record()
+                .profile()
+                .and_then(|p| p.nip05())
+                .map(str::to_owned)
+        });
+
+        if !nip05.is_some_and(|nip05| nip05_matches(&nip05, query)) {
+            continue;
+        }
+
+        seen.insert(pk);
+        let is_contact = matches!(
+            contacts,
+            ContactState::Received { contacts, .. } if contacts.contains(&pk)
+        );
+        results.push(ProfileSearchResult { pk, is_contact });
+
+        if results.len() >= max_results {
+            break;
+        }
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nip05_matches_user_and_domain() {
+        assert!(nip05_matches("jb55@jb55.com", "JB55@"));
+        assert!(nip05_matches("jb55@jb55.com", "jb55.com"));
+        assert!(!nip05_matches("jb55@jb55.com", "damus.io"));
+    }
+
+    #[test]
+    fn only_identifier_queries_scan_nip05() {
+        assert!(looks_like_nip05("jb55.com"));
+        assert!(looks_like_nip05("@jb55"));
+        assert!(!looks_like_nip05("will"));
+    }
+}
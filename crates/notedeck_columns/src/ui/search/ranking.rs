@@ -0,0 +1,257 @@
+use nostrdb::{Ndb, Transaction};
+use notedeck::{tr, Localization};
+
+use crate::timeline::TimelineUnits;
+
+/// A note loses half of its recency bonus every week
+const RECENCY_HALF_LIFE_SECS: f64 = 7.0 * 24.0 * 60.0 * 60.0;
+
+/// Weight of the recency bonus, which is 1.0 for a brand new note
+const RECENCY_WEIGHT: f64 = 0.5;
+
+/// Bonus for notes by someone the selected account follows
+const FOLLOWED_BOOST: f64 = 0.5;
+
+/// Weight of the proximity bonus, which is 1.0 when all terms sit next to
+/// each other
+const PROXIMITY_WEIGHT: f64 = 0.5;
+
+/// How search results are ordered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SearchSort {
+    #[default]
+    Relevance,
+    Newest,
+}
+
+impl SearchSort {
+    pub const ALL: [SearchSort; 2] = [SearchSort::Relevance, SearchSort::Newest];
+
+    pub fn label(&self, i18n: &mut Localization) -> String {
+        match self {
+            SearchSort::Relevance => tr!(
+                i18n,
+                "Relevance",
+                "Search sort option ranking the best matches first"
+            ),
+            SearchSort::Newest => tr!(
+                i18n,
+                "Newest",
+                "Search sort option showing the newest results first"
+            ),
+        }
+    }
+}
+
+/// Lowercased words of a search query, without duplicates
+pub fn query_terms(query: &str) -> Vec<String> {
+    let mut terms: Vec<String> = Vec::new();
+    for word in query.split_whitespace() {
+        let term = word.trim_start_matches('#').to_lowercase();
+        if !term.is_empty() && !terms.contains(&term) {
+            terms.push(term);
+        }
+    }
+    terms
+}
+
+/// How close together the terms appear, from 1.0 when they are adjacent
+/// words down towards 0.0. Zero unless every one of two or more terms is
+/// present.
+fn term_proximity(content: &str, terms: &[String]) -> f64 {
+    if terms.len() < 2 {
+        return 0.0;
+    }
+
+    let words: Vec<&str> = content
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    let positions: Vec<Vec<usize>> = terms
+        .iter()
+        .map(|term| {
+            words
+                .iter()
+                .enumerate()
+                .filter(|(_, word)| word.contains(term.as_str()))
+                .map(|(i, _)| i)
+                .collect()
+        })
+        .collect();
+
+    if positions.iter().any(Vec::is_empty) {
+        return 0.0;
+    }
+
+    // sweep over every (position, term) pair in order, keeping the smallest
+    // window that contains all of the terms
+    let mut hits: Vec<(usize, usize)> = positions
+        .iter()
+        .enumerate()
+        .flat_map(|(term, ps)| ps.iter().map(move |p| (*p, term)))
+        .collect();
+    hits.sort_unstable();
+
+    let mut counts = vec![0usize; terms.len()];
+    let mut covered = 0;
+    let mut start = 0;
+    let mut best = usize::MAX;
+
+    for &(pos, term) in &hits {
+        if counts[term] == 0 {
+            covered += 1;
+        }
+        counts[term] += 1;
+
+        while covered == terms.len() {
+            let (start_pos, start_term) = hits[start];
+            best = best.min(pos - start_pos + 1);
+            counts[start_term] -= 1;
+            if counts[start_term] == 0 {
+                covered -= 1;
+            }
+            start += 1;
+        }
+    }
+
+    // a single word can contain several terms
+    let span = best.max(terms.len());
+    (terms.len() - 1) as f64 / (span - 1) as f64
+}
+
+/// Score a single result. Each query term adds the square root of its
+/// occurrences, so repeating a word helps less than matching another one.
+/// Terms appearing close together, recency and whether we follow the author
+/// are added on top.
+pub fn relevance_score(
+    content: &str,
+    terms: &[String],
+    created_at: u64,
+    now: u64,
+    followed: bool,
+) -> f64 {
+    let content = content.to_lowercase();
+    let term_frequency: f64 = terms
+        .iter()
+        .map(|term| (content.matches(term.as_str()).count() as f64).sqrt())
+        .sum();
+
+    let proximity = term_proximity(&content, terms);
+
+    let age = now.saturating_sub(created_at) as f64;
+    let recency = 0.5f64.powf(age / RECENCY_HALF_LIFE_SECS);
+
+    let followed = if followed { FOLLOWED_BOOST } else { 0.0 };
+
+    term_frequency + PROXIMITY_WEIGHT * proximity + RECENCY_WEIGHT * recency + followed
+}
+
+/// Indices into `units` ordered from the best match to the worst. Ties keep
+/// their chronological order.
+pub fn rank_units(
+    units: &TimelineUnits,
+    query: &str,
+    ndb: &Ndb,
+    txn: &Transaction,
+    now: u64,
+    is_followed: impl Fn(&[u8; 32]) -> bool,
+) -> Vec<usize> {
+    let terms = query_terms(query);
+    let scores: Vec<f64> = (0..units.len())
+        .map(|i| {
+            let Some(unit) = units.get(i) else {
+                return 0.0;
+            };
+            let note_ref = unit.get_underlying_noteref();
+            let Ok(note) = ndb.get_note_by_key(txn, note_ref.key) else {
+                return 0.0;
+            };
+
+            relevance_score(
+                note.content(),
+                &terms,
+                note.created_at(),
+                now,
+                is_followed(note.pubkey()),
+            )
+        })
+        .collect();
+
+    order_by_score(&scores)
+}
+
+fn order_by_score(scores: &[f64]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..scores.len()).collect();
+    order.sort_by(|a, b| scores[*b].total_cmp(&scores[*a]));
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_700_000_000;
+    const DAY: u64 = 24 * 60 * 60;
+
+    #[test]
+    fn terms_are_lowercased_and_deduped() {
+        assert_eq!(query_terms("Nostr #nostr  Relay"), vec!["nostr", "relay"]);
+        assert!(query_terms("  # ").is_empty());
+    }
+
+    #[test]
+    fn more_matches_rank_higher() {
+        let q = query_terms("nostr relay");
+        let both = relevance_score("Nostr needs a relay", &q, NOW, NOW, false);
+        let one = relevance_score("nostr is neat", &q, NOW, NOW, false);
+        let repeated = relevance_score("nostr nostr nostr", &q, NOW, NOW, false);
+
+        assert!(both > one);
+        // repeating one word shouldn't beat matching every word
+        assert!(both > repeated);
+        assert!(repeated > one);
+    }
+
+    #[test]
+    fn nearby_terms_rank_higher() {
+        let q = query_terms("nostr relay");
+        let adjacent = relevance_score("run a nostr relay today", &q, NOW, NOW, false);
+        let distant = relevance_score(
+            "nostr is neat, and one day I will run a relay",
+            &q,
+            NOW,
+            NOW,
+            false,
+        );
+
+        assert!(adjacent > distant);
+        assert!((term_proximity("nostr relay", &q) - 1.0).abs() < 1e-9);
+        assert!((term_proximity("nostr and a relay", &q) - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(term_proximity("only nostr here", &q), 0.0);
+    }
+
+    #[test]
+    fn recency_decays() {
+        let q = query_terms("nostr");
+        let fresh = relevance_score("nostr", &q, NOW, NOW, false);
+        let week_old = relevance_score("nostr", &q, NOW - 7 * DAY, NOW, false);
+        let ancient = relevance_score("nostr", &q, NOW - 700 * DAY, NOW, false);
+
+        assert!(fresh > week_old && week_old > ancient);
+        assert!((fresh - week_old - RECENCY_WEIGHT / 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn followed_authors_get_a_boost() {
+        let q = query_terms("nostr");
+        let followed = relevance_score("nostr", &q, NOW - DAY, NOW, true);
+        let stranger = relevance_score("nostr", &q, NOW - DAY, NOW, false);
+        assert!((followed - stranger - FOLLOWED_BOOST).abs() < 1e-9);
+    }
+
+    #[test]
+    fn ties_keep_chronological_order() {
+        assert_eq!(order_by_score(&[1.0, 3.0, 1.0, 2.0]), vec![1, 3, 0, 2]);
+    }
+}
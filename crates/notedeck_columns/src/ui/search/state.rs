@@ -1,8 +1,10 @@
 use crate::timeline::TimelineTab;
 use enostr::Pubkey;
+use nostrdb::Subscription;
 use notedeck_ui::ProfileSearchResult;
+use std::time::Instant;
 
-use super::SearchType;
+use super::{HashtagCount, SearchFilters, SearchFiltersEditor, SearchSort, SearchTab, SearchType};
 
 #[derive(Debug, Eq, PartialEq)]
 pub enum SearchState {
@@ -13,6 +15,19 @@ pub enum SearchState {
     New,
 }
 
+/// Local results are loaded a page at a time, so the newest matches show up
+/// before a large query is done
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SearchPaging {
+    #[default]
+    Done,
+
+    /// The next page holds notes created at or before `until`. We already
+    /// have `seen` of the notes created exactly at `until`, so the page asks
+    /// for that many more to get past them.
+    More { until: u64, seen: usize },
+}
+
 #[derive(Debug, Eq, PartialEq)]
 pub enum TypingType {
     Mention(String),
@@ -24,6 +39,26 @@ pub enum RecentSearchItem {
     Profile { pubkey: Pubkey, query: String },
 }
 
+/// Progress of the NIP-50 relay search for the current query
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Default)]
+pub enum RelaySearch {
+    #[default]
+    Idle,
+
+    /// Waiting to be sent to the relays that support search
+    Pending,
+
+    /// Sent to `relays`. Results land in ndb, where `relay_sub` picks them
+    /// up as they arrive
+    Running { relays: usize, started: Instant },
+
+    /// We stopped waiting for more relay results
+    Done,
+
+    /// None of our relays advertise search support
+    Unsupported,
+}
+
 #[derive(Debug, Eq, PartialEq, Clone, Default)]
 pub enum FocusState {
     /// Get ready to focus
@@ -53,12 +88,18 @@ pub struct SearchQueryState {
     /// The search results
     pub notes: TimelineTab,
 
+    /// Whether there are more local results to load into `notes`
+    pub paging: SearchPaging,
+
     /// Currently selected item index in search results (-1 = none, 0 = "search posts", 1+ = users)
     pub selected_index: i32,
 
     /// Cached user search results for the current query
     pub user_results: Vec<Vec<u8>>,
 
+    /// The query string that produced `user_results`
+    pub last_user_query: String,
+
     /// Recent search history (most recent first, max 10)
     pub recent_searches: Vec<RecentSearchItem>,
 
@@ -67,6 +108,44 @@ pub struct SearchQueryState {
 
     /// The query string that produced `mention_results`
     pub last_mention_query: String,
+
+    /// Hashtags harvested from recent notes for `#` autocomplete, `None`
+    /// until the query first starts with `#`
+    pub recent_hashtags: Option<Vec<HashtagCount>>,
+
+    /// Cached `#` autocomplete suggestions
+    pub hashtag_results: Vec<HashtagCount>,
+
+    /// The query string that produced `hashtag_results`
+    pub last_hashtag_query: String,
+
+    /// Also send post searches to relays that support NIP-50
+    pub search_relays: bool,
+
+    /// Relay search progress for the current query
+    pub relay_search: RelaySearch,
+
+    /// Local subscription mirroring the relay search while it runs
+    pub relay_sub: Option<Subscription>,
+
+    /// Kind, author and date constraints applied to post searches
+    pub filters: SearchFilters,
+
+    /// Input state of the filter controls
+    pub filters_editor: SearchFiltersEditor,
+
+    /// How the results in `notes` are ordered
+    pub sort: SearchSort,
+
+    /// Indices into `notes` from best to worst match, used when sorting by
+    /// relevance
+    pub ranked: Vec<usize>,
+
+    /// Whether notes or profiles are shown for a text search
+    pub tab: SearchTab,
+
+    /// Profiles matching the last text search
+    pub profile_results: Vec<ProfileSearchResult>,
 }
 
 impl Default for SearchQueryState {
@@ -81,12 +160,26 @@ impl SearchQueryState {
             string: "".to_string(),
             state: SearchState::New,
             notes: TimelineTab::default(),
+            paging: SearchPaging::default(),
             focus_state: FocusState::Navigating,
             selected_index: -1,
             user_results: Vec::new(),
+            last_user_query: String::new(),
             recent_searches: Vec::new(),
             mention_results: Vec::new(),
             last_mention_query: String::new(),
+            recent_hashtags: None,
+            hashtag_results: Vec::new(),
+            last_hashtag_query: String::new(),
+            search_relays: false,
+            relay_search: RelaySearch::Idle,
+            relay_sub: None,
+            filters: SearchFilters::default(),
+            filters_editor: SearchFiltersEditor::default(),
+            sort: SearchSort::default(),
+            ranked: Vec::new(),
+            tab: SearchTab::default(),
+            profile_results: Vec::new(),
         }
     }
 
@@ -127,4 +220,11 @@ impl SearchQueryState {
     pub fn clear_recent_searches(&mut self) {
         self.recent_searches.clear();
     }
+
+    /// Forget the harvested hashtags so the next `#` query sees new notes
+    pub fn clear_hashtag_suggestions(&mut self) {
+        self.recent_hashtags = None;
+        self.hashtag_results.clear();
+        self.last_hashtag_query.clear();
+    }
 }
@@ -3,9 +3,14 @@ use egui::{
     TextEdit, ThemePreference,
 };
 use egui_extras::{Size, StripBuilder};
+use enostr::Pubkey;
 use notedeck::{
-    tr, ui::richtext_small, DragResponse, LanguageIdentifier, NoteContext, NotedeckTextStyle,
-    Settings, DEFAULT_MAX_HASHTAGS_PER_NOTE,
+    is_open_with_template, tr, tr_plural,
+    ui::{is_compiled_as_mobile, richtext_small},
+    ActionBarItem, ContentWarningMode, DragResponse, KeyExport, LanguageIdentifier, Localization,
+    NoteContext, NotedeckTextStyle, OsNotificationKind, Settings, SigPolicy, ZapPreferences,
+    DEFAULT_ACTION_BAR, DEFAULT_MAX_HASHTAGS_PER_NOTE, DEFAULT_MAX_PREVIEW_DEPTH,
+    DEFAULT_PROFILE_REFRESH_HOURS, OPEN_WITH_PLACEHOLDER,
 };
 use notedeck_ui::{
     app_images::{copy_to_clipboard_dark_image, copy_to_clipboard_image},
@@ -14,7 +19,13 @@ use notedeck_ui::{
 
 use nostrdb::Transaction;
 
-use crate::{nav::RouterAction, ui::account_login_view::eye_button, Damus, Route};
+use crate::{
+    identity_archive::{self, ArchiveStatus, IdentityArchiveState},
+    key_export::KeyExportState,
+    nav::RouterAction,
+    ui::account_login_view::eye_button,
+    Damus, Route,
+};
 
 const PREVIEW_NOTE_ID: [u8; 32] = [
     0xcb, 0x65, 0x83, 0xa1, 0x12, 0x7f, 0xae, 0xe6, 0x7b, 0xc8, 0x55, 0x5f, 0x4e, 0x5f, 0x52, 0x54,
@@ -33,7 +44,26 @@ pub enum SettingsAction {
     SetRepliestNewestFirst(bool),
     SetAnimateNavTransitions(bool),
     SetMaxHashtagsPerNote(usize),
+    SetKeepMediaMetadata(bool),
+    SetOsKeyring(bool),
+    SetVerifyPublishes(bool),
+    SetSigPolicy(SigPolicy),
+    SetSendTypingIndicators(bool),
+    SetOsNotification(OsNotificationKind, bool),
+    SetMaxPreviewDepth(u8),
+    SetProfileRefreshHours(u32),
+    SetActionBar(Vec<ActionBarItem>),
+    SetOpenWithTemplate(String),
+    SetHideBadges(bool),
+    SetContentWarningMode(Pubkey, ContentWarningMode),
+    RotateKey,
+    ExportIdentity,
+    ImportIdentity,
+    SetZapPreferences(Pubkey, ZapPreferences),
     OpenRelays,
+    OpenMediaServers,
+    OpenMuteList,
+    OpenDrafts,
     OpenCacheFolder,
     ClearCacheFolder,
     CompactDatabase,
@@ -52,6 +82,15 @@ impl SettingsAction {
             Self::OpenRelays => {
                 route_action = Some(RouterAction::route_to(Route::Relays));
             }
+            Self::OpenMediaServers => {
+                route_action = Some(RouterAction::route_to(Route::MediaServers));
+            }
+            Self::OpenMuteList => {
+                route_action = Some(RouterAction::route_to(Route::MuteList));
+            }
+            Self::OpenDrafts => {
+                route_action = Some(RouterAction::route_to(Route::Drafts));
+            }
             Self::SetZoomFactor(zoom_factor) => {
                 egui_ctx.set_zoom_factor(zoom_factor);
                 app_ctx.settings.set_zoom_factor(zoom_factor);
@@ -84,42 +123,114 @@ impl SettingsAction {
                 app_ctx.settings.set_max_hashtags_per_note(value);
                 app_ctx.accounts.update_max_hashtags_per_note(value);
             }
+            Self::SetKeepMediaMetadata(value) => {
+                app.note_options.set(NoteOptions::KeepMediaMetadata, value);
+                app_ctx.settings.set_keep_media_metadata(value);
+            }
+            Self::SetVerifyPublishes(value) => {
+                app_ctx.settings.set_verify_publishes(value);
+            }
+            Self::SetOsKeyring(value) => {
+                app_ctx.settings.set_os_keyring(value);
+                app_ctx.accounts.set_os_keyring(value);
+            }
+            Self::SetSigPolicy(value) => {
+                app_ctx.settings.set_sig_policy(value);
+            }
+            Self::SetSendTypingIndicators(value) => {
+                app_ctx.settings.set_send_typing_indicators(value);
+            }
+            Self::SetOsNotification(kind, enabled) => {
+                app_ctx.settings.set_os_notification(kind, enabled);
+            }
+            Self::SetMaxPreviewDepth(value) => {
+                app.note_options.set_max_preview_depth(value);
+                app_ctx.settings.set_max_preview_depth(value);
+            }
+            Self::SetProfileRefreshHours(value) => {
+                app_ctx.settings.set_profile_refresh_hours(value);
+                app_ctx
+                    .unknown_ids
+                    .set_profile_max_age(app_ctx.settings.profile_max_age());
+            }
+            Self::SetActionBar(items) => app_ctx.settings.set_action_bar(items),
+            Self::SetOpenWithTemplate(template) => {
+                app.note_options.set(
+                    NoteOptions::OpenWithTemplate,
+                    is_open_with_template(&template),
+                );
+                app_ctx.settings.set_open_with_template(template);
+            }
+            Self::SetHideBadges(value) => {
+                app.note_options.set(NoteOptions::HideBadges, value);
+                app_ctx.settings.set_hide_badges(value);
+            }
+            Self::SetContentWarningMode(account, mode) => {
+                app_ctx.settings.set_content_warning_mode(&account, mode);
+            }
+            // moving to a new key switches accounts, which happens in nav
+            Self::RotateKey => {}
+            Self::ExportIdentity => {
+                identity_archive::export_identity(
+                    app_ctx.ndb,
+                    app_ctx.accounts,
+                    app_ctx.job_pool,
+                    &mut app.view_state.identity_archive,
+                );
+            }
+            // importing switches to the imported account, so it's done in nav
+            Self::ImportIdentity => {}
+            Self::SetZapPreferences(account, preferences) => {
+                app_ctx.settings.set_zap_preferences(&account, preferences);
+            }
             Self::CompactDatabase => {
-                let own_pubkeys: Vec<[u8; 32]> = app_ctx
-                    .accounts
-                    .cache
-                    .accounts()
-                    .map(|a| *a.key.pubkey.bytes())
-                    .collect();
+                compact_database(app_ctx, &mut app.view_state.compact);
+            }
+        }
+        route_action
+    }
+}
+
+/// Start compacting the database in the background, keeping all profiles and
+/// the notes of the accounts that are still signed in.
+pub fn compact_database(
+    app_ctx: &mut notedeck::AppContext<'_>,
+    compact: &mut notedeck::compact::CompactState,
+) {
+    if matches!(compact.status, notedeck::compact::CompactStatus::Running(_)) {
+        return;
+    }
+
+    let own_pubkeys: Vec<[u8; 32]> = app_ctx
+        .accounts
+        .cache
+        .accounts()
+        .map(|a| *a.key.pubkey.bytes())
+        .collect();
 
-                let db_path = app_ctx.args.db_path(app_ctx.path);
-                let compact_path = app_ctx.args.db_compact_path(app_ctx.path);
-                let _ = std::fs::create_dir_all(&compact_path);
+    let db_path = app_ctx.args.db_path(app_ctx.path);
+    let compact_path = app_ctx.args.db_compact_path(app_ctx.path);
+    let _ = std::fs::create_dir_all(&compact_path);
 
-                let old_size = std::fs::metadata(db_path.join("data.mdb"))
+    let old_size = std::fs::metadata(db_path.join("data.mdb"))
+        .map(|m| m.len())
+        .unwrap_or(0);
+
+    let compact_path_str = compact_path.to_str().unwrap_or("").to_string();
+    let ndb = app_ctx.ndb.clone();
+
+    let receiver = app_ctx.job_pool.schedule_receivable(move || {
+        ndb.compact(&compact_path_str, &own_pubkeys)
+            .map(|()| {
+                let new_size = std::fs::metadata(format!("{compact_path_str}/data.mdb"))
                     .map(|m| m.len())
                     .unwrap_or(0);
+                notedeck::compact::CompactResult { old_size, new_size }
+            })
+            .map_err(|e| format!("{e}"))
+    });
 
-                let compact_path_str = compact_path.to_str().unwrap_or("").to_string();
-                let ndb = app_ctx.ndb.clone();
-
-                let receiver = app_ctx.job_pool.schedule_receivable(move || {
-                    ndb.compact(&compact_path_str, &own_pubkeys)
-                        .map(|()| {
-                            let new_size =
-                                std::fs::metadata(format!("{compact_path_str}/data.mdb"))
-                                    .map(|m| m.len())
-                                    .unwrap_or(0);
-                            notedeck::compact::CompactResult { old_size, new_size }
-                        })
-                        .map_err(|e| format!("{e}"))
-                });
-
-                app.view_state.compact.status = notedeck::compact::CompactStatus::Running(receiver);
-            }
-        }
-        route_action
-    }
+    compact.status = notedeck::compact::CompactStatus::Running(receiver);
 }
 
 pub struct SettingsView<'a> {
@@ -127,6 +238,8 @@ pub struct SettingsView<'a> {
     note_context: &'a mut NoteContext<'a>,
     db_path: &'a std::path::Path,
     compact: &'a mut notedeck::compact::CompactState,
+    identity_archive: &'a mut IdentityArchiveState,
+    key_export: &'a mut KeyExportState,
 }
 
 fn settings_group<S>(ui: &mut egui::Ui, title: S, contents: impl FnOnce(&mut egui::Ui))
@@ -154,12 +267,16 @@ impl<'a> SettingsView<'a> {
         note_context: &'a mut NoteContext<'a>,
         db_path: &'a std::path::Path,
         compact: &'a mut notedeck::compact::CompactState,
+        identity_archive: &'a mut IdentityArchiveState,
+        key_export: &'a mut KeyExportState,
     ) -> Self {
         Self {
             settings,
             note_context,
             db_path,
             compact,
+            identity_archive,
+            key_export,
         }
     }
 
@@ -586,6 +703,33 @@ impl<'a> SettingsView<'a> {
                 }
             });
 
+            ui.horizontal_wrapped(|ui| {
+                ui.label(richtext_small(tr!(
+                    self.note_context.i18n,
+                    "Hide profile badges:",
+                    "Label for hiding NIP-58 badges on profiles, others settings section",
+                )));
+
+                if ui
+                    .toggle_value(
+                        &mut self.settings.hide_badges,
+                        RichText::new(tr!(
+                            self.note_context.i18n,
+                            "On",
+                            "Setting to hide the badges shown on profiles"
+                        ))
+                        .text_style(NotedeckTextStyle::Small.text_style()),
+                    )
+                    .changed()
+                {
+                    action = Some(SettingsAction::SetHideBadges(self.settings.hide_badges));
+                }
+            });
+
+            if let Some(cw_action) = self.content_warning_option(ui) {
+                action = Some(cw_action);
+            }
+
             ui.horizontal_wrapped(|ui| {
                 ui.label(richtext_small("Animate view transitions:"));
 
@@ -653,12 +797,395 @@ impl<'a> SettingsView<'a> {
                     richtext_small(&text).color(ui.visuals().gray_out(ui.visuals().text_color())),
                 );
             });
+
+            ui.horizontal_wrapped(|ui| {
+                ui.label(richtext_small(tr!(
+                    self.note_context.i18n,
+                    "Nested quote previews:",
+                    "Label for how many levels of quoted notes are previewed, others settings section",
+                )));
+
+                if ui
+                    .add(
+                        egui::Slider::new(&mut self.settings.max_preview_depth, 1..=3)
+                            .text("")
+                            .step_by(1.0),
+                    )
+                    .changed()
+                {
+                    action = Some(SettingsAction::SetMaxPreviewDepth(
+                        self.settings.max_preview_depth,
+                    ));
+                };
+
+                if ui
+                    .button(richtext_small(tr!(
+                        self.note_context.i18n,
+                        "Reset",
+                        "Label for reset nested quote previews, others settings section",
+                    )))
+                    .clicked()
+                {
+                    action = Some(SettingsAction::SetMaxPreviewDepth(
+                        DEFAULT_MAX_PREVIEW_DEPTH,
+                    ));
+                }
+            });
+
+            ui.horizontal_wrapped(|ui| {
+                ui.label(richtext_small(tr!(
+                    self.note_context.i18n,
+                    "Refresh profiles older than (hours):",
+                    "Label for how old cached profiles get before viewing them refetches them, others settings section",
+                )));
+
+                if ui
+                    .add(
+                        egui::Slider::new(&mut self.settings.profile_refresh_hours, 1..=168)
+                            .text("")
+                            .step_by(1.0),
+                    )
+                    .changed()
+                {
+                    action = Some(SettingsAction::SetProfileRefreshHours(
+                        self.settings.profile_refresh_hours,
+                    ));
+                };
+
+                if ui
+                    .button(richtext_small(tr!(
+                        self.note_context.i18n,
+                        "Reset",
+                        "Label for reset profile refresh age, others settings section",
+                    )))
+                    .clicked()
+                {
+                    action = Some(SettingsAction::SetProfileRefreshHours(
+                        DEFAULT_PROFILE_REFRESH_HOURS,
+                    ));
+                }
+            });
+
+            ui.horizontal_wrapped(|ui| {
+                ui.label(richtext_small(tr!(
+                    self.note_context.i18n,
+                    "Keep image metadata on upload:",
+                    "Label for keeping EXIF metadata in uploaded images, others settings section",
+                )));
+
+                if ui
+                    .toggle_value(
+                        &mut self.settings.keep_media_metadata,
+                        RichText::new(tr!(
+                            self.note_context.i18n,
+                            "On",
+                            "Setting to keep EXIF metadata in uploaded images"
+                        ))
+                        .text_style(NotedeckTextStyle::Small.text_style()),
+                    )
+                    .changed()
+                {
+                    action = Some(SettingsAction::SetKeepMediaMetadata(
+                        self.settings.keep_media_metadata,
+                    ));
+                }
+            });
+
+            if self.settings.keep_media_metadata {
+                ui.label(
+                    richtext_small(tr!(
+                        self.note_context.i18n,
+                        "Uploaded photos may reveal where they were taken",
+                        "Warning shown when image metadata is kept on upload"
+                    ))
+                    .color(ui.visuals().warn_fg_color),
+                );
+            }
+
+            ui.horizontal_wrapped(|ui| {
+                ui.label(richtext_small(tr!(
+                    self.note_context.i18n,
+                    "Show when I'm typing a message:",
+                    "Label for sending typing indicators in direct messages, others settings section",
+                )));
+
+                if ui
+                    .toggle_value(
+                        &mut self.settings.send_typing_indicators,
+                        RichText::new(tr!(
+                            self.note_context.i18n,
+                            "On",
+                            "Setting to send typing indicators in direct messages"
+                        ))
+                        .text_style(NotedeckTextStyle::Small.text_style()),
+                    )
+                    .changed()
+                {
+                    action = Some(SettingsAction::SetSendTypingIndicators(
+                        self.settings.send_typing_indicators,
+                    ));
+                }
+            });
+
+            if !is_compiled_as_mobile() {
+                for kind in OsNotificationKind::ALL {
+                    if let Some(a) = self.os_notification_toggle(ui, kind) {
+                        action = Some(a);
+                    }
+                }
+            }
+
+            ui.horizontal_wrapped(|ui| {
+                ui.label(richtext_small(tr!(
+                    self.note_context.i18n,
+                    "Open with link:",
+                    "Label for the link template used to open notes and profiles in another client, others settings section",
+                )))
+                .on_hover_text(tr!(
+                    self.note_context.i18n,
+                    "{placeholder} is replaced with the note or profile's id",
+                    "Tooltip explaining the open with link template",
+                    placeholder = OPEN_WITH_PLACEHOLDER
+                ));
+
+                let input = ui.add(
+                    TextEdit::singleline(&mut self.settings.open_with_template)
+                        .hint_text(format!("https://example.com/{OPEN_WITH_PLACEHOLDER}"))
+                        .desired_width(220.0),
+                );
+                if input.changed() {
+                    action = Some(SettingsAction::SetOpenWithTemplate(
+                        self.settings.open_with_template.clone(),
+                    ));
+                }
+            });
+
+            let template = self.settings.open_with_template.trim();
+            if !template.is_empty() && !is_open_with_template(template) {
+                ui.label(
+                    richtext_small(tr!(
+                        self.note_context.i18n,
+                        "The link needs {placeholder} where the id goes",
+                        "Warning when the open with link template has no placeholder",
+                        placeholder = OPEN_WITH_PLACEHOLDER
+                    ))
+                    .color(ui.visuals().warn_fg_color),
+                );
+            }
+        });
+
+        action
+    }
+
+    fn os_notification_toggle(
+        &mut self,
+        ui: &mut egui::Ui,
+        kind: OsNotificationKind,
+    ) -> Option<SettingsAction> {
+        let label = match kind {
+            OsNotificationKind::Mention => tr!(
+                self.note_context.i18n,
+                "System notifications for mentions and replies:",
+                "Label for desktop notifications of mentions while notedeck is in the background, others settings section",
+            ),
+            OsNotificationKind::DirectMessage => tr!(
+                self.note_context.i18n,
+                "System notifications for direct messages:",
+                "Label for desktop notifications of direct messages while notedeck is in the background, others settings section",
+            ),
+        };
+
+        let mut enabled = self.settings.os_notifications.contains(&kind);
+        let mut action = None;
+
+        ui.horizontal_wrapped(|ui| {
+            ui.label(richtext_small(label));
+
+            if ui
+                .toggle_value(
+                    &mut enabled,
+                    RichText::new(tr!(
+                        self.note_context.i18n,
+                        "On",
+                        "Setting to show a system notification while notedeck is in the background"
+                    ))
+                    .text_style(NotedeckTextStyle::Small.text_style()),
+                )
+                .changed()
+            {
+                if enabled {
+                    self.settings.os_notifications.insert(kind);
+                } else {
+                    self.settings.os_notifications.remove(&kind);
+                }
+                action = Some(SettingsAction::SetOsNotification(kind, enabled));
+            }
+        });
+
+        action
+    }
+
+    fn action_bar_section(&mut self, ui: &mut egui::Ui) -> Option<SettingsAction> {
+        let mut action = None;
+        let title = tr!(
+            self.note_context.i18n,
+            "Note actions",
+            "Label for the note action bar settings section"
+        );
+
+        settings_group(ui, title, |ui| {
+            let items = &self.settings.action_bar;
+            let mut new_items: Option<Vec<ActionBarItem>> = None;
+
+            for (i, item) in items.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.label(richtext_small(action_bar_item_label(
+                        self.note_context.i18n,
+                        *item,
+                    )));
+
+                    ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                        let remove = ui.button(richtext_small(tr!(
+                            self.note_context.i18n,
+                            "Remove",
+                            "Button to remove a button from the note action bar"
+                        )));
+                        if remove.clicked() {
+                            let mut updated = items.clone();
+                            updated.remove(i);
+                            new_items = Some(updated);
+                        }
+
+                        if ui
+                            .add_enabled(i + 1 < items.len(), Button::new("▼"))
+                            .clicked()
+                        {
+                            let mut updated = items.clone();
+                            updated.swap(i, i + 1);
+                            new_items = Some(updated);
+                        }
+
+                        if ui.add_enabled(i > 0, Button::new("▲")).clicked() {
+                            let mut updated = items.clone();
+                            updated.swap(i - 1, i);
+                            new_items = Some(updated);
+                        }
+                    });
+                });
+            }
+
+            ui.horizontal_wrapped(|ui| {
+                for item in ActionBarItem::ALL {
+                    if items.contains(&item) {
+                        continue;
+                    }
+
+                    let label =
+                        format!("+ {}", action_bar_item_label(self.note_context.i18n, item));
+                    if ui.button(richtext_small(label)).clicked() {
+                        let mut updated = items.clone();
+                        updated.push(item);
+                        new_items = Some(updated);
+                    }
+                }
+
+                if ui
+                    .button(richtext_small(tr!(
+                        self.note_context.i18n,
+                        "Reset",
+                        "Label for reset note action bar, note actions settings section",
+                    )))
+                    .clicked()
+                {
+                    new_items = Some(DEFAULT_ACTION_BAR.to_vec());
+                }
+            });
+
+            action = new_items.map(SettingsAction::SetActionBar);
         });
 
         action
     }
 
-    fn keys_section(&mut self, ui: &mut egui::Ui) {
+    fn zaps_section(&mut self, ui: &mut egui::Ui) -> Option<SettingsAction> {
+        let account = self.note_context.accounts.get_selected_account();
+        if account.key.secret_key.is_none() {
+            return None;
+        }
+        let pubkey = account.key.pubkey;
+
+        let mut prefs = self
+            .settings
+            .zap_preferences
+            .get(&pubkey.hex())
+            .cloned()
+            .unwrap_or_default();
+        let mut changed = false;
+        let id = ui.id().with(("zap-preferences", pubkey));
+
+        let title = tr!(
+            self.note_context.i18n,
+            "Zaps",
+            "Label for zap settings section"
+        );
+        settings_group(ui, title, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                ui.label(richtext_small(tr!(
+                    self.note_context.i18n,
+                    "Default amount (sats):",
+                    "Label for the default zap amount, zap settings section"
+                )));
+                let hint = tr!(
+                    self.note_context.i18n,
+                    "Wallet default",
+                    "Placeholder when no default zap amount is set"
+                );
+                changed |= sats_input(ui, id.with("default"), &mut prefs.default_sats, &hint);
+            });
+
+            ui.horizontal_wrapped(|ui| {
+                ui.label(richtext_small(tr!(
+                    self.note_context.i18n,
+                    "Default message:",
+                    "Label for the default zap message, zap settings section"
+                )));
+                let hint = tr!(
+                    self.note_context.i18n,
+                    "None",
+                    "Placeholder when no default zap message is set"
+                );
+                changed |= comment_input(ui, id.with("comment"), &mut prefs.default_comment, &hint);
+            });
+
+            ui.horizontal_wrapped(|ui| {
+                ui.label(richtext_small(tr!(
+                    self.note_context.i18n,
+                    "Confirm zaps over (sats):",
+                    "Label for the zap confirmation threshold, zap settings section"
+                )));
+                let hint = tr!(
+                    self.note_context.i18n,
+                    "Never",
+                    "Placeholder when zaps never need confirmation"
+                );
+                changed |= sats_input(ui, id.with("confirm"), &mut prefs.confirm_over_sats, &hint);
+            });
+
+            ui.label(
+                richtext_small(tr!(
+                    self.note_context.i18n,
+                    "Right click or long press the zap button to pick a different amount.",
+                    "Hint about customizing a single zap, zap settings section"
+                ))
+                .color(ui.visuals().gray_out(ui.visuals().text_color())),
+            );
+        });
+
+        changed.then(|| SettingsAction::SetZapPreferences(pubkey, prefs))
+    }
+
+    fn keys_section(&mut self, ui: &mut egui::Ui) -> Option<SettingsAction> {
+        let mut action = None;
         let title = tr!(
             self.note_context.i18n,
             "Keys",
@@ -721,14 +1248,35 @@ impl<'a> SettingsView<'a> {
                 });
             }
 
-            let Some(filled) = self.note_context.accounts.selected_filled() else {
-                return;
-            };
-            let Some(mut nsec) = bech32::encode::<bech32::Bech32>(
-                bech32::Hrp::parse_unchecked("nsec"),
-                &filled.secret_key.secret_bytes(),
-            )
-            .ok() else {
+            ui.horizontal_wrapped(|ui| {
+                ui.label(richtext_small(tr!(
+                    self.note_context.i18n,
+                    "Keep secret keys in the system keychain:",
+                    "Label for storing secret keys in the OS secure store, keys settings section"
+                )));
+
+                if ui
+                    .toggle_value(
+                        &mut self.settings.os_keyring,
+                        RichText::new(tr!(
+                            self.note_context.i18n,
+                            "On",
+                            "Setting to store secret keys in the OS secure store"
+                        ))
+                        .text_style(NotedeckTextStyle::Small.text_style()),
+                    )
+                    .changed()
+                {
+                    action = Some(SettingsAction::SetOsKeyring(self.settings.os_keyring));
+                }
+            });
+
+            let pubkey = *self.note_context.accounts.selected_account_pubkey();
+            let Some(Ok(mut nsec)) = self
+                .note_context
+                .accounts
+                .export_key(&pubkey, KeyExport::Nsec)
+            else {
                 return;
             };
 
@@ -785,7 +1333,8 @@ impl<'a> SettingsView<'a> {
                                         copy_img.paint_at(ui, helper.scaled_rect());
 
                                         if helper.take_animation_response().clicked() {
-                                            ui.ctx().copy_text(nsec);
+                                            self.key_export
+                                                .copy(self.note_context.clipboard, nsec.clone());
                                         }
 
                                         if eye_button(ui, is_password).clicked() {
@@ -798,7 +1347,265 @@ impl<'a> SettingsView<'a> {
                         });
                     });
             });
+
+            ui.horizontal_wrapped(|ui| {
+                ui.label(
+                    richtext_small(tr!(
+                        self.note_context.i18n,
+                        "ENCRYPTED SECRET KEY",
+                        "label describing the passphrase encrypted (ncryptsec) secret key"
+                    ))
+                    .color(ui.visuals().gray_out(ui.visuals().text_color())),
+                );
+            });
+
+            ui.add(
+                TextEdit::singleline(&mut self.key_export.passphrase)
+                    .password(true)
+                    .hint_text(tr!(
+                        self.note_context.i18n,
+                        "Passphrase",
+                        "Hint for the passphrase used to encrypt an exported secret key"
+                    )),
+            );
+            ui.add(
+                TextEdit::singleline(&mut self.key_export.passphrase_confirm)
+                    .password(true)
+                    .hint_text(tr!(
+                        self.note_context.i18n,
+                        "Repeat passphrase",
+                        "Hint for repeating the passphrase used to encrypt an exported secret key"
+                    )),
+            );
+
+            let can_encrypt = !self.key_export.passphrase.is_empty()
+                && self.key_export.passphrase == self.key_export.passphrase_confirm;
+            let copy_encrypted = ui.add_enabled(
+                can_encrypt,
+                Button::new(richtext_small(tr!(
+                    self.note_context.i18n,
+                    "Copy ncryptsec",
+                    "Button to copy the passphrase encrypted secret key"
+                ))),
+            );
+
+            if copy_encrypted.clicked() {
+                let exported = self
+                    .note_context
+                    .accounts
+                    .export_key(&pubkey, KeyExport::Ncryptsec(&self.key_export.passphrase));
+
+                match exported {
+                    Some(Ok(ncryptsec)) => {
+                        self.key_export.copy(self.note_context.clipboard, ncryptsec);
+                        self.key_export.passphrase.clear();
+                        self.key_export.passphrase_confirm.clear();
+                    }
+                    Some(Err(err)) => self.key_export.error = Some(err.to_string()),
+                    None => {}
+                }
+            }
+
+            if let Some(err) = &self.key_export.error {
+                ui.colored_label(ui.visuals().error_fg_color, richtext_small(err));
+            } else if self.key_export.is_copied() {
+                ui.label(richtext_small(tr!(
+                    self.note_context.i18n,
+                    "Copied. It will be cleared from the clipboard in a minute.",
+                    "Status after copying a secret key"
+                )));
+            }
+
+            let confirm_id = ui.id().with("confirm-key-rotation");
+            let confirming = ui
+                .ctx()
+                .data_mut(|d| d.get_temp(confirm_id))
+                .unwrap_or(false);
+
+            if !confirming {
+                if ui
+                    .button(richtext_small(tr!(
+                        self.note_context.i18n,
+                        "Move to a new key…",
+                        "Button to start moving the account to a newly generated key"
+                    )))
+                    .clicked()
+                {
+                    ui.ctx().data_mut(|d| d.insert_temp(confirm_id, true));
+                }
+                return;
+            }
+
+            ui.label(richtext_small(tr!(
+                self.note_context.i18n,
+                "A new key will be generated and your profile, follows and relay lists copied to it. Your old key will follow the new one and post a note pointing to it, then you'll be switched to the new account. Back up its secret key afterwards: anyone holding the old key can still post as it.",
+                "Explanation shown before moving the account to a new key"
+            )));
+
+            ui.horizontal(|ui| {
+                if ui
+                    .button(richtext_small(tr!(
+                        self.note_context.i18n,
+                        "Move to new key",
+                        "Button to confirm moving the account to a new key"
+                    )))
+                    .clicked()
+                {
+                    action = Some(SettingsAction::RotateKey);
+                    ui.ctx().data_mut(|d| d.insert_temp(confirm_id, false));
+                }
+
+                if ui
+                    .button(richtext_small(tr!(
+                        self.note_context.i18n,
+                        "Cancel",
+                        "Button to cancel moving the account to a new key"
+                    )))
+                    .clicked()
+                {
+                    ui.ctx().data_mut(|d| d.insert_temp(confirm_id, false));
+                }
+            });
         });
+
+        action
+    }
+
+    fn backup_section(&mut self, ui: &mut egui::Ui) -> Option<SettingsAction> {
+        let mut action = None;
+        let title = tr!(
+            self.note_context.i18n,
+            "Backup",
+            "Label for the identity backup settings section"
+        );
+
+        settings_group(ui, title, |ui| {
+            ui.label(richtext_small(tr!(
+                self.note_context.i18n,
+                "Save your keys, profile, follows, relays, mutes, bookmarks and notes to a single file, or restore an account from one. The secret key is encrypted with the password below.",
+                "Explanation of the identity backup settings section"
+            )));
+
+            ui.add(
+                TextEdit::singleline(&mut self.identity_archive.password)
+                    .password(true)
+                    .hint_text(tr!(
+                        self.note_context.i18n,
+                        "Backup password",
+                        "Hint for the password protecting an identity backup"
+                    )),
+            );
+
+            let exporting = self.identity_archive.is_exporting();
+            let importing = self.identity_archive.is_importing();
+            ui.horizontal(|ui| {
+                ui.add_enabled_ui(!exporting && !importing, |ui| {
+                    if ui
+                        .button(richtext_small(tr!(
+                            self.note_context.i18n,
+                            "Export…",
+                            "Button to save the account's identity backup to a file"
+                        )))
+                        .clicked()
+                    {
+                        action = Some(SettingsAction::ExportIdentity);
+                    }
+
+                    if ui
+                        .button(richtext_small(tr!(
+                            self.note_context.i18n,
+                            "Import…",
+                            "Button to restore an account from an identity backup file"
+                        )))
+                        .clicked()
+                    {
+                        action = Some(SettingsAction::ImportIdentity);
+                    }
+                });
+            });
+
+            if exporting || importing {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    let text = if exporting {
+                        tr!(
+                            self.note_context.i18n,
+                            "Saving backup...",
+                            "Status while an identity backup is being written"
+                        )
+                    } else {
+                        tr!(
+                            self.note_context.i18n,
+                            "Restoring backup...",
+                            "Status while an identity backup is being restored"
+                        )
+                    };
+                    ui.label(richtext_small(text));
+                });
+            }
+
+            match &self.identity_archive.status {
+                Some(ArchiveStatus::Exported(path)) => {
+                    ui.label(richtext_small(tr!(
+                        self.note_context.i18n,
+                        "Saved to {path}",
+                        "Status after saving an identity backup",
+                        path = path.display().to_string()
+                    )));
+                }
+                Some(ArchiveStatus::Imported(events)) => {
+                    ui.label(richtext_small(tr_plural!(
+                        self.note_context.i18n,
+                        "Restored {count} event",
+                        "Restored {count} events",
+                        "Status after restoring an identity backup",
+                        *events,
+                    )));
+                }
+                Some(ArchiveStatus::Failed(err)) => {
+                    ui.label(richtext_small(err).color(Color32::LIGHT_RED));
+                }
+                None => {}
+            }
+        });
+
+        action
+    }
+
+    fn manage_media_servers_section(&mut self, ui: &mut egui::Ui) -> Option<SettingsAction> {
+        let button = Button::new(richtext_small(tr!(
+            self.note_context.i18n,
+            "Configure media servers",
+            "Label for configure Blossom media servers, settings section",
+        )));
+
+        ui.add_sized([ui.available_width(), 30.0], button)
+            .clicked()
+            .then_some(SettingsAction::OpenMediaServers)
+    }
+
+    fn manage_mute_list_section(&mut self, ui: &mut egui::Ui) -> Option<SettingsAction> {
+        let button = Button::new(richtext_small(tr!(
+            self.note_context.i18n,
+            "Manage mute list",
+            "Label for managing muted users, hashtags, words and threads, settings section",
+        )));
+
+        ui.add_sized([ui.available_width(), 30.0], button)
+            .clicked()
+            .then_some(SettingsAction::OpenMuteList)
+    }
+
+    fn drafts_section(&mut self, ui: &mut egui::Ui) -> Option<SettingsAction> {
+        let button = Button::new(richtext_small(tr!(
+            self.note_context.i18n,
+            "Drafts and scheduled posts",
+            "Label for opening the list of saved drafts and scheduled posts, settings section",
+        )));
+
+        ui.add_sized([ui.available_width(), 30.0], button)
+            .clicked()
+            .then_some(SettingsAction::OpenDrafts)
     }
 
     fn manage_relays_section(&mut self, ui: &mut egui::Ui) -> Option<SettingsAction> {
@@ -818,6 +1625,164 @@ impl<'a> SettingsView<'a> {
             action = Some(SettingsAction::OpenRelays);
         }
 
+        ui.horizontal_wrapped(|ui| {
+            ui.label(richtext_small(tr!(
+                self.note_context.i18n,
+                "Check that posts reach other relays:",
+                "Label for verifying published notes on other relays, relays settings section",
+            )));
+
+            if ui
+                .toggle_value(
+                    &mut self.settings.verify_publishes,
+                    RichText::new(tr!(
+                        self.note_context.i18n,
+                        "On",
+                        "Setting to verify published notes on other relays"
+                    ))
+                    .text_style(NotedeckTextStyle::Small.text_style()),
+                )
+                .changed()
+            {
+                action = Some(SettingsAction::SetVerifyPublishes(
+                    self.settings.verify_publishes,
+                ));
+            }
+        });
+
+        action.or(self.sig_policy_option(ui))
+    }
+
+    /// Set for the selected account, like zap preferences
+    fn content_warning_option(&mut self, ui: &mut egui::Ui) -> Option<SettingsAction> {
+        let pubkey = *self.note_context.accounts.selected_account_pubkey();
+        let mut current = self
+            .settings
+            .content_warning_modes
+            .get(&pubkey.hex())
+            .copied()
+            .unwrap_or_default();
+
+        let modes = [
+            (
+                ContentWarningMode::Ask,
+                tr!(
+                    self.note_context.i18n,
+                    "Ask",
+                    "Content warning setting that hides marked notes behind a Show button"
+                ),
+            ),
+            (
+                ContentWarningMode::AlwaysShow,
+                tr!(
+                    self.note_context.i18n,
+                    "Always show",
+                    "Content warning setting that shows marked notes as usual"
+                ),
+            ),
+            (
+                ContentWarningMode::AlwaysHide,
+                tr!(
+                    self.note_context.i18n,
+                    "Always hide",
+                    "Content warning setting that never reveals marked notes"
+                ),
+            ),
+        ];
+
+        let selected_text = modes
+            .iter()
+            .find(|(mode, _)| *mode == current)
+            .map(|(_, label)| label.clone())
+            .unwrap_or_default();
+
+        let mut action = None;
+        ui.horizontal_wrapped(|ui| {
+            ui.label(richtext_small(tr!(
+                self.note_context.i18n,
+                "Notes with content warnings:",
+                "Label for what to do with notes marked with a content warning, others settings section",
+            )));
+
+            ComboBox::from_id_salt("content_warning_mode")
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    for (mode, label) in modes {
+                        if ui.selectable_value(&mut current, mode, label).clicked() {
+                            action = Some(SettingsAction::SetContentWarningMode(pubkey, mode));
+                        }
+                    }
+                });
+        });
+
+        action
+    }
+
+    fn sig_policy_option(&mut self, ui: &mut egui::Ui) -> Option<SettingsAction> {
+        let policies = [
+            (
+                SigPolicy::VerifyAll,
+                tr!(
+                    self.note_context.i18n,
+                    "As they arrive",
+                    "Signature policy that checks every event before storing it"
+                ),
+            ),
+            (
+                SigPolicy::VerifyOnDisplay,
+                tr!(
+                    self.note_context.i18n,
+                    "When shown",
+                    "Signature policy that only checks notes when they are displayed"
+                ),
+            ),
+            (
+                SigPolicy::TrustNdb,
+                tr!(
+                    self.note_context.i18n,
+                    "Leave it to the database",
+                    "Signature policy that relies on nostrdb's own checks"
+                ),
+            ),
+        ];
+
+        let selected_text = policies
+            .iter()
+            .find(|(policy, _)| *policy == self.settings.sig_policy)
+            .map(|(_, label)| label.clone())
+            .unwrap_or_default();
+
+        let mut action = None;
+        ui.horizontal_wrapped(|ui| {
+            ui.label(richtext_small(tr!(
+                self.note_context.i18n,
+                "Check event signatures:",
+                "Label for the signature verification policy, relays settings section",
+            )));
+
+            ComboBox::from_id_salt("sig_policy")
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    for (policy, label) in policies {
+                        if ui
+                            .selectable_value(&mut self.settings.sig_policy, policy, label)
+                            .clicked()
+                        {
+                            action = Some(SettingsAction::SetSigPolicy(policy));
+                        }
+                    }
+                });
+        });
+
+        ui.label(
+            richtext_small(tr!(
+                self.note_context.i18n,
+                "Changes take effect after a restart.",
+                "Note under the signature verification policy setting"
+            ))
+            .weak(),
+        );
+
         action
     }
 
@@ -845,7 +1810,27 @@ impl<'a> SettingsView<'a> {
 
                     ui.add_space(5.0);
 
-                    self.keys_section(ui);
+                    if let Some(new_action) = self.keys_section(ui) {
+                        action = Some(new_action);
+                    }
+
+                    ui.add_space(5.0);
+
+                    if let Some(new_action) = self.backup_section(ui) {
+                        action = Some(new_action);
+                    }
+
+                    ui.add_space(5.0);
+
+                    if let Some(new_action) = self.zaps_section(ui) {
+                        action = Some(new_action);
+                    }
+
+                    ui.add_space(5.0);
+
+                    if let Some(new_action) = self.action_bar_section(ui) {
+                        action = Some(new_action);
+                    }
 
                     ui.add_space(5.0);
 
@@ -858,6 +1843,24 @@ impl<'a> SettingsView<'a> {
                     if let Some(new_action) = self.manage_relays_section(ui) {
                         action = Some(new_action);
                     }
+
+                    ui.add_space(5.0);
+
+                    if let Some(new_action) = self.manage_media_servers_section(ui) {
+                        action = Some(new_action);
+                    }
+
+                    ui.add_space(5.0);
+
+                    if let Some(new_action) = self.manage_mute_list_section(ui) {
+                        action = Some(new_action);
+                    }
+
+                    ui.add_space(5.0);
+
+                    if let Some(new_action) = self.drafts_section(ui) {
+                        action = Some(new_action);
+                    }
                     action
                 })
             })
@@ -867,6 +1870,77 @@ impl<'a> SettingsView<'a> {
     }
 }
 
+fn action_bar_item_label(i18n: &mut Localization, item: ActionBarItem) -> String {
+    match item {
+        ActionBarItem::Reply => tr!(i18n, "Reply", "Reply button on the note action bar"),
+        ActionBarItem::React => tr!(i18n, "React", "Reaction button on the note action bar"),
+        ActionBarItem::Repost => tr!(i18n, "Repost", "Repost button on the note action bar"),
+        ActionBarItem::Zap => tr!(i18n, "Zap", "Zap button on the note action bar"),
+        ActionBarItem::Bookmark => tr!(i18n, "Bookmark", "Bookmark button on the note action bar"),
+        ActionBarItem::Share => tr!(i18n, "Share", "Share button on the note action bar"),
+    }
+}
+
+/// Editable sats amount, where an empty field means unset. Returns true
+/// when `value` changed.
+fn sats_input(ui: &mut egui::Ui, id: egui::Id, value: &mut Option<u64>, hint: &str) -> bool {
+    let mut text: String = ui
+        .data(|d| d.get_temp(id))
+        .unwrap_or_else(|| value.map(|sats| sats.to_string()).unwrap_or_default());
+
+    let resp = ui.add(
+        TextEdit::singleline(&mut text)
+            .desired_width(100.0)
+            .hint_text(hint),
+    );
+
+    let mut changed = false;
+    if resp.changed() {
+        let trimmed = text.trim();
+        let parsed = if trimmed.is_empty() {
+            Some(None)
+        } else {
+            trimmed
+                .parse::<u64>()
+                .ok()
+                .filter(|sats| *sats > 0)
+                .map(Some)
+        };
+
+        // keep the previous value while the input is invalid
+        if let Some(parsed) = parsed {
+            changed = parsed != *value;
+            *value = parsed;
+        }
+    }
+
+    ui.data_mut(|d| d.insert_temp(id, text));
+    changed
+}
+
+fn comment_input(ui: &mut egui::Ui, id: egui::Id, value: &mut Option<String>, hint: &str) -> bool {
+    let mut text: String = ui
+        .data(|d| d.get_temp(id))
+        .unwrap_or_else(|| value.clone().unwrap_or_default());
+
+    let resp = ui.add(
+        TextEdit::singleline(&mut text)
+            .desired_width(200.0)
+            .hint_text(hint),
+    );
+
+    let mut changed = false;
+    if resp.changed() {
+        let trimmed = text.trim();
+        let parsed = (!trimmed.is_empty()).then(|| trimmed.to_owned());
+        changed = parsed != *value;
+        *value = parsed;
+    }
+
+    ui.data_mut(|d| d.insert_temp(id, text));
+    changed
+}
+
 pub fn format_size(size_bytes: u64) -> String {
     const KB: f64 = 1024.0;
     const MB: f64 = KB * 1024.0;
@@ -1,14 +1,31 @@
 use egui::InnerResponse;
 use egui_virtual_list::VirtualList;
+use enostr::{NoteId, Pubkey};
 use nostrdb::{Note, Transaction};
 use notedeck::note::root_note_id_from_selected_id;
-use notedeck::{NoteAction, NoteContext};
-use notedeck_ui::note::NoteResponse;
+use notedeck::{
+    name::get_display_name, tr, tr_plural, IsFollowing, MuteMode, NoteAction, NoteContext,
+};
+use notedeck_ui::note::{muted_placeholder, NoteResponse};
 use notedeck_ui::{NoteOptions, NoteView};
 
-use crate::timeline::thread::{NoteSeenFlags, ParentState, Threads};
+use crate::timeline::thread::{
+    direct_replies, NoteSeenFlags, ParentState, ParticipantFilter, ReplySort, Threads,
+};
 use notedeck::DragResponse;
 
+/// Threads with fewer direct replies don't get a participant filter bar
+const PARTICIPANT_FILTER_MIN_REPLIES: usize = 8;
+
+/// Expanded replies nest at most this deep
+const MAX_REPLY_DEPTH: usize = 16;
+
+/// How far each level of an expanded subtree is indented
+const REPLY_INDENT: f32 = 16.0;
+
+/// Deeper levels stop indenting, so deep subtrees keep some width
+const MAX_REPLY_INDENT_LEVELS: usize = 6;
+
 pub struct ThreadView<'a, 'd> {
     threads: &'a mut Threads,
     selected_note_id: &'a [u8; 32],
@@ -78,8 +95,19 @@ impl<'a, 'd> ThreadView<'a, 'd> {
             .ndb
             .get_note_by_id(txn, self.selected_note_id)
         else {
-            let id = *self.selected_note_id;
-            tracing::error!("ndb: Did not find note {}", enostr::NoteId::new(id).hex());
+            self.note_context.unknown_ids.add_note_id_if_missing(
+                self.note_context.ndb,
+                txn,
+                self.selected_note_id,
+            );
+            loading_notes(
+                ui,
+                tr!(
+                    self.note_context.i18n,
+                    "Looking for this note...",
+                    "Shown while the selected note of a thread is fetched from relays"
+                ),
+            );
             return None;
         };
 
@@ -116,27 +144,122 @@ impl<'a, 'd> ThreadView<'a, 'd> {
             }
         }
 
-        let list = &mut self
+        let node = self
             .threads
             .threads
             .get_mut(&self.selected_note_id)
-            .unwrap()
-            .list;
+            .unwrap();
 
-        let notes = note_builder.into_notes(
-            self.note_options.contains(NoteOptions::RepliesNewestFirst),
-            &mut self.threads.seen_flags,
+        if note_builder.replies.len() >= PARTICIPANT_FILTER_MIN_REPLIES
+            || node.participant_filter != ParticipantFilter::All
+        {
+            let participants = note_builder.participants();
+            let mut filter = node.participant_filter;
+            participant_filter_bar(ui, self.note_context, txn, &mut filter, &participants);
+            if filter != node.participant_filter {
+                node.participant_filter = filter;
+                node.list.reset();
+            }
+        }
+
+        if note_builder.replies.len() > 1 || node.reply_sort != ReplySort::default() {
+            let mut sort = node.reply_sort;
+            reply_sort_bar(ui, self.note_context, &mut sort);
+            if sort != node.reply_sort {
+                node.reply_sort = sort;
+                node.list.reset();
+            }
+        }
+
+        if node.participant_filter != ParticipantFilter::All {
+            let op = thread_op(self.note_context, txn, &note_builder.selected);
+            let account = self.note_context.accounts.get_selected_account();
+            let filter = node.participant_filter;
+            note_builder.replies.retain(|note| {
+                filter.matches(note.pubkey(), op.as_ref(), |pk| {
+                    account.is_following(pk) == IsFollowing::Yes
+                })
+            });
+        }
+
+        let ndb = self.note_context.ndb;
+        let sort = node.reply_sort;
+        let newest_first = self.note_options.contains(NoteOptions::RepliesNewestFirst);
+        let stats = &mut node.stats;
+        let zaps = &mut *self.note_context.zaps;
+        sort.sort(
+            &mut note_builder.replies,
+            newest_first,
+            stats,
+            zaps,
+            ndb,
+            txn,
         );
 
+        let list = &mut node.list;
+        let expanded = &mut node.expanded;
+        let collapsed = &mut node.collapsed;
+
+        let notes = note_builder.into_notes(&mut self.threads.seen_flags, |note| {
+            let id = NoteId::new(*note.id());
+            let reply_count = stats.reply_count(ndb, txn, note);
+            if collapsed.contains(&id) {
+                return ReplyLayout::Collapsed { reply_count };
+            }
+            if !expanded.contains(&id) {
+                return ReplyLayout::Open {
+                    reply_count,
+                    children: Vec::new(),
+                };
+            }
+
+            let mut children = direct_replies(ndb, txn, note.id());
+            sort.sort(&mut children, newest_first, stats, zaps, ndb, txn);
+            ReplyLayout::Open {
+                reply_count,
+                children,
+            }
+        });
+
         if !full_chain {
-            // TODO(kernelkind): insert UI denoting we don't have the full chain yet
-            ui.colored_label(ui.visuals().error_fg_color, "LOADING NOTES");
+            loading_notes(
+                ui,
+                tr!(
+                    self.note_context.i18n,
+                    "Loading earlier notes...",
+                    "Shown above a thread while the notes it replies to are fetched"
+                ),
+            );
         }
 
-        show_notes(ui, list, &notes, self.note_context, self.note_options, txn)
+        let (action, toggled) =
+            show_notes(ui, list, &notes, self.note_context, self.note_options, txn);
+
+        if let Some((id, toggle)) = toggled {
+            let toggled_set = match toggle {
+                ReplyToggle::Subtree => expanded,
+                ReplyToggle::Collapse => collapsed,
+            };
+            if !toggled_set.remove(&id) {
+                toggled_set.insert(id);
+            }
+            // the notes below the toggled one moved
+            list.reset();
+        }
+
+        action
     }
 }
 
+fn loading_notes(ui: &mut egui::Ui, text: String) {
+    notedeck_ui::padding(8.0, ui, |ui| {
+        ui.horizontal(|ui| {
+            ui.spinner();
+            ui.weak(text);
+        });
+    });
+}
+
 #[allow(clippy::too_many_arguments)]
 fn show_notes(
     ui: &mut egui::Ui,
@@ -145,8 +268,9 @@ fn show_notes(
     note_context: &mut NoteContext<'_>,
     flags: NoteOptions,
     txn: &Transaction,
-) -> Option<NoteAction> {
+) -> (Option<NoteAction>, Option<(NoteId, ReplyToggle)>) {
     let mut action = None;
+    let mut toggled = None;
 
     ui.spacing_mut().item_spacing.y = 0.0;
     ui.spacing_mut().item_spacing.x = 4.0;
@@ -154,7 +278,7 @@ fn show_notes(
     let selected_note_index = thread_notes.selected_index;
     let notes = &thread_notes.notes;
 
-    let is_muted = note_context.accounts.mutefun();
+    let mute_mode = note_context.accounts.mutefun();
 
     list.ui_custom_layout(ui, notes.len(), |ui, cur_index| {
         let note = &notes[cur_index];
@@ -167,25 +291,145 @@ fn show_notes(
             note.note.id(),
         )
         .ok()
-        .is_some_and(|root_id| is_muted(&note.note, root_id.bytes()));
-
-        if muted {
-            return 1;
+        .and_then(|root_id| mute_mode(&note.note, root_id.bytes()));
+
+        match muted {
+            // threads aren't notifications
+            None | Some(MuteMode::NotificationsOnly) => {}
+            Some(MuteMode::Collapse) => {
+                if muted_placeholder(ui, note_context.i18n, note.note.id()) {
+                    return 1;
+                }
+            }
+            Some(MuteMode::Hide) => return 1,
         }
 
-        let resp = note.show(note_context, flags, ui);
+        let (note_action, toggle) = note.show(note_context, txn, flags, ui);
+        if let Some(toggle) = toggle {
+            toggled = Some((NoteId::new(*note.note.id()), toggle));
+        }
 
         action = if cur_index == selected_note_index {
-            resp.action.and_then(strip_note_action)
+            note_action.and_then(strip_note_action)
         } else {
-            resp.action
+            note_action
         }
         .or(action.take());
 
         1
     });
 
-    action
+    (action, toggled)
+}
+
+/// Author of the thread root, when it is known locally
+fn thread_op(
+    note_context: &mut NoteContext<'_>,
+    txn: &Transaction,
+    selected: &Note<'_>,
+) -> Option<[u8; 32]> {
+    let root_id = root_note_id_from_selected_id(
+        note_context.ndb,
+        note_context.note_cache,
+        txn,
+        selected.id(),
+    )
+    .ok()?;
+
+    if root_id.bytes() == selected.id() {
+        return Some(*selected.pubkey());
+    }
+
+    note_context
+        .ndb
+        .get_note_by_id(txn, root_id.bytes())
+        .ok()
+        .map(|root| *root.pubkey())
+}
+
+fn participant_filter_bar(
+    ui: &mut egui::Ui,
+    note_context: &mut NoteContext<'_>,
+    txn: &Transaction,
+    filter: &mut ParticipantFilter,
+    participants: &[([u8; 32], usize)],
+) {
+    let all = tr!(
+        note_context.i18n,
+        "All",
+        "Thread filter showing every reply"
+    );
+    let op = tr!(
+        note_context.i18n,
+        "OP",
+        "Thread filter showing only replies from the thread author"
+    );
+    let following = tr!(
+        note_context.i18n,
+        "Following",
+        "Thread filter showing only replies from followed accounts"
+    );
+    let someone = tr!(
+        note_context.i18n,
+        "Participant",
+        "Thread filter dropdown for replies from a single participant"
+    );
+
+    let name = |pk: &[u8; 32]| {
+        let profile = note_context.ndb.get_profile_by_pubkey(txn, pk).ok();
+        get_display_name(profile.as_ref()).name().to_owned()
+    };
+
+    notedeck_ui::padding(8.0, ui, |ui| {
+        ui.horizontal_wrapped(|ui| {
+            ui.selectable_value(filter, ParticipantFilter::All, all);
+            ui.selectable_value(filter, ParticipantFilter::Op, op);
+            ui.selectable_value(filter, ParticipantFilter::Following, following);
+
+            let selected_text = match filter {
+                ParticipantFilter::Author(pk) => name(pk.bytes()),
+                _ => someone,
+            };
+
+            egui::ComboBox::from_id_salt("thread-participant-filter")
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    for (pk, replies) in participants {
+                        ui.selectable_value(
+                            filter,
+                            ParticipantFilter::Author(Pubkey::new(*pk)),
+                            format!("{} ({replies})", name(pk)),
+                        );
+                    }
+                });
+        });
+    });
+}
+
+fn reply_sort_bar(ui: &mut egui::Ui, note_context: &mut NoteContext<'_>, sort: &mut ReplySort) {
+    let chronological = tr!(
+        note_context.i18n,
+        "Chronological",
+        "Thread reply order by posting time"
+    );
+    let most_replied = tr!(
+        note_context.i18n,
+        "Most replied",
+        "Thread reply order putting replies with the most replies first"
+    );
+    let most_zapped = tr!(
+        note_context.i18n,
+        "Most zapped",
+        "Thread reply order putting the most zapped replies first"
+    );
+
+    notedeck_ui::padding(8.0, ui, |ui| {
+        ui.horizontal_wrapped(|ui| {
+            ui.selectable_value(sort, ReplySort::Chronological, chronological);
+            ui.selectable_value(sort, ReplySort::MostReplied, most_replied);
+            ui.selectable_value(sort, ReplySort::MostZapped, most_zapped);
+        });
+    });
 }
 
 fn strip_note_action(action: NoteAction) -> Option<NoteAction> {
@@ -226,10 +470,24 @@ impl<'a> ThreadNoteBuilder<'a> {
         self.replies.push(note);
     }
 
+    /// Authors of the replies, most replies first
+    pub fn participants(&self) -> Vec<([u8; 32], usize)> {
+        let mut counts: hashbrown::HashMap<[u8; 32], usize> = hashbrown::HashMap::new();
+        for reply in &self.replies {
+            *counts.entry(*reply.pubkey()).or_default() += 1;
+        }
+
+        let mut participants: Vec<_> = counts.into_iter().collect();
+        participants.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        participants
+    }
+
+    /// The chain, the selected note and its replies in display order. The
+    /// replies keep their order. `layout` says how each reply is shown.
     pub fn into_notes(
         mut self,
-        replies_newer_first: bool,
         seen_flags: &mut NoteSeenFlags,
+        mut layout: impl FnMut(&Note<'a>) -> ReplyLayout<'a>,
     ) -> ThreadNotes<'a> {
         let mut notes = Vec::new();
 
@@ -253,17 +511,8 @@ impl<'a> ThreadNoteBuilder<'a> {
             unread_and_have_replies: false,
         });
 
-        if replies_newer_first {
-            self.replies
-                .sort_by_key(|b| std::cmp::Reverse(b.created_at()));
-        }
-
         for reply in self.replies {
-            notes.push(ThreadNote {
-                unread_and_have_replies: *seen_flags.get(reply.id()).unwrap_or(&false),
-                note: reply,
-                note_type: ThreadNoteType::Reply,
-            });
+            push_reply(&mut notes, reply, 0, seen_flags, &mut layout);
         }
 
         ThreadNotes {
@@ -273,10 +522,82 @@ impl<'a> ThreadNoteBuilder<'a> {
     }
 }
 
+/// How a reply is laid out in the thread, with how many direct replies it
+/// has
+enum ReplyLayout<'a> {
+    /// Folded down to a single line, hiding its subtree
+    Collapsed { reply_count: usize },
+    /// Shown in full, with the replies to show under it
+    Open {
+        reply_count: usize,
+        children: Vec<Note<'a>>,
+    },
+}
+
+fn push_reply<'a>(
+    notes: &mut Vec<ThreadNote<'a>>,
+    reply: Note<'a>,
+    depth: usize,
+    seen_flags: &NoteSeenFlags,
+    layout: &mut impl FnMut(&Note<'a>) -> ReplyLayout<'a>,
+) {
+    let (reply_count, children) = match layout(&reply) {
+        ReplyLayout::Collapsed { reply_count } => {
+            notes.push(ThreadNote {
+                unread_and_have_replies: false,
+                note: reply,
+                note_type: ThreadNoteType::Collapsed { depth, reply_count },
+            });
+            return;
+        }
+        ReplyLayout::Open {
+            reply_count,
+            children,
+        } if depth < MAX_REPLY_DEPTH => (reply_count, children),
+        ReplyLayout::Open { reply_count, .. } => (reply_count, Vec::new()),
+    };
+
+    notes.push(ThreadNote {
+        unread_and_have_replies: *seen_flags.get(reply.id()).unwrap_or(&false),
+        note: reply,
+        note_type: ThreadNoteType::Reply {
+            depth,
+            expanded: !children.is_empty(),
+            reply_count,
+        },
+    });
+
+    for child in children {
+        push_reply(notes, child, depth + 1, seen_flags, layout);
+    }
+}
+
 enum ThreadNoteType {
-    Chain { root: bool },
-    Selected { root: bool },
-    Reply,
+    Chain {
+        root: bool,
+    },
+    Selected {
+        root: bool,
+    },
+    /// `depth` is 0 for direct replies to the selected note
+    Reply {
+        depth: usize,
+        expanded: bool,
+        reply_count: usize,
+    },
+    /// A reply folded down to a single line
+    Collapsed {
+        depth: usize,
+        reply_count: usize,
+    },
+}
+
+/// A click on one of the controls folding a reply
+enum ReplyToggle {
+    /// Show or hide the reply's own replies
+    Subtree,
+    /// Fold the reply to a single line, or unfold it
+    Collapse,
 }
 
 impl ThreadNoteType {
@@ -306,16 +627,34 @@ impl<'a> ThreadNote<'a> {
                 cur_options.set(NoteOptions::FullCreatedDate, true);
                 cur_options
             }
-            ThreadNoteType::Reply => cur_options,
+            ThreadNoteType::Reply { .. } | ThreadNoteType::Collapsed { .. } => cur_options,
         }
     }
 
     fn show(
         &self,
         note_context: &'a mut NoteContext<'_>,
+        txn: &Transaction,
         flags: NoteOptions,
         ui: &mut egui::Ui,
-    ) -> NoteResponse {
+    ) -> (Option<NoteAction>, Option<ReplyToggle>) {
+        match self.note_type {
+            ThreadNoteType::Reply {
+                depth,
+                expanded,
+                reply_count,
+            } => {
+                return self.show_reply(note_context, flags, ui, depth, expanded, reply_count);
+            }
+            ThreadNoteType::Collapsed { depth, reply_count } => {
+                return (
+                    None,
+                    self.show_collapsed(note_context, txn, ui, depth, reply_count),
+                );
+            }
+            ThreadNoteType::Chain { .. } | ThreadNoteType::Selected { .. } => {}
+        }
+
         let inner = notedeck_ui::padding(8.0, ui, |ui| {
             NoteView::new(note_context, &self.note, self.options(flags))
                 .selected_style(self.note_type.is_selected())
@@ -326,11 +665,165 @@ impl<'a> ThreadNote<'a> {
         match self.note_type {
             ThreadNoteType::Chain { root } => add_chain_adornment(ui, &inner, root),
             ThreadNoteType::Selected { root } => add_selected_adornment(ui, &inner, root),
-            ThreadNoteType::Reply => notedeck_ui::hline(ui),
+            ThreadNoteType::Reply { .. } | ThreadNoteType::Collapsed { .. } => {}
+        }
+
+        (inner.inner.action, None)
+    }
+
+    /// A reply, indented by its depth, with controls to fold it and to
+    /// show its own replies
+    fn show_reply(
+        &self,
+        note_context: &mut NoteContext<'_>,
+        flags: NoteOptions,
+        ui: &mut egui::Ui,
+        depth: usize,
+        expanded: bool,
+        reply_count: usize,
+    ) -> (Option<NoteAction>, Option<ReplyToggle>) {
+        let indent = reply_indent(depth);
+
+        let inner = notedeck_ui::padding(8.0, ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.add_space(indent);
+                ui.vertical(|ui| {
+                    let resp = NoteView::new(note_context, &self.note, self.options(flags))
+                        .unread_indicator(self.unread_and_have_replies)
+                        .show(ui);
+
+                    let toggle = ui
+                        .horizontal(|ui| {
+                            let collapse = tr!(
+                                note_context.i18n,
+                                "Collapse",
+                                "Button folding a reply in a thread down to a single line"
+                            );
+                            if reply_control(ui, collapse).clicked() {
+                                return Some(ReplyToggle::Collapse);
+                            }
+
+                            let show_subtree = expanded || reply_count > 0;
+                            (show_subtree
+                                && subtree_toggle(ui, note_context, expanded, reply_count))
+                            .then_some(ReplyToggle::Subtree)
+                        })
+                        .inner;
+
+                    (resp.action, toggle)
+                })
+                .inner
+            })
+            .inner
+        });
+
+        if depth > 0 {
+            add_subtree_adornment(ui, &inner.response.rect, indent);
         }
+        notedeck_ui::hline(ui);
 
         inner.inner
     }
+
+    /// A folded reply: who wrote it and how many replies it has. Clicking
+    /// it unfolds it.
+    fn show_collapsed(
+        &self,
+        note_context: &mut NoteContext<'_>,
+        txn: &Transaction,
+        ui: &mut egui::Ui,
+        depth: usize,
+        reply_count: usize,
+    ) -> Option<ReplyToggle> {
+        let indent = reply_indent(depth);
+
+        let profile = note_context
+            .ndb
+            .get_profile_by_pubkey(txn, self.note.pubkey())
+            .ok();
+        let name = get_display_name(profile.as_ref()).name().to_owned();
+
+        let inner = notedeck_ui::padding(8.0, ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.add_space(indent);
+
+                let expand = tr!(
+                    note_context.i18n,
+                    "Show reply from {name}",
+                    "Button unfolding a collapsed reply in a thread",
+                    name = name
+                );
+                let clicked = reply_control(ui, expand).clicked();
+
+                if reply_count > 0 {
+                    ui.weak(tr_plural!(
+                        note_context.i18n,
+                        "{count} reply",   // one
+                        "{count} replies", // other
+                        "How many replies a collapsed reply in a thread has",
+                        reply_count,
+                    ));
+                }
+
+                clicked
+            })
+            .inner
+        });
+
+        if depth > 0 {
+            add_subtree_adornment(ui, &inner.response.rect, indent);
+        }
+        notedeck_ui::hline(ui);
+
+        inner.inner.then_some(ReplyToggle::Collapse)
+    }
+}
+
+fn reply_indent(depth: usize) -> f32 {
+    depth.min(MAX_REPLY_INDENT_LEVELS) as f32 * REPLY_INDENT
+}
+
+fn reply_control(ui: &mut egui::Ui, label: String) -> egui::Response {
+    ui.add(egui::Button::new(egui::RichText::new(label).small()).frame(false))
+        .on_hover_cursor(egui::CursorIcon::PointingHand)
+}
+
+/// "Show replies" with how many there are, or "Hide replies"
+fn subtree_toggle(
+    ui: &mut egui::Ui,
+    note_context: &mut NoteContext<'_>,
+    expanded: bool,
+    reply_count: usize,
+) -> bool {
+    let label = if expanded {
+        tr!(
+            note_context.i18n,
+            "Hide replies",
+            "Button collapsing the replies shown under a reply in a thread"
+        )
+    } else {
+        tr_plural!(
+            note_context.i18n,
+            "Show {count} reply",   // one
+            "Show {count} replies", // other
+            "Button expanding the replies to a reply in a thread",
+            reply_count,
+        )
+    };
+
+    reply_control(ui, label).clicked()
+}
+
+/// A line along the left of a nested reply, tying it to its parent
+fn add_subtree_adornment(ui: &mut egui::Ui, note_rect: &egui::Rect, indent: f32) {
+    let x = note_rect.left() + indent - REPLY_INDENT / 2.0;
+    ui.painter_at(*note_rect).line_segment(
+        [
+            egui::pos2(x, note_rect.top()),
+            egui::pos2(x, note_rect.bottom()),
+        ],
+        LINE_STROKE(ui),
+    );
 }
 
 fn add_chain_adornment(ui: &mut egui::Ui, note_resp: &InnerResponse<NoteResponse>, root: bool) {
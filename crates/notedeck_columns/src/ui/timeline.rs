@@ -6,22 +6,24 @@ use nostrdb::{Note, ProfileRecord, Transaction};
 use notedeck::fonts::get_font_size;
 use notedeck::name::get_display_name;
 use notedeck::ui::is_narrow;
-use notedeck::{tr_plural, Muted, NotedeckTextStyle};
+use notedeck::{tr_plural, MuteMode, Muted, NotedeckTextStyle};
 use notedeck_ui::app_images::{like_image_filled, repost_image};
 use notedeck_ui::{ProfilePic, ProfilePreview};
 use std::f32::consts::PI;
 use tracing::{error, warn};
 
 use crate::timeline::{
-    CompositeType, CompositeUnit, NoteUnit, ReactionUnit, RepostUnit, TimelineCache, TimelineKind,
-    TimelineTab,
+    CompositeType, CompositeUnit, NoteUnit, ParentPrefetch, ReactionUnit, RepostUnit,
+    TimelineCache, TimelineKind, TimelineTab, ViewFilter,
 };
 use notedeck::DragResponse;
 use notedeck::{
-    note::root_note_id_from_selected_id, tr, Localization, NoteAction, NoteContext, ScrollInfo,
+    note::root_note_id_from_selected_id, tr, Localization, NoteAction, NoteAddress, NoteContext,
+    ScrollInfo,
 };
 use notedeck_ui::{
     anim::{AnimationHelper, ICON_EXPANSION_MULTIPLE},
+    note::muted_placeholder,
     NoteOptions, NoteView,
 };
 
@@ -162,25 +164,48 @@ fn timeline_ui(
     }
 
     let scroll_output = scroll_area.show(ui, |ui| {
-        let timeline = if let Some(timeline) = timeline_cache.get(timeline_id) {
-            timeline
-        } else {
-            error!("tried to render timeline in column, but timeline was missing");
-            // TODO (jb55): render error when timeline is missing?
-            // this shouldn't happen...
-            //
-            // NOTE (jb55): it can easily happen if you add a timeline column without calling
-            // add_new_timeline_column, since that sets up the initial subs, etc
-            return None;
-        };
+        let (timeline, prefetch) =
+            if let Some(found) = timeline_cache.get_with_prefetch(timeline_id) {
+                found
+            } else {
+                error!("tried to render timeline in column, but timeline was missing");
+                // TODO (jb55): render error when timeline is missing?
+                // this shouldn't happen...
+                //
+                // NOTE (jb55): it can easily happen if you add a timeline column without calling
+                // add_new_timeline_column, since that sets up the initial subs, etc
+                return None;
+            };
 
         let txn = Transaction::new(note_context.ndb).expect("failed to create txn");
 
-        if matches!(timeline_id, TimelineKind::Notifications(_)) {
+        let is_notifications = matches!(timeline_id, TimelineKind::Notifications(_));
+        if is_notifications {
             note_options.set(NoteOptions::Notification, true)
         }
 
-        TimelineTabView::new(timeline.current_view(), note_options, &txn, note_context).show(ui)
+        let address_action = if let TimelineKind::Address(address) = timeline_id {
+            addressed_note_ui(ui, note_context, &txn, address, note_options)
+        } else {
+            None
+        };
+
+        let action =
+            TimelineTabView::new(timeline.current_view(), note_options, &txn, note_context)
+                .prefetch_parents(prefetch)
+                .show(ui)
+                .or(address_action);
+
+        // notifications open the note on its own, a full thread is a
+        // heavy load for checking a single reply or mention
+        match action {
+            Some(NoteAction::Note {
+                note_id,
+                preview: false,
+                ..
+            }) if is_notifications => Some(NoteAction::Permalink(note_id)),
+            action => action,
+        }
     });
 
     let at_top_after_scroll = scroll_output.state.offset.y == 0.0;
@@ -219,6 +244,44 @@ fn timeline_ui(
     DragResponse::output(action).scroll_raw(scroll_id)
 }
 
+/// The note an address column is about, in full, above its replies
+fn addressed_note_ui(
+    ui: &mut egui::Ui,
+    note_context: &mut NoteContext,
+    txn: &Transaction,
+    address: &NoteAddress,
+    mut note_options: NoteOptions,
+) -> Option<NoteAction> {
+    let Some(note) = address.lookup(note_context.ndb, txn) else {
+        note_context
+            .unknown_ids
+            .add_address_if_missing(note_context.ndb, txn, address);
+
+        ui.add_space(16.0);
+        ui.vertical_centered(|ui| {
+            ui.spinner();
+            ui.label(tr!(
+                note_context.i18n,
+                "Looking for this note...",
+                "Shown atop an address column while its note is fetched from relays"
+            ));
+        });
+        ui.add_space(16.0);
+        notedeck_ui::hline(ui);
+        return None;
+    };
+
+    note_options.set(NoteOptions::Truncate, false);
+    note_options.set(NoteOptions::FullCreatedDate, true);
+
+    let resp = NoteView::new(note_context, &note, note_options)
+        .selected_style(true)
+        .show(ui);
+    notedeck_ui::hline(ui);
+
+    resp.action
+}
+
 fn goto_top_button(center: Pos2) -> impl egui::Widget {
     move |ui: &mut egui::Ui| -> egui::Response {
         let radius = 12.0;
@@ -279,10 +342,20 @@ pub fn tabs_ui(
     i18n: &mut Localization,
     selected: usize,
     views: &[TimelineTab],
+) -> egui::InnerResponse<usize> {
+    let labels: Vec<String> = views.iter().map(|view| view.filter.name(i18n)).collect();
+    labeled_tabs_ui(ui, selected, &labels)
+}
+
+/// Underlined tab strip. Returns the index of the selected tab.
+pub fn labeled_tabs_ui(
+    ui: &mut egui::Ui,
+    selected: usize,
+    labels: &[String],
 ) -> egui::InnerResponse<usize> {
     ui.spacing_mut().item_spacing.y = 0.0;
 
-    let tab_res = egui_tabs::Tabs::new(views.len() as i32)
+    let tab_res = egui_tabs::Tabs::new(labels.len() as i32)
         .selected(selected as i32)
         .hover_bg(TabColor::none())
         .selected_fg(TabColor::none())
@@ -296,15 +369,15 @@ pub fn tabs_ui(
 
             let ind = state.index();
 
-            let txt = views[ind as usize].filter.name(i18n);
+            let txt = &labels[ind as usize];
 
-            let res = ui.add(egui::Label::new(txt.clone()).selectable(false));
+            let res = ui.add(egui::Label::new(txt.as_str()).selectable(false));
 
             // underline
             if state.is_selected() {
                 let rect = res.rect;
                 let underline =
-                    shrink_range_to_width(rect.x_range(), get_label_width(ui, &txt) * 1.15);
+                    shrink_range_to_width(rect.x_range(), get_label_width(ui, txt) * 1.15);
                 #[allow(deprecated)]
                 let underline_y = ui.painter().round_to_pixel(rect.bottom()) - 1.5;
                 return (underline, underline_y);
@@ -368,6 +441,10 @@ fn shrink_range_to_width(range: egui::Rangef, width: f32) -> egui::Rangef {
 
 pub struct TimelineTabView<'a, 'd> {
     tab: &'a TimelineTab,
+    /// Indices into the tab's units to show instead of its own order
+    order: Option<&'a [usize]>,
+    /// Where to queue the missing ancestors of replies as they scroll by
+    prefetch: Option<&'a mut ParentPrefetch>,
     note_options: NoteOptions,
     txn: &'a Transaction,
     note_context: &'a mut NoteContext<'d>,
@@ -383,15 +460,28 @@ impl<'a, 'd> TimelineTabView<'a, 'd> {
     ) -> Self {
         Self {
             tab,
+            order: None,
+            prefetch: None,
             note_options,
             txn,
             note_context,
         }
     }
 
+    /// Show the units in this order rather than newest first
+    pub fn order(mut self, order: &'a [usize]) -> Self {
+        self.order = Some(order);
+        self
+    }
+
+    pub fn prefetch_parents(mut self, prefetch: &'a mut ParentPrefetch) -> Self {
+        self.prefetch = Some(prefetch);
+        self
+    }
+
     pub fn show(&mut self, ui: &mut egui::Ui) -> Option<NoteAction> {
         let mut action: Option<NoteAction> = None;
-        let len = self.tab.units.len();
+        let len = self.order.map_or(self.tab.units.len(), |order| order.len());
 
         let mute = self.note_context.accounts.mute();
 
@@ -403,6 +493,13 @@ impl<'a, 'd> TimelineTabView<'a, 'd> {
                 ui.spacing_mut().item_spacing.y = 0.0;
                 ui.spacing_mut().item_spacing.x = 4.0;
 
+                let Some(index) = self
+                    .order
+                    .map_or(Some(index), |order| order.get(index).copied())
+                else {
+                    return 0;
+                };
+
                 let Some(entry) = self.tab.units.get(index) else {
                     return 0;
                 };
@@ -450,16 +547,31 @@ impl<'a, 'd> TimelineTabView<'a, 'd> {
             note
         };
 
-        let muted = root_note_id_from_selected_id(
+        let notifications = self.note_options.contains(NoteOptions::Notification);
+        let mute_mode = root_note_id_from_selected_id(
             self.note_context.ndb,
             self.note_context.note_cache,
             self.txn,
             underlying_note.id(),
         )
-        .is_ok_and(|root_id| mute.is_muted(&underlying_note, root_id.bytes()));
+        .ok()
+        .and_then(|root_id| mute.mute_mode(&underlying_note, root_id.bytes()))
+        .filter(|mode| mode.applies_to(notifications));
+
+        match mute_mode {
+            None => {}
+            Some(MuteMode::Collapse) => {
+                if muted_placeholder(ui, self.note_context.i18n, underlying_note.id()) {
+                    return RenderEntryResponse::Success(None);
+                }
+            }
+            Some(MuteMode::Hide | MuteMode::NotificationsOnly) => {
+                return RenderEntryResponse::Success(None);
+            }
+        }
 
-        if muted {
-            return RenderEntryResponse::Success(None);
+        if let Some(prefetch) = self.prefetch.as_deref_mut() {
+            prefetch.visible_note(self.note_context.ndb, self.txn, &underlying_note);
         }
 
         match entry {
@@ -467,6 +579,10 @@ impl<'a, 'd> TimelineTabView<'a, 'd> {
                 render_note(ui, self.note_context, self.note_options, &underlying_note)
             }
             NoteUnit::Composite(composite) => match composite {
+                // the liked note itself, the reaction is implied by the tab
+                CompositeUnit::Reaction(_) if self.tab.filter == ViewFilter::Likes => {
+                    render_note(ui, self.note_context, self.note_options, &underlying_note)
+                }
                 CompositeUnit::Reaction(reaction_unit) => render_reaction_cluster(
                     ui,
                     self.note_context,
@@ -7,7 +7,13 @@ use notedeck::ReportType;
 use notedeck_ui::nip51_set::Nip51SetUiCache;
 
 use crate::deck_state::DeckState;
+use crate::group::GroupBrowseState;
+use crate::identity_archive::IdentityArchiveState;
+use crate::key_export::KeyExportState;
 use crate::login_manager::AcquireKeyState;
+use crate::media_servers::MediaServersState;
+use crate::mute_list::MuteListState;
+use crate::ui::publish_status::RecentPublish;
 use crate::ui::search::SearchQueryState;
 use enostr::ProfileState;
 use notedeck_ui::media::MediaViewerState;
@@ -43,9 +49,36 @@ pub struct ViewState {
     /// Database compaction state
     pub compact: CompactState,
 
+    /// Identity export and import in the keys section
+    pub identity_archive: IdentityArchiveState,
+
+    /// Secret key export in the keys section
+    pub key_export: KeyExportState,
+
+    /// Media servers page input and upload probes
+    pub media_servers: MediaServersState,
+
+    /// Mute list page input
+    pub mute_list: MuteListState,
+
+    /// Whether the quick post composer is showing
+    pub quick_post: bool,
+
+    /// Note just published from a composer, whose relay answers are shown
+    pub recent_publish: Option<RecentPublish>,
+
     /// Cache for people list selection in "Add Column" UI
     pub people_lists: Option<Nip51SetCache>,
 
+    /// Cache for people lists published by followed accounts in "Add Column" UI
+    pub followed_people_lists: Option<Nip51SetCache>,
+
+    /// Cache for bookmark set selection in "Add Column" UI
+    pub bookmark_sets: Option<Nip51SetCache>,
+
+    /// Relay being browsed in the "Add Column" group picker
+    pub group_browse: GroupBrowseState,
+
     /// State for the "Create People List" flow
     pub create_people_list: CreatePeopleListState,
 }
@@ -53,6 +86,9 @@ pub struct ViewState {
 #[derive(Default)]
 pub struct CreatePeopleListState {
     pub selected_members: HashSet<Pubkey>,
+
+    /// `d` tag of the list being edited, if this isn't a new list
+    pub editing: Option<String>,
 }
 
 impl ViewState {
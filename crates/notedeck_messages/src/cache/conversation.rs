@@ -1,4 +1,5 @@
 use std::cmp::Ordering;
+use std::time::{Duration, Instant};
 
 use crate::{
     cache::{
@@ -9,8 +10,12 @@ use crate::{
         },
     },
     convo_renderable::ConversationRenderable,
-    nip17::get_participants,
+    nip17::{
+        get_participants,
+        typing::{TypingSignal, TYPING_TIMEOUT},
+    },
     relay_ensure::DmListState,
+    typing::TypingListener,
 };
 
 use super::message_store::MessageStore;
@@ -25,6 +30,7 @@ pub struct ConversationCache {
     order: Vec<ConversationOrder>,
     pub state: ConversationListState,
     dm_relay_list_ensure: DmListState,
+    typing: TypingListener,
     pub active: Option<ConversationId>,
 }
 
@@ -120,6 +126,25 @@ impl ConversationCache {
     pub fn dm_relay_list_ensure_mut(&mut self) -> &mut DmListState {
         &mut self.dm_relay_list_ensure
     }
+
+    pub(crate) fn typing_listener_mut(&mut self) -> &mut TypingListener {
+        &mut self.typing
+    }
+
+    /// Mark the sender of a typing indicator as typing in the conversation
+    /// it was sent to. Indicators for conversations we don't have are dropped.
+    pub fn record_typing(&mut self, signal: &TypingSignal, now: Instant) {
+        let participants = signal.participants.iter().map(|pk| pk.bytes()).collect();
+        let Some(id) = self.registry.get(ConversationIdentifierUnowned::Nip17(
+            ParticipantSetUnowned::new(participants),
+        )) else {
+            return;
+        };
+
+        if let Some(conversation) = self.conversations.get_mut(&id) {
+            conversation.typing.insert(signal.sender, now);
+        }
+    }
 }
 
 fn refresh_order(order: &mut Vec<ConversationOrder>, id: ConversationId, latest: LatestMessage) {
@@ -192,6 +217,8 @@ pub struct Conversation {
     pub messages: MessageStore,
     pub metadata: ConversationMetadata,
     pub renderable: ConversationRenderable,
+    /// When each participant last told us they were typing
+    typing: HashMap<Pubkey, Instant>,
 }
 
 impl Conversation {
@@ -201,7 +228,26 @@ impl Conversation {
             messages: MessageStore::default(),
             metadata: ConversationMetadata::new(participants),
             renderable: ConversationRenderable::new(&[]),
+            typing: HashMap::new(),
+        }
+    }
+
+    /// Participants typing at `now`, along with how long until the first of
+    /// them stops counting as typing
+    pub fn typing(&self, now: Instant) -> (Vec<Pubkey>, Option<Duration>) {
+        let mut typing = Vec::new();
+        let mut next_expiry: Option<Duration> = None;
+
+        for (pk, at) in &self.typing {
+            let Some(left) = TYPING_TIMEOUT.checked_sub(now.saturating_duration_since(*at)) else {
+                continue;
+            };
+            typing.push(*pk);
+            next_expiry = Some(next_expiry.map_or(left, |cur| cur.min(left)));
         }
+
+        typing.sort();
+        (typing, next_expiry)
     }
 
     fn last_activity(&self) -> u64 {
@@ -230,6 +276,9 @@ impl Conversation {
             }
         }
 
+        // a message ends whatever its author was typing
+        self.typing.remove(&Pubkey::new(*note.pubkey()));
+
         let inserted = self.messages.insert(NotePkg {
             note_ref: NoteRef {
                 key,
@@ -254,6 +303,7 @@ impl Default for ConversationCache {
             order: Vec::new(),
             state: Default::default(),
             dm_relay_list_ensure: Default::default(),
+            typing: Default::default(),
             active: None,
         }
     }
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::time::Instant;
 
 use crate::cache::ConversationId;
 use egui_virtual_list::VirtualList;
@@ -37,4 +38,6 @@ pub struct ConversationState {
     pub list: VirtualList,
     pub last_read: Option<NoteRef>,
     pub composer: String,
+    /// When we last told the conversation we're typing
+    pub typing_sent_at: Option<Instant>,
 }
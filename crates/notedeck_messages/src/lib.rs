@@ -5,6 +5,7 @@ pub mod nav;
 pub mod nip17;
 mod relay_ensure;
 mod relay_prefetch;
+mod typing;
 pub mod ui;
 
 use enostr::Pubkey;
@@ -12,7 +13,8 @@ use hashbrown::{HashMap, HashSet};
 use nav::{process_messages_ui_response, Route};
 use nostrdb::{Ndb, Subscription, Transaction};
 use notedeck::{
-    ui::is_narrow, Accounts, App, AppContext, AppResponse, RemoteApi, Router, SubKey, SubOwnerKey,
+    name::get_display_name, tr, ui::is_narrow, unix_time_secs, Accounts, App, AppContext,
+    AppResponse, OsNotificationKind, OsNotifications, RemoteApi, Router, SubKey, SubOwnerKey,
 };
 
 use crate::{
@@ -27,6 +29,10 @@ use std::thread;
 /// Max loader messages to process per frame to avoid UI stalls.
 const MAX_LOADER_MSGS_PER_FRAME: usize = 8;
 
+/// Messages older than this when they show up were fetched, not just sent,
+/// and don't get a system notification.
+const NOTIFY_MAX_AGE_SECS: u64 = 10 * 60;
+
 /// Messages application state and background loaders.
 pub struct MessagesApp {
     messages: ConversationsCtx,
@@ -65,6 +71,8 @@ impl Drop for MessagesApp {
 impl App for MessagesApp {
     #[profiling::function]
     fn update(&mut self, ctx: &mut AppContext<'_>, egui_ctx: &egui::Context) {
+        self.messages.drop_removed_accounts(ctx.accounts);
+
         let Some(cache) = self.messages.get_current_mut(ctx.accounts) else {
             return;
         };
@@ -72,6 +80,7 @@ impl App for MessagesApp {
         self.loader.start(egui_ctx.clone(), ctx.ndb.clone());
 
         ensure_selected_account_dm_relay_list(ctx.ndb, &mut ctx.remote, ctx.accounts, cache);
+        typing::poll_typing(ctx.ndb, &mut ctx.remote, ctx.accounts, cache);
 
         match cache.state {
             ConversationListState::Initializing => {
@@ -86,14 +95,14 @@ impl App for MessagesApp {
             }
             ConversationListState::Loading { subscription } => {
                 if let Some(sub) = subscription {
-                    update_initialized(ctx, cache, sub);
+                    update_initialized(ctx, egui_ctx, cache, sub);
                 }
             }
             ConversationListState::Initialized(subscription) => 's: {
                 let Some(sub) = subscription else {
                     break 's;
                 };
-                update_initialized(ctx, cache, sub);
+                update_initialized(ctx, egui_ctx, cache, sub);
             }
         }
 
@@ -234,9 +243,21 @@ fn join_giftwrap_workers(workers: &mut Vec<thread::JoinHandle<()>>) {
 
 /// Poll the live subscription for new conversation notes.
 #[profiling::function]
-fn update_initialized(ctx: &mut AppContext, cache: &mut ConversationCache, sub: Subscription) {
+fn update_initialized(
+    ctx: &mut AppContext,
+    egui_ctx: &egui::Context,
+    cache: &mut ConversationCache,
+    sub: Subscription,
+) {
     let notes = ctx.ndb.poll_for_notes(sub, 10);
     let txn = Transaction::new(ctx.ndb).expect("txn");
+    let notify = OsNotifications::in_background(egui_ctx)
+        && ctx
+            .settings
+            .os_notification(OsNotificationKind::DirectMessage);
+    let me = *ctx.accounts.selected_account_pubkey();
+    let now = unix_time_secs();
+
     for key in notes {
         let note = match ctx.ndb.get_note_by_key(&txn, key) {
             Ok(n) => n,
@@ -245,6 +266,26 @@ fn update_initialized(ctx: &mut AppContext, cache: &mut ConversationCache, sub:
                 continue;
             }
         };
+
+        if notify
+            && note.pubkey() != me.bytes()
+            && now.saturating_sub(note.created_at()) < NOTIFY_MAX_AGE_SECS
+        {
+            let profile = ctx.ndb.get_profile_by_pubkey(&txn, note.pubkey()).ok();
+            let title = tr!(
+                ctx.i18n,
+                "New message from {name}",
+                "Title of the system notification for a direct message",
+                name = get_display_name(profile.as_ref()).name()
+            );
+            ctx.os_notifications.show(
+                egui_ctx,
+                OsNotificationKind::DirectMessage,
+                &title,
+                note.content(),
+            );
+        }
+
         cache.ingest_chatroom_msg(note, key, ctx.ndb, &txn, ctx.note_cache, ctx.unknown_ids);
     }
 }
@@ -440,6 +481,12 @@ struct ConversationsCtx {
 }
 
 impl ConversationsCtx {
+    /// Forget the decrypted conversations of accounts that were signed out
+    fn drop_removed_accounts(&mut self, accounts: &Accounts) {
+        self.convos_per_acc
+            .retain(|pk, _| accounts.cache.get(pk).is_some());
+    }
+
     /// Get the conversation cache for the selected account. Return None if we don't have a full kp
     pub fn get_current_mut(&mut self, accounts: &Accounts) -> Option<&mut ConversationCache> {
         accounts.get_selected_account().keypair().secret_key?;
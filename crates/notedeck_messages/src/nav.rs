@@ -8,7 +8,7 @@ use crate::{
         ConversationCache, ConversationId, ConversationIdentifierUnowned, ParticipantSetUnowned,
     },
     loader::MessagesLoader,
-    nip17::send_conversation_message,
+    nip17::{message::send_typing_indicator, send_conversation_message},
     open_conversation_with_prefetch,
 };
 
@@ -26,6 +26,8 @@ pub enum MessagesAction {
         content: String,
     },
     Open(ConversationId),
+    /// The user is typing in a conversation's composer
+    Typing(ConversationId),
     Creating,
     Back,
     Create {
@@ -203,6 +205,9 @@ fn handle_messages_action(
             conversation_id,
             content,
         } => send_conversation_message(conversation_id, content, cache, ctx),
+        MessagesAction::Typing(conversation_id) => {
+            send_typing_indicator(conversation_id, cache, ctx)
+        }
         MessagesAction::Open(conversation_id) => open_coversation_action(
             conversation_id,
             ctx,
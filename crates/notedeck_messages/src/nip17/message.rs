@@ -1,8 +1,10 @@
-use nostrdb::Transaction;
+use enostr::Pubkey;
+use nostrdb::{Ndb, Transaction};
 use notedeck::enostr::RelayId;
 use notedeck::{AppContext, RelayType};
 
 use crate::cache::{ConversationCache, ConversationId};
+use crate::nip17::typing::build_typing_wrap;
 use crate::nip17::{build_rumor_json, giftwrap_message, query_participant_dm_relays, OsRng};
 
 pub fn send_conversation_message(
@@ -56,19 +58,67 @@ pub fn send_conversation_message(
             }
         }
 
-        let participant_relays = query_participant_dm_relays(ctx.ndb, &txn, participant);
-        let relay_type = if participant_relays.is_empty() {
-            RelayType::AccountsWrite
-        } else {
-            RelayType::Explicit(
-                participant_relays
-                    .into_iter()
-                    .map(RelayId::Websocket)
-                    .collect(),
-            )
+        let relay_type = participant_relay_type(ctx.ndb, &txn, participant);
+        let mut publisher = ctx.remote.publisher(ctx.accounts);
+        publisher.publish_note(&gifrwrap_note, relay_type);
+    }
+}
+
+/// Tell the other participants of a conversation that we're typing, unless
+/// the user turned typing indicators off
+pub fn send_typing_indicator(
+    conversation_id: ConversationId,
+    cache: &ConversationCache,
+    ctx: &mut AppContext<'_>,
+) {
+    if !ctx.settings.send_typing_indicators() {
+        return;
+    }
+
+    let Some(conversation) = cache.get(conversation_id) else {
+        return;
+    };
+
+    let Some(selected_kp) = ctx.accounts.selected_filled() else {
+        return;
+    };
+
+    let txn = Transaction::new(ctx.ndb).expect("txn");
+    let mut rng = OsRng;
+    for participant in &conversation.metadata.participants {
+        // our own devices don't need to know
+        if participant == selected_kp.pubkey {
+            continue;
+        }
+
+        let Some(wrap) = build_typing_wrap(
+            &mut rng,
+            selected_kp.secret_key,
+            selected_kp.pubkey,
+            &conversation.metadata.participants,
+            participant,
+        ) else {
+            continue;
         };
 
+        let relay_type = participant_relay_type(ctx.ndb, &txn, participant);
         let mut publisher = ctx.remote.publisher(ctx.accounts);
-        publisher.publish_note(&gifrwrap_note, relay_type);
+        publisher.publish_note(&wrap, relay_type);
+    }
+}
+
+/// A participant's DM relays, or our own write relays when they haven't
+/// published a DM relay list
+fn participant_relay_type(ndb: &Ndb, txn: &Transaction, participant: &Pubkey) -> RelayType {
+    let participant_relays = query_participant_dm_relays(ndb, txn, participant);
+    if participant_relays.is_empty() {
+        RelayType::AccountsWrite
+    } else {
+        RelayType::Explicit(
+            participant_relays
+                .into_iter()
+                .map(RelayId::Websocket)
+                .collect(),
+        )
     }
 }
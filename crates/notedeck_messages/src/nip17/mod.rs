@@ -1,4 +1,5 @@
 pub mod message;
+pub mod typing;
 
 use enostr::{FullKeypair, NormRelayUrl, Pubkey, SecretKey};
 use hashbrown::HashSet;
@@ -18,6 +19,20 @@ fn build_rumor_json(
     message: &str,
     participants: &[Pubkey],
     sender_pubkey: &Pubkey,
+) -> Option<String> {
+    build_rumor_json_of_kind(
+        Kind::PrivateDirectMessage,
+        message,
+        participants,
+        sender_pubkey,
+    )
+}
+
+fn build_rumor_json_of_kind(
+    kind: Kind,
+    message: &str,
+    participants: &[Pubkey],
+    sender_pubkey: &Pubkey,
 ) -> Option<String> {
     let sender = nostrcrate_pk(sender_pubkey)?;
     let mut tags = Vec::new();
@@ -29,15 +44,52 @@ fn build_rumor_json(
         }
     }
 
-    let builder = EventBuilder::new(Kind::PrivateDirectMessage, message).tags(tags);
+    let builder = EventBuilder::new(kind, message).tags(tags);
     Some(builder.build(sender).as_json())
 }
 
+/// How a sealed rumor gets wrapped for its recipient
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum WrapKind {
+    /// Regular kind `1059` gift wrap that relays store, with a randomized
+    /// timestamp so it can't be matched to when it was sent
+    Stored,
+    /// Kind `21059` gift wrap that relays only forward to live subscribers.
+    /// Its timestamp is the real one, since it is only useful while fresh.
+    Ephemeral,
+}
+
+impl WrapKind {
+    fn kind(self) -> u32 {
+        match self {
+            WrapKind::Stored => 1059,
+            WrapKind::Ephemeral => typing::EPHEMERAL_GIFTWRAP_KIND,
+        }
+    }
+
+    fn created_at(self, rng: &mut OsRng) -> u64 {
+        match self {
+            WrapKind::Stored => randomized_timestamp(rng),
+            WrapKind::Ephemeral => current_timestamp(),
+        }
+    }
+}
+
 pub fn giftwrap_message(
     rng: &mut OsRng,
     sender_secret: &SecretKey,
     recipient: &Pubkey,
     rumor_json: &str,
+) -> Option<Note<'static>> {
+    seal_and_wrap(rng, sender_secret, recipient, rumor_json, WrapKind::Stored)
+}
+
+fn seal_and_wrap(
+    rng: &mut OsRng,
+    sender_secret: &SecretKey,
+    recipient: &Pubkey,
+    rumor_json: &str,
+    wrap_kind: WrapKind,
 ) -> Option<Note<'static>> {
     let Some(recipient_pk) = nostrcrate_pk(recipient) else {
         tracing::warn!("failed to convert recipient pubkey {}", recipient);
@@ -58,7 +110,7 @@ pub fn giftwrap_message(
         }
     };
 
-    let seal_created = randomized_timestamp(rng);
+    let seal_created = wrap_kind.created_at(rng);
     let Some(seal_json) = build_seal_json(&encrypted_rumor, sender_secret, seal_created) else {
         tracing::error!("failed to build seal for recipient {}", recipient);
         return None;
@@ -79,8 +131,14 @@ pub fn giftwrap_message(
         }
     };
 
-    let wrap_created = randomized_timestamp(rng);
-    build_giftwrap_note(&encrypted_seal, &wrap_keys, recipient, wrap_created)
+    let wrap_created = wrap_kind.created_at(rng);
+    build_giftwrap_note(
+        wrap_kind.kind(),
+        &encrypted_seal,
+        &wrap_keys,
+        recipient,
+        wrap_created,
+    )
 }
 
 fn build_seal_json(
@@ -101,13 +159,14 @@ fn build_seal_json(
 }
 
 fn build_giftwrap_note(
+    kind: u32,
     content: &str,
     wrap_keys: &FullKeypair,
     recipient: &Pubkey,
     created_at: u64,
 ) -> Option<Note<'static>> {
     let builder = NoteBuilder::new()
-        .kind(1059)
+        .kind(kind)
         .content(content)
         .created_at(created_at)
         .start_tag()
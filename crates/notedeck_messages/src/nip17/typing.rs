@@ -0,0 +1,132 @@
+//! Typing indicators for DM conversations.
+//!
+//! There is no NIP for these yet, so they borrow the NIP-17 shape: a rumor
+//! listing the conversation's participants is sealed and gift wrapped for
+//! each of them, only with an ephemeral wrap kind that relays forward to
+//! live subscribers but never store.
+
+use std::time::Duration;
+
+use enostr::{Pubkey, SecretKey};
+use nostr::{
+    event::{Event, Kind, UnsignedEvent},
+    nips::nip44,
+    util::JsonUtil,
+};
+use nostrdb::{Filter, FilterBuilder, Note};
+
+use super::{build_rumor_json_of_kind, nostrcrate_pk, seal_and_wrap, OsRng, WrapKind};
+
+/// Rumor kind of a typing indicator, an ephemeral counterpart to kind `14`
+pub const TYPING_KIND: u16 = 20014;
+
+/// Gift wrap kind for rumors that are only worth delivering live
+pub const EPHEMERAL_GIFTWRAP_KIND: u32 = 21059;
+
+/// How often we tell a conversation we're still typing
+pub const TYPING_SEND_INTERVAL: Duration = Duration::from_secs(4);
+
+/// How long someone counts as typing after their last indicator
+pub const TYPING_TIMEOUT: Duration = Duration::from_secs(8);
+
+/// Someone typing in a conversation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypingSignal {
+    pub sender: Pubkey,
+    /// Everyone in the conversation, including the sender
+    pub participants: Vec<Pubkey>,
+    pub created_at: u64,
+}
+
+/// Ephemeral gift wraps addressed to `me`
+pub fn typing_filter(me: &Pubkey) -> Filter {
+    FilterBuilder::new()
+        .kinds([EPHEMERAL_GIFTWRAP_KIND as u64])
+        .pubkey([me.bytes()])
+        .build()
+}
+
+/// Wrap a typing indicator for one of the conversation's participants
+pub fn build_typing_wrap(
+    rng: &mut OsRng,
+    sender_secret: &SecretKey,
+    sender: &Pubkey,
+    participants: &[Pubkey],
+    recipient: &Pubkey,
+) -> Option<Note<'static>> {
+    let rumor_json = build_rumor_json_of_kind(Kind::Custom(TYPING_KIND), "", participants, sender)?;
+    seal_and_wrap(
+        rng,
+        sender_secret,
+        recipient,
+        &rumor_json,
+        WrapKind::Ephemeral,
+    )
+}
+
+/// Open an ephemeral gift wrap sent to us. Returns `None` unless it holds a
+/// typing indicator whose rumor author matches the seal's signer.
+pub fn unwrap_typing(wrap: &Note<'_>, my_secret: &SecretKey) -> Option<TypingSignal> {
+    if wrap.kind() != EPHEMERAL_GIFTWRAP_KIND {
+        return None;
+    }
+
+    let wrap_pk = nostrcrate_pk(&Pubkey::new(*wrap.pubkey()))?;
+    let seal_json = nip44::decrypt(my_secret, &wrap_pk, wrap.content()).ok()?;
+    let seal = Event::from_json(seal_json).ok()?;
+    if seal.kind != Kind::Seal || seal.verify().is_err() {
+        return None;
+    }
+
+    let rumor_json = nip44::decrypt(my_secret, &seal.pubkey, &seal.content).ok()?;
+    let rumor = UnsignedEvent::from_json(rumor_json).ok()?;
+    if rumor.kind != Kind::Custom(TYPING_KIND) || rumor.pubkey != seal.pubkey {
+        return None;
+    }
+
+    let sender = Pubkey::new(seal.pubkey.to_bytes());
+    let mut participants: Vec<Pubkey> = rumor
+        .tags
+        .public_keys()
+        .map(|pk| Pubkey::new(pk.to_bytes()))
+        .collect();
+    if !participants.contains(&sender) {
+        participants.push(sender);
+    }
+
+    Some(TypingSignal {
+        sender,
+        participants,
+        created_at: wrap.created_at(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use enostr::FullKeypair;
+
+    #[test]
+    fn typing_wrap_roundtrip() {
+        let alice = FullKeypair::generate();
+        let bob = FullKeypair::generate();
+        let participants = vec![alice.pubkey, bob.pubkey];
+
+        let wrap = build_typing_wrap(
+            &mut OsRng,
+            &alice.secret_key,
+            &alice.pubkey,
+            &participants,
+            &bob.pubkey,
+        )
+        .expect("typing wrap");
+        assert_eq!(wrap.kind(), EPHEMERAL_GIFTWRAP_KIND);
+
+        let signal = unwrap_typing(&wrap, &bob.secret_key).expect("typing signal");
+        assert_eq!(signal.sender, alice.pubkey);
+        assert!(signal.participants.contains(&bob.pubkey));
+
+        // only the recipient can open it
+        assert!(unwrap_typing(&wrap, &FullKeypair::generate().secret_key).is_none());
+    }
+}
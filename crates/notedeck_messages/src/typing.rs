@@ -0,0 +1,159 @@
+use std::time::{Duration, Instant};
+
+use enostr::{NormRelayUrl, Pubkey};
+use hashbrown::HashSet;
+use nostrdb::{Ndb, Subscription, Transaction};
+use notedeck::{
+    unix_time_secs, Accounts, RelaySelection, RemoteApi, ScopedSubIdentity, SubConfig, SubKey,
+    SubOwnerKey,
+};
+
+use crate::{
+    cache::ConversationCache,
+    nip17::{
+        query_participant_dm_relays,
+        typing::{typing_filter, unwrap_typing, TypingSignal, TYPING_TIMEOUT},
+    },
+};
+
+/// How often the relays we listen on are re-resolved, so a DM relay list
+/// that arrives after startup is picked up
+const RELAY_REFRESH: Duration = Duration::from_secs(30);
+
+/// Max typing wraps to open per frame
+const MAX_TYPING_NOTES_PER_FRAME: u32 = 16;
+
+const TYPING_KEY: &str = "dm_typing";
+
+/// Listens for typing indicators addressed to one account
+#[derive(Default)]
+pub struct TypingListener {
+    local_sub: Option<Subscription>,
+    relays: Option<HashSet<NormRelayUrl>>,
+    relays_checked_at: Option<Instant>,
+}
+
+fn typing_owner_key(account_pk: Pubkey) -> SubOwnerKey {
+    SubOwnerKey::builder(TYPING_KEY).with(account_pk).finish()
+}
+
+/// Our own DM relays, which is where others send typing indicators. Empty
+/// when we don't have a DM relay list yet.
+fn listen_relays(ndb: &Ndb, me: &Pubkey) -> HashSet<NormRelayUrl> {
+    let txn = Transaction::new(ndb).expect("txn");
+    query_participant_dm_relays(ndb, &txn, me)
+        .into_iter()
+        .collect()
+}
+
+fn typing_spec(me: &Pubkey, relays: &HashSet<NormRelayUrl>) -> SubConfig {
+    SubConfig {
+        relays: if relays.is_empty() {
+            RelaySelection::AccountsRead
+        } else {
+            RelaySelection::Explicit(relays.clone())
+        },
+        filters: vec![typing_filter(me)],
+        use_transparent: false,
+    }
+}
+
+/// Keep the typing subscriptions alive and record who is typing in which
+/// conversation
+#[profiling::function]
+pub(crate) fn poll_typing(
+    ndb: &Ndb,
+    remote: &mut RemoteApi<'_>,
+    accounts: &Accounts,
+    cache: &mut ConversationCache,
+) {
+    let Some(secret) = accounts.selected_filled().map(|kp| kp.secret_key.clone()) else {
+        return;
+    };
+    let me = *accounts.selected_account_pubkey();
+    let now = Instant::now();
+
+    let listener = cache.typing_listener_mut();
+    ensure_typing_subs(listener, ndb, remote, accounts, &me, now);
+
+    let Some(sub) = listener.local_sub else {
+        return;
+    };
+
+    let keys = ndb.poll_for_notes(sub, MAX_TYPING_NOTES_PER_FRAME);
+    if keys.is_empty() {
+        return;
+    }
+
+    let txn = Transaction::new(ndb).expect("txn");
+    let signals: Vec<TypingSignal> = keys
+        .into_iter()
+        .filter_map(|key| ndb.get_note_by_key(&txn, key).ok())
+        .filter_map(|wrap| unwrap_typing(&wrap, &secret))
+        .filter(|signal| signal.sender != me && is_fresh(signal, unix_time_secs()))
+        .collect();
+
+    for signal in &signals {
+        cache.record_typing(signal, now);
+    }
+}
+
+fn ensure_typing_subs(
+    listener: &mut TypingListener,
+    ndb: &Ndb,
+    remote: &mut RemoteApi<'_>,
+    accounts: &Accounts,
+    me: &Pubkey,
+    now: Instant,
+) {
+    if listener.local_sub.is_none() {
+        match ndb.subscribe(&[typing_filter(me)]) {
+            Ok(sub) => listener.local_sub = Some(sub),
+            Err(err) => tracing::error!("couldn't subscribe to typing indicators: {err}"),
+        }
+    }
+
+    if listener
+        .relays_checked_at
+        .is_some_and(|at| now.saturating_duration_since(at) < RELAY_REFRESH)
+    {
+        return;
+    }
+    listener.relays_checked_at = Some(now);
+
+    let relays = listen_relays(ndb, me);
+    if listener.relays.as_ref() == Some(&relays) {
+        return;
+    }
+
+    let identity = ScopedSubIdentity::account(typing_owner_key(*me), SubKey::new(TYPING_KEY));
+    let mut scoped_subs = remote.scoped_subs(accounts);
+    let _ = scoped_subs.set_sub(identity, typing_spec(me, &relays));
+    listener.relays = Some(relays);
+}
+
+/// Ephemeral wraps carry their real creation time, so anything older than
+/// the typing timeout is a replay we can ignore
+fn is_fresh(signal: &TypingSignal, now_secs: u64) -> bool {
+    now_secs.saturating_sub(signal.created_at) <= TYPING_TIMEOUT.as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signal(created_at: u64) -> TypingSignal {
+        TypingSignal {
+            sender: Pubkey::new([1; 32]),
+            participants: vec![Pubkey::new([1; 32]), Pubkey::new([2; 32])],
+            created_at,
+        }
+    }
+
+    #[test]
+    fn stale_typing_signals_are_ignored() {
+        let now = 1_700_000_100;
+        assert!(is_fresh(&signal(now - 2), now));
+        assert!(!is_fresh(&signal(now - 60), now));
+    }
+}
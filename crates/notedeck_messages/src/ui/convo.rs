@@ -1,3 +1,5 @@
+use std::time::Instant;
+
 use chrono::{DateTime, Duration, Local, NaiveDate};
 use egui::{
     vec2, Align, Color32, CornerRadius, Frame, Key, KeyboardShortcut, Layout, Margin, Modifiers,
@@ -21,7 +23,7 @@ use crate::{
     },
     convo_renderable::{ConversationItem, MessageType},
     nav::MessagesAction,
-    nip17::{parse_chat_message, Nip17ChatMessage},
+    nip17::{parse_chat_message, typing::TYPING_SEND_INTERVAL, Nip17ChatMessage},
     ui::{local_datetime_from_nostr, title_label},
 };
 
@@ -80,6 +82,7 @@ impl<'a> ConversationUi<'a> {
                     }
                     comp_resp.composer_has_focus
                 });
+                typing_line(ui, self.conversation, self.ndb, &txn, self.i18n);
                 ui.with_layout(Layout::top_down(Align::Min), |ui| {
                     ScrollArea::vertical()
                         .stick_to_bottom(true)
@@ -105,6 +108,45 @@ impl<'a> ConversationUi<'a> {
     }
 }
 
+/// "… is typing" under the history while other participants are typing
+fn typing_line(
+    ui: &mut egui::Ui,
+    conversation: &Conversation,
+    ndb: &Ndb,
+    txn: &Transaction,
+    i18n: &mut Localization,
+) {
+    let (typing, next_expiry) = conversation.typing(Instant::now());
+    if let Some(left) = next_expiry {
+        ui.ctx().request_repaint_after(left);
+    }
+
+    let text = match typing.as_slice() {
+        [] => return,
+        [pk] => {
+            let profile = ndb.get_profile_by_pubkey(txn, pk.bytes()).ok();
+            let name = get_display_name(profile.as_ref()).name();
+            tr!(
+                i18n,
+                "{name} is typing…",
+                "Shown under a chat while the other participant is typing",
+                name = name
+            )
+        }
+        _ => tr!(
+            i18n,
+            "Several people are typing…",
+            "Shown under a group chat while more than one participant is typing"
+        ),
+    };
+
+    Frame::new()
+        .inner_margin(Margin::symmetric(16, 2))
+        .show(ui, |ui| {
+            ui.label(RichText::new(text).small().weak().italics());
+        });
+}
+
 #[allow(clippy::too_many_arguments)]
 fn conversation_history(
     ui: &mut egui::Ui,
@@ -310,6 +352,7 @@ fn conversation_composer(
             ))
             .color(ui.visuals().noninteractive().fg_stroke.color);
             let mut send = false;
+            let mut typing = false;
             let is_narrow = is_narrow(ui.ctx());
             let send_button_section = if is_narrow { 32.0 } else { 0.0 };
 
@@ -352,6 +395,7 @@ fn conversation_composer(
                             PasteBehavior::Append,
                         );
                         composer_has_focus = text_resp.has_focus();
+                        typing = text_resp.changed() && !state.composer.trim().is_empty();
                     });
 
                     if is_narrow {
@@ -373,6 +417,8 @@ fn conversation_composer(
                 });
             if send {
                 action = prepare_send_action(conversation_id, state);
+            } else if typing {
+                action = prepare_typing_action(conversation_id, state, Instant::now());
             }
         });
     });
@@ -397,12 +443,31 @@ fn prepare_send_action(
     }
 
     let message = std::mem::take(&mut state.composer);
+    // the next message starts a fresh typing indicator
+    state.typing_sent_at = None;
     Some(MessagesAction::SendMessage {
         conversation_id,
         content: message,
     })
 }
 
+/// Typing indicators are debounced so a burst of keystrokes sends one
+fn prepare_typing_action(
+    conversation_id: ConversationId,
+    state: &mut ConversationState,
+    now: Instant,
+) -> Option<MessagesAction> {
+    if state
+        .typing_sent_at
+        .is_some_and(|sent| now.saturating_duration_since(sent) < TYPING_SEND_INTERVAL)
+    {
+        return None;
+    }
+
+    state.typing_sent_at = Some(now);
+    Some(MessagesAction::Typing(conversation_id))
+}
+
 fn chat_bubble<R>(
     ui: &mut egui::Ui,
     msg_type: MessageType,
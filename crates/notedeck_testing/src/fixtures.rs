@@ -6,7 +6,7 @@ use std::time::{Duration, Instant};
 
 use enostr::{FullKeypair, ProfileState, Pubkey};
 use nostr::key::PublicKey;
-use nostrdb::{Config, FilterBuilder, Ndb, NoteBuilder, Transaction};
+use nostrdb::{Config, FilterBuilder, Ndb, Note, NoteBuilder, Transaction};
 use notedeck::{DataPath, DataPathType, RelayType};
 
 use crate::cluster::AccountCluster;
@@ -166,3 +166,39 @@ pub fn publish_note_via_device(device: &mut DeviceHarness, note: &nostrdb::Note<
     std::thread::sleep(Duration::from_millis(25));
     device.step();
 }
+
+/// Builds an empty note of `kind` with `tags` and signs it with `keypair`.
+pub fn signed_note(keypair: &FullKeypair, kind: u32, tags: &[&[&str]]) -> Note<'static> {
+    sign_with_tags(keypair, NoteBuilder::new().kind(kind).content(""), tags)
+}
+
+/// Like [`signed_note`], created at `created_at`.
+pub fn signed_note_at(
+    keypair: &FullKeypair,
+    kind: u32,
+    created_at: u64,
+    tags: &[&[&str]],
+) -> Note<'static> {
+    let builder = NoteBuilder::new()
+        .kind(kind)
+        .content("")
+        .created_at(created_at);
+    sign_with_tags(keypair, builder, tags)
+}
+
+fn sign_with_tags<'a>(
+    keypair: &FullKeypair,
+    mut builder: NoteBuilder<'a>,
+    tags: &[&[&str]],
+) -> Note<'a> {
+    for tag in tags {
+        builder = builder.start_tag();
+        for value in *tag {
+            builder = builder.tag_str(value);
+        }
+    }
+    builder
+        .sign(&keypair.secret_key.secret_bytes())
+        .build()
+        .expect("signed note")
+}
@@ -19,6 +19,15 @@ pub fn accounts_image() -> Image<'static> {
     Image::new(include_image!("../../../assets/icons/accounts_4x.png"))
 }
 
+pub fn bookmark_image(dark_mode: bool) -> Image<'static> {
+    let img = Image::new(include_image!("../../../assets/icons/bookmark.svg"));
+    if dark_mode {
+        img
+    } else {
+        img.tint(Color32::BLACK)
+    }
+}
+
 pub fn cln_image() -> Image<'static> {
     Image::new(include_image!("../../../assets/icons/clnlogo.svg"))
 }
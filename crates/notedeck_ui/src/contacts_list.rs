@@ -6,8 +6,8 @@ use enostr::Pubkey;
 use nostrdb::{Ndb, ProfileRecord, Transaction};
 use notedeck::tokens::{PFP_LG, SPACING_SM, SPACING_XS, STROKE_MEDIUM};
 use notedeck::{
-    name::get_display_name, profile::get_profile_url, tr, ContactState, DragResponse, Images,
-    Localization, MediaJobSender,
+    fuzzy::fuzzy_match, name::get_display_name, profile::get_profile_url, tr, ContactState,
+    DragResponse, Images, Localization, MediaJobSender,
 };
 
 /// Configuration options for profile row rendering.
@@ -292,3 +292,76 @@ pub fn search_profiles(
     contact_results.truncate(max_results);
     contact_results
 }
+
+/// How many of the newest profiles [`fuzzy_search_profiles`] looks through
+/// besides our contacts
+const FUZZY_SCAN_LIMIT: u64 = 2000;
+
+/// Like [`search_profiles`], but also finds names with a typo or two, so
+/// "jak" still finds "jack". Those come after the regular matches, closest
+/// first and contacts before others. This scans profiles, so cache the
+/// results instead of calling it every frame.
+pub fn fuzzy_search_profiles(
+    ndb: &Ndb,
+    txn: &Transaction,
+    query: &str,
+    contacts_state: &ContactState,
+    max_results: usize,
+) -> Vec<ProfileSearchResult> {
+    let mut results = search_profiles(ndb, txn, query, contacts_state, max_results);
+    if results.len() >= max_results || parse_pubkey_query(query).is_some() {
+        return results;
+    }
+
+    let contacts = match contacts_state {
+        ContactState::Received { contacts, .. } => Some(contacts),
+        _ => None,
+    };
+
+    let mut seen: HashSet<[u8; 32]> = results.iter().map(|r| r.pk).collect();
+    let mut candidates: Vec<[u8; 32]> = contacts
+        .map(|c| c.iter().map(|pk| *pk.bytes()).collect())
+        .unwrap_or_default();
+
+    let filter = nostrdb::Filter::new()
+        .kinds([0])
+        .limit(FUZZY_SCAN_LIMIT)
+        .build();
+    if let Ok(recent) = ndb.query(txn, &[filter], FUZZY_SCAN_LIMIT as i32) {
+        candidates.extend(recent.into_iter().map(|r| *r.note.pubkey()));
+    }
+
+    let mut fuzzy: Vec<(usize, bool, [u8; 32])> = Vec::new();
+    for pk in candidates {
+        if !seen.insert(pk) {
+            continue;
+        }
+
+        let Ok(profile) = ndb.get_profile_by_pubkey(txn, &pk) else {
+            continue;
+        };
+        let Some(record) = profile.record().profile() else {
+            continue;
+        };
+
+        let distance = [record.name(), record.display_name()]
+            .into_iter()
+            .flatten()
+            .filter_map(|name| fuzzy_match(name, query))
+            .min();
+
+        if let Some(distance) = distance {
+            let is_contact = contacts.is_some_and(|c| c.contains(&pk));
+            fuzzy.push((distance, is_contact, pk));
+        }
+    }
+
+    fuzzy.sort_by_key(|(distance, is_contact, _)| (*distance, !*is_contact));
+    results.extend(
+        fuzzy
+            .into_iter()
+            .map(|(_, is_contact, pk)| ProfileSearchResult { pk, is_contact }),
+    );
+    results.truncate(max_results);
+    results
+}
@@ -1,5 +1,6 @@
 /// Context menu helpers (paste, etc)
 use egui_winit::clipboard::Clipboard;
+use notedeck::{tr, Localization, OpenWith};
 
 #[derive(Copy, Clone, Eq, PartialEq)]
 pub enum PasteBehavior {
@@ -25,6 +26,18 @@ pub fn input_context(
     clipboard: &mut Clipboard,
     input: &mut String,
     paste_behavior: PasteBehavior,
+) {
+    input_context_with(ui, response, clipboard, input, paste_behavior, |_| {})
+}
+
+/// [`input_context`] with extra menu items shown after the standard ones
+pub fn input_context_with(
+    ui: &mut egui::Ui,
+    response: &egui::Response,
+    clipboard: &mut Clipboard,
+    input: &mut String,
+    paste_behavior: PasteBehavior,
+    extra_items: impl FnOnce(&mut egui::Ui),
 ) {
     context_menu(response, |ui| {
         if ui.button("Paste").clicked() {
@@ -42,6 +55,8 @@ pub fn input_context(
             input.clear();
             ui.close_menu();
         }
+
+        extra_items(ui);
     });
 
     if response.middle_clicked() {
@@ -155,3 +170,55 @@ pub fn context_button(ui: &mut egui::Ui, id: egui::Id, put_at: egui::Rect) -> eg
 
     response
 }
+
+/// "Open with" submenu for handing a note or profile to another client.
+/// The custom link entry only shows up once a template is configured.
+pub fn open_with_menu(
+    ui: &mut egui::Ui,
+    i18n: &mut Localization,
+    has_template: bool,
+) -> Option<OpenWith> {
+    let mut selection = None;
+
+    ui.menu_button(
+        tr!(
+            i18n,
+            "Open with",
+            "Submenu for opening in another nostr client"
+        ),
+        |ui| {
+            if ui.button("njump.me").clicked() {
+                selection = Some(OpenWith::Njump);
+            }
+
+            if ui
+                .button(tr!(
+                    i18n,
+                    "Nostr app",
+                    "Open in the app the system uses for nostr: links"
+                ))
+                .clicked()
+            {
+                selection = Some(OpenWith::NostrClient);
+            }
+
+            if has_template
+                && ui
+                    .button(tr!(
+                        i18n,
+                        "Custom link",
+                        "Open using the link template from settings"
+                    ))
+                    .clicked()
+            {
+                selection = Some(OpenWith::Template);
+            }
+
+            if selection.is_some() {
+                ui.close_menu();
+            }
+        },
+    );
+
+    selection
+}
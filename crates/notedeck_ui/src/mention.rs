@@ -1,8 +1,15 @@
-use crate::ProfilePreview;
+use crate::{
+    username::{impersonation_warning, is_known},
+    ProfilePreview,
+};
 use egui::Sense;
 use enostr::Pubkey;
 use nostrdb::{Ndb, Transaction};
-use notedeck::{name::get_display_name, Images, MediaJobSender, NoteAction, NotedeckTextStyle};
+use notedeck::{
+    name::{get_display_name, sanitize_name},
+    Accounts, Images, Localization, MediaJobSender, Nip05Cache, NoteAction, NotedeckTextStyle,
+    UnknownIds,
+};
 
 pub struct Mention<'a> {
     ndb: &'a Ndb,
@@ -12,6 +19,8 @@ pub struct Mention<'a> {
     pk: &'a [u8; 32],
     selectable: bool,
     size: Option<f32>,
+    unknown_ids: Option<&'a mut UnknownIds>,
+    impersonation: Option<(&'a Accounts, &'a mut Localization, &'a mut Nip05Cache)>,
 }
 
 impl<'a> Mention<'a> {
@@ -32,6 +41,8 @@ impl<'a> Mention<'a> {
             selectable,
             size,
             jobs,
+            unknown_ids: None,
+            impersonation: None,
         }
     }
 
@@ -45,6 +56,25 @@ impl<'a> Mention<'a> {
         self
     }
 
+    /// Refetch the profile when it's hovered and our copy is stale
+    pub fn refresh_stale(mut self, unknown_ids: &'a mut UnknownIds) -> Self {
+        self.unknown_ids = Some(unknown_ids);
+        self
+    }
+
+    /// Warn when the mentioned name poses as a key or handle and isn't
+    /// someone the selected account follows. A name showing its own nip05
+    /// handle counts as posing until that handle is verified.
+    pub fn flag_impersonation(
+        mut self,
+        accounts: &'a Accounts,
+        i18n: &'a mut Localization,
+        nip05_cache: &'a mut Nip05Cache,
+    ) -> Self {
+        self.impersonation = Some((accounts, i18n, nip05_cache));
+        self
+    }
+
     pub fn show(self, ui: &mut egui::Ui) -> Option<NoteAction> {
         mention_ui(
             self.ndb,
@@ -55,6 +85,8 @@ impl<'a> Mention<'a> {
             ui,
             self.size,
             self.selectable,
+            self.unknown_ids,
+            self.impersonation,
         )
     }
 }
@@ -70,15 +102,15 @@ fn mention_ui(
     ui: &mut egui::Ui,
     size: Option<f32>,
     selectable: bool,
+    unknown_ids: Option<&mut UnknownIds>,
+    impersonation: Option<(&Accounts, &mut Localization, &mut Nip05Cache)>,
 ) -> Option<NoteAction> {
     let link_color = ui.visuals().hyperlink_color;
 
     let profile = ndb.get_profile_by_pubkey(txn, pk).ok();
 
-    let name: String = format!(
-        "@{}",
-        get_display_name(profile.as_ref()).username_or_displayname()
-    );
+    let mut display_name = get_display_name(profile.as_ref());
+    let name: String = format!("@{}", sanitize_name(display_name.username_or_displayname()));
 
     let mut text = egui::RichText::new(name)
         .color(link_color)
@@ -95,12 +127,28 @@ fn mention_ui(
         )
         .on_hover_cursor(egui::CursorIcon::PointingHand);
 
+    if let Some((accounts, i18n, nip05_cache)) = impersonation {
+        display_name.check_own_handle(&Pubkey::new(*pk), nip05_cache);
+
+        if let Some(kind) = display_name.impersonation() {
+            if !is_known(accounts, pk) {
+                impersonation_warning(ui, i18n, kind);
+            }
+        }
+    }
+
     let note_action = if resp.clicked() {
         Some(NoteAction::Profile(Pubkey::new(*pk)))
     } else {
         None
     };
 
+    if resp.hovered() {
+        if let Some(unknown_ids) = unknown_ids {
+            unknown_ids.refresh_profile_if_stale(pk, profile.as_ref());
+        }
+    }
+
     if let Some(rec) = profile.as_ref() {
         resp.on_hover_ui_at_pointer(|ui| {
             ui.set_max_width(300.0);
@@ -0,0 +1,163 @@
+use egui::{Frame, Label, RichText, Sense};
+use nostrdb::{Note, Transaction};
+use notedeck::{
+    name::get_display_name, tr, LiveEvent, Localization, NoteAction, NoteAddress, NoteContext,
+    NotedeckTextStyle,
+};
+
+use super::live_event_card;
+use crate::{secondary_label, NoteOptions, ProfilePic};
+
+/// A compact card for an `naddr` link: kind, title and author of the
+/// addressed note. Clicking it opens the note with its replies. Live
+/// events get the full [`live_event_card`] instead.
+pub struct AddressPreview<'a, 'd> {
+    note_context: &'a mut NoteContext<'d>,
+    txn: &'a Transaction,
+    address: &'a NoteAddress,
+    options: NoteOptions,
+}
+
+impl<'a, 'd> AddressPreview<'a, 'd> {
+    pub fn new(
+        note_context: &'a mut NoteContext<'d>,
+        txn: &'a Transaction,
+        address: &'a NoteAddress,
+        options: NoteOptions,
+    ) -> Self {
+        Self {
+            note_context,
+            txn,
+            address,
+            options,
+        }
+    }
+
+    #[profiling::function]
+    pub fn show(self, ui: &mut egui::Ui) -> Option<NoteAction> {
+        let ndb = self.note_context.ndb;
+        let note = self
+            .note_context
+            .note_cache
+            .address_key(ndb, self.txn, self.address)
+            .and_then(|key| ndb.get_note_by_key(self.txn, key).ok());
+        if note.is_none() {
+            self.note_context
+                .unknown_ids
+                .add_address_if_missing(ndb, self.txn, self.address);
+        }
+
+        if let Some(note) = &note {
+            if let Some(event) = LiveEvent::from_note(note) {
+                return live_event_card(
+                    ui,
+                    self.note_context,
+                    self.txn,
+                    note,
+                    &event,
+                    self.options,
+                )
+                .inner;
+            }
+        }
+
+        let profile = ndb
+            .get_profile_by_pubkey(self.txn, self.address.pubkey.bytes())
+            .ok();
+        if profile.is_none() {
+            self.note_context.unknown_ids.add_pubkey_if_missing(
+                ndb,
+                self.txn,
+                self.address.pubkey.bytes(),
+            );
+        }
+
+        let kind = kind_label(self.note_context.i18n, self.address.kind);
+        let title = note
+            .as_ref()
+            .and_then(address_title)
+            .map(|t| t.to_owned())
+            .unwrap_or_else(|| {
+                if note.is_some() {
+                    self.address.identifier.clone()
+                } else {
+                    tr!(
+                        self.note_context.i18n,
+                        "Loading…",
+                        "Placeholder title while a linked note is being fetched"
+                    )
+                }
+            });
+        let status = note.as_ref().and_then(|n| tag_value(n, "status"));
+
+        let resp = Frame::new()
+            .inner_margin(egui::Margin::same(8))
+            .corner_radius(notedeck::tokens::RADIUS_MD as u8)
+            .stroke(ui.visuals().noninteractive().bg_stroke)
+            .show(ui, |ui| {
+                ui.set_width(ui.available_width());
+
+                ui.horizontal(|ui| {
+                    secondary_label(ui, kind);
+                    if let Some(status) = status {
+                        secondary_label(ui, "⋅");
+                        secondary_label(ui, status);
+                    }
+                });
+
+                ui.add(
+                    Label::new(
+                        RichText::new(title)
+                            .text_style(NotedeckTextStyle::Body.text_style())
+                            .strong(),
+                    )
+                    .wrap()
+                    .selectable(false),
+                );
+
+                ui.horizontal(|ui| {
+                    ui.add(
+                        &mut ProfilePic::new(
+                            self.note_context.img_cache,
+                            self.note_context.jobs,
+                            notedeck::profile::get_profile_url(profile.as_ref()),
+                        )
+                        .size(16.0),
+                    );
+                    secondary_label(ui, get_display_name(profile.as_ref()).name());
+                });
+            })
+            .response
+            .interact(Sense::click());
+
+        resp.on_hover_cursor(egui::CursorIcon::PointingHand)
+            .clicked()
+            .then(|| NoteAction::Address(self.address.clone()))
+    }
+}
+
+fn kind_label(i18n: &mut Localization, kind: u32) -> String {
+    tr!(
+        i18n,
+        "Kind {kind}",
+        "Label for a linked note, by its kind number",
+        kind = kind
+    )
+}
+
+fn address_title<'a>(note: &Note<'a>) -> Option<&'a str> {
+    tag_value(note, "title")
+        .or_else(|| tag_value(note, "name"))
+        .or_else(|| tag_value(note, "summary"))
+        .or_else(|| tag_value(note, "alt"))
+        .filter(|t| !t.trim().is_empty())
+}
+
+fn tag_value<'a>(note: &Note<'a>, name: &str) -> Option<&'a str> {
+    note.tags().into_iter().find_map(|tag| {
+        if tag.count() < 2 || tag.get_str(0) != Some(name) {
+            return None;
+        }
+        tag.get_str(1)
+    })
+}
@@ -0,0 +1,63 @@
+use egui::{Frame, RichText};
+use notedeck::{tr, ContentWarningMode, Localization};
+
+/// Stands in for the contents of a note with a NIP-36 content warning.
+/// Returns the placeholder while the contents stay hidden, and `None` once
+/// they're revealed or when the account always shows them.
+pub fn content_warning_placeholder(
+    ui: &mut egui::Ui,
+    i18n: &mut Localization,
+    note_id: &[u8; 32],
+    reason: &str,
+    mode: ContentWarningMode,
+) -> Option<egui::Response> {
+    let revealed_id = egui::Id::new(("cw-revealed", note_id));
+    let revealed = ui.data(|d| d.get_temp::<bool>(revealed_id).unwrap_or(false));
+    if mode == ContentWarningMode::AlwaysShow || (mode == ContentWarningMode::Ask && revealed) {
+        return None;
+    }
+
+    let resp = Frame::new()
+        .fill(ui.visuals().faint_bg_color)
+        .inner_margin(egui::Margin::same(8))
+        .corner_radius(notedeck::tokens::RADIUS_MD as u8)
+        .show(ui, |ui| {
+            ui.set_width(ui.available_width());
+            ui.label(
+                RichText::new(tr!(
+                    i18n,
+                    "Content warning",
+                    "Title of the placeholder hiding a note marked with a content warning"
+                ))
+                .strong(),
+            );
+
+            if !reason.trim().is_empty() {
+                ui.label(RichText::new(reason).weak());
+            }
+
+            if mode == ContentWarningMode::AlwaysHide {
+                ui.label(
+                    RichText::new(tr!(
+                        i18n,
+                        "Hidden by your content warning setting",
+                        "Shown on notes with a content warning when the account always hides them"
+                    ))
+                    .small()
+                    .weak(),
+                );
+            } else if ui
+                .small_button(tr!(
+                    i18n,
+                    "Show",
+                    "Button to reveal a note hidden behind a content warning"
+                ))
+                .clicked()
+            {
+                ui.data_mut(|d| d.insert_temp(revealed_id, true));
+            }
+        })
+        .response;
+
+    Some(resp)
+}
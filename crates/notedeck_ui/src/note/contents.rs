@@ -1,13 +1,20 @@
 use super::media::image_carousel;
 use crate::{
-    note::{NoteAction, NoteOptions, NoteResponse, NoteView},
+    note::{
+        content_warning_placeholder, file_card, has_renderer, live_event_card, poll_card,
+        unknown_kind_card, AddressPreview, NoteAction, NoteOptions, NoteResponse, NoteView,
+    },
     secondary_label,
 };
 use egui::{Color32, Hyperlink, Label, RichText};
+use enostr::Pubkey;
 use nostrdb::{BlockType, Mention, Note, NoteKey, Transaction};
-use notedeck::Localization;
 use notedeck::RenderableMedia;
-use notedeck::{time_format, update_imeta_blurhashes, NoteCache, NoteContext, NotedeckTextStyle};
+use notedeck::{
+    content_warning, time_format, tokenize_text, update_imeta_blurhashes, FileMetadata, LiveEvent,
+    NoteAddress, NoteCache, NoteContext, NotedeckTextStyle, Poll, TextToken,
+};
+use notedeck::{tr, Localization};
 use tracing::warn;
 
 pub struct NoteContents<'a, 'd> {
@@ -15,6 +22,7 @@ pub struct NoteContents<'a, 'd> {
     txn: &'a Transaction,
     note: &'a Note<'a>,
     options: NoteOptions,
+    preview_depth: u8,
     pub action: Option<NoteAction>,
 }
 
@@ -31,14 +39,29 @@ impl<'a, 'd> NoteContents<'a, 'd> {
             txn,
             note,
             options,
+            preview_depth: 0,
             action: None,
         }
     }
+
+    /// How many quotes deep these contents are. Quoted notes past
+    /// [`NoteOptions::max_preview_depth`] are collapsed.
+    pub fn preview_depth(mut self, depth: u8) -> Self {
+        self.preview_depth = depth;
+        self
+    }
 }
 
 impl egui::Widget for &mut NoteContents<'_, '_> {
     fn ui(self, ui: &mut egui::Ui) -> egui::Response {
-        let result = render_note_contents(ui, self.note_context, self.txn, self.note, self.options);
+        let result = render_note_contents(
+            ui,
+            self.note_context,
+            self.txn,
+            self.note,
+            self.options,
+            self.preview_depth,
+        );
         self.action = result.action;
         result.response
     }
@@ -63,7 +86,8 @@ fn render_client_name(ui: &mut egui::Ui, note_cache: &mut NoteCache, note: &Note
 }
 
 /// Render an inline note preview with a border. These are used when
-/// notes are references within a note
+/// notes are references within a note. `depth` is how many quotes deep
+/// the preview itself sits, starting at 1.
 #[allow(clippy::too_many_arguments)]
 #[profiling::function]
 pub fn render_note_preview(
@@ -73,23 +97,33 @@ pub fn render_note_preview(
     id: &[u8; 32],
     parent: NoteKey,
     note_options: NoteOptions,
+    depth: u8,
 ) -> NoteResponse {
     let note = if let Ok(note) = note_context.ndb.get_note_by_id(txn, id) {
-        // TODO: support other preview kinds
-        if note.kind() == 1 {
+        if has_renderer(note.kind()) {
             note
         } else {
-            return NoteResponse::new(ui.colored_label(
-                Color32::RED,
-                format!("TODO: can't preview kind {}", note.kind()),
-            ));
+            let resp = unknown_kind_card(ui, note_context, txn, &note);
+            let action = resp.action.map(|a| match a {
+                NoteAction::Note { note_id, .. } => NoteAction::Note {
+                    note_id,
+                    preview: true,
+                    scroll_offset: 0.0,
+                },
+                other => other,
+            });
+            return NoteResponse::new(resp.response).with_action(action);
         }
     } else {
         note_context
             .unknown_ids
             .add_note_id_if_missing(note_context.ndb, txn, id);
 
-        return NoteResponse::new(ui.colored_label(Color32::RED, "TODO: COULD NOT LOAD"));
+        return NoteResponse::new(ui.weak(tr!(
+            note_context.i18n,
+            "Loading note…",
+            "Placeholder for a quoted note that hasn't been fetched yet"
+        )));
         /*
         return ui
             .horizontal(|ui| {
@@ -103,6 +137,7 @@ pub fn render_note_preview(
 
     NoteView::new(note_context, &note, note_options)
         .preview_style()
+        .preview_depth(depth)
         .parent(parent)
         .show(ui)
 }
@@ -114,8 +149,26 @@ fn render_note_contents(
     txn: &Transaction,
     note: &Note,
     options: NoteOptions,
+    preview_depth: u8,
 ) -> NoteResponse {
-    let response = render_undecorated_note_contents(ui, note_context, txn, note, options);
+    if let Some(reason) = content_warning(note) {
+        if let Some(placeholder) = content_warning_placeholder(
+            ui,
+            note_context.i18n,
+            note.id(),
+            reason,
+            options.content_warning_mode(),
+        ) {
+            return NoteResponse::new(placeholder);
+        }
+    }
+
+    if !has_renderer(note.kind()) {
+        return unknown_kind_card(ui, note_context, txn, note);
+    }
+
+    let response =
+        render_undecorated_note_contents(ui, note_context, txn, note, options, preview_depth);
 
     ui.horizontal_wrapped(|ui| {
         note_bottom_metadata_ui(
@@ -157,11 +210,13 @@ fn render_undecorated_note_contents<'a>(
     txn: &Transaction,
     note: &'a Note,
     options: NoteOptions,
+    preview_depth: u8,
 ) -> NoteResponse {
     let note_key = note.key().expect("todo: implement non-db notes");
     let selectable = options.contains(NoteOptions::SelectableText);
     let mut note_action: Option<NoteAction> = None;
     let mut inline_note: Option<(&[u8; 32], &str)> = None;
+    let mut inline_addrs: Vec<NoteAddress> = Vec::new();
     let hide_media = options.contains(NoteOptions::HideMedia);
     let link_color = ui.visuals().hyperlink_color;
 
@@ -199,6 +254,12 @@ fn render_undecorated_note_contents<'a>(
                             txn,
                             profile.pubkey(),
                         )
+                        .refresh_stale(note_context.unknown_ids)
+                        .flag_impersonation(
+                            note_context.accounts,
+                            note_context.i18n,
+                            note_context.nip05_cache,
+                        )
                         .show(ui);
 
                         if act.is_some() {
@@ -215,6 +276,12 @@ fn render_undecorated_note_contents<'a>(
                             txn,
                             npub.pubkey(),
                         )
+                        .refresh_stale(note_context.unknown_ids)
+                        .flag_impersonation(
+                            note_context.accounts,
+                            note_context.i18n,
+                            note_context.nip05_cache,
+                        )
                         .show(ui);
 
                         if act.is_some() {
@@ -230,6 +297,14 @@ fn render_undecorated_note_contents<'a>(
                         inline_note = Some((note.id(), block.as_str()));
                     }
 
+                    Mention::Addr(addr) if options.contains(NoteOptions::HasNotePreviews) => {
+                        inline_addrs.push(NoteAddress::new(
+                            addr.kind(),
+                            Pubkey::new(*addr.pubkey()),
+                            addr.identifier(),
+                        ));
+                    }
+
                     _ => {
                         ui.colored_label(
                             link_color,
@@ -321,14 +396,10 @@ fn render_undecorated_note_contents<'a>(
                             .selectable(selectable),
                         );
                     } else {
-                        let mut richtext = RichText::new(block_str)
-                            .text_style(NotedeckTextStyle::NoteBody.text_style());
-
-                        if options.contains(NoteOptions::NotificationPreview) {
-                            richtext = richtext.color(egui::Color32::from_rgb(0x87, 0x87, 0x8D));
+                        let act = render_text_block(ui, note_context, txn, block_str, options);
+                        if act.is_some() {
+                            note_action = act;
                         }
-
-                        ui.add(Label::new(richtext).wrap().selectable(selectable));
                     }
                     // don't render any more blocks
                     if truncate {
@@ -344,18 +415,69 @@ fn render_undecorated_note_contents<'a>(
     });
 
     let preview_note_action = inline_note.and_then(|(id, _)| {
-        render_note_preview(ui, note_context, txn, id, note_key, options)
-            .action
-            .map(|a| match a {
-                NoteAction::Note { note_id, .. } => NoteAction::Note {
-                    note_id,
-                    preview: true,
-                    scroll_offset: 0.0,
-                },
-                other => other,
-            })
+        let expanded_id = egui::Id::new(("quote-expanded", note_key, id));
+        let expanded = ui.data(|d| d.get_temp::<bool>(expanded_id).unwrap_or(false));
+
+        if preview_depth >= options.max_preview_depth() && !expanded {
+            ui.add_space(2.0);
+            let label = tr!(
+                note_context.i18n,
+                "Show quoted note",
+                "Button to expand a quoted note nested too deep to preview"
+            );
+            if ui.small_button(label).clicked() {
+                ui.data_mut(|d| d.insert_temp(expanded_id, true));
+            }
+            return None;
+        }
+
+        render_note_preview(
+            ui,
+            note_context,
+            txn,
+            id,
+            note_key,
+            options,
+            preview_depth + 1,
+        )
+        .action
+        .map(|a| match a {
+            NoteAction::Note { note_id, .. } => NoteAction::Note {
+                note_id,
+                preview: true,
+                scroll_offset: 0.0,
+            },
+            other => other,
+        })
     });
 
+    let mut addr_action = None;
+    for address in &inline_addrs {
+        ui.add_space(2.0);
+        if let Some(action) = AddressPreview::new(note_context, txn, address, options).show(ui) {
+            addr_action = Some(action);
+        }
+    }
+
+    if let Some(file) = FileMetadata::from_note(note) {
+        ui.add_space(2.0);
+        file_card(ui, note_context.i18n, &file);
+        if !hide_media {
+            supported_medias.extend(file.renderable_media());
+        }
+    }
+
+    let mut live_action = None;
+    if let Some(event) = LiveEvent::from_note(note) {
+        ui.add_space(2.0);
+        live_action = live_event_card(ui, note_context, txn, note, &event, options).inner;
+    }
+
+    let mut poll_action = None;
+    if let Some(poll) = Poll::from_note(note) {
+        poll_action = poll_card(ui, note_context, txn, note, &poll);
+    }
+
     let mut media_action = None;
     if !supported_medias.is_empty() && !options.contains(NoteOptions::Textmode) {
         ui.add_space(2.0);
@@ -374,12 +496,96 @@ fn render_undecorated_note_contents<'a>(
     }
 
     let note_action = preview_note_action
+        .or(addr_action)
+        .or(live_action)
+        .or(poll_action)
         .or(note_action)
         .or(media_action.map(NoteAction::Media));
 
     NoteResponse::new(response.response).with_action(note_action)
 }
 
+/// Plain text, with the cashtags, non-ascii hashtags and bare bech32
+/// mentions nostrdb leaves in text blocks made clickable
+fn render_text_block(
+    ui: &mut egui::Ui,
+    note_context: &mut NoteContext,
+    txn: &Transaction,
+    text: &str,
+    options: NoteOptions,
+) -> Option<NoteAction> {
+    let selectable = options.contains(NoteOptions::SelectableText);
+    let link_color = ui.visuals().hyperlink_color;
+    let mut action = None;
+
+    for token in tokenize_text(text) {
+        match token {
+            TextToken::Text(text) => {
+                let mut richtext =
+                    RichText::new(text).text_style(NotedeckTextStyle::NoteBody.text_style());
+
+                if options.contains(NoteOptions::NotificationPreview) {
+                    richtext = richtext.color(egui::Color32::from_rgb(0x87, 0x87, 0x8D));
+                }
+
+                ui.add(Label::new(richtext).wrap().selectable(selectable));
+            }
+
+            TextToken::Hashtag(tag) => {
+                if text_link(ui, link_color, format!("#{tag}")).clicked() {
+                    action = Some(NoteAction::Hashtag(tag.to_lowercase()));
+                }
+            }
+
+            // tickers are tagged like any other topic, so they open the
+            // hashtag timeline
+            TextToken::Cashtag(ticker) => {
+                if text_link(ui, link_color, format!("${ticker}")).clicked() {
+                    action = Some(NoteAction::Hashtag(ticker.to_lowercase()));
+                }
+            }
+
+            TextToken::Profile { pubkey, .. } => {
+                let act = crate::Mention::new(
+                    note_context.ndb,
+                    note_context.img_cache,
+                    note_context.jobs,
+                    txn,
+                    pubkey.bytes(),
+                )
+                .refresh_stale(note_context.unknown_ids)
+                .flag_impersonation(
+                    note_context.accounts,
+                    note_context.i18n,
+                    note_context.nip05_cache,
+                )
+                .show(ui);
+
+                if act.is_some() {
+                    action = act;
+                }
+            }
+
+            TextToken::Note { note_id, bech } => {
+                let label = format!("{}…", &bech[..bech.len().min(16)]);
+                if text_link(ui, link_color, label).clicked() {
+                    action = Some(NoteAction::note(note_id));
+                }
+            }
+        }
+    }
+
+    action
+}
+
+fn text_link(ui: &mut egui::Ui, color: Color32, text: String) -> egui::Response {
+    ui.colored_label(
+        color,
+        RichText::new(text).text_style(NotedeckTextStyle::NoteBody.text_style()),
+    )
+    .on_hover_cursor(egui::CursorIcon::PointingHand)
+}
+
 fn rot13(input: &str) -> String {
     input
         .chars()
@@ -1,9 +1,21 @@
 use egui::{Rect, Vec2};
 use enostr::NoteId;
 use nostrdb::NoteKey;
-use notedeck::{tr, BroadcastContext, Localization, NoteContextSelection};
+use notedeck::{
+    tr, BookmarkTarget, BroadcastContext, Localization, Nip51Set, NoteContextSelection,
+    ThreadExport, ThreadExportFormat, ThreadExportTarget,
+};
 
-use crate::context_menu::{context_button, stationary_arbitrary_menu_button};
+use crate::context_menu::{context_button, open_with_menu, stationary_arbitrary_menu_button};
+
+/// What of a note is on the account's mute list, for the mute items of its
+/// context menu
+pub struct NoteMutes {
+    pub user: bool,
+    pub thread: bool,
+    /// The note's hashtags and whether each is muted
+    pub hashtags: Vec<(String, bool)>,
+}
 
 pub struct NoteContextButton {
     put_at: Option<Rect>,
@@ -61,6 +73,8 @@ impl NoteContextButton {
         context_button(ui, id, put_at)
     }
 
+    /// `bookmark_sets` is only called while the bookmark submenu is open.
+    #[allow(clippy::too_many_arguments)]
     #[profiling::function]
     pub fn menu(
         ui: &mut egui::Ui,
@@ -68,7 +82,10 @@ impl NoteContextButton {
         button_response: egui::Response,
         note_id: NoteId,
         can_sign: bool,
-        is_muted: bool,
+        own_note: bool,
+        mutes: NoteMutes,
+        has_open_with_template: bool,
+        bookmark_sets: impl FnOnce() -> Vec<Nip51Set>,
     ) -> Option<NoteContextSelection> {
         let mut context_selection: Option<NoteContextSelection> = None;
 
@@ -87,6 +104,20 @@ impl NoteContextButton {
                 ui.close_menu();
             }
 
+            ui.menu_button(
+                tr!(
+                    i18n,
+                    "Export Thread",
+                    "Submenu to export this note's thread as text"
+                ),
+                |ui| {
+                    if let Some(export) = thread_export_menu(ui, i18n) {
+                        context_selection = Some(NoteContextSelection::ExportThread(export));
+                        ui.close_menu();
+                    }
+                },
+            );
+
             if ui
                 .button(tr!(
                     i18n,
@@ -99,6 +130,11 @@ impl NoteContextButton {
                 ui.close_menu();
             }
 
+            if let Some(target) = open_with_menu(ui, i18n, has_open_with_template) {
+                context_selection = Some(NoteContextSelection::OpenWith(target));
+                ui.close_menu();
+            }
+
             // Debug: Check what the tr! macro returns
             let copy_text = tr!(
                 i18n,
@@ -171,7 +207,7 @@ impl NoteContextButton {
             }
 
             if can_sign {
-                let label = if is_muted {
+                let label = if mutes.user {
                     tr!(i18n, "Unmute User", "Unmute the author of this note")
                 } else {
                     tr!(i18n, "Mute User", "Mute the author of this note")
@@ -181,6 +217,61 @@ impl NoteContextButton {
                     ui.close_menu();
                 }
 
+                let label = if mutes.thread {
+                    tr!(i18n, "Unmute Thread", "Unmute the thread this note is in")
+                } else {
+                    tr!(i18n, "Mute Thread", "Mute the thread this note is in")
+                };
+                if ui.button(label).clicked() {
+                    context_selection = Some(NoteContextSelection::MuteThread);
+                    ui.close_menu();
+                }
+
+                if !mutes.hashtags.is_empty() {
+                    ui.menu_button(
+                        tr!(
+                            i18n,
+                            "Mute Hashtag",
+                            "Submenu to mute one of this note's hashtags"
+                        ),
+                        |ui| {
+                            for (hashtag, muted) in &mutes.hashtags {
+                                let label = if *muted {
+                                    tr!(
+                                        i18n,
+                                        "Unmute #{hashtag}",
+                                        "Unmute one of this note's hashtags",
+                                        hashtag = hashtag
+                                    )
+                                } else {
+                                    tr!(
+                                        i18n,
+                                        "Mute #{hashtag}",
+                                        "Mute one of this note's hashtags",
+                                        hashtag = hashtag
+                                    )
+                                };
+                                if ui.button(label).clicked() {
+                                    context_selection =
+                                        Some(NoteContextSelection::MuteHashtag(hashtag.clone()));
+                                    ui.close_menu();
+                                }
+                            }
+                        },
+                    );
+                }
+
+                ui.menu_button(
+                    tr!(i18n, "Bookmark", "Add this note to a bookmark folder"),
+                    |ui| {
+                        let sets = bookmark_sets();
+                        if let Some(target) = bookmark_menu(ui, i18n, &note_id, &sets) {
+                            context_selection = Some(NoteContextSelection::Bookmark(target));
+                            ui.close_menu();
+                        }
+                    },
+                );
+
                 if ui
                     .button(tr!(
                         i18n,
@@ -192,9 +283,174 @@ impl NoteContextButton {
                     context_selection = Some(NoteContextSelection::ReportUser);
                     ui.close_menu();
                 }
+
+                if own_note {
+                    // a submenu, so deleting takes a second deliberate click
+                    ui.menu_button(
+                        tr!(
+                            i18n,
+                            "Delete Note",
+                            "Submenu to delete one of our own notes"
+                        ),
+                        |ui| {
+                            ui.label(tr!(
+                                i18n,
+                                "Relays and clients may still keep a copy",
+                                "Warning before asking relays to delete a note"
+                            ));
+                            if ui
+                                .button(tr!(
+                                    i18n,
+                                    "Delete",
+                                    "Confirm asking relays to delete one of our own notes"
+                                ))
+                                .clicked()
+                            {
+                                context_selection = Some(NoteContextSelection::Delete);
+                                ui.close_menu();
+                            }
+                        },
+                    );
+                }
             }
         });
 
         context_selection
     }
 }
+
+/// Format and destination picker for the export thread submenu. Saving to
+/// a file needs a desktop file dialog.
+fn thread_export_menu(ui: &mut egui::Ui, i18n: &mut Localization) -> Option<ThreadExport> {
+    let mut options = vec![
+        (
+            tr!(
+                i18n,
+                "Copy as Markdown",
+                "Copy the thread to the clipboard as Markdown"
+            ),
+            ThreadExportFormat::Markdown,
+            ThreadExportTarget::Clipboard,
+        ),
+        (
+            tr!(
+                i18n,
+                "Copy as Text",
+                "Copy the thread to the clipboard as plain text"
+            ),
+            ThreadExportFormat::PlainText,
+            ThreadExportTarget::Clipboard,
+        ),
+    ];
+
+    if cfg!(any(
+        target_os = "windows",
+        target_os = "macos",
+        target_os = "linux"
+    )) {
+        options.push((
+            tr!(
+                i18n,
+                "Save as Markdown…",
+                "Save the thread to a Markdown file"
+            ),
+            ThreadExportFormat::Markdown,
+            ThreadExportTarget::File,
+        ));
+        options.push((
+            tr!(
+                i18n,
+                "Save as Text…",
+                "Save the thread to a plain text file"
+            ),
+            ThreadExportFormat::PlainText,
+            ThreadExportTarget::File,
+        ));
+    }
+
+    let mut export = None;
+    for (label, format, target) in options {
+        if ui.button(label).clicked() {
+            export = Some(ThreadExport { format, target });
+        }
+    }
+    export
+}
+
+/// Folder picker for the bookmark submenu. Existing folders that already
+/// hold the note publicly are disabled.
+pub(crate) fn bookmark_menu(
+    ui: &mut egui::Ui,
+    i18n: &mut Localization,
+    note_id: &NoteId,
+    sets: &[Nip51Set],
+) -> Option<BookmarkTarget> {
+    let private_id = egui::Id::new("bookmark_menu_private");
+    let new_set_id = egui::Id::new("bookmark_menu_new_set");
+
+    let mut private = ui.data(|d| d.get_temp::<bool>(private_id)).unwrap_or(false);
+    ui.checkbox(
+        &mut private,
+        tr!(
+            i18n,
+            "Private",
+            "Checkbox to store a bookmark encrypted so only you can see it"
+        ),
+    );
+    ui.data_mut(|d| d.insert_temp(private_id, private));
+    ui.separator();
+
+    let mut target = None;
+    for set in sets {
+        let title = set.title.as_deref().unwrap_or(&set.identifier);
+        let enabled = !set.notes.contains(note_id);
+        if ui.add_enabled(enabled, egui::Button::new(title)).clicked() {
+            target = Some(BookmarkTarget {
+                identifier: set.identifier.clone(),
+                private,
+            });
+        }
+    }
+
+    if !sets.is_empty() {
+        ui.separator();
+    }
+
+    let mut name = ui
+        .data(|d| d.get_temp::<String>(new_set_id))
+        .unwrap_or_default();
+    ui.horizontal(|ui| {
+        ui.add(
+            egui::TextEdit::singleline(&mut name)
+                .hint_text(tr!(
+                    i18n,
+                    "New folder",
+                    "Placeholder for the name of a new bookmark folder"
+                ))
+                .desired_width(120.0),
+        );
+
+        let trimmed = name.trim();
+        let create = ui.add_enabled(
+            !trimmed.is_empty(),
+            egui::Button::new(tr!(
+                i18n,
+                "Add",
+                "Button to create a bookmark folder containing this note"
+            )),
+        );
+        if create.clicked() {
+            target = Some(BookmarkTarget {
+                identifier: trimmed.to_owned(),
+                private,
+            });
+        }
+    });
+
+    if target.is_some() {
+        name.clear();
+    }
+    ui.data_mut(|d| d.insert_temp(new_set_id, name));
+
+    target
+}
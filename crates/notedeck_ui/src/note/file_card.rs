@@ -0,0 +1,58 @@
+use egui::{Frame, Hyperlink, Label, RichText};
+use notedeck::{format_file_size, tr, FileMetadata, Localization, NotedeckTextStyle};
+
+use crate::secondary_label;
+
+/// A card for a NIP-94 file: its name, type and size, and links to
+/// download it or fetch it over bittorrent.
+pub fn file_card(ui: &mut egui::Ui, i18n: &mut Localization, file: &FileMetadata) {
+    Frame::new()
+        .inner_margin(egui::Margin::same(8))
+        .corner_radius(notedeck::tokens::RADIUS_MD as u8)
+        .stroke(ui.visuals().noninteractive().bg_stroke)
+        .show(ui, |ui| {
+            ui.set_width(ui.available_width());
+
+            ui.add(
+                Label::new(
+                    RichText::new(file.filename())
+                        .text_style(NotedeckTextStyle::Body.text_style())
+                        .strong(),
+                )
+                .wrap()
+                .selectable(false),
+            );
+
+            let details: Vec<String> = file
+                .mime
+                .map(str::to_owned)
+                .into_iter()
+                .chain(file.size.map(format_file_size))
+                .collect();
+            if !details.is_empty() {
+                secondary_label(ui, details.join(" ⋅ "));
+            }
+
+            if let Some(summary) = file.summary {
+                ui.add(Label::new(RichText::new(summary).weak()).wrap());
+            }
+
+            ui.horizontal(|ui| {
+                ui.add(Hyperlink::from_label_and_url(
+                    tr!(i18n, "Download", "Link to download a shared file"),
+                    file.url,
+                ));
+
+                if let Some(magnet) = file.magnet {
+                    ui.add(Hyperlink::from_label_and_url(
+                        tr!(
+                            i18n,
+                            "Magnet link",
+                            "Link to fetch a shared file over bittorrent"
+                        ),
+                        magnet,
+                    ));
+                }
+            });
+        });
+}
@@ -0,0 +1,221 @@
+use egui::{Color32, Frame, Hyperlink, InnerResponse, Label, RichText, Sense};
+use enostr::Pubkey;
+use nostrdb::{Note, Transaction};
+use notedeck::{
+    name::get_display_name, time_format, tr, unix_time_secs, LiveEvent, LiveStatus, NoteAction,
+    NoteContext, NotedeckTextStyle,
+};
+
+use super::media::image_carousel;
+use crate::{secondary_label, NoteOptions, ProfilePic};
+
+/// Participants listed on a card before the rest are summed up
+const MAX_SHOWN_PARTICIPANTS: usize = 8;
+
+const LIVE_COLOR: Color32 = Color32::from_rgb(0xE5, 0x39, 0x35);
+
+/// A card for a NIP-53 live event: whether it's live, its title, cover
+/// image and participants, and a link to watch it.
+pub fn live_event_card(
+    ui: &mut egui::Ui,
+    note_context: &mut NoteContext,
+    txn: &Transaction,
+    note: &Note,
+    event: &LiveEvent,
+    options: NoteOptions,
+) -> InnerResponse<Option<NoteAction>> {
+    let now = unix_time_secs();
+    let status = event.status_at(now);
+
+    Frame::new()
+        .inner_margin(egui::Margin::same(8))
+        .corner_radius(notedeck::tokens::RADIUS_MD as u8)
+        .stroke(ui.visuals().noninteractive().bg_stroke)
+        .show(ui, |ui| {
+            ui.set_width(ui.available_width());
+            let mut action = None;
+
+            ui.horizontal(|ui| {
+                status_label(ui, note_context, status);
+                if status == LiveStatus::Live {
+                    if let Some(count) = event.current_participants {
+                        secondary_label(ui, "⋅");
+                        secondary_label(
+                            ui,
+                            tr!(
+                                note_context.i18n,
+                                "{count} watching",
+                                "Number of viewers of a live stream",
+                                count = count
+                            ),
+                        );
+                    }
+                } else if let Some(starts) = event.starts.filter(|_| status == LiveStatus::Planned)
+                {
+                    secondary_label(ui, "⋅");
+                    secondary_label(ui, time_format(note_context.i18n, starts));
+                }
+            });
+
+            let title = event
+                .title
+                .filter(|t| !t.trim().is_empty())
+                .unwrap_or(event.identifier);
+            ui.add(
+                Label::new(
+                    RichText::new(title)
+                        .text_style(NotedeckTextStyle::Body.text_style())
+                        .strong(),
+                )
+                .wrap()
+                .selectable(false),
+            );
+
+            if let Some(summary) = event.summary.filter(|s| !s.trim().is_empty()) {
+                ui.add(Label::new(RichText::new(summary).weak()).wrap());
+            }
+
+            let show_cover = !options.contains(NoteOptions::HideMedia)
+                && !options.contains(NoteOptions::Textmode);
+            if let Some(cover) = event.cover_media().filter(|_| show_cover) {
+                ui.add_space(4.0);
+                action = image_carousel(
+                    ui,
+                    note_context.img_cache,
+                    note_context.jobs,
+                    std::slice::from_ref(&cover),
+                    egui::Id::new(("live_event_cover", note.id())),
+                    note_context.i18n,
+                    options,
+                )
+                .map(NoteAction::Media);
+            }
+
+            if let Some(profile_action) = participants(ui, note_context, txn, event) {
+                action = Some(profile_action);
+            }
+
+            if let Some(url) = event.watch_url(now) {
+                let label = if status == LiveStatus::Ended && event.recording.is_some() {
+                    tr!(
+                        note_context.i18n,
+                        "Watch recording",
+                        "Link to the recording of an ended live stream"
+                    )
+                } else {
+                    tr!(
+                        note_context.i18n,
+                        "Watch stream",
+                        "Link to the streaming url of a live event"
+                    )
+                };
+                ui.add(Hyperlink::from_label_and_url(label, url));
+            }
+
+            action
+        })
+}
+
+fn status_label(ui: &mut egui::Ui, note_context: &mut NoteContext, status: LiveStatus) {
+    match status {
+        LiveStatus::Live => {
+            let text = tr!(
+                note_context.i18n,
+                "● LIVE",
+                "Badge of a live event that is streaming now"
+            );
+            ui.add(
+                Label::new(RichText::new(text).size(10.0).strong().color(LIVE_COLOR))
+                    .selectable(false),
+            );
+        }
+        LiveStatus::Planned => {
+            secondary_label(
+                ui,
+                tr!(
+                    note_context.i18n,
+                    "Planned",
+                    "Status of a live event that hasn't started"
+                ),
+            );
+        }
+        LiveStatus::Ended => {
+            secondary_label(
+                ui,
+                tr!(
+                    note_context.i18n,
+                    "Ended",
+                    "Status of a live event that is over"
+                ),
+            );
+        }
+    }
+}
+
+/// The hosts, speakers and other participants, each opening their profile
+fn participants(
+    ui: &mut egui::Ui,
+    note_context: &mut NoteContext,
+    txn: &Transaction,
+    event: &LiveEvent,
+) -> Option<NoteAction> {
+    if event.participants.is_empty() {
+        return None;
+    }
+
+    let ndb = note_context.ndb;
+    let mut action = None;
+
+    ui.add_space(4.0);
+    ui.horizontal_wrapped(|ui| {
+        for participant in event.participants.iter().take(MAX_SHOWN_PARTICIPANTS) {
+            let profile = ndb.get_profile_by_pubkey(txn, participant.pubkey).ok();
+            if profile.is_none() {
+                note_context
+                    .unknown_ids
+                    .add_pubkey_if_missing(ndb, txn, participant.pubkey);
+            }
+
+            let resp = ui
+                .horizontal(|ui| {
+                    ui.add(
+                        &mut ProfilePic::new(
+                            note_context.img_cache,
+                            note_context.jobs,
+                            notedeck::profile::get_profile_url(profile.as_ref()),
+                        )
+                        .size(16.0),
+                    );
+                    secondary_label(ui, get_display_name(profile.as_ref()).name());
+                    if let Some(role) = participant.role {
+                        secondary_label(ui, format!("({role})"));
+                    }
+                })
+                .response
+                .interact(Sense::click())
+                .on_hover_cursor(egui::CursorIcon::PointingHand);
+
+            if resp.clicked() {
+                action = Some(NoteAction::Profile(Pubkey::new(*participant.pubkey)));
+            }
+        }
+
+        let hidden = event
+            .participants
+            .len()
+            .saturating_sub(MAX_SHOWN_PARTICIPANTS);
+        if hidden > 0 {
+            secondary_label(
+                ui,
+                tr!(
+                    note_context.i18n,
+                    "+{count} more",
+                    "Number of live event participants not listed on its card",
+                    count = hidden
+                ),
+            );
+        }
+    });
+
+    action
+}
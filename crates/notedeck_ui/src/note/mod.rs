@@ -1,14 +1,29 @@
+pub mod address_preview;
+pub mod content_warning;
 pub mod contents;
 pub mod context;
+pub mod file_card;
+pub mod live_event_card;
 pub mod media;
+pub mod muted;
 pub mod options;
+pub mod poll_card;
+pub mod reactions;
 pub mod reply_description;
+pub mod unknown_kind;
 
-use crate::{app_images, secondary_label};
-use crate::{widgets::x_button, ProfilePic, ProfilePreview, PulseAlpha, Username};
+use crate::{app_images, context_menu::stationary_arbitrary_menu_button, secondary_label};
+use crate::{
+    username::is_known, widgets::x_button, ProfilePic, ProfilePreview, PulseAlpha, Username,
+};
 
+pub use address_preview::AddressPreview;
+pub use content_warning::content_warning_placeholder;
 pub use contents::{render_note_preview, NoteContents};
-pub use context::NoteContextButton;
+pub use context::{NoteContextButton, NoteMutes};
+pub use file_card::file_card;
+pub use live_event_card::live_event_card;
+pub use muted::muted_placeholder;
 use notedeck::note::{reaction_sent_id, ZapTargetAmount};
 use notedeck::ui::is_narrow;
 use notedeck::Accounts;
@@ -18,15 +33,18 @@ use notedeck::Localization;
 use notedeck::MediaAction;
 use notedeck::{get_current_wallet, MediaJobSender};
 pub use options::NoteOptions;
+pub use poll_card::poll_card;
 pub use reply_description::reply_desc;
+pub use unknown_kind::{has_renderer, note_alt, unknown_kind_card};
 
 use egui::emath::{pos2, Vec2};
 use egui::{Id, Pos2, Rect, Response, Sense};
 use enostr::{KeypairUnowned, NoteId, Pubkey};
-use nostrdb::{Ndb, Note, NoteKey, ProfileRecord, Transaction};
+use nostrdb::{Ndb, Note, NoteKey, NoteReply, ProfileRecord, Transaction};
 use notedeck::{
     note::{NoteAction, NoteContext, ReactAction, ZapAction},
-    tr, AnyZapState, ContextSelection, NoteZapTarget, NoteZapTargetOwned, ZapTarget, Zaps,
+    tr, ActionBarItem, AnyZapState, ContextSelection, Nip51Set, NoteContextSelection,
+    NoteZapTarget, NoteZapTargetOwned, ZapTarget, ZapTargetOwned, Zaps,
 };
 
 pub struct NoteView<'a, 'd> {
@@ -34,6 +52,8 @@ pub struct NoteView<'a, 'd> {
     parent: Option<NoteKey>,
     note: &'a nostrdb::Note<'a>,
     flags: NoteOptions,
+    /// How many quotes deep this note is rendered
+    preview_depth: u8,
 }
 
 pub struct NoteResponse {
@@ -89,6 +109,7 @@ impl<'a, 'd> NoteView<'a, 'd> {
             parent,
             note,
             flags,
+            preview_depth: 0,
         }
     }
 
@@ -97,13 +118,18 @@ impl<'a, 'd> NoteView<'a, 'd> {
             .small_pfp(true)
             .frame(true)
             .wide(true)
-            .note_previews(false)
             .options_button(true)
             .is_preview(true)
             .full_date(false)
             .client_name(false)
     }
 
+    #[inline]
+    pub fn preview_depth(mut self, depth: u8) -> Self {
+        self.preview_depth = depth;
+        self
+    }
+
     pub fn selected_style(self, selected: bool) -> Self {
         self.wide(selected)
             .full_date(selected)
@@ -233,6 +259,7 @@ impl<'a, 'd> NoteView<'a, 'd> {
             ui.put(rect, |ui: &mut egui::Ui| {
                 render_notetime(ui, self.note_context.i18n, self.note.created_at(), false)
             });
+            let known = is_known(self.note_context.accounts, self.note.pubkey());
             let (_id, rect) = ui.allocate_space(egui::vec2(150.0, 20.0));
             ui.allocate_rect(rect, Sense::hover());
             ui.put(rect, |ui: &mut egui::Ui| {
@@ -243,16 +270,15 @@ impl<'a, 'd> NoteView<'a, 'd> {
                         self.note.pubkey(),
                     )
                     .abbreviated(6)
-                    .pk_colored(true),
+                    .pk_colored(true)
+                    .flag_impersonation(!known, self.note_context.nip05_cache),
                 )
             });
 
-            ui.add(&mut NoteContents::new(
-                self.note_context,
-                txn,
-                self.note,
-                self.flags,
-            ));
+            ui.add(
+                &mut NoteContents::new(self.note_context, txn, self.note, self.flags)
+                    .preview_depth(self.preview_depth),
+            );
             //});
         })
         .response
@@ -276,7 +302,7 @@ impl<'a, 'd> NoteView<'a, 'd> {
 
         let pfp_size = self.options().pfp_size();
 
-        match profile
+        let pfp_resp = match profile
             .as_ref()
             .ok()
             .and_then(|p| p.record().profile()?.picture())
@@ -299,10 +325,55 @@ impl<'a, 'd> NoteView<'a, 'd> {
                 self.note_context.jobs,
                 pfp_size,
             ),
+        };
+
+        if pfp_resp.response.hovered() {
+            self.note_context
+                .unknown_ids
+                .refresh_profile_if_stale(self.note.pubkey(), profile.as_ref().ok());
         }
+
+        pfp_resp
     }
 
     pub fn show(&mut self, ui: &mut egui::Ui) -> NoteResponse {
+        if let Some(note_key) = self.note.key() {
+            if !self
+                .note_context
+                .note_cache
+                .signature_valid(note_key, self.note)
+            {
+                return NoteResponse::new(ui.weak(tr!(
+                    self.note_context.i18n,
+                    "This note's signature doesn't check out, so it isn't shown",
+                    "Shown in place of a note with a forged signature"
+                )));
+            }
+
+            if let Some(txn) = self.note.txn() {
+                if self.note_context.note_cache.deleted(
+                    self.note_context.ndb,
+                    txn,
+                    note_key,
+                    self.note,
+                ) {
+                    return NoteResponse::new(ui.weak(tr!(
+                        self.note_context.i18n,
+                        "Deleted by author",
+                        "Shown in place of a note its author asked to delete"
+                    )));
+                }
+            }
+        }
+
+        if notedeck::is_expired(self.note, notedeck::unix_time_secs()) {
+            return NoteResponse::new(ui.weak(tr!(
+                self.note_context.i18n,
+                "This note has expired",
+                "Shown in place of a note whose NIP-40 expiration time has passed"
+            )));
+        }
+
         if !self.flags.contains(NoteOptions::TrustMedia) {
             let acc = self.note_context.accounts.get_selected_account();
             if self.note.pubkey() == acc.key.pubkey.bytes()
@@ -354,13 +425,23 @@ impl<'a, 'd> NoteView<'a, 'd> {
         let horiz_resp = ui
             .horizontal_wrapped(|ui| {
                 ui.spacing_mut().item_spacing.x = if is_narrow(ui.ctx()) { 1.0 } else { 2.0 };
+                let known = is_known(note_context.accounts, note.pubkey());
                 let response = ui.add(
                     Username::new(note_context.i18n, profile.as_ref().ok(), note.pubkey())
-                        .abbreviated(20),
+                        .abbreviated(20)
+                        .flag_impersonation(!known, note_context.nip05_cache),
                 );
-                if !flags.contains(NoteOptions::FullCreatedDate) {
-                    return render_notetime(ui, note_context.i18n, note.created_at(), true);
+                let response = if flags.contains(NoteOptions::FullCreatedDate) {
+                    response
+                } else {
+                    render_notetime(ui, note_context.i18n, note.created_at(), true)
+                };
+
+                if let Some(expiration) = notedeck::expiring_soon(note, notedeck::unix_time_secs())
+                {
+                    render_expiration_countdown(ui, note_context.i18n, expiration);
                 }
+
                 response
             })
             .response;
@@ -458,13 +539,15 @@ impl<'a, 'd> NoteView<'a, 'd> {
                 });
             }
 
-            let mut contents = NoteContents::new(self.note_context, txn, self.note, self.flags);
+            let mut contents = NoteContents::new(self.note_context, txn, self.note, self.flags)
+                .preview_depth(self.preview_depth);
 
             ui.add(&mut contents);
 
             note_action = contents.action.or(note_action);
 
             if self.options().contains(NoteOptions::ActionBar) {
+                let reacted = self.reactions_ui(ui, txn, note_key);
                 note_action = ui
                     .horizontal_wrapped(|ui| {
                         // NOTE(jb55): without this we get a weird artifact where
@@ -487,6 +570,15 @@ impl<'a, 'd> NoteView<'a, 'd> {
                                 })
                             });
 
+                        let ndb = self.note_context.ndb;
+                        let account_pk = *self.note_context.accounts.selected_account_pubkey();
+                        let can_sign = self
+                            .note_context
+                            .accounts
+                            .get_selected_account()
+                            .key
+                            .secret_key
+                            .is_some();
                         actionbar_ui(
                             ui,
                             counts,
@@ -496,9 +588,16 @@ impl<'a, 'd> NoteView<'a, 'd> {
                                 self.note_context.zaps,
                             ),
                             self.note,
-                            self.note_context.accounts.selected_account_pubkey(),
+                            &account_pk,
+                            reacted,
                             note_key,
+                            self.note_context.action_bar.iter().copied(),
+                            can_sign,
                             self.note_context.i18n,
+                            self.note_context.img_cache,
+                            self.note_context.jobs,
+                            || notedeck::emoji::user_emojis(ndb, txn, &account_pk),
+                            || notedeck::bookmarks::bookmark_sets(ndb, txn, &account_pk),
                         )
                     })
                     .inner
@@ -562,12 +661,14 @@ impl<'a, 'd> NoteView<'a, 'd> {
                     });
                 }
 
-                let mut contents = NoteContents::new(self.note_context, txn, self.note, self.flags);
+                let mut contents = NoteContents::new(self.note_context, txn, self.note, self.flags)
+                    .preview_depth(self.preview_depth);
                 ui.add(&mut contents);
 
                 note_action = contents.action.or(note_action);
 
                 if self.options().contains(NoteOptions::ActionBar) {
+                    let reacted = self.reactions_ui(ui, txn, note_key);
                     let counts = self
                         .note_context
                         .ndb
@@ -585,6 +686,15 @@ impl<'a, 'd> NoteView<'a, 'd> {
 
                     note_action = ui
                         .horizontal_wrapped(|ui| {
+                            let ndb = self.note_context.ndb;
+                            let account_pk = *self.note_context.accounts.selected_account_pubkey();
+                            let can_sign = self
+                                .note_context
+                                .accounts
+                                .get_selected_account()
+                                .key
+                                .secret_key
+                                .is_some();
                             actionbar_ui(
                                 ui,
                                 counts,
@@ -594,9 +704,16 @@ impl<'a, 'd> NoteView<'a, 'd> {
                                     self.note_context.zaps,
                                 ),
                                 self.note,
-                                self.note_context.accounts.selected_account_pubkey(),
+                                &account_pk,
+                                reacted,
                                 note_key,
+                                self.note_context.action_bar.iter().copied(),
+                                can_sign,
                                 self.note_context.i18n,
+                                self.note_context.img_cache,
+                                self.note_context.jobs,
+                                || notedeck::emoji::user_emojis(ndb, txn, &account_pk),
+                                || notedeck::bookmarks::bookmark_sets(ndb, txn, &account_pk),
                             )
                         })
                         .inner
@@ -612,6 +729,26 @@ impl<'a, 'd> NoteView<'a, 'd> {
         })
     }
 
+    /// Shows what people reacted with, when the action bar has a react
+    /// button. Returns whether the selected account reacted.
+    fn reactions_ui(&mut self, ui: &mut egui::Ui, txn: &Transaction, note_key: NoteKey) -> bool {
+        if !self.note_context.action_bar.contains(&ActionBarItem::React) {
+            return false;
+        }
+
+        let account_pk = *self.note_context.accounts.selected_account_pubkey();
+        let summary =
+            self.note_context
+                .note_cache
+                .reactions(self.note_context.ndb, txn, note_key, self.note);
+
+        if !summary.is_empty() {
+            reactions::reaction_summary_ui(ui, &summary.counts);
+        }
+
+        summary.reacted(&account_pk)
+    }
+
     #[profiling::function]
     fn show_standard(&mut self, ui: &mut egui::Ui) -> NoteResponse {
         let note_key = self.note.key().expect("todo: support non-db notes");
@@ -651,19 +788,34 @@ impl<'a, 'd> NoteView<'a, 'd> {
                 .key
                 .secret_key
                 .is_some();
-            let is_muted = self
-                .note_context
-                .accounts
-                .mute()
-                .is_pk_muted(self.note.pubkey());
+            let muted = self.note_context.accounts.mute();
+            let thread = NoteReply::new(self.note.tags())
+                .root()
+                .map_or(*self.note.id(), |root| *root.id);
+            let mutes = NoteMutes {
+                user: muted.is_pk_muted(self.note.pubkey()),
+                thread: muted.threads.contains(&thread),
+                hashtags: notedeck::note_hashtags(self.note)
+                    .into_iter()
+                    .map(|hashtag| {
+                        let muted = muted.hashtags.contains(&hashtag);
+                        (hashtag, muted)
+                    })
+                    .collect(),
+            };
             let note_id = NoteId::new(*self.note.id());
+            let ndb = self.note_context.ndb;
+            let account_pk = *self.note_context.accounts.selected_account_pubkey();
             if let Some(action) = NoteContextButton::menu(
                 ui,
                 self.note_context.i18n,
                 resp.clone(),
                 note_id,
                 can_sign,
-                is_muted,
+                self.note.pubkey() == account_pk.bytes(),
+                mutes,
+                self.flags.contains(NoteOptions::OpenWithTemplate),
+                || notedeck::bookmarks::bookmark_sets(ndb, txn, &account_pk),
             ) {
                 note_action = Some(NoteAction::Context(ContextSelection { note_key, action }));
             }
@@ -875,10 +1027,10 @@ fn zap_actionbar_button(
 
     let zap_state = zaps.any_zap_state_for(cur_acc.pubkey.bytes(), zap_target);
 
-    let target = NoteZapTargetOwned {
+    let target = ZapTargetOwned::Note(NoteZapTargetOwned {
         note_id: NoteId::new(*note_id),
         zap_recipient: Pubkey::new(*note_pubkey),
-    };
+    });
 
     cur_acc.secret_key.as_ref()?;
 
@@ -886,7 +1038,7 @@ fn zap_actionbar_button(
         Ok(any_zap_state) => {
             let zap_resp = ui.add(zap_button(i18n, any_zap_state, note_id));
 
-            if zap_resp.secondary_clicked() {
+            if zap_resp.secondary_clicked() || zap_resp.long_touched() {
                 action = Some(NoteAction::Zap(ZapAction::CustomizeAmount(target.clone())));
             }
 
@@ -894,6 +1046,7 @@ fn zap_actionbar_button(
                 action = Some(NoteAction::Zap(ZapAction::Send(ZapTargetAmount {
                     target,
                     specified_msats: None,
+                    comment: None,
                 })));
             }
 
@@ -930,84 +1083,163 @@ fn is_root_note(note: &Note) -> bool {
 }
 
 #[profiling::function]
+#[allow(clippy::too_many_arguments)]
 fn actionbar_ui(
     ui: &mut egui::Ui,
     counts: Option<nostrdb::CountsEntry<'_>>,
     zapper: Option<Zapper<'_>>,
     note: &Note,
     current_user_pubkey: &Pubkey,
+    reacted: bool,
     note_key: NoteKey,
+    items: impl Iterator<Item = ActionBarItem>,
+    can_sign: bool,
     i18n: &mut Localization,
+    img_cache: &mut Images,
+    jobs: &MediaJobSender,
+    custom_emojis: impl FnOnce() -> Vec<notedeck::emoji::CustomEmoji>,
+    bookmark_sets: impl FnOnce() -> Vec<Nip51Set>,
 ) -> Option<NoteAction> {
     let mut action = None;
     let spacing = notedeck::tokens::SPACING_XL;
+    let note_id = NoteId::new(*note.id());
+
+    let can_zap = zapper
+        .as_ref()
+        .is_some_and(|zapper| zapper.cur_acc.secret_key.is_some());
+    let mut zapper = zapper;
+    let mut custom_emojis = Some(custom_emojis);
+    let mut bookmark_sets = Some(bookmark_sets);
 
     ui.spacing_mut().item_spacing.x = 2.0;
     ui.set_min_height(26.0);
 
-    let reply_resp =
-        reply_button(ui, i18n, note_key).on_hover_cursor(egui::CursorIcon::PointingHand);
-
-    if let Some(c) = &counts {
-        let count = if is_root_note(note) {
-            c.thread_replies()
-        } else {
-            c.direct_replies() as u32
-        };
+    // skip buttons we can't act on so they don't leave gaps
+    let items = items.filter(|item| match item {
+        ActionBarItem::Zap => can_zap,
+        ActionBarItem::Bookmark => can_sign,
+        _ => true,
+    });
 
-        if count > 0 {
-            //ui.weak(format!("{}", count));
-            crate::anim::rolling_number(ui, egui::Id::new((note_key, "replies")), count);
+    for (i, item) in items.enumerate() {
+        if i > 0 {
+            ui.add_space(spacing);
         }
-    }
 
-    ui.add_space(spacing);
+        let item_action = match item {
+            ActionBarItem::Reply => {
+                let reply_resp = reply_button(ui, i18n, note_key)
+                    .on_hover_cursor(egui::CursorIcon::PointingHand);
 
-    let filled = ui
-        .ctx()
-        .data(|d| d.get_temp(reaction_sent_id(current_user_pubkey, note.id())))
-        == Some(true);
+                if let Some(c) = &counts {
+                    let count = if is_root_note(note) {
+                        c.thread_replies()
+                    } else {
+                        c.direct_replies() as u32
+                    };
 
-    let like_resp =
-        like_button(ui, i18n, note_key, filled).on_hover_cursor(egui::CursorIcon::PointingHand);
+                    if count > 0 {
+                        crate::anim::rolling_number(
+                            ui,
+                            egui::Id::new((note_key, "replies")),
+                            count,
+                        );
+                    }
+                }
 
-    if let Some(c) = &counts {
-        let count = c.reactions();
-        if count > 0 {
-            crate::anim::rolling_number(ui, egui::Id::new((note_key, "likes")), count);
-        }
-    }
+                reply_resp.clicked().then_some(NoteAction::Reply(note_id))
+            }
 
-    ui.add_space(spacing);
+            ActionBarItem::React => {
+                let filled = reacted
+                    || ui
+                        .ctx()
+                        .data(|d| d.get_temp(reaction_sent_id(current_user_pubkey, note.id())))
+                        == Some(true);
 
-    let quote_resp =
-        quote_repost_button(ui, i18n, note_key).on_hover_cursor(egui::CursorIcon::PointingHand);
+                let like_resp = like_button(ui, i18n, note_key, filled)
+                    .on_hover_cursor(egui::CursorIcon::PointingHand);
 
-    if let Some(c) = &counts {
-        let count = c.quotes() + c.reposts();
-        if count > 0 {
-            crate::anim::rolling_number(ui, egui::Id::new((note_key, "quotes")), count as u32);
-        }
-    }
+                if let Some(c) = &counts {
+                    let count = c.reactions();
+                    if count > 0 {
+                        crate::anim::rolling_number(ui, egui::Id::new((note_key, "likes")), count);
+                    }
+                }
 
-    ui.add_space(spacing);
+                let picked = reactions::reaction_picker(
+                    ui,
+                    i18n,
+                    &like_resp,
+                    note_id,
+                    img_cache,
+                    jobs,
+                    || custom_emojis.take().map(|f| f()).unwrap_or_default(),
+                );
 
-    if reply_resp.clicked() {
-        action = Some(NoteAction::Reply(NoteId::new(*note.id())));
-    }
+                picked.map(NoteAction::React).or_else(|| {
+                    like_resp
+                        .clicked()
+                        .then(|| NoteAction::React(ReactAction::new(note_id, "🤙🏻")))
+                })
+            }
 
-    if like_resp.clicked() {
-        action = Some(NoteAction::React(ReactAction::new(
-            NoteId::new(*note.id()),
-            "🤙🏻",
-        )));
-    }
+            ActionBarItem::Repost => {
+                let quote_resp = quote_repost_button(ui, i18n, note_key)
+                    .on_hover_cursor(egui::CursorIcon::PointingHand);
 
-    if quote_resp.clicked() {
-        action = Some(NoteAction::Repost(NoteId::new(*note.id())));
-    }
+                if let Some(c) = &counts {
+                    let count = c.quotes() + c.reposts();
+                    if count > 0 {
+                        crate::anim::rolling_number(
+                            ui,
+                            egui::Id::new((note_key, "quotes")),
+                            count as u32,
+                        );
+                    }
+                }
+
+                quote_resp.clicked().then_some(NoteAction::Repost(note_id))
+            }
+
+            ActionBarItem::Zap => {
+                zap_actionbar_button(ui, note.id(), note.pubkey(), zapper.take(), i18n)
+            }
+
+            ActionBarItem::Bookmark => {
+                let bookmark_resp = bookmark_button(ui, i18n, note_key)
+                    .on_hover_cursor(egui::CursorIcon::PointingHand);
+
+                stationary_arbitrary_menu_button(ui, bookmark_resp, |ui| {
+                    ui.set_max_width(200.0);
+                    let sets = bookmark_sets.take().map(|f| f()).unwrap_or_default();
+                    let target = context::bookmark_menu(ui, i18n, &note_id, &sets);
+                    if target.is_some() {
+                        ui.close_menu();
+                    }
+                    target
+                })
+                .inner
+                .flatten()
+                .map(|target| {
+                    NoteAction::Context(ContextSelection {
+                        note_key,
+                        action: NoteContextSelection::Bookmark(target),
+                    })
+                })
+            }
+
+            ActionBarItem::Share => share_button(ui, i18n, note_key)
+                .on_hover_cursor(egui::CursorIcon::PointingHand)
+                .clicked()
+                .then_some(NoteAction::Context(ContextSelection {
+                    note_key,
+                    action: NoteContextSelection::CopyNeventLink,
+                })),
+        };
 
-    action = zap_actionbar_button(ui, note.id(), note.pubkey(), zapper, i18n).or(action);
+        action = item_action.or(action);
+    }
 
     action
 }
@@ -1032,6 +1264,18 @@ fn render_notetime(
     }
 }
 
+/// A "⏳ 3h" badge on notes whose NIP-40 expiration is coming up
+fn render_expiration_countdown(ui: &mut egui::Ui, i18n: &mut Localization, expiration: u64) {
+    let remaining = notedeck::time_until(i18n, expiration);
+    let expires_at = notedeck::time_format(i18n, expiration);
+    secondary_label(ui, format!(" ⋅ ⏳ {remaining}")).on_hover_text(tr!(
+        i18n,
+        "Expires at {time}",
+        "Hover text on the countdown of a note that will expire, with the expiration time",
+        time = expires_at
+    ));
+}
+
 fn reply_button(ui: &mut egui::Ui, i18n: &mut Localization, note_key: NoteKey) -> egui::Response {
     let img = if ui.style().visuals.dark_mode {
         app_images::reply_dark_image()
@@ -1120,6 +1364,51 @@ fn quote_repost_button(
     resp.union(put_resp)
 }
 
+fn bookmark_button(
+    ui: &mut egui::Ui,
+    i18n: &mut Localization,
+    note_key: NoteKey,
+) -> egui::Response {
+    let (rect, size, resp) =
+        crate::anim::hover_expand_small(ui, ui.id().with(("bookmark_anim", note_key)));
+
+    // align rect to note contents
+    let expand_size = 5.0; // from hover_expand_small
+    let rect = rect.translate(egui::vec2(-(expand_size / 2.0), 0.0));
+
+    let img = app_images::bookmark_image(ui.visuals().dark_mode);
+    let put_resp = ui.put(rect, img.max_width(size)).on_hover_text(tr!(
+        i18n,
+        "Bookmark this note",
+        "Hover text for bookmark button"
+    ));
+
+    resp.union(put_resp)
+}
+
+fn share_button(ui: &mut egui::Ui, i18n: &mut Localization, note_key: NoteKey) -> egui::Response {
+    let img = if ui.visuals().dark_mode {
+        app_images::link_dark_image()
+    } else {
+        app_images::link_light_image()
+    };
+
+    let (rect, size, resp) =
+        crate::anim::hover_expand_small(ui, ui.id().with(("share_anim", note_key)));
+
+    // align rect to note contents
+    let expand_size = 5.0; // from hover_expand_small
+    let rect = rect.translate(egui::vec2(-(expand_size / 2.0), 0.0));
+
+    let put_resp = ui.put(rect, img.max_width(size)).on_hover_text(tr!(
+        i18n,
+        "Copy a link to this note",
+        "Hover text for share button"
+    ));
+
+    resp.union(put_resp)
+}
+
 fn zap_button<'a>(
     i18n: &'a mut Localization,
     state: AnyZapState,
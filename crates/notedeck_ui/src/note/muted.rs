@@ -0,0 +1,35 @@
+use egui::RichText;
+use notedeck::{tr, Localization};
+
+/// Stands in for a note muted with [`notedeck::MuteMode::Collapse`].
+/// Returns whether the note is still collapsed, once the user expands it
+/// the caller shows the note as usual.
+pub fn muted_placeholder(ui: &mut egui::Ui, i18n: &mut Localization, note_id: &[u8; 32]) -> bool {
+    let expanded_id = egui::Id::new(("mute-expanded", note_id));
+    if ui.data(|d| d.get_temp::<bool>(expanded_id).unwrap_or(false)) {
+        return false;
+    }
+
+    crate::padding(8.0, ui, |ui| {
+        ui.horizontal(|ui| {
+            ui.label(
+                RichText::new(tr!(
+                    i18n,
+                    "Muted content",
+                    "Placeholder shown instead of a note from a collapsed mute"
+                ))
+                .weak(),
+            );
+
+            if ui
+                .small_button(tr!(i18n, "Show", "Button to reveal a collapsed muted note"))
+                .clicked()
+            {
+                ui.data_mut(|d| d.insert_temp(expanded_id, true));
+            }
+        });
+    });
+    crate::hline(ui);
+
+    true
+}
@@ -1,5 +1,6 @@
 use crate::ProfilePic;
 use bitflags::bitflags;
+use notedeck::ContentWarningMode;
 
 bitflags! {
     // Attributes can be applied to flags types
@@ -47,19 +48,75 @@ bitflags! {
 
         /// There is enough trust to show media in this note
         const TrustMedia = 1 << 20;
+
+        /// Don't strip EXIF/XMP metadata from uploaded images
+        const KeepMediaMetadata = 1 << 21;
+
+        /// Two bits holding how many levels of quoted notes are previewed
+        /// inline. Use [`NoteOptions::max_preview_depth`] to read it.
+        const MaxPreviewDepth = 0b11 << 22;
+
+        /// An open with link template is set up, so offer it in menus
+        const OpenWithTemplate = 1 << 42;
+
+        /// Don't show NIP-58 badges on profiles
+        const HideBadges = 1 << 43;
+
+        /// Two bits holding what the account does with notes that have a
+        /// content warning. Use [`NoteOptions::content_warning_mode`] to
+        /// read it.
+        const ContentWarnings = 0b11 << 44;
     }
 }
 
 impl Default for NoteOptions {
     fn default() -> NoteOptions {
-        NoteOptions::OptionsButton
+        let mut options = NoteOptions::OptionsButton
             | NoteOptions::HasNotePreviews
             | NoteOptions::ActionBar
-            | NoteOptions::Truncate
+            | NoteOptions::Truncate;
+        options.set_max_preview_depth(notedeck::DEFAULT_MAX_PREVIEW_DEPTH);
+        options
     }
 }
 
 impl NoteOptions {
+    const PREVIEW_DEPTH_SHIFT: u32 = 22;
+    const CONTENT_WARNINGS_SHIFT: u32 = 44;
+
+    /// The deepest quoted note rendered inline. Anything past it is
+    /// collapsed behind a "show quoted note" button.
+    pub fn max_preview_depth(&self) -> u8 {
+        ((self.bits() & NoteOptions::MaxPreviewDepth.bits()) >> Self::PREVIEW_DEPTH_SHIFT) as u8
+    }
+
+    /// Clamped to 3, the most the flag can hold
+    pub fn set_max_preview_depth(&mut self, depth: u8) {
+        let depth = u64::from(depth.min(3)) << Self::PREVIEW_DEPTH_SHIFT;
+        self.remove(NoteOptions::MaxPreviewDepth);
+        self.insert(NoteOptions::from_bits_retain(depth));
+    }
+
+    pub fn content_warning_mode(&self) -> ContentWarningMode {
+        let bits =
+            (self.bits() & NoteOptions::ContentWarnings.bits()) >> Self::CONTENT_WARNINGS_SHIFT;
+        ContentWarningMode::ALL
+            .get(bits as usize)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn set_content_warning_mode(&mut self, mode: ContentWarningMode) {
+        let index = ContentWarningMode::ALL
+            .iter()
+            .position(|m| *m == mode)
+            .unwrap_or(0) as u64;
+        self.remove(NoteOptions::ContentWarnings);
+        self.insert(NoteOptions::from_bits_retain(
+            index << Self::CONTENT_WARNINGS_SHIFT,
+        ));
+    }
+
     pub fn new(is_universe_timeline: bool) -> Self {
         let mut options = NoteOptions::default();
         options.set(NoteOptions::HideMedia, is_universe_timeline);
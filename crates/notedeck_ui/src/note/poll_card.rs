@@ -0,0 +1,189 @@
+use egui::{vec2, Align2, Button, FontId, Sense};
+use enostr::NoteId;
+use nostrdb::{Note, Transaction};
+use notedeck::{
+    time_format, tr, unix_time_secs, NoteAction, NoteContext, Poll, PollType, PollVote,
+};
+
+use crate::secondary_label;
+
+const OPTION_HEIGHT: f32 = 28.0;
+
+/// The options of a NIP-88 poll. Until we've voted, and while the poll is
+/// open, they are buttons to vote with. After that they show the results.
+pub fn poll_card(
+    ui: &mut egui::Ui,
+    note_context: &mut NoteContext,
+    txn: &Transaction,
+    note: &Note,
+    poll: &Poll,
+) -> Option<NoteAction> {
+    let note_key = note.key()?;
+    let tally = note_context
+        .note_cache
+        .poll_tally(note_context.ndb, txn, note_key, note, poll)
+        .clone();
+
+    let now = unix_time_secs();
+    let closed = poll.is_closed(now);
+    let ballot = tally.ballot(note_context.accounts.selected_account_pubkey());
+    let mut action = None;
+
+    ui.add_space(4.0);
+    if ballot.is_some() || closed {
+        for (i, option) in poll.options.iter().enumerate() {
+            let voted_for = ballot.is_some_and(|b| b.contains(&i));
+            result_bar(ui, option.label, tally.share(i), voted_for);
+        }
+    } else {
+        action = voting_options(ui, note_context, note, poll);
+    }
+
+    ui.horizontal(|ui| {
+        secondary_label(
+            ui,
+            tr!(
+                note_context.i18n,
+                "{count} votes",
+                "Number of people who voted on a poll",
+                count = tally.voters()
+            ),
+        );
+
+        if poll.poll_type == PollType::MultipleChoice {
+            secondary_label(ui, "⋅");
+            secondary_label(
+                ui,
+                tr!(
+                    note_context.i18n,
+                    "Multiple choice",
+                    "Label of a poll that takes more than one answer"
+                ),
+            );
+        }
+
+        if closed {
+            secondary_label(ui, "⋅");
+            secondary_label(
+                ui,
+                tr!(
+                    note_context.i18n,
+                    "Final results",
+                    "Label of a poll that has ended"
+                ),
+            );
+        } else if let Some(ends_at) = poll.ends_at {
+            secondary_label(ui, "⋅");
+            secondary_label(
+                ui,
+                tr!(
+                    note_context.i18n,
+                    "Ends {time}",
+                    "When an open poll stops taking votes",
+                    time = time_format(note_context.i18n, ends_at)
+                ),
+            );
+        }
+    });
+
+    action
+}
+
+fn voting_options(
+    ui: &mut egui::Ui,
+    note_context: &mut NoteContext,
+    note: &Note,
+    poll: &Poll,
+) -> Option<NoteAction> {
+    let poll_id = NoteId::new(*note.id());
+    let width = ui.available_width();
+
+    if poll.poll_type == PollType::SingleChoice {
+        let mut action = None;
+        for option in &poll.options {
+            let button = Button::new(option.label).min_size(vec2(width, OPTION_HEIGHT));
+            if ui.add(button).clicked() {
+                action = Some(NoteAction::Vote(PollVote {
+                    poll_id,
+                    options: vec![option.id.to_owned()],
+                }));
+            }
+        }
+        return action;
+    }
+
+    let selection_id = egui::Id::new(("poll_selection", note.id()));
+    let mut selected: Vec<bool> = ui
+        .data_mut(|d| d.get_temp(selection_id))
+        .unwrap_or_else(|| vec![false; poll.options.len()]);
+    selected.resize(poll.options.len(), false);
+
+    for (option, checked) in poll.options.iter().zip(selected.iter_mut()) {
+        ui.checkbox(checked, option.label);
+    }
+
+    let vote = ui
+        .add_enabled(
+            selected.contains(&true),
+            Button::new(tr!(
+                note_context.i18n,
+                "Vote",
+                "Button to send the answers picked on a multiple choice poll"
+            )),
+        )
+        .clicked();
+
+    if !vote {
+        ui.data_mut(|d| d.insert_temp(selection_id, selected));
+        return None;
+    }
+
+    ui.data_mut(|d| d.remove::<Vec<bool>>(selection_id));
+    Some(NoteAction::Vote(PollVote {
+        poll_id,
+        options: poll
+            .options
+            .iter()
+            .zip(selected)
+            .filter(|(_, checked)| *checked)
+            .map(|(option, _)| option.id.to_owned())
+            .collect(),
+    }))
+}
+
+/// An option with the share of voters who picked it drawn behind it
+fn result_bar(ui: &mut egui::Ui, label: &str, share: f32, voted_for: bool) {
+    let (rect, _) =
+        ui.allocate_exact_size(vec2(ui.available_width(), OPTION_HEIGHT), Sense::hover());
+
+    let visuals = ui.visuals();
+    let painter = ui.painter();
+    painter.rect_filled(rect, 4.0, visuals.faint_bg_color);
+
+    let mut filled = rect;
+    filled.set_width(rect.width() * share.clamp(0.0, 1.0));
+    painter.rect_filled(filled, 4.0, visuals.selection.bg_fill.gamma_multiply(0.5));
+
+    let label = if voted_for {
+        format!("✓ {label}")
+    } else {
+        label.to_owned()
+    };
+    let font = FontId::proportional(14.0);
+    painter.text(
+        rect.left_center() + vec2(8.0, 0.0),
+        Align2::LEFT_CENTER,
+        label,
+        font.clone(),
+        visuals.text_color(),
+    );
+    painter.text(
+        rect.right_center() - vec2(8.0, 0.0),
+        Align2::RIGHT_CENTER,
+        format!("{:.0}%", share * 100.0),
+        font,
+        visuals.weak_text_color(),
+    );
+
+    ui.add_space(2.0);
+}
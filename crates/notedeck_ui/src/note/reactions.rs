@@ -0,0 +1,169 @@
+use egui::{PopupCloseBehavior, RichText, Sense};
+use enostr::NoteId;
+use notedeck::{emoji::CustomEmoji, note::ReactAction, tr, Images, Localization, MediaJobSender};
+
+use crate::ProfilePic;
+
+/// Emoji offered before the user has reacted with anything else
+const QUICK_REACTIONS: [&str; 8] = ["🤙🏻", "❤️", "😂", "🔥", "👀", "🫡", "🙏", "💯"];
+
+/// How many recently used reactions are remembered
+const MAX_RECENT: usize = 8;
+
+const CUSTOM_EMOJI_SIZE: f32 = 20.0;
+
+/// Distinct reactions listed under a note before the rest are left out
+const MAX_SUMMARY_REACTIONS: usize = 6;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RecentReaction {
+    content: String,
+    emoji: Option<CustomEmoji>,
+}
+
+fn recent_id() -> egui::Id {
+    egui::Id::new("recent-reactions")
+}
+
+fn recent_reactions(ctx: &egui::Context) -> Vec<RecentReaction> {
+    ctx.data(|d| d.get_temp(recent_id())).unwrap_or_default()
+}
+
+fn remember_reaction(ctx: &egui::Context, reaction: &ReactAction) {
+    let used = RecentReaction {
+        content: reaction.content.clone(),
+        emoji: reaction.emoji.clone(),
+    };
+
+    let mut recent = recent_reactions(ctx);
+    recent.retain(|r| *r != used);
+    recent.insert(0, used);
+    recent.truncate(MAX_RECENT);
+
+    ctx.data_mut(|d| d.insert_temp(recent_id(), recent));
+}
+
+/// Compact reaction picker, opened with a right click or long press on the
+/// like button. `custom_emojis` is only called while the picker is open.
+pub fn reaction_picker(
+    ui: &mut egui::Ui,
+    i18n: &mut Localization,
+    like_resp: &egui::Response,
+    note_id: NoteId,
+    img_cache: &mut Images,
+    jobs: &MediaJobSender,
+    custom_emojis: impl FnOnce() -> Vec<CustomEmoji>,
+) -> Option<ReactAction> {
+    let popup_id = like_resp.id.with("reaction_picker");
+
+    if like_resp.secondary_clicked() || like_resp.long_touched() {
+        ui.memory_mut(|m| m.open_popup(popup_id));
+    }
+
+    let picked = egui::popup_below_widget(
+        ui,
+        popup_id,
+        like_resp,
+        PopupCloseBehavior::CloseOnClickOutside,
+        |ui| {
+            ui.set_max_width(240.0);
+            let mut picked = None;
+
+            let recent = recent_reactions(ui.ctx());
+            if !recent.is_empty() {
+                ui.label(
+                    RichText::new(tr!(
+                        i18n,
+                        "Recently used",
+                        "Heading for recently used reactions in the reaction picker"
+                    ))
+                    .small()
+                    .weak(),
+                );
+                ui.horizontal_wrapped(|ui| {
+                    for reaction in &recent {
+                        let clicked = match &reaction.emoji {
+                            Some(emoji) => custom_emoji_button(ui, img_cache, jobs, emoji),
+                            None => unicode_button(ui, &reaction.content),
+                        };
+                        if clicked {
+                            picked = Some(match &reaction.emoji {
+                                Some(emoji) => ReactAction::custom(note_id, emoji.clone()),
+                                None => ReactAction::new(note_id, reaction.content.clone()),
+                            });
+                        }
+                    }
+                });
+                ui.separator();
+            }
+
+            ui.horizontal_wrapped(|ui| {
+                for content in QUICK_REACTIONS {
+                    if unicode_button(ui, content) {
+                        picked = Some(ReactAction::new(note_id, content));
+                    }
+                }
+            });
+
+            let emojis = custom_emojis();
+            if !emojis.is_empty() {
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .max_height(120.0)
+                    .show(ui, |ui| {
+                        ui.horizontal_wrapped(|ui| {
+                            for emoji in emojis {
+                                if custom_emoji_button(ui, img_cache, jobs, &emoji) {
+                                    picked = Some(ReactAction::custom(note_id, emoji));
+                                }
+                            }
+                        });
+                    });
+            }
+
+            picked
+        },
+    )
+    .flatten();
+
+    if let Some(reaction) = &picked {
+        remember_reaction(ui.ctx(), reaction);
+        ui.memory_mut(|m| m.close_popup());
+    }
+
+    picked
+}
+
+fn unicode_button(ui: &mut egui::Ui, content: &str) -> bool {
+    ui.add(egui::Button::new(RichText::new(content).size(18.0)).frame(false))
+        .on_hover_cursor(egui::CursorIcon::PointingHand)
+        .clicked()
+}
+
+fn custom_emoji_button(
+    ui: &mut egui::Ui,
+    img_cache: &mut Images,
+    jobs: &MediaJobSender,
+    emoji: &CustomEmoji,
+) -> bool {
+    ui.add(
+        &mut ProfilePic::new(img_cache, jobs, &emoji.url)
+            .size(CUSTOM_EMOJI_SIZE)
+            .border(egui::Stroke::NONE)
+            .sense(Sense::click()),
+    )
+    .on_hover_text(emoji.content())
+    .on_hover_cursor(egui::CursorIcon::PointingHand)
+    .clicked()
+}
+
+/// The most used reactions to a note and their counts, shown under its
+/// contents
+pub fn reaction_summary_ui(ui: &mut egui::Ui, counts: &[(String, u32)]) {
+    ui.horizontal_wrapped(|ui| {
+        ui.spacing_mut().item_spacing.x = 8.0;
+        for (content, count) in counts.iter().take(MAX_SUMMARY_REACTIONS) {
+            ui.label(RichText::new(format!("{content} {count}")).small());
+        }
+    });
+}
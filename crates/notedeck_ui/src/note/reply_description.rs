@@ -131,6 +131,11 @@ fn render_text_segments(
                 )
                 .size(size)
                 .selectable(selectable)
+                .flag_impersonation(
+                    note_context.accounts,
+                    note_context.i18n,
+                    note_context.nip05_cache,
+                )
                 .show(ui);
 
                 if action.is_some() {
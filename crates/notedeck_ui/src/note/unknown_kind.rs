@@ -0,0 +1,222 @@
+use egui::{Frame, Label, RichText, ScrollArea, Sense};
+use enostr::NoteId;
+use nostrdb::{NdbStrVariant, Note, Tag, Transaction};
+use notedeck::{
+    app_handlers, note::note_nip19_event_bech, tr, NoteAction, NoteContext, NotedeckTextStyle,
+    FILE_METADATA_KIND, LIVE_EVENT_KIND, POLL_KIND,
+};
+
+use crate::{note::NoteResponse, secondary_label};
+
+/// Tags listed before the rest are summarized
+const MAX_TAGS_SHOWN: usize = 50;
+
+/// Kinds whose contents we know how to show. Everything else gets an
+/// [`unknown_kind_card`].
+pub fn has_renderer(kind: u32) -> bool {
+    matches!(
+        kind,
+        // text notes, group chat, threads, DMs, comments and articles
+        1 | 9 | 11 | 14 | 1111 | 30023 | FILE_METADATA_KIND | LIVE_EVENT_KIND | POLL_KIND
+    )
+}
+
+/// The NIP-31 `alt` description of a note, which clients attach to custom
+/// kinds so others can say what the note is without understanding it.
+pub fn note_alt<'a>(note: &Note<'a>) -> Option<&'a str> {
+    note.tags()
+        .into_iter()
+        .find_map(|tag| {
+            if tag.count() < 2 || tag.get_str(0) != Some("alt") {
+                return None;
+            }
+            tag.get_str(1)
+        })
+        .filter(|alt| !alt.trim().is_empty())
+}
+
+/// Fallback for a note of a kind we can't render: its kind, `alt`
+/// description, tags and raw JSON, plus the apps that can open it.
+/// Clicking the kind and description opens the note.
+pub fn unknown_kind_card(
+    ui: &mut egui::Ui,
+    note_context: &mut NoteContext,
+    txn: &Transaction,
+    note: &Note,
+) -> NoteResponse {
+    let mut action = None;
+
+    let resp = Frame::new()
+        .inner_margin(egui::Margin::same(8))
+        .corner_radius(notedeck::tokens::RADIUS_MD as u8)
+        .stroke(ui.visuals().noninteractive().bg_stroke)
+        .show(ui, |ui| {
+            ui.set_width(ui.available_width());
+
+            let header = ui
+                .vertical(|ui| {
+                    secondary_label(
+                        ui,
+                        tr!(
+                            note_context.i18n,
+                            "Kind {kind}",
+                            "Label for a note of a kind notedeck can't display",
+                            kind = note.kind()
+                        ),
+                    );
+
+                    if let Some(alt) = note_alt(note) {
+                        ui.add(
+                            Label::new(
+                                RichText::new(alt).text_style(NotedeckTextStyle::Body.text_style()),
+                            )
+                            .wrap()
+                            .selectable(false),
+                        );
+                    }
+                })
+                .response
+                .interact(Sense::click())
+                .on_hover_cursor(egui::CursorIcon::PointingHand);
+
+            if header.clicked() {
+                action = Some(NoteAction::note(NoteId::new(*note.id())));
+            }
+
+            let tag_count = usize::from(note.tags().count());
+            if tag_count > 0 {
+                let title = tr!(
+                    note_context.i18n,
+                    "Tags ({count})",
+                    "Header of the tag list of a note of an unsupported kind",
+                    count = tag_count
+                );
+                egui::CollapsingHeader::new(RichText::new(title).small())
+                    .id_salt(("unknown-kind-tags", note.id()))
+                    .show(ui, |ui| {
+                        for tag in note.tags().into_iter().take(MAX_TAGS_SHOWN) {
+                            ui.add(
+                                Label::new(RichText::new(tag_text(tag)).monospace().small()).wrap(),
+                            );
+                        }
+                        if tag_count > MAX_TAGS_SHOWN {
+                            secondary_label(
+                                ui,
+                                tr!(
+                                    note_context.i18n,
+                                    "…and {count} more",
+                                    "Shown after the tags listed for a note of an unsupported kind",
+                                    count = tag_count - MAX_TAGS_SHOWN
+                                ),
+                            );
+                        }
+                    });
+            }
+
+            let json_title = tr!(
+                note_context.i18n,
+                "Raw JSON",
+                "Header of the raw event of a note of an unsupported kind"
+            );
+            egui::CollapsingHeader::new(RichText::new(json_title).small())
+                .id_salt(("unknown-kind-json", note.id()))
+                .show(ui, |ui| match note.json() {
+                    Ok(json) => {
+                        if ui
+                            .small_button(tr!(
+                                note_context.i18n,
+                                "Copy",
+                                "Button to copy the raw event of a note of an unsupported kind"
+                            ))
+                            .clicked()
+                        {
+                            ui.ctx().copy_text(json.clone());
+                        }
+                        ScrollArea::vertical()
+                            .id_salt(("unknown-kind-json-scroll", note.id()))
+                            .max_height(240.0)
+                            .show(ui, |ui| {
+                                ui.add(Label::new(RichText::new(json).monospace().small()).wrap());
+                            });
+                    }
+                    Err(err) => {
+                        secondary_label(ui, err.to_string());
+                    }
+                });
+
+            app_handler_links(ui, note_context, txn, note);
+        })
+        .response;
+
+    NoteResponse::new(resp).with_action(action)
+}
+
+/// A tag as `["name", "value", …]`, with ids in hex
+fn tag_text(tag: Tag) -> String {
+    let values: Vec<String> = tag
+        .into_iter()
+        .map(|elem| match elem.variant() {
+            NdbStrVariant::Str(s) => format!("{s:?}"),
+            NdbStrVariant::Id(id) => format!("\"{}\"", NoteId::new(*id).hex()),
+        })
+        .collect();
+
+    format!("[{}]", values.join(", "))
+}
+
+/// "Open with" buttons for the NIP-89 apps that announced they handle
+/// the note's kind. Asks relays for them while we don't know any.
+fn app_handler_links(
+    ui: &mut egui::Ui,
+    note_context: &mut NoteContext,
+    txn: &Transaction,
+    note: &Note,
+) {
+    let handlers = app_handlers(note_context.ndb, txn, note.kind());
+    if handlers.is_empty() {
+        note_context
+            .unknown_ids
+            .add_app_handlers_if_missing(note_context.ndb, txn, note.kind());
+
+        secondary_label(
+            ui,
+            tr!(
+                note_context.i18n,
+                "No app that can open this is known yet",
+                "Shown under a note of an unsupported kind when no NIP-89 app handler was found"
+            ),
+        );
+        return;
+    }
+
+    let Some(nevent) =
+        note_nip19_event_bech(note, txn).or_else(|| NoteId::new(*note.id()).to_bech())
+    else {
+        return;
+    };
+
+    ui.horizontal_wrapped(|ui| {
+        secondary_label(
+            ui,
+            tr!(
+                note_context.i18n,
+                "Open with",
+                "Label before the apps that can open a note of an unsupported kind"
+            ),
+        );
+
+        for handler in &handlers {
+            let Some(link) = handler.link(&nevent) else {
+                continue;
+            };
+
+            if ui
+                .small_button(&handler.name)
+                .on_hover_text(&link)
+                .clicked()
+            {
+                ui.ctx().open_url(egui::OpenUrl::new_tab(link));
+            }
+        }
+    });
+}
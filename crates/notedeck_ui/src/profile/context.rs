@@ -1,7 +1,7 @@
 use enostr::Pubkey;
-use notedeck::{tr, Localization, ProfileContextSelection};
+use notedeck::{tr, Localization, MuteMode, ProfileContextSelection};
 
-use crate::context_menu::{context_button, stationary_arbitrary_menu_button};
+use crate::context_menu::{context_button, open_with_menu, stationary_arbitrary_menu_button};
 
 pub struct ProfileContextWidget {
     place_at: egui::Rect,
@@ -29,7 +29,9 @@ impl ProfileContextWidget {
         i18n: &mut Localization,
         button_response: egui::Response,
         can_sign: bool,
-        is_muted: bool,
+        mute_mode: Option<MuteMode>,
+        notifications_muted: Option<bool>,
+        has_open_with_template: bool,
     ) -> Option<ProfileContextSelection> {
         let mut context_selection: Option<ProfileContextSelection> = None;
 
@@ -68,8 +70,33 @@ impl ProfileContextWidget {
                 ui.close_menu();
             }
 
+            if let Some(target) = open_with_menu(ui, i18n, has_open_with_template) {
+                context_selection = Some(ProfileContextSelection::OpenWith(target));
+                ui.close_menu();
+            }
+
+            if let Some(notifications_muted) = notifications_muted {
+                let label = if notifications_muted {
+                    tr!(
+                        i18n,
+                        "Notify me about this user",
+                        "Show notifications from this user again"
+                    )
+                } else {
+                    tr!(
+                        i18n,
+                        "Don't notify me about this user",
+                        "Hide this user from notifications without muting them"
+                    )
+                };
+                if ui.button(label).clicked() {
+                    context_selection = Some(ProfileContextSelection::MuteNotifications);
+                    ui.close_menu();
+                }
+            }
+
             if can_sign {
-                let label = if is_muted {
+                let label = if mute_mode.is_some() {
                     tr!(i18n, "Unmute User", "Unmute this user's content")
                 } else {
                     tr!(i18n, "Mute User", "Mute this user's content")
@@ -79,6 +106,23 @@ impl ProfileContextWidget {
                     ui.close_menu();
                 }
 
+                if let Some(mode) = mute_mode {
+                    ui.menu_button(
+                        tr!(
+                            i18n,
+                            "When muted",
+                            "Submenu for how a muted user's notes are shown"
+                        ),
+                        |ui| {
+                            if let Some(mode) = mute_mode_menu(ui, i18n, mode) {
+                                context_selection =
+                                    Some(ProfileContextSelection::SetMuteMode(mode));
+                                ui.close_menu();
+                            }
+                        },
+                    );
+                }
+
                 if ui
                     .button(tr!(
                         i18n,
@@ -96,3 +140,44 @@ impl ProfileContextWidget {
         context_selection
     }
 }
+
+fn mute_mode_menu(
+    ui: &mut egui::Ui,
+    i18n: &mut Localization,
+    current: MuteMode,
+) -> Option<MuteMode> {
+    let modes = [
+        (
+            MuteMode::Hide,
+            tr!(
+                i18n,
+                "Hide their notes",
+                "Mute mode that leaves the notes out entirely"
+            ),
+        ),
+        (
+            MuteMode::Collapse,
+            tr!(
+                i18n,
+                "Collapse their notes",
+                "Mute mode that shows an expandable placeholder instead of the notes"
+            ),
+        ),
+        (
+            MuteMode::NotificationsOnly,
+            tr!(
+                i18n,
+                "Only mute in notifications",
+                "Mute mode that only keeps the notes out of notifications"
+            ),
+        ),
+    ];
+
+    let mut selected = None;
+    for (mode, label) in modes {
+        if ui.radio(current == mode, label).clicked() && current != mode {
+            selected = Some(mode);
+        }
+    }
+    selected
+}
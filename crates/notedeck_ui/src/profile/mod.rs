@@ -12,7 +12,8 @@ use egui::{Label, RichText, TextureHandle};
 use notedeck::media::images::ImageType;
 use notedeck::media::AnimationMode;
 use notedeck::{
-    Images, IsFollowing, MediaJobSender, NostrName, NotedeckTextStyle, PointDimensions,
+    name::sanitize_name, Images, IsFollowing, MediaJobSender, NostrName, NotedeckTextStyle,
+    PointDimensions,
 };
 
 use crate::{app_images, colors, widgets::styled_button_toggleable};
@@ -25,7 +26,8 @@ pub fn display_name_widget<'a>(
         let disp_resp = name.display_name.map(|disp_name| {
             ui.add(
                 Label::new(
-                    RichText::new(disp_name).text_style(NotedeckTextStyle::Heading3.text_style()),
+                    RichText::new(sanitize_name(disp_name))
+                        .text_style(NotedeckTextStyle::Heading3.text_style()),
                 )
                 .selectable(false),
             )
@@ -36,7 +38,7 @@ pub fn display_name_widget<'a>(
                 let username_resp = name.username.map(|username| {
                     ui.add(
                         Label::new(
-                            RichText::new(format!("@{username}"))
+                            RichText::new(format!("@{}", sanitize_name(username)))
                                 .size(16.0)
                                 .color(crate::colors::MID_GRAY),
                         )
@@ -1,6 +1,11 @@
 use egui::{Color32, RichText, Widget};
+use enostr::Pubkey;
 use nostrdb::ProfileRecord;
-use notedeck::{fonts::NamedFontFamily, tr, Localization};
+use notedeck::{
+    fonts::NamedFontFamily,
+    name::{get_display_name, sanitize_name},
+    tr, Accounts, Impersonation, IsFollowing, Localization, Nip05Cache,
+};
 
 pub struct Username<'a> {
     i18n: &'a mut Localization,
@@ -8,6 +13,7 @@ pub struct Username<'a> {
     pk: &'a [u8; 32],
     pk_colored: bool,
     abbrev: usize,
+    impersonation: Option<&'a mut Nip05Cache>,
 }
 
 impl<'a> Username<'a> {
@@ -21,6 +27,14 @@ impl<'a> Username<'a> {
         self
     }
 
+    /// Warn when the name poses as a key or handle. Only worth it for
+    /// profiles the user doesn't already follow. A name showing its own
+    /// nip05 handle counts as posing until that handle is verified.
+    pub fn flag_impersonation(mut self, flag: bool, nip05_cache: &'a mut Nip05Cache) -> Self {
+        self.impersonation = flag.then_some(nip05_cache);
+        self
+    }
+
     pub fn new(
         i18n: &'a mut Localization,
         profile: Option<&'a ProfileRecord>,
@@ -34,6 +48,7 @@ impl<'a> Username<'a> {
             pk,
             pk_colored,
             abbrev,
+            impersonation: None,
         }
     }
 }
@@ -57,6 +72,14 @@ impl Widget for Username<'_> {
                         ui_abbreviate_name(ui, name, self.abbrev, color);
                     }
                 }
+
+                if let Some(nip05_cache) = self.impersonation {
+                    let mut name = get_display_name(Some(profile));
+                    name.check_own_handle(&Pubkey::new(*self.pk), nip05_cache);
+                    if let Some(kind) = name.impersonation() {
+                        impersonation_warning(ui, self.i18n, kind);
+                    }
+                }
             } else {
                 let mut txt = RichText::new(tr!(
                     self.i18n,
@@ -85,6 +108,8 @@ fn colored_name(name: &str, color: Option<Color32>) -> RichText {
 }
 
 fn ui_abbreviate_name(ui: &mut egui::Ui, name: &str, len: usize, color: Option<Color32>) {
+    let name = sanitize_name(name);
+    let name = name.as_ref();
     let should_abbrev = name.len() > len;
     let name = if should_abbrev {
         let closest = notedeck::abbrev::floor_char_boundary(name, len);
@@ -103,3 +128,37 @@ fn ui_abbreviate_name(ui: &mut egui::Ui, name: &str, len: usize, color: Option<C
 fn pk_color(pk: &[u8; 32]) -> Color32 {
     Color32::from_rgb(pk[8], pk[10], pk[12])
 }
+
+/// Whether the selected account follows `pk` or is `pk`, in which case a
+/// lookalike name is the real thing
+pub(crate) fn is_known(accounts: &Accounts, pk: &[u8; 32]) -> bool {
+    let acc = accounts.get_selected_account();
+    acc.key.pubkey.bytes() == pk || acc.is_following(pk) == IsFollowing::Yes
+}
+
+/// A small warning marker next to a name that looks like someone else's
+pub(crate) fn impersonation_warning(
+    ui: &mut egui::Ui,
+    i18n: &mut Localization,
+    kind: Impersonation,
+) -> egui::Response {
+    let hover = match kind {
+        Impersonation::Npub => tr!(
+            i18n,
+            "This name is made to look like a public key",
+            "Tooltip on a warning next to a profile name that imitates an npub"
+        ),
+        Impersonation::Handle => tr!(
+            i18n,
+            "This name shows a handle or verified badge that isn't theirs",
+            "Tooltip on a warning next to a profile name that imitates a verified nip05 handle"
+        ),
+    };
+
+    ui.label(
+        RichText::new(" ⚠")
+            .family(NamedFontFamily::Medium.as_family())
+            .color(ui.visuals().warn_fg_color),
+    )
+    .on_hover_text(hover)
+}